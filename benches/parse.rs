@@ -0,0 +1,77 @@
+//! Measures parsing and displaying the symbols used by `tests/test-against-rustc-demangle.rs`, and parsing a large
+//! symbol with deeply nested types.
+//!
+//! Run with `cargo bench --bench parse`.
+
+use ast_demangle::rust_v0::{self, DisplayStyle, ParseOptions, Symbol};
+use std::fmt::Write;
+use std::hint;
+use std::time::{Duration, Instant};
+
+const TEST_DATA: &str = include_str!("../tests/test-against-rustc-demangle-data.txt");
+const MIN_DURATION: Duration = Duration::from_secs(2);
+const NESTING_DEPTH: usize = 2000;
+
+fn bench(name: &str, symbols: &[&str], mut f: impl FnMut(&str)) {
+    let start = Instant::now();
+    let mut iterations = 0_u32;
+
+    while start.elapsed() < MIN_DURATION {
+        for symbol in symbols {
+            f(hint::black_box(symbol));
+        }
+
+        iterations += 1;
+    }
+
+    let per_symbol = start.elapsed() / (iterations * u32::try_from(symbols.len()).unwrap());
+
+    println!("{name:<24} {per_symbol:>12?} per symbol ({iterations} iterations)");
+}
+
+fn main() {
+    let symbols = TEST_DATA
+        .lines()
+        .filter(|line| line.starts_with("_R"))
+        .collect::<Vec<_>>();
+
+    println!("{} symbols", symbols.len());
+
+    bench("parse", &symbols, |symbol| {
+        hint::black_box(Symbol::parse_from_str(symbol).ok());
+    });
+
+    bench("parse_path_head", &symbols, |symbol| {
+        hint::black_box(Symbol::parse_path_head(symbol).ok());
+    });
+
+    let mut buffer = String::new();
+
+    bench("parse and display", &symbols, |symbol| {
+        if let Ok((symbol, _)) = Symbol::parse_from_str(symbol) {
+            buffer.clear();
+            write!(buffer, "{symbol}").ok();
+            hint::black_box(&buffer);
+        }
+    });
+
+    bench("demangle_to_writer", &symbols, |symbol| {
+        buffer.clear();
+        rust_v0::demangle_to_writer(symbol, DisplayStyle::Long, &mut buffer).ok();
+        hint::black_box(&buffer);
+    });
+
+    // Nested types are recorded for back references after their children, so this measures inserting nodes out of
+    // order.
+    let nested = format!("_RINvC3foo3bar{}lE", "R".repeat(NESTING_DEPTH));
+    let options = ParseOptions {
+        max_depth: NESTING_DEPTH + 10,
+        ..ParseOptions::default()
+    };
+
+    assert!(Symbol::parse_from_str_with_options(&nested, options).is_ok());
+
+    bench("parse deeply nested", &[&nested], |symbol| {
+        hint::black_box(Symbol::parse_from_str_with_options(symbol, options).ok());
+    });
+}
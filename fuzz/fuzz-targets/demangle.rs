@@ -0,0 +1,14 @@
+#![no_main]
+
+use std::io::{self, Write};
+use test_utilities::BoundedWriter;
+
+libfuzzer_sys::fuzz_target!(|data: &str| {
+    // Every scheme is tried, so this also covers the C++ and Swift parsers with arbitrary text.
+    if let Ok((symbol, _)) = ast_demangle::demangle(data) {
+        let mut sink = BoundedWriter::new(io::sink(), 65536);
+
+        let _ = write!(sink, "{}", symbol);
+        let _ = write!(sink, "{:#}", symbol);
+    }
+});
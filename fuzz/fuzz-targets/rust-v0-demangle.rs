@@ -1,7 +1,10 @@
 #![no_main]
 
-use ast_demangle::rust_v0::Symbol;
+use ast_demangle::rust_v0::{self, CachedSymbol, DemangleError, DisplayError, DisplayStyle, Symbol};
+use ast_demangle::{breakpad, filter};
+use std::fmt::Write as _;
 use std::io::{self, Sink, Write};
+use std::slice;
 use test_utilities::BoundedWriter;
 
 fn bounded_writer() -> BoundedWriter<Sink> {
@@ -11,15 +14,54 @@ fn bounded_writer() -> BoundedWriter<Sink> {
 fn fuzz_with(data: &str) {
     if data
         .strip_prefix("_R")
-        .map_or(false, |rest| rest.bytes().all(|c| c.is_ascii_graphic()))
+        .is_some_and(|rest| rest.bytes().all(|c| c.is_ascii_graphic()))
     {
         let mut sink_1 = bounded_writer();
 
-        if let Ok((symbol, _)) = Symbol::parse_from_str(data) {
+        let parsed = Symbol::parse_from_str(data);
+
+        if let Ok((symbol, _)) = &parsed {
             let _ = write!(sink_1, "{}", symbol);
             let _ = write!(sink_1, "{:#}", symbol);
+
+            for style in [DisplayStyle::Short, DisplayStyle::Normal, DisplayStyle::Long] {
+                let _ = symbol.try_display(&mut String::new(), style, 65536);
+            }
+
+            assert!(write!(String::new(), "{}", symbol.debug_tree()).is_ok());
+
+            // The wrappers display symbols without a budget, so they are only run with short outputs.
+            let is_short = [
+                DisplayStyle::Short,
+                DisplayStyle::Normal,
+                DisplayStyle::Long,
+                DisplayStyle::Debuginfo,
+            ]
+            .into_iter()
+            .all(|style| symbol.display_len(style, 65536) != Err(DisplayError::BudgetExceeded));
+
+            if is_short {
+                // `ParseCache::demangle` stores what `CachedSymbol::new` returns.
+                let _ = CachedSymbol::new(symbol, "");
+                let _ = breakpad::demangle_line(&format!("FUNC 0 0 0 {data}"), DisplayStyle::Normal);
+                let _ = filter::demangle_line(data);
+                let _ = write!(
+                    String::new(),
+                    "{}",
+                    rust_v0::display_grouped(slice::from_ref(symbol), DisplayStyle::Normal)
+                );
+            }
         }
 
+        let demangled = rust_v0::demangle_to_writer(
+            data,
+            DisplayStyle::Normal,
+            &mut BoundedWriter::new(String::new(), 65536),
+        );
+
+        assert_eq!(parsed.is_ok(), demangled != Err(DemangleError::Invalid));
+        assert!(parsed.is_err() || matches!(ast_demangle::demangle(data), Ok((ast_demangle::Symbol::RustV0(_), _))));
+
         let mut sink_2 = bounded_writer();
 
         if let Ok(symbol) = rustc_demangle::try_demangle(data) {
@@ -0,0 +1,32 @@
+/* C interface of the ast-demangle crate, built with the `ffi` feature, for example with
+ * `cargo rustc --lib --release --features ffi --crate-type cdylib`. See `src/ffi.rs` for documentation. */
+
+#ifndef AST_DEMANGLE_H
+#define AST_DEMANGLE_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define AST_DEMANGLE_STYLE_SHORT 0
+#define AST_DEMANGLE_STYLE_NORMAL 1
+#define AST_DEMANGLE_STYLE_LONG 2
+#define AST_DEMANGLE_STYLE_DEBUGINFO 3
+
+#define AST_DEMANGLE_ERROR_INVALID (-1)
+#define AST_DEMANGLE_ERROR_NOT_DISPLAYABLE (-2)
+#define AST_DEMANGLE_ERROR_INVALID_ARGUMENT (-3)
+#define AST_DEMANGLE_ERROR_PANIC (-4)
+
+/* Demangles the Rust v0 mangled name `mangled` into `buffer` like `snprintf`. Returns the length of the full
+ * demangled name, or one of the negative `AST_DEMANGLE_ERROR_*` values. */
+intptr_t ast_demangle_rust_v0(const char *mangled, char *buffer, size_t length, int style);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
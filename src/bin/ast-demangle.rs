@@ -0,0 +1,172 @@
+//! Demangles names given as arguments or read from stdin, or demangles names embedded in arbitrary text with
+//! `--filter`, like [rustfilt](https://github.com/luser/rustfilt) does.
+
+use ast_demangle::rust_v0::DisplayStyle;
+use ast_demangle::{filter, Symbol};
+use serde_json::{json, Value};
+use std::fmt::Write as _;
+use std::io::{self, BufRead, Write};
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: ast-demangle [OPTIONS] [NAMES]...
+
+Demangles NAMES, or names read from stdin, one per line, if no NAMES are given.
+
+Options:
+      --style <STYLE>  Display style: short, normal, long or debuginfo [default: normal]
+      --json           Print a JSON object per name, containing the parsed Rust v0 symbol if there is one
+      --filter         Demangle Rust v0 names embedded in arbitrary text read from stdin
+  -h, --help           Print help
+";
+
+struct Options {
+    style: DisplayStyle,
+    json: bool,
+    filter: bool,
+    names: Vec<String>,
+}
+
+fn parse_style(style: &str) -> Option<DisplayStyle> {
+    Some(match style {
+        "short" => DisplayStyle::Short,
+        "normal" => DisplayStyle::Normal,
+        "long" => DisplayStyle::Long,
+        "debuginfo" => DisplayStyle::Debuginfo,
+        _ => return None,
+    })
+}
+
+fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Option<Options>, String> {
+    let mut options = Options {
+        style: DisplayStyle::Normal,
+        json: false,
+        filter: false,
+        names: Vec::new(),
+    };
+
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "--json" => options.json = true,
+            "--filter" => options.filter = true,
+            "--style" => {
+                let style = args.next().ok_or("missing value for `--style`")?;
+
+                options.style = parse_style(&style).ok_or_else(|| format!("invalid style: `{style}`"))?;
+            }
+            "--" => options.names.extend(args.by_ref()),
+            _ => {
+                if let Some(style) = arg.strip_prefix("--style=") {
+                    options.style = parse_style(style).ok_or_else(|| format!("invalid style: `{style}`"))?;
+                } else if arg.starts_with('-') {
+                    return Err(format!("unknown option: `{arg}`"));
+                } else {
+                    options.names.push(arg);
+                }
+            }
+        }
+    }
+
+    if options.filter && (options.json || !options.names.is_empty()) {
+        return Err("`--filter` can not be used with `--json` or names".into());
+    }
+
+    Ok(Some(options))
+}
+
+/// Returns the demangled form of `name` followed by the suffix that is not part of the mangled name, keeping Rust v0
+/// vendor specific suffixes except the `.llvm.` ones, or `None` if `name` can not be demangled.
+fn demangle_name(name: &str, style: DisplayStyle) -> Option<(Symbol<'_>, String)> {
+    let (symbol, suffix) = ast_demangle::demangle(name).ok()?;
+    let mut demangled = String::new();
+
+    // Some names parse but can not be displayed, like ones referring to lifetimes that are not bound.
+    write!(demangled, "{}", symbol.display(style)).ok()?;
+
+    if let Symbol::RustV0(symbol) = &symbol {
+        if let Some(vendor_specific_suffix) = symbol
+            .vendor_specific_suffix
+            .as_deref()
+            .filter(|vendor_specific_suffix| !vendor_specific_suffix.starts_with(".llvm."))
+        {
+            demangled.push_str(vendor_specific_suffix);
+        }
+    }
+
+    demangled.push_str(suffix);
+
+    Some((symbol, demangled))
+}
+
+fn to_json(name: &str, style: DisplayStyle) -> Value {
+    match demangle_name(name, style) {
+        Some((Symbol::RustV0(symbol), demangled)) => json!({
+            "mangled": name,
+            "demangled": demangled,
+            "symbol": symbol,
+        }),
+        Some((_, demangled)) => json!({ "mangled": name, "demangled": demangled }),
+        None => json!({ "mangled": name, "error": "invalid mangled name" }),
+    }
+}
+
+fn write_name(out: &mut impl Write, name: &str, options: &Options) -> io::Result<()> {
+    if options.json {
+        serde_json::to_writer(&mut *out, &to_json(name, options.style))?;
+
+        writeln!(out)
+    } else {
+        match demangle_name(name, options.style) {
+            Some((_, demangled)) => writeln!(out, "{demangled}"),
+            None => writeln!(out, "{name}"),
+        }
+    }
+}
+
+fn run(options: &Options) -> io::Result<()> {
+    let stdin = io::stdin().lock();
+    let mut stdout = io::BufWriter::new(io::stdout().lock());
+
+    if options.filter {
+        return filter::demangle_stream(stdin, stdout, options.style);
+    }
+
+    if options.names.is_empty() {
+        for line in stdin.lines() {
+            write_name(&mut stdout, line?.trim(), options)?;
+        }
+    } else {
+        for name in &options.names {
+            write_name(&mut stdout, name, options)?;
+        }
+    }
+
+    stdout.flush()
+}
+
+fn main() -> ExitCode {
+    match parse_args(std::env::args().skip(1)) {
+        Ok(Some(options)) => match run(&options) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(error) if error.kind() == io::ErrorKind::BrokenPipe => ExitCode::SUCCESS,
+            Err(error) => {
+                _ = writeln!(io::stderr(), "ast-demangle: {error}");
+
+                ExitCode::FAILURE
+            }
+        },
+        Ok(None) => {
+            _ = io::stdout().write_all(USAGE.as_bytes());
+
+            ExitCode::SUCCESS
+        }
+        Err(message) => {
+            _ = write!(io::stderr(), "ast-demangle: {message}\n\n{USAGE}");
+
+            ExitCode::from(2)
+        }
+    }
+}
@@ -0,0 +1,136 @@
+//! Demangling the function names in Breakpad `.sym` files.
+
+use crate::rust_v0::{DisplayStyle, Symbol};
+use std::borrow::Cow;
+use std::fmt::Write as _;
+use std::io::{self, BufRead, Write};
+
+/// Returns the name field of a `FUNC` or `PUBLIC` record, with the part of the line preceding it.
+fn split_name(line: &str) -> Option<(&str, &str)> {
+    let (record_type, rest) = line.split_once(' ')?;
+
+    let field_count = match record_type {
+        "FUNC" => 3,
+        "PUBLIC" => 2,
+        _ => return None,
+    };
+
+    // The optional `m` field marks functions that share their address with other functions.
+    let field_count = field_count + usize::from(rest.starts_with("m "));
+
+    let mut name_start = record_type.len() + 1;
+
+    for _ in 0..field_count {
+        name_start += line[name_start..].find(' ')? + 1;
+    }
+
+    Some(line.split_at(name_start))
+}
+
+/// Demangles the name in `line` if it is a `FUNC` or `PUBLIC` record with a Rust v0 mangled name, and returns other
+/// lines unchanged. Names that can not be displayed are kept as they are.
+#[must_use]
+pub fn demangle_line(line: &str, style: DisplayStyle) -> Cow<'_, str> {
+    if let Some((prefix, name)) = split_name(line) {
+        if let Ok((symbol, "")) = Symbol::parse_from_str(name) {
+            let mut result = prefix.to_string();
+
+            if write!(result, "{}", symbol.display(style)).is_ok() {
+                return Cow::Owned(result);
+            }
+        }
+    }
+
+    Cow::Borrowed(line)
+}
+
+/// Copies a Breakpad `.sym` file from `reader` to `writer`, replacing Rust v0 mangled names in `FUNC` and `PUBLIC`
+/// records with names displayed with `style`. All other content is copied unchanged, including line endings.
+///
+/// # Errors
+///
+/// Returns an error if reading from `reader` or writing to `writer` fails, or if the content is not valid UTF-8.
+pub fn demangle_sym_file(mut reader: impl BufRead, mut writer: impl Write, style: DisplayStyle) -> io::Result<()> {
+    let mut line = String::new();
+
+    while reader.read_line(&mut line)? != 0 {
+        let content = line.trim_end_matches(['\r', '\n']);
+        let line_ending = &line[content.len()..];
+
+        writer.write_all(demangle_line(content, style).as_bytes())?;
+        writer.write_all(line_ending.as_bytes())?;
+
+        line.clear();
+    }
+
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rust_v0::DisplayStyle;
+
+    #[test]
+    fn test_split_name() {
+        assert_eq!(
+            super::split_name("FUNC 1000 20 0 foo bar"),
+            Some(("FUNC 1000 20 0 ", "foo bar"))
+        );
+        assert_eq!(
+            super::split_name("FUNC m 1000 20 0 foo"),
+            Some(("FUNC m 1000 20 0 ", "foo"))
+        );
+        assert_eq!(super::split_name("PUBLIC 1000 0 foo"), Some(("PUBLIC 1000 0 ", "foo")));
+        assert_eq!(
+            super::split_name("PUBLIC m 1000 0 foo"),
+            Some(("PUBLIC m 1000 0 ", "foo"))
+        );
+        assert_eq!(super::split_name("FUNC 1000 20"), None);
+        assert_eq!(super::split_name("1000 4 12 1"), None);
+        assert_eq!(super::split_name("MODULE windows x86_64 0123 foo.pdb"), None);
+    }
+
+    #[test]
+    fn test_demangle_line() {
+        assert_eq!(
+            super::demangle_line("FUNC 1 2 3 _RNvC3foo3bar", DisplayStyle::Short),
+            "FUNC 1 2 3 bar"
+        );
+
+        // The name parses, but refers to a lifetime that is not bound.
+        assert_eq!(
+            super::demangle_line("FUNC 1 2 3 _RMC0RLY_z", DisplayStyle::Normal),
+            "FUNC 1 2 3 _RMC0RLY_z"
+        );
+    }
+
+    #[test]
+    fn test_demangle_sym_file() {
+        let input = "MODULE Linux x86_64 0123 foo\r\n\
+                     FILE 0 src/main.rs\n\
+                     FUNC 1000 20 0 _RNvCs123_3foo3bar\n\
+                     1000 4 12 0\n\
+                     PUBLIC m 2000 0 _RNvC3foo3baz\n\
+                     PUBLIC 3000 0 _RNvC3foo3baz.llvm.123\n\
+                     PUBLIC 3100 0 _RNvC3foo3bazX\n\
+                     FUNC 3200 10 0 _RMC0RLY_z\n\
+                     PUBLIC 4000 0 main";
+
+        let mut output = Vec::new();
+
+        super::demangle_sym_file(input.as_bytes(), &mut output, DisplayStyle::Normal).unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "MODULE Linux x86_64 0123 foo\r\n\
+             FILE 0 src/main.rs\n\
+             FUNC 1000 20 0 foo::bar\n\
+             1000 4 12 0\n\
+             PUBLIC m 2000 0 foo::baz\n\
+             PUBLIC 3000 0 foo::baz\n\
+             PUBLIC 3100 0 _RNvC3foo3bazX\n\
+             FUNC 3200 10 0 _RMC0RLY_z\n\
+             PUBLIC 4000 0 main"
+        );
+    }
+}
@@ -0,0 +1,693 @@
+use crate::cxx_itanium::{
+    operator_text, BuiltinType, CallOffset, CvQualifiers, DisplayStyle, Encoding, Function, FunctionType, Name,
+    RefQualifier, SpecialName, StdAbbreviation, Symbol, TemplateArg, Type, UnqualifiedName,
+};
+use std::fmt::{self, Write};
+use std::mem;
+use std::rc::Rc;
+
+// Output follows the formatting of GNU `c++filt`, see
+// <https://github.com/gcc-mirror/gcc/blob/master/libiberty/cp-demangle.c>.
+
+pub fn write_symbol(symbol: &Symbol, out: &mut dyn Write, style: DisplayStyle) -> fmt::Result {
+    let mut printer = Printer {
+        out,
+        last: None,
+        template_args: None,
+        pack_index: None,
+        is_in_lambda_signature: false,
+    };
+
+    printer.write_encoding(&symbol.encoding, style)?;
+
+    for suffix in &symbol.clone_suffixes {
+        write!(printer, " [clone {suffix}]")?;
+    }
+
+    Ok(())
+}
+
+struct Printer<'f, 's, 'a> {
+    out: &'f mut dyn Write,
+    /// The last character written, for deciding whether a separating space is needed.
+    last: Option<char>,
+    /// The template arguments that template parameters refer to.
+    template_args: Option<&'s [TemplateArg<'a>]>,
+    /// The index of the pack element being written when expanding a pack.
+    pack_index: Option<usize>,
+    /// Whether template parameters are the implicit template parameters of a generic lambda, written as `auto:1`.
+    is_in_lambda_signature: bool,
+}
+
+impl Write for Printer<'_, '_, '_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if let Some(c) = s.chars().next_back() {
+            self.last = Some(c);
+        }
+
+        self.out.write_str(s)
+    }
+}
+
+impl<'s, 'a> Printer<'_, 's, 'a> {
+    fn with_template_args(
+        &mut self,
+        template_args: Option<&'s [TemplateArg<'a>]>,
+        f: impl FnOnce(&mut Self) -> fmt::Result,
+    ) -> fmt::Result {
+        let saved_template_args = mem::replace(&mut self.template_args, template_args);
+        let saved_pack_index = self.pack_index.take();
+        let result = f(self);
+
+        self.template_args = saved_template_args;
+        self.pack_index = saved_pack_index;
+
+        result
+    }
+
+    fn write_encoding(&mut self, encoding: &'s Encoding<'a>, style: DisplayStyle) -> fmt::Result {
+        match encoding {
+            Encoding::Function(function) => self.with_template_args(innermost_template_args(&function.name), |p| {
+                p.write_function(function, style)
+            }),
+            Encoding::Data(name) => {
+                if style == DisplayStyle::Short {
+                    self.write_short_name(name)
+                } else {
+                    self.write_name(name)
+                }
+            }
+            Encoding::Special(special_name) => self.write_special_name(special_name, style),
+        }
+    }
+
+    fn write_function(&mut self, function: &'s Function<'a>, style: DisplayStyle) -> fmt::Result {
+        if style == DisplayStyle::Short {
+            return self.write_short_name(&function.name);
+        }
+
+        if let (DisplayStyle::Long, Some(return_type)) = (style, &function.return_type) {
+            self.write_type(return_type)?;
+            self.write_str(" ")?;
+        }
+
+        self.write_name(&function.name)?;
+        self.write_str("(")?;
+        self.write_type_list(&function.parameters)?;
+        self.write_str(")")?;
+        self.write_cv_qualifiers(function.cv_qualifiers)?;
+        self.write_ref_qualifier(function.ref_qualifier)
+    }
+
+    fn write_special_name(&mut self, special_name: &'s SpecialName<'a>, style: DisplayStyle) -> fmt::Result {
+        match special_name {
+            SpecialName::VirtualTable(type_) => {
+                self.write_str("vtable for ")?;
+                self.write_type(type_)
+            }
+            SpecialName::Vtt(type_) => {
+                self.write_str("VTT for ")?;
+                self.write_type(type_)
+            }
+            SpecialName::TypeInfo(type_) => {
+                self.write_str("typeinfo for ")?;
+                self.write_type(type_)
+            }
+            SpecialName::TypeInfoName(type_) => {
+                self.write_str("typeinfo name for ")?;
+                self.write_type(type_)
+            }
+            SpecialName::GuardVariable(name) => {
+                self.write_str("guard variable for ")?;
+                self.write_name(name)
+            }
+            SpecialName::TransactionClone(target) => {
+                self.write_str("transaction clone for ")?;
+                self.write_encoding(target, style)
+            }
+            SpecialName::NonTransactionClone(target) => {
+                self.write_str("non-transaction clone for ")?;
+                self.write_encoding(target, style)
+            }
+            SpecialName::Thunk { offset, target } => {
+                self.write_str(match offset {
+                    CallOffset::NonVirtual(_) => "non-virtual thunk to ",
+                    CallOffset::Virtual { .. } => "virtual thunk to ",
+                })?;
+
+                self.write_encoding(target, style)
+            }
+            SpecialName::CovariantThunk { target, .. } => {
+                self.write_str("covariant return thunk to ")?;
+                self.write_encoding(target, style)
+            }
+        }
+    }
+
+    fn write_name(&mut self, name: &'s Name<'a>) -> fmt::Result {
+        match name {
+            Name::Unqualified(name) => self.write_unqualified_name(name, None),
+            Name::Nested { prefix, name } => {
+                self.write_name(prefix)?;
+                self.write_str("::")?;
+                self.write_unqualified_name(name, Some(prefix))
+            }
+            Name::Template { name, args } => {
+                self.write_name(name)?;
+                self.write_template_args(args)
+            }
+            Name::Local { function, entity, .. } => {
+                self.write_encoding(function, DisplayStyle::Normal)?;
+                self.write_str("::")?;
+
+                match entity {
+                    None => self.write_str("string literal"),
+                    Some(entity) => self.write_name(entity),
+                }
+            }
+            Name::Std => self.write_str("std"),
+            Name::StdAbbreviation(abbreviation) => self.write_str(match abbreviation {
+                StdAbbreviation::Allocator => "std::allocator",
+                StdAbbreviation::BasicString => "std::basic_string",
+                StdAbbreviation::String => "std::basic_string<char, std::char_traits<char>, std::allocator<char> >",
+                StdAbbreviation::Istream => "std::basic_istream<char, std::char_traits<char> >",
+                StdAbbreviation::Ostream => "std::basic_ostream<char, std::char_traits<char> >",
+                StdAbbreviation::Iostream => "std::basic_iostream<char, std::char_traits<char> >",
+            }),
+            Name::TemplateParam(index) => self.write_template_param(*index),
+        }
+    }
+
+    /// Writes the innermost name without enclosing scopes.
+    fn write_short_name(&mut self, name: &'s Name<'a>) -> fmt::Result {
+        match name {
+            Name::Nested { prefix, name } => self.write_unqualified_name(name, Some(prefix)),
+            Name::Template { name, args } => {
+                self.write_short_name(name)?;
+                self.write_template_args(args)
+            }
+            Name::Local { entity, .. } => match entity {
+                None => self.write_str("string literal"),
+                Some(entity) => self.write_short_name(entity),
+            },
+            _ => self.write_name(name),
+        }
+    }
+
+    fn write_unqualified_name(&mut self, name: &'s UnqualifiedName<'a>, prefix: Option<&'s Name<'a>>) -> fmt::Result {
+        match name {
+            UnqualifiedName::Source(name) => self.write_str(name),
+            UnqualifiedName::AnonymousNamespace => self.write_str("(anonymous namespace)"),
+            UnqualifiedName::Operator(code) => {
+                let text = operator_text(code).unwrap_or(code);
+
+                self.write_str("operator")?;
+
+                if text.starts_with(|c: char| c.is_ascii_alphabetic()) {
+                    self.write_str(" ")?;
+                }
+
+                self.write_str(text)
+            }
+            UnqualifiedName::Conversion(type_) => {
+                self.write_str("operator ")?;
+                self.write_type(type_)
+            }
+            UnqualifiedName::Constructor {
+                inherited_from: Some(base),
+                ..
+            } => match &**base {
+                Type::Named(base) => self.write_class_name(Some(base)),
+                _ => self.write_type(base),
+            },
+            UnqualifiedName::Constructor { .. } => self.write_class_name(prefix),
+            UnqualifiedName::Destructor(_) => {
+                self.write_str("~")?;
+                self.write_class_name(prefix)
+            }
+            UnqualifiedName::Lambda { parameters, index } => {
+                self.write_str("{lambda(")?;
+
+                self.with_template_args(None, |p| {
+                    let saved = mem::replace(&mut p.is_in_lambda_signature, true);
+                    let result = p.write_type_list(parameters);
+
+                    p.is_in_lambda_signature = saved;
+
+                    result
+                })?;
+
+                write!(self, ")#{}}}", u128::from(*index) + 1)
+            }
+            UnqualifiedName::UnnamedType(index) => write!(self, "{{unnamed type#{}}}", u128::from(*index) + 1),
+            UnqualifiedName::AbiTagged { name, tag } => {
+                self.write_unqualified_name(name, prefix)?;
+                write!(self, "[abi:{tag}]")
+            }
+        }
+    }
+
+    /// Writes the name of the class that a constructor or a destructor belongs to.
+    fn write_class_name(&mut self, class: Option<&'s Name<'a>>) -> fmt::Result {
+        match class {
+            None => Ok(()),
+            Some(Name::Unqualified(name) | Name::Nested { name, .. }) => {
+                self.write_unqualified_name(name.untagged(), None)
+            }
+            Some(Name::Template { name, .. }) => self.write_class_name(Some(name)),
+            Some(Name::StdAbbreviation(abbreviation)) => self.write_str(match abbreviation {
+                StdAbbreviation::Allocator => "allocator",
+                StdAbbreviation::BasicString | StdAbbreviation::String => "basic_string",
+                StdAbbreviation::Istream => "basic_istream",
+                StdAbbreviation::Ostream => "basic_ostream",
+                StdAbbreviation::Iostream => "basic_iostream",
+            }),
+            Some(name) => self.write_name(name),
+        }
+    }
+
+    fn write_template_args(&mut self, args: &'s [TemplateArg<'a>]) -> fmt::Result {
+        if self.last == Some('<') {
+            self.write_str(" ")?;
+        }
+
+        self.write_str("<")?;
+        self.write_template_arg_list(args, &mut true)?;
+
+        // GNU `c++filt` does not separate the closing brackets if the arguments end with an empty pack.
+        if self.last == Some('>') && !matches!(args.last(), Some(TemplateArg::Pack(args)) if args.is_empty()) {
+            self.write_str(" ")?;
+        }
+
+        self.write_str(">")
+    }
+
+    /// Writes comma separated template arguments, with argument packs flattened.
+    fn write_template_arg_list(&mut self, args: &'s [TemplateArg<'a>], is_first: &mut bool) -> fmt::Result {
+        for arg in args {
+            match arg {
+                TemplateArg::Type(type_) => self.write_list_item(type_, is_first)?,
+                TemplateArg::Pack(args) => self.write_template_arg_list(args, is_first)?,
+                _ => {
+                    self.write_separator(is_first)?;
+                    self.write_template_arg(arg)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn write_template_arg(&mut self, arg: &'s TemplateArg<'a>) -> fmt::Result {
+        match arg {
+            TemplateArg::Type(type_) => self.write_type(type_),
+            TemplateArg::Literal {
+                type_,
+                is_negative,
+                value,
+            } => self.write_literal(type_, *is_negative, value),
+            TemplateArg::External(encoding) => self.write_encoding(encoding, DisplayStyle::Normal),
+            TemplateArg::Pack(args) => self.write_template_arg_list(args, &mut true),
+        }
+    }
+
+    fn write_literal(&mut self, type_: &'s Type<'a>, is_negative: bool, value: &str) -> fmt::Result {
+        let sign = if is_negative { "-" } else { "" };
+
+        let suffix = match type_ {
+            Type::Builtin(BuiltinType::Bool) if !is_negative && value == "0" => return self.write_str("false"),
+            Type::Builtin(BuiltinType::Bool) if !is_negative && value == "1" => return self.write_str("true"),
+            Type::Builtin(BuiltinType::Int) => "",
+            Type::Builtin(BuiltinType::UnsignedInt) => "u",
+            Type::Builtin(BuiltinType::Long) => "l",
+            Type::Builtin(BuiltinType::UnsignedLong) => "ul",
+            Type::Builtin(BuiltinType::LongLong) => "ll",
+            Type::Builtin(BuiltinType::UnsignedLongLong) => "ull",
+            _ => {
+                self.write_str("(")?;
+                self.write_type(type_)?;
+
+                return write!(self, "){sign}{value}");
+            }
+        };
+
+        write!(self, "{sign}{value}{suffix}")
+    }
+
+    fn resolve_template_param(&self, index: u64) -> Option<&'s TemplateArg<'a>> {
+        let arg = self.template_args?.get(usize::try_from(index).ok()?)?;
+
+        match (arg, self.pack_index) {
+            (TemplateArg::Pack(args), Some(pack_index)) => args.get(pack_index),
+            _ => Some(arg),
+        }
+    }
+
+    /// Writes the template argument a template parameter refers to. Template parameters inside the argument are not
+    /// resolved again, so self-referencing arguments cannot cause infinite recursion.
+    fn write_template_param(&mut self, index: u64) -> fmt::Result {
+        match self.resolve_template_param(index) {
+            Some(arg) => self.with_template_args(None, |p| p.write_template_arg(arg)),
+            None if self.is_in_lambda_signature => write!(self, "auto:{}", u128::from(index) + 1),
+            None if index == 0 => self.write_str("T_"),
+            None => write!(self, "T{}_", index - 1),
+        }
+    }
+
+    /// Returns the number of elements of the argument pack that a pack expansion pattern refers to.
+    fn pack_size(&self, type_: &Type<'a>) -> Option<usize> {
+        match type_ {
+            Type::TemplateParam(index) => match self.template_args?.get(usize::try_from(*index).ok()?)? {
+                TemplateArg::Pack(args) => Some(args.len()),
+                _ => None,
+            },
+            Type::Qualified { type_, .. }
+            | Type::Pointer(type_)
+            | Type::LValueReference(type_)
+            | Type::RValueReference(type_)
+            | Type::Array { element: type_, .. } => self.pack_size(type_),
+            Type::Function(function_type) => std::iter::once(&function_type.return_type)
+                .chain(&function_type.parameters)
+                .find_map(|type_| self.pack_size(type_)),
+            Type::PointerToMember { class, member } => self.pack_size(class).or_else(|| self.pack_size(member)),
+            Type::Named(name) => match &**name {
+                Name::Template { args, .. } => args.iter().find_map(|arg| match arg {
+                    TemplateArg::Type(type_) => self.pack_size(type_),
+                    _ => None,
+                }),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn write_separator(&mut self, is_first: &mut bool) -> fmt::Result {
+        if mem::replace(is_first, false) {
+            Ok(())
+        } else {
+            self.write_str(", ")
+        }
+    }
+
+    fn write_type_list(&mut self, types: &'s [Rc<Type<'a>>]) -> fmt::Result {
+        let mut is_first = true;
+
+        for type_ in types {
+            self.write_list_item(type_, &mut is_first)?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes a type in a comma separated list, with pack expansions expanded.
+    fn write_list_item(&mut self, type_: &'s Type<'a>, is_first: &mut bool) -> fmt::Result {
+        if let Type::PackExpansion(pattern) = type_ {
+            if let Some(size) = self.pack_size(pattern) {
+                for i in 0..size {
+                    self.write_separator(is_first)?;
+
+                    let saved_pack_index = self.pack_index.replace(i);
+                    let result = self.write_type(pattern);
+
+                    self.pack_index = saved_pack_index;
+
+                    result?;
+                }
+
+                return Ok(());
+            }
+        }
+
+        self.write_separator(is_first)?;
+        self.write_type(type_)
+    }
+
+    fn write_type(&mut self, type_: &'s Type<'a>) -> fmt::Result {
+        self.write_type_left(type_)?;
+        self.write_type_right(type_)
+    }
+
+    /// Writes the part of a declarator that precedes the declared name.
+    fn write_type_left(&mut self, type_: &'s Type<'a>) -> fmt::Result {
+        match type_ {
+            Type::Builtin(builtin_type) => self.write_str(builtin_type_name(*builtin_type)),
+            Type::Vendor(name) => self.write_str(name),
+            Type::Named(name) => self.write_name(name),
+            Type::Qualified { qualifiers, type_ } => {
+                self.write_type_left(type_)?;
+
+                if is_function(type_) {
+                    Ok(())
+                } else {
+                    self.write_cv_qualifiers(*qualifiers)
+                }
+            }
+            Type::Pointer(type_) => self.write_pointer_left(type_, "*"),
+            Type::LValueReference(type_) => self.write_reference_left(type_, false),
+            Type::RValueReference(type_) => self.write_reference_left(type_, true),
+            Type::Function(function_type) => {
+                self.write_type_left(&function_type.return_type)?;
+
+                if is_declarator_open(&function_type.return_type) {
+                    Ok(())
+                } else {
+                    self.write_str(" ")
+                }
+            }
+            Type::Array { element, .. } => self.write_type_left(element),
+            Type::PointerToMember { class, member } => {
+                self.write_type_left(member)?;
+
+                if needs_parentheses(member) {
+                    self.write_open_parenthesis()?;
+                } else if self.last != Some(' ') {
+                    self.write_str(" ")?;
+                }
+
+                self.write_type(class)?;
+                self.write_str("::*")
+            }
+            Type::TemplateParam(index) => match self.resolve_template_param(*index) {
+                Some(TemplateArg::Type(type_)) => self.with_template_args(None, |p| p.write_type_left(type_)),
+                _ => self.write_template_param(*index),
+            },
+            Type::PackExpansion(pattern) => {
+                self.write_type(pattern)?;
+                self.write_str("...")
+            }
+        }
+    }
+
+    fn write_pointer_left(&mut self, pointee: &'s Type<'a>, symbol: &str) -> fmt::Result {
+        self.write_type_left(pointee)?;
+
+        if needs_parentheses(pointee) {
+            self.write_open_parenthesis()?;
+        }
+
+        self.write_str(symbol)
+    }
+
+    /// Writes a reference type, collapsing it with the reference type a template parameter refers to, so `T&&` with `T`
+    /// being `int&` is written as `int&`.
+    fn write_reference_left(&mut self, referee: &'s Type<'a>, is_rvalue: bool) -> fmt::Result {
+        if let Some((referee, is_rvalue)) = self.collapse_reference(referee, is_rvalue) {
+            self.with_template_args(None, |p| {
+                p.write_pointer_left(referee, if is_rvalue { "&&" } else { "&" })
+            })
+        } else {
+            self.write_pointer_left(referee, if is_rvalue { "&&" } else { "&" })
+        }
+    }
+
+    fn write_reference_right(&mut self, referee: &'s Type<'a>, is_rvalue: bool) -> fmt::Result {
+        if let Some((referee, _)) = self.collapse_reference(referee, is_rvalue) {
+            self.with_template_args(None, |p| p.write_pointer_right(referee))
+        } else {
+            self.write_pointer_right(referee)
+        }
+    }
+
+    fn collapse_reference(&self, referee: &Type<'a>, is_rvalue: bool) -> Option<(&'s Type<'a>, bool)> {
+        let Type::TemplateParam(index) = referee else {
+            return None;
+        };
+
+        let Some(TemplateArg::Type(type_)) = self.resolve_template_param(*index) else {
+            return None;
+        };
+
+        match &**type_ {
+            Type::LValueReference(referee) => Some((referee, false)),
+            Type::RValueReference(referee) => Some((referee, is_rvalue)),
+            _ => None,
+        }
+    }
+
+    fn write_pointer_right(&mut self, pointee: &'s Type<'a>) -> fmt::Result {
+        if needs_parentheses(pointee) {
+            self.write_str(")")?;
+        }
+
+        self.write_type_right(pointee)
+    }
+
+    fn write_open_parenthesis(&mut self) -> fmt::Result {
+        if matches!(self.last, Some(' ' | '(' | '*' | '&')) {
+            self.write_str("(")
+        } else {
+            self.write_str(" (")
+        }
+    }
+
+    /// Writes the part of a declarator that follows the declared name.
+    fn write_type_right(&mut self, type_: &'s Type<'a>) -> fmt::Result {
+        match type_ {
+            Type::Qualified { qualifiers, type_ } => match &**type_ {
+                Type::Function(function_type) => self.write_function_type_right(function_type, *qualifiers),
+                _ => self.write_type_right(type_),
+            },
+            Type::Pointer(type_) => self.write_pointer_right(type_),
+            Type::LValueReference(type_) => self.write_reference_right(type_, false),
+            Type::RValueReference(type_) => self.write_reference_right(type_, true),
+            Type::Function(function_type) => self.write_function_type_right(function_type, CvQualifiers::default()),
+            Type::Array { .. } => {
+                let mut type_ = type_;
+
+                self.write_str(" ")?;
+
+                while let Type::Array { dimension, element } = type_ {
+                    match dimension {
+                        None => self.write_str("[]")?,
+                        Some(dimension) => write!(self, "[{dimension}]")?,
+                    }
+
+                    type_ = element;
+                }
+
+                self.write_type_right(type_)
+            }
+            Type::PointerToMember { member, .. } => self.write_pointer_right(member),
+            Type::TemplateParam(index) => match self.resolve_template_param(*index) {
+                Some(TemplateArg::Type(type_)) => self.with_template_args(None, |p| p.write_type_right(type_)),
+                _ => Ok(()),
+            },
+            Type::Builtin(_) | Type::Vendor(_) | Type::Named(_) | Type::PackExpansion(_) => Ok(()),
+        }
+    }
+
+    fn write_function_type_right(
+        &mut self,
+        function_type: &'s FunctionType<'a>,
+        qualifiers: CvQualifiers,
+    ) -> fmt::Result {
+        self.write_str("(")?;
+        self.write_type_list(&function_type.parameters)?;
+        self.write_str(")")?;
+        self.write_cv_qualifiers(qualifiers)?;
+        self.write_ref_qualifier(function_type.ref_qualifier)?;
+
+        if function_type.is_noexcept {
+            self.write_str(" noexcept")?;
+        }
+
+        self.write_type_right(&function_type.return_type)
+    }
+
+    fn write_cv_qualifiers(&mut self, qualifiers: CvQualifiers) -> fmt::Result {
+        if qualifiers.const_ {
+            self.write_str(" const")?;
+        }
+
+        if qualifiers.volatile {
+            self.write_str(" volatile")?;
+        }
+
+        if qualifiers.restrict {
+            self.write_str(" restrict")?;
+        }
+
+        Ok(())
+    }
+
+    fn write_ref_qualifier(&mut self, ref_qualifier: Option<RefQualifier>) -> fmt::Result {
+        match ref_qualifier {
+            None => Ok(()),
+            Some(RefQualifier::LValue) => self.write_str(" &"),
+            Some(RefQualifier::RValue) => self.write_str(" &&"),
+        }
+    }
+}
+
+/// Returns the template arguments that the template parameters in a function signature refer to.
+fn innermost_template_args<'s, 'a>(name: &'s Name<'a>) -> Option<&'s [TemplateArg<'a>]> {
+    match name {
+        Name::Template { args, .. } => Some(args),
+        Name::Nested { prefix, .. } => innermost_template_args(prefix),
+        Name::Local {
+            entity: Some(entity), ..
+        } => innermost_template_args(entity),
+        _ => None,
+    }
+}
+
+fn is_function(type_: &Type) -> bool {
+    match type_ {
+        Type::Function(_) => true,
+        Type::Qualified { type_, .. } => matches!(**type_, Type::Function(_)),
+        _ => false,
+    }
+}
+
+/// Returns whether the left part of a declarator ends with an unclosed parenthesis, like `int (*`.
+fn is_declarator_open(type_: &Type) -> bool {
+    match type_ {
+        Type::Pointer(type_) | Type::LValueReference(type_) | Type::RValueReference(type_) => {
+            needs_parentheses(type_) || is_declarator_open(type_)
+        }
+        Type::PointerToMember { member, .. } => needs_parentheses(member) || is_declarator_open(member),
+        Type::Qualified { type_, .. } => is_declarator_open(type_),
+        _ => false,
+    }
+}
+
+/// Pointers and references to functions and arrays are written like `int (*)()` and `int (&) [2]`.
+fn needs_parentheses(type_: &Type) -> bool {
+    is_function(type_) || matches!(type_, Type::Array { .. })
+}
+
+fn builtin_type_name(builtin_type: BuiltinType) -> &'static str {
+    match builtin_type {
+        BuiltinType::Void => "void",
+        BuiltinType::WcharT => "wchar_t",
+        BuiltinType::Bool => "bool",
+        BuiltinType::Char => "char",
+        BuiltinType::SignedChar => "signed char",
+        BuiltinType::UnsignedChar => "unsigned char",
+        BuiltinType::Short => "short",
+        BuiltinType::UnsignedShort => "unsigned short",
+        BuiltinType::Int => "int",
+        BuiltinType::UnsignedInt => "unsigned int",
+        BuiltinType::Long => "long",
+        BuiltinType::UnsignedLong => "unsigned long",
+        BuiltinType::LongLong => "long long",
+        BuiltinType::UnsignedLongLong => "unsigned long long",
+        BuiltinType::Int128 => "__int128",
+        BuiltinType::UnsignedInt128 => "unsigned __int128",
+        BuiltinType::Float => "float",
+        BuiltinType::Double => "double",
+        BuiltinType::LongDouble => "long double",
+        BuiltinType::Float128 => "__float128",
+        BuiltinType::Ellipsis => "...",
+        BuiltinType::Decimal64 => "decimal64",
+        BuiltinType::Decimal128 => "decimal128",
+        BuiltinType::Decimal32 => "decimal32",
+        BuiltinType::Half => "half",
+        BuiltinType::Char32T => "char32_t",
+        BuiltinType::Char16T => "char16_t",
+        BuiltinType::Char8T => "char8_t",
+        BuiltinType::Auto => "auto",
+        BuiltinType::DecltypeAuto => "decltype(auto)",
+        BuiltinType::NullptrT => "decltype(nullptr)",
+    }
+}
@@ -0,0 +1,471 @@
+//! Tools for demangling C++ symbols using the
+//! [Itanium C++ ABI](https://itanium-cxx-abi.github.io/cxx-abi/abi.html#mangling).
+//!
+//! The commonly emitted subset of the mangling grammar is supported: nested, local and unscoped names, templates
+//! (including argument packs), substitutions, constructors, destructors, operators, lambdas, ABI tags, the built-in
+//! and compound types, and the special names for virtual tables, type information, guard variables and thunks.
+//! Names containing expressions (`X...E`), `decltype` types or vector types are rejected.
+//!
+//! With [`DisplayStyle::Long`], symbols are displayed the same way as GNU `c++filt` displays them.
+
+use crate::padding;
+pub use crate::rust_v0::DisplayStyle;
+use std::fmt::{self, Display, Formatter};
+use std::rc::Rc;
+
+mod display;
+mod parsers;
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct ParseSymbolError;
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Symbol<'a> {
+    pub encoding: Rc<Encoding<'a>>,
+    /// Suffixes added by compiler optimizations, like `.cold` or `.constprop.0`.
+    pub clone_suffixes: Vec<&'a str>,
+}
+
+impl<'a> Symbol<'a> {
+    /// Returns an object that implements [`Display`] for printing the symbol.
+    ///
+    /// [`DisplayStyle::Short`] shows the innermost name without enclosing scopes and function parameters,
+    /// [`DisplayStyle::Normal`] and [`DisplayStyle::Debuginfo`] show the qualified name with function parameters, and
+    /// [`DisplayStyle::Long`] also shows the return types of function templates.
+    #[must_use]
+    pub fn display(&self, style: DisplayStyle) -> impl Display + '_ {
+        struct Wrapper<'s, 'a>(&'s Symbol<'a>, DisplayStyle);
+
+        impl Display for Wrapper<'_, '_> {
+            fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+                padding::write_padded(f, |out| display::write_symbol(self.0, out, self.1))
+            }
+        }
+
+        Wrapper(self, style)
+    }
+
+    /// Parses `input` with the Itanium C++ ABI mangling scheme, returns a tuple that contains a [`Symbol`] object and
+    /// an [`&str`] object containing the suffix that is not part of the mangled name. Names with `_Z` and `__Z`
+    /// prefixes are accepted.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParseSymbolError`] if `input` does not start with a supported mangled name.
+    pub fn parse_from_str(input: &'a str) -> Result<(Self, &'a str), ParseSymbolError> {
+        let input = input
+            .strip_prefix("_Z")
+            .or_else(|| input.strip_prefix("__Z"))
+            .ok_or(ParseSymbolError)?;
+
+        parsers::parse_symbol(input).map_err(|()| ParseSymbolError)
+    }
+}
+
+impl Display for Symbol<'_> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.display(if f.alternate() {
+            DisplayStyle::Normal
+        } else {
+            DisplayStyle::Long
+        })
+        .fmt(f)
+    }
+}
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Encoding<'a> {
+    Function(Function<'a>),
+    Data(Rc<Name<'a>>),
+    Special(SpecialName<'a>),
+}
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Function<'a> {
+    pub name: Rc<Name<'a>>,
+    /// The qualifiers of a member function.
+    pub cv_qualifiers: CvQualifiers,
+    /// The reference qualifier of a member function.
+    pub ref_qualifier: Option<RefQualifier>,
+    /// The return type, which is only encoded for function templates.
+    pub return_type: Option<Rc<Type<'a>>>,
+    pub parameters: Vec<Rc<Type<'a>>>,
+}
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum SpecialName<'a> {
+    VirtualTable(Rc<Type<'a>>),
+    Vtt(Rc<Type<'a>>),
+    TypeInfo(Rc<Type<'a>>),
+    TypeInfoName(Rc<Type<'a>>),
+    GuardVariable(Rc<Name<'a>>),
+    TransactionClone(Rc<Encoding<'a>>),
+    NonTransactionClone(Rc<Encoding<'a>>),
+    Thunk {
+        offset: CallOffset,
+        target: Rc<Encoding<'a>>,
+    },
+    CovariantThunk {
+        this_offset: CallOffset,
+        result_offset: CallOffset,
+        target: Rc<Encoding<'a>>,
+    },
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum CallOffset {
+    NonVirtual(i64),
+    Virtual { offset: i64, virtual_offset: i64 },
+}
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Name<'a> {
+    Unqualified(UnqualifiedName<'a>),
+    Nested {
+        prefix: Rc<Self>,
+        name: UnqualifiedName<'a>,
+    },
+    Template {
+        name: Rc<Self>,
+        args: Vec<TemplateArg<'a>>,
+    },
+    /// An entity declared in a function. A `None` entity denotes a string literal.
+    Local {
+        function: Rc<Encoding<'a>>,
+        entity: Option<Rc<Self>>,
+        discriminator: Option<u64>,
+    },
+    /// The `std` namespace.
+    Std,
+    StdAbbreviation(StdAbbreviation),
+    TemplateParam(u64),
+}
+
+impl<'a> Name<'a> {
+    fn last_unqualified_name(&self) -> Option<&UnqualifiedName<'a>> {
+        match self {
+            Self::Unqualified(name) | Self::Nested { name, .. } => Some(name),
+            Self::Template { name, .. } => name.last_unqualified_name(),
+            Self::Local { entity, .. } => entity.as_deref().and_then(Self::last_unqualified_name),
+            Self::Std | Self::StdAbbreviation(_) | Self::TemplateParam(_) => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum StdAbbreviation {
+    /// `Sa`: `std::allocator`.
+    Allocator,
+    /// `Sb`: `std::basic_string`.
+    BasicString,
+    /// `Ss`: `std::basic_string<char, std::char_traits<char>, std::allocator<char>>`.
+    String,
+    /// `Si`: `std::basic_istream<char, std::char_traits<char>>`.
+    Istream,
+    /// `So`: `std::basic_ostream<char, std::char_traits<char>>`.
+    Ostream,
+    /// `Sd`: `std::basic_iostream<char, std::char_traits<char>>`.
+    Iostream,
+}
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum UnqualifiedName<'a> {
+    Source(&'a str),
+    AnonymousNamespace,
+    /// An operator, denoted by its two letter code, like `pl` for `operator+`.
+    Operator(&'a str),
+    Conversion(Rc<Type<'a>>),
+    Constructor {
+        kind: u8,
+        /// The base class that an inheriting constructor is inherited from.
+        inherited_from: Option<Rc<Type<'a>>>,
+    },
+    Destructor(u8),
+    Lambda {
+        parameters: Vec<Rc<Type<'a>>>,
+        index: u64,
+    },
+    UnnamedType(u64),
+    AbiTagged {
+        name: Box<Self>,
+        tag: &'a str,
+    },
+}
+
+impl UnqualifiedName<'_> {
+    /// Returns the name with ABI tags removed.
+    fn untagged(&self) -> &Self {
+        match self {
+            Self::AbiTagged { name, .. } => name.untagged(),
+            _ => self,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Type<'a> {
+    Builtin(BuiltinType),
+    Vendor(&'a str),
+    Named(Rc<Name<'a>>),
+    Qualified { qualifiers: CvQualifiers, type_: Rc<Self> },
+    Pointer(Rc<Self>),
+    LValueReference(Rc<Self>),
+    RValueReference(Rc<Self>),
+    Function(FunctionType<'a>),
+    Array { dimension: Option<u64>, element: Rc<Self> },
+    PointerToMember { class: Rc<Self>, member: Rc<Self> },
+    TemplateParam(u64),
+    PackExpansion(Rc<Self>),
+}
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct FunctionType<'a> {
+    pub is_extern_c: bool,
+    pub is_noexcept: bool,
+    pub ref_qualifier: Option<RefQualifier>,
+    pub return_type: Rc<Type<'a>>,
+    pub parameters: Vec<Rc<Type<'a>>>,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct CvQualifiers {
+    pub restrict: bool,
+    pub volatile: bool,
+    pub const_: bool,
+}
+
+impl CvQualifiers {
+    #[must_use]
+    pub fn is_empty(self) -> bool {
+        !(self.restrict || self.volatile || self.const_)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum RefQualifier {
+    LValue,
+    RValue,
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum BuiltinType {
+    Void,
+    WcharT,
+    Bool,
+    Char,
+    SignedChar,
+    UnsignedChar,
+    Short,
+    UnsignedShort,
+    Int,
+    UnsignedInt,
+    Long,
+    UnsignedLong,
+    LongLong,
+    UnsignedLongLong,
+    Int128,
+    UnsignedInt128,
+    Float,
+    Double,
+    LongDouble,
+    Float128,
+    Ellipsis,
+    Decimal64,
+    Decimal128,
+    Decimal32,
+    Half,
+    Char32T,
+    Char16T,
+    Char8T,
+    Auto,
+    DecltypeAuto,
+    NullptrT,
+}
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum TemplateArg<'a> {
+    Type(Rc<Type<'a>>),
+    /// An integer, enumeration or floating point literal. `value` holds the mangled digits, without the leading `n`
+    /// of negative numbers.
+    Literal {
+        type_: Rc<Type<'a>>,
+        is_negative: bool,
+        value: &'a str,
+    },
+    /// The address of an external entity.
+    External(Rc<Encoding<'a>>),
+    Pack(Vec<Self>),
+}
+
+/// Returns the operator token for a two letter operator code.
+fn operator_text(code: &str) -> Option<&'static str> {
+    Some(match code {
+        "nw" => "new",
+        "na" => "new[]",
+        "dl" => "delete",
+        "da" => "delete[]",
+        "aw" => "co_await",
+        "ps" | "pl" => "+",
+        "ng" | "mi" => "-",
+        "ad" | "an" => "&",
+        "de" | "ml" => "*",
+        "co" => "~",
+        "dv" => "/",
+        "rm" => "%",
+        "or" => "|",
+        "eo" => "^",
+        "aS" => "=",
+        "pL" => "+=",
+        "mI" => "-=",
+        "mL" => "*=",
+        "dV" => "/=",
+        "rM" => "%=",
+        "aN" => "&=",
+        "oR" => "|=",
+        "eO" => "^=",
+        "ls" => "<<",
+        "rs" => ">>",
+        "lS" => "<<=",
+        "rS" => ">>=",
+        "eq" => "==",
+        "ne" => "!=",
+        "lt" => "<",
+        "gt" => ">",
+        "le" => "<=",
+        "ge" => ">=",
+        "ss" => "<=>",
+        "nt" => "!",
+        "aa" => "&&",
+        "oo" => "||",
+        "pp" => "++",
+        "mm" => "--",
+        "cm" => ",",
+        "pm" => "->*",
+        "pt" => "->",
+        "cl" => "()",
+        "ix" => "[]",
+        "qu" => "?",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DisplayStyle, Symbol};
+
+    #[test]
+    fn test_display() {
+        // Long style expectations are the outputs of GNU `c++filt`.
+        let test_cases = [
+            ("_Z3foov", "foo()", "foo()", "foo"),
+            ("_ZL3foov", "foo()", "foo()", "foo"),
+            ("__Z3fooi", "foo(int)", "foo(int)", "foo"),
+            ("_ZN3foo3barE", "foo::bar", "foo::bar", "bar"),
+            (
+                "_ZN3foo3barIiEEvT_PKc",
+                "void foo::bar<int>(int, char const*)",
+                "foo::bar<int>(int, char const*)",
+                "bar<int>",
+            ),
+            (
+                "_ZNSt6vectorIS_IiSaIiEESaIS1_EE9push_backERKS1_",
+                "std::vector<std::vector<int, std::allocator<int> >, std::allocator<std::vector<int, std::allocator<int> > \
+                 > >::push_back(std::vector<int, std::allocator<int> > const&)",
+                "std::vector<std::vector<int, std::allocator<int> >, std::allocator<std::vector<int, std::allocator<int> > \
+                 > >::push_back(std::vector<int, std::allocator<int> > const&)",
+                "push_back",
+            ),
+            (
+                "_ZSt4swapIiEvRT_S1_",
+                "void std::swap<int>(int&, int&)",
+                "std::swap<int>(int&, int&)",
+                "swap<int>",
+            ),
+            (
+                "_Z3fooIJidEEvDpPT_",
+                "void foo<int, double>(int*, double*)",
+                "foo<int, double>(int*, double*)",
+                "foo<int, double>",
+            ),
+            ("_Z3fooIJEEvDpT_", "void foo<>()", "foo<>()", "foo<>"),
+            (
+                "_ZZ4mainENKUliPcE0_clEiS0_",
+                "main::{lambda(int, char*)#2}::operator()(int, {lambda(int, char*)#2}) const",
+                "main::{lambda(int, char*)#2}::operator()(int, {lambda(int, char*)#2}) const",
+                "operator()",
+            ),
+            (
+                "_ZZ6useallvENKUlT_E0_clIdEEDaS_",
+                "auto useall()::{lambda(auto:1)#2}::operator()<double>(double) const",
+                "useall()::{lambda(auto:1)#2}::operator()<double>(double) const",
+                "operator()<double>",
+            ),
+            ("_Z3fooPFPFivEvE", "foo(int (*(*)())())", "foo(int (*(*)())())", "foo"),
+            ("_Z3fooPM1AFvvE", "foo(void (A::**)())", "foo(void (A::**)())", "foo"),
+            ("_Z3fooM1AKFvvE", "foo(void (A::*)() const)", "foo(void (A::*)() const)", "foo"),
+            ("_Z3fooRKM1Ai", "foo(int A::* const&)", "foo(int A::* const&)", "foo"),
+            ("_Z3fooPA10_i", "foo(int (*) [10])", "foo(int (*) [10])", "foo"),
+            ("_Z3fooA2_A3_i", "foo(int [2][3])", "foo(int [2][3])", "foo"),
+            ("_Z3fooFivE", "foo(int ())", "foo(int ())", "foo"),
+            ("_Z3fooPDoFvvE", "foo(void (*)() noexcept)", "foo(void (*)() noexcept)", "foo"),
+            ("_Z3fooPrVKi", "foo(int const volatile restrict*)", "foo(int const volatile restrict*)", "foo"),
+            (
+                "_Z3fooILb1ELc65ELj5ELin5ELDn0EEvv",
+                "void foo<true, (char)65, 5u, -5, (decltype(nullptr))0>()",
+                "foo<true, (char)65, 5u, -5, (decltype(nullptr))0>()",
+                "foo<true, (char)65, 5u, -5, (decltype(nullptr))0>",
+            ),
+            ("_Z3fooIL_Z3barvEEvv", "void foo<bar()>()", "foo<bar()>()", "foo<bar()>"),
+            ("_ZNKR1A1fEv", "A::f() const &", "A::f() const &", "f"),
+            ("_ZN3FooB5cxx11C1Ev", "Foo[abi:cxx11]::Foo()", "Foo[abi:cxx11]::Foo()", "Foo"),
+            ("_ZN12_GLOBAL__N_13fooEv", "(anonymous namespace)::foo()", "(anonymous namespace)::foo()", "foo"),
+            ("_ZN1AltIiEEbT_", "bool A::operator< <int>(int)", "A::operator< <int>(int)", "operator< <int>"),
+            ("_Znwm", "operator new(unsigned long)", "operator new(unsigned long)", "operator new"),
+            (
+                "_ZNSaIcED1Ev",
+                "std::allocator<char>::~allocator()",
+                "std::allocator<char>::~allocator()",
+                "~allocator",
+            ),
+            ("_ZZ4mainEs_0", "main::string literal", "main::string literal", "string literal"),
+            ("_ZZ3fooIiEvvE1x", "foo<int>()::x", "foo<int>()::x", "x"),
+            ("_ZGVZ4mainE1x", "guard variable for main::x", "guard variable for main::x", "guard variable for main::x"),
+            ("_ZTV1A", "vtable for A", "vtable for A", "vtable for A"),
+            ("_ZTv0_n24_N1A1fEv", "virtual thunk to A::f()", "virtual thunk to A::f()", "virtual thunk to f"),
+            (
+                "_ZGTtNSt11logic_errorD1Ev",
+                "transaction clone for std::logic_error::~logic_error()",
+                "transaction clone for std::logic_error::~logic_error()",
+                "transaction clone for ~logic_error",
+            ),
+            (
+                "_Z3foov.isra.0.cold",
+                "foo() [clone .isra.0] [clone .cold]",
+                "foo() [clone .isra.0] [clone .cold]",
+                "foo [clone .isra.0] [clone .cold]",
+            ),
+        ];
+
+        for (name, long, normal, short) in test_cases {
+            let (symbol, suffix) = Symbol::parse_from_str(name).unwrap();
+
+            assert_eq!(suffix, "", "{name}");
+            assert_eq!(symbol.display(DisplayStyle::Long).to_string(), long, "{name}");
+            assert_eq!(symbol.display(DisplayStyle::Normal).to_string(), normal, "{name}");
+            assert_eq!(symbol.display(DisplayStyle::Short).to_string(), short, "{name}");
+            assert_eq!(symbol.to_string(), long, "{name}");
+            assert_eq!(format!("{symbol:#}"), normal, "{name}");
+        }
+
+        assert!(Symbol::parse_from_str("_RNvC3foo3bar").is_err());
+        assert!(Symbol::parse_from_str("_Z1fIiEDTfp_E").is_err());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        // Non-ASCII characters where two letter operator codes are expected.
+        assert!(Symbol::parse_from_str("_Zném").is_err());
+        assert!(Symbol::parse_from_str("_ZN1AléIiEEbT_").is_err());
+        assert!(Symbol::parse_from_str("_ZZ4mainENKUliPcE0_céEiS0_").is_err());
+    }
+}
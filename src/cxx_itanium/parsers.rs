@@ -0,0 +1,1042 @@
+use crate::cxx_itanium::{
+    operator_text, BuiltinType, CallOffset, CvQualifiers, Encoding, Function, FunctionType, Name, RefQualifier,
+    SpecialName, StdAbbreviation, Symbol, TemplateArg, Type, UnqualifiedName,
+};
+use crate::mini_parser::parsers::{digit1, take, take_while, take_while1};
+use crate::mini_parser::Parser;
+use std::rc::Rc;
+
+// References:
+//
+// - <https://itanium-cxx-abi.github.io/cxx-abi/abi.html#mangling>.
+// - <https://github.com/llvm/llvm-project/blob/main/libcxxabi/src/demangle/ItaniumDemangle.h>.
+
+const MAX_DEPTH: usize = 256;
+
+#[derive(Clone)]
+enum Substitution<'a> {
+    Name(Rc<Name<'a>>),
+    Type(Rc<Type<'a>>),
+}
+
+impl<'a> Substitution<'a> {
+    fn into_name(self) -> Option<Rc<Name<'a>>> {
+        match self {
+            Self::Name(name) => Some(name),
+            Self::Type(type_) => match &*type_ {
+                Type::Named(name) => Some(Rc::clone(name)),
+                Type::TemplateParam(index) => Some(Rc::new(Name::TemplateParam(*index))),
+                _ => None,
+            },
+        }
+    }
+
+    fn into_type(self) -> Rc<Type<'a>> {
+        match self {
+            Self::Name(name) => Rc::new(Type::Named(name)),
+            Self::Type(type_) => type_,
+        }
+    }
+}
+
+#[derive(Default)]
+struct Context<'a> {
+    substitutions: Vec<Substitution<'a>>,
+    depth: usize,
+}
+
+impl<'a> Context<'a> {
+    fn push_name(&mut self, name: &Rc<Name<'a>>) {
+        self.substitutions.push(Substitution::Name(Rc::clone(name)));
+    }
+
+    fn push_type(&mut self, type_: Type<'a>) -> Rc<Type<'a>> {
+        let type_ = Rc::new(type_);
+
+        self.substitutions.push(Substitution::Type(Rc::clone(&type_)));
+
+        type_
+    }
+}
+
+fn limit_recursion_depth<'a, T>(
+    mut parser: impl Parser<&'a str, Context<'a>, Output = T>,
+) -> impl Parser<&'a str, Context<'a>, Output = T> {
+    move |input, context: &mut Context<'a>| {
+        if context.depth < MAX_DEPTH {
+            context.depth += 1;
+
+            let result = parser.parse(input, context);
+
+            context.depth -= 1;
+
+            result
+        } else {
+            Err(())
+        }
+    }
+}
+
+pub fn parse_symbol(input: &str) -> Result<(Symbol<'_>, &str), ()> {
+    let (encoding, mut rest) = parse_encoding(input, &mut Context::default())?;
+    let mut clone_suffixes = Vec::new();
+
+    while let Ok((suffix, next)) = parse_clone_suffix(rest) {
+        clone_suffixes.push(suffix);
+        rest = next;
+    }
+
+    Ok((
+        Symbol {
+            encoding,
+            clone_suffixes,
+        },
+        rest,
+    ))
+}
+
+/// Parses a suffix like `.isra.0` or `.cold`, following the rules of GNU `c++filt`.
+fn parse_clone_suffix(input: &str) -> Result<(&str, &str), ()> {
+    let rest = input
+        .strip_prefix('.')
+        .and_then(|rest| rest.strip_prefix(|c: char| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_'))
+        .ok_or(())?;
+
+    let (_, mut rest) = take_while(|c: char| c.is_ascii_lowercase() || c == '_').parse(rest, &mut ())?;
+
+    while let Some((_, next)) = rest.strip_prefix('.').and_then(|next| digit1.parse(next, &mut ()).ok()) {
+        rest = next;
+    }
+
+    Ok((&input[..input.len() - rest.len()], rest))
+}
+
+fn parse_number<'a, C>(input: &'a str, context: &mut C) -> Result<(u64, &'a str), ()> {
+    digit1.map_opt(|digits: &str| digits.parse().ok()).parse(input, context)
+}
+
+fn parse_signed_number<'a, C>(input: &'a str, context: &mut C) -> Result<(i64, &'a str), ()> {
+    let (is_negative, rest) = input.strip_prefix('n').map_or((false, input), |rest| (true, rest));
+
+    digit1
+        .map_opt(|digits: &str| {
+            let value = digits.parse::<i64>().ok()?;
+
+            Some(if is_negative { -value } else { value })
+        })
+        .parse(rest, context)
+}
+
+/// Parses `_` as 0 and `<number> _` as `<number> + 1`.
+fn parse_optional_index<'a, C>(input: &'a str, context: &mut C) -> Result<(u64, &'a str), ()> {
+    if let Some(rest) = input.strip_prefix('_') {
+        Ok((0, rest))
+    } else {
+        let (index, rest) = parse_number(input, context)?;
+        let rest = rest.strip_prefix('_').ok_or(())?;
+
+        Ok((index.checked_add(1).ok_or(())?, rest))
+    }
+}
+
+fn parse_source_name<'a, C>(input: &'a str, context: &mut C) -> Result<(&'a str, &'a str), ()> {
+    parse_number
+        .map_opt(|length| usize::try_from(length).ok())
+        .flat_map(take)
+        .parse(input, context)
+}
+
+fn parse_cv_qualifiers(input: &str) -> (CvQualifiers, &str) {
+    let (restrict, rest) = input.strip_prefix('r').map_or((false, input), |rest| (true, rest));
+    let (volatile, rest) = rest.strip_prefix('V').map_or((false, rest), |rest| (true, rest));
+    let (const_, rest) = rest.strip_prefix('K').map_or((false, rest), |rest| (true, rest));
+
+    (
+        CvQualifiers {
+            restrict,
+            volatile,
+            const_,
+        },
+        rest,
+    )
+}
+
+fn parse_encoding<'a>(input: &'a str, context: &mut Context<'a>) -> Result<(Rc<Encoding<'a>>, &'a str), ()> {
+    limit_recursion_depth(|input: &'a str, context: &mut Context<'a>| {
+        if input.starts_with(['T', 'G']) {
+            let (special_name, rest) = parse_special_name(input, context)?;
+
+            return Ok((Rc::new(Encoding::Special(special_name)), rest));
+        }
+
+        let ((name, cv_qualifiers, ref_qualifier), rest) = parse_name(input, context)?;
+
+        if is_end_of_parameters(rest) {
+            return Ok((Rc::new(Encoding::Data(name)), rest));
+        }
+
+        let (return_type, rest) = if has_return_type(&name) {
+            let (return_type, rest) = parse_type(rest, context)?;
+
+            (Some(return_type), rest)
+        } else {
+            (None, rest)
+        };
+
+        let (parameters, rest) = parse_bare_function_type(rest, context)?;
+
+        Ok((
+            Rc::new(Encoding::Function(Function {
+                name,
+                cv_qualifiers,
+                ref_qualifier,
+                return_type,
+                parameters,
+            })),
+            rest,
+        ))
+    })
+    .parse(input, context)
+}
+
+/// Parameter types end at an `E` or at anything that cannot start a type, like a clone suffix.
+fn is_end_of_parameters(input: &str) -> bool {
+    !input.starts_with(|c: char| c.is_ascii_alphanumeric()) || input.starts_with('E')
+}
+
+/// Function templates encode their return types, except for constructors, destructors and conversion operators.
+fn has_return_type(name: &Name) -> bool {
+    match name {
+        Name::Template { name, .. } => !matches!(
+            name.last_unqualified_name().map(UnqualifiedName::untagged),
+            Some(UnqualifiedName::Constructor { .. } | UnqualifiedName::Destructor(_) | UnqualifiedName::Conversion(_))
+        ),
+        Name::Local {
+            entity: Some(entity), ..
+        } => has_return_type(entity),
+        _ => false,
+    }
+}
+
+fn parse_bare_function_type<'a>(input: &'a str, context: &mut Context<'a>) -> Result<(Vec<Rc<Type<'a>>>, &'a str), ()> {
+    let mut parameters = Vec::new();
+    let mut rest = input;
+
+    loop {
+        let (parameter, next) = parse_type(rest, context)?;
+
+        parameters.push(parameter);
+        rest = next;
+
+        if is_end_of_parameters(rest) {
+            break;
+        }
+    }
+
+    remove_void_parameter(&mut parameters);
+
+    Ok((parameters, rest))
+}
+
+/// A single `void` parameter denotes an empty parameter list.
+fn remove_void_parameter(parameters: &mut Vec<Rc<Type>>) {
+    if let [parameter] = parameters.as_slice() {
+        if matches!(**parameter, Type::Builtin(BuiltinType::Void)) {
+            parameters.clear();
+        }
+    }
+}
+
+fn parse_special_name<'a>(input: &'a str, context: &mut Context<'a>) -> Result<(SpecialName<'a>, &'a str), ()> {
+    if let Some(rest) = input.strip_prefix("GV") {
+        let ((name, _, _), rest) = parse_name(rest, context)?;
+
+        return Ok((SpecialName::GuardVariable(name), rest));
+    } else if let Some(rest) = input.strip_prefix("GTt") {
+        let (target, rest) = parse_encoding(rest, context)?;
+
+        return Ok((SpecialName::TransactionClone(target), rest));
+    } else if let Some(rest) = input.strip_prefix("GTn") {
+        let (target, rest) = parse_encoding(rest, context)?;
+
+        return Ok((SpecialName::NonTransactionClone(target), rest));
+    }
+
+    let rest = input.strip_prefix('T').ok_or(())?;
+
+    let type_constructor: Option<fn(Rc<Type<'a>>) -> SpecialName<'a>> = match rest.as_bytes().first() {
+        Some(b'V') => Some(SpecialName::VirtualTable),
+        Some(b'T') => Some(SpecialName::Vtt),
+        Some(b'I') => Some(SpecialName::TypeInfo),
+        Some(b'S') => Some(SpecialName::TypeInfoName),
+        _ => None,
+    };
+
+    if let Some(type_constructor) = type_constructor {
+        let (type_, rest) = parse_type(&rest[1..], context)?;
+
+        Ok((type_constructor(type_), rest))
+    } else if let Some(rest) = rest.strip_prefix('c') {
+        let (this_offset, rest) = parse_call_offset(rest, context)?;
+        let (result_offset, rest) = parse_call_offset(rest, context)?;
+        let (target, rest) = parse_encoding(rest, context)?;
+
+        Ok((
+            SpecialName::CovariantThunk {
+                this_offset,
+                result_offset,
+                target,
+            },
+            rest,
+        ))
+    } else {
+        let (offset, rest) = parse_call_offset(rest, context)?;
+        let (target, rest) = parse_encoding(rest, context)?;
+
+        Ok((SpecialName::Thunk { offset, target }, rest))
+    }
+}
+
+fn parse_call_offset<'a>(input: &'a str, context: &mut Context<'a>) -> Result<(CallOffset, &'a str), ()> {
+    if let Some(rest) = input.strip_prefix('h') {
+        let (offset, rest) = parse_signed_number(rest, context)?;
+        let rest = rest.strip_prefix('_').ok_or(())?;
+
+        Ok((CallOffset::NonVirtual(offset), rest))
+    } else {
+        let rest = input.strip_prefix('v').ok_or(())?;
+        let (offset, rest) = parse_signed_number(rest, context)?;
+        let rest = rest.strip_prefix('_').ok_or(())?;
+        let (virtual_offset, rest) = parse_signed_number(rest, context)?;
+        let rest = rest.strip_prefix('_').ok_or(())?;
+
+        Ok((CallOffset::Virtual { offset, virtual_offset }, rest))
+    }
+}
+
+type QualifiedName<'a> = (Rc<Name<'a>>, CvQualifiers, Option<RefQualifier>);
+
+/// Parses a name, along with the qualifiers of the member function it may denote.
+fn parse_name<'a>(input: &'a str, context: &mut Context<'a>) -> Result<(QualifiedName<'a>, &'a str), ()> {
+    limit_recursion_depth(
+        |input: &'a str, context: &mut Context<'a>| match input.as_bytes().first() {
+            Some(b'N') => parse_nested_name(input, context),
+            Some(b'Z') => parse_local_name(input, context),
+            _ => {
+                let (name, rest) = if input.starts_with('S') && !input.starts_with("St") {
+                    // A substitution can only be used as an unscoped template name.
+                    let (substitution, rest) = parse_substitution(input, context)?;
+
+                    if !rest.starts_with('I') {
+                        return Err(());
+                    }
+
+                    (substitution.into_name().ok_or(())?, rest)
+                } else {
+                    let (name, rest) = parse_unscoped_name(input, context)?;
+
+                    if rest.starts_with('I') {
+                        context.push_name(&name);
+                    }
+
+                    (name, rest)
+                };
+
+                let (name, rest) = if rest.starts_with('I') {
+                    let (args, rest) = parse_template_args(rest, context)?;
+
+                    (Rc::new(Name::Template { name, args }), rest)
+                } else {
+                    (name, rest)
+                };
+
+                Ok(((name, CvQualifiers::default(), None), rest))
+            }
+        },
+    )
+    .parse(input, context)
+}
+
+fn parse_unscoped_name<'a>(input: &'a str, context: &mut Context<'a>) -> Result<(Rc<Name<'a>>, &'a str), ()> {
+    let (is_std, rest) = input.strip_prefix("St").map_or((false, input), |rest| (true, rest));
+    let rest = rest.strip_prefix('L').unwrap_or(rest);
+    let (name, rest) = parse_unqualified_name(rest, context)?;
+
+    if matches!(
+        name.untagged(),
+        UnqualifiedName::Constructor { .. } | UnqualifiedName::Destructor(_)
+    ) {
+        return Err(());
+    }
+
+    Ok((
+        Rc::new(if is_std {
+            Name::Nested {
+                prefix: Rc::new(Name::Std),
+                name,
+            }
+        } else {
+            Name::Unqualified(name)
+        }),
+        rest,
+    ))
+}
+
+/// Parses `N [<CV-qualifiers>] [<ref-qualifier>] <prefix> <unqualified-name> E`. Every prefix component except
+/// substitutions and `St` is a substitution candidate, and so is the whole name excluding the last component.
+fn parse_nested_name<'a>(input: &'a str, context: &mut Context<'a>) -> Result<(QualifiedName<'a>, &'a str), ()> {
+    let rest = input.strip_prefix('N').ok_or(())?;
+    let (cv_qualifiers, rest) = parse_cv_qualifiers(rest);
+
+    let (ref_qualifier, mut rest) = if let Some(rest) = rest.strip_prefix('R') {
+        (Some(RefQualifier::LValue), rest)
+    } else if let Some(rest) = rest.strip_prefix('O') {
+        (Some(RefQualifier::RValue), rest)
+    } else {
+        (None, rest)
+    };
+
+    let mut name = None::<Rc<Name<'a>>>;
+    let mut is_last_pushed = false;
+
+    loop {
+        if let Some(next) = rest.strip_prefix('E') {
+            rest = next;
+
+            break;
+        }
+
+        let current = rest.strip_prefix('L').unwrap_or(rest);
+
+        // `M` marks the preceding component as a data member, which is the scope of closure types in its initializer.
+        if let Some(next) = current.strip_prefix('M') {
+            if !is_last_pushed {
+                return Err(());
+            }
+
+            rest = next;
+
+            continue;
+        }
+
+        if let Some(next) = current.strip_prefix("St") {
+            if name.is_some() {
+                return Err(());
+            }
+
+            name = Some(Rc::new(Name::Std));
+            is_last_pushed = false;
+            rest = next;
+        } else if current.starts_with('S') {
+            if name.is_some() {
+                return Err(());
+            }
+
+            let (substitution, next) = parse_substitution(current, context)?;
+
+            name = Some(substitution.into_name().ok_or(())?);
+            is_last_pushed = false;
+            rest = next;
+        } else {
+            let (component, next) = if current.starts_with('I') {
+                let (args, next) = parse_template_args(current, context)?;
+
+                (
+                    Name::Template {
+                        name: name.take().ok_or(())?,
+                        args,
+                    },
+                    next,
+                )
+            } else if current.starts_with('T') {
+                if name.is_some() {
+                    return Err(());
+                }
+
+                let (index, next) = parse_template_param(current, context)?;
+
+                (Name::TemplateParam(index), next)
+            } else {
+                let (unqualified_name, next) = parse_unqualified_name(current, context)?;
+
+                if let Some(prefix) = name.take() {
+                    (
+                        Name::Nested {
+                            prefix,
+                            name: unqualified_name,
+                        },
+                        next,
+                    )
+                } else if matches!(
+                    unqualified_name.untagged(),
+                    UnqualifiedName::Constructor { .. } | UnqualifiedName::Destructor(_)
+                ) {
+                    return Err(());
+                } else {
+                    (Name::Unqualified(unqualified_name), next)
+                }
+            };
+
+            let component = Rc::new(component);
+
+            context.push_name(&component);
+            name = Some(component);
+            is_last_pushed = true;
+            rest = next;
+        }
+    }
+
+    let name = name.ok_or(())?;
+
+    if is_last_pushed {
+        context.substitutions.pop();
+    }
+
+    Ok(((name, cv_qualifiers, ref_qualifier), rest))
+}
+
+/// Parses `Z <function encoding> E <entity name> [<discriminator>]` or `Z <function encoding> E s [<discriminator>]`.
+fn parse_local_name<'a>(input: &'a str, context: &mut Context<'a>) -> Result<(QualifiedName<'a>, &'a str), ()> {
+    let rest = input.strip_prefix('Z').ok_or(())?;
+    let (function, rest) = parse_encoding(rest, context)?;
+    let rest = rest.strip_prefix('E').ok_or(())?;
+
+    let ((entity, cv_qualifiers, ref_qualifier), rest) = if let Some(rest) = rest.strip_prefix('s') {
+        ((None, CvQualifiers::default(), None), rest)
+    } else {
+        let ((entity, cv_qualifiers, ref_qualifier), rest) = parse_name(rest, context)?;
+
+        ((Some(entity), cv_qualifiers, ref_qualifier), rest)
+    };
+
+    let (discriminator, rest) = parse_discriminator(rest);
+
+    Ok((
+        (
+            Rc::new(Name::Local {
+                function,
+                entity,
+                discriminator,
+            }),
+            cv_qualifiers,
+            ref_qualifier,
+        ),
+        rest,
+    ))
+}
+
+/// Parses `_ <digit>` or `__ <number> _`.
+fn parse_discriminator(input: &str) -> (Option<u64>, &str) {
+    if let Some(rest) = input.strip_prefix("__") {
+        if let Ok((value, rest)) = parse_number(rest, &mut ()) {
+            if let Some(rest) = rest.strip_prefix('_') {
+                return (Some(value), rest);
+            }
+        }
+    } else if let Some(rest) = input.strip_prefix('_') {
+        if let Some(digit) = rest.bytes().next().filter(u8::is_ascii_digit) {
+            return (Some(u64::from(digit - b'0')), &rest[1..]);
+        }
+    }
+
+    (None, input)
+}
+
+fn parse_unqualified_name<'a>(input: &'a str, context: &mut Context<'a>) -> Result<(UnqualifiedName<'a>, &'a str), ()> {
+    let bytes = input.as_bytes();
+
+    let (mut name, mut rest) = match (bytes.first().ok_or(())?, bytes.get(1)) {
+        (b'0'..=b'9', _) => {
+            let (name, rest) = parse_source_name(input, context)?;
+
+            if name.starts_with("_GLOBAL__N") {
+                (UnqualifiedName::AnonymousNamespace, rest)
+            } else {
+                (UnqualifiedName::Source(name), rest)
+            }
+        }
+        (b'C', Some(kind @ b'1'..=b'5')) => (
+            UnqualifiedName::Constructor {
+                kind: kind - b'0',
+                inherited_from: None,
+            },
+            &input[2..],
+        ),
+        (b'C', Some(b'I')) => {
+            let kind = match input.as_bytes().get(2) {
+                Some(kind @ b'1'..=b'5') => kind - b'0',
+                _ => return Err(()),
+            };
+
+            let (base, rest) = parse_type(&input[3..], context)?;
+
+            (
+                UnqualifiedName::Constructor {
+                    kind,
+                    inherited_from: Some(base),
+                },
+                rest,
+            )
+        }
+        (b'D', Some(kind @ b'0'..=b'5')) => (UnqualifiedName::Destructor(kind - b'0'), &input[2..]),
+        (b'U', Some(b't')) => {
+            let (index, rest) = parse_optional_index(&input[2..], context)?;
+
+            (UnqualifiedName::UnnamedType(index), rest)
+        }
+        (b'U', Some(b'l')) => {
+            let (parameters, rest) = parse_bare_function_type(&input[2..], context)?;
+            let rest = rest.strip_prefix('E').ok_or(())?;
+            let (index, rest) = parse_optional_index(rest, context)?;
+
+            (UnqualifiedName::Lambda { parameters, index }, rest)
+        }
+        (b'c', Some(b'v')) => {
+            let (type_, rest) = parse_type(&input[2..], context)?;
+
+            (UnqualifiedName::Conversion(type_), rest)
+        }
+        (b'a'..=b'z', Some(_)) => {
+            let code = input.get(..2).ok_or(())?;
+
+            operator_text(code).ok_or(())?;
+
+            (UnqualifiedName::Operator(code), &input[2..])
+        }
+        _ => return Err(()),
+    };
+
+    while let Some(next) = rest.strip_prefix('B') {
+        let (tag, next) = parse_source_name(next, context)?;
+
+        name = UnqualifiedName::AbiTagged {
+            name: Box::new(name),
+            tag,
+        };
+
+        rest = next;
+    }
+
+    Ok((name, rest))
+}
+
+/// Parses `S_`, `S <seq-id> _` or a standard abbreviation other than `St`.
+fn parse_substitution<'a>(input: &'a str, context: &mut Context<'a>) -> Result<(Substitution<'a>, &'a str), ()> {
+    let rest = input.strip_prefix('S').ok_or(())?;
+
+    let abbreviation = match rest.as_bytes().first() {
+        Some(b'a') => Some(StdAbbreviation::Allocator),
+        Some(b'b') => Some(StdAbbreviation::BasicString),
+        Some(b's') => Some(StdAbbreviation::String),
+        Some(b'i') => Some(StdAbbreviation::Istream),
+        Some(b'o') => Some(StdAbbreviation::Ostream),
+        Some(b'd') => Some(StdAbbreviation::Iostream),
+        _ => None,
+    };
+
+    if let Some(abbreviation) = abbreviation {
+        return Ok((
+            Substitution::Name(Rc::new(Name::StdAbbreviation(abbreviation))),
+            &rest[1..],
+        ));
+    }
+
+    let (index, rest) = if let Some(rest) = rest.strip_prefix('_') {
+        (0, rest)
+    } else {
+        let (index, rest) = take_while1(|c: char| c.is_ascii_digit() || c.is_ascii_uppercase())
+            .map_opt(|digits| usize::from_str_radix(digits, 36).ok()?.checked_add(1))
+            .parse(rest, context)?;
+
+        (index, rest.strip_prefix('_').ok_or(())?)
+    };
+
+    let substitution = context.substitutions.get(index).ok_or(())?.clone();
+
+    Ok((substitution, rest))
+}
+
+fn parse_template_param<'a>(input: &'a str, context: &mut Context<'a>) -> Result<(u64, &'a str), ()> {
+    let rest = input.strip_prefix('T').ok_or(())?;
+
+    parse_optional_index(rest, context)
+}
+
+fn parse_template_args<'a>(input: &'a str, context: &mut Context<'a>) -> Result<(Vec<TemplateArg<'a>>, &'a str), ()> {
+    let rest = input.strip_prefix('I').ok_or(())?;
+
+    parse_template_arg_list(rest, context)
+}
+
+/// Parses template arguments until an `E`.
+fn parse_template_arg_list<'a>(
+    input: &'a str,
+    context: &mut Context<'a>,
+) -> Result<(Vec<TemplateArg<'a>>, &'a str), ()> {
+    let mut args = Vec::new();
+    let mut rest = input;
+
+    loop {
+        if let Some(next) = rest.strip_prefix('E') {
+            return Ok((args, next));
+        }
+
+        let (arg, next) = parse_template_arg(rest, context)?;
+
+        args.push(arg);
+        rest = next;
+    }
+}
+
+fn parse_template_arg<'a>(input: &'a str, context: &mut Context<'a>) -> Result<(TemplateArg<'a>, &'a str), ()> {
+    limit_recursion_depth(|input: &'a str, context: &mut Context<'a>| {
+        if let Some(rest) = input.strip_prefix('J') {
+            let (args, rest) = parse_template_arg_list(rest, context)?;
+
+            Ok((TemplateArg::Pack(args), rest))
+        } else if let Some(rest) = input.strip_prefix('L') {
+            if let Some(rest) = rest.strip_prefix("_Z").or_else(|| rest.strip_prefix('Z')) {
+                let (encoding, rest) = parse_encoding(rest, context)?;
+                let rest = rest.strip_prefix('E').ok_or(())?;
+
+                Ok((TemplateArg::External(encoding), rest))
+            } else {
+                let (type_, rest) = parse_type(rest, context)?;
+                let (is_negative, rest) = rest.strip_prefix('n').map_or((false, rest), |rest| (true, rest));
+
+                let (value, rest) =
+                    take_while(|c: char| c.is_ascii_digit() || matches!(c, 'a'..='f')).parse(rest, context)?;
+
+                let rest = rest.strip_prefix('E').ok_or(())?;
+
+                Ok((
+                    TemplateArg::Literal {
+                        type_,
+                        is_negative,
+                        value,
+                    },
+                    rest,
+                ))
+            }
+        } else {
+            let (type_, rest) = parse_type(input, context)?;
+
+            Ok((TemplateArg::Type(type_), rest))
+        }
+    })
+    .parse(input, context)
+}
+
+fn parse_builtin_type(input: &str) -> Option<(BuiltinType, &str)> {
+    let bytes = input.as_bytes();
+
+    let type_ = match bytes.first()? {
+        b'v' => BuiltinType::Void,
+        b'w' => BuiltinType::WcharT,
+        b'b' => BuiltinType::Bool,
+        b'c' => BuiltinType::Char,
+        b'a' => BuiltinType::SignedChar,
+        b'h' => BuiltinType::UnsignedChar,
+        b's' => BuiltinType::Short,
+        b't' => BuiltinType::UnsignedShort,
+        b'i' => BuiltinType::Int,
+        b'j' => BuiltinType::UnsignedInt,
+        b'l' => BuiltinType::Long,
+        b'm' => BuiltinType::UnsignedLong,
+        b'x' => BuiltinType::LongLong,
+        b'y' => BuiltinType::UnsignedLongLong,
+        b'n' => BuiltinType::Int128,
+        b'o' => BuiltinType::UnsignedInt128,
+        b'f' => BuiltinType::Float,
+        b'd' => BuiltinType::Double,
+        b'e' => BuiltinType::LongDouble,
+        b'g' => BuiltinType::Float128,
+        b'z' => BuiltinType::Ellipsis,
+        b'D' => {
+            let type_ = match bytes.get(1)? {
+                b'd' => BuiltinType::Decimal64,
+                b'e' => BuiltinType::Decimal128,
+                b'f' => BuiltinType::Decimal32,
+                b'h' => BuiltinType::Half,
+                b'i' => BuiltinType::Char32T,
+                b's' => BuiltinType::Char16T,
+                b'u' => BuiltinType::Char8T,
+                b'a' => BuiltinType::Auto,
+                b'c' => BuiltinType::DecltypeAuto,
+                b'n' => BuiltinType::NullptrT,
+                _ => return None,
+            };
+
+            return Some((type_, &input[2..]));
+        }
+        _ => return None,
+    };
+
+    Some((type_, &input[1..]))
+}
+
+fn parse_type<'a>(input: &'a str, context: &mut Context<'a>) -> Result<(Rc<Type<'a>>, &'a str), ()> {
+    limit_recursion_depth(parse_type_inner).parse(input, context)
+}
+
+/// Parses a type. Every type except builtin types and bare substitutions is a substitution candidate.
+fn parse_type_inner<'a>(input: &'a str, context: &mut Context<'a>) -> Result<(Rc<Type<'a>>, &'a str), ()> {
+    if let Some((type_, rest)) = parse_builtin_type(input) {
+        return Ok((Rc::new(Type::Builtin(type_)), rest));
+    }
+
+    let bytes = input.as_bytes();
+
+    let (type_, rest) = match (bytes.first().ok_or(())?, bytes.get(1)) {
+        (b'u', _) => {
+            let (name, rest) = parse_source_name(&input[1..], context)?;
+
+            (Type::Vendor(name), rest)
+        }
+        (b'r' | b'V' | b'K', _) => {
+            let (qualifiers, rest) = parse_cv_qualifiers(input);
+            let (type_, rest) = parse_type(rest, context)?;
+
+            (Type::Qualified { qualifiers, type_ }, rest)
+        }
+        (b'P', _) => {
+            let (type_, rest) = parse_type(&input[1..], context)?;
+
+            (Type::Pointer(type_), rest)
+        }
+        (b'R', _) => {
+            let (type_, rest) = parse_type(&input[1..], context)?;
+
+            (Type::LValueReference(type_), rest)
+        }
+        (b'O', _) => {
+            let (type_, rest) = parse_type(&input[1..], context)?;
+
+            (Type::RValueReference(type_), rest)
+        }
+        (b'F', _) => parse_function_type(input, false, context)?,
+        (b'D', Some(b'o')) => parse_function_type(&input[2..], true, context)?,
+        (b'D', Some(b'p')) => {
+            let (type_, rest) = parse_type(&input[2..], context)?;
+
+            (Type::PackExpansion(type_), rest)
+        }
+        (b'A', _) => {
+            let (dimension, rest) = if let Some(rest) = input[1..].strip_prefix('_') {
+                (None, rest)
+            } else {
+                let (dimension, rest) = parse_number(&input[1..], context)?;
+
+                (Some(dimension), rest.strip_prefix('_').ok_or(())?)
+            };
+
+            let (element, rest) = parse_type(rest, context)?;
+
+            (Type::Array { dimension, element }, rest)
+        }
+        (b'M', _) => {
+            let (class, rest) = parse_type(&input[1..], context)?;
+            let (member, rest) = parse_type(rest, context)?;
+
+            (Type::PointerToMember { class, member }, rest)
+        }
+        (b'T', _) => {
+            let (index, rest) = parse_template_param(input, context)?;
+            let type_ = context.push_type(Type::TemplateParam(index));
+
+            if !rest.starts_with('I') {
+                return Ok((type_, rest));
+            }
+
+            let (args, rest) = parse_template_args(rest, context)?;
+
+            (
+                Type::Named(Rc::new(Name::Template {
+                    name: Rc::new(Name::TemplateParam(index)),
+                    args,
+                })),
+                rest,
+            )
+        }
+        (b'S', next) if next != Some(&b't') => {
+            let (substitution, rest) = parse_substitution(input, context)?;
+
+            if !rest.starts_with('I') {
+                return Ok((substitution.into_type(), rest));
+            }
+
+            let name = substitution.into_name().ok_or(())?;
+            let (args, rest) = parse_template_args(rest, context)?;
+
+            (Type::Named(Rc::new(Name::Template { name, args })), rest)
+        }
+        (b'N' | b'Z' | b'S' | b'0'..=b'9', _) => {
+            let ((name, _, _), rest) = parse_name(input, context)?;
+
+            (Type::Named(name), rest)
+        }
+        _ => return Err(()),
+    };
+
+    Ok((context.push_type(type_), rest))
+}
+
+/// Parses `F [Y] <return type> <parameter types> [<ref-qualifier>] E`.
+fn parse_function_type<'a>(
+    input: &'a str,
+    is_noexcept: bool,
+    context: &mut Context<'a>,
+) -> Result<(Type<'a>, &'a str), ()> {
+    let rest = input.strip_prefix('F').ok_or(())?;
+    let (is_extern_c, rest) = rest.strip_prefix('Y').map_or((false, rest), |rest| (true, rest));
+    let (return_type, mut rest) = parse_type(rest, context)?;
+    let mut parameters = Vec::new();
+
+    let ref_qualifier = loop {
+        if let Some(next) = rest.strip_prefix('E') {
+            rest = next;
+
+            break None;
+        } else if let Some(next) = rest.strip_prefix("RE") {
+            rest = next;
+
+            break Some(RefQualifier::LValue);
+        } else if let Some(next) = rest.strip_prefix("OE") {
+            rest = next;
+
+            break Some(RefQualifier::RValue);
+        }
+
+        let (parameter, next) = parse_type(rest, context)?;
+
+        parameters.push(parameter);
+        rest = next;
+    };
+
+    remove_void_parameter(&mut parameters);
+
+    Ok((
+        Type::Function(FunctionType {
+            is_extern_c,
+            is_noexcept,
+            ref_qualifier,
+            return_type,
+            parameters,
+        }),
+        rest,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Context;
+    use crate::cxx_itanium::{BuiltinType, CvQualifiers, Name, TemplateArg, Type, UnqualifiedName};
+    use std::rc::Rc;
+
+    fn named(name: &str) -> Rc<Type<'_>> {
+        Rc::new(Type::Named(Rc::new(Name::Unqualified(UnqualifiedName::Source(name)))))
+    }
+
+    #[test]
+    fn test_parse_type() {
+        let mut context = Context::default();
+
+        assert_eq!(
+            super::parse_type("PKc", &mut context),
+            Ok((
+                Rc::new(Type::Pointer(Rc::new(Type::Qualified {
+                    qualifiers: CvQualifiers {
+                        const_: true,
+                        ..CvQualifiers::default()
+                    },
+                    type_: Rc::new(Type::Builtin(BuiltinType::Char)),
+                }))),
+                ""
+            ))
+        );
+
+        // `char const` and `char const*` are substitution candidates, `char` is not.
+        assert_eq!(context.substitutions.len(), 2);
+
+        assert_eq!(
+            super::parse_type("A10_i", &mut Context::default()),
+            Ok((
+                Rc::new(Type::Array {
+                    dimension: Some(10),
+                    element: Rc::new(Type::Builtin(BuiltinType::Int)),
+                }),
+                ""
+            ))
+        );
+
+        assert_eq!(super::parse_type("S_", &mut Context::default()), Err(()));
+        assert_eq!(super::parse_type("X1aE", &mut Context::default()), Err(()));
+        assert_eq!(super::parse_type(&"P".repeat(1000), &mut Context::default()), Err(()));
+    }
+
+    #[test]
+    fn test_parse_substitution() {
+        let mut context = Context::default();
+
+        super::parse_type("1A", &mut context).unwrap();
+        super::parse_type("1B", &mut context).unwrap();
+
+        assert_eq!(super::parse_type("S_", &mut context), Ok((named("A"), "")));
+        assert_eq!(super::parse_type("S0_", &mut context), Ok((named("B"), "")));
+        assert_eq!(super::parse_type("S1_", &mut context), Err(()));
+
+        // Bare substitutions are not substitution candidates again.
+        assert_eq!(context.substitutions.len(), 2);
+    }
+
+    #[test]
+    fn test_parse_template_arg() {
+        assert_eq!(
+            super::parse_template_arg("Lin5E", &mut Context::default()),
+            Ok((
+                TemplateArg::Literal {
+                    type_: Rc::new(Type::Builtin(BuiltinType::Int)),
+                    is_negative: true,
+                    value: "5",
+                },
+                ""
+            ))
+        );
+
+        assert_eq!(
+            super::parse_template_arg("JicE", &mut Context::default()),
+            Ok((
+                TemplateArg::Pack(vec![
+                    TemplateArg::Type(Rc::new(Type::Builtin(BuiltinType::Int))),
+                    TemplateArg::Type(Rc::new(Type::Builtin(BuiltinType::Char))),
+                ]),
+                ""
+            ))
+        );
+
+        assert_eq!(super::parse_template_arg("XT_E", &mut Context::default()), Err(()));
+    }
+
+    #[test]
+    fn test_parse_clone_suffix() {
+        assert_eq!(super::parse_clone_suffix(".isra.0.cold"), Ok((".isra.0", ".cold")));
+        assert_eq!(super::parse_clone_suffix(".cold"), Ok((".cold", "")));
+        assert_eq!(super::parse_clone_suffix(".llvm.123"), Ok((".llvm.123", "")));
+        assert_eq!(super::parse_clone_suffix(".Foo"), Err(()));
+        assert_eq!(super::parse_clone_suffix("cold"), Err(()));
+    }
+
+    #[test]
+    fn test_parse_symbol() {
+        assert!(super::parse_symbol("3foov").is_ok());
+        assert!(super::parse_symbol("N3fooC1Ev").is_ok());
+        assert!(super::parse_symbol("NC1Ev").is_err());
+        assert!(super::parse_symbol("C1Ev").is_err());
+        assert!(super::parse_symbol("3foo").is_ok());
+        assert!(super::parse_symbol("4foo").is_err());
+        assert!(super::parse_symbol("1fIiEDTfp_E").is_err());
+        assert!(super::parse_symbol("né").is_err());
+        assert!(super::parse_symbol("2aév").is_err());
+        assert_eq!(super::parse_symbol("3foov$").map(|(_, suffix)| suffix), Ok("$"));
+    }
+}
@@ -0,0 +1,188 @@
+//! Demangling symbols without knowing their mangling schemes in advance.
+
+use crate::rust_v0::DisplayStyle;
+use crate::scheme::{self, Demangle};
+use crate::{cxx_itanium, msvc, rust_legacy, rust_v0, swift};
+use std::fmt::{self, Display, Formatter};
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct DemangleError;
+
+/// A symbol parsed by [`demangle`], tagged with its mangling scheme.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[non_exhaustive]
+pub enum Symbol<'a> {
+    RustV0(rust_v0::Symbol<'a>),
+    RustLegacy(rust_legacy::Symbol<'a>),
+    CxxItanium(cxx_itanium::Symbol<'a>),
+    Msvc(msvc::Symbol<'a>),
+    Swift(swift::Symbol<'a>),
+}
+
+impl Symbol<'_> {
+    /// Returns an object that implements [`Display`] for printing the symbol.
+    #[must_use]
+    pub fn display(&self, style: DisplayStyle) -> impl Display + '_ {
+        struct Wrapper<'s, 'a>(&'s Symbol<'a>, DisplayStyle);
+
+        impl Display for Wrapper<'_, '_> {
+            fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+                match self.0 {
+                    Symbol::RustV0(symbol) => symbol.display(self.1).fmt(f),
+                    Symbol::RustLegacy(symbol) => symbol.display(self.1).fmt(f),
+                    Symbol::CxxItanium(symbol) => symbol.display(self.1).fmt(f),
+                    Symbol::Msvc(symbol) => symbol.display(self.1).fmt(f),
+                    Symbol::Swift(symbol) => symbol.display(self.1).fmt(f),
+                }
+            }
+        }
+
+        Wrapper(self, style)
+    }
+}
+
+impl Display for Symbol<'_> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::RustV0(symbol) => symbol.fmt(f),
+            Self::RustLegacy(symbol) => symbol.fmt(f),
+            Self::CxxItanium(symbol) => symbol.fmt(f),
+            Self::Msvc(symbol) => symbol.fmt(f),
+            Self::Swift(symbol) => symbol.fmt(f),
+        }
+    }
+}
+
+/// Parses `input` with the mangling scheme its prefix indicates, returns a tuple that contains a [`Symbol`] object and
+/// an [`&str`] object containing the suffix that is not part of the mangled name.
+///
+/// Rust v0 names start with `_R`, `R` or `__R`, and legacy Rust names start with `_ZN`, `ZN` or `__ZN`. Other names
+/// starting with `_Z` or `__Z` are parsed as Itanium C++ names. Since legacy Rust names are also valid C++ names, a
+/// name is only treated as a legacy Rust name if nothing but a `.` suffix follows the path, so C++ functions like
+/// `_ZN3foo3barEv` are not mistaken for legacy Rust names. Names starting with `?` are parsed as Microsoft Visual C++
+/// names, and names starting with `$s` or `_$s` are parsed as Swift names. To add other schemes or change the order,
+/// see [`Registry`](crate::scheme::Registry).
+///
+/// Parsing never panics, whatever the input, including non-ASCII text where a scheme expects ASCII codes.
+///
+/// # Errors
+///
+/// Returns [`DemangleError`] if `input` does not start with a mangled name in any supported scheme.
+pub fn demangle(input: &str) -> Result<(Symbol<'_>, &str), DemangleError> {
+    if let Ok((symbol, suffix)) = scheme::RustV0.demangle(input) {
+        Ok((Symbol::RustV0(symbol), suffix))
+    } else if let Ok((symbol, suffix)) = scheme::RustLegacy.demangle(input) {
+        Ok((Symbol::RustLegacy(symbol), suffix))
+    } else if let Ok((symbol, suffix)) = scheme::CxxItanium.demangle(input) {
+        Ok((Symbol::CxxItanium(symbol), suffix))
+    } else if let Ok((symbol, suffix)) = scheme::Msvc.demangle(input) {
+        Ok((Symbol::Msvc(symbol), suffix))
+    } else {
+        scheme::Swift
+            .demangle(input)
+            .map(|(symbol, suffix)| (Symbol::Swift(symbol), suffix))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DemangleError, Symbol};
+    use crate::rust_v0::DisplayStyle;
+    use std::fmt::Write;
+    use std::hint;
+
+    #[test]
+    fn test_demangle_padded() {
+        for (name, expected) in [
+            ("_RNvCs123_3foo3bar", "[        foo::bar]"),
+            ("_ZN3foo3bar17h05af221e174051e9E", "[        foo::bar]"),
+            ("_ZN3foo3barEv", "[      foo::bar()]"),
+            ("?bar@foo@@YAXXZ", "[  foo::bar(void)]"),
+        ] {
+            let (symbol, _) = super::demangle(name).unwrap();
+
+            assert_eq!(format!("[{symbol:>#16}]"), expected);
+        }
+    }
+
+    #[test]
+    fn test_demangle() {
+        let (symbol, suffix) = super::demangle("_RNvCs123_3foo3bar.llvm.123").unwrap();
+
+        assert!(matches!(symbol, Symbol::RustV0(_)));
+        assert_eq!(suffix, "");
+        assert_eq!(symbol.to_string(), "foo[f85]::bar");
+        assert_eq!(symbol.display(DisplayStyle::Normal).to_string(), "foo::bar");
+
+        let (symbol, suffix) = super::demangle("_ZN3foo3bar17h05af221e174051e9E.cold").unwrap();
+
+        assert!(matches!(symbol, Symbol::RustLegacy(_)));
+        assert_eq!(suffix, ".cold");
+        assert_eq!(symbol.to_string(), "foo::bar::h05af221e174051e9");
+        assert_eq!(format!("{symbol:#}"), "foo::bar");
+        assert_eq!(symbol.display(DisplayStyle::Short).to_string(), "bar");
+
+        let (symbol, suffix) = super::demangle("_ZN3foo3barEv").unwrap();
+
+        assert!(matches!(symbol, Symbol::CxxItanium(_)));
+        assert_eq!(suffix, "");
+        assert_eq!(symbol.to_string(), "foo::bar()");
+
+        let (symbol, suffix) = super::demangle("?foo@@YAHH@Z").unwrap();
+
+        assert!(matches!(symbol, Symbol::Msvc(_)));
+        assert_eq!(suffix, "");
+        assert_eq!(symbol.to_string(), "int __cdecl foo(int)");
+        assert_eq!(format!("{symbol:#}"), "foo(int)");
+
+        let (symbol, _) = super::demangle("$s4main3add1a1bS2i_SitF").unwrap();
+
+        assert!(matches!(symbol, Symbol::Swift(_)));
+        assert_eq!(symbol.display(DisplayStyle::Short).to_string(), "add(a:b:)");
+
+        assert_eq!(super::demangle("main"), Err(DemangleError));
+    }
+
+    #[test]
+    fn test_demangle_non_ascii() {
+        assert_eq!(super::demangle("??0Foo@@é"), Err(DemangleError));
+        assert_eq!(super::demangle("_Zném"), Err(DemangleError));
+        assert_eq!(super::demangle("_ZN1AléIiEEbT_"), Err(DemangleError));
+        assert_eq!(super::demangle("_ZZ4mainENKUliPcE0_céEiS0_"), Err(DemangleError));
+    }
+
+    fn check_never_panics(name: &str) {
+        if let Ok((symbol, _)) = super::demangle(name) {
+            let mut buffer = String::new();
+
+            hint::black_box(write!(buffer, "{symbol} {symbol:#}").is_ok());
+        }
+    }
+
+    #[test]
+    fn test_never_panics() {
+        let names = [
+            "_ZN3foo3bar17h05af221e174051e9E",
+            "_ZN1AleIiEEbT_",
+            "_ZZ4mainENKUliPcE0_clEiS0_",
+            "_ZNSt6vectorIiSaIiEE9push_backERKi",
+            "_ZTv0_n24_N3Foo3barEv.cold",
+            "??0Foo@@QAE@XZ",
+            "?f@ns@@YAXVA@1@PAV21@0@Z",
+            "?x@?$A@H$0A@@ns@@2HA",
+            "??$f@$$QAH@@YAX$$QAH@Z",
+            "$s4main3add1a1bS2i_SitF",
+        ];
+        let replacements = ["", "0", "_", "@", "?", "$", "E", "S_", "\u{e9}", "\u{1f980}"];
+
+        for name in names {
+            for index in 0..name.len() {
+                check_never_panics(&name[..index]);
+
+                for replacement in replacements {
+                    check_never_panics(&format!("{}{replacement}{}", &name[..index], &name[index + 1..]));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,212 @@
+//! C interface for demangling names from non-Rust code, enabled by the `ffi` feature. `include/ast_demangle.h`
+//! declares the functions and constants here.
+//!
+//! The crate is only built as a Rust library by default, so that crates depending on it do not link C libraries they
+//! do not use. To link with C or C++ programs, build a shared or static library with
+//! `cargo rustc --lib --release --features ffi --crate-type cdylib`, or with `--crate-type staticlib`.
+//!
+//! Functions here never unwind across the FFI boundary: panics are caught and reported as
+//! [`AST_DEMANGLE_ERROR_PANIC`].
+
+#![allow(unsafe_code)]
+
+use crate::rust_v0::{self, DemangleError, DisplayStyle};
+use std::ffi::{c_char, c_int, CStr};
+use std::fmt::{self, Write};
+use std::panic::{self, AssertUnwindSafe};
+use std::slice;
+
+/// Displays the name with [`DisplayStyle::Short`].
+pub const AST_DEMANGLE_STYLE_SHORT: c_int = 0;
+
+/// Displays the name with [`DisplayStyle::Normal`].
+pub const AST_DEMANGLE_STYLE_NORMAL: c_int = 1;
+
+/// Displays the name with [`DisplayStyle::Long`].
+pub const AST_DEMANGLE_STYLE_LONG: c_int = 2;
+
+/// Displays the name with [`DisplayStyle::Debuginfo`].
+pub const AST_DEMANGLE_STYLE_DEBUGINFO: c_int = 3;
+
+/// The input is not a mangled name with Rust v0 syntax.
+pub const AST_DEMANGLE_ERROR_INVALID: isize = -1;
+
+/// The symbol can not be displayed, for example because it refers to a lifetime that is not bound.
+pub const AST_DEMANGLE_ERROR_NOT_DISPLAYABLE: isize = -2;
+
+/// An argument is a null pointer or an unknown style.
+pub const AST_DEMANGLE_ERROR_INVALID_ARGUMENT: isize = -3;
+
+/// Demangling panicked. This indicates a bug in the library.
+pub const AST_DEMANGLE_ERROR_PANIC: isize = -4;
+
+/// Writes as much of the output as fits into a buffer, while counting the length of the full output.
+struct TruncatingWriter<'a> {
+    buffer: &'a mut [u8],
+    length: usize,
+}
+
+impl Write for TruncatingWriter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if let Some(rest) = self.buffer.get_mut(self.length..) {
+            let n = rest.len().min(s.len());
+
+            rest[..n].copy_from_slice(&s.as_bytes()[..n]);
+        }
+
+        self.length += s.len();
+
+        Ok(())
+    }
+}
+
+fn style_from_c_int(style: c_int) -> Option<DisplayStyle> {
+    Some(match style {
+        AST_DEMANGLE_STYLE_SHORT => DisplayStyle::Short,
+        AST_DEMANGLE_STYLE_NORMAL => DisplayStyle::Normal,
+        AST_DEMANGLE_STYLE_LONG => DisplayStyle::Long,
+        AST_DEMANGLE_STYLE_DEBUGINFO => DisplayStyle::Debuginfo,
+        _ => return None,
+    })
+}
+
+/// Demangles `mangled` into `writer`, returning the length of the full output or a negative error code.
+fn write_demangled(mangled: &CStr, writer: &mut TruncatingWriter, style: c_int) -> isize {
+    let Some(style) = style_from_c_int(style) else {
+        return AST_DEMANGLE_ERROR_INVALID_ARGUMENT;
+    };
+
+    let Ok(mangled) = mangled.to_str() else {
+        return AST_DEMANGLE_ERROR_INVALID;
+    };
+
+    match rust_v0::demangle_to_writer(mangled, style, writer) {
+        Ok("") => isize::try_from(writer.length).unwrap_or(isize::MAX),
+        Ok(_) | Err(DemangleError::Invalid | DemangleError::Write) => AST_DEMANGLE_ERROR_INVALID,
+        Err(DemangleError::NotDisplayable) => AST_DEMANGLE_ERROR_NOT_DISPLAYABLE,
+    }
+}
+
+/// Demangles `mangled` into `buffer` as a NUL-terminated string, returning the length of the full output or a negative
+/// error code.
+fn demangle_rust_v0(mangled: Option<&CStr>, buffer: &mut [u8], style: c_int) -> isize {
+    // Reserve the last byte for the terminating NUL character.
+    let capacity = buffer.len().saturating_sub(1);
+
+    let mut writer = TruncatingWriter {
+        buffer: &mut buffer[..capacity],
+        length: 0,
+    };
+
+    let result = mangled.map_or(AST_DEMANGLE_ERROR_INVALID_ARGUMENT, |mangled| {
+        write_demangled(mangled, &mut writer, style)
+    });
+
+    let terminator = if result < 0 { 0 } else { writer.length.min(capacity) };
+
+    if let Some(byte) = buffer.get_mut(terminator) {
+        *byte = 0;
+    }
+
+    result
+}
+
+/// Demangles the NUL-terminated Rust v0 mangled name `mangled`, and writes the demangled name displayed with `style`
+/// into `buffer` as a NUL-terminated string. `style` is one of the `AST_DEMANGLE_STYLE_*` constants.
+///
+/// Like `snprintf`, returns the length of the full demangled name, not counting the terminating NUL character. If the
+/// return value is not less than `length`, the output has been truncated to `length - 1` bytes, possibly in the middle
+/// of a UTF-8 sequence. `buffer` may be null if `length` is zero, which can be used to query the required size.
+///
+/// Vendor specific suffixes like `.llvm.1234` are accepted but not written. Returns one of the negative
+/// `AST_DEMANGLE_ERROR_*` constants on failure, in which case an empty string is written if `length` is not zero.
+///
+/// # Safety
+///
+/// `mangled` must point to a NUL-terminated string, and `buffer` must be valid for writing `length` bytes unless
+/// `length` is zero.
+#[no_mangle]
+pub unsafe extern "C" fn ast_demangle_rust_v0(
+    mangled: *const c_char,
+    buffer: *mut c_char,
+    length: usize,
+    style: c_int,
+) -> isize {
+    if buffer.is_null() && length != 0 {
+        return AST_DEMANGLE_ERROR_INVALID_ARGUMENT;
+    }
+
+    // SAFETY: The caller guarantees that `mangled` points to a NUL-terminated string if it is not null.
+    let mangled = (!mangled.is_null()).then(|| unsafe { CStr::from_ptr(mangled) });
+
+    let buffer: &mut [u8] = if length == 0 {
+        &mut []
+    } else {
+        // SAFETY: The caller guarantees that `buffer` is valid for writing `length` bytes.
+        unsafe { slice::from_raw_parts_mut(buffer.cast(), length) }
+    };
+
+    panic::catch_unwind(AssertUnwindSafe(|| demangle_rust_v0(mangled, buffer, style)))
+        .unwrap_or(AST_DEMANGLE_ERROR_PANIC)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::{c_char, c_int, CStr};
+    use std::ptr;
+
+    fn demangle(mangled: &CStr, length: usize, style: c_int) -> (isize, Vec<u8>) {
+        let mut buffer = vec![0xFF; length];
+
+        let result = unsafe {
+            super::ast_demangle_rust_v0(mangled.as_ptr(), buffer.as_mut_ptr().cast::<c_char>(), length, style)
+        };
+
+        (result, buffer)
+    }
+
+    #[test]
+    fn test_ast_demangle_rust_v0() {
+        let mangled = c"_RNvNtCs1234_3std3mem4swap.llvm.123";
+
+        assert_eq!(
+            demangle(mangled, 16, super::AST_DEMANGLE_STYLE_NORMAL),
+            (14, b"std::mem::swap\0\xFF".to_vec())
+        );
+
+        assert_eq!(
+            demangle(mangled, 8, super::AST_DEMANGLE_STYLE_LONG),
+            (21, b"std[3c1\0".to_vec())
+        );
+
+        assert_eq!(
+            demangle(mangled, 1, super::AST_DEMANGLE_STYLE_SHORT),
+            (4, b"\0".to_vec())
+        );
+
+        assert_eq!(
+            unsafe { super::ast_demangle_rust_v0(mangled.as_ptr(), ptr::null_mut(), 0, 0) },
+            4
+        );
+
+        let (result, buffer) = demangle(c"_RNvC3foo3bar foo", 4, super::AST_DEMANGLE_STYLE_NORMAL);
+
+        assert_eq!(result, super::AST_DEMANGLE_ERROR_INVALID);
+        assert_eq!(buffer[0], 0);
+
+        assert_eq!(
+            demangle(c"_RINvC3foo3barRL1_hE", 32, super::AST_DEMANGLE_STYLE_NORMAL).0,
+            super::AST_DEMANGLE_ERROR_NOT_DISPLAYABLE
+        );
+
+        assert_eq!(
+            demangle(mangled, 4, 42),
+            (super::AST_DEMANGLE_ERROR_INVALID_ARGUMENT, b"\0\xFF\xFF\xFF".to_vec())
+        );
+
+        assert_eq!(
+            unsafe { super::ast_demangle_rust_v0(ptr::null(), ptr::null_mut(), 0, 0) },
+            super::AST_DEMANGLE_ERROR_INVALID_ARGUMENT
+        );
+    }
+}
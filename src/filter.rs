@@ -0,0 +1,130 @@
+//! Replacing mangled names embedded in text with their demangled forms, like
+//! [rustfilt](https://github.com/luser/rustfilt) does.
+
+use crate::rust_v0::{self, DisplayStyle, Symbol};
+use std::borrow::Cow;
+use std::fmt::Write as _;
+use std::io::{self, BufRead, Write};
+
+/// Writes the demangled form of `symbol` into `out`. Vendor specific suffixes are kept, except for the `.llvm.`
+/// suffixes LLVM adds, the same way rustc-demangle does.
+fn write_demangled(symbol: &Symbol, style: DisplayStyle, out: &mut String) -> bool {
+    if write!(out, "{}", symbol.display(style)).is_err() {
+        return false;
+    }
+
+    if let Some(vendor_specific_suffix) = symbol
+        .vendor_specific_suffix
+        .as_deref()
+        .filter(|vendor_specific_suffix| !vendor_specific_suffix.starts_with(".llvm."))
+    {
+        out.push_str(vendor_specific_suffix);
+    }
+
+    true
+}
+
+/// Replaces every mangled name with Rust v0 syntax in `line` with its demangled form displayed with
+/// [`DisplayStyle::Normal`], leaving other text untouched. See [`demangle_line_with_style`].
+#[must_use]
+pub fn demangle_line(line: &str) -> Cow<'_, str> {
+    demangle_line_with_style(line, DisplayStyle::Normal)
+}
+
+/// Replaces every mangled name with Rust v0 syntax in `line` with its demangled form displayed with `style`, leaving
+/// other text untouched. Mangled names are found the same way as [`rust_v0::find_symbols`] does, and the ones that can
+/// not be displayed are kept as they are.
+///
+/// Returns `line` itself if it does not contain any mangled name.
+#[must_use]
+pub fn demangle_line_with_style(line: &str, style: DisplayStyle) -> Cow<'_, str> {
+    let mut result = String::new();
+    let mut copied = 0;
+
+    for (range, symbol, _) in rust_v0::find_symbols(line) {
+        let length = result.len();
+
+        result.push_str(&line[copied..range.start]);
+
+        if write_demangled(&symbol, style, &mut result) {
+            copied = range.end;
+        } else {
+            result.truncate(length);
+        }
+    }
+
+    if copied == 0 {
+        Cow::Borrowed(line)
+    } else {
+        result.push_str(&line[copied..]);
+
+        Cow::Owned(result)
+    }
+}
+
+/// Copies `reader` into `writer` line by line, replacing every mangled name with Rust v0 syntax with its demangled form
+/// displayed with `style`, see [`demangle_line_with_style`]. Lines that are not valid UTF-8 are copied unchanged.
+///
+/// # Errors
+///
+/// Returns an error if reading from `reader` or writing to `writer` fails.
+pub fn demangle_stream(mut reader: impl BufRead, mut writer: impl Write, style: DisplayStyle) -> io::Result<()> {
+    let mut buffer = Vec::new();
+
+    loop {
+        buffer.clear();
+
+        if reader.read_until(b'\n', &mut buffer)? == 0 {
+            break;
+        }
+
+        match std::str::from_utf8(&buffer) {
+            Ok(line) => writer.write_all(demangle_line_with_style(line, style).as_bytes())?,
+            Err(_) => writer.write_all(&buffer)?,
+        }
+    }
+
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rust_v0::DisplayStyle;
+    use std::borrow::Cow;
+
+    #[test]
+    fn test_demangle_line() {
+        let test_cases = [
+            ("no symbols here", "no symbols here"),
+            ("  at _RNvNtCs1234_3std3mem4swap+0x10", "  at std::mem::swap+0x10"),
+            (
+                "_RNvC3foo3bar, __RNvC3foo3baz and _RNvC3foo3qux.cold",
+                "foo::bar, foo::baz and foo::qux.cold",
+            ),
+            ("(_RNvC3foo3bar.llvm.123)", "(foo::bar)"),
+            ("FOO_RNvC3foo3bar _RNvC3foo _Rfoo", "FOO_RNvC3foo3bar _RNvC3foo _Rfoo"),
+            ("é _RNvC3foo3bar é", "é foo::bar é"),
+        ];
+
+        for (line, expected) in test_cases {
+            assert_eq!(super::demangle_line(line), expected);
+        }
+
+        assert!(matches!(super::demangle_line("_Rfoo bar"), Cow::Borrowed(_)));
+
+        assert_eq!(
+            super::demangle_line_with_style("_RNvCs1234_3foo3bar", DisplayStyle::Long),
+            "foo[3c1c0]::bar"
+        );
+    }
+
+    #[test]
+    fn test_demangle_stream() {
+        let input = b"first _RNvC3foo3bar\n\xFF _RNvC3foo3bar\nlast _RNvC3foo3baz";
+        let mut output = Vec::new();
+
+        super::demangle_stream(&input[..], &mut output, DisplayStyle::Normal).unwrap();
+
+        assert_eq!(output, b"first foo::bar\n\xFF _RNvC3foo3bar\nlast foo::baz");
+    }
+}
@@ -5,7 +5,7 @@
 //! Example:
 //!
 //! ```rust
-//! use ast_demangle::rust_v0::{DisplayStyle, Identifier, Path, Symbol};
+//! use ast_demangle::rust_v0::{DisplayStyle, Identifier, Namespace, Path, Symbol};
 //! use std::borrow::Cow;
 //!
 //! let mangled_name = "_RNvNtCs6GSVXm7oiwY_5regex4utf811decode_utf8.llvm.1119170478327948870";
@@ -37,28 +37,31 @@
 //!     Symbol {
 //!         version: None,
 //!         path: Path::Nested {
-//!             namespace: b'v',
+//!             namespace: Namespace::Value,
 //!             path: Path::Nested {
-//!                 namespace: b't',
+//!                 namespace: Namespace::Type,
 //!                 path: Path::CrateRoot(Identifier {
 //!                     disambiguator: 0x4df1_4705_8689_a776,
-//!                     name: Cow::Borrowed("regex")
+//!                     name: Cow::Borrowed("regex"),
+//!                     is_undecoded_punycode: false,
 //!                 })
 //!                 .into(),
 //!                 identifier: Identifier {
 //!                     disambiguator: 0,
-//!                     name: Cow::Borrowed("utf8")
+//!                     name: Cow::Borrowed("utf8"),
+//!                     is_undecoded_punycode: false,
 //!                 }
 //!             }
 //!             .into(),
 //!             identifier: Identifier {
 //!                 disambiguator: 0,
-//!                 name: Cow::Borrowed("decode_utf8")
+//!                 name: Cow::Borrowed("decode_utf8"),
+//!                 is_undecoded_punycode: false,
 //!             }
 //!         }
 //!         .into(),
 //!         instantiating_crate: None,
-//!         vendor_specific_suffix: Some(".llvm.1119170478327948870"),
+//!         vendor_specific_suffix: Some(Cow::Borrowed(".llvm.1119170478327948870")),
 //!     }
 //! );
 //! ```
@@ -70,7 +73,6 @@
     missing_abi,
     // missing_docs,
     noop_method_call,
-    pointer_structural_match,
     // single_use_lifetimes, // See <https://github.com/rust-lang/rust/issues/69952>.
     trivial_casts,
     trivial_numeric_casts,
@@ -109,7 +111,6 @@
     clippy::rc_mutex,
     clippy::rest_pat_in_fully_bound_structs,
     clippy::string_lit_as_bytes,
-    clippy::string_to_string,
     clippy::suboptimal_flops,
     clippy::suspicious_operation_groupings,
     clippy::todo,
@@ -126,5 +127,24 @@
 )]
 #![allow(clippy::module_name_repetitions, clippy::non_ascii_literal)]
 
-mod mini_parser;
+pub use self::demangle::{demangle, DemangleError, Symbol};
+
+pub mod breakpad;
+pub mod cxx_itanium;
+mod demangle;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod filter;
+pub mod mini_parser;
+pub mod msvc;
+#[cfg(feature = "object")]
+pub mod object;
+mod padding;
+#[cfg(feature = "pdb")]
+pub mod pdb;
+pub mod rust_legacy;
 pub mod rust_v0;
+pub mod scheme;
+pub mod swift;
+#[cfg(feature = "wasm")]
+pub mod wasm;
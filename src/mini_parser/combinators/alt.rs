@@ -1,6 +1,7 @@
 use crate::mini_parser::generic_tuple::{Tuple1, Tuple2};
 use crate::mini_parser::Parser;
 
+/// Tries `lhs`, then `rhs`, see [`alt`].
 pub struct AltImpl<P, Q> {
     lhs: P,
     rhs: Q,
@@ -21,9 +22,12 @@ where
     }
 }
 
+/// A tuple of parsers that can be combined with [`alt`].
 pub trait AltBuilder {
+    /// The combined parser.
     type Output;
 
+    /// Combines the parsers.
     fn build(self) -> Self::Output;
 }
 
@@ -49,6 +53,7 @@ where
     }
 }
 
+/// The parser returned by [`alt`].
 pub struct Alt<T>
 where
     T: AltBuilder,
@@ -68,6 +73,8 @@ where
     }
 }
 
+/// Returns a parser that tries each parser in the tuple `parsers` on the same input, returning the output of the first
+/// one that succeeds. All parsers must have the same output type.
 pub fn alt<T>(parsers: T) -> Alt<T>
 where
     T: AltBuilder,
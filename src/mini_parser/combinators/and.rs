@@ -1,6 +1,7 @@
 use crate::mini_parser::combinators::tuple::{self, Tuple};
 use crate::mini_parser::Parser;
 
+/// The parser returned by [`and`].
 pub struct And<P, Q> {
     inner: Tuple<(P, Q)>,
 }
@@ -17,6 +18,7 @@ where
     }
 }
 
+/// Returns a parser that applies `lhs` and then `rhs` to the rest of the input, returning both outputs as a pair.
 pub fn and<I, C, P, Q>(lhs: P, rhs: Q) -> And<P, Q>
 where
     P: Parser<I, C>,
@@ -1,5 +1,6 @@
 use crate::mini_parser::Parser;
 
+/// The parser returned by [`delimited`].
 pub struct Delimited<P, Q, R> {
     left: P,
     middle: Q,
@@ -23,6 +24,7 @@ where
     }
 }
 
+/// Returns a parser that applies `left`, `middle` and `right` in sequence, returning the output of `middle`.
 pub fn delimited<I, C, P, Q, R>(left: P, middle: Q, right: R) -> Delimited<P, Q, R>
 where
     P: Parser<I, C>,
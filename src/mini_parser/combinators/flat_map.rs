@@ -1,5 +1,6 @@
 use crate::mini_parser::Parser;
 
+/// The parser returned by [`flat_map`].
 pub struct FlatMap<P, F> {
     parser: P,
     f: F,
@@ -20,6 +21,8 @@ where
     }
 }
 
+/// Returns a parser that applies `parser`, passes its output to `f`, and applies the parser returned by `f` to the rest
+/// of the input. This allows the rest of the grammar to depend on parsed values, like lengths.
 pub fn flat_map<I, C, P, F, Q>(parser: P, f: F) -> FlatMap<P, F>
 where
     P: Parser<I, C>,
@@ -1,5 +1,6 @@
 use crate::mini_parser::Parser;
 
+/// The parser returned by [`many0`].
 pub struct Many0<P> {
     parser: P,
 }
@@ -24,6 +25,8 @@ where
     }
 }
 
+/// Returns a parser that applies `parser` repeatedly until it fails, returning the outputs. It never fails, and returns
+/// an empty vector if `parser` fails on the input.
 pub fn many0<I, C, P>(parser: P) -> Many0<P>
 where
     I: Clone,
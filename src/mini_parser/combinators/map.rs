@@ -1,5 +1,6 @@
 use crate::mini_parser::Parser;
 
+/// The parser returned by [`map`].
 pub struct Map<P, F> {
     parser: P,
     f: F,
@@ -19,6 +20,7 @@ where
     }
 }
 
+/// Returns a parser that applies `parser` and converts its output with `f`.
 pub fn map<I, C, P, F, U>(parser: P, f: F) -> Map<P, F>
 where
     P: Parser<I, C>,
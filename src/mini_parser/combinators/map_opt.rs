@@ -1,5 +1,6 @@
 use crate::mini_parser::Parser;
 
+/// The parser returned by [`map_opt`].
 pub struct MapOpt<P, F> {
     parser: P,
     f: F,
@@ -18,6 +19,8 @@ where
         Ok(((self.f)(output).ok_or(())?, input))
     }
 }
+
+/// Returns a parser that applies `parser` and converts its output with `f`, failing if `f` returns [`None`].
 pub fn map_opt<I, C, P, F, U>(parser: P, f: F) -> MapOpt<P, F>
 where
     P: Parser<I, C>,
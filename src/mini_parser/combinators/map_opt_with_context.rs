@@ -1,5 +1,6 @@
 use crate::mini_parser::Parser;
 
+/// The parser returned by [`map_opt_with_context`].
 pub struct MapOptWithContext<P, F> {
     parser: P,
     f: F,
@@ -18,6 +19,8 @@ where
         Ok(((self.f)(output, context).ok_or(())?, input))
     }
 }
+
+/// Like [`map_opt`](super::map_opt), but `f` also has access to the context.
 pub fn map_opt_with_context<I, C, P, F, U>(parser: P, f: F) -> MapOptWithContext<P, F>
 where
     P: Parser<I, C>,
@@ -0,0 +1,30 @@
+use crate::mini_parser::Parser;
+
+/// The parser returned by [`map_with_context`].
+pub struct MapWithContext<P, F> {
+    parser: P,
+    f: F,
+}
+
+impl<I, C, P, F, U> Parser<I, C> for MapWithContext<P, F>
+where
+    P: Parser<I, C>,
+    F: FnMut(P::Output, &mut C) -> U,
+{
+    type Output = U;
+
+    fn parse(&mut self, input: I, context: &mut C) -> Result<(Self::Output, I), ()> {
+        let (output, input) = self.parser.parse(input, context)?;
+
+        Ok(((self.f)(output, context), input))
+    }
+}
+
+/// Like [`map`](super::map), but `f` also has access to the context.
+pub fn map_with_context<I, C, P, F, U>(parser: P, f: F) -> MapWithContext<P, F>
+where
+    P: Parser<I, C>,
+    F: FnMut(P::Output, &mut C) -> U,
+{
+    MapWithContext { parser, f }
+}
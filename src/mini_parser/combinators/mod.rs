@@ -1,29 +1,31 @@
+//! Functions that combine parsers into new parsers.
+
 mod alt;
 mod and;
 mod delimited;
 mod flat_map;
-mod inspect_with_context;
 mod many0;
 mod map;
 mod map_opt;
 mod map_opt_with_context;
+mod map_with_context;
 mod opt;
 mod or;
 mod preceded;
 mod terminated;
 mod tuple;
 
-pub use self::alt::{alt, Alt};
-pub use self::and::{and, And};
-pub use self::delimited::{delimited, Delimited};
+pub use self::alt::alt;
+pub use self::and::and;
+pub use self::delimited::delimited;
 pub use self::flat_map::{flat_map, FlatMap};
-pub use self::inspect_with_context::{inspect_with_context, InspectWithContext};
 pub use self::many0::{many0, Many0};
 pub use self::map::{map, Map};
 pub use self::map_opt::{map_opt, MapOpt};
 pub use self::map_opt_with_context::{map_opt_with_context, MapOptWithContext};
+pub use self::map_with_context::{map_with_context, MapWithContext};
 pub use self::opt::{opt, Opt};
-pub use self::or::{or, Or};
-pub use self::preceded::{preceded, Preceded};
-pub use self::terminated::{terminated, Terminated};
-pub use self::tuple::{tuple, Tuple};
+pub use self::or::or;
+pub use self::preceded::preceded;
+pub use self::terminated::terminated;
+pub use self::tuple::tuple;
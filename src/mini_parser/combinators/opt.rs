@@ -1,5 +1,6 @@
 use crate::mini_parser::Parser;
 
+/// The parser returned by [`opt`].
 pub struct Opt<P> {
     parser: P,
 }
@@ -19,6 +20,7 @@ where
     }
 }
 
+/// Returns a parser that applies `parser`, returning [`None`] without consuming any input if it fails.
 pub fn opt<I, C, P>(parser: P) -> Opt<P>
 where
     I: Clone,
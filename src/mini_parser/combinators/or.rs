@@ -1,6 +1,7 @@
 use crate::mini_parser::combinators::alt::{self, Alt};
 use crate::mini_parser::Parser;
 
+/// The parser returned by [`or`].
 pub struct Or<P, Q> {
     inner: Alt<(P, Q)>,
 }
@@ -18,6 +19,7 @@ where
     }
 }
 
+/// Returns a parser that tries `lhs`, then `rhs` on the same input, see [`alt`](super::alt).
 pub fn or<I, C, P, Q>(lhs: P, rhs: Q) -> Or<P, Q>
 where
     I: Clone,
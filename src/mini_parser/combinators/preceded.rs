@@ -1,5 +1,6 @@
 use crate::mini_parser::Parser;
 
+/// The parser returned by [`preceded`].
 pub struct Preceded<P, Q> {
     left: P,
     right: Q,
@@ -19,6 +20,7 @@ where
     }
 }
 
+/// Returns a parser that applies `left` and then `right`, returning the output of `right`.
 pub fn preceded<I, C, P, Q>(left: P, right: Q) -> Preceded<P, Q>
 where
     P: Parser<I, C>,
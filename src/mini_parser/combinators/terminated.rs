@@ -1,5 +1,6 @@
 use crate::mini_parser::Parser;
 
+/// The parser returned by [`terminated`].
 pub struct Terminated<P, Q> {
     left: P,
     right: Q,
@@ -20,6 +21,7 @@ where
     }
 }
 
+/// Returns a parser that applies `left` and then `right`, returning the output of `left`.
 pub fn terminated<I, C, P, Q>(left: P, right: Q) -> Terminated<P, Q>
 where
     P: Parser<I, C>,
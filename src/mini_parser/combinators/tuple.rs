@@ -1,14 +1,22 @@
 use crate::mini_parser::generic_tuple::{Tuple1, TupleAppend};
 use crate::mini_parser::Parser;
 
+/// Applies `lhs`, then `rhs`, see [`tuple`].
 pub struct TupleImpl<P, Q> {
     lhs: P,
     rhs: Q,
 }
 
+/// A parser that appends its outputs to an accumulated tuple, used to implement [`tuple`].
 pub trait AccParser<A, I, C> {
+    /// The accumulated tuple with the outputs appended.
     type Output;
 
+    /// Parses `input`, appending the outputs to `acc`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err(())` if any of the parsers fails.
     fn parse(&mut self, acc: A, input: I, context: &mut C) -> Result<(Self::Output, I), ()>;
 }
 
@@ -35,9 +43,12 @@ where
     }
 }
 
+/// A tuple of parsers that can be combined with [`tuple`].
 pub trait TupleBuilder {
+    /// The combined parser.
     type Output;
 
+    /// Combines the parsers.
     fn build(self) -> Self::Output;
 }
 
@@ -61,6 +72,7 @@ where
     }
 }
 
+/// The parser returned by [`tuple`].
 pub struct Tuple<T>
 where
     T: TupleBuilder,
@@ -80,6 +92,7 @@ where
     }
 }
 
+/// Returns a parser that applies each parser in the tuple `parsers` in sequence, returning their outputs as a tuple.
 pub fn tuple<T>(parsers: T) -> Tuple<T>
 where
     T: TupleBuilder,
@@ -1,10 +1,18 @@
+//! Traits for manipulating tuples of up to 32 elements generically, used to implement [`alt`](super::combinators::alt)
+//! and [`tuple`](super::combinators::tuple).
+
+/// A tuple with at least one element.
 pub trait Tuple1 {
+    /// The type of the first element.
     type First;
+    /// The tuple of the remaining elements.
     type Rest;
 
+    /// Splits the tuple into the first element and the remaining elements.
     fn split_first(self) -> (Self::First, Self::Rest);
 }
 
+/// A tuple with at least two elements.
 pub trait Tuple2: Tuple1
 where
     Self::Rest: Tuple1,
@@ -18,9 +26,12 @@ where
 {
 }
 
+/// A tuple that can be extended with an element of type `T`.
 pub trait TupleAppend<T> {
+    /// The extended tuple.
     type Output;
 
+    /// Appends `value` to the tuple.
     fn append(self, value: T) -> Self::Output;
 }
 
@@ -1,6 +1,11 @@
+//! Traits of parser inputs, implemented for `&str`.
+
+/// An input that can be searched item by item.
 pub trait Find {
+    /// The items of the input, like `char`.
     type Item;
 
+    /// Returns the index of the first item matching `pattern`, or the length of the input if there is none.
     fn find(&self, pattern: impl FnMut(Self::Item) -> bool) -> usize;
 }
 
@@ -12,9 +17,13 @@ impl Find for &str {
     }
 }
 
+/// An input that can be split at an index.
 pub trait SplitAt: Sized {
+    /// The type of the split off prefix.
     type Prefix;
 
+    /// Splits the input into the prefix before `index` and the rest, or returns [`None`] if `index` is out of bounds
+    /// or not at an item boundary.
     fn split_at(self, index: usize) -> Option<(Self::Prefix, Self)>;
 }
 
@@ -26,13 +35,17 @@ impl SplitAt for &str {
     }
 }
 
+/// An input that can be matched against a prefix pattern of type `P`.
 pub trait StripPrefix<P>: Sized {
+    /// The type of the matched prefix.
     type Prefix;
 
+    /// Splits the input into the matched prefix and the rest, or returns [`None`] if the input does not start with
+    /// `prefix`.
     fn strip_prefix(self, prefix: P) -> Option<(Self::Prefix, Self)>;
 }
 
-impl<'a> StripPrefix<char> for &'a str {
+impl StripPrefix<char> for &str {
     type Prefix = Self;
 
     fn strip_prefix(self, prefix: char) -> Option<(Self::Prefix, Self)> {
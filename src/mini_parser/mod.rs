@@ -1,16 +1,66 @@
+//! A minimal parser combinator framework, used by the demanglers of this crate and usable for implementing other
+//! mangling schemes.
+//!
+//! A [`Parser`] consumes a prefix of an input and produces an output, with access to a mutable context that is shared
+//! by all parsers of a run, like tables of previously parsed nodes for back references, limits, or the farthest
+//! failure for error reporting. Failures carry no information, so parsers that need to report errors record them in
+//! the context.
+//!
+//! Parsers are built from the primitive parsers in [`parsers`] with the combinators in [`combinators`] and the methods
+//! of [`Parser`]. Functions and closures taking an input and a context are parsers too, which allows recursive
+//! grammars. Inputs implement the traits in [`input`], which are implemented for `&str`, and can be implemented for
+//! inputs that track additional state, like their offset in the original string.
+//!
+//! ```rust
+//! use ast_demangle::mini_parser::combinators::{preceded, terminated};
+//! use ast_demangle::mini_parser::parsers::{digit1, tag};
+//! use ast_demangle::mini_parser::Parser;
+//!
+//! /// Parses `s<number>_`, counting the parsed numbers in the context.
+//! fn parse_number<'a>(input: &'a str, count: &mut usize) -> Result<(u32, &'a str), ()> {
+//!     terminated(preceded(tag('s'), digit1), tag('_'))
+//!         .map_opt(|digits: &str| digits.parse().ok())
+//!         .map_with_context(|number, count: &mut usize| {
+//!             *count += 1;
+//!
+//!             number
+//!         })
+//!         .parse(input, count)
+//! }
+//!
+//! let mut count = 0;
+//!
+//! assert_eq!(parse_number.many0().parse("s12_s3_x", &mut count), Ok((vec![12, 3], "x")));
+//! assert_eq!(count, 2);
+//! assert_eq!(parse_number("s_", &mut count), Err(()));
+//! ```
+
+// Failures carry no information, errors are recorded in the context instead.
+#![allow(clippy::result_unit_err)]
+
 use self::combinators::{FlatMap, Map, MapOpt, Opt};
-use crate::mini_parser::combinators::{InspectWithContext, Many0, MapOptWithContext};
+use crate::mini_parser::combinators::{Many0, MapOptWithContext, MapWithContext};
 
 pub mod combinators;
 pub mod generic_tuple;
 pub mod input;
 pub mod parsers;
 
+/// A parser that consumes a prefix of an input of type `I`, with access to a context of type `C`. It is implemented for
+/// functions and closures with the signature of [`Parser::parse`].
 pub trait Parser<I, C> {
+    /// The value produced by the parser.
     type Output;
 
+    /// Parses a prefix of `input`, returning the output and the rest of the input.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err(())` if `input` does not start with a match. The context may have been modified before the
+    /// failure, and is not restored.
     fn parse(&mut self, input: I, context: &mut C) -> Result<(Self::Output, I), ()>;
 
+    /// Creates a parser with [`combinators::flat_map`].
     fn flat_map<F, Q>(self, f: F) -> FlatMap<Self, F>
     where
         Self: Sized,
@@ -20,6 +70,7 @@ pub trait Parser<I, C> {
         combinators::flat_map(self, f)
     }
 
+    /// Creates a parser with [`combinators::map`].
     fn map<F, U>(self, f: F) -> Map<Self, F>
     where
         Self: Sized,
@@ -28,6 +79,7 @@ pub trait Parser<I, C> {
         combinators::map(self, f)
     }
 
+    /// Creates a parser with [`combinators::map_opt`].
     fn map_opt<F, U>(self, f: F) -> MapOpt<Self, F>
     where
         Self: Sized,
@@ -36,22 +88,25 @@ pub trait Parser<I, C> {
         combinators::map_opt(self, f)
     }
 
-    fn map_opt_with_context<F, U>(self, f: F) -> MapOptWithContext<Self, F>
+    /// Creates a parser with [`combinators::map_with_context`].
+    fn map_with_context<F, U>(self, f: F) -> MapWithContext<Self, F>
     where
         Self: Sized,
-        F: FnMut(Self::Output, &mut C) -> Option<U>,
+        F: FnMut(Self::Output, &mut C) -> U,
     {
-        combinators::map_opt_with_context(self, f)
+        combinators::map_with_context(self, f)
     }
 
-    fn inspect_with_context<F>(self, f: F) -> InspectWithContext<Self, F>
+    /// Creates a parser with [`combinators::map_opt_with_context`].
+    fn map_opt_with_context<F, U>(self, f: F) -> MapOptWithContext<Self, F>
     where
         Self: Sized,
-        F: FnMut(&Self::Output, &mut C),
+        F: FnMut(Self::Output, &mut C) -> Option<U>,
     {
-        combinators::inspect_with_context(self, f)
+        combinators::map_opt_with_context(self, f)
     }
 
+    /// Creates a parser with [`combinators::many0`].
     fn many0(self) -> Many0<Self>
     where
         Self: Sized,
@@ -60,6 +115,7 @@ pub trait Parser<I, C> {
         combinators::many0(self)
     }
 
+    /// Creates a parser with [`combinators::opt`].
     fn opt(self) -> Opt<Self>
     where
         Self: Sized,
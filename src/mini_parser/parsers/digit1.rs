@@ -2,6 +2,7 @@ use crate::mini_parser::input::{Find, SplitAt};
 use crate::mini_parser::{parsers, Parser};
 use std::marker::PhantomData;
 
+/// The parser of [`digit1`].
 pub struct Digit1<I, C> {
     _phantom: PhantomData<fn(I, &mut C)>,
 }
@@ -23,6 +24,11 @@ where
     }
 }
 
+/// Parses one or more ASCII digits.
+///
+/// # Errors
+///
+/// Returns `Err(())` if the input does not start with a match.
 pub fn digit1<I, C>(input: I, context: &mut C) -> Result<(I::Prefix, I), ()>
 where
     I: Find<Item = char> + SplitAt,
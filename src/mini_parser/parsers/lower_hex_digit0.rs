@@ -2,6 +2,7 @@ use crate::mini_parser::input::{Find, SplitAt};
 use crate::mini_parser::{parsers, Parser};
 use std::marker::PhantomData;
 
+/// The parser of [`lower_hex_digit0`].
 pub struct LowerHexDigit0<I, C> {
     _phantom: PhantomData<fn(I, &mut C)>,
 }
@@ -19,10 +20,15 @@ where
     type Output = I::Prefix;
 
     fn parse(&mut self, input: I, context: &mut C) -> Result<(Self::Output, I), ()> {
-        parsers::take_while(|c| matches!(c, '0'..='9' | 'a'..='z')).parse(input, context)
+        parsers::take_while(|c| matches!(c, '0'..='9' | 'a'..='f')).parse(input, context)
     }
 }
 
+/// Parses zero or more lowercase hexadecimal digits.
+///
+/// # Errors
+///
+/// Returns `Err(())` if the input does not start with a match.
 pub fn lower_hex_digit0<I, C>(input: I, context: &mut C) -> Result<(I::Prefix, I), ()>
 where
     I: Find<Item = char> + SplitAt,
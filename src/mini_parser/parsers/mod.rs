@@ -1,3 +1,5 @@
+//! Primitive parsers.
+
 mod alphanumeric0;
 mod digit1;
 mod lower_hex_digit0;
@@ -6,10 +8,10 @@ mod take;
 mod take_while;
 mod take_while1;
 
-pub use self::alphanumeric0::{alphanumeric0, Alphanumeric0};
-pub use self::digit1::{digit1, Digit1};
-pub use self::lower_hex_digit0::{lower_hex_digit0, LowerHexDigit0};
-pub use self::tag::{tag, Tag};
-pub use self::take::{take, Take};
-pub use self::take_while::{take_while, TakeWhile};
-pub use self::take_while1::{take_while1, TakeWhile1};
+pub use self::alphanumeric0::alphanumeric0;
+pub use self::digit1::digit1;
+pub use self::lower_hex_digit0::lower_hex_digit0;
+pub use self::tag::tag;
+pub use self::take::take;
+pub use self::take_while::take_while;
+pub use self::take_while1::take_while1;
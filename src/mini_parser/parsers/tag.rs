@@ -2,6 +2,7 @@ use crate::mini_parser::input::StripPrefix;
 use crate::mini_parser::Parser;
 use std::marker::PhantomData;
 
+/// The parser returned by [`tag`].
 pub struct Tag<I, C, T> {
     tag: T,
     _phantom: PhantomData<fn(I, &mut C)>,
@@ -19,6 +20,7 @@ where
     }
 }
 
+/// Returns a parser that matches the prefix pattern `tag`, like a `char`, and returns the matched prefix.
 pub fn tag<I, C, T>(tag: T) -> Tag<I, C, T> {
     Tag {
         tag,
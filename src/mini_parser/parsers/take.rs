@@ -2,6 +2,7 @@ use crate::mini_parser::input::SplitAt;
 use crate::mini_parser::Parser;
 use std::marker::PhantomData;
 
+/// The parser returned by [`take`].
 pub struct Take<I, C> {
     length: usize,
     _phantom: PhantomData<fn(I, &mut C)>,
@@ -18,6 +19,9 @@ where
     }
 }
 
+/// Returns a parser that splits off the first `length` bytes of the input, failing if the input is shorter or the
+/// split is not at an item boundary.
+#[must_use]
 pub fn take<I, C>(length: usize) -> Take<I, C>
 where
     I: SplitAt,
@@ -2,6 +2,7 @@ use crate::mini_parser::input::{Find, SplitAt};
 use crate::mini_parser::Parser;
 use std::marker::PhantomData;
 
+/// The parser returned by [`take_while`].
 pub struct TakeWhile<I, C, F> {
     f: F,
     _phantom: PhantomData<fn(I, &mut C)>,
@@ -21,6 +22,7 @@ where
     }
 }
 
+/// Returns a parser that splits off the longest prefix of items matching `f`, which may be empty.
 pub fn take_while<I, C, F>(f: F) -> TakeWhile<I, C, F>
 where
     I: Find + SplitAt,
@@ -2,6 +2,7 @@ use crate::mini_parser::input::{Find, SplitAt};
 use crate::mini_parser::Parser;
 use std::marker::PhantomData;
 
+/// The parser returned by [`take_while1`].
 pub struct TakeWhile1<I, C, F> {
     f: F,
     _phantom: PhantomData<fn(I, &mut C)>,
@@ -25,6 +26,7 @@ where
     }
 }
 
+/// Like [`take_while`](super::take_while), but fails if the prefix is empty.
 pub fn take_while1<I, C, F>(f: F) -> TakeWhile1<I, C, F>
 where
     I: Find + SplitAt,
@@ -0,0 +1,569 @@
+use crate::msvc::{
+    operator_text, special_text, Access, BuiltinType, CallingConvention, CvQualifiers, DisplayStyle, DynamicTarget,
+    Encoding, Function, FunctionClass, FunctionType, MemberKind, Name, PointerKind, PointerType, RefQualifier,
+    StorageClass, Symbol, Tag, TemplateArg, Type, UnqualifiedName,
+};
+use std::fmt::{self, Write};
+use std::mem;
+use std::rc::Rc;
+
+// Output follows the formatting of LLVM `llvm-undname`, see
+// <https://github.com/llvm/llvm-project/blob/main/llvm/lib/Demangle/MicrosoftDemangleNodes.cpp>.
+
+pub fn write_symbol(symbol: &Symbol, out: &mut dyn Write, style: DisplayStyle) -> fmt::Result {
+    let mut printer = Printer {
+        out,
+        last: None,
+        style,
+        conversion_type: None,
+    };
+
+    printer.write_symbol(symbol, style)
+}
+
+struct Printer<'f, 's, 'a> {
+    out: &'f mut dyn Write,
+    /// The last character written, for deciding whether a separating space is needed.
+    last: Option<char>,
+    /// The style of the symbol being written, which is never [`DisplayStyle::Short`].
+    style: DisplayStyle,
+    /// The target type of a conversion operator, which is the return type of the function being written.
+    conversion_type: Option<&'s Rc<Type<'a>>>,
+}
+
+impl Write for Printer<'_, '_, '_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if let Some(c) = s.chars().next_back() {
+            self.last = Some(c);
+        }
+
+        self.out.write_str(s)
+    }
+}
+
+impl<'s, 'a> Printer<'_, 's, 'a> {
+    fn is_long(&self) -> bool {
+        self.style == DisplayStyle::Long
+    }
+
+    fn write_space_if_necessary(&mut self) -> fmt::Result {
+        if self.last.is_some_and(|c| c.is_ascii_alphanumeric() || c == '>') {
+            self.write_str(" ")?;
+        }
+
+        Ok(())
+    }
+
+    fn write_symbol(&mut self, symbol: &'s Symbol<'a>, style: DisplayStyle) -> fmt::Result {
+        let conversion_type = match &symbol.encoding {
+            Encoding::Function(function) => function.type_.return_type.as_ref(),
+            _ => None,
+        };
+
+        let saved_style = mem::replace(
+            &mut self.style,
+            if style == DisplayStyle::Short {
+                DisplayStyle::Normal
+            } else {
+                style
+            },
+        );
+
+        let saved_conversion_type = mem::replace(&mut self.conversion_type, conversion_type);
+
+        let result = if style == DisplayStyle::Short {
+            self.write_short_name(&symbol.name)
+        } else {
+            match &symbol.encoding {
+                Encoding::Function(function) => self.write_function(&symbol.name, function),
+                Encoding::Variable(variable) => {
+                    self.write_variable(&symbol.name, variable.storage_class, &variable.type_)
+                }
+                Encoding::Table { qualifiers, targets } => self.write_table(&symbol.name, *qualifiers, targets),
+                Encoding::Rtti => self.write_name(&symbol.name),
+            }
+        };
+
+        self.style = saved_style;
+        self.conversion_type = saved_conversion_type;
+
+        result
+    }
+
+    fn write_function(&mut self, name: &'s Name<'a>, function: &'s Function<'a>) -> fmt::Result {
+        let (access, kind) = match function.class {
+            FunctionClass::Global => (None, None),
+            FunctionClass::Member { access, kind } => (Some(access), Some(kind)),
+        };
+
+        if matches!(
+            kind,
+            Some(MemberKind::Adjustor { .. } | MemberKind::VtorDisp { .. } | MemberKind::VtorDispEx { .. })
+        ) {
+            self.write_str("[thunk]: ")?;
+        }
+
+        let type_ = &function.type_;
+
+        if self.is_long() {
+            if let Some(access) = access {
+                self.write_str(match access {
+                    Access::Private => "private: ",
+                    Access::Protected => "protected: ",
+                    Access::Public => "public: ",
+                })?;
+            }
+
+            match kind {
+                None | Some(MemberKind::NonVirtual) => {}
+                Some(MemberKind::Static) => self.write_str("static ")?,
+                Some(_) => self.write_str("virtual ")?,
+            }
+
+            if let Some(return_type) = &type_.return_type {
+                self.write_type_pre(return_type)?;
+                self.write_str(" ")?;
+            }
+
+            self.write_calling_convention(type_.calling_convention)?;
+            self.write_space_if_necessary()?;
+        }
+
+        self.write_name(name)?;
+
+        match kind {
+            Some(MemberKind::Adjustor { offset }) => write!(self, "`adjustor{{{offset}}}'")?,
+            Some(MemberKind::VtorDisp {
+                vtordisp_offset,
+                offset,
+            }) => write!(self, "`vtordisp{{{vtordisp_offset}, {offset}}}'")?,
+            Some(MemberKind::VtorDispEx {
+                vbptr_offset,
+                vboffset_offset,
+                vtordisp_offset,
+                offset,
+            }) => write!(
+                self,
+                "`vtordispex{{{vbptr_offset}, {vboffset_offset}, {vtordisp_offset}, {offset}}}'"
+            )?,
+            _ => {}
+        }
+
+        self.write_function_type_post(type_, self.is_long())
+    }
+
+    fn write_variable(&mut self, name: &'s Name<'a>, storage_class: StorageClass, type_: &'s Type<'a>) -> fmt::Result {
+        if self.is_long() {
+            let access = match storage_class {
+                StorageClass::PrivateStatic => Some("private"),
+                StorageClass::ProtectedStatic => Some("protected"),
+                StorageClass::PublicStatic => Some("public"),
+                StorageClass::Global | StorageClass::FunctionLocalStatic => None,
+            };
+
+            if let Some(access) = access {
+                write!(self, "{access}: static ")?;
+            }
+
+            self.write_type_pre(type_)?;
+            self.write_space_if_necessary()?;
+            self.write_name(name)?;
+            self.write_type_post(type_)
+        } else {
+            self.write_name(name)
+        }
+    }
+
+    fn write_table(
+        &mut self,
+        name: &'s Name<'a>,
+        qualifiers: CvQualifiers,
+        targets: &'s [Rc<Name<'a>>],
+    ) -> fmt::Result {
+        if !qualifiers.is_empty() {
+            self.write_cv_qualifiers(qualifiers, false)?;
+            self.write_str(" ")?;
+        }
+
+        self.write_name(name)?;
+
+        if let Some((first, rest)) = targets.split_first() {
+            self.write_str("{for `")?;
+            self.write_name(first)?;
+
+            for target in rest {
+                self.write_str("'s `")?;
+                self.write_name(target)?;
+            }
+
+            self.write_str("'}")?;
+        }
+
+        Ok(())
+    }
+
+    fn write_calling_convention(&mut self, calling_convention: CallingConvention) -> fmt::Result {
+        self.write_str(match calling_convention {
+            CallingConvention::Cdecl => "__cdecl",
+            CallingConvention::Pascal => "__pascal",
+            CallingConvention::Thiscall => "__thiscall",
+            CallingConvention::Stdcall => "__stdcall",
+            CallingConvention::Fastcall => "__fastcall",
+            CallingConvention::Clrcall => "__clrcall",
+            CallingConvention::Eabi => "__eabi",
+            CallingConvention::Vectorcall => "__vectorcall",
+        })
+    }
+
+    fn write_cv_qualifiers(&mut self, qualifiers: CvQualifiers, mut space_before: bool) -> fmt::Result {
+        for (is_present, text) in [
+            (qualifiers.const_, "const"),
+            (qualifiers.volatile, "volatile"),
+            (qualifiers.restrict, "__restrict"),
+        ] {
+            if is_present {
+                if space_before {
+                    self.write_str(" ")?;
+                }
+
+                self.write_str(text)?;
+                space_before = true;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Writes the parameters, the qualifiers and optionally the return type post part of a function type.
+    fn write_function_type_post(&mut self, type_: &'s FunctionType<'a>, with_return_type: bool) -> fmt::Result {
+        self.write_str("(")?;
+
+        if type_.parameters.is_empty() && !type_.is_variadic {
+            self.write_str("void")?;
+        } else {
+            let mut iter = type_.parameters.iter();
+
+            if let Some(first) = iter.next() {
+                self.write_type(first)?;
+
+                for parameter in iter {
+                    self.write_str(", ")?;
+                    self.write_type(parameter)?;
+                }
+            }
+
+            if type_.is_variadic {
+                if !type_.parameters.is_empty() {
+                    self.write_str(", ")?;
+                }
+
+                self.write_str("...")?;
+            }
+        }
+
+        self.write_str(")")?;
+
+        if let Some(this_qualifiers) = type_.this_qualifiers {
+            self.write_cv_qualifiers(this_qualifiers.qualifiers, true)?;
+
+            if this_qualifiers.is_unaligned {
+                self.write_str(" __unaligned")?;
+            }
+        }
+
+        if type_.is_noexcept {
+            self.write_str(" noexcept")?;
+        }
+
+        match type_
+            .this_qualifiers
+            .and_then(|this_qualifiers| this_qualifiers.ref_qualifier)
+        {
+            None => {}
+            Some(RefQualifier::LValue) => self.write_str(" &")?,
+            Some(RefQualifier::RValue) => self.write_str(" &&")?,
+        }
+
+        if let (true, Some(return_type)) = (with_return_type, &type_.return_type) {
+            self.write_type_post(return_type)?;
+        }
+
+        Ok(())
+    }
+
+    fn write_type(&mut self, type_: &'s Type<'a>) -> fmt::Result {
+        self.write_type_pre(type_)?;
+        self.write_type_post(type_)
+    }
+
+    fn write_type_pre(&mut self, type_: &'s Type<'a>) -> fmt::Result {
+        match type_ {
+            Type::Builtin(builtin_type) => self.write_builtin_type(*builtin_type),
+            Type::Tagged { tag, name } => {
+                if self.is_long() {
+                    self.write_str(match tag {
+                        Tag::Class => "class ",
+                        Tag::Struct => "struct ",
+                        Tag::Union => "union ",
+                        Tag::Enum => "enum ",
+                    })?;
+                }
+
+                self.write_name(name)
+            }
+            Type::Qualified { qualifiers, type_ } => {
+                self.write_type_pre(type_)?;
+
+                let space_before = !matches!(self.last, Some('*' | '&'));
+
+                self.write_cv_qualifiers(*qualifiers, space_before)
+            }
+            Type::Pointer(pointer) => self.write_pointer_type_pre(pointer),
+            Type::Function(function_type) => {
+                if let Some(return_type) = &function_type.return_type {
+                    self.write_type_pre(return_type)?;
+                    self.write_str(" ")?;
+                }
+
+                if self.is_long() {
+                    self.write_calling_convention(function_type.calling_convention)?;
+                }
+
+                Ok(())
+            }
+            Type::Array { element, .. } => self.write_type_pre(element),
+        }
+    }
+
+    fn write_type_post(&mut self, type_: &'s Type<'a>) -> fmt::Result {
+        match type_ {
+            Type::Builtin(_) | Type::Tagged { .. } => Ok(()),
+            Type::Qualified { type_, .. } => self.write_type_post(type_),
+            Type::Pointer(pointer) => {
+                if matches!(*pointer.pointee, Type::Array { .. } | Type::Function(_)) {
+                    self.write_str(")")?;
+                }
+
+                self.write_type_post(&pointer.pointee)
+            }
+            Type::Function(function_type) => self.write_function_type_post(function_type, true),
+            Type::Array { dimensions, element } => {
+                for dimension in dimensions {
+                    if *dimension == 0 {
+                        self.write_str("[]")?;
+                    } else {
+                        write!(self, "[{dimension}]")?;
+                    }
+                }
+
+                self.write_type_post(element)
+            }
+        }
+    }
+
+    fn write_pointer_type_pre(&mut self, pointer: &'s PointerType<'a>) -> fmt::Result {
+        if let Type::Function(function_type) = &*pointer.pointee {
+            // The calling convention goes inside the parentheses.
+            if let Some(return_type) = &function_type.return_type {
+                self.write_type_pre(return_type)?;
+                self.write_str(" ")?;
+            }
+        } else {
+            self.write_type_pre(&pointer.pointee)?;
+        }
+
+        self.write_space_if_necessary()?;
+
+        if pointer.is_unaligned {
+            self.write_str("__unaligned ")?;
+        }
+
+        match &*pointer.pointee {
+            Type::Array { .. } => self.write_str("(")?,
+            Type::Function(function_type) => {
+                self.write_str("(")?;
+
+                if self.is_long() {
+                    self.write_calling_convention(function_type.calling_convention)?;
+                    self.write_str(" ")?;
+                }
+            }
+            _ => {}
+        }
+
+        if let Some(class) = &pointer.class {
+            self.write_name(class)?;
+            self.write_str("::")?;
+        }
+
+        self.write_str(match pointer.kind {
+            PointerKind::Pointer => "*",
+            PointerKind::LValueReference => "&",
+            PointerKind::RValueReference => "&&",
+        })
+    }
+
+    fn write_builtin_type(&mut self, builtin_type: BuiltinType) -> fmt::Result {
+        self.write_str(match builtin_type {
+            BuiltinType::Void => "void",
+            BuiltinType::Char => "char",
+            BuiltinType::SignedChar => "signed char",
+            BuiltinType::UnsignedChar => "unsigned char",
+            BuiltinType::Short => "short",
+            BuiltinType::UnsignedShort => "unsigned short",
+            BuiltinType::Int => "int",
+            BuiltinType::UnsignedInt => "unsigned int",
+            BuiltinType::Long => "long",
+            BuiltinType::UnsignedLong => "unsigned long",
+            BuiltinType::Int64 => "__int64",
+            BuiltinType::UnsignedInt64 => "unsigned __int64",
+            BuiltinType::Float => "float",
+            BuiltinType::Double => "double",
+            BuiltinType::LongDouble => "long double",
+            BuiltinType::Bool => "bool",
+            BuiltinType::WcharT => "wchar_t",
+            BuiltinType::Char8T => "char8_t",
+            BuiltinType::Char16T => "char16_t",
+            BuiltinType::Char32T => "char32_t",
+            BuiltinType::NullptrT => "std::nullptr_t",
+        })
+    }
+
+    fn write_name(&mut self, name: &'s Name<'a>) -> fmt::Result {
+        let mut parent = None;
+
+        for scope in &name.scopes {
+            self.write_unqualified_name(scope, parent)?;
+            self.write_str("::")?;
+
+            parent = Some(&**scope);
+        }
+
+        self.write_unqualified_name(&name.name, parent)
+    }
+
+    fn write_short_name(&mut self, name: &'s Name<'a>) -> fmt::Result {
+        self.write_unqualified_name(&name.name, name.scopes.last().map(|scope| &**scope))
+    }
+
+    /// Writes an unqualified name, `parent` is the enclosing scope, which names constructors and destructors.
+    fn write_unqualified_name(
+        &mut self,
+        name: &'s UnqualifiedName<'a>,
+        parent: Option<&'s UnqualifiedName<'a>>,
+    ) -> fmt::Result {
+        match name {
+            UnqualifiedName::Identifier(identifier) => self.write_str(identifier),
+            UnqualifiedName::Template { name, args } => {
+                if let UnqualifiedName::Conversion = **name {
+                    self.write_str("operator")?;
+                    self.write_template_args(args)?;
+                    self.write_conversion_type()
+                } else {
+                    self.write_unqualified_name(name, parent)?;
+                    self.write_template_args(args)
+                }
+            }
+            UnqualifiedName::AnonymousNamespace(_) => self.write_str("`anonymous namespace'"),
+            UnqualifiedName::Local { parent, index } => {
+                self.write_str("`")?;
+                self.write_symbol(parent, self.style)?;
+                write!(self, "'::`{index}'")
+            }
+            UnqualifiedName::Constructor => self.write_structor_name(parent, false),
+            UnqualifiedName::Destructor => self.write_structor_name(parent, true),
+            UnqualifiedName::Conversion => {
+                self.write_str("operator")?;
+                self.write_conversion_type()
+            }
+            UnqualifiedName::Operator(code) => self.write_str(operator_text(code).ok_or(fmt::Error)?),
+            UnqualifiedName::Special(code) => write!(self, "`{}'", special_text(code).ok_or(fmt::Error)?),
+            UnqualifiedName::LiteralOperator(name) => write!(self, "operator \"\"{name}"),
+            UnqualifiedName::RttiTypeDescriptor(type_) => {
+                self.write_type_pre(type_)?;
+                self.write_space_if_necessary()?;
+                self.write_str("`RTTI Type Descriptor'")?;
+                self.write_type_post(type_)
+            }
+            UnqualifiedName::RttiBaseClassDescriptor {
+                nv_offset,
+                vbptr_offset,
+                vbtable_offset,
+                flags,
+            } => write!(
+                self,
+                "`RTTI Base Class Descriptor at ({nv_offset}, {vbptr_offset}, {vbtable_offset}, {flags})'"
+            ),
+            UnqualifiedName::DynamicInitializer(target) => {
+                self.write_str("`dynamic initializer for ")?;
+                self.write_dynamic_target(target)
+            }
+            UnqualifiedName::DynamicAtexitDestructor(target) => {
+                self.write_str("`dynamic atexit destructor for ")?;
+                self.write_dynamic_target(target)
+            }
+        }
+    }
+
+    fn write_structor_name(&mut self, parent: Option<&'s UnqualifiedName<'a>>, is_destructor: bool) -> fmt::Result {
+        if is_destructor {
+            self.write_str("~")?;
+        }
+
+        // Parsers reject constructors and destructors outside of classes.
+        self.write_unqualified_name(parent.ok_or(fmt::Error)?, None)
+    }
+
+    fn write_conversion_type(&mut self) -> fmt::Result {
+        self.write_str(" ")?;
+
+        // Conversion operators are always functions with return types.
+        let type_ = self.conversion_type.ok_or(fmt::Error)?;
+
+        self.write_type(type_)
+    }
+
+    fn write_dynamic_target(&mut self, target: &'s DynamicTarget<'a>) -> fmt::Result {
+        match target {
+            DynamicTarget::Name(name) => {
+                self.write_str("'")?;
+                self.write_name(name)?;
+            }
+            DynamicTarget::Variable(variable) => {
+                self.write_str("`")?;
+                self.write_symbol(variable, self.style)?;
+            }
+        }
+
+        self.write_str("''")
+    }
+
+    fn write_template_args(&mut self, args: &'s [TemplateArg<'a>]) -> fmt::Result {
+        self.write_str("<")?;
+
+        let mut iter = args.iter();
+
+        if let Some(first) = iter.next() {
+            self.write_template_arg(first)?;
+
+            for arg in iter {
+                self.write_str(", ")?;
+                self.write_template_arg(arg)?;
+            }
+        }
+
+        self.write_str(">")
+    }
+
+    fn write_template_arg(&mut self, arg: &'s TemplateArg<'a>) -> fmt::Result {
+        match arg {
+            TemplateArg::Type(type_) => self.write_type(type_),
+            TemplateArg::Integer(value) => write!(self, "{value}"),
+            TemplateArg::Address(symbol) => {
+                self.write_str("&")?;
+                self.write_symbol(symbol, self.style)
+            }
+            TemplateArg::Reference(symbol) => self.write_symbol(symbol, self.style),
+        }
+    }
+}
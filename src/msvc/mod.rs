@@ -0,0 +1,596 @@
+//! Tools for demangling C++ symbols using the Microsoft Visual C++ mangling scheme, as found in PDB files and COFF
+//! symbol tables.
+//!
+//! Functions, variables, virtual function tables, virtual base tables and RTTI data are supported, including templates,
+//! operators, constructors, destructors, anonymous namespaces, function local scopes, pointers to members, arrays,
+//! `this` adjusting thunks, and dynamic initializers and destructors. String literals (`??_C@`), local static guards,
+//! virtual call thunks, MD5 names (`??@`) and template parameter types are rejected.
+//!
+//! With [`DisplayStyle::Long`], symbols are displayed the same way as LLVM `llvm-undname` displays them.
+
+use crate::padding;
+pub use crate::rust_v0::DisplayStyle;
+use std::fmt::{self, Display, Formatter};
+use std::rc::Rc;
+
+mod display;
+mod parsers;
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct ParseSymbolError;
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Symbol<'a> {
+    pub name: Rc<Name<'a>>,
+    pub encoding: Encoding<'a>,
+}
+
+impl<'a> Symbol<'a> {
+    /// Returns an object that implements [`Display`] for printing the symbol.
+    ///
+    /// [`DisplayStyle::Short`] shows the innermost name without enclosing scopes and function parameters,
+    /// [`DisplayStyle::Normal`] and [`DisplayStyle::Debuginfo`] show the qualified name with function parameters, and
+    /// [`DisplayStyle::Long`] also shows access specifiers, calling conventions, return types and variable types.
+    #[must_use]
+    pub fn display(&self, style: DisplayStyle) -> impl Display + '_ {
+        struct Wrapper<'s, 'a>(&'s Symbol<'a>, DisplayStyle);
+
+        impl Display for Wrapper<'_, '_> {
+            fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+                padding::write_padded(f, |out| display::write_symbol(self.0, out, self.1))
+            }
+        }
+
+        Wrapper(self, style)
+    }
+
+    /// Parses `input` with the Microsoft Visual C++ mangling scheme, returns a tuple that contains a [`Symbol`] object
+    /// and an [`&str`] object containing the suffix that is not part of the mangled name. Names start with `?`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParseSymbolError`] if `input` does not start with a supported mangled name.
+    pub fn parse_from_str(input: &'a str) -> Result<(Self, &'a str), ParseSymbolError> {
+        parsers::parse_symbol(input).map_err(|()| ParseSymbolError)
+    }
+}
+
+impl Display for Symbol<'_> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.display(if f.alternate() {
+            DisplayStyle::Normal
+        } else {
+            DisplayStyle::Long
+        })
+        .fmt(f)
+    }
+}
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Encoding<'a> {
+    Function(Function<'a>),
+    Variable(Variable<'a>),
+    /// A virtual function table, a virtual base table or an RTTI complete object locator, along with the base classes
+    /// it is generated for.
+    Table {
+        qualifiers: CvQualifiers,
+        targets: Vec<Rc<Name<'a>>>,
+    },
+    /// RTTI data that is described by its name.
+    Rtti,
+}
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Function<'a> {
+    pub class: FunctionClass,
+    pub type_: FunctionType<'a>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum FunctionClass {
+    Global,
+    Member { access: Access, kind: MemberKind },
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Access {
+    Private,
+    Protected,
+    Public,
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum MemberKind {
+    NonVirtual,
+    Static,
+    Virtual,
+    /// A thunk that adjusts `this` by a constant offset before calling a virtual function.
+    Adjustor {
+        offset: i32,
+    },
+    /// A thunk that adjusts `this` with a `vtordisp` field before calling a virtual function.
+    VtorDisp {
+        vtordisp_offset: i32,
+        offset: i32,
+    },
+    /// A thunk that adjusts `this` with a `vtordisp` field and a virtual base before calling a virtual function.
+    VtorDispEx {
+        vbptr_offset: i32,
+        vboffset_offset: i32,
+        vtordisp_offset: i32,
+        offset: i32,
+    },
+}
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Variable<'a> {
+    pub storage_class: StorageClass,
+    pub type_: Rc<Type<'a>>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum StorageClass {
+    PrivateStatic,
+    ProtectedStatic,
+    PublicStatic,
+    Global,
+    FunctionLocalStatic,
+}
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Name<'a> {
+    /// The enclosing scopes, from the outermost one to the innermost one.
+    pub scopes: Vec<Rc<UnqualifiedName<'a>>>,
+    pub name: Rc<UnqualifiedName<'a>>,
+}
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum UnqualifiedName<'a> {
+    Identifier(&'a str),
+    Template {
+        name: Rc<Self>,
+        args: Vec<TemplateArg<'a>>,
+    },
+    /// An anonymous namespace, along with its mangled key, which is usually empty or a hexadecimal number.
+    AnonymousNamespace(&'a str),
+    /// A scope inside the body of `parent`, `index` tells different scopes apart.
+    Local {
+        parent: Rc<Symbol<'a>>,
+        index: u64,
+    },
+    Constructor,
+    Destructor,
+    /// A conversion operator, which converts to the return type of the function.
+    Conversion,
+    /// An operator, denoted by its code, like `H` for `operator+` or `_U` for `operator new[]`.
+    Operator(&'a str),
+    /// A compiler generated entity, denoted by its code, like `_7` for virtual function tables.
+    Special(&'a str),
+    /// A user defined literal operator, like `operator ""_km`.
+    LiteralOperator(&'a str),
+    RttiTypeDescriptor(Rc<Type<'a>>),
+    RttiBaseClassDescriptor {
+        nv_offset: u64,
+        vbptr_offset: i64,
+        vbtable_offset: u64,
+        flags: u64,
+    },
+    DynamicInitializer(DynamicTarget<'a>),
+    DynamicAtexitDestructor(DynamicTarget<'a>),
+}
+
+/// The variable that a dynamic initializer or a dynamic atexit destructor is generated for.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum DynamicTarget<'a> {
+    Name(Rc<Name<'a>>),
+    Variable(Rc<Symbol<'a>>),
+}
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Type<'a> {
+    Builtin(BuiltinType),
+    Tagged {
+        tag: Tag,
+        name: Rc<Name<'a>>,
+    },
+    Qualified {
+        qualifiers: CvQualifiers,
+        type_: Rc<Self>,
+    },
+    Pointer(PointerType<'a>),
+    Function(FunctionType<'a>),
+    /// An array, dimensions of unknown sizes are zeros.
+    Array {
+        dimensions: Vec<u64>,
+        element: Rc<Self>,
+    },
+}
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct PointerType<'a> {
+    pub kind: PointerKind,
+    /// The class of a pointer to member.
+    pub class: Option<Rc<Name<'a>>>,
+    pub is_64bit: bool,
+    pub is_unaligned: bool,
+    pub pointee: Rc<Type<'a>>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum PointerKind {
+    Pointer,
+    LValueReference,
+    RValueReference,
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Tag {
+    Class,
+    Struct,
+    Union,
+    Enum,
+}
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct FunctionType<'a> {
+    /// The qualifiers of `this`, for non-static member functions and pointers to member functions.
+    pub this_qualifiers: Option<ThisQualifiers>,
+    pub calling_convention: CallingConvention,
+    /// The return type, which is absent for constructors and destructors.
+    pub return_type: Option<Rc<Type<'a>>>,
+    /// The parameter types, which are empty for functions taking `void`.
+    pub parameters: Vec<Rc<Type<'a>>>,
+    pub is_variadic: bool,
+    pub is_noexcept: bool,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct ThisQualifiers {
+    pub qualifiers: CvQualifiers,
+    pub ref_qualifier: Option<RefQualifier>,
+    pub is_64bit: bool,
+    pub is_unaligned: bool,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct CvQualifiers {
+    pub restrict: bool,
+    pub volatile: bool,
+    pub const_: bool,
+}
+
+impl CvQualifiers {
+    #[must_use]
+    pub fn is_empty(self) -> bool {
+        !(self.restrict || self.volatile || self.const_)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum RefQualifier {
+    LValue,
+    RValue,
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum CallingConvention {
+    Cdecl,
+    Pascal,
+    Thiscall,
+    Stdcall,
+    Fastcall,
+    Clrcall,
+    Eabi,
+    Vectorcall,
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum BuiltinType {
+    Void,
+    Char,
+    SignedChar,
+    UnsignedChar,
+    Short,
+    UnsignedShort,
+    Int,
+    UnsignedInt,
+    Long,
+    UnsignedLong,
+    Int64,
+    UnsignedInt64,
+    Float,
+    Double,
+    LongDouble,
+    Bool,
+    WcharT,
+    Char8T,
+    Char16T,
+    Char32T,
+    NullptrT,
+}
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum TemplateArg<'a> {
+    Type(Rc<Type<'a>>),
+    Integer(i64),
+    /// The address of an entity, like `&x`.
+    Address(Rc<Symbol<'a>>),
+    /// A reference to an entity.
+    Reference(Rc<Symbol<'a>>),
+}
+
+/// Returns the text of an operator code, like `operator+` for `H`.
+fn operator_text(code: &str) -> Option<&'static str> {
+    Some(match code {
+        "2" => "operator new",
+        "3" => "operator delete",
+        "4" => "operator=",
+        "5" => "operator>>",
+        "6" => "operator<<",
+        "7" => "operator!",
+        "8" => "operator==",
+        "9" => "operator!=",
+        "A" => "operator[]",
+        "C" => "operator->",
+        "D" => "operator*",
+        "E" => "operator++",
+        "F" => "operator--",
+        "G" => "operator-",
+        "H" => "operator+",
+        "I" => "operator&",
+        "J" => "operator->*",
+        "K" => "operator/",
+        "L" => "operator%",
+        "M" => "operator<",
+        "N" => "operator<=",
+        "O" => "operator>",
+        "P" => "operator>=",
+        "Q" => "operator,",
+        "R" => "operator()",
+        "S" => "operator~",
+        "T" => "operator^",
+        "U" => "operator|",
+        "V" => "operator&&",
+        "W" => "operator||",
+        "X" => "operator*=",
+        "Y" => "operator+=",
+        "Z" => "operator-=",
+        "_0" => "operator/=",
+        "_1" => "operator%=",
+        "_2" => "operator>>=",
+        "_3" => "operator<<=",
+        "_4" => "operator&=",
+        "_5" => "operator|=",
+        "_6" => "operator^=",
+        "_U" => "operator new[]",
+        "_V" => "operator delete[]",
+        "__L" => "operator co_await",
+        "__M" => "operator<=>",
+        _ => return None,
+    })
+}
+
+/// Returns the text of a compiler generated entity code, like `vftable` for `_7`.
+fn special_text(code: &str) -> Option<&'static str> {
+    Some(match code {
+        "_7" => "vftable",
+        "_8" => "vbtable",
+        "_D" => "vbase dtor",
+        "_E" => "vector deleting dtor",
+        "_F" => "default ctor closure",
+        "_G" => "scalar deleting dtor",
+        "_H" => "vector ctor iterator",
+        "_I" => "vector dtor iterator",
+        "_J" => "vector vbase ctor iterator",
+        "_K" => "virtual displacement map",
+        "_L" => "eh vector ctor iterator",
+        "_M" => "eh vector dtor iterator",
+        "_N" => "eh vector vbase ctor iterator",
+        "_O" => "copy ctor closure",
+        "_R2" => "RTTI Base Class Array",
+        "_R3" => "RTTI Class Hierarchy Descriptor",
+        "_R4" => "RTTI Complete Object Locator",
+        "_S" => "local vftable",
+        "_T" => "local vftable ctor closure",
+        "_X" => "placement delete closure",
+        "_Y" => "placement delete[] closure",
+        "__A" => "managed vector ctor iterator",
+        "__B" => "managed vector dtor iterator",
+        "__C" => "EH vector copy ctor iterator",
+        "__D" => "EH vector vbase copy ctor iterator",
+        "__G" => "vector copy ctor iterator",
+        "__H" => "vector vbase copy constructor iterator",
+        "__I" => "managed vector vbase copy constructor iterator",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DisplayStyle, Symbol};
+
+    // Long style expectations are the outputs of LLVM `llvm-undname`.
+    fn check_display(test_cases: &[(&str, &str, &str, &str)]) {
+        for &(name, long, normal, short) in test_cases {
+            let (symbol, suffix) = Symbol::parse_from_str(name).unwrap();
+
+            assert_eq!(suffix, "", "{name}");
+            assert_eq!(symbol.display(DisplayStyle::Long).to_string(), long, "{name}");
+            assert_eq!(symbol.display(DisplayStyle::Normal).to_string(), normal, "{name}");
+            assert_eq!(symbol.display(DisplayStyle::Short).to_string(), short, "{name}");
+            assert_eq!(symbol.to_string(), long, "{name}");
+            assert_eq!(format!("{symbol:#}"), normal, "{name}");
+        }
+    }
+
+    #[test]
+    fn test_display_functions() {
+        check_display(&[
+            ("?foo@@YAHH@Z", "int __cdecl foo(int)", "foo(int)", "foo"),
+            (
+                "?bar@ns@@QEAAXPEBD@Z",
+                "public: void __cdecl ns::bar(char const *)",
+                "ns::bar(char const *)",
+                "bar",
+            ),
+            (
+                "??0Foo@@QAE@XZ",
+                "public: __thiscall Foo::Foo(void)",
+                "Foo::Foo(void)",
+                "Foo",
+            ),
+            (
+                "??1Foo@@UAE@XZ",
+                "public: virtual __thiscall Foo::~Foo(void)",
+                "Foo::~Foo(void)",
+                "~Foo",
+            ),
+            (
+                "??$max@H@std@@YAHAEBH0@Z",
+                "int __cdecl std::max<int>(int const &, int const &)",
+                "std::max<int>(int const &, int const &)",
+                "max<int>",
+            ),
+            (
+                "?f@?$vector@HV?$allocator@H@std@@@std@@QEAAXAEBH@Z",
+                "public: void __cdecl std::vector<int, class std::allocator<int>>::f(int const &)",
+                "std::vector<int, std::allocator<int>>::f(int const &)",
+                "f",
+            ),
+            (
+                "?f@@YAXP6AHH@Z@Z",
+                "void __cdecl f(int (__cdecl *)(int))",
+                "f(int (*)(int))",
+                "f",
+            ),
+            (
+                "?f@@YAP6AHXZXZ",
+                "int (__cdecl * __cdecl f(void))(void)",
+                "f(void)",
+                "f",
+            ),
+            (
+                "?f@@YAXP8A@@BEXXZ@Z",
+                "void __cdecl f(void (__thiscall A::*)(void) const)",
+                "f(void (A::*)(void) const)",
+                "f",
+            ),
+            (
+                "??4A@@QAEAAV0@$$QAV0@@Z",
+                "public: class A & __thiscall A::operator=(class A &&)",
+                "A::operator=(A &&)",
+                "operator=",
+            ),
+            (
+                "?g@A@@SAXXZ",
+                "public: static void __cdecl A::g(void)",
+                "A::g(void)",
+                "g",
+            ),
+            (
+                "?h@A@@AEBAHXZ",
+                "private: int __cdecl A::h(void) const",
+                "A::h(void) const",
+                "h",
+            ),
+            (
+                "??Bfoo@@QAEHXZ",
+                "public: int __thiscall foo::operator int(void)",
+                "foo::operator int(void)",
+                "operator int",
+            ),
+            (
+                "?f@A@@WBA@EAAXXZ",
+                "[thunk]: public: virtual void __cdecl A::f`adjustor{16}'(void)",
+                "[thunk]: A::f`adjustor{16}'(void)",
+                "f",
+            ),
+            (
+                "?f@A@@$4PPPPPPPM@A@EAAXXZ",
+                "[thunk]: public: virtual void __cdecl A::f`vtordisp{-4, 0}'(void)",
+                "[thunk]: A::f`vtordisp{-4, 0}'(void)",
+                "f",
+            ),
+            ("?f@@YAXZZ", "void __cdecl f(...)", "f(...)", "f"),
+            (
+                "?f@@YAXPIFAH@Z",
+                "void __cdecl f(int __unaligned *__restrict)",
+                "f(int __unaligned *__restrict)",
+                "f",
+            ),
+            (
+                "?f@?A0x123@@YAXXZ",
+                "void __cdecl `anonymous namespace'::f(void)",
+                "`anonymous namespace'::f(void)",
+                "f",
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_display_data() {
+        check_display(&[
+            ("?x@@3HA", "int x", "x", "x"),
+            ("?x@@3PEBDEB", "char const *x", "x", "x"),
+            ("?x@A@@0HB", "private: static int const A::x", "A::x", "x"),
+            ("?a@@3PAY01HA", "int (*a)[2]", "a", "a"),
+            ("?x@@3PQA@@HQ1@", "int A::*x", "x", "x"),
+            (
+                "?x@?1??f@@YAXXZ@4HA",
+                "int `void __cdecl f(void)'::`2'::x",
+                "`f(void)'::`2'::x",
+                "x",
+            ),
+            (
+                "??$f@$0?5$1?x@@3HA@@YAXXZ",
+                "void __cdecl f<-6, &int x>(void)",
+                "f<-6, &x>(void)",
+                "f<-6, &x>",
+            ),
+            (
+                "??_7Foo@@6B@",
+                "const Foo::`vftable'",
+                "const Foo::`vftable'",
+                "`vftable'",
+            ),
+            (
+                "??_7Foo@@6BBar@@@",
+                "const Foo::`vftable'{for `Bar'}",
+                "const Foo::`vftable'{for `Bar'}",
+                "`vftable'",
+            ),
+            (
+                "??_R0?AVFoo@@@8",
+                "class Foo `RTTI Type Descriptor'",
+                "Foo `RTTI Type Descriptor'",
+                "Foo `RTTI Type Descriptor'",
+            ),
+            (
+                "??_R1A@?0A@EA@Foo@@8",
+                "Foo::`RTTI Base Class Descriptor at (0, -1, 0, 64)'",
+                "Foo::`RTTI Base Class Descriptor at (0, -1, 0, 64)'",
+                "`RTTI Base Class Descriptor at (0, -1, 0, 64)'",
+            ),
+            (
+                "??__Fx@A@@YAXXZ",
+                "void __cdecl `dynamic atexit destructor for 'A::x''(void)",
+                "`dynamic atexit destructor for 'A::x''(void)",
+                "`dynamic atexit destructor for 'A::x''",
+            ),
+            (
+                "??__E?x@A@@2HA@@YAXXZ",
+                "void __cdecl `dynamic initializer for `public: static int A::x''(void)",
+                "`dynamic initializer for `A::x''(void)",
+                "`dynamic initializer for `A::x''",
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Symbol::parse_from_str("_Z3foov").is_err());
+        assert!(Symbol::parse_from_str("??_C@_05KLLGLPLH@hello?$AA@").is_err());
+        assert!(Symbol::parse_from_str("??0Foo@@é").is_err());
+        assert!(Symbol::parse_from_str("?f@ns@@é").is_err());
+    }
+}
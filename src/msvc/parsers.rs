@@ -0,0 +1,1172 @@
+use crate::mini_parser::parsers::{take_while, take_while1};
+use crate::mini_parser::Parser;
+use crate::msvc::{
+    operator_text, special_text, Access, BuiltinType, CallingConvention, CvQualifiers, DynamicTarget, Encoding,
+    Function, FunctionClass, FunctionType, MemberKind, Name, PointerKind, PointerType, RefQualifier, StorageClass,
+    Symbol, Tag, TemplateArg, ThisQualifiers, Type, UnqualifiedName, Variable,
+};
+use std::mem;
+use std::rc::Rc;
+
+// References:
+//
+// - <https://github.com/llvm/llvm-project/blob/main/llvm/lib/Demangle/MicrosoftDemangle.cpp>.
+// - <https://github.com/wine-mirror/wine/blob/master/dlls/msvcrt/undname.c>.
+
+const MAX_DEPTH: usize = 256;
+const MAX_BACK_REFERENCES: usize = 10;
+
+/// Back reference tables. Template names have their own tables.
+#[derive(Default)]
+struct Context<'a> {
+    names: Vec<Rc<UnqualifiedName<'a>>>,
+    parameters: Vec<Rc<Type<'a>>>,
+    depth: usize,
+}
+
+impl<'a> Context<'a> {
+    fn memorize_name(&mut self, name: &Rc<UnqualifiedName<'a>>) {
+        if self.names.len() < MAX_BACK_REFERENCES && !self.names.contains(name) {
+            self.names.push(Rc::clone(name));
+        }
+    }
+
+    fn memorize_parameter(&mut self, type_: &Rc<Type<'a>>) {
+        if self.parameters.len() < MAX_BACK_REFERENCES {
+            self.parameters.push(Rc::clone(type_));
+        }
+    }
+}
+
+fn limit_recursion_depth<'a, T>(
+    mut parser: impl Parser<&'a str, Context<'a>, Output = T>,
+) -> impl Parser<&'a str, Context<'a>, Output = T> {
+    move |input, context: &mut Context<'a>| {
+        if context.depth < MAX_DEPTH {
+            context.depth += 1;
+
+            let result = parser.parse(input, context);
+
+            context.depth -= 1;
+
+            result
+        } else {
+            Err(())
+        }
+    }
+}
+
+pub fn parse_symbol(input: &str) -> Result<(Symbol<'_>, &str), ()> {
+    parse_nested_symbol(input, &mut Context::default())
+}
+
+/// Parses a symbol, including the leading `?`. Symbols nested in other symbols share the back reference tables.
+fn parse_nested_symbol<'a>(input: &'a str, context: &mut Context<'a>) -> Result<(Symbol<'a>, &'a str), ()> {
+    limit_recursion_depth(|input: &'a str, context: &mut Context<'a>| {
+        let rest = input.strip_prefix('?').ok_or(())?;
+
+        if let Some(rest) = rest.strip_prefix("?__E") {
+            return parse_dynamic_structor(rest, context, UnqualifiedName::DynamicInitializer);
+        } else if let Some(rest) = rest.strip_prefix("?__F") {
+            return parse_dynamic_structor(rest, context, UnqualifiedName::DynamicAtexitDestructor);
+        }
+
+        let (name, rest) = parse_symbol_name(rest, context)?;
+        let (encoding, rest) = parse_encoding(rest, context)?;
+
+        Ok((Symbol { name, encoding }, rest))
+    })
+    .parse(input, context)
+}
+
+fn parse_dynamic_structor<'a>(
+    input: &'a str,
+    context: &mut Context<'a>,
+    constructor: fn(DynamicTarget<'a>) -> UnqualifiedName<'a>,
+) -> Result<(Symbol<'a>, &'a str), ()> {
+    let (target, rest) = if input.starts_with('?') {
+        let (variable, rest) = parse_nested_symbol(input, context)?;
+
+        if !matches!(variable.encoding, Encoding::Variable(_)) {
+            return Err(());
+        }
+
+        (
+            DynamicTarget::Variable(Rc::new(variable)),
+            rest.strip_prefix("@@").ok_or(())?,
+        )
+    } else {
+        let (name, rest) = parse_symbol_name(input, context)?;
+
+        (DynamicTarget::Name(name), rest)
+    };
+
+    let (function, rest) = parse_function(rest, context)?;
+
+    Ok((
+        Symbol {
+            name: Rc::new(Name {
+                scopes: Vec::new(),
+                name: Rc::new(constructor(target)),
+            }),
+            encoding: Encoding::Function(function),
+        },
+        rest,
+    ))
+}
+
+fn parse_encoding<'a>(input: &'a str, context: &mut Context<'a>) -> Result<(Encoding<'a>, &'a str), ()> {
+    let storage_class = match input.as_bytes().first().ok_or(())? {
+        b'0' => StorageClass::PrivateStatic,
+        b'1' => StorageClass::ProtectedStatic,
+        b'2' => StorageClass::PublicStatic,
+        b'3' => StorageClass::Global,
+        b'4' => StorageClass::FunctionLocalStatic,
+        b'6' | b'7' => {
+            let ((qualifiers, _), mut rest) = parse_qualifiers(&input[1..])?;
+            let mut targets = Vec::new();
+
+            loop {
+                if let Some(next) = rest.strip_prefix('@') {
+                    return Ok((Encoding::Table { qualifiers, targets }, next));
+                }
+
+                let (target, next) = parse_type_name(rest, context)?;
+
+                targets.push(target);
+                rest = next;
+            }
+        }
+        b'8' => return Ok((Encoding::Rtti, &input[1..])),
+        _ => {
+            let (function, rest) = parse_function(input, context)?;
+
+            return Ok((Encoding::Function(function), rest));
+        }
+    };
+
+    let (type_, rest) = parse_variable_type(&input[1..], context)?;
+
+    Ok((Encoding::Variable(Variable { storage_class, type_ }), rest))
+}
+
+/// Parses a variable type, which is followed by the qualifiers of the variable, or the qualifiers of the pointee if
+/// the variable is a pointer.
+fn parse_variable_type<'a>(input: &'a str, context: &mut Context<'a>) -> Result<(Rc<Type<'a>>, &'a str), ()> {
+    let (type_, rest) = parse_type(input, context)?;
+
+    let (outer_qualifiers, pointer) = match &*type_ {
+        Type::Pointer(pointer) => (CvQualifiers::default(), pointer),
+        Type::Qualified { qualifiers, type_ } => match &**type_ {
+            Type::Pointer(pointer) => (*qualifiers, pointer),
+            _ => return Err(()),
+        },
+        _ => {
+            let ((qualifiers, _), rest) = parse_qualifiers(rest)?;
+
+            return Ok((add_qualifiers(&type_, qualifiers), rest));
+        }
+    };
+
+    let ((is_64bit, restrict, is_unaligned), rest) = parse_pointer_extended_qualifiers(rest);
+    let ((pointee_qualifiers, is_member), mut rest) = parse_qualifiers(rest)?;
+
+    if is_member && pointer.class.is_some() {
+        (_, rest) = parse_type_name(rest, context)?;
+    }
+
+    let type_ = Rc::new(Type::Pointer(PointerType {
+        kind: pointer.kind,
+        class: pointer.class.clone(),
+        is_64bit: pointer.is_64bit || is_64bit,
+        is_unaligned: pointer.is_unaligned || is_unaligned,
+        pointee: add_qualifiers(&pointer.pointee, pointee_qualifiers),
+    }));
+
+    let outer_qualifiers = CvQualifiers {
+        restrict: outer_qualifiers.restrict || restrict,
+        ..outer_qualifiers
+    };
+
+    Ok((add_qualifiers(&type_, outer_qualifiers), rest))
+}
+
+/// Adds qualifiers to a type. Qualifiers of function types are ignored.
+fn add_qualifiers<'a>(type_: &Rc<Type<'a>>, qualifiers: CvQualifiers) -> Rc<Type<'a>> {
+    if qualifiers.is_empty() || matches!(**type_, Type::Function(_)) {
+        Rc::clone(type_)
+    } else if let Type::Qualified {
+        qualifiers: inner_qualifiers,
+        type_,
+    } = &**type_
+    {
+        Rc::new(Type::Qualified {
+            qualifiers: CvQualifiers {
+                restrict: inner_qualifiers.restrict || qualifiers.restrict,
+                volatile: inner_qualifiers.volatile || qualifiers.volatile,
+                const_: inner_qualifiers.const_ || qualifiers.const_,
+            },
+            type_: Rc::clone(type_),
+        })
+    } else {
+        Rc::new(Type::Qualified {
+            qualifiers,
+            type_: Rc::clone(type_),
+        })
+    }
+}
+
+fn parse_function<'a>(input: &'a str, context: &mut Context<'a>) -> Result<(Function<'a>, &'a str), ()> {
+    let (class, rest) = parse_function_class(input)?;
+
+    let has_this_qualifiers = !matches!(
+        class,
+        FunctionClass::Global
+            | FunctionClass::Member {
+                kind: MemberKind::Static,
+                ..
+            }
+    );
+
+    let (type_, rest) = parse_function_type(rest, has_this_qualifiers, context)?;
+
+    Ok((Function { class, type_ }, rest))
+}
+
+fn parse_function_class(input: &str) -> Result<(FunctionClass, &str), ()> {
+    let bytes = input.as_bytes();
+    let rest = input.get(1..).ok_or(())?;
+
+    let access = |group| match group {
+        0 => Access::Private,
+        1 => Access::Protected,
+        _ => Access::Public,
+    };
+
+    match bytes.first().ok_or(())? {
+        c @ b'A'..=b'X' => {
+            let index = c - b'A';
+
+            // Each access specifier has two codes for each kind of member functions, one of them is for far functions.
+            let (kind, rest) = match index % 8 / 2 {
+                0 => (MemberKind::NonVirtual, rest),
+                1 => (MemberKind::Static, rest),
+                2 => (MemberKind::Virtual, rest),
+                _ => {
+                    let (offset, rest) = parse_offset(rest)?;
+
+                    (MemberKind::Adjustor { offset }, rest)
+                }
+            };
+
+            Ok((
+                FunctionClass::Member {
+                    access: access(index / 8),
+                    kind,
+                },
+                rest,
+            ))
+        }
+        b'Y' | b'Z' => Ok((FunctionClass::Global, rest)),
+        b'$' => {
+            let (is_extended, rest) = rest.strip_prefix('R').map_or((false, rest), |rest| (true, rest));
+
+            let group = match rest.as_bytes().first() {
+                Some(c @ b'0'..=b'5') => (c - b'0') / 2,
+                _ => return Err(()),
+            };
+
+            let rest = &rest[1..];
+
+            let (kind, rest) = if is_extended {
+                let (vbptr_offset, rest) = parse_offset(rest)?;
+                let (vboffset_offset, rest) = parse_offset(rest)?;
+                let (vtordisp_offset, rest) = parse_offset(rest)?;
+                let (offset, rest) = parse_offset(rest)?;
+
+                (
+                    MemberKind::VtorDispEx {
+                        vbptr_offset,
+                        vboffset_offset,
+                        vtordisp_offset,
+                        offset,
+                    },
+                    rest,
+                )
+            } else {
+                let (vtordisp_offset, rest) = parse_offset(rest)?;
+                let (offset, rest) = parse_offset(rest)?;
+
+                (
+                    MemberKind::VtorDisp {
+                        vtordisp_offset,
+                        offset,
+                    },
+                    rest,
+                )
+            };
+
+            Ok((
+                FunctionClass::Member {
+                    access: access(group),
+                    kind,
+                },
+                rest,
+            ))
+        }
+        _ => Err(()),
+    }
+}
+
+fn parse_function_type<'a>(
+    input: &'a str,
+    has_this_qualifiers: bool,
+    context: &mut Context<'a>,
+) -> Result<(FunctionType<'a>, &'a str), ()> {
+    let (this_qualifiers, rest) = if has_this_qualifiers {
+        let ((is_64bit, restrict, is_unaligned), rest) = parse_pointer_extended_qualifiers(input);
+
+        let (ref_qualifier, rest) = match rest.as_bytes().first() {
+            Some(b'G') => (Some(RefQualifier::LValue), &rest[1..]),
+            Some(b'H') => (Some(RefQualifier::RValue), &rest[1..]),
+            _ => (None, rest),
+        };
+
+        let ((qualifiers, _), rest) = parse_qualifiers(rest)?;
+
+        (
+            Some(ThisQualifiers {
+                qualifiers: CvQualifiers { restrict, ..qualifiers },
+                ref_qualifier,
+                is_64bit,
+                is_unaligned,
+            }),
+            rest,
+        )
+    } else {
+        (None, input)
+    };
+
+    let (calling_convention, rest) = parse_calling_convention(rest)?;
+
+    let (return_type, rest) = if let Some(rest) = rest.strip_prefix('@') {
+        (None, rest)
+    } else {
+        let (return_type, rest) = parse_result_type(rest, context)?;
+
+        (Some(return_type), rest)
+    };
+
+    let ((parameters, is_variadic), rest) = parse_parameters(rest, context)?;
+
+    let (is_noexcept, rest) = if let Some(rest) = rest.strip_prefix("_E") {
+        (true, rest)
+    } else {
+        (false, rest.strip_prefix('Z').ok_or(())?)
+    };
+
+    Ok((
+        FunctionType {
+            this_qualifiers,
+            calling_convention,
+            return_type,
+            parameters,
+            is_variadic,
+            is_noexcept,
+        },
+        rest,
+    ))
+}
+
+fn parse_calling_convention(input: &str) -> Result<(CallingConvention, &str), ()> {
+    let calling_convention = match input.as_bytes().first().ok_or(())? {
+        b'A' | b'B' => CallingConvention::Cdecl,
+        b'C' | b'D' => CallingConvention::Pascal,
+        b'E' | b'F' => CallingConvention::Thiscall,
+        b'G' | b'H' => CallingConvention::Stdcall,
+        b'I' | b'J' => CallingConvention::Fastcall,
+        b'M' | b'N' => CallingConvention::Clrcall,
+        b'O' | b'P' => CallingConvention::Eabi,
+        b'Q' => CallingConvention::Vectorcall,
+        _ => return Err(()),
+    };
+
+    Ok((calling_convention, &input[1..]))
+}
+
+type Parameters<'a> = (Vec<Rc<Type<'a>>>, bool);
+
+/// Parses function parameters, along with whether the function is variadic.
+fn parse_parameters<'a>(input: &'a str, context: &mut Context<'a>) -> Result<(Parameters<'a>, &'a str), ()> {
+    if let Some(rest) = input.strip_prefix('X') {
+        return Ok(((Vec::new(), false), rest));
+    }
+
+    let mut parameters = Vec::new();
+    let mut rest = input;
+
+    loop {
+        if let Some(next) = rest.strip_prefix('@') {
+            return Ok(((parameters, false), next));
+        } else if let Some(next) = rest.strip_prefix('Z') {
+            return Ok(((parameters, true), next));
+        }
+
+        if let Some(index) = parse_back_reference(rest) {
+            parameters.push(Rc::clone(context.parameters.get(index).ok_or(())?));
+            rest = &rest[1..];
+        } else {
+            let (parameter, next) = parse_type(rest, context)?;
+
+            // Single character types are not memorized since back references do not make them any shorter.
+            if rest.len() - next.len() > 1 {
+                context.memorize_parameter(&parameter);
+            }
+
+            parameters.push(parameter);
+            rest = next;
+        }
+    }
+}
+
+fn parse_back_reference(input: &str) -> Option<usize> {
+    match input.as_bytes().first() {
+        Some(c @ b'0'..=b'9') => Some(usize::from(c - b'0')),
+        _ => None,
+    }
+}
+
+/// Parses `<digit>` as `<digit> + 1`, and `[A-P]+ @` as a hexadecimal number with `A` to `P` as digits. A leading `?`
+/// denotes a negative number.
+fn parse_number(input: &str) -> Result<((bool, u64), &str), ()> {
+    let (is_negative, rest) = input.strip_prefix('?').map_or((false, input), |rest| (true, rest));
+
+    if let Some(digit) = parse_back_reference(rest) {
+        return Ok(((is_negative, digit as u64 + 1), &rest[1..]));
+    }
+
+    let (digits, rest) = take_while(|c: char| matches!(c, 'A'..='P')).parse(rest, &mut ())?;
+
+    let rest = rest.strip_prefix('@').ok_or(())?;
+
+    let value = digits.bytes().try_fold(0_u64, |value, digit| {
+        value.checked_mul(16).map(|value| value + u64::from(digit - b'A'))
+    });
+
+    Ok(((is_negative, value.ok_or(())?), rest))
+}
+
+fn parse_unsigned_number(input: &str) -> Result<(u64, &str), ()> {
+    match parse_number(input)? {
+        ((false, value), rest) => Ok((value, rest)),
+        _ => Err(()),
+    }
+}
+
+fn parse_signed_number(input: &str) -> Result<(i64, &str), ()> {
+    let ((is_negative, value), rest) = parse_number(input)?;
+    let value = i64::try_from(value).map_err(|_| ())?;
+
+    Ok((if is_negative { -value } else { value }, rest))
+}
+
+/// Parses a `this` adjustment offset. Offsets are 32-bit, MSVC encodes negative offsets in two's complement forms like
+/// `PPPPPPPM` as well as with the `?` prefix.
+fn parse_offset(input: &str) -> Result<(i32, &str), ()> {
+    let ((is_negative, value), rest) = parse_number(input)?;
+
+    #[allow(clippy::cast_possible_truncation)]
+    let value = (value as u32).cast_signed();
+
+    Ok((if is_negative { value.wrapping_neg() } else { value }, rest))
+}
+
+/// Parses qualifiers, along with whether they are the qualifiers of a member.
+fn parse_qualifiers(input: &str) -> Result<((CvQualifiers, bool), &str), ()> {
+    let (index, is_member) = match input.as_bytes().first().ok_or(())? {
+        c @ b'A'..=b'D' => (c - b'A', false),
+        c @ b'Q'..=b'T' => (c - b'Q', true),
+        _ => return Err(()),
+    };
+
+    Ok((
+        (
+            CvQualifiers {
+                restrict: false,
+                volatile: index & 2 != 0,
+                const_: index & 1 != 0,
+            },
+            is_member,
+        ),
+        &input[1..],
+    ))
+}
+
+/// Parses the `__ptr64`, `__restrict` and `__unaligned` qualifiers.
+fn parse_pointer_extended_qualifiers(input: &str) -> ((bool, bool, bool), &str) {
+    let (is_64bit, rest) = input.strip_prefix('E').map_or((false, input), |rest| (true, rest));
+    let (restrict, rest) = rest.strip_prefix('I').map_or((false, rest), |rest| (true, rest));
+    let (is_unaligned, rest) = rest.strip_prefix('F').map_or((false, rest), |rest| (true, rest));
+
+    ((is_64bit, restrict, is_unaligned), rest)
+}
+
+/// Parses the name of a symbol, followed by its scopes.
+fn parse_symbol_name<'a>(input: &'a str, context: &mut Context<'a>) -> Result<(Rc<Name<'a>>, &'a str), ()> {
+    let (name, rest) = parse_unqualified_symbol_name(input, context)?;
+    let (scopes, rest) = parse_scopes(rest, context)?;
+
+    let untemplated_name = match &*name {
+        UnqualifiedName::Template { name, .. } => name,
+        _ => &name,
+    };
+
+    // Constructors and destructors are named after their classes.
+    if scopes.is_empty()
+        && matches!(
+            **untemplated_name,
+            UnqualifiedName::Constructor | UnqualifiedName::Destructor
+        )
+    {
+        return Err(());
+    }
+
+    Ok((Rc::new(Name { scopes, name }), rest))
+}
+
+/// Parses the name of a type, followed by its scopes.
+fn parse_type_name<'a>(input: &'a str, context: &mut Context<'a>) -> Result<(Rc<Name<'a>>, &'a str), ()> {
+    let (name, rest) = if let Some(index) = parse_back_reference(input) {
+        (Rc::clone(context.names.get(index).ok_or(())?), &input[1..])
+    } else if let Some(rest) = input.strip_prefix("?$") {
+        parse_template_name(rest, true, context)?
+    } else {
+        parse_identifier(input, context)?
+    };
+
+    let (scopes, rest) = parse_scopes(rest, context)?;
+
+    Ok((Rc::new(Name { scopes, name }), rest))
+}
+
+fn parse_unqualified_symbol_name<'a>(
+    input: &'a str,
+    context: &mut Context<'a>,
+) -> Result<(Rc<UnqualifiedName<'a>>, &'a str), ()> {
+    if let Some(index) = parse_back_reference(input) {
+        Ok((Rc::clone(context.names.get(index).ok_or(())?), &input[1..]))
+    } else if let Some(rest) = input.strip_prefix("?$") {
+        parse_template_name(rest, false, context)
+    } else if let Some(rest) = input.strip_prefix('?') {
+        let (name, rest) = parse_function_identifier(rest, context)?;
+
+        Ok((Rc::new(name), rest))
+    } else {
+        parse_identifier(input, context)
+    }
+}
+
+/// Parses scopes until an `@`, returns them from the outermost one to the innermost one.
+fn parse_scopes<'a>(input: &'a str, context: &mut Context<'a>) -> Result<(Vec<Rc<UnqualifiedName<'a>>>, &'a str), ()> {
+    let mut scopes = Vec::new();
+    let mut rest = input;
+
+    loop {
+        if let Some(next) = rest.strip_prefix('@') {
+            scopes.reverse();
+
+            return Ok((scopes, next));
+        }
+
+        let (scope, next) = parse_scope(rest, context)?;
+
+        scopes.push(scope);
+        rest = next;
+    }
+}
+
+fn parse_scope<'a>(input: &'a str, context: &mut Context<'a>) -> Result<(Rc<UnqualifiedName<'a>>, &'a str), ()> {
+    if let Some(index) = parse_back_reference(input) {
+        Ok((Rc::clone(context.names.get(index).ok_or(())?), &input[1..]))
+    } else if let Some(rest) = input.strip_prefix("?$") {
+        parse_template_name(rest, true, context)
+    } else if let Some(rest) = input.strip_prefix("?A") {
+        let (key, rest) = rest.split_once('@').ok_or(())?;
+        let name = Rc::new(UnqualifiedName::AnonymousNamespace(key));
+
+        context.memorize_name(&name);
+
+        Ok((name, rest))
+    } else if let Some((index, rest)) = parse_local_scope_index(input) {
+        let (parent, rest) = parse_nested_symbol(rest, context)?;
+
+        Ok((
+            Rc::new(UnqualifiedName::Local {
+                parent: Rc::new(parent),
+                index,
+            }),
+            rest,
+        ))
+    } else {
+        parse_identifier(input, context)
+    }
+}
+
+/// Parses `? <number> ?`, which precedes the function that a local scope belongs to.
+fn parse_local_scope_index(input: &str) -> Option<(u64, &str)> {
+    let rest = input.strip_prefix('?')?;
+
+    if rest.starts_with('A') {
+        return None;
+    }
+
+    let (index, rest) = parse_unsigned_number(rest).ok()?;
+
+    rest.starts_with("??").then(|| (index, &rest[1..]))
+}
+
+fn parse_identifier<'a>(input: &'a str, context: &mut Context<'a>) -> Result<(Rc<UnqualifiedName<'a>>, &'a str), ()> {
+    let (identifier, rest) = parse_string(input)?;
+    let name = Rc::new(UnqualifiedName::Identifier(identifier));
+
+    context.memorize_name(&name);
+
+    Ok((name, rest))
+}
+
+/// Parses a non-empty string terminated by an `@`.
+fn parse_string(input: &str) -> Result<(&str, &str), ()> {
+    let (string, rest) = take_while1(|c: char| c != '@').parse(input, &mut ())?;
+
+    Ok((string, rest.strip_prefix('@').ok_or(())?))
+}
+
+fn parse_template_name<'a>(
+    input: &'a str,
+    is_memorized: bool,
+    context: &mut Context<'a>,
+) -> Result<(Rc<UnqualifiedName<'a>>, &'a str), ()> {
+    limit_recursion_depth(|input: &'a str, context: &mut Context<'a>| {
+        let saved_names = mem::take(&mut context.names);
+        let saved_parameters = mem::take(&mut context.parameters);
+
+        let result = parse_unqualified_symbol_name(input, context)
+            .and_then(|(name, rest)| parse_template_args(rest, context).map(|(args, rest)| ((name, args), rest)));
+
+        context.names = saved_names;
+        context.parameters = saved_parameters;
+
+        let ((name, args), rest) = result?;
+        let name = Rc::new(UnqualifiedName::Template { name, args });
+
+        if is_memorized {
+            if let UnqualifiedName::Template { name: inner, .. } = &*name {
+                if matches!(
+                    **inner,
+                    UnqualifiedName::Constructor | UnqualifiedName::Destructor | UnqualifiedName::Conversion
+                ) {
+                    return Err(());
+                }
+            }
+
+            context.memorize_name(&name);
+        }
+
+        Ok((name, rest))
+    })
+    .parse(input, context)
+}
+
+/// Parses template arguments until an `@`.
+fn parse_template_args<'a>(input: &'a str, context: &mut Context<'a>) -> Result<(Vec<TemplateArg<'a>>, &'a str), ()> {
+    let mut args = Vec::new();
+    let mut rest = input;
+
+    loop {
+        if let Some(next) = rest.strip_prefix('@') {
+            return Ok((args, next));
+        }
+
+        // Empty parameter packs.
+        if let Some(next) = ["$S", "$$V", "$$$V", "$$Z"]
+            .into_iter()
+            .find_map(|prefix| rest.strip_prefix(prefix))
+        {
+            rest = next;
+
+            continue;
+        }
+
+        let (arg, next) = parse_template_arg(rest, context)?;
+
+        args.push(arg);
+        rest = next;
+    }
+}
+
+fn parse_template_arg<'a>(input: &'a str, context: &mut Context<'a>) -> Result<(TemplateArg<'a>, &'a str), ()> {
+    if let Some(rest) = input.strip_prefix("$0") {
+        let (value, rest) = parse_signed_number(rest)?;
+
+        Ok((TemplateArg::Integer(value), rest))
+    } else if let Some(rest) = input.strip_prefix("$1").filter(|rest| rest.starts_with('?')) {
+        let (symbol, rest) = parse_nested_symbol(rest, context)?;
+
+        context.memorize_name(&symbol.name.name);
+
+        Ok((TemplateArg::Address(Rc::new(symbol)), rest))
+    } else if let Some(rest) = input.strip_prefix("$E").filter(|rest| rest.starts_with('?')) {
+        let (symbol, rest) = parse_nested_symbol(rest, context)?;
+
+        Ok((TemplateArg::Reference(Rc::new(symbol)), rest))
+    } else if let Some(rest) = input.strip_prefix("$$C") {
+        let ((qualifiers, _), rest) = parse_qualifiers(rest)?;
+        let (type_, rest) = parse_type(rest, context)?;
+
+        Ok((TemplateArg::Type(add_qualifiers(&type_, qualifiers)), rest))
+    } else {
+        let (type_, rest) = parse_type(input.strip_prefix("$$B").unwrap_or(input), context)?;
+
+        Ok((TemplateArg::Type(type_), rest))
+    }
+}
+
+/// Parses the name of an operator or a compiler generated entity, without the leading `?`.
+fn parse_function_identifier<'a>(
+    input: &'a str,
+    context: &mut Context<'a>,
+) -> Result<(UnqualifiedName<'a>, &'a str), ()> {
+    if let Some(rest) = input.strip_prefix("__K") {
+        let (name, rest) = parse_string(rest)?;
+
+        return Ok((UnqualifiedName::LiteralOperator(name), rest));
+    } else if let Some(rest) = input.strip_prefix("_R0") {
+        let (type_, rest) = parse_result_type(rest, context)?;
+
+        return Ok((UnqualifiedName::RttiTypeDescriptor(type_), rest));
+    } else if let Some(rest) = input.strip_prefix("_R1") {
+        let (nv_offset, rest) = parse_unsigned_number(rest)?;
+        let (vbptr_offset, rest) = parse_signed_number(rest)?;
+        let (vbtable_offset, rest) = parse_unsigned_number(rest)?;
+        let (flags, rest) = parse_unsigned_number(rest)?;
+
+        return Ok((
+            UnqualifiedName::RttiBaseClassDescriptor {
+                nv_offset,
+                vbptr_offset,
+                vbtable_offset,
+                flags,
+            },
+            rest,
+        ));
+    }
+
+    let length = if input.starts_with("__") || input.starts_with("_R") {
+        3
+    } else if input.starts_with('_') {
+        2
+    } else {
+        1
+    };
+
+    let code = input.get(..length).ok_or(())?;
+    let rest = &input[length..];
+
+    let name = match code {
+        "0" => UnqualifiedName::Constructor,
+        "1" => UnqualifiedName::Destructor,
+        "B" => UnqualifiedName::Conversion,
+        _ if operator_text(code).is_some() => UnqualifiedName::Operator(code),
+        _ if special_text(code).is_some() => UnqualifiedName::Special(code),
+        _ => return Err(()),
+    };
+
+    Ok((name, rest))
+}
+
+/// Parses a type that may be preceded by `?` and its qualifiers, like return types.
+fn parse_result_type<'a>(input: &'a str, context: &mut Context<'a>) -> Result<(Rc<Type<'a>>, &'a str), ()> {
+    if let Some(rest) = input.strip_prefix('?') {
+        let ((qualifiers, _), rest) = parse_qualifiers(rest)?;
+        let (type_, rest) = parse_type(rest, context)?;
+
+        Ok((add_qualifiers(&type_, qualifiers), rest))
+    } else {
+        parse_type(input, context)
+    }
+}
+
+fn parse_builtin_type(input: &str) -> Option<(BuiltinType, &str)> {
+    let bytes = input.as_bytes();
+
+    let (builtin_type, length) = match bytes.first()? {
+        b'X' => (BuiltinType::Void, 1),
+        b'C' => (BuiltinType::SignedChar, 1),
+        b'D' => (BuiltinType::Char, 1),
+        b'E' => (BuiltinType::UnsignedChar, 1),
+        b'F' => (BuiltinType::Short, 1),
+        b'G' => (BuiltinType::UnsignedShort, 1),
+        b'H' => (BuiltinType::Int, 1),
+        b'I' => (BuiltinType::UnsignedInt, 1),
+        b'J' => (BuiltinType::Long, 1),
+        b'K' => (BuiltinType::UnsignedLong, 1),
+        b'M' => (BuiltinType::Float, 1),
+        b'N' => (BuiltinType::Double, 1),
+        b'O' => (BuiltinType::LongDouble, 1),
+        b'_' => (
+            match bytes.get(1)? {
+                b'J' => BuiltinType::Int64,
+                b'K' => BuiltinType::UnsignedInt64,
+                b'N' => BuiltinType::Bool,
+                b'Q' => BuiltinType::Char8T,
+                b'S' => BuiltinType::Char16T,
+                b'U' => BuiltinType::Char32T,
+                b'W' => BuiltinType::WcharT,
+                _ => return None,
+            },
+            2,
+        ),
+        b'$' if input.starts_with("$$T") => (BuiltinType::NullptrT, 3),
+        _ => return None,
+    };
+
+    Some((builtin_type, &input[length..]))
+}
+
+fn parse_type<'a>(input: &'a str, context: &mut Context<'a>) -> Result<(Rc<Type<'a>>, &'a str), ()> {
+    limit_recursion_depth(parse_type_inner).parse(input, context)
+}
+
+fn parse_type_inner<'a>(input: &'a str, context: &mut Context<'a>) -> Result<(Rc<Type<'a>>, &'a str), ()> {
+    if let Some((builtin_type, rest)) = parse_builtin_type(input) {
+        return Ok((Rc::new(Type::Builtin(builtin_type)), rest));
+    }
+
+    let bytes = input.as_bytes();
+
+    let tag = match bytes.first().ok_or(())? {
+        b'T' => Some((Tag::Union, 1)),
+        b'U' => Some((Tag::Struct, 1)),
+        b'V' => Some((Tag::Class, 1)),
+        b'W' if bytes.get(1) == Some(&b'4') => Some((Tag::Enum, 2)),
+        _ => None,
+    };
+
+    if let Some((tag, length)) = tag {
+        let (name, rest) = parse_type_name(&input[length..], context)?;
+
+        return Ok((Rc::new(Type::Tagged { tag, name }), rest));
+    }
+
+    let pointer = match bytes.first() {
+        Some(b'A') => Some((PointerKind::LValueReference, false, false, &input[1..])),
+        Some(b'P') => Some((PointerKind::Pointer, false, false, &input[1..])),
+        Some(b'Q') => Some((PointerKind::Pointer, true, false, &input[1..])),
+        Some(b'R') => Some((PointerKind::Pointer, false, true, &input[1..])),
+        Some(b'S') => Some((PointerKind::Pointer, true, true, &input[1..])),
+        _ => input
+            .strip_prefix("$$Q")
+            .map(|rest| (PointerKind::RValueReference, false, false, rest)),
+    };
+
+    if let Some((kind, const_, volatile, rest)) = pointer {
+        return parse_pointer_type(
+            rest,
+            kind,
+            CvQualifiers {
+                restrict: false,
+                volatile,
+                const_,
+            },
+            context,
+        );
+    }
+
+    if let Some(rest) = input.strip_prefix('Y') {
+        parse_array_type(rest, context)
+    } else if let Some(rest) = input.strip_prefix("$$A6") {
+        let (function_type, rest) = parse_function_type(rest, false, context)?;
+
+        Ok((Rc::new(Type::Function(function_type)), rest))
+    } else if let Some(rest) = input.strip_prefix("$$A8@@") {
+        let (function_type, rest) = parse_function_type(rest, true, context)?;
+
+        Ok((Rc::new(Type::Function(function_type)), rest))
+    } else {
+        Err(())
+    }
+}
+
+/// Parses a pointer or reference type after the code denoting its kind and qualifiers.
+fn parse_pointer_type<'a>(
+    input: &'a str,
+    kind: PointerKind,
+    qualifiers: CvQualifiers,
+    context: &mut Context<'a>,
+) -> Result<(Rc<Type<'a>>, &'a str), ()> {
+    let (pointer, qualifiers, rest) = if let Some(rest) = input.strip_prefix('6') {
+        let (function_type, rest) = parse_function_type(rest, false, context)?;
+
+        (
+            PointerType {
+                kind,
+                class: None,
+                is_64bit: false,
+                is_unaligned: false,
+                pointee: Rc::new(Type::Function(function_type)),
+            },
+            qualifiers,
+            rest,
+        )
+    } else {
+        let ((is_64bit, restrict, is_unaligned), rest) = parse_pointer_extended_qualifiers(input);
+
+        let (class, pointee, rest) = if let Some(rest) = rest.strip_prefix('8') {
+            let (class, rest) = parse_type_name(rest, context)?;
+            let (function_type, rest) = parse_function_type(rest, true, context)?;
+
+            (Some(class), Rc::new(Type::Function(function_type)), rest)
+        } else {
+            let ((pointee_qualifiers, is_member), rest) = parse_qualifiers(rest)?;
+
+            let (class, rest) = if is_member {
+                let (class, rest) = parse_type_name(rest, context)?;
+
+                (Some(class), rest)
+            } else {
+                (None, rest)
+            };
+
+            let (pointee, rest) = parse_type(rest, context)?;
+
+            (class, add_qualifiers(&pointee, pointee_qualifiers), rest)
+        };
+
+        (
+            PointerType {
+                kind,
+                class,
+                is_64bit,
+                is_unaligned,
+                pointee,
+            },
+            CvQualifiers { restrict, ..qualifiers },
+            rest,
+        )
+    };
+
+    Ok((add_qualifiers(&Rc::new(Type::Pointer(pointer)), qualifiers), rest))
+}
+
+fn parse_array_type<'a>(input: &'a str, context: &mut Context<'a>) -> Result<(Rc<Type<'a>>, &'a str), ()> {
+    let (rank, mut rest) = parse_unsigned_number(input)?;
+
+    if rank == 0 {
+        return Err(());
+    }
+
+    let mut dimensions = Vec::new();
+
+    for _ in 0..rank {
+        let (dimension, next) = parse_unsigned_number(rest)?;
+
+        dimensions.push(dimension);
+        rest = next;
+    }
+
+    let (qualifiers, rest) = if let Some(rest) = rest.strip_prefix("$$C") {
+        match parse_qualifiers(rest)? {
+            ((qualifiers, false), rest) => (qualifiers, rest),
+            _ => return Err(()),
+        }
+    } else {
+        (CvQualifiers::default(), rest)
+    };
+
+    let (element, rest) = parse_type(rest, context)?;
+
+    Ok((
+        Rc::new(Type::Array {
+            dimensions,
+            element: add_qualifiers(&element, qualifiers),
+        }),
+        rest,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::msvc::{
+        BuiltinType, CallingConvention, CvQualifiers, Encoding, FunctionClass, FunctionType, Name, PointerKind,
+        PointerType, StorageClass, Tag, TemplateArg, Type, UnqualifiedName, Variable,
+    };
+    use std::rc::Rc;
+
+    fn identifier(name: &str) -> Rc<UnqualifiedName<'_>> {
+        Rc::new(UnqualifiedName::Identifier(name))
+    }
+
+    fn name(name: &str) -> Rc<Name<'_>> {
+        Rc::new(Name {
+            scopes: Vec::new(),
+            name: identifier(name),
+        })
+    }
+
+    #[test]
+    fn test_parse_number() {
+        let test_cases = [
+            ("0", ((false, 1), "")),
+            ("9x", ((false, 10), "x")),
+            ("A@", ((false, 0), "")),
+            ("BA@", ((false, 16), "")),
+            ("?5", ((true, 6), "")),
+            ("@", ((false, 0), "")),
+        ];
+
+        for (input, expected) in test_cases {
+            assert_eq!(super::parse_number(input), Ok(expected), "{input}");
+        }
+
+        assert!(super::parse_number("Q@").is_err());
+        assert!(super::parse_number("AB").is_err());
+        assert!(super::parse_number("BAAAAAAAAAAAAAAAA@").is_err());
+    }
+
+    #[test]
+    fn test_parse_type() {
+        let int = Rc::new(Type::Builtin(BuiltinType::Int));
+
+        let const_int = Rc::new(Type::Qualified {
+            qualifiers: CvQualifiers {
+                const_: true,
+                ..CvQualifiers::default()
+            },
+            type_: Rc::clone(&int),
+        });
+
+        let pointer = |pointee, class| {
+            Rc::new(Type::Pointer(PointerType {
+                kind: PointerKind::Pointer,
+                class,
+                is_64bit: false,
+                is_unaligned: false,
+                pointee,
+            }))
+        };
+
+        let test_cases = [
+            ("H", Rc::clone(&int)),
+            ("$$T", Rc::new(Type::Builtin(BuiltinType::NullptrT))),
+            (
+                "VA@ns@@",
+                Rc::new(Type::Tagged {
+                    tag: Tag::Class,
+                    name: Rc::new(Name {
+                        scopes: vec![identifier("ns")],
+                        name: identifier("A"),
+                    }),
+                }),
+            ),
+            ("PBH", pointer(Rc::clone(&const_int), None)),
+            (
+                "QAH",
+                Rc::new(Type::Qualified {
+                    qualifiers: CvQualifiers {
+                        const_: true,
+                        ..CvQualifiers::default()
+                    },
+                    type_: pointer(Rc::clone(&int), None),
+                }),
+            ),
+            ("PQA@@H", pointer(Rc::clone(&int), Some(name("A")))),
+            (
+                "P6AXXZ",
+                pointer(
+                    Rc::new(Type::Function(FunctionType {
+                        this_qualifiers: None,
+                        calling_convention: CallingConvention::Cdecl,
+                        return_type: Some(Rc::new(Type::Builtin(BuiltinType::Void))),
+                        parameters: Vec::new(),
+                        is_variadic: false,
+                        is_noexcept: false,
+                    })),
+                    None,
+                ),
+            ),
+            (
+                "Y01$$CBH",
+                Rc::new(Type::Array {
+                    dimensions: vec![2],
+                    element: const_int,
+                }),
+            ),
+        ];
+
+        for (input, expected) in test_cases {
+            assert_eq!(
+                super::parse_type(input, &mut super::Context::default()),
+                Ok((expected, "")),
+                "{input}"
+            );
+        }
+
+        assert!(super::parse_type("L", &mut super::Context::default()).is_err());
+        assert!(super::parse_type("V0@", &mut super::Context::default()).is_err());
+    }
+
+    #[test]
+    fn test_parse_symbol() {
+        let (symbol, suffix) = super::parse_symbol("?x@?$A@H$0A@@ns@@2HA$").unwrap();
+
+        assert_eq!(suffix, "$");
+
+        assert_eq!(
+            *symbol.name,
+            Name {
+                scopes: vec![
+                    identifier("ns"),
+                    Rc::new(UnqualifiedName::Template {
+                        name: identifier("A"),
+                        args: vec![
+                            TemplateArg::Type(Rc::new(Type::Builtin(BuiltinType::Int))),
+                            TemplateArg::Integer(0),
+                        ],
+                    }),
+                ],
+                name: identifier("x"),
+            }
+        );
+
+        assert_eq!(
+            symbol.encoding,
+            Encoding::Variable(Variable {
+                storage_class: StorageClass::PublicStatic,
+                type_: Rc::new(Type::Builtin(BuiltinType::Int)),
+            })
+        );
+
+        // Back references to names and parameter types.
+        let (symbol, _) = super::parse_symbol("?f@ns@@YAXVA@1@PAV21@0@Z").unwrap();
+
+        let Encoding::Function(function) = symbol.encoding else {
+            panic!();
+        };
+
+        assert_eq!(function.class, FunctionClass::Global);
+        assert_eq!(function.type_.parameters.len(), 3);
+        assert_eq!(function.type_.parameters[0], function.type_.parameters[2]);
+
+        assert!(super::parse_symbol("??0@QAE@XZ").is_err());
+        assert!(super::parse_symbol("?f@@YAX0@Z").is_err());
+        assert!(super::parse_symbol("?f@@YAXH").is_err());
+
+        // Non-ASCII characters where codes are expected.
+        assert!(super::parse_symbol("??0Foo@@é").is_err());
+        assert!(super::parse_symbol("?f@@é").is_err());
+        assert!(super::parse_symbol("?f@@$é").is_err());
+        assert!(super::parse_symbol("?f@@YAé").is_err());
+        assert!(super::parse_symbol("?f@@YAXé").is_err());
+        assert!(super::parse_symbol("?é").is_err());
+    }
+}
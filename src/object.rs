@@ -0,0 +1,139 @@
+//! Demangling the symbol tables of object files, executables and shared libraries.
+
+use crate::rust_v0::{Demangler, NodeRc, Symbol};
+use ::object::{Object, ObjectSymbol as _};
+use std::str;
+
+/// The name of a symbol in a symbol table, see [`demangle_symbols`].
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum SymbolName<'data> {
+    /// A name with Rust v0 syntax, together with the suffix that is not part of the syntax.
+    Rust {
+        symbol: NodeRc<Symbol<'static>>,
+        suffix: &'data str,
+    },
+    /// Any other name, as it is stored in the symbol table.
+    Raw(&'data [u8]),
+}
+
+/// A symbol in a symbol table, see [`demangle_symbols`].
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct ObjectSymbol<'data> {
+    /// The address of the symbol.
+    pub address: u64,
+    /// The size of the symbol, which is 0 if the format does not record it.
+    pub size: u64,
+    /// The name of the symbol.
+    pub name: SymbolName<'data>,
+}
+
+/// Reads the symbols that `data`, the contents of an object file in any format the `object` crate supports, defines,
+/// and demangles the ones with Rust v0 mangled names with `demangler`, which shares parse results between duplicate
+/// names. If the file has no symbol table, like stripped executables, the dynamic symbol table is read instead.
+///
+/// ```rust,no_run
+/// use ast_demangle::object::{self, SymbolName};
+/// use ast_demangle::rust_v0::{Demangler, DisplayStyle};
+///
+/// let data = std::fs::read("a.out").unwrap();
+///
+/// for symbol in object::demangle_symbols(&data, &mut Demangler::default()).unwrap() {
+///     if let SymbolName::Rust { symbol: demangled, .. } = &symbol.name {
+///         println!("{:#x} {}", symbol.address, demangled.display(DisplayStyle::Normal));
+///     }
+/// }
+/// ```
+///
+/// # Errors
+///
+/// Returns an error if `data` is not an object file, or if its symbol table cannot be read.
+pub fn demangle_symbols<'data>(
+    data: &'data [u8],
+    demangler: &mut Demangler,
+) -> ::object::Result<Vec<ObjectSymbol<'data>>> {
+    let file = ::object::File::parse(data)?;
+    let mut symbols = file.symbols().peekable();
+
+    let symbols = if symbols.peek().is_some() {
+        symbols
+    } else {
+        file.dynamic_symbols().peekable()
+    };
+
+    let mut result = Vec::new();
+
+    for symbol in symbols.filter(::object::ObjectSymbol::is_definition) {
+        let raw_name = symbol.name_bytes()?;
+
+        let name = str::from_utf8(raw_name)
+            .ok()
+            .and_then(|name| demangler.demangle(name).ok())
+            .map_or(SymbolName::Raw(raw_name), |(symbol, suffix)| SymbolName::Rust {
+                symbol,
+                suffix,
+            });
+
+        result.push(ObjectSymbol {
+            address: symbol.address(),
+            size: symbol.size(),
+            name,
+        });
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ObjectSymbol, SymbolName};
+    use crate::rust_v0::{Demangler, DisplayStyle};
+    use ::object::write::{Object, StandardSection, Symbol, SymbolSection};
+    use ::object::{Architecture, BinaryFormat, Endianness, SymbolFlags, SymbolKind, SymbolScope};
+
+    #[test]
+    fn test_demangle_symbols() {
+        let mut object = Object::new(BinaryFormat::Elf, Architecture::X86_64, Endianness::Little);
+        let text = object.section_id(StandardSection::Text);
+
+        object.append_section_data(text, &[0xc3; 16], 1);
+
+        for (name, value, size) in [("_RNvCs123_3foo3bar.llvm.123", 0, 4), ("main", 4, 12)] {
+            object.add_symbol(Symbol {
+                name: name.into(),
+                value,
+                size,
+                kind: SymbolKind::Text,
+                scope: SymbolScope::Linkage,
+                weak: false,
+                section: SymbolSection::Section(text),
+                flags: SymbolFlags::None,
+            });
+        }
+
+        let data = object.write().unwrap();
+        let mut demangler = Demangler::default();
+        let symbols = super::demangle_symbols(&data, &mut demangler).unwrap();
+
+        assert_eq!(symbols.len(), 2);
+
+        let SymbolName::Rust { symbol, suffix } = &symbols[0].name else {
+            panic!();
+        };
+
+        assert_eq!((symbols[0].address, symbols[0].size), (0, 4));
+        assert_eq!(symbol.display(DisplayStyle::Normal).to_string(), "foo::bar");
+        assert_eq!(symbol.vendor_specific_suffix.as_deref(), Some(".llvm.123"));
+        assert_eq!(*suffix, "");
+
+        assert_eq!(
+            symbols[1],
+            ObjectSymbol {
+                address: 4,
+                size: 12,
+                name: SymbolName::Raw(b"main"),
+            }
+        );
+
+        assert!(super::demangle_symbols(b"not an object file", &mut demangler).is_err());
+    }
+}
@@ -0,0 +1,54 @@
+//! Padding displayed symbols to the width of a formatter, like `{:>40}`.
+
+use std::fmt::{self, Alignment, Formatter, Write};
+
+/// Writes the output of `write` into `f`, padded with the fill character of `f` to its width, and aligned according to
+/// its alignment, which defaults to the left like for strings. The output is only buffered if `f` has a width, since
+/// the padding depends on the length of the output.
+pub fn write_padded(f: &mut Formatter, write: impl FnOnce(&mut dyn Write) -> fmt::Result) -> fmt::Result {
+    let Some(width) = f.width() else {
+        return write(f);
+    };
+
+    let mut buffer = String::new();
+
+    write(&mut buffer)?;
+
+    let padding = width.saturating_sub(buffer.chars().count());
+
+    let (before, after) = match f.align() {
+        None | Some(Alignment::Left) => (0, padding),
+        Some(Alignment::Right) => (padding, 0),
+        Some(Alignment::Center) => (padding / 2, padding - padding / 2),
+    };
+
+    let fill = f.fill();
+
+    (0..before).try_for_each(|_| f.write_char(fill))?;
+    f.write_str(&buffer)?;
+    (0..after).try_for_each(|_| f.write_char(fill))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::write_padded;
+    use std::fmt::{self, Display, Formatter};
+
+    struct Padded(&'static str);
+
+    impl Display for Padded {
+        fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+            write_padded(f, |out| out.write_str(self.0))
+        }
+    }
+
+    #[test]
+    fn test_write_padded() {
+        assert_eq!(format!("{}", Padded("foo")), "foo");
+        assert_eq!(format!("{:6}", Padded("foo")), "foo   ");
+        assert_eq!(format!("{:>6}", Padded("foo")), "   foo");
+        assert_eq!(format!("{:*^8}", Padded("foo")), "**foo***");
+        assert_eq!(format!("{:2}", Padded("foo")), "foo");
+        assert_eq!(format!("{:>4}", Padded("äö")), "  äö");
+    }
+}
@@ -0,0 +1,100 @@
+//! Demangling the public symbols of Windows PDB files.
+
+use crate::rust_v0::Symbol;
+use ::pdb::{FallibleIterator, Source, SymbolData, PDB};
+use std::str;
+
+/// A public symbol with a Rust v0 mangled name, see [`demangle_public_symbols`].
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct PublicRustSymbol {
+    /// The relative virtual address of the symbol.
+    pub rva: u32,
+    /// Whether the symbol is a function.
+    pub is_function: bool,
+    /// The mangled name of the symbol.
+    pub mangled_name: String,
+    /// The parsed mangled name. Display it with [`Symbol::try_display`] to handle symbols that can not be displayed.
+    pub symbol: Symbol<'static>,
+    /// The suffix of the mangled name that is not part of the Rust v0 syntax.
+    pub suffix: String,
+}
+
+/// Reads the public symbols of `pdb`, and demangles the ones with Rust v0 mangled names. Symbols without a relative
+/// virtual address are skipped.
+///
+/// # Errors
+///
+/// Returns an error if the symbol table or the address map of `pdb` cannot be read.
+pub fn demangle_public_symbols<'s, S: Source<'s> + 's>(pdb: &mut PDB<'s, S>) -> ::pdb::Result<Vec<PublicRustSymbol>> {
+    let address_map = pdb.address_map()?;
+    let symbol_table = pdb.global_symbols()?;
+    let mut symbols = symbol_table.iter();
+    let mut result = Vec::new();
+
+    while let Some(symbol) = symbols.next()? {
+        // Symbol kinds unknown to the `pdb` crate are not public symbols, so parse errors can be ignored.
+        if let Ok(SymbolData::Public(public)) = symbol.parse() {
+            let Ok(mangled_name) = str::from_utf8(public.name.as_bytes()) else {
+                continue;
+            };
+
+            if let (Ok((symbol, suffix)), Some(rva)) =
+                (Symbol::parse_from_str(mangled_name), public.offset.to_rva(&address_map))
+            {
+                result.push(PublicRustSymbol {
+                    rva: rva.0,
+                    is_function: public.function,
+                    mangled_name: mangled_name.to_string(),
+                    symbol: symbol.into_owned(),
+                    suffix: suffix.to_string(),
+                });
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rust_v0::{DisplayError, DisplayStyle};
+    use ::pdb::PDB;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_demangle_public_symbols() {
+        // See `tests/pdb/public-symbols.s` for the symbols in the file.
+        let data = include_bytes!("../tests/pdb/public-symbols.pdb");
+        let mut pdb = PDB::open(Cursor::new(data.as_slice())).unwrap();
+        let mut symbols = super::demangle_public_symbols(&mut pdb).unwrap();
+
+        symbols.sort_by(|lhs, rhs| lhs.mangled_name.cmp(&rhs.mangled_name));
+
+        let summary = symbols
+            .iter()
+            .map(|symbol| (symbol.mangled_name.as_str(), symbol.is_function, symbol.suffix.as_str()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            summary,
+            [
+                ("_RMC0RLY_z", true, ""),
+                ("_RNvCs123_3foo3bar", true, ""),
+                ("_RNvCs123_3foo7COUNTER", false, ""),
+            ]
+        );
+
+        let mut buffer = String::new();
+
+        assert_eq!(
+            symbols[0].symbol.try_display(&mut buffer, DisplayStyle::Normal, 1024),
+            Err(DisplayError::NotDisplayable)
+        );
+        assert_eq!(symbols[1].symbol.to_string(), "foo[f85]::bar");
+        assert_eq!(
+            symbols[2].symbol.display(DisplayStyle::Normal).to_string(),
+            "foo::COUNTER"
+        );
+        assert_ne!(symbols[1].rva, symbols[2].rva);
+    }
+}
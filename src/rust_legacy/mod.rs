@@ -0,0 +1,126 @@
+//! Tools for demangling symbols using the legacy Rust mangling scheme, which encodes paths like the Itanium C++ ABI
+//! and appends a hash element, like `_ZN5regex4utf811decode_utf817h05af221e174051e9E`.
+
+use crate::padding;
+use crate::rust_v0::DisplayStyle;
+use std::borrow::Cow;
+use std::fmt::{self, Display, Formatter};
+
+mod parsers;
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct ParseSymbolError;
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Symbol<'a> {
+    /// The path elements, with escape sequences decoded.
+    pub path: Vec<Cow<'a, str>>,
+    /// The hexadecimal digits of the hash element that ends the path, if there is one.
+    pub hash: Option<&'a str>,
+}
+
+impl<'a> Symbol<'a> {
+    /// Returns an object that implements [`Display`] for printing the symbol.
+    ///
+    /// [`DisplayStyle::Short`] only shows the last path element, [`DisplayStyle::Long`] shows the hash, and the other
+    /// styles show the path without the hash.
+    #[must_use]
+    pub fn display(&self, style: DisplayStyle) -> impl Display + '_ {
+        struct Wrapper<'s, 'a>(&'s Symbol<'a>, DisplayStyle);
+
+        impl Display for Wrapper<'_, '_> {
+            fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+                padding::write_padded(f, |out| self.0.write(out, self.1))
+            }
+        }
+
+        Wrapper(self, style)
+    }
+
+    fn write(&self, f: &mut dyn fmt::Write, style: DisplayStyle) -> fmt::Result {
+        let path = if style == DisplayStyle::Short {
+            &self.path[self.path.len().saturating_sub(1)..]
+        } else {
+            &self.path
+        };
+
+        for (i, element) in path.iter().enumerate() {
+            if i != 0 {
+                f.write_str("::")?;
+            }
+
+            f.write_str(element)?;
+        }
+
+        match (style, self.hash) {
+            (DisplayStyle::Long, Some(hash)) => {
+                if !self.path.is_empty() {
+                    f.write_str("::")?;
+                }
+
+                write!(f, "h{hash}")
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Parses `input` with the legacy Rust mangling scheme, returns a tuple that contains a [`Symbol`] object and an
+    /// [`&str`] object containing the suffix that follows the mangled name. Names with `_ZN`, `ZN` and `__ZN` prefixes
+    /// are accepted.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParseSymbolError`] if `input` does not start with a valid legacy mangled name.
+    pub fn parse_from_str(input: &'a str) -> Result<(Self, &'a str), ParseSymbolError> {
+        let input = input
+            .strip_prefix("_ZN")
+            .or_else(|| input.strip_prefix("ZN"))
+            .or_else(|| input.strip_prefix("__ZN"))
+            .ok_or(ParseSymbolError)?;
+
+        parsers::parse_symbol(input).map_err(|()| ParseSymbolError)
+    }
+}
+
+impl Display for Symbol<'_> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.display(if f.alternate() {
+            DisplayStyle::Normal
+        } else {
+            DisplayStyle::Long
+        })
+        .fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Symbol;
+    use crate::rust_v0::DisplayStyle;
+
+    #[test]
+    fn test_display() {
+        let (symbol, suffix) = Symbol::parse_from_str(
+            "_ZN71_$LT$Test$u20$$u2b$$u20$$u27$static$u20$as$u20$foo..Bar$LT$Test$GT$$GT$3bar17h1234567890abcdefE",
+        )
+        .unwrap();
+
+        assert_eq!(suffix, "");
+
+        assert_eq!(symbol.display(DisplayStyle::Short).to_string(), "bar");
+
+        assert_eq!(
+            symbol.display(DisplayStyle::Normal).to_string(),
+            "<Test + 'static as foo::Bar<Test>>::bar"
+        );
+
+        assert_eq!(
+            symbol.display(DisplayStyle::Long).to_string(),
+            "<Test + 'static as foo::Bar<Test>>::bar::h1234567890abcdef"
+        );
+
+        assert_eq!(format!("{symbol:#}"), "<Test + 'static as foo::Bar<Test>>::bar");
+
+        assert!(Symbol::parse_from_str("_RNvC3foo3bar").is_err());
+    }
+}
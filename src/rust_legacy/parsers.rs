@@ -0,0 +1,170 @@
+use crate::mini_parser::combinators::terminated;
+use crate::mini_parser::parsers::{digit1, tag, take};
+use crate::mini_parser::Parser;
+use crate::rust_legacy::Symbol;
+use std::borrow::Cow;
+
+// References:
+//
+// - <https://github.com/rust-lang/rustc-demangle/blob/main/src/legacy.rs>.
+// - <https://github.com/rust-lang/rust/blob/master/compiler/rustc_symbol_mangling/src/legacy.rs>.
+
+pub fn parse_symbol(input: &str) -> Result<(Symbol<'_>, &str), ()> {
+    let (mut path, rest) = terminated(parse_element.many0(), tag('E')).parse(input, &mut ())?;
+
+    if !input[..input.len() - rest.len()].is_ascii() {
+        return Err(());
+    }
+
+    let hash = path
+        .last()
+        .and_then(|element| element.strip_prefix('h'))
+        .filter(|hash| hash.bytes().all(|c| c.is_ascii_hexdigit()));
+
+    if hash.is_some() {
+        path.pop();
+    }
+
+    Ok((
+        Symbol {
+            path: path.into_iter().map(decode_element).collect(),
+            hash,
+        },
+        rest,
+    ))
+}
+
+fn parse_element<'a>(input: &'a str, context: &mut ()) -> Result<(&'a str, &'a str), ()> {
+    digit1
+        .map_opt(|length: &str| length.parse::<usize>().ok())
+        .flat_map(take)
+        .parse(input, context)
+}
+
+fn decode_escape(escape: &str) -> Option<char> {
+    Some(match escape {
+        "SP" => '@',
+        "BP" => '*',
+        "RF" => '&',
+        "LT" => '<',
+        "GT" => '>',
+        "LP" => '(',
+        "RP" => ')',
+        "C" => ',',
+        _ => {
+            let digits = escape.strip_prefix('u')?;
+
+            if !digits.bytes().all(|c| matches!(c, b'0'..=b'9' | b'a'..=b'f')) {
+                return None;
+            }
+
+            u32::from_str_radix(digits, 16)
+                .ok()
+                .and_then(char::from_u32)
+                .filter(|c| !c.is_control())?
+        }
+    })
+}
+
+/// Decodes the `$`-escapes and `..` separators in an element. Decoding stops at the first invalid escape, and the
+/// rest of the element is kept as it is.
+fn decode_element(element: &str) -> Cow<'_, str> {
+    let element = if element.starts_with("_$") {
+        &element[1..]
+    } else {
+        element
+    };
+
+    if !element.contains(['$', '.']) {
+        return Cow::Borrowed(element);
+    }
+
+    let mut result = String::with_capacity(element.len());
+    let mut rest = element;
+
+    while let Some(i) = rest.find(['$', '.']) {
+        result.push_str(&rest[..i]);
+        rest = &rest[i..];
+
+        if let Some(after_dots) = rest.strip_prefix("..") {
+            result.push_str("::");
+            rest = after_dots;
+        } else if let Some(after_dot) = rest.strip_prefix('.') {
+            result.push('.');
+            rest = after_dot;
+        } else if let Some((c, after_escape)) = rest[1..]
+            .split_once('$')
+            .and_then(|(escape, after_escape)| Some((decode_escape(escape)?, after_escape)))
+        {
+            result.push(c);
+            rest = after_escape;
+        } else {
+            break;
+        }
+    }
+
+    result.push_str(rest);
+
+    Cow::Owned(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rust_legacy::Symbol;
+    use std::borrow::Cow;
+
+    #[test]
+    fn test_parse_symbol() {
+        assert_eq!(
+            super::parse_symbol("3foo3barE"),
+            Ok((
+                Symbol {
+                    path: vec![Cow::Borrowed("foo"), Cow::Borrowed("bar")],
+                    hash: None,
+                },
+                ""
+            ))
+        );
+
+        assert_eq!(
+            super::parse_symbol("3foo17h05af221e174051e9E.llvm.123"),
+            Ok((
+                Symbol {
+                    path: vec![Cow::Borrowed("foo")],
+                    hash: Some("05af221e174051e9"),
+                },
+                ".llvm.123"
+            ))
+        );
+
+        assert_eq!(
+            super::parse_symbol("0E"),
+            Ok((
+                Symbol {
+                    path: vec![Cow::Borrowed("")],
+                    hash: None,
+                },
+                ""
+            ))
+        );
+
+        assert_eq!(super::parse_symbol("3foo"), Err(()));
+        assert_eq!(super::parse_symbol("4fooE"), Err(()));
+        assert_eq!(super::parse_symbol("3fooxE"), Err(()));
+        assert_eq!(super::parse_symbol("2\u{e9}E"), Err(()));
+        assert_eq!(super::parse_symbol("99999999999999999999999fooE"), Err(()));
+    }
+
+    #[test]
+    fn test_decode_element() {
+        assert_eq!(super::decode_element("foo"), "foo");
+        assert_eq!(super::decode_element("_$LT$impl$GT$"), "<impl>");
+        assert_eq!(super::decode_element("a..b.c"), "a::b.c");
+        assert_eq!(super::decode_element("$RF$$u20$$u7b$"), "& {");
+        assert_eq!(super::decode_element("$C$$SP$$BP$$LP$$RP$"), ",@*()");
+        assert_eq!(super::decode_element("a$u7B$b"), "a$u7B$b");
+        assert_eq!(super::decode_element("a$LT$b$XY$c$GT$"), "a<b$XY$c$GT$");
+        assert_eq!(super::decode_element("a$u1$"), "a$u1$");
+        assert_eq!(super::decode_element("a$"), "a$");
+    }
+}
@@ -0,0 +1,435 @@
+//! Generating well-formed ASTs with the `arbitrary` crate, enabled by the `arbitrary` feature.
+
+use crate::rust_v0::{
+    Abi, BasicType, Const, ConstFields, ConstStr, DynBounds, DynTrait, DynTraitAssocBinding, FnSig, GenericArg,
+    Identifier, ImplPath, Namespace, NodeRc, Path, Symbol, Type,
+};
+use ::arbitrary::{Arbitrary, Result, Unstructured};
+use std::borrow::Cow;
+
+/// The maximum nesting depth of generated paths, types and constants, which keeps generated symbols well within the
+/// default [`ParseOptions::max_depth`](crate::rust_v0::ParseOptions::max_depth).
+const MAX_DEPTH: u32 = 6;
+
+/// The maximum number of items in generated lists, like generic arguments and tuple elements.
+const MAX_ITEMS: usize = 3;
+
+/// The maximum number of lifetimes bound by a generated binder.
+const MAX_BOUND_LIFETIMES: u64 = 3;
+
+const IDENTIFIER_START: &[char] = &['_', 'a', 'b', 'c', 'x', 'y', 'z', 'A', 'B', 'Z', 'é', 'ж', '字'];
+const IDENTIFIER_CONTINUE: &[char] = &['_', 'a', 'f', 'z', 'Q', '0', '7', '9', 'ü', 'λ'];
+
+const BASIC_TYPES: &[BasicType] = &[
+    BasicType::I8,
+    BasicType::Bool,
+    BasicType::Char,
+    BasicType::F64,
+    BasicType::Str,
+    BasicType::F32,
+    BasicType::U8,
+    BasicType::Isize,
+    BasicType::Usize,
+    BasicType::I32,
+    BasicType::U32,
+    BasicType::I128,
+    BasicType::U128,
+    BasicType::I16,
+    BasicType::U16,
+    BasicType::Unit,
+    BasicType::Ellipsis,
+    BasicType::I64,
+    BasicType::U64,
+    BasicType::Never,
+    BasicType::Placeholder,
+];
+
+/// Generates nodes while tracking the remaining nesting depth and the number of lifetimes bound by enclosing binders,
+/// so that every lifetime refers to a binder and the symbol can be displayed.
+struct Generator<'u, 'a> {
+    u: &'u mut Unstructured<'a>,
+    depth: u32,
+    bound_lifetime_depth: u64,
+}
+
+impl<'u, 'a> Generator<'u, 'a> {
+    fn new(u: &'u mut Unstructured<'a>) -> Self {
+        Self {
+            u,
+            depth: MAX_DEPTH,
+            bound_lifetime_depth: 0,
+        }
+    }
+
+    /// Generates a child node, or a leaf if the maximum depth is reached.
+    fn nested<T>(&mut self, f: impl FnOnce(&mut Self, bool) -> Result<T>) -> Result<T> {
+        let is_leaf = self.depth == 0;
+
+        self.depth = self.depth.saturating_sub(1);
+
+        let result = f(self, is_leaf);
+
+        if !is_leaf {
+            self.depth += 1;
+        }
+
+        result
+    }
+
+    fn binder<T>(&mut self, f: impl FnOnce(&mut Self) -> Result<T>) -> Result<(u64, T)> {
+        let bound_lifetimes = self.u.int_in_range(0..=MAX_BOUND_LIFETIMES)?;
+
+        self.bound_lifetime_depth += bound_lifetimes;
+
+        let result = f(self);
+
+        self.bound_lifetime_depth -= bound_lifetimes;
+
+        Ok((bound_lifetimes, result?))
+    }
+
+    fn list<T>(&mut self, min: usize, mut f: impl FnMut(&mut Self) -> Result<T>) -> Result<Vec<T>> {
+        let length = self.u.int_in_range(min..=MAX_ITEMS)?;
+
+        (0..length).map(|_| f(self)).collect()
+    }
+
+    fn lifetime(&mut self) -> Result<u64> {
+        // Zero is the erased lifetime, other values refer to lifetimes bound by enclosing binders.
+        self.u.int_in_range(0..=self.bound_lifetime_depth)
+    }
+
+    fn name(&mut self) -> Result<String> {
+        let mut name = String::from(*self.u.choose(IDENTIFIER_START)?);
+
+        for _ in 0..self.u.int_in_range(0..=4)? {
+            name.push(*self.u.choose(IDENTIFIER_CONTINUE)?);
+        }
+
+        Ok(name)
+    }
+
+    fn disambiguator(&mut self) -> Result<u64> {
+        Ok(if self.u.ratio(1, 4)? { self.u.arbitrary()? } else { 0 })
+    }
+
+    fn identifier(&mut self) -> Result<Identifier<'static>> {
+        Ok(Identifier {
+            disambiguator: self.disambiguator()?,
+            name: Cow::Owned(self.name()?),
+            is_undecoded_punycode: false,
+        })
+    }
+
+    fn namespace(&mut self) -> Result<Namespace> {
+        Ok(match self.u.int_in_range(0..=9)? {
+            0..=3 => Namespace::Type,
+            4..=6 => Namespace::Value,
+            7 => Namespace::Closure,
+            8 => Namespace::Shim,
+            _ => {
+                let c = self.u.choose(&['A', 'I', 'Z', 'a', 'k', 'z'])?;
+
+                Namespace::Other(*c)
+            }
+        })
+    }
+
+    fn symbol(&mut self) -> Result<Symbol<'static>> {
+        Ok(Symbol {
+            version: None,
+            path: self.path()?,
+            instantiating_crate: if self.u.arbitrary()? {
+                Some(NodeRc::new(Path::CrateRoot(self.identifier()?)))
+            } else {
+                None
+            },
+            vendor_specific_suffix: if self.u.ratio(1, 4)? {
+                Some(Cow::Owned(format!(".llvm.{}", self.u.arbitrary::<u32>()?)))
+            } else {
+                None
+            },
+        })
+    }
+
+    fn path(&mut self) -> Result<NodeRc<Path<'static>>> {
+        self.nested(|this, is_leaf| {
+            let path = match if is_leaf { 0 } else { this.u.int_in_range(0..=9)? } {
+                0 => Path::CrateRoot(this.identifier()?),
+                1 => Path::InherentImpl {
+                    impl_path: this.impl_path()?,
+                    type_: this.type_()?,
+                },
+                2 => Path::TraitImpl {
+                    impl_path: this.impl_path()?,
+                    type_: this.type_()?,
+                    trait_: this.path()?,
+                },
+                3 => Path::TraitDefinition {
+                    type_: this.type_()?,
+                    trait_: this.path()?,
+                },
+                4..=7 => {
+                    let namespace = this.namespace()?;
+
+                    Path::Nested {
+                        namespace,
+                        path: this.path()?,
+                        identifier: if namespace.is_special() && this.u.arbitrary()? {
+                            Identifier::new_unchecked(this.disambiguator()?, "")
+                        } else {
+                            this.identifier()?
+                        },
+                    }
+                }
+                _ => Path::Generic {
+                    path: this.path()?,
+                    generic_args: this.list(1, Self::generic_arg)?,
+                },
+            };
+
+            Ok(NodeRc::new(path))
+        })
+    }
+
+    fn impl_path(&mut self) -> Result<ImplPath<'static>> {
+        Ok(ImplPath {
+            disambiguator: self.disambiguator()?,
+            path: self.path()?,
+        })
+    }
+
+    fn generic_arg(&mut self) -> Result<GenericArg<'static>> {
+        Ok(match self.u.int_in_range(0..=3)? {
+            0 => GenericArg::Lifetime(self.lifetime()?),
+            1 => GenericArg::Const(self.const_()?),
+            _ => GenericArg::Type(self.type_()?),
+        })
+    }
+
+    fn type_(&mut self) -> Result<NodeRc<Type<'static>>> {
+        self.nested(|this, is_leaf| {
+            let type_ = match if is_leaf { 0 } else { this.u.int_in_range(0..=11)? } {
+                0..=2 => Type::Basic(*this.u.choose(BASIC_TYPES)?),
+                3 => Type::Named(this.path()?),
+                4 => Type::Array(this.type_()?, this.const_()?),
+                5 => Type::Slice(this.type_()?),
+                6 => Type::Tuple(this.list(0, Self::type_)?),
+                7 => Type::Ref {
+                    lifetime: this.lifetime()?,
+                    type_: this.type_()?,
+                },
+                8 => Type::RefMut {
+                    lifetime: this.lifetime()?,
+                    type_: this.type_()?,
+                },
+                9 => {
+                    if this.u.arbitrary()? {
+                        Type::PtrConst(this.type_()?)
+                    } else {
+                        Type::PtrMut(this.type_()?)
+                    }
+                }
+                10 => Type::Fn(this.fn_sig()?),
+                _ => Type::DynTrait {
+                    dyn_bounds: this.dyn_bounds()?,
+                    lifetime: this.lifetime()?,
+                },
+            };
+
+            Ok(NodeRc::new(type_))
+        })
+    }
+
+    fn fn_sig(&mut self) -> Result<FnSig<'static>> {
+        let (bound_lifetimes, (is_unsafe, abi, argument_types, return_type)) = self.binder(|this| {
+            let abi = match this.u.int_in_range(0..=2)? {
+                0 => None,
+                1 => Some(Abi::C),
+                _ => Some(Abi::Named(Cow::Borrowed(this.u.choose(&[
+                    "Rust",
+                    "system",
+                    "rust_call",
+                    "C_unwind",
+                ])?))),
+            };
+
+            Ok((this.u.arbitrary()?, abi, this.list(0, Self::type_)?, this.type_()?))
+        })?;
+
+        Ok(FnSig {
+            bound_lifetimes,
+            is_unsafe,
+            abi,
+            argument_types,
+            return_type,
+        })
+    }
+
+    fn dyn_bounds(&mut self) -> Result<DynBounds<'static>> {
+        let (bound_lifetimes, dyn_traits) = self.binder(|this| this.list(1, Self::dyn_trait))?;
+
+        Ok(DynBounds {
+            bound_lifetimes,
+            dyn_traits,
+        })
+    }
+
+    fn dyn_trait(&mut self) -> Result<DynTrait<'static>> {
+        Ok(DynTrait {
+            path: self.path()?,
+            dyn_trait_assoc_bindings: self.list(0, |this| {
+                Ok(DynTraitAssocBinding {
+                    name: Cow::Owned(this.name()?),
+                    type_: this.type_()?,
+                    is_undecoded_punycode: false,
+                })
+            })?,
+        })
+    }
+
+    /// Generates a signed integer, except for the minimum value, which can not be mangled because its magnitude does
+    /// not fit in the type.
+    fn signed<T: Arbitrary<'a> + PartialEq + num_traits::Bounded + num_traits::One + std::ops::Add<Output = T>>(
+        &mut self,
+    ) -> Result<T> {
+        let value = self.u.arbitrary::<T>()?;
+
+        Ok(if value == T::min_value() {
+            value + T::one()
+        } else {
+            value
+        })
+    }
+
+    fn const_(&mut self) -> Result<NodeRc<Const<'static>>> {
+        self.nested(|this, is_leaf| {
+            let const_ = match if is_leaf {
+                this.u.int_in_range(0..=13)?
+            } else {
+                this.u.int_in_range(0..=20)?
+            } {
+                0 => Const::I8(this.signed()?),
+                1 => Const::U8(this.u.arbitrary()?),
+                2 => Const::Isize(this.signed()?),
+                3 => Const::Usize(this.u.arbitrary()?),
+                4 => Const::I32(this.signed()?),
+                5 => Const::U32(this.u.arbitrary()?),
+                6 => Const::I128(this.signed()?),
+                7 => Const::U128(this.u.arbitrary()?),
+                8 => Const::I16(this.signed()?),
+                9 => Const::U16(this.u.arbitrary()?),
+                10 => Const::I64(this.signed()?),
+                11 => Const::U64(this.u.arbitrary()?),
+                12 => Const::Bool(this.u.arbitrary()?),
+                13 => Const::Char(this.u.arbitrary()?),
+                14 => Const::Str(ConstStr::from(this.u.arbitrary::<String>()?)),
+                15 => Const::Ref(this.const_()?),
+                16 => Const::RefMut(this.const_()?),
+                17 => Const::Array(this.list(0, Self::const_)?),
+                18 => Const::Tuple(this.list(0, Self::const_)?),
+                19 => Const::NamedStruct {
+                    path: this.path()?,
+                    fields: match this.u.int_in_range(0..=2)? {
+                        0 => ConstFields::Unit,
+                        1 => ConstFields::Tuple(this.list(0, Self::const_)?),
+                        _ => ConstFields::Struct(this.list(0, |this| Ok((this.identifier()?, this.const_()?)))?),
+                    },
+                },
+                _ => Const::Placeholder,
+            };
+
+            Ok(NodeRc::new(const_))
+        })
+    }
+}
+
+/// Generates symbols that can be mangled with [`Symbol::mangle`], parsed back into equal symbols, and displayed.
+impl<'a> Arbitrary<'a> for Symbol<'a> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Generator::new(u).symbol()
+    }
+}
+
+/// Generates well-formed paths, see the [`Arbitrary`] implementation of [`Symbol`].
+impl<'a> Arbitrary<'a> for Path<'a> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Generator::new(u).path().map(NodeRc::unwrap_or_clone)
+    }
+}
+
+/// Generates well-formed types, see the [`Arbitrary`] implementation of [`Symbol`]. Lifetimes are erased, since there
+/// are no binders outside the type.
+impl<'a> Arbitrary<'a> for Type<'a> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Generator::new(u).type_().map(NodeRc::unwrap_or_clone)
+    }
+}
+
+/// Generates well-formed constant values, see the [`Arbitrary`] implementation of [`Symbol`].
+impl<'a> Arbitrary<'a> for Const<'a> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Generator::new(u).const_().map(NodeRc::unwrap_or_clone)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rust_v0::{Const, Path, Symbol, Type};
+    use ::arbitrary::{Arbitrary, Unstructured};
+
+    /// Returns pseudo-random bytes from a xorshift generator, so that the test is deterministic.
+    fn random_bytes(seed: u64, length: usize) -> Vec<u8> {
+        let mut state = seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1;
+
+        (0..length)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+
+                state.to_le_bytes()[0]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_arbitrary_symbol_round_trip() {
+        for seed in 0..2000 {
+            let data = random_bytes(seed, 512);
+            let symbol = Symbol::arbitrary(&mut Unstructured::new(&data)).unwrap();
+            let mangled = symbol.mangle();
+            let (reparsed, suffix) =
+                Symbol::parse_from_str(&mangled).unwrap_or_else(|error| panic!("{mangled}: {error}"));
+
+            assert_eq!(suffix, "", "{mangled}");
+            assert_eq!(reparsed, symbol, "{mangled}");
+            assert_eq!(reparsed.to_string(), symbol.to_string(), "{mangled}");
+        }
+    }
+
+    #[test]
+    fn test_arbitrary_nodes() {
+        for seed in 0..200 {
+            let data = random_bytes(seed, 256);
+
+            assert!(!Path::arbitrary(&mut Unstructured::new(&data))
+                .unwrap()
+                .to_string()
+                .is_empty());
+            assert!(!Type::arbitrary(&mut Unstructured::new(&data))
+                .unwrap()
+                .to_string()
+                .is_empty());
+            assert!(!Const::arbitrary(&mut Unstructured::new(&data))
+                .unwrap()
+                .to_string()
+                .is_empty());
+        }
+
+        // Generation succeeds even without data.
+        let symbol = Symbol::arbitrary(&mut Unstructured::new(&[])).unwrap();
+
+        assert_eq!(Symbol::parse_from_str(&symbol.mangle()).unwrap().0, symbol);
+    }
+}
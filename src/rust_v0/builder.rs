@@ -0,0 +1,390 @@
+//! Fluent builders for constructing [`Symbol`], [`Path`] and [`Type`] ASTs.
+
+use crate::rust_v0::{
+    BasicType, Const, DynBounds, DynTrait, FnSig, GenericArg, Identifier, ImplPath, Namespace, NodeRc, Path, Symbol,
+    Type,
+};
+use std::borrow::Cow;
+
+/// Builds a [`Path`] from the crate root inwards, like
+/// `PathBuilder::crate_root("regex", 0x1234).nested_ty("utf8").nested_value("decode_utf8")`.
+///
+/// Disambiguators have the same meaning as [`Identifier::disambiguator`], where 0 means no disambiguator. Names are
+/// not validated, use [`Identifier::new`] to check them.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct PathBuilder<'a> {
+    path: NodeRc<Path<'a>>,
+}
+
+impl<'a> PathBuilder<'a> {
+    /// Starts from an existing path.
+    #[must_use]
+    pub fn new(path: NodeRc<Path<'a>>) -> Self {
+        Self { path }
+    }
+
+    /// Starts from the root of a crate.
+    #[must_use]
+    pub fn crate_root(name: impl Into<Cow<'a, str>>, disambiguator: u64) -> Self {
+        Self::new(NodeRc::new(Path::CrateRoot(Identifier::new_unchecked(
+            disambiguator,
+            name,
+        ))))
+    }
+
+    /// Starts from an inherent implementation for `type_`, like `<Foo>`, defined inside `parent`.
+    #[must_use]
+    pub fn inherent_impl(
+        parent: impl Into<NodeRc<Path<'a>>>,
+        disambiguator: u64,
+        type_: impl Into<NodeRc<Type<'a>>>,
+    ) -> Self {
+        Self::new(NodeRc::new(Path::InherentImpl {
+            impl_path: ImplPath {
+                disambiguator,
+                path: parent.into(),
+            },
+            type_: type_.into(),
+        }))
+    }
+
+    /// Starts from a trait implementation for `type_`, like `<Foo as Trait>`, defined inside `parent`.
+    #[must_use]
+    pub fn trait_impl(
+        parent: impl Into<NodeRc<Path<'a>>>,
+        disambiguator: u64,
+        type_: impl Into<NodeRc<Type<'a>>>,
+        trait_: impl Into<NodeRc<Path<'a>>>,
+    ) -> Self {
+        Self::new(NodeRc::new(Path::TraitImpl {
+            impl_path: ImplPath {
+                disambiguator,
+                path: parent.into(),
+            },
+            type_: type_.into(),
+            trait_: trait_.into(),
+        }))
+    }
+
+    /// Starts from a qualified path, like `<Foo as Trait>`, which refers to items in a trait definition.
+    #[must_use]
+    pub fn trait_definition(type_: impl Into<NodeRc<Type<'a>>>, trait_: impl Into<NodeRc<Path<'a>>>) -> Self {
+        Self::new(NodeRc::new(Path::TraitDefinition {
+            type_: type_.into(),
+            trait_: trait_.into(),
+        }))
+    }
+
+    /// Appends an identifier in `namespace`.
+    #[must_use]
+    pub fn nested(self, namespace: Namespace, identifier: Identifier<'a>) -> Self {
+        Self::new(NodeRc::new(Path::Nested {
+            namespace,
+            path: self.path,
+            identifier,
+        }))
+    }
+
+    /// Appends an item in the type namespace, like a module, a type or a trait.
+    #[must_use]
+    pub fn nested_ty(self, name: impl Into<Cow<'a, str>>) -> Self {
+        self.nested(Namespace::Type, Identifier::new_unchecked(0, name))
+    }
+
+    /// Appends an item in the value namespace, like a function, a constant or a static.
+    #[must_use]
+    pub fn nested_value(self, name: impl Into<Cow<'a, str>>) -> Self {
+        self.nested(Namespace::Value, Identifier::new_unchecked(0, name))
+    }
+
+    /// Appends a closure, which has an empty name.
+    #[must_use]
+    pub fn closure(self, disambiguator: u64) -> Self {
+        self.nested(Namespace::Closure, Identifier::new_unchecked(disambiguator, ""))
+    }
+
+    /// Applies generic arguments to the path.
+    #[must_use]
+    pub fn generic(self, generic_args: impl IntoIterator<Item = GenericArg<'a>>) -> Self {
+        Self::new(NodeRc::new(Path::Generic {
+            path: self.path,
+            generic_args: generic_args.into_iter().collect(),
+        }))
+    }
+
+    /// Returns a type that refers to the path.
+    #[must_use]
+    pub fn into_type(self) -> TypeBuilder<'a> {
+        TypeBuilder::named(self.path)
+    }
+
+    /// Returns a symbol builder for the path.
+    #[must_use]
+    pub fn into_symbol(self) -> SymbolBuilder<'a> {
+        SymbolBuilder::new(self.path)
+    }
+
+    #[must_use]
+    pub fn build(self) -> NodeRc<Path<'a>> {
+        self.path
+    }
+}
+
+impl<'a> From<PathBuilder<'a>> for NodeRc<Path<'a>> {
+    fn from(builder: PathBuilder<'a>) -> Self {
+        builder.build()
+    }
+}
+
+/// Builds a [`Type`] from the inside out, like `TypeBuilder::basic(BasicType::U8).slice().reference()` for `&[u8]`.
+///
+/// Lifetimes have the same meaning as the ones in [`Type::Ref`], where 0 means an erased lifetime.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct TypeBuilder<'a> {
+    type_: NodeRc<Type<'a>>,
+}
+
+impl<'a> TypeBuilder<'a> {
+    /// Starts from an existing type.
+    #[must_use]
+    pub fn new(type_: NodeRc<Type<'a>>) -> Self {
+        Self { type_ }
+    }
+
+    #[must_use]
+    pub fn basic(basic_type: BasicType) -> Self {
+        Self::new(NodeRc::new(Type::Basic(basic_type)))
+    }
+
+    /// Starts from a type named by `path`, like a struct or an enum.
+    #[must_use]
+    pub fn named(path: impl Into<NodeRc<Path<'a>>>) -> Self {
+        Self::new(NodeRc::new(Type::Named(path.into())))
+    }
+
+    #[must_use]
+    pub fn tuple(types: impl IntoIterator<Item = NodeRc<Type<'a>>>) -> Self {
+        Self::new(NodeRc::new(Type::Tuple(types.into_iter().collect())))
+    }
+
+    /// Starts from a function pointer type without bound lifetimes and ABI, like `fn(u8) -> bool`.
+    #[must_use]
+    pub fn fn_ptr(
+        argument_types: impl IntoIterator<Item = NodeRc<Type<'a>>>,
+        return_type: impl Into<NodeRc<Type<'a>>>,
+    ) -> Self {
+        Self::new(NodeRc::new(Type::Fn(FnSig {
+            bound_lifetimes: 0,
+            is_unsafe: false,
+            abi: None,
+            argument_types: argument_types.into_iter().collect(),
+            return_type: return_type.into(),
+        })))
+    }
+
+    /// Starts from a trait object type without bound lifetimes, like `dyn Any + Send`.
+    #[must_use]
+    pub fn dyn_trait(traits: impl IntoIterator<Item = NodeRc<Path<'a>>>, lifetime: u64) -> Self {
+        Self::new(NodeRc::new(Type::DynTrait {
+            dyn_bounds: DynBounds {
+                bound_lifetimes: 0,
+                dyn_traits: traits
+                    .into_iter()
+                    .map(|path| DynTrait {
+                        path,
+                        dyn_trait_assoc_bindings: Vec::new(),
+                    })
+                    .collect(),
+            },
+            lifetime,
+        }))
+    }
+
+    /// Wraps the type in an array of `length` elements.
+    #[must_use]
+    pub fn array(self, length: impl Into<NodeRc<Const<'a>>>) -> Self {
+        Self::new(NodeRc::new(Type::Array(self.type_, length.into())))
+    }
+
+    #[must_use]
+    pub fn slice(self) -> Self {
+        Self::new(NodeRc::new(Type::Slice(self.type_)))
+    }
+
+    /// Wraps the type in a shared reference with an erased lifetime.
+    #[must_use]
+    pub fn reference(self) -> Self {
+        self.reference_with_lifetime(0)
+    }
+
+    #[must_use]
+    pub fn reference_with_lifetime(self, lifetime: u64) -> Self {
+        Self::new(NodeRc::new(Type::Ref {
+            lifetime,
+            type_: self.type_,
+        }))
+    }
+
+    /// Wraps the type in a mutable reference with an erased lifetime.
+    #[must_use]
+    pub fn reference_mut(self) -> Self {
+        self.reference_mut_with_lifetime(0)
+    }
+
+    #[must_use]
+    pub fn reference_mut_with_lifetime(self, lifetime: u64) -> Self {
+        Self::new(NodeRc::new(Type::RefMut {
+            lifetime,
+            type_: self.type_,
+        }))
+    }
+
+    #[must_use]
+    pub fn ptr_const(self) -> Self {
+        Self::new(NodeRc::new(Type::PtrConst(self.type_)))
+    }
+
+    #[must_use]
+    pub fn ptr_mut(self) -> Self {
+        Self::new(NodeRc::new(Type::PtrMut(self.type_)))
+    }
+
+    #[must_use]
+    pub fn build(self) -> NodeRc<Type<'a>> {
+        self.type_
+    }
+}
+
+impl<'a> From<TypeBuilder<'a>> for NodeRc<Type<'a>> {
+    fn from(builder: TypeBuilder<'a>) -> Self {
+        builder.build()
+    }
+}
+
+impl<'a> From<TypeBuilder<'a>> for GenericArg<'a> {
+    fn from(builder: TypeBuilder<'a>) -> Self {
+        Self::Type(builder.build())
+    }
+}
+
+/// Builds a [`Symbol`] from its path, like `SymbolBuilder::new(path).instantiating_crate(crate_root).build()`.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct SymbolBuilder<'a> {
+    symbol: Symbol<'a>,
+}
+
+impl<'a> SymbolBuilder<'a> {
+    #[must_use]
+    pub fn new(path: impl Into<NodeRc<Path<'a>>>) -> Self {
+        Self {
+            symbol: Symbol {
+                version: None,
+                path: path.into(),
+                instantiating_crate: None,
+                vendor_specific_suffix: None,
+            },
+        }
+    }
+
+    #[must_use]
+    pub fn version(mut self, version: u64) -> Self {
+        self.symbol.version = Some(version);
+
+        self
+    }
+
+    /// Sets the crate that instantiates generic items in the path.
+    #[must_use]
+    pub fn instantiating_crate(mut self, instantiating_crate: impl Into<NodeRc<Path<'a>>>) -> Self {
+        self.symbol.instantiating_crate = Some(instantiating_crate.into());
+
+        self
+    }
+
+    /// Sets the vendor specific suffix, which should start with `.` or `$`, like `.llvm.1234`.
+    #[must_use]
+    pub fn vendor_specific_suffix(mut self, suffix: impl Into<Cow<'a, str>>) -> Self {
+        self.symbol.vendor_specific_suffix = Some(suffix.into());
+
+        self
+    }
+
+    #[must_use]
+    pub fn build(self) -> Symbol<'a> {
+        self.symbol
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PathBuilder, SymbolBuilder, TypeBuilder};
+    use crate::rust_v0::{BasicType, Const, GenericArg, NodeRc, Symbol};
+
+    #[test]
+    fn test_build_path() {
+        let path = PathBuilder::crate_root("regex", 0x1234)
+            .nested_ty("utf8")
+            .nested_value("decode_utf8")
+            .build();
+
+        assert_eq!(path.to_string(), "regex[1234]::utf8::decode_utf8");
+
+        let vec = PathBuilder::crate_root("alloc", 0)
+            .nested_ty("vec")
+            .nested_ty("Vec")
+            .generic([TypeBuilder::basic(BasicType::U8).into()]);
+
+        let path = PathBuilder::inherent_impl(PathBuilder::crate_root("alloc", 0), 1, vec.into_type())
+            .nested_value("push")
+            .closure(0)
+            .build();
+
+        assert_eq!(path.to_string(), "<alloc::vec::Vec<u8>>::push::{closure#0}");
+    }
+
+    #[test]
+    fn test_build_type() {
+        let u8_type = TypeBuilder::basic(BasicType::U8);
+
+        let test_cases = [
+            (u8_type.clone().slice().reference(), "&[u8]"),
+            (
+                u8_type.clone().array(NodeRc::new(Const::Usize(4))).reference_mut(),
+                "&mut [u8; 4usize]",
+            ),
+            (u8_type.clone().ptr_const().ptr_mut(), "*mut *const u8"),
+            (
+                TypeBuilder::tuple([u8_type.clone().build(), TypeBuilder::basic(BasicType::Bool).build()]),
+                "(u8, bool)",
+            ),
+            (
+                TypeBuilder::fn_ptr([u8_type.build()], TypeBuilder::basic(BasicType::Bool)),
+                "fn(u8) -> bool",
+            ),
+            (
+                TypeBuilder::dyn_trait([PathBuilder::crate_root("core", 0).nested_ty("Any").build()], 0),
+                "dyn core::Any",
+            ),
+        ];
+
+        for (builder, expected) in test_cases {
+            assert_eq!(builder.build().to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn test_build_symbol() {
+        let path = PathBuilder::crate_root("foo", 0x123)
+            .nested_value("bar")
+            .generic([GenericArg::Lifetime(0)]);
+
+        let symbol = SymbolBuilder::new(path)
+            .instantiating_crate(PathBuilder::crate_root("baz", 0x456))
+            .vendor_specific_suffix(".llvm.123")
+            .build();
+
+        let mangled = symbol.mangle();
+
+        assert_eq!(Symbol::parse_from_str(&mangled), Ok((symbol, "")));
+    }
+}
@@ -0,0 +1,429 @@
+//! A persistent cache of parse results keyed by mangled names.
+//!
+//! The cache holds rendered output rather than ASTs: an entry records the symbol displayed with every [`DisplayStyle`]
+//! and its suffix, or that the name could not be parsed. Since the entries are rendered, they depend on the display
+//! code as much as on the file layout, so the format version is bumped whenever either of them changes, including
+//! output changes like the display of crate disambiguators. The crate version is recorded too, so caches written by
+//! other releases are discarded as well.
+
+use crate::rust_v0::{DisplayStyle, ParseSymbolError, Symbol};
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::io::{self, BufRead, BufReader, BufWriter, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+const MAGIC: &str = "ast-demangle-parse-cache";
+/// The version of the cache file layout and of the rendered output stored in it.
+const FORMAT_VERSION: u32 = 4;
+const CRATE_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// The parse result of a single mangled name, as stored in a [`ParseCache`]. A display string is [`None`] if the
+/// symbol can not be displayed with that style, for example because it refers to a lifetime that is not bound.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct CachedSymbol {
+    /// The symbol displayed with [`DisplayStyle::Short`].
+    pub short: Option<String>,
+    /// The symbol displayed with [`DisplayStyle::Normal`].
+    pub normal: Option<String>,
+    /// The symbol displayed with [`DisplayStyle::Long`].
+    pub long: Option<String>,
+    /// The symbol displayed with [`DisplayStyle::Debuginfo`].
+    pub debuginfo: Option<String>,
+    /// The suffix that is not part of the Rust v0 syntax.
+    pub suffix: String,
+}
+
+impl CachedSymbol {
+    /// Displays `symbol` with every [`DisplayStyle`], and records `suffix` as the part of the mangled name that is not
+    /// part of the Rust v0 syntax.
+    #[must_use]
+    pub fn new(symbol: &Symbol, suffix: &str) -> Self {
+        let display = |style| {
+            let mut buffer = String::new();
+
+            write!(buffer, "{}", symbol.display(style)).ok().map(|()| buffer)
+        };
+
+        Self {
+            short: display(DisplayStyle::Short),
+            normal: display(DisplayStyle::Normal),
+            long: display(DisplayStyle::Long),
+            debuginfo: display(DisplayStyle::Debuginfo),
+            suffix: suffix.to_string(),
+        }
+    }
+
+    /// Returns the cached display string for `style`, or [`None`] if the symbol can not be displayed with `style`.
+    #[must_use]
+    pub fn display(&self, style: DisplayStyle) -> Option<&str> {
+        match style {
+            DisplayStyle::Short => &self.short,
+            DisplayStyle::Normal => &self.normal,
+            DisplayStyle::Long => &self.long,
+            DisplayStyle::Debuginfo => &self.debuginfo,
+        }
+        .as_deref()
+    }
+}
+
+/// A file-backed cache that maps mangled names to their parse results, so repeated runs over the same symbols can
+/// skip parsing. The parse results are stored as rendered [`CachedSymbol`] strings, not as ASTs.
+///
+/// The cache file records the cache format version and the version of this crate. If either of them does not match
+/// the current ones, the stored entries are discarded when the cache is opened, so a cache never returns output
+/// rendered by a different version of the display code.
+#[derive(Debug)]
+pub struct ParseCache {
+    path: PathBuf,
+    entries: HashMap<String, Option<CachedSymbol>>,
+    dirty: bool,
+}
+
+impl ParseCache {
+    /// Opens the cache stored at `path`. If the file does not exist, or if it is written by an incompatible version,
+    /// an empty cache is returned. The file is not modified until [`ParseCache::save`] is called.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file exists but cannot be read.
+    pub fn open(path: impl Into<PathBuf>) -> io::Result<Self> {
+        let path = path.into();
+
+        let entries = match fs::File::open(&path) {
+            Ok(file) => read_entries(BufReader::new(file))?.unwrap_or_default(),
+            Err(error) if error.kind() == ErrorKind::NotFound => HashMap::new(),
+            Err(error) => return Err(error),
+        };
+
+        Ok(Self {
+            path,
+            entries,
+            dirty: false,
+        })
+    }
+
+    /// Returns the path of the cache file.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the number of cached mangled names.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the cache contains no entries.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the cached parse result of `mangled_name` without parsing it, or `None` if `mangled_name` is not
+    /// cached.
+    #[must_use]
+    pub fn get(&self, mangled_name: &str) -> Option<Result<&CachedSymbol, ParseSymbolError>> {
+        self.entries
+            .get(mangled_name)
+            .map(|entry| entry.as_ref().ok_or(ParseSymbolError))
+    }
+
+    /// Returns the parse result of `mangled_name`, parsing it and recording the result if it is not cached yet.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParseSymbolError`] if `mangled_name` cannot be parsed, see [`Symbol::parse_from_str`].
+    pub fn demangle(&mut self, mangled_name: &str) -> Result<&CachedSymbol, ParseSymbolError> {
+        if !self.entries.contains_key(mangled_name) {
+            let entry = Symbol::parse_from_str(mangled_name)
+                .ok()
+                .map(|(symbol, suffix)| CachedSymbol::new(&symbol, suffix));
+
+            self.entries.insert(mangled_name.to_string(), entry);
+            self.dirty = true;
+        }
+
+        self.entries[mangled_name].as_ref().ok_or(ParseSymbolError)
+    }
+
+    /// Removes all entries from the cache.
+    pub fn clear(&mut self) {
+        if !self.entries.is_empty() {
+            self.entries.clear();
+            self.dirty = true;
+        }
+    }
+
+    /// Removes the entries whose mangled names do not satisfy `f`, which can be used to drop symbols that are no
+    /// longer present in the analyzed binaries.
+    pub fn retain(&mut self, mut f: impl FnMut(&str) -> bool) {
+        let length = self.entries.len();
+
+        self.entries.retain(|mangled_name, _| f(mangled_name));
+        self.dirty |= self.entries.len() != length;
+    }
+
+    /// Writes the cache to its file if it has been modified since it was opened or last saved. The file is replaced
+    /// atomically where the platform supports it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written.
+    pub fn save(&mut self) -> io::Result<()> {
+        if self.dirty {
+            let mut temp_path = self.path.clone().into_os_string();
+
+            temp_path.push(".tmp");
+
+            let temp_path = PathBuf::from(temp_path);
+
+            write_entries(&self.entries, BufWriter::new(fs::File::create(&temp_path)?))?;
+            fs::rename(&temp_path, &self.path)?;
+
+            self.dirty = false;
+        }
+
+        Ok(())
+    }
+}
+
+fn header() -> String {
+    format!("{MAGIC} {FORMAT_VERSION} {CRATE_VERSION}")
+}
+
+fn escape(value: &str, out: &mut impl Write) -> io::Result<()> {
+    for c in value.chars() {
+        match c {
+            '\\' => out.write_all(b"\\\\")?,
+            '\t' => out.write_all(b"\\t")?,
+            '\n' => out.write_all(b"\\n")?,
+            '\r' => out.write_all(b"\\r")?,
+            _ => write!(out, "{c}")?,
+        }
+    }
+
+    Ok(())
+}
+
+fn unescape(value: &str) -> Option<String> {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        result.push(if c == '\\' {
+            match chars.next()? {
+                '\\' => '\\',
+                't' => '\t',
+                'n' => '\n',
+                'r' => '\r',
+                _ => return None,
+            }
+        } else {
+            c
+        });
+    }
+
+    Some(result)
+}
+
+/// Display strings that are not available are written as `\0`, which [`escape`] never produces.
+fn escape_display(value: Option<&str>, out: &mut impl Write) -> io::Result<()> {
+    match value {
+        Some(value) => escape(value, out),
+        None => out.write_all(b"\\0"),
+    }
+}
+
+fn unescape_display(value: &str) -> Result<Option<String>, ()> {
+    if value == "\\0" {
+        Ok(None)
+    } else {
+        unescape(value).map(Some).ok_or(())
+    }
+}
+
+/// Returns `None` if the content is written by an incompatible version or is malformed.
+fn read_entries(reader: impl BufRead) -> io::Result<Option<HashMap<String, Option<CachedSymbol>>>> {
+    let mut lines = reader.lines();
+
+    if lines.next().transpose()?.as_deref() != Some(header().as_str()) {
+        return Ok(None);
+    }
+
+    let mut entries = HashMap::new();
+
+    for line in lines {
+        let line = line?;
+        let mut fields = line.split('\t');
+
+        let Some(mangled_name) = fields.next().and_then(unescape) else {
+            return Ok(None);
+        };
+
+        let fields = fields.collect::<Vec<_>>();
+
+        let entry = match *fields.as_slice() {
+            [] => None,
+            [short, normal, long, debuginfo, suffix] => {
+                let (Ok(short), Ok(normal), Ok(long), Ok(debuginfo), Some(suffix)) = (
+                    unescape_display(short),
+                    unescape_display(normal),
+                    unescape_display(long),
+                    unescape_display(debuginfo),
+                    unescape(suffix),
+                ) else {
+                    return Ok(None);
+                };
+
+                Some(CachedSymbol {
+                    short,
+                    normal,
+                    long,
+                    debuginfo,
+                    suffix,
+                })
+            }
+            _ => return Ok(None),
+        };
+
+        entries.insert(mangled_name, entry);
+    }
+
+    Ok(Some(entries))
+}
+
+fn write_entries(entries: &HashMap<String, Option<CachedSymbol>>, mut writer: impl Write) -> io::Result<()> {
+    writeln!(writer, "{}", header())?;
+
+    for (mangled_name, entry) in entries {
+        escape(mangled_name, &mut writer)?;
+
+        if let Some(entry) = entry {
+            for field in [&entry.short, &entry.normal, &entry.long, &entry.debuginfo] {
+                writer.write_all(b"\t")?;
+                escape_display(field.as_deref(), &mut writer)?;
+            }
+
+            writer.write_all(b"\t")?;
+            escape(&entry.suffix, &mut writer)?;
+        }
+
+        writer.write_all(b"\n")?;
+    }
+
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CachedSymbol, ParseCache};
+    use crate::rust_v0::{DisplayStyle, ParseSymbolError};
+    use std::collections::HashMap;
+    use std::{env, fs, process};
+
+    #[test]
+    fn test_escape() {
+        let mut buffer = Vec::new();
+
+        super::escape("a\\b\tc\nd\re", &mut buffer).unwrap();
+
+        let escaped = String::from_utf8(buffer).unwrap();
+
+        assert_eq!(escaped, "a\\\\b\\tc\\nd\\re");
+        assert_eq!(super::unescape(&escaped).as_deref(), Some("a\\b\tc\nd\re"));
+        assert_eq!(super::unescape("\\x"), None);
+        assert_eq!(super::unescape("\\"), None);
+    }
+
+    #[test]
+    fn test_read_write_entries() {
+        let entries = HashMap::from([
+            (
+                "_RNvC6_123foo3bar\t".to_string(),
+                Some(CachedSymbol {
+                    short: Some("bar".to_string()),
+                    normal: Some("123foo::bar".to_string()),
+                    long: Some("123foo::bar".to_string()),
+                    debuginfo: Some("123foo::bar".to_string()),
+                    suffix: "\t".to_string(),
+                }),
+            ),
+            (
+                "_RMC0RLY_z".to_string(),
+                Some(CachedSymbol {
+                    short: None,
+                    normal: None,
+                    long: None,
+                    debuginfo: None,
+                    suffix: "\\0".to_string(),
+                }),
+            ),
+            ("_RX".to_string(), None),
+        ]);
+
+        let mut buffer = Vec::new();
+
+        super::write_entries(&entries, &mut buffer).unwrap();
+
+        assert_eq!(super::read_entries(buffer.as_slice()).unwrap(), Some(entries));
+        assert_eq!(
+            super::read_entries(b"ast-demangle-parse-cache 0 0.0.0\n".as_slice()).unwrap(),
+            None
+        );
+        assert_eq!(super::read_entries(b"".as_slice()).unwrap(), None);
+
+        let mut malformed = super::header().into_bytes();
+
+        malformed.extend(b"\n_RX\ta\tb\n");
+
+        assert_eq!(super::read_entries(malformed.as_slice()).unwrap(), None);
+    }
+
+    #[test]
+    fn test_parse_cache() {
+        let path = env::temp_dir().join(format!("ast-demangle-test-parse-cache-{}", process::id()));
+        fs::remove_file(&path).ok();
+
+        let mut cache = ParseCache::open(&path).unwrap();
+
+        assert!(cache.is_empty());
+        assert_eq!(cache.get("_RNvC6_123foo3bar"), None);
+
+        let symbol = cache.demangle("_RNvC6_123foo3bar.llvm.123").unwrap().clone();
+
+        assert_eq!(symbol.display(DisplayStyle::Short), Some("bar"));
+        assert_eq!(symbol.display(DisplayStyle::Normal), Some("123foo::bar"));
+        assert_eq!(symbol.suffix, "");
+        assert_eq!(cache.demangle("foo"), Err(ParseSymbolError));
+
+        // The symbol parses, but refers to a lifetime that is not bound.
+        let undisplayable = cache.demangle("_RMC0RLY_z").unwrap().clone();
+
+        assert_eq!(undisplayable.display(DisplayStyle::Normal), None);
+
+        cache.save().unwrap();
+
+        let mut cache = ParseCache::open(&path).unwrap();
+
+        assert_eq!(cache.len(), 3);
+        assert_eq!(cache.get("_RMC0RLY_z"), Some(Ok(&undisplayable)));
+        assert_eq!(cache.get("_RNvC6_123foo3bar.llvm.123"), Some(Ok(&symbol)));
+        assert_eq!(cache.get("foo"), Some(Err(ParseSymbolError)));
+
+        cache.retain(|mangled_name| mangled_name.starts_with("_R"));
+
+        assert_eq!(cache.len(), 2);
+
+        cache.save().unwrap();
+
+        assert_eq!(ParseCache::open(&path).unwrap().len(), 2);
+
+        fs::write(&path, "ast-demangle-parse-cache 0 0.0.0\nfoo\n").unwrap();
+
+        assert!(ParseCache::open(&path).unwrap().is_empty());
+
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,221 @@
+//! Validating lists of mangled names.
+
+use crate::rust_v0::{self, Const, DisplayStyle, Namespace, NodeRc, Path, Symbol, Type, Visitor};
+use std::collections::HashSet;
+use std::fmt::{self, Write};
+
+const MAX_DISPLAY_LENGTH: usize = 1 << 20;
+
+/// A problem found in a mangled name that otherwise parses successfully.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum CheckWarning<'a> {
+    /// Characters following the symbol that are neither part of the Rust v0 syntax nor a vendor specific suffix.
+    TrailingCharacters(&'a str),
+    /// An explicit encoding version, which the current specification does not define.
+    EncodingVersion(u64),
+    /// A special namespace other than closures (`C`) and shims (`S`).
+    UnknownNamespace(char),
+    /// The symbol cannot be displayed, for example because it refers to a lifetime that is not bound.
+    NotDisplayable,
+}
+
+/// The result of checking a single mangled name.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct SymbolCheck<'a> {
+    /// The checked mangled name.
+    pub name: &'a str,
+    /// Whether the name parses with Rust v0 syntax.
+    pub parsed: bool,
+    /// Problems found in the parsed symbol.
+    pub warnings: Vec<CheckWarning<'a>>,
+}
+
+impl SymbolCheck<'_> {
+    /// Returns `true` if the name parses and no problems are found.
+    #[must_use]
+    pub fn is_ok(&self) -> bool {
+        self.parsed && self.warnings.is_empty()
+    }
+}
+
+/// Checks whether `name` is a valid Rust v0 mangled name, and reports constructs in it that are unexpected or
+/// unsupported.
+#[must_use]
+pub fn check_symbol(name: &str) -> SymbolCheck<'_> {
+    let mut warnings = Vec::new();
+
+    let parsed = if let Ok((symbol, suffix)) = Symbol::parse_from_str(name) {
+        if !suffix.is_empty() {
+            warnings.push(CheckWarning::TrailingCharacters(suffix));
+        }
+
+        if let Some(version) = symbol.version {
+            warnings.push(CheckWarning::EncodingVersion(version));
+        }
+
+        let mut namespaces = Vec::new();
+        let mut visitor = NamespaceCollector {
+            visited: HashSet::new(),
+            namespaces: &mut namespaces,
+        };
+
+        visitor.visit_symbol(&symbol);
+
+        namespaces.sort_unstable();
+        namespaces.dedup();
+
+        warnings.extend(namespaces.into_iter().map(CheckWarning::UnknownNamespace));
+
+        if !is_displayable(&symbol) {
+            warnings.push(CheckWarning::NotDisplayable);
+        }
+
+        true
+    } else {
+        false
+    };
+
+    SymbolCheck { name, parsed, warnings }
+}
+
+/// Checks every name in `names` with [`check_symbol`].
+pub fn check_symbols<'a>(names: impl IntoIterator<Item = &'a str>) -> impl Iterator<Item = SymbolCheck<'a>> {
+    names.into_iter().map(check_symbol)
+}
+
+/// Output that is too long to check is considered displayable.
+fn is_displayable(symbol: &Symbol) -> bool {
+    struct Sink {
+        remaining: usize,
+        truncated: bool,
+    }
+
+    impl Write for Sink {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            if let Some(remaining) = self.remaining.checked_sub(s.len()) {
+                self.remaining = remaining;
+
+                Ok(())
+            } else {
+                self.truncated = true;
+
+                Err(fmt::Error)
+            }
+        }
+    }
+
+    [DisplayStyle::Short, DisplayStyle::Long].into_iter().all(|style| {
+        let mut sink = Sink {
+            remaining: MAX_DISPLAY_LENGTH,
+            truncated: false,
+        };
+
+        write!(sink, "{}", symbol.display(style)).is_ok() || sink.truncated
+    })
+}
+
+struct NamespaceCollector<'v> {
+    visited: HashSet<usize>,
+    namespaces: &'v mut Vec<char>,
+}
+
+impl NamespaceCollector<'_> {
+    /// Shared subtrees are only visited once, so back references can not cause exponential work.
+    fn first_visit<T>(&mut self, node: &NodeRc<T>) -> bool {
+        self.visited.insert(NodeRc::as_ptr(node).cast::<()>() as usize)
+    }
+}
+
+impl<'a> Visitor<'a> for NamespaceCollector<'_> {
+    fn visit_path(&mut self, path: &NodeRc<Path<'a>>) {
+        if self.first_visit(path) {
+            if let Path::Nested {
+                namespace: Namespace::Other(namespace),
+                ..
+            } = path.as_ref()
+            {
+                if namespace.is_ascii_uppercase() {
+                    self.namespaces.push(*namespace);
+                }
+            }
+
+            rust_v0::walk_path(self, path);
+        }
+    }
+
+    fn visit_type(&mut self, type_: &NodeRc<Type<'a>>) {
+        if self.first_visit(type_) {
+            rust_v0::walk_type(self, type_);
+        }
+    }
+
+    fn visit_const(&mut self, const_: &NodeRc<Const<'a>>) {
+        if self.first_visit(const_) {
+            rust_v0::walk_const(self, const_);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CheckWarning, SymbolCheck};
+
+    #[test]
+    fn test_check_symbols() {
+        let checks = super::check_symbols([
+            "_RNvC6_123foo3bar",
+            "_RNvC6_123foo3bar.llvm.123",
+            "_RNvC6_123foo3bar foo",
+            "_R0NvC6_123foo3bar",
+            "_RNXNYC3foo3bar3baz",
+            "_RINvC3foo3barRL0_hE",
+            "foo",
+        ])
+        .collect::<Vec<_>>();
+
+        assert_eq!(
+            checks,
+            [
+                SymbolCheck {
+                    name: "_RNvC6_123foo3bar",
+                    parsed: true,
+                    warnings: Vec::new(),
+                },
+                SymbolCheck {
+                    name: "_RNvC6_123foo3bar.llvm.123",
+                    parsed: true,
+                    warnings: Vec::new(),
+                },
+                SymbolCheck {
+                    name: "_RNvC6_123foo3bar foo",
+                    parsed: true,
+                    warnings: vec![CheckWarning::TrailingCharacters(" foo")],
+                },
+                SymbolCheck {
+                    name: "_R0NvC6_123foo3bar",
+                    parsed: true,
+                    warnings: vec![CheckWarning::EncodingVersion(0)],
+                },
+                SymbolCheck {
+                    name: "_RNXNYC3foo3bar3baz",
+                    parsed: true,
+                    warnings: vec![CheckWarning::UnknownNamespace('X'), CheckWarning::UnknownNamespace('Y')],
+                },
+                SymbolCheck {
+                    name: "_RINvC3foo3barRL0_hE",
+                    parsed: true,
+                    warnings: vec![CheckWarning::NotDisplayable],
+                },
+                SymbolCheck {
+                    name: "foo",
+                    parsed: false,
+                    warnings: Vec::new(),
+                },
+            ]
+        );
+
+        assert!(checks[0].is_ok());
+        assert!(!checks[2].is_ok());
+        assert!(!checks[6].is_ok());
+    }
+}
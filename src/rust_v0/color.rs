@@ -0,0 +1,108 @@
+//! Displaying symbols with ANSI escape codes, enabled by the `color` feature.
+
+use crate::rust_v0::{DisplayStyle, Symbol, TokenKind};
+use std::fmt::{self, Display, Formatter};
+
+/// The colors [`Symbol::display_colored`] uses for each kind of token, given as the parameters of ANSI SGR escape
+/// sequences, like `"1;34"` for bold blue, or [`None`] to leave the token uncolored.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct ColorScheme {
+    /// The color of punctuation and keywords. The default is [`None`].
+    pub syntax: Option<&'static str>,
+    /// The color of crate names. The default is bold blue.
+    pub crate_root: Option<&'static str>,
+    /// The color of identifiers. The default is [`None`].
+    pub identifier: Option<&'static str>,
+    /// The color of identifiers in special namespaces, like `{closure#0}`. The default is magenta.
+    pub special_identifier: Option<&'static str>,
+    /// The color of basic types. The default is green.
+    pub basic_type: Option<&'static str>,
+    /// The color of lifetimes. The default is yellow.
+    pub lifetime: Option<&'static str>,
+    /// The color of constant values. The default is cyan.
+    pub const_: Option<&'static str>,
+}
+
+impl ColorScheme {
+    fn color(&self, kind: TokenKind) -> Option<&'static str> {
+        match kind {
+            TokenKind::CrateRoot => self.crate_root,
+            TokenKind::Identifier => self.identifier,
+            TokenKind::SpecialIdentifier => self.special_identifier,
+            TokenKind::BasicType => self.basic_type,
+            TokenKind::Lifetime => self.lifetime,
+            TokenKind::Const => self.const_,
+            _ => self.syntax,
+        }
+    }
+}
+
+impl Default for ColorScheme {
+    fn default() -> Self {
+        Self {
+            syntax: None,
+            crate_root: Some("1;34"),
+            identifier: None,
+            special_identifier: Some("35"),
+            basic_type: Some("32"),
+            lifetime: Some("33"),
+            const_: Some("36"),
+        }
+    }
+}
+
+pub fn display_colored<'s>(symbol: &'s Symbol, style: DisplayStyle, color_scheme: ColorScheme) -> impl Display + 's {
+    struct Wrapper<'s, 'a> {
+        symbol: &'s Symbol<'a>,
+        style: DisplayStyle,
+        color_scheme: ColorScheme,
+    }
+
+    impl Display for Wrapper<'_, '_> {
+        fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+            self.symbol.render_tokens(
+                &mut |kind, text: &str| match self.color_scheme.color(kind) {
+                    None => f.write_str(text),
+                    Some(color) => write!(f, "\x1b[{color}m{text}\x1b[0m"),
+                },
+                self.style,
+            )
+        }
+    }
+
+    Wrapper {
+        symbol,
+        style,
+        color_scheme,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ColorScheme;
+    use crate::rust_v0::{DisplayStyle, Symbol};
+
+    #[test]
+    fn test_display_colored() {
+        let (symbol, _) = Symbol::parse_from_str("_RINvNtC3std3mem4swapL_hE").unwrap();
+
+        assert_eq!(
+            symbol
+                .display_colored(DisplayStyle::Normal, ColorScheme::default())
+                .to_string(),
+            "\x1b[1;34mstd\x1b[0m::mem::swap::<\x1b[33m'_\x1b[0m, \x1b[32mu8\x1b[0m>"
+        );
+
+        let color_scheme = ColorScheme {
+            crate_root: None,
+            basic_type: None,
+            lifetime: None,
+            ..ColorScheme::default()
+        };
+
+        assert_eq!(
+            symbol.display_colored(DisplayStyle::Normal, color_scheme).to_string(),
+            symbol.display(DisplayStyle::Normal).to_string()
+        );
+    }
+}
@@ -0,0 +1,464 @@
+//! Printing the AST of a symbol as an indented tree, for investigating how a mangled name was demangled.
+
+use super::mangle::{self, MangledNode};
+use crate::rust_v0::display;
+use crate::rust_v0::{Abi, Const, ConstFields, GenericArg, Identifier, Path, Symbol, Type};
+use std::fmt::{self, Display, Formatter};
+use std::slice;
+
+struct TreeWriter<'f, 'b, 'n> {
+    out: &'f mut Formatter<'b>,
+    nodes: slice::Iter<'n, MangledNode>,
+    depth: usize,
+}
+
+struct IdentifierLabel<'s, 'a>(&'s Identifier<'a>);
+
+impl Display for IdentifierLabel<'_, '_> {
+    #[allow(clippy::use_debug)] // `Debug` is used for escaping char and string literals.
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{:?}", self.0.display().to_string())?;
+
+        if self.0.disambiguator != 0 {
+            write!(f, " (disambiguator {})", self.0.disambiguator)?;
+        }
+
+        Ok(())
+    }
+}
+
+struct LifetimeLabel(u64);
+
+impl Display for LifetimeLabel {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        if self.0 == 0 {
+            f.write_str("'_")
+        } else {
+            write!(f, "lifetime {}", self.0)
+        }
+    }
+}
+
+struct PathLabel<'s, 'a>(&'s Path<'a>);
+
+impl Display for PathLabel<'_, '_> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self.0 {
+            Path::CrateRoot(identifier) => write!(f, "CrateRoot {}", IdentifierLabel(identifier)),
+            Path::InherentImpl { impl_path, .. } | Path::TraitImpl { impl_path, .. } => {
+                f.write_str(if matches!(self.0, Path::InherentImpl { .. }) {
+                    "InherentImpl"
+                } else {
+                    "TraitImpl"
+                })?;
+
+                if impl_path.disambiguator != 0 {
+                    write!(f, " (disambiguator {})", impl_path.disambiguator)?;
+                }
+
+                Ok(())
+            }
+            Path::TraitDefinition { .. } => f.write_str("TraitDefinition"),
+            Path::Nested {
+                namespace, identifier, ..
+            } => write!(f, "Nested {} {}", namespace.as_char(), IdentifierLabel(identifier)),
+            Path::Generic { .. } => f.write_str("Generic"),
+        }
+    }
+}
+
+struct TypeLabel<'s, 'a>(&'s Type<'a>);
+
+impl Display for TypeLabel<'_, '_> {
+    #[allow(clippy::use_debug)] // `Debug` is used for escaping char and string literals.
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self.0 {
+            Type::Basic(basic_type) => write!(f, "Basic {basic_type}"),
+            Type::Named(_) => f.write_str("Named"),
+            Type::Array(..) => f.write_str("Array"),
+            Type::Slice(_) => f.write_str("Slice"),
+            Type::Tuple(_) => f.write_str("Tuple"),
+            Type::Ref { lifetime, .. } => write!(f, "Ref {}", LifetimeLabel(*lifetime)),
+            Type::RefMut { lifetime, .. } => write!(f, "RefMut {}", LifetimeLabel(*lifetime)),
+            Type::PtrConst(_) => f.write_str("PtrConst"),
+            Type::PtrMut(_) => f.write_str("PtrMut"),
+            Type::Fn(fn_sig) => {
+                f.write_str("Fn")?;
+
+                if fn_sig.bound_lifetimes != 0 {
+                    write!(f, " binding {} lifetimes", fn_sig.bound_lifetimes)?;
+                }
+
+                if fn_sig.is_unsafe {
+                    f.write_str(" unsafe")?;
+                }
+
+                match &fn_sig.abi {
+                    None => Ok(()),
+                    Some(Abi::C) => f.write_str(" extern \"C\""),
+                    Some(Abi::Named(name)) => write!(f, " extern {name:?}"),
+                }
+            }
+            Type::DynTrait { dyn_bounds, lifetime } => {
+                f.write_str("DynTrait")?;
+
+                if dyn_bounds.bound_lifetimes != 0 {
+                    write!(f, " binding {} lifetimes", dyn_bounds.bound_lifetimes)?;
+                }
+
+                write!(f, " {}", LifetimeLabel(*lifetime))
+            }
+        }
+    }
+}
+
+struct ConstLabel<'s, 'a>(&'s Const<'a>);
+
+impl Display for ConstLabel<'_, '_> {
+    #[allow(clippy::use_debug)] // `Debug` is used for escaping char and string literals.
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self.0 {
+            Const::I8(value) => write!(f, "I8 {value}"),
+            Const::U8(value) => write!(f, "U8 {value}"),
+            Const::Isize(value) => write!(f, "Isize {value}"),
+            Const::Usize(value) => write!(f, "Usize {value}"),
+            Const::I32(value) => write!(f, "I32 {value}"),
+            Const::U32(value) => write!(f, "U32 {value}"),
+            Const::I128(value) => write!(f, "I128 {value}"),
+            Const::U128(value) => write!(f, "U128 {value}"),
+            Const::I16(value) => write!(f, "I16 {value}"),
+            Const::U16(value) => write!(f, "U16 {value}"),
+            Const::I64(value) => write!(f, "I64 {value}"),
+            Const::U64(value) => write!(f, "U64 {value}"),
+            Const::Bool(value) => write!(f, "Bool {value}"),
+            Const::Char(value) => write!(f, "Char {value:?}"),
+            Const::Str(value) => {
+                f.write_str("Str ")?;
+                display::write_const_str(value, f)
+            }
+            Const::Ref(_) => f.write_str("Ref"),
+            Const::RefMut(_) => f.write_str("RefMut"),
+            Const::Array(_) => f.write_str("Array"),
+            Const::Tuple(_) => f.write_str("Tuple"),
+            Const::NamedStruct { fields, .. } => f.write_str(match fields {
+                ConstFields::Unit => "NamedStruct unit",
+                ConstFields::Tuple(_) => "NamedStruct tuple",
+                ConstFields::Struct(_) => "NamedStruct struct",
+            }),
+            Const::Placeholder => f.write_str("Placeholder"),
+            Const::F32(bits) => write!(f, "F32 {:?}", f32::from_bits(*bits)),
+            Const::F64(bits) => write!(f, "F64 {:?}", f64::from_bits(*bits)),
+        }
+    }
+}
+
+impl TreeWriter<'_, '_, '_> {
+    fn write_line(&mut self, role: Option<&str>, label: impl Display) -> fmt::Result {
+        write!(self.out, "{:1$}", "", self.depth * 2)?;
+
+        if let Some(role) = role {
+            write!(self.out, "{role}: ")?;
+        }
+
+        write!(self.out, "{label}")
+    }
+
+    /// Writes the line of a path, type or constant, returns `true` if its children should be written.
+    fn write_node_line(&mut self, role: Option<&str>, label: impl Display) -> Result<bool, fmt::Error> {
+        self.write_line(role, label)?;
+
+        // The walk follows the back reference decisions of the mangler, so the nodes should never run out. If they do,
+        // the tree is still written, without positions.
+        let Some(node) = self.nodes.next() else {
+            writeln!(self.out)?;
+
+            return Ok(true);
+        };
+
+        write!(self.out, " @{}..{}", node.range.start, node.range.end)?;
+
+        if let Some(target) = node.back_ref {
+            write!(self.out, " -> back reference to @{target}")?;
+        }
+
+        writeln!(self.out)?;
+
+        Ok(node.back_ref.is_none())
+    }
+
+    fn with_children(&mut self, f: impl FnOnce(&mut Self) -> fmt::Result) -> fmt::Result {
+        self.depth += 1;
+
+        let result = f(self);
+
+        self.depth -= 1;
+
+        result
+    }
+
+    fn write_path(&mut self, role: Option<&str>, path: &Path) -> fmt::Result {
+        if !self.write_node_line(role, PathLabel(path))? {
+            return Ok(());
+        }
+
+        self.with_children(|this| match path {
+            Path::CrateRoot(_) => Ok(()),
+            Path::InherentImpl { impl_path, type_ } => {
+                this.write_path(Some("module"), &impl_path.path)?;
+                this.write_type(Some("self type"), type_)
+            }
+            Path::TraitImpl {
+                impl_path,
+                type_,
+                trait_,
+            } => {
+                this.write_path(Some("module"), &impl_path.path)?;
+                this.write_type(Some("self type"), type_)?;
+                this.write_path(Some("trait"), trait_)
+            }
+            Path::TraitDefinition { type_, trait_ } => {
+                this.write_type(Some("self type"), type_)?;
+                this.write_path(Some("trait"), trait_)
+            }
+            Path::Nested { path, .. } => this.write_path(None, path),
+            Path::Generic { path, generic_args } => {
+                this.write_path(None, path)?;
+
+                generic_args
+                    .iter()
+                    .try_for_each(|generic_arg| this.write_generic_arg(generic_arg))
+            }
+        })
+    }
+
+    fn write_generic_arg(&mut self, generic_arg: &GenericArg) -> fmt::Result {
+        match generic_arg {
+            GenericArg::Lifetime(lifetime) => {
+                self.write_line(None, format_args!("Lifetime {}", LifetimeLabel(*lifetime)))?;
+                writeln!(self.out)
+            }
+            GenericArg::Type(type_) => self.write_type(None, type_),
+            GenericArg::Const(const_) => self.write_const(None, const_),
+        }
+    }
+
+    fn write_type(&mut self, role: Option<&str>, type_: &Type) -> fmt::Result {
+        if !self.write_node_line(role, TypeLabel(type_))? {
+            return Ok(());
+        }
+
+        self.with_children(|this| match type_ {
+            Type::Basic(_) => Ok(()),
+            Type::Named(path) => this.write_path(None, path),
+            Type::Array(type_, length) => {
+                this.write_type(None, type_)?;
+                this.write_const(Some("length"), length)
+            }
+            Type::Slice(type_)
+            | Type::Ref { type_, .. }
+            | Type::RefMut { type_, .. }
+            | Type::PtrConst(type_)
+            | Type::PtrMut(type_) => this.write_type(None, type_),
+            Type::Tuple(tuple_types) => tuple_types.iter().try_for_each(|type_| this.write_type(None, type_)),
+            Type::Fn(fn_sig) => {
+                for argument_type in &fn_sig.argument_types {
+                    this.write_type(None, argument_type)?;
+                }
+
+                this.write_type(Some("return"), &fn_sig.return_type)
+            }
+            Type::DynTrait { dyn_bounds, .. } => dyn_bounds.dyn_traits.iter().try_for_each(|dyn_trait| {
+                this.write_path(Some("trait"), &dyn_trait.path)?;
+
+                dyn_trait
+                    .dyn_trait_assoc_bindings
+                    .iter()
+                    .try_for_each(|binding| this.write_type(Some(&binding.display_name().to_string()), &binding.type_))
+            }),
+        })
+    }
+
+    fn write_const(&mut self, role: Option<&str>, const_: &Const) -> fmt::Result {
+        if !self.write_node_line(role, ConstLabel(const_))? {
+            return Ok(());
+        }
+
+        self.with_children(|this| match const_ {
+            Const::Ref(value) | Const::RefMut(value) => this.write_const(None, value),
+            Const::Array(values) | Const::Tuple(values) => {
+                values.iter().try_for_each(|value| this.write_const(None, value))
+            }
+            Const::NamedStruct { path, fields } => {
+                this.write_path(None, path)?;
+
+                match fields {
+                    ConstFields::Unit => Ok(()),
+                    ConstFields::Tuple(values) => values.iter().try_for_each(|value| this.write_const(None, value)),
+                    ConstFields::Struct(fields) => fields.iter().try_for_each(|(identifier, value)| {
+                        this.write_const(Some(&identifier.display().to_string()), value)
+                    }),
+                }
+            }
+            _ => Ok(()),
+        })
+    }
+}
+
+fn write_symbol(symbol: &Symbol, f: &mut Formatter) -> fmt::Result {
+    let (_, nodes) = mangle::mangle_symbol_with_nodes(symbol);
+
+    f.write_str("Symbol")?;
+
+    if let Some(version) = symbol.version {
+        write!(f, " version {version}")?;
+    }
+
+    writeln!(f)?;
+
+    let mut writer = TreeWriter {
+        out: f,
+        nodes: nodes.iter(),
+        depth: 1,
+    };
+
+    writer.write_path(Some("path"), &symbol.path)?;
+
+    if let Some(instantiating_crate) = &symbol.instantiating_crate {
+        writer.write_path(Some("instantiating crate"), instantiating_crate)?;
+    }
+
+    if let Some(suffix) = &symbol.vendor_specific_suffix {
+        writer.write_line(Some("vendor specific suffix"), format_args!("{suffix:?}"))?;
+        writeln!(writer.out)?;
+    }
+
+    debug_assert!(writer.nodes.next().is_none());
+
+    Ok(())
+}
+
+pub fn display_debug_tree<'s>(symbol: &'s Symbol) -> impl Display + 's {
+    struct Wrapper<'s, 'a>(&'s Symbol<'a>);
+
+    impl Display for Wrapper<'_, '_> {
+        fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+            write_symbol(self.0, f)
+        }
+    }
+
+    Wrapper(symbol)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rust_v0::Symbol;
+
+    #[test]
+    fn test_debug_tree() {
+        let (symbol, _) = Symbol::parse_from_str("_RINvNtCs1234_3std3mem4swapNtB4_3FooKj1_EB4_.llvm.12").unwrap();
+
+        assert_eq!(
+            symbol.debug_tree().to_string(),
+            r#"Symbol
+  path: Generic @0..39
+    Nested v "swap" @1..25
+      Nested t "mem" @3..20
+        CrateRoot "std" (disambiguator 246208) @5..16
+    Named @25..34
+      Nested t "Foo" @25..34
+        CrateRoot "std" (disambiguator 246208) @27..30 -> back reference to @5
+    Usize 1 @35..38
+  instantiating crate: CrateRoot "std" (disambiguator 246208) @39..42 -> back reference to @5
+  vendor specific suffix: ".llvm.12"
+"#
+        );
+    }
+
+    #[test]
+    fn test_debug_tree_nodes() {
+        let (symbol, _) =
+            Symbol::parse_from_str("_RNvXs0_Cs123_3fooINtB5_3BarFG_RL0_hEuENtNtCs456_4core3fmt7Display3fmt").unwrap();
+
+        assert_eq!(
+            symbol.debug_tree().to_string(),
+            r#"Symbol
+  path: Nested v "fmt" @0..68
+    TraitImpl (disambiguator 2) @2..64
+      module: CrateRoot "foo" (disambiguator 3973) @6..16
+      self type: Named @16..37
+        Generic @16..37
+          Nested t "Bar" @17..26
+            CrateRoot "foo" (disambiguator 3973) @19..22 -> back reference to @6
+          Fn binding 1 lifetimes @26..36
+            Ref lifetime 1 @29..34
+              Basic u8 @33..34
+            return: Basic () @35..36
+      trait: Nested t "Display" @37..64
+        Nested t "fmt" @39..56
+          CrateRoot "core" (disambiguator 15694) @41..52
+"#
+        );
+
+        let (symbol, _) =
+            Symbol::parse_from_str("_RINvCs123_3foo3barDNtB2_5Traitp4ItemhEL_KVNtB2_3BazS1xRb0_EE").unwrap();
+
+        assert_eq!(
+            symbol.debug_tree().to_string(),
+            r#"Symbol
+  path: Generic @0..59
+    Nested v "bar" @1..17
+      CrateRoot "foo" (disambiguator 3973) @3..13
+    DynTrait '_ @17..39
+      trait: Nested t "Trait" @18..29
+        CrateRoot "foo" (disambiguator 3973) @20..23 -> back reference to @3
+      Item: Basic u8 @35..36
+    NamedStruct struct @40..58
+      Nested t "Baz" @41..50
+        CrateRoot "foo" (disambiguator 3973) @43..46 -> back reference to @3
+      x: Ref @53..57
+        Bool false @54..57
+"#
+        );
+
+        // A named type whose path is a back reference, and constants that are back references.
+        let (symbol, _) =
+            Symbol::parse_from_str("_RIC5_YsMC4SRRRRTdRdyyEbjC5jjjjsMC2_RxAae_B0_AbBB_AbBB_jAbBB_E").unwrap();
+
+        assert_eq!(
+            symbol.debug_tree().to_string(),
+            r#"Symbol
+  path: Generic @0..54
+    CrateRoot "YsMC4" @1..8
+    Slice @8..20
+      Ref '_ @9..20
+        Ref '_ @10..20
+          Ref '_ @11..20
+            Ref '_ @12..20
+              Tuple @13..20
+                Basic f64 @14..15
+                Ref '_ @15..17
+                  Basic f64 @16..17
+                Basic u64 @17..18
+                Basic u64 @18..19
+    Basic bool @20..21
+    Basic usize @21..22
+    Named @22..29
+      CrateRoot "jjjjs" @22..29
+    Named @29..38
+      InherentImpl @29..38
+        module: CrateRoot "Rx" @30..34
+        self type: Array @34..38
+          Basic i8 @35..36
+          length: Str "" @36..38
+    Named @38..41
+      CrateRoot "YsMC4" @38..41 -> back reference to @1
+    Array @41..46
+      Basic bool @42..43
+      length: Str "" @43..46 -> back reference to @36
+    Array @46..49 -> back reference to @41
+    Basic usize @49..50
+    Array @50..53 -> back reference to @41
+"#
+        );
+    }
+}
@@ -0,0 +1,168 @@
+//! Demangling many symbols while sharing work and memory between them.
+
+use crate::rust_v0::{Interner, NodeRc, ParseSymbolError, Symbol};
+use std::collections::{BTreeMap, HashMap};
+
+const DEFAULT_CAPACITY: usize = 4096;
+
+type Entry = Result<(NodeRc<Symbol<'static>>, usize), ParseSymbolError>;
+
+/// Demangles symbols for tools that process whole binaries, where symbol tables contain many duplicate mangled names,
+/// and many symbols share crate roots and module paths.
+///
+/// The parse results of the most recently demangled names are remembered, so demangling a name again returns the same
+/// [`Symbol`] without parsing. Symbols are converted with an [`Interner`], so equal paths, types and constants in
+/// different symbols share one AST node.
+pub struct Demangler {
+    capacity: usize,
+    entries: HashMap<String, (u64, Entry)>,
+    recency: BTreeMap<u64, String>,
+    clock: u64,
+    interner: Interner,
+}
+
+impl Demangler {
+    /// Creates a demangler that remembers the parse results of at most `capacity` mangled names. When it is full, the
+    /// least recently used result is forgotten.
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: HashMap::new(),
+            recency: BTreeMap::new(),
+            clock: 0,
+            interner: Interner::new(),
+        }
+    }
+
+    /// Returns the maximum number of remembered parse results.
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of remembered parse results.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if no parse results are remembered.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Parses `mangled_name` like [`Symbol::parse_from_str`], or returns the remembered result if `mangled_name` has
+    /// been demangled recently. The symbol is returned together with the suffix of `mangled_name` that is not part of
+    /// the Rust v0 syntax.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParseSymbolError`] if `mangled_name` cannot be parsed.
+    pub fn demangle<'n>(
+        &mut self,
+        mangled_name: &'n str,
+    ) -> Result<(NodeRc<Symbol<'static>>, &'n str), ParseSymbolError> {
+        self.clock += 1;
+
+        let result = if let Some((last_used, entry)) = self.entries.get_mut(mangled_name) {
+            if let Some(key) = self.recency.remove(last_used) {
+                self.recency.insert(self.clock, key);
+            }
+
+            *last_used = self.clock;
+
+            entry.clone()
+        } else {
+            let entry = self.parse(mangled_name);
+
+            if self.capacity != 0 {
+                if self.entries.len() == self.capacity {
+                    if let Some((_, key)) = self.recency.pop_first() {
+                        self.entries.remove(&key);
+                    }
+                }
+
+                self.recency.insert(self.clock, mangled_name.to_string());
+                self.entries
+                    .insert(mangled_name.to_string(), (self.clock, entry.clone()));
+            }
+
+            self.interner.prune();
+
+            entry
+        };
+
+        result.map(|(symbol, suffix_length)| (symbol, &mangled_name[mangled_name.len() - suffix_length..]))
+    }
+
+    fn parse(&mut self, mangled_name: &str) -> Entry {
+        let (symbol, suffix) = Symbol::parse_interned(mangled_name, &mut self.interner)?;
+
+        Ok((NodeRc::new(symbol), suffix.len()))
+    }
+
+    /// Forgets all remembered parse results and shared nodes.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.recency.clear();
+        self.interner.clear();
+    }
+}
+
+impl Default for Demangler {
+    /// Creates a demangler that remembers the parse results of 4096 mangled names.
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Demangler;
+    use crate::rust_v0::{NodeRc, ParseSymbolError, Path};
+
+    #[test]
+    fn test_demangler() {
+        let mut demangler = Demangler::new(2);
+        let (first, suffix) = demangler.demangle("_RNvNtC3std3mem4swap foo").unwrap();
+
+        assert_eq!(first.to_string(), "std::mem::swap");
+        assert_eq!(suffix, " foo");
+
+        // Remembered results are returned without parsing.
+        let (again, _) = demangler.demangle("_RNvNtC3std3mem4swap foo").unwrap();
+
+        assert!(NodeRc::ptr_eq(&first, &again));
+
+        // Equal paths are shared between symbols.
+        let (second, _) = demangler.demangle("_RNvNtC3std3mem7replace").unwrap();
+
+        let (
+            Path::Nested { path: first_parent, .. },
+            Path::Nested {
+                path: second_parent, ..
+            },
+        ) = (first.path.as_ref(), second.path.as_ref())
+        else {
+            unreachable!()
+        };
+
+        assert!(NodeRc::ptr_eq(first_parent, second_parent));
+
+        // Failures are remembered too.
+        assert_eq!(demangler.demangle("foo"), Err(ParseSymbolError));
+        assert_eq!(demangler.len(), 2);
+
+        // The least recently used result is forgotten.
+        let (third, _) = demangler.demangle("_RNvNtC3std3mem4swap foo").unwrap();
+
+        assert!(!NodeRc::ptr_eq(&first, &third));
+        assert_eq!(first, third);
+
+        demangler.clear();
+
+        assert!(demangler.is_empty());
+    }
+}
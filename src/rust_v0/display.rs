@@ -1,9 +1,14 @@
 //! Pretty printing demangled symbol names.
 
+use crate::padding;
 use crate::rust_v0::{
-    Abi, BasicType, Const, ConstFields, DynBounds, DynTrait, DynTraitAssocBinding, FnSig, GenericArg, Path, Type,
+    Abi, BasicType, Const, ConstFields, ConstStr, DisplayError, DynBounds, DynTrait, DynTraitAssocBinding,
+    FnReturnType, FnSig, GenericArg, Identifier, ImplPath, Namespace, Path, Symbol, Type,
 };
-use std::{any, fmt};
+use std::collections::HashMap;
+use std::{any, fmt, io};
+
+const IO_BUFFER_SIZE: usize = 1024;
 
 /// Denote the style for displaying the symbol.
 #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
@@ -15,6 +20,9 @@ pub enum Style {
     /// Show crate hashes and const value types. This matches rustc-demangle’s `{:#}` format. Note that even with this
     /// style, impl paths are still omitted.
     Long,
+    /// Follow the naming conventions rustc uses for debuginfo (e.g. DWARF `DW_AT_name`): no crate hashes, impl blocks
+    /// as `{impl#N}`, generic arguments without turbofish, and no lifetimes or higher-ranked binders.
+    Debuginfo,
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
@@ -39,8 +47,16 @@ pub trait DemangleWrite {
     /// Called when we are entering the scope of some AST node.
     fn push_demangle_node(&mut self, _: DemangleNodeType) {}
     /// Same as `fmt::Write::write_str`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the underlying sink fails to accept the string.
     fn write_str(&mut self, s: &str) -> fmt::Result;
     /// Same as `fmt::write::write_fmt`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the underlying sink fails to accept the formatted string.
     fn write_fmt(&mut self, args: fmt::Arguments<'_>) -> fmt::Result {
         if let Some(s) = args.as_str() {
             self.write_str(s)
@@ -62,12 +78,362 @@ impl<W: fmt::Write> DemangleWrite for W {
     }
 }
 
-pub fn display_fn(f: impl Fn(&mut fmt::Formatter) -> fmt::Result) -> impl fmt::Display {
+/// Customization points for displaying individual kinds of AST nodes. Every method defaults to the built-in output, so
+/// an implementation only needs to override the methods for the node kinds it wants to display differently.
+pub trait Renderer {
+    /// Writes the identifier of a crate root.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `out` fails to accept the output.
+    fn render_crate_root(&mut self, identifier: &Identifier, out: &mut dyn DemangleWrite, style: Style) -> fmt::Result {
+        write_crate_root(identifier, out, style)
+    }
+
+    /// Writes an identifier that is nested in a path, or that names a field of a constant struct value.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `out` fails to accept the output.
+    fn render_identifier(&mut self, identifier: &Identifier, out: &mut dyn DemangleWrite, style: Style) -> fmt::Result {
+        let _ = style;
+
+        write!(out, "{}", identifier.display())
+    }
+
+    /// Writes an identifier in a special namespace, like `{closure#0}` for closures or `{shim:vtable#0}` for shims.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `out` fails to accept the output.
+    fn render_special_identifier(
+        &mut self,
+        namespace: Namespace,
+        identifier: &Identifier,
+        out: &mut dyn DemangleWrite,
+        style: Style,
+    ) -> fmt::Result {
+        let _ = style;
+
+        write_special_identifier(namespace, identifier, out)
+    }
+
+    /// Writes the generic argument list of a path, including the enclosing angle brackets.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `out` fails to accept the output.
+    fn render_generic_args(
+        &mut self,
+        generic_args: &[GenericArg],
+        out: &mut dyn DemangleWrite,
+        style: Style,
+        bound_lifetime_depth: u64,
+    ) -> fmt::Result {
+        write_generic_args(generic_args, out, self, style, bound_lifetime_depth)
+    }
+
+    /// Writes a constant value. `in_value` is `true` if the constant is part of another constant value.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `out` fails to accept the output.
+    fn render_const(
+        &mut self,
+        const_: &Const,
+        out: &mut dyn DemangleWrite,
+        style: Style,
+        bound_lifetime_depth: u64,
+        in_value: bool,
+    ) -> fmt::Result {
+        write_const(const_, out, self, style, bound_lifetime_depth, in_value)
+    }
+
+    /// Writes a basic type, like `u8` or `str`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `out` fails to accept the output.
+    fn render_basic_type(&mut self, basic_type: BasicType, out: &mut dyn DemangleWrite, style: Style) -> fmt::Result {
+        let _ = style;
+
+        write_basic_type(basic_type, out)
+    }
+
+    /// Writes a function pointer signature, like `unsafe fn(u8) -> bool`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `out` fails to accept the output.
+    fn render_fn_sig(
+        &mut self,
+        fn_sig: &FnSig,
+        out: &mut dyn DemangleWrite,
+        style: Style,
+        bound_lifetime_depth: u64,
+    ) -> fmt::Result {
+        write_fn_sig(fn_sig, out, self, style, bound_lifetime_depth)
+    }
+
+    /// Writes the path of the impl block an inherent or trait impl path refers to, after the `<Type>` or
+    /// `<Type as Trait>` part. The built-in output omits it, the same as rustc-demangle does.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `out` fails to accept the output.
+    fn render_impl_path(
+        &mut self,
+        impl_path: &ImplPath,
+        out: &mut dyn DemangleWrite,
+        style: Style,
+        bound_lifetime_depth: u64,
+    ) -> fmt::Result {
+        let _ = (impl_path, out, style, bound_lifetime_depth);
+
+        Ok(())
+    }
+
+    /// Writes a lifetime given its de Bruijn index and the number of lifetimes bound by enclosing binders.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `out` fails to accept the output, or if `lifetime` does not refer to a bound lifetime.
+    fn render_lifetime(
+        &mut self,
+        lifetime: u64,
+        out: &mut dyn DemangleWrite,
+        bound_lifetime_depth: u64,
+    ) -> fmt::Result {
+        write_lifetime(lifetime, out, bound_lifetime_depth)
+    }
+}
+
+/// A [`Renderer`] that produces the built-in output.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct DefaultRenderer;
+
+impl Renderer for DefaultRenderer {}
+
+/// Collects output in a fixed size buffer before passing it to an [`io::Write`] object, so writing the many small
+/// fragments of a demangled name does not cause a write call for each of them.
+struct IoWriter<'w, W: ?Sized> {
+    inner: &'w mut W,
+    buffer: [u8; IO_BUFFER_SIZE],
+    length: usize,
+    error: Option<io::Error>,
+}
+
+impl<W: io::Write + ?Sized> IoWriter<'_, W> {
+    fn flush_buffer(&mut self) -> io::Result<()> {
+        let length = std::mem::take(&mut self.length);
+
+        self.inner.write_all(&self.buffer[..length])
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) -> io::Result<()> {
+        if self.length + bytes.len() > IO_BUFFER_SIZE {
+            self.flush_buffer()?;
+        }
+
+        if bytes.len() < IO_BUFFER_SIZE {
+            self.buffer[self.length..self.length + bytes.len()].copy_from_slice(bytes);
+            self.length += bytes.len();
+
+            Ok(())
+        } else {
+            self.inner.write_all(bytes)
+        }
+    }
+}
+
+impl<W: io::Write + ?Sized> fmt::Write for IoWriter<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.write_bytes(s.as_bytes()).map_err(|error| {
+            self.error = Some(error);
+
+            fmt::Error
+        })
+    }
+}
+
+/// Writes the output of `f` into `out`. The output is buffered, but `out` itself is not flushed.
+pub fn write_io(
+    out: &mut (impl io::Write + ?Sized),
+    f: impl FnOnce(&mut dyn DemangleWrite) -> fmt::Result,
+) -> io::Result<()> {
+    let mut writer = IoWriter {
+        inner: out,
+        buffer: [0; IO_BUFFER_SIZE],
+        length: 0,
+        error: None,
+    };
+
+    match f(&mut writer) {
+        Ok(()) => writer.flush_buffer(),
+        Err(fmt::Error) => Err(writer
+            .error
+            .unwrap_or_else(|| io::Error::other("the symbol can not be displayed"))),
+    }
+}
+
+/// Forwards output to an inner writer, failing once the output is longer than the remaining budget.
+struct BudgetedWriter<'w, W: ?Sized> {
+    inner: &'w mut W,
+    remaining: usize,
+    exceeded: bool,
+    write_failed: bool,
+}
+
+impl<W: fmt::Write + ?Sized> fmt::Write for BudgetedWriter<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if let Some(remaining) = self.remaining.checked_sub(s.len()) {
+            self.remaining = remaining;
+
+            self.inner.write_str(s).inspect_err(|_| self.write_failed = true)
+        } else {
+            self.exceeded = true;
+
+            Err(fmt::Error)
+        }
+    }
+}
+
+/// Writes the output of `f` into `out` if it is at most `budget` bytes. Writing stops as soon as the budget is
+/// exhausted, so the time spent is bounded by the budget rather than by the full length of the output.
+pub fn try_display<W: fmt::Write + ?Sized>(
+    out: &mut W,
+    budget: usize,
+    f: impl FnOnce(&mut dyn DemangleWrite) -> fmt::Result,
+) -> Result<(), DisplayError> {
+    let mut writer = BudgetedWriter {
+        inner: out,
+        remaining: budget,
+        exceeded: false,
+        write_failed: false,
+    };
+
+    match f(&mut writer) {
+        Ok(()) => Ok(()),
+        Err(fmt::Error) if writer.exceeded => Err(DisplayError::BudgetExceeded),
+        Err(fmt::Error) if writer.write_failed => Err(DisplayError::Write),
+        Err(fmt::Error) => Err(DisplayError::NotDisplayable),
+    }
+}
+
+/// Collects the output of `f` into a string of at most `budget` bytes.
+pub fn to_string_with_budget(
+    budget: usize,
+    f: impl FnOnce(&mut dyn DemangleWrite) -> fmt::Result,
+) -> Result<String, DisplayError> {
+    let mut buffer = String::new();
+
+    try_display(&mut buffer, budget, f).map(|()| buffer)
+}
+
+/// Counts the bytes of output without storing them, failing once the count is larger than the cap.
+struct CountingWriter {
+    length: usize,
+    cap: usize,
+    exceeded: bool,
+}
+
+impl fmt::Write for CountingWriter {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        match self.length.checked_add(s.len()) {
+            Some(length) if length <= self.cap => {
+                self.length = length;
+
+                Ok(())
+            }
+            _ => {
+                self.exceeded = true;
+
+                Err(fmt::Error)
+            }
+        }
+    }
+}
+
+/// Returns the length in bytes of the output of `f` if it is at most `cap`. Writing stops as soon as the cap is
+/// exceeded, and the output is never stored.
+pub fn display_len(cap: usize, f: impl FnOnce(&mut dyn DemangleWrite) -> fmt::Result) -> Result<usize, DisplayError> {
+    let mut writer = CountingWriter {
+        length: 0,
+        cap,
+        exceeded: false,
+    };
+
+    match f(&mut writer) {
+        Ok(()) => Ok(writer.length),
+        Err(fmt::Error) if writer.exceeded => Err(DisplayError::BudgetExceeded),
+        Err(fmt::Error) => Err(DisplayError::NotDisplayable),
+    }
+}
+
+/// Collects at most `limit` characters of output, failing when more characters are written so the rendering stops
+/// early.
+struct TruncatingWriter {
+    buffer: String,
+    remaining: usize,
+    truncated: bool,
+}
+
+impl fmt::Write for TruncatingWriter {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for c in s.chars() {
+            if self.remaining == 0 {
+                self.truncated = true;
+
+                return Err(fmt::Error);
+            }
+
+            self.buffer.push(c);
+            self.remaining -= 1;
+        }
+
+        Ok(())
+    }
+}
+
+/// Writes the output of `f` into `out`, truncated to at most `max_len` characters. If the output is truncated, its last
+/// character is replaced with `…`.
+pub fn write_truncated(
+    out: &mut dyn DemangleWrite,
+    max_len: usize,
+    f: impl FnOnce(&mut dyn DemangleWrite) -> fmt::Result,
+) -> fmt::Result {
+    let mut writer = TruncatingWriter {
+        buffer: String::new(),
+        remaining: max_len,
+        truncated: false,
+    };
+
+    match f(&mut writer) {
+        Ok(()) => {}
+        Err(fmt::Error) if writer.truncated => {
+            if writer.buffer.pop().is_some() {
+                writer.buffer.push('…');
+            }
+        }
+        Err(fmt::Error) => return Err(fmt::Error),
+    }
+
+    out.write_str(&writer.buffer)
+}
+
+/// Implements [`fmt::Display`] by writing the output of `f`. If the formatter has a precision, the output is truncated
+/// to that many characters, and if it has a width, the output is padded to that width.
+pub fn display_fn(f: impl Fn(&mut dyn DemangleWrite) -> fmt::Result) -> impl fmt::Display {
     struct Wrapper<F>(F);
 
-    impl<F: Fn(&mut fmt::Formatter) -> fmt::Result> fmt::Display for Wrapper<F> {
+    impl<F: Fn(&mut dyn DemangleWrite) -> fmt::Result> fmt::Display for Wrapper<F> {
         fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-            self.0(f)
+            let precision = f.precision();
+
+            padding::write_padded(f, |mut out| match precision {
+                None => self.0(&mut out),
+                Some(max_len) => write_truncated(&mut out, max_len, &self.0),
+            })
         }
     }
 
@@ -94,9 +460,10 @@ fn write_separated_list<T>(
     Ok(())
 }
 
-pub fn write_path(
+pub fn write_path<R: Renderer + ?Sized>(
     path: &Path,
     out: &mut dyn DemangleWrite,
+    renderer: &mut R,
     style: Style,
     bound_lifetime_depth: u64,
     in_value: bool,
@@ -104,59 +471,56 @@ pub fn write_path(
     match path {
         Path::CrateRoot(identifier) => {
             out.push_demangle_node(DemangleNodeType::CrateRoot);
-            match style {
-                Style::Short | Style::Normal => out.write_str(&identifier.name),
-                Style::Long => {
-                    write!(out, "{}[{:x}]", identifier.name, identifier.disambiguator)
-                }
-            }?;
-            out.pop_demangle_node();
-        },
-        Path::InherentImpl { type_, .. } => {
-            out.push_demangle_node(DemangleNodeType::Impl);
-            out.write_str("<")?;
-            write_type(type_, out, style, bound_lifetime_depth)?;
-            out.write_str(">")?;
+            renderer.render_crate_root(identifier, out, style)?;
             out.pop_demangle_node();
         }
-        Path::TraitImpl { type_, trait_, .. } | Path::TraitDefinition { type_, trait_ } => {
+        Path::InherentImpl { impl_path, .. } | Path::TraitImpl { impl_path, .. } if style == Style::Debuginfo => {
             out.push_demangle_node(DemangleNodeType::Impl);
-            out.write_str("<")?;
-            write_type(type_, out, style, bound_lifetime_depth)?;
-            out.write_str(" as ")?;
-            write_path(trait_, out, style, bound_lifetime_depth, false)?;
-            out.write_str(">")?;
+            write_path(&impl_path.path, out, renderer, style, bound_lifetime_depth, in_value)?;
+            write!(out, "::{{impl#{}}}", impl_path.disambiguator)?;
             out.pop_demangle_node();
         }
+        Path::TraitDefinition { trait_, .. } if style == Style::Debuginfo => {
+            write_path(trait_, out, renderer, style, bound_lifetime_depth, in_value)?;
+        }
+        Path::InherentImpl { impl_path, type_ } => {
+            write_impl(type_, None, Some(impl_path), out, renderer, style, bound_lifetime_depth)?;
+        }
+        Path::TraitImpl {
+            impl_path,
+            type_,
+            trait_,
+        } => {
+            write_impl(
+                type_,
+                Some(trait_),
+                Some(impl_path),
+                out,
+                renderer,
+                style,
+                bound_lifetime_depth,
+            )?;
+        }
+        Path::TraitDefinition { type_, trait_ } => {
+            write_impl(type_, Some(trait_), None, out, renderer, style, bound_lifetime_depth)?;
+        }
         Path::Nested {
             namespace,
             path,
             identifier,
         } => match namespace {
-            b'A'..=b'Z' => {
+            Namespace::Closure | Namespace::Shim | Namespace::Other('A'..='Z') => {
                 out.push_demangle_node(DemangleNodeType::Namespace);
-                write_path(path, out, style, bound_lifetime_depth, in_value)?;
+                write_path(path, out, renderer, style, bound_lifetime_depth, in_value)?;
 
                 out.write_str("::")?;
                 out.push_demangle_node(DemangleNodeType::Identifier);
-                out.write_str("{")?;
-
-                match namespace {
-                    b'C' => out.write_str("closure")?,
-                    b'S' => out.write_str("shim")?,
-                    _ => write!(out, "{}", char::from(*namespace))?,
-                }
-
-                if !identifier.name.is_empty() {
-                    write!(out, ":{}", identifier.name)?;
-                }
-
-                write!(out, "#{}}}", identifier.disambiguator)?;
+                renderer.render_special_identifier(*namespace, identifier, out, style)?;
                 out.pop_demangle_node();
                 out.pop_demangle_node();
             }
-            b'a'..=b'z' => {
-                if matches!(style, Style::Normal | Style::Long)
+            Namespace::Value | Namespace::Type | Namespace::Other('a'..='z') => {
+                if matches!(style, Style::Normal | Style::Long | Style::Debuginfo)
                     || matches!(
                         path.as_ref(),
                         Path::InherentImpl { .. }
@@ -166,50 +530,146 @@ pub fn write_path(
                     )
                 {
                     out.push_demangle_node(DemangleNodeType::Namespace);
-                    write_path(path, out, style, bound_lifetime_depth, in_value)?;
+                    write_path(path, out, renderer, style, bound_lifetime_depth, in_value)?;
 
                     if !identifier.name.is_empty() {
                         out.write_str("::")?;
                         out.push_demangle_node(DemangleNodeType::Identifier);
-                        write!(out, "{}", identifier.name)?;
+                        renderer.render_identifier(identifier, out, style)?;
                         out.pop_demangle_node();
                     }
                     out.pop_demangle_node();
                 } else if identifier.name.is_empty() {
                     out.push_demangle_node(DemangleNodeType::Namespace);
-                    write_path(path, out, style, bound_lifetime_depth, in_value)?;
+                    write_path(path, out, renderer, style, bound_lifetime_depth, in_value)?;
                     out.pop_demangle_node();
                 } else {
                     out.push_demangle_node(DemangleNodeType::Identifier);
-                    write!(out, "{}", identifier.name)?;
+                    renderer.render_identifier(identifier, out, style)?;
                     out.pop_demangle_node();
                 }
             }
-            _ => return Err(fmt::Error),
+            Namespace::Other(_) => return Err(fmt::Error),
         },
         Path::Generic { path, generic_args } => {
-            write_path(path, out, style, bound_lifetime_depth, in_value)?;
+            write_path(path, out, renderer, style, bound_lifetime_depth, in_value)?;
 
-            if in_value {
+            if in_value && style != Style::Debuginfo {
                 out.write_str("::")?;
             }
 
             out.push_demangle_node(DemangleNodeType::GenericArgs);
-            out.write_str("<")?;
-            write_separated_list(
-                generic_args,
-                out,
-                |generic_arg, out| write_generic_arg(generic_arg, out, style, bound_lifetime_depth),
-                ", ",
-            )?;
-            out.write_str(">")?;
+            renderer.render_generic_args(generic_args, out, style, bound_lifetime_depth)?;
             out.pop_demangle_node();
         }
     }
     Ok(())
 }
 
-fn write_lifetime(lifetime: u64, out: &mut dyn DemangleWrite, bound_lifetime_depth: u64) -> fmt::Result {
+/// Writes `<Type>` or `<Type as Trait>`, followed by the impl path if there is one.
+fn write_impl<R: Renderer + ?Sized>(
+    type_: &Type,
+    trait_: Option<&Path>,
+    impl_path: Option<&ImplPath>,
+    out: &mut dyn DemangleWrite,
+    renderer: &mut R,
+    style: Style,
+    bound_lifetime_depth: u64,
+) -> fmt::Result {
+    out.push_demangle_node(DemangleNodeType::Impl);
+    out.write_str("<")?;
+    write_type(type_, out, renderer, style, bound_lifetime_depth)?;
+
+    if let Some(trait_) = trait_ {
+        out.write_str(" as ")?;
+        write_path(trait_, out, renderer, style, bound_lifetime_depth, false)?;
+    }
+
+    out.write_str(">")?;
+
+    if let Some(impl_path) = impl_path {
+        renderer.render_impl_path(impl_path, out, style, bound_lifetime_depth)?;
+    }
+
+    out.pop_demangle_node();
+
+    Ok(())
+}
+
+/// An identifier name as it is displayed, with Punycode that is kept undecoded wrapped in `punycode{...}`. The braces
+/// can not appear in identifiers, so such names can not be confused with decoded ones.
+pub struct DisplayName<'a> {
+    pub name: &'a str,
+    pub is_undecoded_punycode: bool,
+}
+
+impl fmt::Display for DisplayName<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_undecoded_punycode {
+            write!(f, "punycode{{{}}}", self.name)
+        } else {
+            f.write_str(self.name)
+        }
+    }
+}
+
+pub fn write_crate_root(identifier: &Identifier, out: &mut dyn DemangleWrite, style: Style) -> fmt::Result {
+    match style {
+        Style::Long if identifier.disambiguator != 0 => {
+            write!(out, "{}[{:x}]", identifier.display(), identifier.disambiguator)
+        }
+        _ => write!(out, "{}", identifier.display()),
+    }
+}
+
+/// Writes an identifier in a special namespace the same way rustc-demangle does: the namespace name, followed by the
+/// identifier name if there is one, and the disambiguator as the index.
+pub fn write_special_identifier(
+    namespace: Namespace,
+    identifier: &Identifier,
+    out: &mut dyn DemangleWrite,
+) -> fmt::Result {
+    out.write_str("{")?;
+
+    match namespace {
+        Namespace::Closure => out.write_str("closure")?,
+        Namespace::Shim => out.write_str("shim")?,
+        _ => write!(out, "{}", namespace.as_char())?,
+    }
+
+    if !identifier.name.is_empty() {
+        write!(out, ":{}", identifier.display())?;
+    }
+
+    write!(out, "#{}}}", identifier.disambiguator)
+}
+
+pub fn write_generic_args<R: Renderer + ?Sized>(
+    generic_args: &[GenericArg],
+    out: &mut dyn DemangleWrite,
+    renderer: &mut R,
+    style: Style,
+    bound_lifetime_depth: u64,
+) -> fmt::Result {
+    // Debuginfo names do not contain lifetimes.
+    let is_shown =
+        |generic_arg: &&GenericArg| style != Style::Debuginfo || !matches!(generic_arg, GenericArg::Lifetime(_));
+
+    if style == Style::Debuginfo && !generic_args.iter().any(|generic_arg| is_shown(&generic_arg)) {
+        return Ok(());
+    }
+
+    out.write_str("<")?;
+    write_separated_list(
+        generic_args.iter().filter(is_shown),
+        out,
+        |generic_arg, out| write_generic_arg(generic_arg, out, renderer, style, bound_lifetime_depth),
+        ", ",
+    )?;
+    out.write_str(">")
+}
+
+pub fn write_lifetime(lifetime: u64, out: &mut dyn DemangleWrite, bound_lifetime_depth: u64) -> fmt::Result {
     out.write_str("'")?;
 
     if lifetime == 0 {
@@ -218,51 +678,66 @@ fn write_lifetime(lifetime: u64, out: &mut dyn DemangleWrite, bound_lifetime_dep
         if depth < 26 {
             write!(out, "{}", char::from(b'a' + u8::try_from(depth).unwrap()))
         } else {
-            write!(out, "_{}", depth)
+            write!(out, "_{depth}")
         }
     } else {
         Err(fmt::Error)
     }
 }
 
-pub fn write_generic_arg(
+pub fn write_generic_arg<R: Renderer + ?Sized>(
     generic_arg: &GenericArg,
     out: &mut dyn DemangleWrite,
+    renderer: &mut R,
     style: Style,
     bound_lifetime_depth: u64,
 ) -> fmt::Result {
     match generic_arg {
-        GenericArg::Lifetime(lifetime) => write_lifetime(*lifetime, out, bound_lifetime_depth),
-        GenericArg::Type(type_) => write_type(type_, out, style, bound_lifetime_depth),
-        GenericArg::Const(const_) => write_const(const_, out, style, bound_lifetime_depth, false),
+        GenericArg::Lifetime(lifetime) => renderer.render_lifetime(*lifetime, out, bound_lifetime_depth),
+        GenericArg::Type(type_) => write_type(type_, out, renderer, style, bound_lifetime_depth),
+        GenericArg::Const(const_) => renderer.render_const(const_, out, style, bound_lifetime_depth, false),
     }
 }
 
-fn write_binder(bound_lifetimes: u64, out: &mut dyn DemangleWrite, bound_lifetime_depth: u64) -> fmt::Result {
+pub fn write_binder<R: Renderer + ?Sized>(
+    bound_lifetimes: u64,
+    out: &mut dyn DemangleWrite,
+    renderer: &mut R,
+    bound_lifetime_depth: u64,
+) -> fmt::Result {
+    // Binders can only bind more lifetimes in total than `u64` counts in malformed symbols, which are not displayable.
+    let bound_lifetime_depth = bound_lifetime_depth.checked_add(bound_lifetimes).ok_or(fmt::Error)?;
+
     out.write_str("for<")?;
     write_separated_list(
         (1..=bound_lifetimes).rev(),
         out,
-        |i, out| write_lifetime(i, out, bound_lifetime_depth + bound_lifetimes),
+        |i, out| renderer.render_lifetime(i, out, bound_lifetime_depth),
         ", ",
     )?;
     out.write_str(">")
 }
 
-pub fn write_type(type_: &Type, out: &mut dyn DemangleWrite, style: Style, bound_lifetime_depth: u64) -> fmt::Result {
+pub fn write_type<R: Renderer + ?Sized>(
+    type_: &Type,
+    out: &mut dyn DemangleWrite,
+    renderer: &mut R,
+    style: Style,
+    bound_lifetime_depth: u64,
+) -> fmt::Result {
     match type_ {
-        Type::Basic(basic_type) => write_basic_type(*basic_type, out),
-        Type::Named(path) => write_path(path, out, style, bound_lifetime_depth, false),
+        Type::Basic(basic_type) => renderer.render_basic_type(*basic_type, out, style),
+        Type::Named(path) => write_path(path, out, renderer, style, bound_lifetime_depth, false),
         Type::Array(type_, length) => {
             out.write_str("[")?;
-            write_type(type_, out, style, bound_lifetime_depth)?;
+            write_type(type_, out, renderer, style, bound_lifetime_depth)?;
             out.write_str("; ")?;
-            write_const(length, out, style, bound_lifetime_depth, true)?;
+            renderer.render_const(length, out, style, bound_lifetime_depth, true)?;
             out.write_str("]")
         }
         Type::Slice(type_) => {
             out.write_str("[")?;
-            write_type(type_, out, style, bound_lifetime_depth)?;
+            write_type(type_, out, renderer, style, bound_lifetime_depth)?;
             out.write_str("]")
         }
         Type::Tuple(tuple_types) => {
@@ -270,7 +745,7 @@ pub fn write_type(type_: &Type, out: &mut dyn DemangleWrite, style: Style, bound
             write_separated_list(
                 tuple_types,
                 out,
-                |type_, out| write_type(type_, out, style, bound_lifetime_depth),
+                |type_, out| write_type(type_, out, renderer, style, bound_lifetime_depth),
                 ", ",
             )?;
 
@@ -283,84 +758,83 @@ pub fn write_type(type_: &Type, out: &mut dyn DemangleWrite, style: Style, bound
         Type::Ref { lifetime, type_ } => {
             out.write_str("&")?;
 
-            if *lifetime != 0 {
-                write_lifetime(*lifetime, out, bound_lifetime_depth)?;
+            if *lifetime != 0 && style != Style::Debuginfo {
+                renderer.render_lifetime(*lifetime, out, bound_lifetime_depth)?;
                 out.write_str(" ")?;
             }
 
-            write_type(type_, out, style, bound_lifetime_depth)
+            write_type(type_, out, renderer, style, bound_lifetime_depth)
         }
         Type::RefMut { lifetime, type_ } => {
             out.write_str("&")?;
 
-            if *lifetime != 0 {
-                write_lifetime(*lifetime, out, bound_lifetime_depth)?;
+            if *lifetime != 0 && style != Style::Debuginfo {
+                renderer.render_lifetime(*lifetime, out, bound_lifetime_depth)?;
                 out.write_str(" ")?;
             }
 
             out.write_str("mut ")?;
-            write_type(type_, out, style, bound_lifetime_depth)
+            write_type(type_, out, renderer, style, bound_lifetime_depth)
         }
         Type::PtrConst(type_) => {
             out.write_str("*const ")?;
-            write_type(type_, out, style, bound_lifetime_depth)
+            write_type(type_, out, renderer, style, bound_lifetime_depth)
         }
         Type::PtrMut(type_) => {
             out.write_str("*mut ")?;
-            write_type(type_, out, style, bound_lifetime_depth)
+            write_type(type_, out, renderer, style, bound_lifetime_depth)
         }
-        Type::Fn(fn_sig) => write_fn_sig(fn_sig, out, style, bound_lifetime_depth),
+        Type::Fn(fn_sig) => renderer.render_fn_sig(fn_sig, out, style, bound_lifetime_depth),
         Type::DynTrait { dyn_bounds, lifetime } => {
-            write_dyn_bounds(dyn_bounds, out, style, bound_lifetime_depth)?;
+            write_dyn_bounds(dyn_bounds, out, renderer, style, bound_lifetime_depth)?;
 
-            if *lifetime == 0 {
+            if *lifetime == 0 || style == Style::Debuginfo {
                 Ok(())
             } else {
                 out.write_str(" + ")?;
-                write_lifetime(*lifetime, out, bound_lifetime_depth)
+                renderer.render_lifetime(*lifetime, out, bound_lifetime_depth)
             }
         }
     }
 }
 
 pub fn write_basic_type(basic_type: BasicType, out: &mut dyn DemangleWrite) -> fmt::Result {
-    out.write_str(match basic_type {
-        BasicType::I8 => "i8",
-        BasicType::Bool => "bool",
-        BasicType::Char => "char",
-        BasicType::F64 => "f64",
-        BasicType::Str => "str",
-        BasicType::F32 => "f32",
-        BasicType::U8 => "u8",
-        BasicType::Isize => "isize",
-        BasicType::Usize => "usize",
-        BasicType::I32 => "i32",
-        BasicType::U32 => "u32",
-        BasicType::I128 => "i128",
-        BasicType::U128 => "u128",
-        BasicType::I16 => "i16",
-        BasicType::U16 => "u16",
-        BasicType::Unit => "()",
-        BasicType::Ellipsis => "...",
-        BasicType::I64 => "i64",
-        BasicType::U64 => "u64",
-        BasicType::Never => "!",
-        BasicType::Placeholder => "_",
-    })
-}
-
-pub fn write_fn_sig(
+    out.write_str(basic_type.as_str())
+}
+
+pub fn write_fn_sig<R: Renderer + ?Sized>(
+    fn_sig: &FnSig,
+    out: &mut dyn DemangleWrite,
+    renderer: &mut R,
+    style: Style,
+    bound_lifetime_depth: u64,
+) -> fmt::Result {
+    write_fn_sig_with_return_type(
+        fn_sig,
+        out,
+        renderer,
+        style,
+        bound_lifetime_depth,
+        FnReturnType::NonUnit,
+    )
+}
+
+pub fn write_fn_sig_with_return_type<R: Renderer + ?Sized>(
     fn_sig: &FnSig,
     out: &mut dyn DemangleWrite,
+    renderer: &mut R,
     style: Style,
     bound_lifetime_depth: u64,
+    return_type: FnReturnType,
 ) -> fmt::Result {
-    if fn_sig.bound_lifetimes != 0 {
-        write_binder(fn_sig.bound_lifetimes, out, bound_lifetime_depth)?;
+    if fn_sig.bound_lifetimes != 0 && style != Style::Debuginfo {
+        write_binder(fn_sig.bound_lifetimes, out, renderer, bound_lifetime_depth)?;
         out.write_str(" ")?;
     }
 
-    let bound_lifetime_depth = bound_lifetime_depth + fn_sig.bound_lifetimes;
+    let bound_lifetime_depth = bound_lifetime_depth
+        .checked_add(fn_sig.bound_lifetimes)
+        .ok_or(fmt::Error)?;
 
     if fn_sig.is_unsafe {
         out.write_str("unsafe ")?;
@@ -376,20 +850,26 @@ pub fn write_fn_sig(
     write_separated_list(
         &fn_sig.argument_types,
         out,
-        |type_, out| write_type(type_, out, style, bound_lifetime_depth),
+        |type_, out| write_type(type_, out, renderer, style, bound_lifetime_depth),
         ", ",
     )?;
     out.write_str(")")?;
 
-    if let Type::Basic(BasicType::Unit) = fn_sig.return_type.as_ref() {
-        Ok(())
-    } else {
+    let is_shown = match return_type {
+        FnReturnType::Hidden => false,
+        FnReturnType::NonUnit => !matches!(fn_sig.return_type.as_ref(), Type::Basic(BasicType::Unit)),
+        FnReturnType::Always => true,
+    };
+
+    if is_shown {
         out.write_str(" -> ")?;
-        write_type(&fn_sig.return_type, out, style, bound_lifetime_depth)
+        write_type(&fn_sig.return_type, out, renderer, style, bound_lifetime_depth)
+    } else {
+        Ok(())
     }
 }
 
-fn write_abi(abi: &Abi, out: &mut dyn DemangleWrite) -> fmt::Result {
+pub fn write_abi(abi: &Abi, out: &mut dyn DemangleWrite) -> fmt::Result {
     out.write_str("\"")?;
 
     match abi {
@@ -400,7 +880,7 @@ fn write_abi(abi: &Abi, out: &mut dyn DemangleWrite) -> fmt::Result {
             out.write_str(iter.next().unwrap())?;
 
             for item in iter {
-                write!(out, "-{}", item)?;
+                write!(out, "-{item}")?;
             }
         }
     }
@@ -408,39 +888,43 @@ fn write_abi(abi: &Abi, out: &mut dyn DemangleWrite) -> fmt::Result {
     out.write_str("\"")
 }
 
-fn write_dyn_bounds(
+fn write_dyn_bounds<R: Renderer + ?Sized>(
     dyn_bounds: &DynBounds,
     out: &mut dyn DemangleWrite,
+    renderer: &mut R,
     style: Style,
     bound_lifetime_depth: u64,
 ) -> fmt::Result {
     out.write_str("dyn ")?;
 
-    if dyn_bounds.bound_lifetimes != 0 {
-        write_binder(dyn_bounds.bound_lifetimes, out, bound_lifetime_depth)?;
+    if dyn_bounds.bound_lifetimes != 0 && style != Style::Debuginfo {
+        write_binder(dyn_bounds.bound_lifetimes, out, renderer, bound_lifetime_depth)?;
         out.write_str(" ")?;
     }
 
-    let bound_lifetime_depth = bound_lifetime_depth + dyn_bounds.bound_lifetimes;
+    let bound_lifetime_depth = bound_lifetime_depth
+        .checked_add(dyn_bounds.bound_lifetimes)
+        .ok_or(fmt::Error)?;
 
     write_separated_list(
         dyn_bounds.dyn_traits.iter(),
         out,
-        |dyn_trait, out| write_dyn_trait(dyn_trait, out, style, bound_lifetime_depth),
+        |dyn_trait, out| write_dyn_trait(dyn_trait, out, renderer, style, bound_lifetime_depth),
         " + ",
     )
 }
 
-fn write_dyn_trait(
+fn write_dyn_trait<R: Renderer + ?Sized>(
     dyn_trait: &DynTrait,
     out: &mut dyn DemangleWrite,
+    renderer: &mut R,
     style: Style,
     bound_lifetime_depth: u64,
 ) -> fmt::Result {
     if dyn_trait.dyn_trait_assoc_bindings.is_empty() {
-        write_path(&dyn_trait.path, out, style, bound_lifetime_depth, false)
+        write_path(&dyn_trait.path, out, renderer, style, bound_lifetime_depth, false)
     } else if let Path::Generic { path, generic_args } = dyn_trait.path.as_ref() {
-        write_path(path, out, style, bound_lifetime_depth, false)?;
+        write_path(path, out, renderer, style, bound_lifetime_depth, false)?;
         write!(out, "<")?;
         write_separated_list(
             generic_args
@@ -449,22 +933,22 @@ fn write_dyn_trait(
                 .chain(dyn_trait.dyn_trait_assoc_bindings.iter().map(Err)),
             out,
             |value, out| match value {
-                Ok(generic_arg) => write_generic_arg(generic_arg, out, style, bound_lifetime_depth),
+                Ok(generic_arg) => write_generic_arg(generic_arg, out, renderer, style, bound_lifetime_depth),
                 Err(dyn_trait_assoc_binding) => {
-                    write_dyn_trait_assoc_binding(dyn_trait_assoc_binding, out, style, bound_lifetime_depth)
+                    write_dyn_trait_assoc_binding(dyn_trait_assoc_binding, out, renderer, style, bound_lifetime_depth)
                 }
             },
             ", ",
         )?;
         write!(out, ">")
     } else {
-        write_path(&dyn_trait.path, out, style, bound_lifetime_depth, false)?;
+        write_path(&dyn_trait.path, out, renderer, style, bound_lifetime_depth, false)?;
         write!(out, "<")?;
         write_separated_list(
             dyn_trait.dyn_trait_assoc_bindings.iter(),
             out,
             |dyn_trait_assoc_binding, out| {
-                write_dyn_trait_assoc_binding(dyn_trait_assoc_binding, out, style, bound_lifetime_depth)
+                write_dyn_trait_assoc_binding(dyn_trait_assoc_binding, out, renderer, style, bound_lifetime_depth)
             },
             ", ",
         )?;
@@ -472,18 +956,25 @@ fn write_dyn_trait(
     }
 }
 
-fn write_dyn_trait_assoc_binding(
+fn write_dyn_trait_assoc_binding<R: Renderer + ?Sized>(
     dyn_trait_assoc_binding: &DynTraitAssocBinding,
     out: &mut dyn DemangleWrite,
+    renderer: &mut R,
     style: Style,
     bound_lifetime_depth: u64,
 ) -> fmt::Result {
-    write!(out, "{} = ", dyn_trait_assoc_binding.name)?;
-    write_type(&dyn_trait_assoc_binding.type_, out, style, bound_lifetime_depth)
+    write!(out, "{} = ", dyn_trait_assoc_binding.display_name())?;
+    write_type(
+        &dyn_trait_assoc_binding.type_,
+        out,
+        renderer,
+        style,
+        bound_lifetime_depth,
+    )
 }
 
-fn write_integer<T: fmt::Display>(value: T, out: &mut dyn DemangleWrite, style: Style) -> fmt::Result {
-    write!(out, "{}", value)?;
+pub fn write_integer<T: fmt::Display>(value: T, out: &mut dyn DemangleWrite, style: Style) -> fmt::Result {
+    write!(out, "{value}")?;
 
     if matches!(style, Style::Long) {
         out.write_str(any::type_name::<T>())
@@ -492,9 +983,33 @@ fn write_integer<T: fmt::Display>(value: T, out: &mut dyn DemangleWrite, style:
     }
 }
 
-pub fn write_const(
+/// Writes a floating point constant. `Debug` is used because it keeps the decimal point of integral values, like `1.0`.
+#[allow(clippy::use_debug)]
+fn write_float<T: fmt::Debug>(value: T, out: &mut dyn DemangleWrite, style: Style) -> fmt::Result {
+    write!(out, "{value:?}")?;
+
+    if matches!(style, Style::Long) {
+        out.write_str(any::type_name::<T>())
+    } else {
+        Ok(())
+    }
+}
+
+/// Writes a string constant as an escaped string literal. Bytes that are not valid UTF-8 are written as a byte string
+/// literal instead.
+#[allow(clippy::use_debug)] // `Debug` is used for escaping string literals.
+pub fn write_const_str(value: &ConstStr, out: &mut dyn DemangleWrite) -> fmt::Result {
+    match value.to_str() {
+        Some(value) => write!(out, "{value:?}"),
+        None => write!(out, "b\"{}\"", value.bytes.escape_ascii()),
+    }
+}
+
+#[allow(clippy::use_debug)] // `Debug` is used for escaping char and string literals.
+pub fn write_const<R: Renderer + ?Sized>(
     const_: &Const,
     out: &mut dyn DemangleWrite,
+    renderer: &mut R,
     style: Style,
     bound_lifetime_depth: u64,
     in_value: bool,
@@ -512,24 +1027,22 @@ pub fn write_const(
         Const::U16(value) => write_integer(value, out, style),
         Const::I64(value) => write_integer(value, out, style),
         Const::U64(value) => write_integer(value, out, style),
-        Const::Bool(value) => write!(out, "{}", value),
-        Const::Char(value) => write!(out, "{:?}", value),
+        Const::Bool(value) => write!(out, "{value}"),
+        Const::Char(value) => write!(out, "{value:?}"),
         Const::Str(ref value) => {
-            if in_value {
-                write!(out, "*{:?}", value)
-            } else {
-                write!(out, "{{*{:?}}}", value)
-            }
+            out.write_str(if in_value { "*" } else { "{*" })?;
+            write_const_str(value, out)?;
+            out.write_str(if in_value { "" } else { "}" })
         }
         Const::Ref(ref value) => {
             if let Const::Str(value) = value.as_ref() {
-                write!(out, "{:?}", value)
+                write_const_str(value, out)
             } else if in_value {
                 out.write_str("&")?;
-                write_const(value, out, style, bound_lifetime_depth, true)
+                renderer.render_const(value, out, style, bound_lifetime_depth, true)
             } else {
                 out.write_str("{&")?;
-                write_const(value, out, style, bound_lifetime_depth, true)?;
+                renderer.render_const(value, out, style, bound_lifetime_depth, true)?;
                 out.write_str("}")
             }
         }
@@ -539,7 +1052,7 @@ pub fn write_const(
             } else {
                 out.write_str("{&mut ")?;
             }
-            write_const(value, out, style, bound_lifetime_depth, true)?;
+            renderer.render_const(value, out, style, bound_lifetime_depth, true)?;
             if !in_value {
                 out.write_str("}")?;
             }
@@ -554,7 +1067,7 @@ pub fn write_const(
             write_separated_list(
                 items,
                 out,
-                |item, out| write_const(item, out, style, bound_lifetime_depth, true),
+                |item, out| renderer.render_const(item, out, style, bound_lifetime_depth, true),
                 ", ",
             )?;
 
@@ -574,7 +1087,7 @@ pub fn write_const(
             write_separated_list(
                 items,
                 out,
-                |item, out| write_const(item, out, style, bound_lifetime_depth, true),
+                |item, out| renderer.render_const(item, out, style, bound_lifetime_depth, true),
                 ", ",
             )?;
 
@@ -588,8 +1101,8 @@ pub fn write_const(
             if !in_value {
                 out.write_str("{")?;
             }
-            write_path(path, out, style, bound_lifetime_depth, true)?;
-            write_const_fields(fields, out, style, bound_lifetime_depth)?;
+            write_path(path, out, renderer, style, bound_lifetime_depth, true)?;
+            write_const_fields(fields, out, renderer, style, bound_lifetime_depth)?;
 
             if !in_value {
                 out.write_str("}")?;
@@ -597,12 +1110,15 @@ pub fn write_const(
             Ok(())
         }
         Const::Placeholder => out.write_str("_"),
+        Const::F32(bits) => write_float(f32::from_bits(bits), out, style),
+        Const::F64(bits) => write_float(f64::from_bits(bits), out, style),
     }
 }
 
-fn write_const_fields(
+fn write_const_fields<R: Renderer + ?Sized>(
     fields: &ConstFields,
     out: &mut dyn DemangleWrite,
+    renderer: &mut R,
     style: Style,
     bound_lifetime_depth: u64,
 ) -> fmt::Result {
@@ -613,7 +1129,7 @@ fn write_const_fields(
             write_separated_list(
                 fields,
                 out,
-                |field, out| write_const(field, out, style, bound_lifetime_depth, true),
+                |field, out| renderer.render_const(field, out, style, bound_lifetime_depth, true),
                 ", ",
             )?;
             out.write_str(")")
@@ -628,9 +1144,9 @@ fn write_const_fields(
                     fields.iter(),
                     out,
                     |(name, value), out| {
-                        write!(out, "{}", name)?;
+                        renderer.render_identifier(name, out, style)?;
                         out.write_str(": ")?;
-                        write_const(value, out, style, bound_lifetime_depth, true)
+                        renderer.render_const(value, out, style, bound_lifetime_depth, true)
                     },
                     ", ",
                 )?;
@@ -640,11 +1156,80 @@ fn write_const_fields(
     }
 }
 
+/// Returns the path that encloses the final identifier of `path`, if there is one.
+fn parent_path<'p, 'a>(path: &'p Path<'a>) -> Option<&'p Path<'a>> {
+    match path {
+        Path::Nested {
+            namespace,
+            path,
+            identifier,
+        } if !namespace.is_special() && !identifier.name.is_empty() => Some(path),
+        Path::Generic { path, .. } => parent_path(path),
+        _ => None,
+    }
+}
+
+pub fn write_grouped_symbols(symbols: &[Symbol], out: &mut dyn DemangleWrite, style: Style) -> fmt::Result {
+    let mut groups = Vec::<(Option<String>, Vec<String>)>::new();
+    let mut group_indices = HashMap::<String, usize>::new();
+
+    for symbol in symbols {
+        let mut full = String::new();
+
+        write_path(&symbol.path, &mut full, &mut DefaultRenderer, style, 0, true)?;
+
+        let prefix = parent_path(&symbol.path)
+            .map(|parent| {
+                let mut prefix = String::new();
+
+                write_path(parent, &mut prefix, &mut DefaultRenderer, style, 0, true).map(|()| prefix)
+            })
+            .transpose()?
+            .filter(|prefix| {
+                full.strip_prefix(prefix.as_str())
+                    .is_some_and(|rest| rest.starts_with("::"))
+            });
+
+        if let Some(prefix) = prefix {
+            let leaf = full[prefix.len() + 2..].to_string();
+
+            if let Some(&index) = group_indices.get(&prefix) {
+                groups[index].1.push(leaf);
+            } else {
+                group_indices.insert(prefix.clone(), groups.len());
+                groups.push((Some(prefix), vec![leaf]));
+            }
+        } else {
+            groups.push((None, vec![full]));
+        }
+    }
+
+    write_separated_list(
+        &groups,
+        out,
+        |(prefix, leaves), out| {
+            if let Some(prefix) = prefix {
+                out.write_str(prefix)?;
+                out.write_str("::")?;
+            }
+
+            if let [leaf] = leaves.as_slice() {
+                out.write_str(leaf)
+            } else {
+                out.write_str("{")?;
+                write_separated_list(leaves, out, |leaf, out| out.write_str(leaf), ", ")?;
+                out.write_str("}")
+            }
+        },
+        "\n",
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::Style;
-    use crate::rust_v0::Symbol;
-    use std::fmt::Write;
+    use crate::rust_v0::{Identifier, Symbol};
+    use std::fmt::{self, Write};
 
     #[test]
     fn test_display_path() {
@@ -704,13 +1289,50 @@ mod tests {
         check(2, 4, "'c");
     }
 
+    #[test]
+    fn test_display_higher_ranked_lifetimes() {
+        let test_cases = [
+            (
+                "_RINvC3foo3barFG0_RL1_hRL0_hEuE",
+                "foo::bar::<for<'a, 'b> fn(&'a u8, &'b u8)>",
+            ),
+            (
+                "_RINvC3foo3barFG_RL0_hFG_RL0_RL1_hEuEuE",
+                "foo::bar::<for<'a> fn(&'a u8, for<'b> fn(&'b &'a u8))>",
+            ),
+            (
+                "_RINvC3foo3barDG_INtC3foo3BazRL0_hEEL_E",
+                "foo::bar::<dyn for<'a> foo::Baz<&'a u8>>",
+            ),
+            (
+                "_RINvC3foo3barDG0_INtC3foo3BazRL0_hEp3OutRL1_hEL_E",
+                "foo::bar::<dyn for<'a, 'b> foo::Baz<&'b u8, Out = &'a u8>>",
+            ),
+            ("_RINvC3foo3barFG_RL_hRL0_hEuE", "foo::bar::<for<'a> fn(&u8, &'a u8)>"),
+        ];
+
+        for (name, expected) in test_cases {
+            let symbol = Symbol::parse_from_str(name).unwrap().0;
+
+            assert_eq!(symbol.display(Style::Normal).to_string(), expected);
+            assert_eq!(expected, format!("{:#}", rustc_demangle::demangle(name)));
+        }
+
+        assert_eq!(crate::rust_v0::display_lifetime(2, 3).unwrap().to_string(), "'b");
+        assert_eq!(crate::rust_v0::display_lifetime(0, 0).unwrap().to_string(), "'_");
+        assert_eq!(crate::rust_v0::display_lifetime(1, 27).unwrap().to_string(), "'_26");
+        assert!(crate::rust_v0::display_lifetime(1, 0).is_none());
+    }
+
     #[test]
     fn test_display_binder() {
         #[track_caller]
         fn check(bound_lifetimes: u64, bound_lifetime_depth: u64, expected: &str) {
             assert_eq!(
-                super::display_fn(move |f| { super::write_binder(bound_lifetimes, f, bound_lifetime_depth) })
-                    .to_string(),
+                super::display_fn(move |f| {
+                    super::write_binder(bound_lifetimes, f, &mut super::DefaultRenderer, bound_lifetime_depth)
+                })
+                .to_string(),
                 expected
             );
         }
@@ -727,4 +1349,276 @@ mod tests {
         check(2, 1, "for<'b, 'c>");
         check(2, 2, "for<'c, 'd>");
     }
+
+    #[test]
+    fn test_display_grouped_symbols() {
+        let symbols = [
+            "_RNvNtCs6GSVXm7oiwY_5regex4utf811decode_utf8",
+            "_RNvCs6GSVXm7oiwY_5regex3foo",
+            "_RNvNtCs6GSVXm7oiwY_5regex4utf816decode_last_utf8",
+            "_RINvNtC3std3mem8align_ofjE",
+            "_RINvNtC3std3mem7size_ofjE",
+            "_RC3foo",
+        ]
+        .map(|symbol| Symbol::parse_from_str(symbol).unwrap().0);
+
+        let display = |style| super::display_fn(|f| super::write_grouped_symbols(&symbols, f, style)).to_string();
+
+        assert_eq!(
+            display(Style::Normal),
+            "regex::utf8::{decode_utf8, decode_last_utf8}\n\
+             regex::foo\n\
+             std::mem::{align_of::<usize>, size_of::<usize>}\n\
+             foo"
+        );
+
+        assert_eq!(
+            display(Style::Long),
+            "regex[4df147058689a776]::utf8::{decode_utf8, decode_last_utf8}\n\
+             regex[4df147058689a776]::foo\n\
+             std::mem::{align_of::<usize>, size_of::<usize>}\n\
+             foo"
+        );
+
+        assert_eq!(
+            display(Style::Short),
+            "decode_utf8\nfoo\ndecode_last_utf8\nalign_of::<usize>\nsize_of::<usize>\nfoo"
+        );
+
+        // The symbol parses, but refers to a lifetime that is not bound.
+        let symbols = [Symbol::parse_from_str("_RMC0RLY_z").unwrap().0];
+        let mut buffer = String::new();
+
+        assert!(write!(
+            buffer,
+            "{}",
+            super::display_fn(|f| super::write_grouped_symbols(&symbols, f, Style::Normal))
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_renderer() {
+        struct Linkify;
+
+        impl super::Renderer for Linkify {
+            fn render_crate_root(
+                &mut self,
+                identifier: &Identifier,
+                out: &mut dyn super::DemangleWrite,
+                _: Style,
+            ) -> fmt::Result {
+                write!(out, "[{0}](https://docs.rs/{0})", identifier.name)
+            }
+
+            fn render_lifetime(&mut self, _: u64, out: &mut dyn super::DemangleWrite, _: u64) -> fmt::Result {
+                out.write_str("'static")
+            }
+        }
+
+        let symbol = Symbol::parse_from_str(
+            "_RINvCsd5QWgxammnl_7example3fooNcNtINtNtCs454gRYH7d6L_4core6result6ResultL_RhlE2Ok0EB2_",
+        )
+        .unwrap()
+        .0;
+
+        let mut buffer = String::new();
+
+        symbol.render(&mut buffer, &mut Linkify, Style::Normal).unwrap();
+
+        assert_eq!(
+            buffer,
+            "[example](https://docs.rs/example)::foo::<[core](https://docs.rs/core)::result::Result<'static, &u8, i32>\
+             ::Ok>"
+        );
+
+        buffer.clear();
+
+        symbol
+            .render(&mut buffer, &mut super::DefaultRenderer, Style::Normal)
+            .unwrap();
+
+        assert_eq!(buffer, symbol.display(Style::Normal).to_string());
+    }
+
+    #[test]
+    fn test_display_debuginfo() {
+        #[track_caller]
+        fn check(symbol: &str, normal: &str, debuginfo: &str) {
+            let symbol = Symbol::parse_from_str(symbol).unwrap().0;
+
+            assert_eq!(symbol.display(Style::Normal).to_string(), normal);
+            assert_eq!(symbol.display(Style::Debuginfo).to_string(), debuginfo);
+        }
+
+        check(
+            "_RNvMs0_NtCs1234_3foo3barNtNtC3foo3bar3Baz3new",
+            "<foo::bar::Baz>::new",
+            "foo::bar::{impl#2}::new",
+        );
+
+        check(
+            "_RNvXs_NtC3foo3barNtNtC3foo3bar3BazNtNtC4core5clone5Clone5clone",
+            "<foo::bar::Baz as core::clone::Clone>::clone",
+            "foo::bar::{impl#1}::clone",
+        );
+
+        check(
+            "_RNvYNtC3foo3BazNtNtC4core5clone5Clone10clone_from",
+            "<foo::Baz as core::clone::Clone>::clone_from",
+            "core::clone::Clone::clone_from",
+        );
+
+        check(
+            "_RINvCs1234_3foo3barL_RL_hjFG_RL0_hEuE",
+            "foo::bar::<'_, &u8, usize, for<'a> fn(&'a u8)>",
+            "foo::bar<&u8, usize, fn(&u8)>",
+        );
+
+        check("_RINvC3foo3barL_E", "foo::bar::<'_>", "foo::bar");
+        check("_RNCNvC3foo3bar0", "foo::bar::{closure#0}", "foo::bar::{closure#0}");
+    }
+
+    #[test]
+    fn test_write_display() {
+        struct CountingWriter {
+            data: Vec<u8>,
+            writes: usize,
+        }
+
+        impl std::io::Write for CountingWriter {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.writes += 1;
+                self.data.extend_from_slice(buf);
+
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let symbol = Symbol::parse_from_str(
+            "_RINvCsd5QWgxammnl_7example3fooNcNtINtNtCs454gRYH7d6L_4core6result6ResultllE2Ok0EB2_",
+        )
+        .unwrap()
+        .0;
+
+        let mut writer = CountingWriter {
+            data: Vec::new(),
+            writes: 0,
+        };
+
+        symbol.write_display(&mut writer, Style::Normal).unwrap();
+
+        assert_eq!(
+            String::from_utf8(writer.data).unwrap(),
+            "example::foo::<core::result::Result<i32, i32>::Ok>"
+        );
+
+        assert_eq!(writer.writes, 1);
+
+        let long_name = "a".repeat(super::IO_BUFFER_SIZE * 2);
+        let mangled_name = format!("_RNvC3foo{}{long_name}", long_name.len());
+        let symbol = Symbol::parse_from_str(&mangled_name).unwrap().0;
+        let mut buffer = Vec::new();
+
+        symbol.write_display(&mut buffer, Style::Normal).unwrap();
+
+        assert_eq!(buffer, format!("foo::{long_name}").into_bytes());
+
+        let symbol = Symbol::parse_from_str("_RINvC3foo3barRL0_hE").unwrap().0;
+
+        assert!(symbol.write_display(&mut Vec::new(), Style::Normal).is_err());
+
+        let mut full = [0; 4];
+
+        assert_eq!(
+            Symbol::parse_from_str("_RNvC3foo3bar")
+                .unwrap()
+                .0
+                .write_display(&mut full.as_mut_slice(), Style::Normal)
+                .unwrap_err()
+                .kind(),
+            std::io::ErrorKind::WriteZero
+        );
+    }
+
+    #[test]
+    fn test_write_to() {
+        let symbol = Symbol::parse_from_str("_RNvNtC3foo3bar3baz").unwrap().0;
+        let mut buffer = String::from("symbol: ");
+
+        symbol.write_to(&mut buffer, Style::Normal).unwrap();
+
+        assert_eq!(buffer, "symbol: foo::bar::baz");
+
+        let out: &mut dyn Write = &mut buffer;
+
+        symbol.write_to(out, Style::Short).unwrap();
+
+        assert_eq!(buffer, "symbol: foo::bar::bazbaz");
+
+        let symbol = Symbol::parse_from_str("_RINvC3foo3barRL0_hE").unwrap().0;
+
+        assert!(symbol.write_to(&mut String::new(), Style::Normal).is_err());
+    }
+
+    #[test]
+    fn test_display_truncated() {
+        let symbol = Symbol::parse_from_str("_RNvNtC3foo3bar3baz").unwrap().0;
+
+        assert_eq!(symbol.display_truncated(Style::Normal, 13).to_string(), "foo::bar::baz");
+        assert_eq!(symbol.display_truncated(Style::Normal, 12).to_string(), "foo::bar::b…");
+        assert_eq!(symbol.display_truncated(Style::Normal, 1).to_string(), "…");
+        assert_eq!(symbol.display_truncated(Style::Normal, 0).to_string(), "");
+        assert_eq!(format!("{:.8}", symbol.display(Style::Normal)), "foo::ba…");
+        assert_eq!(format!("{:.100}", symbol.display(Style::Short)), "baz");
+        assert_eq!(format!("{symbol:#.5}"), "foo:…");
+
+        let symbol = Symbol::parse_from_str("_RINvC3foo3barRL1_hE").unwrap().0;
+
+        assert_eq!(symbol.display_truncated(Style::Normal, 5).to_string(), "foo:…");
+        assert!(fmt::write(
+            &mut String::new(),
+            format_args!("{:.100}", symbol.display(Style::Normal))
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_display_padded() {
+        let symbol = Symbol::parse_from_str("_RNvNtC3foo3bar3baz").unwrap().0;
+
+        assert_eq!(format!("[{:16}]", symbol.display(Style::Normal)), "[foo::bar::baz   ]");
+        assert_eq!(format!("[{:>16}]", symbol.display(Style::Short)), "[             baz]");
+        assert_eq!(format!("[{:-^9}]", symbol.display(Style::Short)), "[---baz---]");
+        assert_eq!(format!("[{symbol:>#10.5}]"), "[     foo:…]");
+        assert_eq!(format!("[{:4}]", symbol.display(Style::Normal)), "[foo::bar::baz]");
+    }
+
+    #[test]
+    fn test_display_special_namespaces() {
+        let test_cases = [
+            ("_RNCNvC3foo3bar0", "foo::bar::{closure#0}"),
+            ("_RNCNvC3foo3bars_0", "foo::bar::{closure#1}"),
+            ("_RNCNvC3foo3bar4name", "foo::bar::{closure:name#0}"),
+            ("_RNSNvC3foo3bar6vtable", "foo::bar::{shim:vtable#0}"),
+            ("_RNSNvC3foo3bars0_6vtable", "foo::bar::{shim:vtable#2}"),
+            ("_RNSNvC3foo3bar0", "foo::bar::{shim#0}"),
+            ("_RNXNvC3foo3bar0", "foo::bar::{X#0}"),
+            ("_RNvNCNvC3foo3bar0s_3baz", "foo::bar::{closure#0}::baz"),
+        ];
+
+        for (name, expected) in test_cases {
+            let symbol = Symbol::parse_from_str(name).unwrap().0;
+
+            assert_eq!(symbol.display(Style::Normal).to_string(), expected);
+            assert_eq!(
+                symbol.display(Style::Normal).to_string(),
+                format!("{:#}", rustc_demangle::demangle(name))
+            );
+        }
+    }
 }
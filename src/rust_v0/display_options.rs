@@ -0,0 +1,558 @@
+//! Displaying symbols with finer control than the [`DisplayStyle`] presets.
+
+use crate::rust_v0::display::{self, DemangleWrite, Renderer};
+use crate::rust_v0::{normalize, Const, DisplayStyle, FnSig, Identifier, ImplPath, Namespace, Symbol};
+use std::borrow::Cow;
+use std::fmt::{self, Display};
+
+/// Crates whose hashes are hidden by [`DisplayOptions::collapse_std_crate_disambiguators`].
+const STD_CRATES: [&str; 3] = ["std", "core", "alloc"];
+
+/// How the return types of function pointer types are displayed.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum FnReturnType {
+    /// Omit return types, like `fn(u8)`.
+    Hidden,
+    /// Show return types other than `()`, like `fn(u8) -> bool` and `fn(u8)`. This is what every [`DisplayStyle`]
+    /// does.
+    NonUnit,
+    /// Show every return type, like `fn(u8) -> bool` and `fn(u8) -> ()`.
+    Always,
+}
+
+/// How integer constants are displayed.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum IntegerFormat {
+    /// Decimal digits, like `-42`.
+    Decimal,
+    /// Lowercase hexadecimal digits after `0x`, like `-0x2a`.
+    Hexadecimal,
+}
+
+/// How placeholders for constants that are not known, and constants hidden by [`DisplayOptions::const_values`], are
+/// displayed.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum ConstPlaceholder {
+    /// `_`, the way rustc-demangle and rustc diagnostics write them.
+    Underscore,
+    /// `{const}`, the way type names in rustc error messages write them.
+    Braced,
+}
+
+/// Options for [`Symbol::display_with_options`](crate::rust_v0::Symbol::display_with_options), which start from a
+/// [`DisplayStyle`] and change individual parts of its output:
+///
+/// ```rust
+/// use ast_demangle::rust_v0::{DisplayOptions, DisplayStyle, Symbol};
+///
+/// let (symbol, _) = Symbol::parse_from_str("_RNvMs_Cs4Cv8Wi1oAIB_7mycrateNtCsfB4SvvYNZ2l_3std6Vec_u83new").unwrap();
+/// let options = DisplayOptions::new(DisplayStyle::Long).collapse_std_crate_disambiguators(true);
+///
+/// assert_eq!(symbol.display_with_options(options).to_string(), "<std::Vec_u8>::new");
+/// ```
+#[allow(clippy::struct_excessive_bools)] // The flags are independent options.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct DisplayOptions {
+    style: DisplayStyle,
+    crate_disambiguators: bool,
+    collapse_std_crate_disambiguators: bool,
+    impl_paths: bool,
+    const_values: bool,
+    integer_format: IntegerFormat,
+    const_type_suffixes: bool,
+    const_placeholder: ConstPlaceholder,
+    generic_args: bool,
+    fn_return_type: FnReturnType,
+    raw_punycode: bool,
+    instantiating_crate: bool,
+}
+
+impl DisplayOptions {
+    /// Creates options that display the same output as `style`.
+    #[must_use]
+    pub fn new(style: DisplayStyle) -> Self {
+        Self {
+            style,
+            crate_disambiguators: style == DisplayStyle::Long,
+            collapse_std_crate_disambiguators: false,
+            impl_paths: false,
+            const_values: true,
+            integer_format: IntegerFormat::Decimal,
+            const_type_suffixes: style == DisplayStyle::Long,
+            const_placeholder: ConstPlaceholder::Underscore,
+            generic_args: true,
+            fn_return_type: FnReturnType::NonUnit,
+            raw_punycode: false,
+            instantiating_crate: false,
+        }
+    }
+
+    /// Returns the style the options start from, which decides everything the other options do not.
+    #[must_use]
+    pub fn style(&self) -> DisplayStyle {
+        self.style
+    }
+
+    /// Shows crate disambiguators, like `mycrate[1234abcd]`. The default is `true` for [`DisplayStyle::Long`] and
+    /// `false` otherwise.
+    #[must_use]
+    pub fn crate_disambiguators(self, value: bool) -> Self {
+        Self {
+            crate_disambiguators: value,
+            ..self
+        }
+    }
+
+    /// Hides the disambiguators of the `std`, `core` and `alloc` crates even if crate disambiguators are shown, since
+    /// there is only one of each in a program. The default is `false`.
+    #[must_use]
+    pub fn collapse_std_crate_disambiguators(self, value: bool) -> Self {
+        Self {
+            collapse_std_crate_disambiguators: value,
+            ..self
+        }
+    }
+
+    /// Shows the path of the impl block of inherent and trait impls, like `<mycrate::Foo>[mycrate::{impl#0}]`, which
+    /// tells apart impl blocks of the same type. The default is `false`.
+    #[must_use]
+    pub fn impl_paths(self, value: bool) -> Self {
+        Self {
+            impl_paths: value,
+            ..self
+        }
+    }
+
+    /// Shows the values of constants, or placeholders in their place if `false`, see
+    /// [`DisplayOptions::const_placeholder`]. The default is `true`.
+    #[must_use]
+    pub fn const_values(self, value: bool) -> Self {
+        Self {
+            const_values: value,
+            ..self
+        }
+    }
+
+    /// Sets how integer constants are displayed. The default is [`IntegerFormat::Decimal`].
+    #[must_use]
+    pub fn integer_format(self, value: IntegerFormat) -> Self {
+        Self {
+            integer_format: value,
+            ..self
+        }
+    }
+
+    /// Shows the types of integer constants as suffixes, like `3usize` instead of `3`. The default is `true` for
+    /// [`DisplayStyle::Long`] and `false` otherwise.
+    #[must_use]
+    pub fn const_type_suffixes(self, value: bool) -> Self {
+        Self {
+            const_type_suffixes: value,
+            ..self
+        }
+    }
+
+    /// Sets how constant placeholders are displayed. The default is [`ConstPlaceholder::Underscore`].
+    #[must_use]
+    pub fn const_placeholder(self, value: ConstPlaceholder) -> Self {
+        Self {
+            const_placeholder: value,
+            ..self
+        }
+    }
+
+    /// Shows generic argument lists, or omits them if `false`, like `regex::utf8::decode_utf8` for
+    /// `regex::utf8::decode_utf8::<&str>`, which groups the instantiations of a generic item together. See
+    /// [`Symbol::strip_generic_args`] for removing them from the AST instead. The default is `true`.
+    #[must_use]
+    pub fn generic_args(self, value: bool) -> Self {
+        Self {
+            generic_args: value,
+            ..self
+        }
+    }
+
+    /// Sets how the return types of function pointer types are displayed. The default is [`FnReturnType::NonUnit`].
+    #[must_use]
+    pub fn fn_return_type(self, value: FnReturnType) -> Self {
+        Self {
+            fn_return_type: value,
+            ..self
+        }
+    }
+
+    /// Shows identifiers with non-ASCII names in the Punycode encoding they have in the mangled name, like
+    /// `punycode{caf_dma}` for `café`, for matching names byte by byte. See [`Identifier::punycode`] for the encoding,
+    /// and [`Identifier::name`] for the decoded name. The default is `false`.
+    #[must_use]
+    pub fn raw_punycode(self, value: bool) -> Self {
+        Self {
+            raw_punycode: value,
+            ..self
+        }
+    }
+
+    /// Appends the crate that instantiates a generic item, like `alloc::vec::push::<u8> @ in mycrate[1234abcd]`, which
+    /// tells which crate triggers each monomorphization. Symbols without an instantiating crate are unaffected. The
+    /// default is `false`.
+    #[must_use]
+    pub fn instantiating_crate(self, value: bool) -> Self {
+        Self {
+            instantiating_crate: value,
+            ..self
+        }
+    }
+}
+
+impl From<DisplayStyle> for DisplayOptions {
+    fn from(style: DisplayStyle) -> Self {
+        Self::new(style)
+    }
+}
+
+/// Applies [`DisplayOptions`] on top of the built-in output.
+struct OptionsRenderer(DisplayOptions);
+
+impl OptionsRenderer {
+    fn write_placeholder(&self, out: &mut dyn DemangleWrite) -> fmt::Result {
+        out.write_str(match self.0.const_placeholder {
+            ConstPlaceholder::Underscore => "_",
+            ConstPlaceholder::Braced => "{const}",
+        })
+    }
+
+    /// Returns `identifier` with its name replaced with the raw Punycode encoding if that is requested.
+    fn identifier<'i>(&self, identifier: &'i Identifier) -> Cow<'i, Identifier<'i>> {
+        match identifier.punycode() {
+            Some(punycode) if self.0.raw_punycode => Cow::Owned(Identifier {
+                disambiguator: identifier.disambiguator,
+                name: Cow::Owned(punycode),
+                is_undecoded_punycode: true,
+            }),
+            _ => Cow::Borrowed(identifier),
+        }
+    }
+
+    fn write_integer(
+        &self,
+        is_negative: bool,
+        magnitude: u128,
+        type_name: &str,
+        out: &mut dyn DemangleWrite,
+    ) -> fmt::Result {
+        if is_negative {
+            out.write_str("-")?;
+        }
+
+        match self.0.integer_format {
+            IntegerFormat::Decimal => write!(out, "{magnitude}")?,
+            IntegerFormat::Hexadecimal => write!(out, "0x{magnitude:x}")?,
+        }
+
+        if self.0.const_type_suffixes {
+            out.write_str(type_name)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Renderer for OptionsRenderer {
+    fn render_crate_root(
+        &mut self,
+        identifier: &Identifier,
+        out: &mut dyn DemangleWrite,
+        _: DisplayStyle,
+    ) -> fmt::Result {
+        let options = &self.0;
+        let is_collapsed = options.collapse_std_crate_disambiguators && STD_CRATES.contains(&&*identifier.name);
+        let identifier = self.identifier(identifier);
+
+        if options.crate_disambiguators && !is_collapsed && identifier.disambiguator != 0 {
+            write!(out, "{}[{:x}]", identifier.display(), identifier.disambiguator)
+        } else {
+            write!(out, "{}", identifier.display())
+        }
+    }
+
+    fn render_identifier(
+        &mut self,
+        identifier: &Identifier,
+        out: &mut dyn DemangleWrite,
+        _: DisplayStyle,
+    ) -> fmt::Result {
+        write!(out, "{}", self.identifier(identifier).display())
+    }
+
+    fn render_special_identifier(
+        &mut self,
+        namespace: Namespace,
+        identifier: &Identifier,
+        out: &mut dyn DemangleWrite,
+        _: DisplayStyle,
+    ) -> fmt::Result {
+        display::write_special_identifier(namespace, &self.identifier(identifier), out)
+    }
+
+    fn render_const(
+        &mut self,
+        const_: &Const,
+        out: &mut dyn DemangleWrite,
+        style: DisplayStyle,
+        bound_lifetime_depth: u64,
+        in_value: bool,
+    ) -> fmt::Result {
+        if !self.0.const_values {
+            return self.write_placeholder(out);
+        }
+
+        let (is_negative, magnitude, type_name) = match *const_ {
+            Const::I8(value) => (value < 0, value.unsigned_abs().into(), "i8"),
+            Const::U8(value) => (false, value.into(), "u8"),
+            Const::Isize(value) => (value < 0, value.unsigned_abs() as u128, "isize"),
+            Const::Usize(value) => (false, value as u128, "usize"),
+            Const::I32(value) => (value < 0, value.unsigned_abs().into(), "i32"),
+            Const::U32(value) => (false, value.into(), "u32"),
+            Const::I128(value) => (value < 0, value.unsigned_abs(), "i128"),
+            Const::U128(value) => (false, value, "u128"),
+            Const::I16(value) => (value < 0, value.unsigned_abs().into(), "i16"),
+            Const::U16(value) => (false, value.into(), "u16"),
+            Const::I64(value) => (value < 0, value.unsigned_abs().into(), "i64"),
+            Const::U64(value) => (false, value.into(), "u64"),
+            Const::Placeholder => return self.write_placeholder(out),
+            _ => return display::write_const(const_, out, self, style, bound_lifetime_depth, in_value),
+        };
+
+        self.write_integer(is_negative, magnitude, type_name, out)
+    }
+
+    fn render_fn_sig(
+        &mut self,
+        fn_sig: &FnSig,
+        out: &mut dyn DemangleWrite,
+        style: DisplayStyle,
+        bound_lifetime_depth: u64,
+    ) -> fmt::Result {
+        let return_type = self.0.fn_return_type;
+
+        display::write_fn_sig_with_return_type(fn_sig, out, self, style, bound_lifetime_depth, return_type)
+    }
+
+    fn render_impl_path(
+        &mut self,
+        impl_path: &ImplPath,
+        out: &mut dyn DemangleWrite,
+        style: DisplayStyle,
+        bound_lifetime_depth: u64,
+    ) -> fmt::Result {
+        if self.0.impl_paths {
+            out.write_str("[")?;
+            display::write_path(&impl_path.path, out, self, style, bound_lifetime_depth, false)?;
+            write!(out, "::{{impl#{}}}]", impl_path.disambiguator)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+pub fn display_symbol<'s>(symbol: &'s Symbol, options: DisplayOptions) -> impl Display + 's {
+    display::display_fn(move |f| {
+        let mut renderer = OptionsRenderer(options);
+
+        if options.generic_args {
+            symbol.render(f, &mut renderer, options.style)?;
+        } else {
+            normalize::strip_generic_args(symbol).render(f, &mut renderer, options.style)?;
+        }
+
+        match &symbol.instantiating_crate {
+            Some(instantiating_crate) if options.instantiating_crate => {
+                f.write_str(" @ in ")?;
+                instantiating_crate.render(f, &mut renderer, options.style)
+            }
+            _ => Ok(()),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ConstPlaceholder, DisplayOptions, FnReturnType, IntegerFormat};
+    use crate::rust_v0::{DisplayStyle, Symbol};
+
+    #[test]
+    fn test_display_options_match_styles() {
+        let names = [
+            "_RNvMs_Cs4Cv8Wi1oAIB_7mycrateNtCsfB4SvvYNZ2l_3std6Vec_u83new",
+            "_RINvCs123_3foo3barKj1_FiEuEB2_",
+            "_RINvC3foo3barKan2a_Kj1f_KpKAj3_pEE",
+            "_RNvXs2_NtCs123_4core3fmtRNtB5_9ArgumentsNtB5_7Display3fmt",
+        ];
+
+        for name in names {
+            let (symbol, _) = Symbol::parse_from_str(name).unwrap();
+
+            for style in [
+                DisplayStyle::Short,
+                DisplayStyle::Normal,
+                DisplayStyle::Long,
+                DisplayStyle::Debuginfo,
+            ] {
+                assert_eq!(
+                    symbol.display_with_options(style.into()).to_string(),
+                    symbol.display(style).to_string(),
+                    "{name}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_display_options_consts() {
+        let (symbol, _) = Symbol::parse_from_str("_RINvC3foo3barKan2a_Kj1f_KpKAj3_pEE").unwrap();
+
+        let test_cases = [
+            (
+                DisplayOptions::new(DisplayStyle::Normal),
+                "foo::bar::<-42, 31, _, {[3, _]}>",
+            ),
+            (
+                DisplayOptions::new(DisplayStyle::Long),
+                "foo::bar::<-42i8, 31usize, _, {[3usize, _]}>",
+            ),
+            (
+                DisplayOptions::new(DisplayStyle::Long).const_type_suffixes(false),
+                "foo::bar::<-42, 31, _, {[3, _]}>",
+            ),
+            (
+                DisplayOptions::new(DisplayStyle::Normal).integer_format(IntegerFormat::Hexadecimal),
+                "foo::bar::<-0x2a, 0x1f, _, {[0x3, _]}>",
+            ),
+            (
+                DisplayOptions::new(DisplayStyle::Normal)
+                    .integer_format(IntegerFormat::Hexadecimal)
+                    .const_type_suffixes(true),
+                "foo::bar::<-0x2ai8, 0x1fusize, _, {[0x3usize, _]}>",
+            ),
+            (
+                DisplayOptions::new(DisplayStyle::Normal).const_placeholder(ConstPlaceholder::Braced),
+                "foo::bar::<-42, 31, {const}, {[3, {const}]}>",
+            ),
+            (
+                DisplayOptions::new(DisplayStyle::Normal)
+                    .const_values(false)
+                    .const_placeholder(ConstPlaceholder::Braced),
+                "foo::bar::<{const}, {const}, {const}, {const}>",
+            ),
+        ];
+
+        for (options, expected) in test_cases {
+            assert_eq!(
+                symbol.display_with_options(options).to_string(),
+                expected,
+                "{options:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_display_options() {
+        let (symbol, _) =
+            Symbol::parse_from_str("_RINvMs0_NtCs123_5crate6moduleNtCs456_4core4Cell3getKj1_FlEuFlEbEB6_").unwrap();
+
+        let test_cases = [
+            (
+                DisplayOptions::new(DisplayStyle::Normal),
+                "<core::Cell>::get::<1, fn(i32), fn(i32) -> bool>",
+            ),
+            (
+                DisplayOptions::new(DisplayStyle::Long),
+                "<core[3d4e]::Cell>::get::<1usize, fn(i32), fn(i32) -> bool>",
+            ),
+            (
+                DisplayOptions::new(DisplayStyle::Normal).crate_disambiguators(true),
+                "<core[3d4e]::Cell>::get::<1, fn(i32), fn(i32) -> bool>",
+            ),
+            (
+                DisplayOptions::new(DisplayStyle::Long).crate_disambiguators(false),
+                "<core::Cell>::get::<1usize, fn(i32), fn(i32) -> bool>",
+            ),
+            (
+                DisplayOptions::new(DisplayStyle::Long).collapse_std_crate_disambiguators(true),
+                "<core::Cell>::get::<1usize, fn(i32), fn(i32) -> bool>",
+            ),
+            (
+                DisplayOptions::new(DisplayStyle::Long)
+                    .collapse_std_crate_disambiguators(true)
+                    .impl_paths(true),
+                "<core::Cell>[crate[f85]::module::{impl#2}]::get::<1usize, fn(i32), fn(i32) -> bool>",
+            ),
+            (
+                DisplayOptions::new(DisplayStyle::Normal).const_values(false),
+                "<core::Cell>::get::<_, fn(i32), fn(i32) -> bool>",
+            ),
+            (
+                DisplayOptions::new(DisplayStyle::Normal).generic_args(false),
+                "<core::Cell>::get",
+            ),
+            (
+                DisplayOptions::new(DisplayStyle::Normal).fn_return_type(FnReturnType::Always),
+                "<core::Cell>::get::<1, fn(i32) -> (), fn(i32) -> bool>",
+            ),
+            (
+                DisplayOptions::new(DisplayStyle::Short).fn_return_type(FnReturnType::Hidden),
+                "<Cell>::get::<1, fn(i32), fn(i32)>",
+            ),
+        ];
+
+        for (options, expected) in test_cases {
+            assert_eq!(
+                symbol.display_with_options(options).to_string(),
+                expected,
+                "{options:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_display_options_instantiating_crate() {
+        let (symbol, _) = Symbol::parse_from_str("_RINvNtCs123_5alloc3vec4pushhECs456_7mycrate").unwrap();
+        let options = DisplayOptions::new(DisplayStyle::Normal).instantiating_crate(true);
+
+        assert_eq!(
+            symbol.display_with_options(options).to_string(),
+            "alloc::vec::push::<u8> @ in mycrate"
+        );
+
+        assert_eq!(
+            symbol
+                .display_with_options(options.crate_disambiguators(true).generic_args(false))
+                .to_string(),
+            "alloc[f85]::vec::push @ in mycrate[3d4e]"
+        );
+
+        let (symbol, _) = Symbol::parse_from_str("_RINvNtCs123_5alloc3vec4pushhE").unwrap();
+
+        assert_eq!(
+            symbol.display_with_options(options).to_string(),
+            "alloc::vec::push::<u8>"
+        );
+    }
+
+    #[test]
+    fn test_display_options_raw_punycode() {
+        let (symbol, _) = Symbol::parse_from_str("_RNCNvCs1_u7caf_dmau7caf_dmas_u7caf_dma").unwrap();
+
+        assert_eq!(
+            symbol.display(DisplayStyle::Long).to_string(),
+            "café[3]::café::{closure:café#1}"
+        );
+
+        assert_eq!(
+            symbol
+                .display_with_options(DisplayOptions::new(DisplayStyle::Long).raw_punycode(true))
+                .to_string(),
+            "punycode{caf_dma}[3]::punycode{caf_dma}::{closure:punycode{caf_dma}#1}"
+        );
+    }
+}
@@ -0,0 +1,497 @@
+//! Rewriting the AST of a symbol.
+
+use crate::rust_v0::{
+    Const, ConstFields, DynBounds, DynTrait, DynTraitAssocBinding, FnSig, GenericArg, Identifier, ImplPath, NodeRc,
+    Path, Symbol, Type,
+};
+
+/// Rebuilds the nodes of a [`Symbol`] AST. Every method forwards to the corresponding `super_fold_*` function by
+/// default, which folds the children of the node, so an implementation only needs to override the methods for the
+/// nodes it wants to replace, and calls the `super_fold_*` function to keep descending.
+///
+/// Nodes whose children are all unchanged are not rebuilt, the original [`NodeRc`] is returned instead, so folding only
+/// allocates along the paths to the replaced nodes. Nodes shared through back references are folded once for every
+/// place they appear in.
+pub trait Folder<'a> {
+    fn fold_symbol(&mut self, symbol: &Symbol<'a>) -> Symbol<'a> {
+        super_fold_symbol(self, symbol)
+    }
+
+    fn fold_path(&mut self, path: &NodeRc<Path<'a>>) -> NodeRc<Path<'a>> {
+        super_fold_path(self, path)
+    }
+
+    fn fold_impl_path(&mut self, impl_path: &ImplPath<'a>) -> ImplPath<'a> {
+        super_fold_impl_path(self, impl_path)
+    }
+
+    /// Folds an identifier of a path, or a field name of a constant struct.
+    fn fold_identifier(&mut self, identifier: &Identifier<'a>) -> Identifier<'a> {
+        super_fold_identifier(self, identifier)
+    }
+
+    /// Folds the disambiguator of an identifier or an impl path, where 0 means no disambiguator.
+    fn fold_disambiguator(&mut self, disambiguator: u64) -> u64 {
+        disambiguator
+    }
+
+    fn fold_generic_arg(&mut self, generic_arg: &GenericArg<'a>) -> GenericArg<'a> {
+        super_fold_generic_arg(self, generic_arg)
+    }
+
+    /// Folds a lifetime, which is 0 for erased lifetimes, or the distance of the binder that binds the lifetime.
+    fn fold_lifetime(&mut self, lifetime: u64) -> u64 {
+        lifetime
+    }
+
+    fn fold_type(&mut self, type_: &NodeRc<Type<'a>>) -> NodeRc<Type<'a>> {
+        super_fold_type(self, type_)
+    }
+
+    fn fold_fn_sig(&mut self, fn_sig: &FnSig<'a>) -> FnSig<'a> {
+        super_fold_fn_sig(self, fn_sig)
+    }
+
+    fn fold_dyn_bounds(&mut self, dyn_bounds: &DynBounds<'a>) -> DynBounds<'a> {
+        super_fold_dyn_bounds(self, dyn_bounds)
+    }
+
+    fn fold_dyn_trait(&mut self, dyn_trait: &DynTrait<'a>) -> DynTrait<'a> {
+        super_fold_dyn_trait(self, dyn_trait)
+    }
+
+    fn fold_const(&mut self, const_: &NodeRc<Const<'a>>) -> NodeRc<Const<'a>> {
+        super_fold_const(self, const_)
+    }
+}
+
+/// Folds the path of `symbol` and its instantiating crate.
+pub fn super_fold_symbol<'a, F: Folder<'a> + ?Sized>(folder: &mut F, symbol: &Symbol<'a>) -> Symbol<'a> {
+    Symbol {
+        version: symbol.version,
+        path: folder.fold_path(&symbol.path),
+        instantiating_crate: symbol
+            .instantiating_crate
+            .as_ref()
+            .map(|instantiating_crate| folder.fold_path(instantiating_crate)),
+        vendor_specific_suffix: symbol.vendor_specific_suffix.clone(),
+    }
+}
+
+pub fn super_fold_path<'a, F: Folder<'a> + ?Sized>(folder: &mut F, path: &NodeRc<Path<'a>>) -> NodeRc<Path<'a>> {
+    let result = match path.as_ref() {
+        Path::CrateRoot(identifier) => {
+            let new_identifier = folder.fold_identifier(identifier);
+
+            (new_identifier != *identifier).then_some(Path::CrateRoot(new_identifier))
+        }
+        Path::InherentImpl { impl_path, type_ } => {
+            let new_impl_path = folder.fold_impl_path(impl_path);
+            let new_type = folder.fold_type(type_);
+
+            (!(same_impl_path(&new_impl_path, impl_path) && NodeRc::ptr_eq(&new_type, type_))).then_some(
+                Path::InherentImpl {
+                    impl_path: new_impl_path,
+                    type_: new_type,
+                },
+            )
+        }
+        Path::TraitImpl {
+            impl_path,
+            type_,
+            trait_,
+        } => {
+            let new_impl_path = folder.fold_impl_path(impl_path);
+            let new_type = folder.fold_type(type_);
+            let new_trait = folder.fold_path(trait_);
+
+            (!(same_impl_path(&new_impl_path, impl_path)
+                && NodeRc::ptr_eq(&new_type, type_)
+                && NodeRc::ptr_eq(&new_trait, trait_)))
+            .then_some(Path::TraitImpl {
+                impl_path: new_impl_path,
+                type_: new_type,
+                trait_: new_trait,
+            })
+        }
+        Path::TraitDefinition { type_, trait_ } => {
+            let new_type = folder.fold_type(type_);
+            let new_trait = folder.fold_path(trait_);
+
+            (!(NodeRc::ptr_eq(&new_type, type_) && NodeRc::ptr_eq(&new_trait, trait_))).then_some(
+                Path::TraitDefinition {
+                    type_: new_type,
+                    trait_: new_trait,
+                },
+            )
+        }
+        Path::Nested {
+            namespace,
+            path: parent,
+            identifier,
+        } => {
+            let new_parent = folder.fold_path(parent);
+            let new_identifier = folder.fold_identifier(identifier);
+
+            (!(NodeRc::ptr_eq(&new_parent, parent) && new_identifier == *identifier)).then_some(Path::Nested {
+                namespace: *namespace,
+                path: new_parent,
+                identifier: new_identifier,
+            })
+        }
+        Path::Generic {
+            path: parent,
+            generic_args,
+        } => {
+            let new_parent = folder.fold_path(parent);
+            let new_generic_args = fold_slice(generic_args, |arg| folder.fold_generic_arg(arg), same_generic_arg);
+
+            (!NodeRc::ptr_eq(&new_parent, parent) || new_generic_args.is_some()).then(|| Path::Generic {
+                path: new_parent,
+                generic_args: new_generic_args.unwrap_or_else(|| generic_args.clone()),
+            })
+        }
+    };
+
+    result.map_or_else(|| NodeRc::clone(path), NodeRc::new)
+}
+
+pub fn super_fold_impl_path<'a, F: Folder<'a> + ?Sized>(folder: &mut F, impl_path: &ImplPath<'a>) -> ImplPath<'a> {
+    ImplPath {
+        disambiguator: folder.fold_disambiguator(impl_path.disambiguator),
+        path: folder.fold_path(&impl_path.path),
+    }
+}
+
+pub fn super_fold_identifier<'a, F: Folder<'a> + ?Sized>(
+    folder: &mut F,
+    identifier: &Identifier<'a>,
+) -> Identifier<'a> {
+    Identifier {
+        disambiguator: folder.fold_disambiguator(identifier.disambiguator),
+        name: identifier.name.clone(),
+        is_undecoded_punycode: identifier.is_undecoded_punycode,
+    }
+}
+
+pub fn super_fold_generic_arg<'a, F: Folder<'a> + ?Sized>(
+    folder: &mut F,
+    generic_arg: &GenericArg<'a>,
+) -> GenericArg<'a> {
+    match generic_arg {
+        GenericArg::Lifetime(lifetime) => GenericArg::Lifetime(folder.fold_lifetime(*lifetime)),
+        GenericArg::Type(type_) => GenericArg::Type(folder.fold_type(type_)),
+        GenericArg::Const(const_) => GenericArg::Const(folder.fold_const(const_)),
+    }
+}
+
+pub fn super_fold_type<'a, F: Folder<'a> + ?Sized>(folder: &mut F, type_: &NodeRc<Type<'a>>) -> NodeRc<Type<'a>> {
+    let result = match type_.as_ref() {
+        Type::Basic(_) => None,
+        Type::Named(path) => {
+            let new_path = folder.fold_path(path);
+
+            (!NodeRc::ptr_eq(&new_path, path)).then_some(Type::Named(new_path))
+        }
+        Type::Array(element_type, length) => {
+            let new_element_type = folder.fold_type(element_type);
+            let new_length = folder.fold_const(length);
+
+            (!(NodeRc::ptr_eq(&new_element_type, element_type) && NodeRc::ptr_eq(&new_length, length)))
+                .then_some(Type::Array(new_element_type, new_length))
+        }
+        Type::Slice(inner) => fold_inner_type(folder, inner).map(Type::Slice),
+        Type::Tuple(tuple_types) => {
+            fold_slice(tuple_types, |type_| folder.fold_type(type_), NodeRc::ptr_eq).map(Type::Tuple)
+        }
+        Type::Ref { lifetime, type_: inner } => {
+            let new_lifetime = folder.fold_lifetime(*lifetime);
+            let new_inner = folder.fold_type(inner);
+
+            (new_lifetime != *lifetime || !NodeRc::ptr_eq(&new_inner, inner)).then_some(Type::Ref {
+                lifetime: new_lifetime,
+                type_: new_inner,
+            })
+        }
+        Type::RefMut { lifetime, type_: inner } => {
+            let new_lifetime = folder.fold_lifetime(*lifetime);
+            let new_inner = folder.fold_type(inner);
+
+            (new_lifetime != *lifetime || !NodeRc::ptr_eq(&new_inner, inner)).then_some(Type::RefMut {
+                lifetime: new_lifetime,
+                type_: new_inner,
+            })
+        }
+        Type::PtrConst(inner) => fold_inner_type(folder, inner).map(Type::PtrConst),
+        Type::PtrMut(inner) => fold_inner_type(folder, inner).map(Type::PtrMut),
+        Type::Fn(fn_sig) => {
+            let new_fn_sig = folder.fold_fn_sig(fn_sig);
+
+            (!same_fn_sig(&new_fn_sig, fn_sig)).then_some(Type::Fn(new_fn_sig))
+        }
+        Type::DynTrait { dyn_bounds, lifetime } => {
+            let new_dyn_bounds = folder.fold_dyn_bounds(dyn_bounds);
+            let new_lifetime = folder.fold_lifetime(*lifetime);
+
+            (!(same_dyn_bounds(&new_dyn_bounds, dyn_bounds) && new_lifetime == *lifetime)).then_some(Type::DynTrait {
+                dyn_bounds: new_dyn_bounds,
+                lifetime: new_lifetime,
+            })
+        }
+    };
+
+    result.map_or_else(|| NodeRc::clone(type_), NodeRc::new)
+}
+
+pub fn super_fold_fn_sig<'a, F: Folder<'a> + ?Sized>(folder: &mut F, fn_sig: &FnSig<'a>) -> FnSig<'a> {
+    FnSig {
+        bound_lifetimes: fn_sig.bound_lifetimes,
+        is_unsafe: fn_sig.is_unsafe,
+        abi: fn_sig.abi.clone(),
+        argument_types: fold_slice(&fn_sig.argument_types, |type_| folder.fold_type(type_), NodeRc::ptr_eq)
+            .unwrap_or_else(|| fn_sig.argument_types.clone()),
+        return_type: folder.fold_type(&fn_sig.return_type),
+    }
+}
+
+pub fn super_fold_dyn_bounds<'a, F: Folder<'a> + ?Sized>(folder: &mut F, dyn_bounds: &DynBounds<'a>) -> DynBounds<'a> {
+    DynBounds {
+        bound_lifetimes: dyn_bounds.bound_lifetimes,
+        dyn_traits: fold_slice(
+            &dyn_bounds.dyn_traits,
+            |dyn_trait| folder.fold_dyn_trait(dyn_trait),
+            same_dyn_trait,
+        )
+        .unwrap_or_else(|| dyn_bounds.dyn_traits.clone()),
+    }
+}
+
+pub fn super_fold_dyn_trait<'a, F: Folder<'a> + ?Sized>(folder: &mut F, dyn_trait: &DynTrait<'a>) -> DynTrait<'a> {
+    DynTrait {
+        path: folder.fold_path(&dyn_trait.path),
+        dyn_trait_assoc_bindings: fold_slice(
+            &dyn_trait.dyn_trait_assoc_bindings,
+            |binding| DynTraitAssocBinding {
+                name: binding.name.clone(),
+                type_: folder.fold_type(&binding.type_),
+                is_undecoded_punycode: binding.is_undecoded_punycode,
+            },
+            |left, right| NodeRc::ptr_eq(&left.type_, &right.type_),
+        )
+        .unwrap_or_else(|| dyn_trait.dyn_trait_assoc_bindings.clone()),
+    }
+}
+
+pub fn super_fold_const<'a, F: Folder<'a> + ?Sized>(folder: &mut F, const_: &NodeRc<Const<'a>>) -> NodeRc<Const<'a>> {
+    let result = match const_.as_ref() {
+        Const::Ref(value) => fold_inner_const(folder, value).map(Const::Ref),
+        Const::RefMut(value) => fold_inner_const(folder, value).map(Const::RefMut),
+        Const::Array(items) => fold_slice(items, |item| folder.fold_const(item), NodeRc::ptr_eq).map(Const::Array),
+        Const::Tuple(items) => fold_slice(items, |item| folder.fold_const(item), NodeRc::ptr_eq).map(Const::Tuple),
+        Const::NamedStruct { path, fields } => {
+            let new_path = folder.fold_path(path);
+
+            let new_fields = match fields {
+                ConstFields::Unit => None,
+                ConstFields::Tuple(fields) => {
+                    fold_slice(fields, |field| folder.fold_const(field), NodeRc::ptr_eq).map(ConstFields::Tuple)
+                }
+                ConstFields::Struct(fields) => fold_slice(
+                    fields,
+                    |(name, field)| (folder.fold_identifier(name), folder.fold_const(field)),
+                    |(left_name, left_field), (right_name, right_field)| {
+                        left_name == right_name && NodeRc::ptr_eq(left_field, right_field)
+                    },
+                )
+                .map(ConstFields::Struct),
+            };
+
+            (!NodeRc::ptr_eq(&new_path, path) || new_fields.is_some()).then(|| Const::NamedStruct {
+                path: new_path,
+                fields: new_fields.unwrap_or_else(|| fields.clone()),
+            })
+        }
+        _ => None,
+    };
+
+    result.map_or_else(|| NodeRc::clone(const_), NodeRc::new)
+}
+
+fn fold_inner_type<'a, F: Folder<'a> + ?Sized>(folder: &mut F, inner: &NodeRc<Type<'a>>) -> Option<NodeRc<Type<'a>>> {
+    let new_inner = folder.fold_type(inner);
+
+    (!NodeRc::ptr_eq(&new_inner, inner)).then_some(new_inner)
+}
+
+fn fold_inner_const<'a, F: Folder<'a> + ?Sized>(
+    folder: &mut F,
+    inner: &NodeRc<Const<'a>>,
+) -> Option<NodeRc<Const<'a>>> {
+    let new_inner = folder.fold_const(inner);
+
+    (!NodeRc::ptr_eq(&new_inner, inner)).then_some(new_inner)
+}
+
+/// Folds every item of `items`, returns `None` if all of them are unchanged according to `same`, so the original
+/// vector can be reused.
+fn fold_slice<T: Clone>(items: &[T], mut fold: impl FnMut(&T) -> T, same: impl Fn(&T, &T) -> bool) -> Option<Vec<T>> {
+    let mut result = None::<Vec<T>>;
+
+    for (i, item) in items.iter().enumerate() {
+        let new_item = fold(item);
+
+        if let Some(result) = &mut result {
+            result.push(new_item);
+        } else if !same(&new_item, item) {
+            let mut new_items = Vec::with_capacity(items.len());
+
+            new_items.extend_from_slice(&items[..i]);
+            new_items.push(new_item);
+
+            result = Some(new_items);
+        }
+    }
+
+    result
+}
+
+fn same_impl_path(left: &ImplPath, right: &ImplPath) -> bool {
+    left.disambiguator == right.disambiguator && NodeRc::ptr_eq(&left.path, &right.path)
+}
+
+fn same_generic_arg(left: &GenericArg, right: &GenericArg) -> bool {
+    match (left, right) {
+        (GenericArg::Lifetime(left), GenericArg::Lifetime(right)) => left == right,
+        (GenericArg::Type(left), GenericArg::Type(right)) => NodeRc::ptr_eq(left, right),
+        (GenericArg::Const(left), GenericArg::Const(right)) => NodeRc::ptr_eq(left, right),
+        _ => false,
+    }
+}
+
+fn same_types(left: &[NodeRc<Type>], right: &[NodeRc<Type>]) -> bool {
+    left.len() == right.len() && left.iter().zip(right).all(|(left, right)| NodeRc::ptr_eq(left, right))
+}
+
+fn same_fn_sig(left: &FnSig, right: &FnSig) -> bool {
+    left.bound_lifetimes == right.bound_lifetimes
+        && left.is_unsafe == right.is_unsafe
+        && left.abi == right.abi
+        && same_types(&left.argument_types, &right.argument_types)
+        && NodeRc::ptr_eq(&left.return_type, &right.return_type)
+}
+
+fn same_dyn_bounds(left: &DynBounds, right: &DynBounds) -> bool {
+    left.bound_lifetimes == right.bound_lifetimes
+        && left.dyn_traits.len() == right.dyn_traits.len()
+        && left
+            .dyn_traits
+            .iter()
+            .zip(&right.dyn_traits)
+            .all(|(left, right)| same_dyn_trait(left, right))
+}
+
+fn same_dyn_trait(left: &DynTrait, right: &DynTrait) -> bool {
+    NodeRc::ptr_eq(&left.path, &right.path)
+        && left.dyn_trait_assoc_bindings.len() == right.dyn_trait_assoc_bindings.len()
+        && left
+            .dyn_trait_assoc_bindings
+            .iter()
+            .zip(&right.dyn_trait_assoc_bindings)
+            .all(|(left, right)| left.name == right.name && NodeRc::ptr_eq(&left.type_, &right.type_))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Folder;
+    use crate::rust_v0::{Identifier, NodeRc, Path, Symbol};
+    use std::borrow::Cow;
+
+    const SYMBOL: &str = "_RINvNtCs1234_3std3mem8align_ofINtCs5678_3foo3BarNtCs9abc_3baz3QuxEECs1234_3app";
+
+    #[test]
+    fn test_identity_fold_preserves_sharing() {
+        struct Identity;
+
+        impl Folder<'_> for Identity {}
+
+        let (symbol, _) = Symbol::parse_from_str(SYMBOL).unwrap();
+        let folded = Identity.fold_symbol(&symbol);
+
+        assert!(NodeRc::ptr_eq(&folded.path, &symbol.path));
+        assert!(NodeRc::ptr_eq(
+            folded.instantiating_crate.as_ref().unwrap(),
+            symbol.instantiating_crate.as_ref().unwrap()
+        ));
+    }
+
+    #[test]
+    fn test_strip_generic_args() {
+        struct StripGenericArgs;
+
+        impl<'a> Folder<'a> for StripGenericArgs {
+            fn fold_path(&mut self, path: &NodeRc<Path<'a>>) -> NodeRc<Path<'a>> {
+                if let Path::Generic { path, .. } = path.as_ref() {
+                    self.fold_path(path)
+                } else {
+                    super::super_fold_path(self, path)
+                }
+            }
+        }
+
+        let (symbol, _) = Symbol::parse_from_str(SYMBOL).unwrap();
+        let folded = StripGenericArgs.fold_symbol(&symbol);
+
+        assert_eq!(format!("{folded:#}"), "std::mem::align_of");
+    }
+
+    #[test]
+    fn test_blank_disambiguators() {
+        struct BlankDisambiguators;
+
+        impl Folder<'_> for BlankDisambiguators {
+            fn fold_disambiguator(&mut self, _disambiguator: u64) -> u64 {
+                0
+            }
+        }
+
+        let (symbol, _) = Symbol::parse_from_str(SYMBOL).unwrap();
+        let folded = BlankDisambiguators.fold_symbol(&symbol);
+
+        assert_eq!(folded.to_string(), "std::mem::align_of::<foo::Bar<baz::Qux>>");
+    }
+
+    #[test]
+    fn test_rename_crate_root() {
+        struct RenameCrate;
+
+        impl<'a> Folder<'a> for RenameCrate {
+            fn fold_path(&mut self, path: &NodeRc<Path<'a>>) -> NodeRc<Path<'a>> {
+                match path.as_ref() {
+                    Path::CrateRoot(identifier) if identifier.name == "foo" => NodeRc::new(Path::CrateRoot(
+                        Identifier::new_unchecked(identifier.disambiguator, Cow::Borrowed("renamed")),
+                    )),
+                    _ => super::super_fold_path(self, path),
+                }
+            }
+        }
+
+        let (symbol, _) = Symbol::parse_from_str(SYMBOL).unwrap();
+        let folded = RenameCrate.fold_symbol(&symbol);
+
+        assert_eq!(format!("{folded:#}"), "std::mem::align_of::<renamed::Bar<baz::Qux>>");
+
+        // Subtrees that do not contain the renamed crate are shared with the original symbol.
+        let (Path::Generic { path, generic_args }, Path::Generic { path: new_path, .. }) =
+            (symbol.path.as_ref(), folded.path.as_ref())
+        else {
+            unreachable!()
+        };
+
+        assert!(NodeRc::ptr_eq(path, new_path));
+        assert_eq!(generic_args.len(), 1);
+        assert!(NodeRc::ptr_eq(
+            symbol.instantiating_crate.as_ref().unwrap(),
+            folded.instantiating_crate.as_ref().unwrap()
+        ));
+    }
+}
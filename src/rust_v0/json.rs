@@ -0,0 +1,483 @@
+//! Writing symbols as JSON with a stable schema that does not depend on the layout of the AST types, see
+//! [`Symbol::to_json`].
+
+use crate::rust_v0::{Abi, Const, ConstFields, DynBounds, FnSig, GenericArg, Identifier, ImplPath, Path, Symbol, Type};
+use std::fmt::{self, Debug, Display, Formatter, Write};
+
+fn write_string(s: &str, out: &mut Formatter) -> fmt::Result {
+    out.write_char('"')?;
+
+    for c in s.chars() {
+        match c {
+            '"' => out.write_str("\\\"")?,
+            '\\' => out.write_str("\\\\")?,
+            '\n' => out.write_str("\\n")?,
+            '\r' => out.write_str("\\r")?,
+            '\t' => out.write_str("\\t")?,
+            '\0'..='\x1f' => write!(out, "\\u{:04x}", u32::from(c))?,
+            _ => out.write_char(c)?,
+        }
+    }
+
+    out.write_char('"')
+}
+
+fn write_optional_string(s: Option<&str>, out: &mut Formatter) -> fmt::Result {
+    match s {
+        None => out.write_str("null"),
+        Some(s) => write_string(s, out),
+    }
+}
+
+fn write_list<T>(
+    items: &[T],
+    out: &mut Formatter,
+    mut f: impl FnMut(&T, &mut Formatter) -> fmt::Result,
+) -> fmt::Result {
+    out.write_char('[')?;
+
+    for (i, item) in items.iter().enumerate() {
+        if i != 0 {
+            out.write_char(',')?;
+        }
+
+        f(item, out)?;
+    }
+
+    out.write_char(']')
+}
+
+fn write_identifier(identifier: &Identifier, out: &mut Formatter) -> fmt::Result {
+    out.write_str("{\"name\":")?;
+    write_string(&identifier.display().to_string(), out)?;
+    write!(out, ",\"disambiguator\":\"{}\"}}", identifier.disambiguator)
+}
+
+fn write_impl_path(impl_path: &ImplPath, out: &mut Formatter) -> fmt::Result {
+    write!(out, "\"disambiguator\":\"{}\",\"impl_path\":", impl_path.disambiguator)?;
+    write_path(&impl_path.path, out)
+}
+
+fn write_path(path: &Path, out: &mut Formatter) -> fmt::Result {
+    match path {
+        Path::CrateRoot(identifier) => {
+            out.write_str("{\"kind\":\"crate_root\",\"identifier\":")?;
+            write_identifier(identifier, out)?;
+        }
+        Path::InherentImpl { impl_path, type_ } => {
+            out.write_str("{\"kind\":\"inherent_impl\",")?;
+            write_impl_path(impl_path, out)?;
+            out.write_str(",\"type\":")?;
+            write_type(type_, out)?;
+        }
+        Path::TraitImpl {
+            impl_path,
+            type_,
+            trait_,
+        } => {
+            out.write_str("{\"kind\":\"trait_impl\",")?;
+            write_impl_path(impl_path, out)?;
+            out.write_str(",\"type\":")?;
+            write_type(type_, out)?;
+            out.write_str(",\"trait\":")?;
+            write_path(trait_, out)?;
+        }
+        Path::TraitDefinition { type_, trait_ } => {
+            out.write_str("{\"kind\":\"trait_definition\",\"type\":")?;
+            write_type(type_, out)?;
+            out.write_str(",\"trait\":")?;
+            write_path(trait_, out)?;
+        }
+        Path::Nested {
+            namespace,
+            path,
+            identifier,
+        } => {
+            out.write_str("{\"kind\":\"nested\",\"namespace\":")?;
+            write_string(namespace.as_char().encode_utf8(&mut [0; 4]), out)?;
+            out.write_str(",\"path\":")?;
+            write_path(path, out)?;
+            out.write_str(",\"identifier\":")?;
+            write_identifier(identifier, out)?;
+        }
+        Path::Generic { path, generic_args } => {
+            out.write_str("{\"kind\":\"generic\",\"path\":")?;
+            write_path(path, out)?;
+            out.write_str(",\"generic_args\":")?;
+            write_list(generic_args, out, |generic_arg, out| {
+                write_generic_arg(generic_arg, out)
+            })?;
+        }
+    }
+
+    out.write_char('}')
+}
+
+fn write_generic_arg(generic_arg: &GenericArg, out: &mut Formatter) -> fmt::Result {
+    match generic_arg {
+        GenericArg::Lifetime(lifetime) => write!(out, "{{\"kind\":\"lifetime\",\"lifetime\":{lifetime}}}"),
+        GenericArg::Type(type_) => {
+            out.write_str("{\"kind\":\"type\",\"type\":")?;
+            write_type(type_, out)?;
+            out.write_char('}')
+        }
+        GenericArg::Const(const_) => {
+            out.write_str("{\"kind\":\"const\",\"const\":")?;
+            write_const(const_, out)?;
+            out.write_char('}')
+        }
+    }
+}
+
+fn write_fn_sig(fn_sig: &FnSig, out: &mut Formatter) -> fmt::Result {
+    write!(
+        out,
+        "\"bound_lifetimes\":{},\"unsafe\":{},\"abi\":",
+        fn_sig.bound_lifetimes, fn_sig.is_unsafe
+    )?;
+
+    write_optional_string(
+        fn_sig.abi.as_ref().map(|abi| match abi {
+            Abi::C => "C",
+            Abi::Named(name) => name,
+        }),
+        out,
+    )?;
+
+    out.write_str(",\"argument_types\":")?;
+    write_list(&fn_sig.argument_types, out, |type_, out| write_type(type_, out))?;
+    out.write_str(",\"return_type\":")?;
+    write_type(&fn_sig.return_type, out)
+}
+
+fn write_dyn_bounds(dyn_bounds: &DynBounds, out: &mut Formatter) -> fmt::Result {
+    write!(out, "\"bound_lifetimes\":{},\"traits\":", dyn_bounds.bound_lifetimes)?;
+
+    write_list(&dyn_bounds.dyn_traits, out, |dyn_trait, out| {
+        out.write_str("{\"path\":")?;
+        write_path(&dyn_trait.path, out)?;
+        out.write_str(",\"assoc_bindings\":")?;
+
+        write_list(&dyn_trait.dyn_trait_assoc_bindings, out, |binding, out| {
+            out.write_str("{\"name\":")?;
+            write_string(&binding.display_name().to_string(), out)?;
+            out.write_str(",\"type\":")?;
+            write_type(&binding.type_, out)?;
+            out.write_char('}')
+        })?;
+
+        out.write_char('}')
+    })
+}
+
+fn write_type(type_: &Type, out: &mut Formatter) -> fmt::Result {
+    match type_ {
+        Type::Basic(basic_type) => {
+            out.write_str("{\"kind\":\"basic\",\"name\":")?;
+            write_string(&basic_type.to_string(), out)?;
+        }
+        Type::Named(path) => {
+            out.write_str("{\"kind\":\"named\",\"path\":")?;
+            write_path(path, out)?;
+        }
+        Type::Array(type_, length) => {
+            out.write_str("{\"kind\":\"array\",\"element_type\":")?;
+            write_type(type_, out)?;
+            out.write_str(",\"length\":")?;
+            write_const(length, out)?;
+        }
+        Type::Slice(type_) => {
+            out.write_str("{\"kind\":\"slice\",\"element_type\":")?;
+            write_type(type_, out)?;
+        }
+        Type::Tuple(element_types) => {
+            out.write_str("{\"kind\":\"tuple\",\"types\":")?;
+            write_list(element_types, out, |type_, out| write_type(type_, out))?;
+        }
+        Type::Ref { lifetime, type_: inner } | Type::RefMut { lifetime, type_: inner } => {
+            write!(
+                out,
+                "{{\"kind\":\"ref\",\"mutable\":{},\"lifetime\":{lifetime},\"type\":",
+                matches!(type_, Type::RefMut { .. })
+            )?;
+
+            write_type(inner, out)?;
+        }
+        Type::PtrConst(inner) | Type::PtrMut(inner) => {
+            write!(
+                out,
+                "{{\"kind\":\"ptr\",\"mutable\":{},\"type\":",
+                matches!(type_, Type::PtrMut(_))
+            )?;
+
+            write_type(inner, out)?;
+        }
+        Type::Fn(fn_sig) => {
+            out.write_str("{\"kind\":\"fn\",")?;
+            write_fn_sig(fn_sig, out)?;
+        }
+        Type::DynTrait { dyn_bounds, lifetime } => {
+            out.write_str("{\"kind\":\"dyn_trait\",")?;
+            write_dyn_bounds(dyn_bounds, out)?;
+            write!(out, ",\"lifetime\":{lifetime}")?;
+        }
+    }
+
+    out.write_char('}')
+}
+
+fn write_int(type_name: &str, value: impl Display, out: &mut Formatter) -> fmt::Result {
+    write!(
+        out,
+        "{{\"kind\":\"int\",\"type\":\"{type_name}\",\"value\":\"{value}\"}}"
+    )
+}
+
+#[allow(clippy::use_debug)] // `Debug` keeps the decimal point of integral values, like `1.0`.
+fn write_float(type_name: &str, value: impl Debug, out: &mut Formatter) -> fmt::Result {
+    write!(
+        out,
+        "{{\"kind\":\"float\",\"type\":\"{type_name}\",\"value\":\"{value:?}\"}}"
+    )
+}
+
+fn write_const(const_: &Const, out: &mut Formatter) -> fmt::Result {
+    match const_ {
+        Const::I8(value) => write_int("i8", value, out),
+        Const::U8(value) => write_int("u8", value, out),
+        Const::Isize(value) => write_int("isize", value, out),
+        Const::Usize(value) => write_int("usize", value, out),
+        Const::I32(value) => write_int("i32", value, out),
+        Const::U32(value) => write_int("u32", value, out),
+        Const::I128(value) => write_int("i128", value, out),
+        Const::U128(value) => write_int("u128", value, out),
+        Const::I16(value) => write_int("i16", value, out),
+        Const::U16(value) => write_int("u16", value, out),
+        Const::I64(value) => write_int("i64", value, out),
+        Const::U64(value) => write_int("u64", value, out),
+        Const::Bool(value) => write!(out, "{{\"kind\":\"bool\",\"value\":{value}}}"),
+        Const::Char(value) => {
+            out.write_str("{\"kind\":\"char\",\"value\":")?;
+            write_string(value.encode_utf8(&mut [0; 4]), out)?;
+            out.write_char('}')
+        }
+        Const::Str(value) => {
+            out.write_str("{\"kind\":\"str\",\"value\":")?;
+            write_string(&value.decode(), out)?;
+            out.write_char('}')
+        }
+        Const::Ref(value) | Const::RefMut(value) => {
+            write!(
+                out,
+                "{{\"kind\":\"ref\",\"mutable\":{},\"value\":",
+                matches!(const_, Const::RefMut(_))
+            )?;
+
+            write_const(value, out)?;
+            out.write_char('}')
+        }
+        Const::Array(items) | Const::Tuple(items) => {
+            write!(
+                out,
+                "{{\"kind\":\"{}\",\"items\":",
+                if matches!(const_, Const::Array(_)) {
+                    "array"
+                } else {
+                    "tuple"
+                }
+            )?;
+
+            write_list(items, out, |item, out| write_const(item, out))?;
+            out.write_char('}')
+        }
+        Const::NamedStruct { path, fields } => {
+            out.write_str("{\"kind\":\"struct\",\"path\":")?;
+            write_path(path, out)?;
+            out.write_str(",\"fields\":")?;
+
+            match fields {
+                ConstFields::Unit => out.write_str("{\"kind\":\"unit\"}")?,
+                ConstFields::Tuple(items) => {
+                    out.write_str("{\"kind\":\"tuple\",\"items\":")?;
+                    write_list(items, out, |item, out| write_const(item, out))?;
+                    out.write_char('}')?;
+                }
+                ConstFields::Struct(fields) => {
+                    out.write_str("{\"kind\":\"struct\",\"fields\":")?;
+
+                    write_list(fields, out, |(name, value), out| {
+                        out.write_str("{\"name\":")?;
+                        write_identifier(name, out)?;
+                        out.write_str(",\"value\":")?;
+                        write_const(value, out)?;
+                        out.write_char('}')
+                    })?;
+
+                    out.write_char('}')?;
+                }
+            }
+
+            out.write_char('}')
+        }
+        Const::Placeholder => out.write_str("{\"kind\":\"placeholder\"}"),
+        Const::F32(bits) => write_float("f32", f32::from_bits(*bits), out),
+        Const::F64(bits) => write_float("f64", f64::from_bits(*bits), out),
+    }
+}
+
+fn write_symbol(symbol: &Symbol, out: &mut Formatter) -> fmt::Result {
+    out.write_str("{\"version\":")?;
+
+    match symbol.version {
+        None => out.write_str("null")?,
+        Some(version) => write!(out, "{version}")?,
+    }
+
+    out.write_str(",\"path\":")?;
+    write_path(&symbol.path, out)?;
+    out.write_str(",\"instantiating_crate\":")?;
+
+    match &symbol.instantiating_crate {
+        None => out.write_str("null")?,
+        Some(path) => write_path(path, out)?,
+    }
+
+    out.write_str(",\"vendor_specific_suffix\":")?;
+    write_optional_string(symbol.vendor_specific_suffix.as_deref(), out)?;
+    out.write_char('}')
+}
+
+pub fn display_json<'s>(symbol: &'s Symbol) -> impl Display + 's {
+    struct Wrapper<'s, 'a>(&'s Symbol<'a>);
+
+    impl Display for Wrapper<'_, '_> {
+        fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+            write_symbol(self.0, f)
+        }
+    }
+
+    Wrapper(symbol)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rust_v0::Symbol;
+    use serde_json::json;
+
+    fn to_value(name: &str) -> serde_json::Value {
+        serde_json::from_str(&Symbol::parse_from_str(name).unwrap().0.to_json()).unwrap()
+    }
+
+    #[test]
+    fn test_to_json() {
+        assert_eq!(
+            to_value("_RINvNtCs1234_3std3mem4swapRL_hKj1_E.llvm.12"),
+            json!({
+                "version": null,
+                "path": {
+                    "kind": "generic",
+                    "path": {
+                        "kind": "nested",
+                        "namespace": "v",
+                        "path": {
+                            "kind": "nested",
+                            "namespace": "t",
+                            "path": {
+                                "kind": "crate_root",
+                                "identifier": {"name": "std", "disambiguator": "246208"}
+                            },
+                            "identifier": {"name": "mem", "disambiguator": "0"}
+                        },
+                        "identifier": {"name": "swap", "disambiguator": "0"}
+                    },
+                    "generic_args": [
+                        {
+                            "kind": "type",
+                            "type": {
+                                "kind": "ref",
+                                "mutable": false,
+                                "lifetime": 0,
+                                "type": {"kind": "basic", "name": "u8"}
+                            }
+                        },
+                        {"kind": "const", "const": {"kind": "int", "type": "usize", "value": "1"}}
+                    ]
+                },
+                "instantiating_crate": null,
+                "vendor_specific_suffix": ".llvm.12"
+            })
+        );
+    }
+
+    #[test]
+    fn test_to_json_consts() {
+        let value = to_value("_RINvC3foo3barKVNtC3foo3BazS1xc22_1yRe3a2262_EE");
+
+        assert_eq!(
+            value["path"]["generic_args"][0]["const"],
+            json!({
+                "kind": "struct",
+                "path": {
+                    "kind": "nested",
+                    "namespace": "t",
+                    "path": {"kind": "crate_root", "identifier": {"name": "foo", "disambiguator": "0"}},
+                    "identifier": {"name": "Baz", "disambiguator": "0"}
+                },
+                "fields": {
+                    "kind": "struct",
+                    "fields": [
+                        {
+                            "name": {"name": "x", "disambiguator": "0"},
+                            "value": {"kind": "char", "value": "\""}
+                        },
+                        {
+                            "name": {"name": "y", "disambiguator": "0"},
+                            "value": {"kind": "ref", "mutable": false, "value": {"kind": "str", "value": ":\"b"}}
+                        }
+                    ]
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn test_to_json_types() {
+        let value = to_value("_RINvC3foo3barFUKCPDNtC3foo3BazEL_EuE");
+
+        assert_eq!(
+            value["path"]["generic_args"][0]["type"],
+            json!({
+                "kind": "fn",
+                "bound_lifetimes": 0,
+                "unsafe": true,
+                "abi": "C",
+                "argument_types": [
+                    {
+                        "kind": "ptr",
+                        "mutable": false,
+                        "type": {
+                            "kind": "dyn_trait",
+                            "bound_lifetimes": 0,
+                            "traits": [
+                                {
+                                    "path": {
+                                        "kind": "nested",
+                                        "namespace": "t",
+                                        "path": {
+                                            "kind": "crate_root",
+                                            "identifier": {"name": "foo", "disambiguator": "0"}
+                                        },
+                                        "identifier": {"name": "Baz", "disambiguator": "0"}
+                                    },
+                                    "assoc_bindings": []
+                                }
+                            ],
+                            "lifetime": 0
+                        }
+                    }
+                ],
+                "return_type": {"kind": "basic", "name": "()"}
+            })
+        );
+    }
+}
@@ -0,0 +1,97 @@
+//! Classifying symbols that compilers generate for well-known purposes.
+
+use crate::rust_v0::{Namespace, Path, Symbol};
+
+/// The kind of a [`Symbol`], recognized from the structure of its path, see [`Symbol::kind`].
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[non_exhaustive]
+pub enum SymbolKind {
+    /// An instantiation of `core::ptr::drop_in_place`, the drop glue of a type.
+    DropInPlace,
+    /// A closure body, like `foo::bar::{closure#0}`.
+    Closure,
+    /// A shim that adapts a trait method to be called through a vtable, like `foo::bar::{shim:vtable#0}`.
+    VtableShim,
+    /// A shim that turns an item into a function pointer, like `foo::bar::{shim:reify#0}`.
+    ReifyShim,
+    /// Any other shim.
+    Shim,
+    /// Any other item.
+    Item,
+}
+
+fn without_generic_args<'p, 'a>(mut path: &'p Path<'a>) -> &'p Path<'a> {
+    while let Path::Generic { path: inner, .. } = path {
+        path = inner;
+    }
+
+    path
+}
+
+fn is_nested_in(path: &Path, names: &[&str]) -> bool {
+    match (without_generic_args(path), names.split_last()) {
+        (Path::CrateRoot(identifier), Some((name, []))) => identifier.name == *name,
+        (
+            Path::Nested {
+                namespace: Namespace::Type,
+                path,
+                identifier,
+            },
+            Some((name, rest)),
+        ) => identifier.name == *name && is_nested_in(path, rest),
+        _ => false,
+    }
+}
+
+pub fn symbol_kind(symbol: &Symbol) -> SymbolKind {
+    match without_generic_args(&symbol.path) {
+        Path::Nested {
+            namespace: Namespace::Value,
+            path,
+            identifier,
+        } if identifier.name == "drop_in_place" && is_nested_in(path, &["core", "ptr"]) => SymbolKind::DropInPlace,
+        Path::Nested {
+            namespace: Namespace::Closure,
+            ..
+        } => SymbolKind::Closure,
+        Path::Nested {
+            namespace: Namespace::Shim,
+            identifier,
+            ..
+        } => match identifier.name.as_ref() {
+            "vtable" => SymbolKind::VtableShim,
+            "reify" => SymbolKind::ReifyShim,
+            _ => SymbolKind::Shim,
+        },
+        _ => SymbolKind::Item,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SymbolKind;
+    use crate::rust_v0::Symbol;
+
+    #[test]
+    fn test_symbol_kind() {
+        let test_cases = [
+            ("_RINvNtC4core3ptr13drop_in_placeNtC3foo3BarE", SymbolKind::DropInPlace),
+            (
+                "_RINvNtCs1234_4core3ptr13drop_in_placeNtC3foo3BarE",
+                SymbolKind::DropInPlace,
+            ),
+            ("_RINvNtC3foo3ptr13drop_in_placeNtC3foo3BarE", SymbolKind::Item),
+            ("_RNCNvC3foo3bar0", SymbolKind::Closure),
+            ("_RNCINvC3foo3barhE0", SymbolKind::Closure),
+            ("_RNSNvC3foo3bar6vtable", SymbolKind::VtableShim),
+            ("_RNSNvC3foo3bar5reify", SymbolKind::ReifyShim),
+            ("_RNSNvC3foo3bar5clone", SymbolKind::Shim),
+            ("_RNvNtC3std3mem4swap", SymbolKind::Item),
+            ("_RC3foo", SymbolKind::Item),
+        ];
+
+        for (name, expected) in test_cases {
+            assert_eq!(Symbol::parse_from_str(name).unwrap().0.kind(), expected, "{name}");
+        }
+    }
+}
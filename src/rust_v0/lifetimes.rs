@@ -0,0 +1,239 @@
+//! Resolving lifetimes to the binders that introduce them.
+
+use crate::rust_v0::{self, GenericArg, NodeRc, Path, Symbol, Type, Visitor};
+
+/// The place a lifetime appears in.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum LifetimeSite<'a> {
+    /// The lifetime is the generic argument at `index` of a [`Path::Generic`] path.
+    GenericArg { path: NodeRc<Path<'a>>, index: usize },
+    /// The lifetime of a [`Type::Ref`], [`Type::RefMut`] or [`Type::DynTrait`] type.
+    Type(NodeRc<Type<'a>>),
+}
+
+/// What a lifetime refers to.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum LifetimeBinding<'a> {
+    /// The erased lifetime, displayed as `'_`.
+    Erased,
+    /// A lifetime introduced by the `for<...>` binder of `binder`, which is a [`Type::Fn`] or a [`Type::DynTrait`]
+    /// type. `index` is the position of the lifetime among the lifetimes of the binder, and `position` is its position
+    /// among all lifetimes bound by the enclosing binders, counted from the outermost binder. The built-in printer
+    /// names lifetimes after their `position`, so 0 is `'a` and 1 is `'b`.
+    Bound {
+        binder: NodeRc<Type<'a>>,
+        index: u64,
+        position: u64,
+    },
+    /// A lifetime that refers past the outermost binder, or that is enclosed by binders binding more lifetimes in total
+    /// than a `u64` counts. Either makes the symbol not displayable.
+    Unbound,
+}
+
+/// A lifetime in a symbol together with what it refers to, see [`Symbol::resolve_lifetimes`].
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct ResolvedLifetime<'a> {
+    /// The place the lifetime appears in.
+    pub site: LifetimeSite<'a>,
+    /// The lifetime as it is stored in the AST, which is 0 for the erased lifetime, or the de Bruijn index of the
+    /// lifetime otherwise.
+    pub lifetime: u64,
+    /// What the lifetime refers to.
+    pub binding: LifetimeBinding<'a>,
+}
+
+/// Tracks the binders enclosing the visited node. Shared subtrees are visited at every place they appear in, because
+/// their lifetimes may resolve differently in each place.
+#[derive(Default)]
+struct LifetimeResolver<'a> {
+    binders: Vec<(NodeRc<Type<'a>>, u64)>,
+    lifetimes: Vec<ResolvedLifetime<'a>>,
+}
+
+impl<'a> LifetimeResolver<'a> {
+    fn resolve(&self, lifetime: u64) -> LifetimeBinding<'a> {
+        if lifetime == 0 {
+            return LifetimeBinding::Erased;
+        }
+
+        let Some(position) = self
+            .binders
+            .iter()
+            .try_fold(0_u64, |depth, (_, count)| depth.checked_add(*count))
+            .and_then(|depth| depth.checked_sub(lifetime))
+        else {
+            return LifetimeBinding::Unbound;
+        };
+
+        let mut start = 0;
+
+        for (binder, count) in &self.binders {
+            if position < start + count {
+                return LifetimeBinding::Bound {
+                    binder: NodeRc::clone(binder),
+                    index: position - start,
+                    position,
+                };
+            }
+
+            start += count;
+        }
+
+        unreachable!()
+    }
+
+    fn add(&mut self, site: LifetimeSite<'a>, lifetime: u64) {
+        let binding = self.resolve(lifetime);
+
+        self.lifetimes.push(ResolvedLifetime {
+            site,
+            lifetime,
+            binding,
+        });
+    }
+
+    fn with_binder(&mut self, binder: &NodeRc<Type<'a>>, bound_lifetimes: u64, f: impl FnOnce(&mut Self)) {
+        self.binders.push((NodeRc::clone(binder), bound_lifetimes));
+        f(self);
+        self.binders.pop();
+    }
+}
+
+impl<'a> Visitor<'a> for LifetimeResolver<'a> {
+    fn visit_path(&mut self, path: &NodeRc<Path<'a>>) {
+        if let Path::Generic {
+            path: base,
+            generic_args,
+        } = path.as_ref()
+        {
+            self.visit_path(base);
+
+            for (index, generic_arg) in generic_args.iter().enumerate() {
+                if let GenericArg::Lifetime(lifetime) = generic_arg {
+                    self.add(
+                        LifetimeSite::GenericArg {
+                            path: NodeRc::clone(path),
+                            index,
+                        },
+                        *lifetime,
+                    );
+                } else {
+                    self.visit_generic_arg(generic_arg);
+                }
+            }
+        } else {
+            rust_v0::walk_path(self, path);
+        }
+    }
+
+    fn visit_type(&mut self, type_: &NodeRc<Type<'a>>) {
+        match type_.as_ref() {
+            Type::Ref { lifetime, type_: inner } | Type::RefMut { lifetime, type_: inner } => {
+                self.add(LifetimeSite::Type(NodeRc::clone(type_)), *lifetime);
+                self.visit_type(inner);
+            }
+            Type::Fn(fn_sig) => {
+                self.with_binder(type_, fn_sig.bound_lifetimes, |this| this.visit_fn_sig(fn_sig));
+            }
+            Type::DynTrait { dyn_bounds, lifetime } => {
+                self.with_binder(type_, dyn_bounds.bound_lifetimes, |this| {
+                    this.visit_dyn_bounds(dyn_bounds);
+                });
+                self.add(LifetimeSite::Type(NodeRc::clone(type_)), *lifetime);
+            }
+            _ => rust_v0::walk_type(self, type_),
+        }
+    }
+}
+
+pub fn resolve_lifetimes<'a>(symbol: &Symbol<'a>) -> Vec<ResolvedLifetime<'a>> {
+    let mut resolver = LifetimeResolver::default();
+
+    resolver.visit_path(&symbol.path);
+
+    resolver.lifetimes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LifetimeBinding, LifetimeSite};
+    use crate::rust_v0::{DisplayStyle, GenericArg, NodeRc, Path, Symbol, Type};
+
+    #[test]
+    fn test_resolve_lifetimes() {
+        let (symbol, _) =
+            Symbol::parse_from_str("_RINvCs_3foo3barL_FG_RL0_hRL0_DG_INtCs_3baz5TraitL0_EEL0_EuE").unwrap();
+
+        assert_eq!(
+            symbol.display(DisplayStyle::Normal).to_string(),
+            "foo::bar::<'_, for<'a> fn(&'a u8, &'a dyn for<'b> baz::Trait<'b> + 'a)>"
+        );
+
+        let lifetimes = symbol.resolve_lifetimes();
+        let Path::Generic { generic_args, .. } = symbol.path.as_ref() else {
+            panic!();
+        };
+        let GenericArg::Type(fn_type) = &generic_args[1] else {
+            panic!();
+        };
+        let Type::Fn(fn_sig) = fn_type.as_ref() else {
+            panic!();
+        };
+        let Type::Ref { type_: dyn_type, .. } = fn_sig.argument_types[1].as_ref() else {
+            panic!();
+        };
+        let Type::DynTrait { dyn_bounds, .. } = dyn_type.as_ref() else {
+            panic!();
+        };
+
+        assert_eq!(lifetimes.len(), 5);
+
+        assert_eq!(
+            lifetimes[0].site,
+            LifetimeSite::GenericArg {
+                path: NodeRc::clone(&symbol.path),
+                index: 0
+            }
+        );
+        assert_eq!(lifetimes[0].binding, LifetimeBinding::Erased);
+
+        for (lifetime, site) in [
+            (&lifetimes[1], &fn_sig.argument_types[0]),
+            (&lifetimes[2], &fn_sig.argument_types[1]),
+            (&lifetimes[4], dyn_type),
+        ] {
+            assert_eq!(lifetime.site, LifetimeSite::Type(NodeRc::clone(site)));
+            assert_eq!(lifetime.lifetime, 1);
+            assert_eq!(
+                lifetime.binding,
+                LifetimeBinding::Bound {
+                    binder: NodeRc::clone(fn_type),
+                    index: 0,
+                    position: 0
+                }
+            );
+        }
+
+        assert_eq!(
+            lifetimes[3].site,
+            LifetimeSite::GenericArg {
+                path: NodeRc::clone(&dyn_bounds.dyn_traits[0].path),
+                index: 0
+            }
+        );
+        assert_eq!(
+            lifetimes[3].binding,
+            LifetimeBinding::Bound {
+                binder: NodeRc::clone(dyn_type),
+                index: 0,
+                position: 1
+            }
+        );
+
+        let (symbol, _) = Symbol::parse_from_str("_RINvCs_3foo3barL0_E").unwrap();
+        let lifetimes = symbol.resolve_lifetimes();
+
+        assert_eq!(lifetimes.len(), 1);
+        assert_eq!(lifetimes[0].binding, LifetimeBinding::Unbound);
+    }
+}
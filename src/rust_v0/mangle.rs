@@ -0,0 +1,679 @@
+use crate::rust_v0::{
+    Abi, Const, ConstFields, DynBounds, FnSig, GenericArg, Identifier, ImplPath, NodeRc, Path, Symbol, Type,
+};
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::ops::Range;
+
+// Back references are emitted the same way as
+// <https://github.com/rust-lang/rust/blob/master/compiler/rustc_symbol_mangling/src/v0.rs> does, so symbols produced by
+// rustc are mangled back into the same strings.
+
+const PREFIX: &str = "_R";
+
+/// The encoded range of a path, type or constant in a mangled name, relative to the end of the prefix.
+#[derive(Clone, Debug)]
+pub struct MangledNode {
+    pub range: Range<usize>,
+    /// The position of the referenced node if the node is encoded as a back reference.
+    pub back_ref: Option<usize>,
+}
+
+pub fn mangle_symbol(symbol: &Symbol) -> String {
+    mangle(symbol, None)
+}
+
+/// Returns the 64-bit FNV-1a hash of the mangled name of `symbol` without its vendor specific suffix.
+pub fn fingerprint(symbol: &Symbol) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+
+    let mangled = mangle_symbol(symbol);
+    let suffix_length = symbol.vendor_specific_suffix.as_ref().map_or(0, |suffix| suffix.len());
+
+    mangled.as_bytes()[..mangled.len() - suffix_length]
+        .iter()
+        .fold(OFFSET_BASIS, |hash, &byte| (hash ^ u64::from(byte)).wrapping_mul(PRIME))
+}
+
+/// Mangles `symbol`, also returning the encoded ranges of its paths, types and constants in the order they are
+/// written, which is the preorder of the AST where nodes encoded as back references have no children.
+pub fn mangle_symbol_with_nodes(symbol: &Symbol) -> (String, Vec<MangledNode>) {
+    let mut nodes = Vec::new();
+    let mangled = mangle(symbol, Some(&mut nodes));
+
+    (mangled, nodes)
+}
+
+fn mangle(symbol: &Symbol, nodes: Option<&mut Vec<MangledNode>>) -> String {
+    let mut mangler = Mangler {
+        out: String::from(PREFIX),
+        paths: HashMap::new(),
+        types: HashMap::new(),
+        consts: HashMap::new(),
+        trait_refs: HashMap::new(),
+        nodes,
+        last_back_ref: 0,
+    };
+
+    if let Some(version) = symbol.version {
+        write!(mangler.out, "{version}").unwrap();
+    }
+
+    mangler.write_path(&symbol.path);
+
+    if let Some(instantiating_crate) = &symbol.instantiating_crate {
+        mangler.write_path(instantiating_crate);
+    }
+
+    if let Some(suffix) = &symbol.vendor_specific_suffix {
+        mangler.out.push_str(suffix);
+    }
+
+    mangler.out
+}
+
+struct Mangler<'s, 'a, 'n> {
+    out: String,
+    /// Positions of mangled nodes, relative to the end of the prefix.
+    paths: HashMap<&'s Path<'a>, usize>,
+    types: HashMap<&'s Type<'a>, usize>,
+    consts: HashMap<&'s Const<'a>, usize>,
+    /// Positions of traits in trait implementations and qualified paths, keyed by the self types and the traits. rustc
+    /// records these positions even if the traits themselves are back references.
+    trait_refs: HashMap<(&'s Type<'a>, &'s Path<'a>), usize>,
+    nodes: Option<&'n mut Vec<MangledNode>>,
+    /// The position referenced by the last written back reference.
+    last_back_ref: usize,
+}
+
+impl<'s, 'a> Mangler<'s, 'a, '_> {
+    fn position(&self) -> usize {
+        self.out.len() - PREFIX.len()
+    }
+
+    fn write_back_ref(&mut self, position: usize) {
+        self.out.push('B');
+        self.write_base62_number(position as u64);
+        self.last_back_ref = position;
+    }
+
+    /// Calls `write` to write a path, type or constant, recording its range if requested.
+    fn write_node(&mut self, write: impl FnOnce(&mut Self)) {
+        let start = self.position();
+
+        let Some(nodes) = &mut self.nodes else {
+            return write(self);
+        };
+
+        let index = nodes.len();
+
+        nodes.push(MangledNode {
+            range: start..start,
+            back_ref: None,
+        });
+
+        write(self);
+
+        let end = self.position();
+
+        if let Some(nodes) = &mut self.nodes {
+            // A node starting with a back reference may still have a child, like a named type whose path is a back
+            // reference. Only nodes without children are back references themselves.
+            let back_ref = (nodes.len() == index + 1 && self.out.as_bytes()[PREFIX.len() + start] == b'B')
+                .then_some(self.last_back_ref);
+
+            nodes[index] = MangledNode {
+                range: start..end,
+                back_ref,
+            };
+        }
+    }
+
+    fn write_path(&mut self, path: &'s Path<'a>) {
+        self.write_node(|this| this.write_path_node(path));
+    }
+
+    fn write_path_node(&mut self, path: &'s Path<'a>) {
+        if let Some(&position) = self.paths.get(path) {
+            return self.write_back_ref(position);
+        }
+
+        let start = self.position();
+
+        match path {
+            Path::CrateRoot(identifier) => {
+                self.out.push('C');
+                self.write_identifier(identifier);
+            }
+            Path::InherentImpl { impl_path, type_ } => {
+                self.out.push('M');
+                self.write_impl_path(impl_path);
+                self.write_type(type_);
+            }
+            Path::TraitImpl {
+                impl_path,
+                type_,
+                trait_,
+            } => {
+                self.out.push('X');
+                self.write_impl_path(impl_path);
+                self.write_type(type_);
+                self.write_trait_ref(type_, trait_);
+            }
+            Path::TraitDefinition { type_, trait_ } => {
+                self.out.push('Y');
+                self.write_type(type_);
+                self.write_trait_ref(type_, trait_);
+            }
+            Path::Nested {
+                namespace,
+                path,
+                identifier,
+            } => {
+                self.out.push('N');
+                self.out.push(namespace.as_char());
+                self.write_path(path);
+                self.write_identifier(identifier);
+            }
+            Path::Generic { path, generic_args } => {
+                self.out.push('I');
+                self.write_path(path);
+
+                for generic_arg in generic_args {
+                    self.write_generic_arg(generic_arg);
+                }
+
+                self.out.push('E');
+            }
+        }
+
+        // Paths referring to enclosing binders mean different things in different contexts. Like rustc, qualified paths
+        // are not back referenced, only the definition paths containing them are.
+        if !matches!(path, Path::TraitDefinition { .. }) && !path_has_escaping_lifetimes(path, 0) {
+            self.paths.insert(path, start);
+        }
+    }
+
+    fn write_trait_ref(&mut self, self_type: &'s Type<'a>, trait_: &'s Path<'a>) {
+        if let Some(&position) = self.trait_refs.get(&(self_type, trait_)) {
+            return self.write_node(|this| this.write_back_ref(position));
+        }
+
+        let start = self.position();
+
+        self.write_path(trait_);
+
+        if !type_has_escaping_lifetimes(self_type, 0) && !path_has_escaping_lifetimes(trait_, 0) {
+            self.trait_refs.insert((self_type, trait_), start);
+        }
+    }
+
+    fn write_impl_path(&mut self, impl_path: &'s ImplPath<'a>) {
+        self.write_disambiguator(impl_path.disambiguator);
+        self.write_path(&impl_path.path);
+    }
+
+    fn write_identifier(&mut self, identifier: &Identifier) {
+        self.write_disambiguator(identifier.disambiguator);
+        self.write_undisambiguated_identifier(&identifier.name, identifier.is_undecoded_punycode);
+    }
+
+    fn write_disambiguator(&mut self, disambiguator: u64) {
+        if let Some(value) = disambiguator.checked_sub(1) {
+            self.out.push('s');
+            self.write_base62_number(value);
+        }
+    }
+
+    fn write_undisambiguated_identifier(&mut self, name: &str, is_undecoded_punycode: bool) {
+        let punycode = if is_undecoded_punycode {
+            Some(name.to_string())
+        } else {
+            encode_punycode_identifier(name)
+        };
+
+        let name = if let Some(punycode) = &punycode {
+            self.out.push('u');
+
+            punycode
+        } else {
+            name
+        };
+
+        write!(self.out, "{}", name.len()).unwrap();
+
+        if name.starts_with(|c: char| c == '_' || c.is_ascii_digit()) {
+            self.out.push('_');
+        }
+
+        self.out.push_str(name);
+    }
+
+    fn write_generic_arg(&mut self, generic_arg: &'s GenericArg<'a>) {
+        match generic_arg {
+            GenericArg::Lifetime(lifetime) => self.write_lifetime(*lifetime),
+            GenericArg::Type(type_) => self.write_type(type_),
+            GenericArg::Const(const_) => {
+                self.out.push('K');
+                self.write_const(const_);
+            }
+        }
+    }
+
+    fn write_lifetime(&mut self, lifetime: u64) {
+        self.out.push('L');
+        self.write_base62_number(lifetime);
+    }
+
+    /// Writes the lifetime of a reference, which is omitted if erased.
+    fn write_ref_lifetime(&mut self, lifetime: u64) {
+        if lifetime != 0 {
+            self.write_lifetime(lifetime);
+        }
+    }
+
+    fn write_binder(&mut self, bound_lifetimes: u64) {
+        if let Some(value) = bound_lifetimes.checked_sub(1) {
+            self.out.push('G');
+            self.write_base62_number(value);
+        }
+    }
+
+    fn write_type(&mut self, type_: &'s Type<'a>) {
+        self.write_node(|this| this.write_type_node(type_));
+    }
+
+    fn write_type_node(&mut self, type_: &'s Type<'a>) {
+        if let Type::Basic(basic_type) = type_ {
+            return self.out.push(char::from(basic_type.tag()));
+        }
+
+        if let Some(&position) = self.types.get(type_) {
+            return self.write_back_ref(position);
+        }
+
+        let start = self.position();
+
+        match type_ {
+            Type::Basic(_) => unreachable!(),
+            Type::Named(path) => self.write_path(path),
+            Type::Array(type_, length) => {
+                self.out.push('A');
+                self.write_type(type_);
+                self.write_const(length);
+            }
+            Type::Slice(type_) => {
+                self.out.push('S');
+                self.write_type(type_);
+            }
+            Type::Tuple(tuple_types) => {
+                self.out.push('T');
+
+                for type_ in tuple_types {
+                    self.write_type(type_);
+                }
+
+                self.out.push('E');
+            }
+            Type::Ref { lifetime, type_ } => {
+                self.out.push('R');
+                self.write_ref_lifetime(*lifetime);
+                self.write_type(type_);
+            }
+            Type::RefMut { lifetime, type_ } => {
+                self.out.push('Q');
+                self.write_ref_lifetime(*lifetime);
+                self.write_type(type_);
+            }
+            Type::PtrConst(type_) => {
+                self.out.push('P');
+                self.write_type(type_);
+            }
+            Type::PtrMut(type_) => {
+                self.out.push('O');
+                self.write_type(type_);
+            }
+            Type::Fn(fn_sig) => {
+                self.out.push('F');
+                self.write_fn_sig(fn_sig);
+            }
+            Type::DynTrait { dyn_bounds, lifetime } => {
+                self.out.push('D');
+                self.write_dyn_bounds(dyn_bounds);
+                self.write_lifetime(*lifetime);
+            }
+        }
+
+        if !type_has_escaping_lifetimes(type_, 0) {
+            self.types.insert(type_, start);
+        }
+    }
+
+    fn write_fn_sig(&mut self, fn_sig: &'s FnSig<'a>) {
+        self.write_binder(fn_sig.bound_lifetimes);
+
+        if fn_sig.is_unsafe {
+            self.out.push('U');
+        }
+
+        if let Some(abi) = &fn_sig.abi {
+            self.out.push('K');
+
+            match abi {
+                Abi::C => self.out.push('C'),
+                Abi::Named(name) => self.write_undisambiguated_identifier(name, false),
+            }
+        }
+
+        for argument_type in &fn_sig.argument_types {
+            self.write_type(argument_type);
+        }
+
+        self.out.push('E');
+        self.write_type(&fn_sig.return_type);
+    }
+
+    fn write_dyn_bounds(&mut self, dyn_bounds: &'s DynBounds<'a>) {
+        self.write_binder(dyn_bounds.bound_lifetimes);
+
+        for dyn_trait in &dyn_bounds.dyn_traits {
+            self.write_path(&dyn_trait.path);
+
+            for binding in &dyn_trait.dyn_trait_assoc_bindings {
+                self.out.push('p');
+                self.write_undisambiguated_identifier(&binding.name, binding.is_undecoded_punycode);
+                self.write_type(&binding.type_);
+            }
+        }
+
+        self.out.push('E');
+    }
+
+    fn write_const(&mut self, const_: &'s Const<'a>) {
+        self.write_node(|this| this.write_const_node(const_));
+    }
+
+    fn write_const_node(&mut self, const_: &'s Const<'a>) {
+        if let Const::Placeholder = const_ {
+            return self.out.push('p');
+        }
+
+        if let Some(&position) = self.consts.get(const_) {
+            return self.write_back_ref(position);
+        }
+
+        let start = self.position();
+
+        match const_ {
+            Const::I8(value) => self.write_const_int('a', value.is_negative(), value.unsigned_abs().into()),
+            Const::U8(value) => self.write_const_int('h', false, (*value).into()),
+            Const::Isize(value) => self.write_const_int('i', value.is_negative(), value.unsigned_abs() as u128),
+            Const::Usize(value) => self.write_const_int('j', false, *value as u128),
+            Const::I32(value) => self.write_const_int('l', value.is_negative(), value.unsigned_abs().into()),
+            Const::U32(value) => self.write_const_int('m', false, (*value).into()),
+            Const::I128(value) => self.write_const_int('n', value.is_negative(), value.unsigned_abs()),
+            Const::U128(value) => self.write_const_int('o', false, *value),
+            Const::I16(value) => self.write_const_int('s', value.is_negative(), value.unsigned_abs().into()),
+            Const::U16(value) => self.write_const_int('t', false, (*value).into()),
+            Const::I64(value) => self.write_const_int('x', value.is_negative(), value.unsigned_abs().into()),
+            Const::U64(value) => self.write_const_int('y', false, (*value).into()),
+            Const::Bool(value) => self.write_const_int('b', false, (*value).into()),
+            Const::Char(value) => self.write_const_int('c', false, u32::from(*value).into()),
+            Const::Str(value) => {
+                self.out.push('e');
+
+                for byte in &value.bytes {
+                    write!(self.out, "{byte:02x}").unwrap();
+                }
+
+                self.out.push('_');
+            }
+            Const::Ref(value) => {
+                self.out.push('R');
+                self.write_const(value);
+            }
+            Const::RefMut(value) => {
+                self.out.push('Q');
+                self.write_const(value);
+            }
+            Const::Array(values) => {
+                self.out.push('A');
+                self.write_consts(values);
+            }
+            Const::Tuple(values) => {
+                self.out.push('T');
+                self.write_consts(values);
+            }
+            Const::NamedStruct { path, fields } => {
+                self.out.push('V');
+                self.write_path(path);
+
+                match fields {
+                    ConstFields::Unit => self.out.push('U'),
+                    ConstFields::Tuple(values) => {
+                        self.out.push('T');
+                        self.write_consts(values);
+                    }
+                    ConstFields::Struct(fields) => {
+                        self.out.push('S');
+
+                        for (identifier, value) in fields {
+                            self.write_identifier(identifier);
+                            self.write_const(value);
+                        }
+
+                        self.out.push('E');
+                    }
+                }
+            }
+            Const::Placeholder => unreachable!(),
+            Const::F32(bits) => self.write_const_int('f', false, (*bits).into()),
+            Const::F64(bits) => self.write_const_int('d', false, (*bits).into()),
+        }
+
+        self.consts.insert(const_, start);
+    }
+
+    /// Writes constants followed by an `E`.
+    fn write_consts(&mut self, values: &'s [NodeRc<Const<'a>>]) {
+        for value in values {
+            self.write_const(value);
+        }
+
+        self.out.push('E');
+    }
+
+    fn write_const_int(&mut self, type_code: char, is_negative: bool, magnitude: u128) {
+        self.out.push(type_code);
+
+        if is_negative {
+            self.out.push('n');
+        }
+
+        write!(self.out, "{magnitude:x}_").unwrap();
+    }
+
+    /// Writes `<base-62-number>`, where `_` denotes 0, and `<digits> _` denotes the value of the digits plus 1.
+    fn write_base62_number(&mut self, value: u64) {
+        if let Some(mut value) = value.checked_sub(1) {
+            let mut digits = Vec::new();
+
+            loop {
+                let digit = (value % 62) as u8;
+
+                digits.push(match digit {
+                    0..=9 => b'0' + digit,
+                    10..=35 => b'a' + (digit - 10),
+                    _ => b'A' + (digit - 36),
+                });
+
+                value /= 62;
+
+                if value == 0 {
+                    break;
+                }
+            }
+
+            self.out.extend(digits.iter().rev().map(|&digit| char::from(digit)));
+        }
+
+        self.out.push('_');
+    }
+}
+
+/// Returns whether the lifetime refers to a binder outside of the node being checked, `bound` is the number of
+/// lifetimes bound by binders inside the node.
+fn is_escaping_lifetime(lifetime: u64, bound: u64) -> bool {
+    lifetime > bound
+}
+
+fn path_has_escaping_lifetimes(path: &Path, bound: u64) -> bool {
+    match path {
+        Path::CrateRoot(_) => false,
+        Path::InherentImpl { impl_path, type_ } => {
+            path_has_escaping_lifetimes(&impl_path.path, bound) || type_has_escaping_lifetimes(type_, bound)
+        }
+        Path::TraitImpl {
+            impl_path,
+            type_,
+            trait_,
+        } => {
+            path_has_escaping_lifetimes(&impl_path.path, bound)
+                || type_has_escaping_lifetimes(type_, bound)
+                || path_has_escaping_lifetimes(trait_, bound)
+        }
+        Path::TraitDefinition { type_, trait_ } => {
+            type_has_escaping_lifetimes(type_, bound) || path_has_escaping_lifetimes(trait_, bound)
+        }
+        Path::Nested { path, .. } => path_has_escaping_lifetimes(path, bound),
+        Path::Generic { path, generic_args } => {
+            path_has_escaping_lifetimes(path, bound)
+                || generic_args.iter().any(|generic_arg| match generic_arg {
+                    GenericArg::Lifetime(lifetime) => is_escaping_lifetime(*lifetime, bound),
+                    GenericArg::Type(type_) => type_has_escaping_lifetimes(type_, bound),
+                    GenericArg::Const(_) => false,
+                })
+        }
+    }
+}
+
+fn type_has_escaping_lifetimes(type_: &Type, bound: u64) -> bool {
+    match type_ {
+        Type::Basic(_) => false,
+        Type::Named(path) => path_has_escaping_lifetimes(path, bound),
+        Type::Array(type_, _) | Type::Slice(type_) | Type::PtrConst(type_) | Type::PtrMut(type_) => {
+            type_has_escaping_lifetimes(type_, bound)
+        }
+        Type::Tuple(tuple_types) => tuple_types
+            .iter()
+            .any(|type_| type_has_escaping_lifetimes(type_, bound)),
+        Type::Ref { lifetime, type_ } | Type::RefMut { lifetime, type_ } => {
+            is_escaping_lifetime(*lifetime, bound) || type_has_escaping_lifetimes(type_, bound)
+        }
+        Type::Fn(fn_sig) => {
+            let bound = bound.saturating_add(fn_sig.bound_lifetimes);
+
+            fn_sig
+                .argument_types
+                .iter()
+                .chain(Some(&fn_sig.return_type))
+                .any(|type_| type_has_escaping_lifetimes(type_, bound))
+        }
+        Type::DynTrait { dyn_bounds, lifetime } => {
+            let inner_bound = bound.saturating_add(dyn_bounds.bound_lifetimes);
+
+            is_escaping_lifetime(*lifetime, bound)
+                || dyn_bounds.dyn_traits.iter().any(|dyn_trait| {
+                    path_has_escaping_lifetimes(&dyn_trait.path, inner_bound)
+                        || dyn_trait
+                            .dyn_trait_assoc_bindings
+                            .iter()
+                            .any(|binding| type_has_escaping_lifetimes(&binding.type_, inner_bound))
+                })
+        }
+    }
+}
+
+/// Encodes a non-ASCII identifier name with Punycode the way rustc does, or returns [`None`] for ASCII names, which are
+/// mangled as is.
+pub fn encode_punycode_identifier(name: &str) -> Option<String> {
+    (!name.is_ascii()).then(|| {
+        // Punycode uses `-` as the delimiter, which is replaced with `_` to form a valid identifier.
+        punycode::encode(name).map_or_else(
+            |()| name.to_string(),
+            |mut encoded| {
+                if let Some(i) = encoded.rfind('-') {
+                    encoded.replace_range(i..=i, "_");
+                }
+
+                encoded
+            },
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rust_v0::Symbol;
+
+    #[test]
+    fn test_mangle_round_trip() {
+        let test_cases = [
+            "_RNvCs123_3foo3bar",
+            "_RNvNtCs1234_7mycrate3foo3bar",
+            "_RINvCs123_3foo3barmE",
+            "_RNvMs_NtCs123_3foo3barNtB4_3Baz3new",
+            "_RNvXs0_Cs123_3fooNtB5_3BarNtNtCs456_4core3fmt7Display3fmt",
+            "_RNvYNtCs123_3foo3BarNtB4_5Trait6method",
+            "_RINvCs123_3foo3barReQShE",
+            "_RINvCs123_3foo3barFG0_RL0_hEuE",
+            "_RINvCs123_3foo3barDG_NtB2_5TraitEL_E",
+            "_RINvCs123_3foo3barDNtB2_5Traitp4ItemhEL_E",
+            "_RINvCs123_3foo3barFUKCEuFK6systemEuE",
+            "_RINvCs123_3foo3barKa7f_Kanf_Kb1_Kc41_Ke68656c6c6f_E",
+            "_RINvCs123_3foo3barKRe80ff_E",
+            "_RINvCs123_3foo3barKAa1_a2_EKTRb0_EE",
+            "_RINvCs123_3foo3barKVNtB2_5PointS1xl1_EE",
+            "_RINvCs123_3foo3barKpE",
+            "_RNvNvCs123_3foo3bar6___init",
+            "_RNvCs123_3foo8__1_crate",
+            "_RNvCs123_3foou8gdel_5qa",
+            "_RNvCs123_3foo3bar.llvm.123",
+            "_R0NvCs123_3foo3barCs456_3baz",
+            "_RINvCs123_3foo3barTNtB2_3BazBh_EE",
+        ];
+
+        for name in test_cases {
+            let (symbol, suffix) = Symbol::parse_from_str(name).unwrap();
+
+            assert_eq!(suffix, "", "{name}");
+            assert_eq!(symbol.mangle(), name);
+        }
+
+        // Non-canonical forms are normalized.
+        let (symbol, _) = Symbol::parse_from_str("RINvCs123_3foo3barKh0_E").unwrap();
+
+        assert_eq!(symbol.mangle(), "_RINvCs123_3foo3barKh0_E");
+
+        let (symbol, _) = Symbol::parse_from_str("_RINvCs123_3foo3barKh_E").unwrap();
+
+        assert_eq!(symbol.mangle(), "_RINvCs123_3foo3barKh0_E");
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        // Fingerprints are documented to be stable, so they must not change between versions.
+        let (symbol, _) = Symbol::parse_from_str("_RNvCs123_3foo3bar.llvm.123").unwrap();
+
+        assert_eq!(symbol.fingerprint(), 0x617b_b1ad_6121_1b9a);
+
+        let (symbol, _) = Symbol::parse_from_str("_RINvCs123_3foo3barNtB2_3BazNtB2_3BazE").unwrap();
+        let (unshared, _) = Symbol::parse_from_str("_RINvCs123_3foo3barNtCs123_3foo3BazNtCs123_3foo3BazE").unwrap();
+        let (other, _) = Symbol::parse_from_str("_RINvCs123_3foo3barNtB2_3BazNtB2_3QuxE").unwrap();
+
+        assert_eq!(symbol, unshared);
+        assert_eq!(symbol.fingerprint(), unshared.fingerprint());
+        assert_ne!(symbol.fingerprint(), other.fingerprint());
+    }
+}
@@ -0,0 +1,394 @@
+//! Measuring the size of symbols without displaying them.
+
+use crate::rust_v0::{
+    Abi, Const, ConstFields, DisplayStyle, DynBounds, FnSig, GenericArg, Identifier, Namespace, NodeRc, Path, Symbol,
+    Type,
+};
+use std::collections::HashMap;
+
+/// Size measurements of a symbol, returned by [`Symbol::metrics`]. Counts are taken with back references expanded,
+/// so they describe the symbol as it is displayed rather than as it is encoded. They saturate at [`usize::MAX`].
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct SymbolMetrics {
+    /// The number of path nodes, including the paths of named types, traits and constant structs.
+    pub paths: usize,
+    /// The number of type nodes.
+    pub types: usize,
+    /// The number of constant nodes, including the fields of constant aggregates.
+    pub consts: usize,
+    /// The number of paths, types and constants on the longest chain of nested nodes.
+    pub max_depth: usize,
+    /// The number of times a node that appeared before is used again. For parsed symbols, these are the back
+    /// references of the mangled name.
+    pub back_ref_reuses: usize,
+    /// An estimate of the length of the symbol displayed with [`DisplayStyle::Normal`]. Rare nodes like closures,
+    /// higher-ranked lifetimes and ABIs are counted approximately.
+    pub estimated_display_len: usize,
+}
+
+/// The metrics of a subtree.
+#[derive(Clone, Copy, Default)]
+struct Summary {
+    paths: usize,
+    types: usize,
+    consts: usize,
+    depth: usize,
+    display_len: usize,
+}
+
+impl Summary {
+    fn add(&mut self, other: Self) {
+        self.paths = self.paths.saturating_add(other.paths);
+        self.types = self.types.saturating_add(other.types);
+        self.consts = self.consts.saturating_add(other.consts);
+        self.depth = self.depth.max(other.depth);
+        self.display_len = self.display_len.saturating_add(other.display_len);
+    }
+
+    fn text(&mut self, length: usize) {
+        self.display_len = self.display_len.saturating_add(length);
+    }
+}
+
+/// Computes the summary of each shared subtree once, so back references can not cause exponential work.
+#[derive(Default)]
+struct Measurer {
+    summaries: HashMap<usize, Summary>,
+    reuses: usize,
+}
+
+impl Measurer {
+    fn memoized<T>(&mut self, node: &NodeRc<T>, f: impl FnOnce(&mut Self, &T) -> Summary) -> Summary {
+        let key = NodeRc::as_ptr(node).cast::<()>() as usize;
+
+        if let Some(summary) = self.summaries.get(&key) {
+            self.reuses += 1;
+
+            return *summary;
+        }
+
+        let mut summary = f(self, node);
+
+        summary.depth += 1;
+        self.summaries.insert(key, summary);
+
+        summary
+    }
+
+    /// Measures a comma separated list with `delimiters` around it.
+    fn list<T>(&mut self, items: &[T], delimiters: usize, mut f: impl FnMut(&mut Self, &T) -> Summary) -> Summary {
+        let mut summary = Summary::default();
+
+        for item in items {
+            summary.add(f(self, item));
+        }
+
+        summary.text(delimiters + items.len().saturating_sub(1) * 2);
+
+        summary
+    }
+
+    fn path(&mut self, path: &NodeRc<Path>) -> Summary {
+        self.memoized(path, |this, path| {
+            let mut summary = Summary {
+                paths: 1,
+                ..Summary::default()
+            };
+
+            match path {
+                Path::CrateRoot(identifier) => {
+                    summary.text(name_len(&identifier.name, identifier.is_undecoded_punycode));
+                }
+                Path::InherentImpl { type_, .. } => {
+                    summary.add(this.type_(type_));
+                    summary.text("<>".len());
+                }
+                Path::TraitImpl { type_, trait_, .. } | Path::TraitDefinition { type_, trait_ } => {
+                    summary.add(this.type_(type_));
+                    summary.add(this.path(trait_));
+                    summary.text("< as >".len());
+                }
+                Path::Nested {
+                    namespace,
+                    path,
+                    identifier,
+                } => {
+                    summary.add(this.path(path));
+                    summary.text(identifier_len(*namespace, identifier));
+                }
+                Path::Generic { path, generic_args } => {
+                    summary.add(this.path(path));
+                    summary.add(this.generic_args(generic_args));
+                }
+            }
+
+            summary
+        })
+    }
+
+    fn generic_args(&mut self, generic_args: &[GenericArg]) -> Summary {
+        self.list(generic_args, "<>".len(), |this, generic_arg| match generic_arg {
+            GenericArg::Lifetime(_) => Summary {
+                display_len: "'a".len(),
+                ..Summary::default()
+            },
+            GenericArg::Type(type_) => this.type_(type_),
+            GenericArg::Const(const_) => this.const_(const_),
+        })
+    }
+
+    fn type_(&mut self, type_: &NodeRc<Type>) -> Summary {
+        self.memoized(type_, |this, type_| {
+            let mut summary = Summary {
+                types: 1,
+                ..Summary::default()
+            };
+
+            match type_ {
+                Type::Basic(basic_type) => summary.text(basic_type.as_str().len()),
+                Type::Named(path) => summary.add(this.path(path)),
+                Type::Array(type_, const_) => {
+                    summary.add(this.type_(type_));
+                    summary.add(this.const_(const_));
+                    summary.text("[; ]".len());
+                }
+                Type::Slice(type_) => {
+                    summary.add(this.type_(type_));
+                    summary.text("[]".len());
+                }
+                Type::Tuple(tuple_types) => {
+                    summary.add(this.list(tuple_types, "()".len(), Self::type_));
+                    summary.text(usize::from(tuple_types.len() == 1));
+                }
+                Type::Ref { lifetime, type_ } => {
+                    summary.add(this.type_(type_));
+                    summary.text(ref_len(*lifetime));
+                }
+                Type::RefMut { lifetime, type_ } => {
+                    summary.add(this.type_(type_));
+                    summary.text(ref_len(*lifetime) + "mut ".len());
+                }
+                Type::PtrConst(type_) => {
+                    summary.add(this.type_(type_));
+                    summary.text("*const ".len());
+                }
+                Type::PtrMut(type_) => {
+                    summary.add(this.type_(type_));
+                    summary.text("*mut ".len());
+                }
+                Type::Fn(fn_sig) => summary.add(this.fn_sig(fn_sig)),
+                Type::DynTrait { dyn_bounds, lifetime } => {
+                    summary.add(this.dyn_bounds(dyn_bounds));
+
+                    if *lifetime != 0 {
+                        summary.text(" + 'a".len());
+                    }
+                }
+            }
+
+            summary
+        })
+    }
+
+    fn fn_sig(&mut self, fn_sig: &FnSig) -> Summary {
+        let mut summary = self.list(&fn_sig.argument_types, "fn()".len(), Self::type_);
+
+        if fn_sig.bound_lifetimes != 0 {
+            summary.text("for<'a> ".len());
+        }
+
+        if fn_sig.is_unsafe {
+            summary.text("unsafe ".len());
+        }
+
+        match &fn_sig.abi {
+            None => {}
+            Some(Abi::C) => summary.text("extern \"C\" ".len()),
+            Some(Abi::Named(name)) => summary.text("extern \"\" ".len() + name.len()),
+        }
+
+        let return_type = self.type_(&fn_sig.return_type);
+
+        if !matches!(fn_sig.return_type.as_ref(), Type::Tuple(types) if types.is_empty()) {
+            summary.add(return_type);
+            summary.text(" -> ".len());
+        }
+
+        summary
+    }
+
+    fn dyn_bounds(&mut self, dyn_bounds: &DynBounds) -> Summary {
+        let mut summary = Summary::default();
+
+        summary.text("dyn ".len() + dyn_bounds.dyn_traits.len().saturating_sub(1) * " + ".len());
+
+        if dyn_bounds.bound_lifetimes != 0 {
+            summary.text("for<'a> ".len());
+        }
+
+        for dyn_trait in &dyn_bounds.dyn_traits {
+            summary.add(self.path(&dyn_trait.path));
+
+            let bindings = self.list(&dyn_trait.dyn_trait_assoc_bindings, "<>".len(), |this, binding| {
+                let mut summary = this.type_(&binding.type_);
+
+                summary.text(name_len(&binding.name, binding.is_undecoded_punycode) + " = ".len());
+
+                summary
+            });
+
+            if !dyn_trait.dyn_trait_assoc_bindings.is_empty() {
+                summary.add(bindings);
+            }
+        }
+
+        summary
+    }
+
+    fn const_(&mut self, const_: &NodeRc<Const>) -> Summary {
+        self.memoized(const_, |this, const_| {
+            let mut summary = Summary {
+                consts: 1,
+                ..Summary::default()
+            };
+
+            match const_ {
+                Const::Ref(const_) => {
+                    summary.add(this.const_(const_));
+                    summary.text("&".len());
+                }
+                Const::RefMut(const_) => {
+                    summary.add(this.const_(const_));
+                    summary.text("&mut ".len());
+                }
+                Const::Array(items) => summary.add(this.list(items, "[]".len(), Self::const_)),
+                Const::Tuple(items) => {
+                    summary.add(this.list(items, "()".len(), Self::const_));
+                    summary.text(usize::from(items.len() == 1));
+                }
+                Const::NamedStruct { path, fields } => {
+                    summary.add(this.path(path));
+
+                    match fields {
+                        ConstFields::Unit => {}
+                        ConstFields::Tuple(items) => summary.add(this.list(items, "()".len(), Self::const_)),
+                        ConstFields::Struct(fields) => {
+                            summary.add(this.list(fields, " {  }".len(), |this, (name, const_)| {
+                                let mut summary = this.const_(const_);
+
+                                summary.text(name_len(&name.name, name.is_undecoded_punycode) + ": ".len());
+
+                                summary
+                            }));
+                        }
+                    }
+                }
+                _ => summary.text(const_.display(DisplayStyle::Normal).to_string().len()),
+            }
+
+            summary
+        })
+    }
+}
+
+fn ref_len(lifetime: u64) -> usize {
+    "&".len() + if lifetime == 0 { 0 } else { "'a ".len() }
+}
+
+/// Undecoded Punycode names are displayed wrapped in `punycode{...}`.
+fn name_len(name: &str, is_undecoded_punycode: bool) -> usize {
+    name.len() + if is_undecoded_punycode { "punycode{}".len() } else { 0 }
+}
+
+fn identifier_len(namespace: Namespace, identifier: &Identifier) -> usize {
+    let name_len = name_len(&identifier.name, identifier.is_undecoded_punycode);
+
+    match namespace {
+        Namespace::Closure | Namespace::Shim | Namespace::Other('A'..='Z') => {
+            "::{closure#}".len() + name_len + identifier.disambiguator.to_string().len()
+        }
+        _ if identifier.name.is_empty() => 0,
+        _ => "::".len() + name_len,
+    }
+}
+
+pub fn symbol_metrics(symbol: &Symbol) -> SymbolMetrics {
+    let mut measurer = Measurer::default();
+    let mut summary = measurer.path(&symbol.path);
+
+    // Generic arguments of the symbol path itself are written with turbofishes, like `foo::<u8>`.
+    let mut path = symbol.path.as_ref();
+
+    loop {
+        match path {
+            Path::Nested { path: parent, .. } => path = parent,
+            Path::Generic { path: parent, .. } => {
+                summary.text("::".len());
+                path = parent;
+            }
+            _ => break,
+        }
+    }
+
+    SymbolMetrics {
+        paths: summary.paths,
+        types: summary.types,
+        consts: summary.consts,
+        max_depth: summary.depth,
+        back_ref_reuses: measurer.reuses,
+        estimated_display_len: summary.display_len,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SymbolMetrics;
+    use crate::rust_v0::{DisplayStyle, Symbol};
+
+    #[test]
+    fn test_symbol_metrics() {
+        let (symbol, _) = Symbol::parse_from_str("_RNvCs123_3foo3bar").unwrap();
+
+        assert_eq!(
+            symbol.metrics(),
+            SymbolMetrics {
+                paths: 2,
+                types: 0,
+                consts: 0,
+                max_depth: 2,
+                back_ref_reuses: 0,
+                estimated_display_len: "foo::bar".len(),
+            }
+        );
+
+        let (symbol, _) =
+            Symbol::parse_from_str("_RINvCs_3foo3barTNtCs_3baz3QuxRANtCs_3baz3Quxj3_EKj1_ECs_3app").unwrap();
+        let mangled = symbol.mangle();
+        let (shared, _) = Symbol::parse_from_str(&mangled).unwrap();
+        let displayed = symbol.display(DisplayStyle::Normal).to_string();
+
+        assert_eq!(displayed, "foo::bar::<(baz::Qux, &[baz::Qux; 3]), 1>");
+
+        let metrics = symbol.metrics();
+
+        assert_eq!(
+            metrics,
+            SymbolMetrics {
+                paths: 7,
+                types: 5,
+                consts: 2,
+                max_depth: 7,
+                back_ref_reuses: 0,
+                estimated_display_len: displayed.len(),
+            }
+        );
+
+        assert_eq!(
+            shared.metrics(),
+            SymbolMetrics {
+                back_ref_reuses: 1,
+                ..metrics
+            }
+        );
+    }
+}
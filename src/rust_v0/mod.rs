@@ -1,343 +1,1733 @@
 //! Tools for demangling symbols using
 //! [Rust v0 syntax](https://rust-lang.github.io/rfcs/2603-rust-symbol-name-mangling-v0.html#syntax-of-mangled-names).
+//!
+//! With the `serde` feature, the AST types implement `Serialize` and `Deserialize` following their structure. Subtrees
+//! shared through back references are serialized once for every place they appear in, and are not shared after
+//! deserialization. To exchange symbols as mangled names instead, use `MangledSymbol`.
+//!
+//! Shared AST nodes are held by [`NodeRc`], which is an alias of [`std::rc::Rc`] by default and of [`std::sync::Arc`]
+//! with the `sync` feature, so that symbols are `Send` and `Sync` at the cost of atomic reference counting. The feature
+//! is not additive: it changes the types of public AST fields for every crate in the dependency graph, so code that
+//! names `Rc` in them breaks when any crate enables it. Code building nodes should use [`NodeRc`] to compile either
+//! way.
+//!
+//! With the `parallel` feature, `demangle_all` demangles many symbols on the rayon thread pool.
+//!
+//! With the `color` feature, `Symbol::display_colored` highlights the output with ANSI escape codes.
+//!
+//! With the `arbitrary` feature, `Symbol`, `Path`, `Type` and `Const` implement `arbitrary::Arbitrary`, generating
+//! well-formed ASTs that can be mangled, parsed back and displayed, for fuzzing code that consumes symbols.
+//!
+//! Parsing, [`demangle_to_writer`], [`Symbol::try_display`] and formatting [`Symbol::debug_tree`] never panic,
+//! whatever the input: malformed symbols are reported as errors, and with the default [`ParseOptions`], symbols are
+//! never nested deep enough to overflow the stack. Panics raised by user-supplied writers and renderers are not caught.
+//! Formatting a symbol with [`Display`] reports symbols that can not be displayed as [`fmt::Error`], which
+//! [`ToString::to_string`] turns into a panic, so use [`Symbol::try_display`] where unwinding is not acceptable. The
+//! guarantee covers the APIs listed here; for other schemes, see [`demangle`](crate::demangle).
 
+pub use self::builder::{PathBuilder, SymbolBuilder, TypeBuilder};
+pub use self::cache::{CachedSymbol, ParseCache};
+pub use self::check::{check_symbol, check_symbols, CheckWarning, SymbolCheck};
+#[cfg(feature = "color")]
+pub use self::color::ColorScheme;
+pub use self::demangler::Demangler;
 pub use self::display::Style as DisplayStyle;
-pub use self::display::{DemangleNodeType, DemangleWrite};
+pub use self::display::{DefaultRenderer, DemangleNodeType, DemangleWrite, Renderer};
+pub use self::display_options::{ConstPlaceholder, DisplayOptions, FnReturnType, IntegerFormat};
+pub use self::fold::{
+    super_fold_const, super_fold_dyn_bounds, super_fold_dyn_trait, super_fold_fn_sig, super_fold_generic_arg,
+    super_fold_identifier, super_fold_impl_path, super_fold_path, super_fold_symbol, super_fold_type, Folder,
+};
+pub use self::kind::SymbolKind;
+pub use self::lifetimes::{LifetimeBinding, LifetimeSite, ResolvedLifetime};
+pub use self::metrics::SymbolMetrics;
+pub use self::normalize::{GenericRoot, IgnoringDisambiguators, NormalizeOptions};
+pub use self::owned::Interner;
+#[cfg(feature = "parallel")]
+pub use self::parallel::demangle_all;
+pub use self::pattern::{InvalidPatternError, SymbolPattern};
+pub use self::runtime::{RuntimeSymbol, RuntimeSymbolKind};
+pub use self::rustc_compat::RustcCompatible;
+pub use self::scan::{find_symbols, FindSymbols};
+#[cfg(feature = "serde")]
+pub use self::serde::{DemangledSymbol, MangledSymbol};
+pub use self::sharing::{BackRef, SharedNode, SharedSubtree, SharingGraph};
+pub use self::spans::{NodeSpan, SourceSpans};
+pub use self::stream::PathHead;
+pub use self::tokens::{TokenKind, TokenSink};
+pub use self::validate::Violation;
+pub use self::vendor_suffix::{VendorSuffix, VendorSuffixComponent};
+pub use self::visit::{
+    walk_const, walk_dyn_bounds, walk_dyn_trait, walk_fn_sig, walk_generic_arg, walk_impl_path, walk_path, walk_symbol,
+    walk_type, Visitor,
+};
 use std::borrow::Cow;
-use std::fmt::{self, Debug, Display, Formatter, Write};
-use std::rc::Rc;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::io;
+use std::mem;
+use std::str::{self, FromStr};
 
+/// The reference counted pointer that shares AST nodes. It is [`std::rc::Rc`] by default, and [`std::sync::Arc`] with
+/// the `sync` feature, so symbols can be sent to and shared between threads.
+///
+/// Enabling the feature anywhere in a dependency graph changes the types of the public AST fields for every user of
+/// this crate, so code that constructs or matches on nodes should name this alias rather than either pointer type.
+#[cfg(not(feature = "sync"))]
+pub type NodeRc<T> = std::rc::Rc<T>;
+
+/// The reference counted pointer that shares AST nodes. It is [`std::rc::Rc`] by default, and [`std::sync::Arc`] with
+/// the `sync` feature, so symbols can be sent to and shared between threads.
+///
+/// Enabling the feature anywhere in a dependency graph changes the types of the public AST fields for every user of
+/// this crate, so code that constructs or matches on nodes should name this alias rather than either pointer type.
+#[cfg(feature = "sync")]
+pub type NodeRc<T> = std::sync::Arc<T>;
+
+#[cfg(feature = "arbitrary")]
+mod arbitrary;
+mod builder;
+mod cache;
+mod check;
+#[cfg(feature = "color")]
+mod color;
+mod debug_tree;
+mod demangler;
 mod display;
+mod display_options;
+mod fold;
+mod json;
+mod kind;
+mod lifetimes;
+mod mangle;
+mod metrics;
+mod normalize;
+mod owned;
+#[cfg(feature = "parallel")]
+mod parallel;
 mod parsers;
+mod pattern;
+mod references;
+mod rename;
+mod runtime;
+mod rustc_compat;
+mod scan;
+#[cfg(feature = "serde")]
+mod serde;
+mod sharing;
+mod spans;
+mod stream;
+mod tokens;
+mod validate;
+mod vendor_suffix;
+mod visit;
 
-#[derive(Clone, Debug, Eq, PartialEq)]
-pub struct ParseSymbolError;
-
+/// A mangled name that can not be parsed, used where the reason is not kept, see [`ParseError`] for the reason.
 #[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
-pub struct Symbol<'a> {
-    pub version: Option<u64>,
-    pub path: Rc<Path<'a>>,
-    pub instantiating_crate: Option<Rc<Path<'a>>>,
-    pub vendor_specific_suffix: Option<&'a str>,
-}
+pub struct ParseSymbolError;
 
-impl<'a> Symbol<'a> {
-    /// Returns an object that implements [`Display`] for printing the symbol.
-    #[must_use]
-    pub fn display(&self, style: DisplayStyle) -> impl Display + '_ {
-        display::display_fn(move |f| display::write_path(&self.path, f, style, 0, true))
+impl From<ParseError> for ParseSymbolError {
+    fn from(_: ParseError) -> Self {
+        Self
     }
+}
 
-    /// Parses `input` with Rust
-    /// [v0 syntax](https://rust-lang.github.io/rfcs/2603-rust-symbol-name-mangling-v0.html#syntax-of-mangled-names),
-    /// returns a tuple that contains a [`Symbol`] object and an [`&str`] object containing the suffix that is
-    /// not part of the the Rust v0 syntax.
-    ///
-    /// # Errors
-    ///
-    /// Returns [`ParseSymbolError`] if `input` does not start with a valid prefix with Rust v0 syntax.
-    pub fn parse_from_str(input: &'a str) -> Result<(Self, &'a str), ParseSymbolError> {
-        let input = input
-            .strip_prefix("_R")
-            .or_else(|| input.strip_prefix('R'))
-            .or_else(|| input.strip_prefix("__R"))
-            .ok_or(ParseSymbolError)?;
+/// The reason a mangled name is rejected by [`Symbol::parse_from_str`].
+///
+/// Since the parser backtracks when an alternative does not match, the reported failure is the one that happens
+/// farthest into the input.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct ParseError {
+    /// The byte offset in the mangled name where the failed production starts.
+    pub offset: usize,
+    /// The production that fails to parse.
+    pub production: Production,
+    /// Why the production fails to parse.
+    pub kind: ParseErrorKind,
+}
 
-        parsers::parse_symbol(input).map_err(|()| ParseSymbolError)
+impl ParseError {
+    fn shifted(self, offset: usize) -> Self {
+        Self {
+            offset: self.offset + offset,
+            production: self.production,
+            kind: match self.kind {
+                ParseErrorKind::ForwardBackRef { target } => ParseErrorKind::ForwardBackRef {
+                    target: target + offset,
+                },
+                ParseErrorKind::BackRefTargetMismatch { target, expected } => ParseErrorKind::BackRefTargetMismatch {
+                    target: target + offset,
+                    expected,
+                },
+                kind => kind,
+            },
+        }
     }
 }
 
-impl Display for Symbol<'_> {
+impl Display for ParseError {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        self.display(if f.alternate() {
-            DisplayStyle::Normal
-        } else {
-            DisplayStyle::Long
-        })
-        .fmt(f)
+        match self.kind {
+            ParseErrorKind::Invalid => write!(f, "invalid {} at offset {}", self.production, self.offset),
+            ParseErrorKind::RecursionLimit => write!(
+                f,
+                "recursion limit exceeded when parsing {} at offset {}",
+                self.production, self.offset
+            ),
+            ParseErrorKind::BudgetExceeded => write!(
+                f,
+                "parsing budget exceeded when parsing {} at offset {}",
+                self.production, self.offset
+            ),
+            ParseErrorKind::ForwardBackRef { target } => write!(
+                f,
+                "back reference at offset {} refers to offset {target}, which is not before the back reference",
+                self.offset
+            ),
+            ParseErrorKind::BackRefTargetMismatch { target, expected } => write!(
+                f,
+                "back reference at offset {} refers to offset {target}, where no {expected} starts",
+                self.offset
+            ),
+        }
     }
 }
 
-#[derive(Clone, Eq, Hash, Ord, PartialEq, PartialOrd)]
-pub enum Path<'a> {
-    CrateRoot(Identifier<'a>),
-    InherentImpl {
-        impl_path: ImplPath<'a>,
-        type_: Rc<Type<'a>>,
-    },
-    TraitImpl {
-        impl_path: ImplPath<'a>,
-        type_: Rc<Type<'a>>,
-        trait_: Rc<Path<'a>>,
-    },
-    TraitDefinition {
-        type_: Rc<Type<'a>>,
-        trait_: Rc<Path<'a>>,
-    },
-    Nested {
-        namespace: u8,
-        path: Rc<Path<'a>>,
-        identifier: Identifier<'a>,
-    },
-    Generic {
-        path: Rc<Path<'a>>,
-        generic_args: Vec<GenericArg<'a>>,
-    },
-}
-
-impl Debug for Path<'_> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        struct DebugNamespace(u8);
-
-        impl Debug for DebugNamespace {
-            fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-                f.write_char('b')?;
-                Debug::fmt(&char::from(self.0), f)
-            }
-        }
+impl std::error::Error for ParseError {}
 
-        match self {
-            Self::CrateRoot(identifier) => f.debug_tuple("CrateRoot").field(identifier).finish(),
-            Self::InherentImpl { impl_path, type_ } => f
-                .debug_struct("InherentImpl")
-                .field("impl_path", impl_path)
-                .field("type_", type_.as_ref())
-                .finish(),
-            Self::TraitImpl {
-                impl_path,
-                type_,
-                trait_,
-            } => f
-                .debug_struct("TraitImpl")
-                .field("impl_path", impl_path)
-                .field("type_", type_.as_ref())
-                .field("trait_", trait_.as_ref())
-                .finish(),
-            Self::TraitDefinition { type_, trait_ } => f
-                .debug_struct("TraitDefinition")
-                .field("type_", type_.as_ref())
-                .field("trait_", trait_.as_ref())
-                .finish(),
-            Self::Nested {
-                namespace,
-                path,
-                identifier,
-            } => f
-                .debug_struct("Nested")
-                .field("namespace", &DebugNamespace(*namespace))
-                .field("path", path.as_ref())
-                .field("identifier", identifier)
-                .finish(),
-            Self::Generic { path, generic_args } => f
-                .debug_struct("Generic")
-                .field("path", path.as_ref())
-                .field("generic_args", &generic_args.as_slice())
-                .finish(),
-        }
-    }
+/// Why a [`ParseError`] occurs.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[non_exhaustive]
+pub enum ParseErrorKind {
+    /// The input does not match the syntax of the production.
+    Invalid,
+    /// The production is nested deeper than [`ParseOptions::max_depth`].
+    RecursionLimit,
+    /// Parsing takes more steps than [`ParseOptions::fuel`] allows.
+    BudgetExceeded,
+    /// A back reference refers to the byte offset `target` in the mangled name, which is not before the back
+    /// reference. Back references may only refer to nodes that are already parsed.
+    ForwardBackRef { target: usize },
+    /// A back reference refers to the byte offset `target` in the mangled name, where no node of the `expected` kind
+    /// ([`Production::Path`], [`Production::Type`] or [`Production::Const`]) starts.
+    BackRefTargetMismatch { target: usize, expected: Production },
 }
 
-impl Path<'_> {
-    /// Returns an object that implements [`Display`] for printing the path.
+impl ParseErrorKind {
+    /// Returns `true` if the error is caused by exceeding a limit in [`ParseOptions`] rather than by invalid syntax.
     #[must_use]
-    pub fn display(&self, style: DisplayStyle) -> impl Display + '_ {
-        display::display_fn(move |f| self.structured_demangle(f, style))
+    pub fn is_limit(self) -> bool {
+        matches!(self, Self::RecursionLimit | Self::BudgetExceeded)
     }
+}
 
-    pub fn structured_demangle(&self, out: &mut dyn DemangleWrite, style: DisplayStyle) -> fmt::Result {
-        display::write_path(self, out, style, 0, false)
-    }
+/// The reason [`Symbol::try_display`] or [`Symbol::to_string_with_budget`] fails.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[non_exhaustive]
+pub enum DisplayError {
+    /// The output is longer than the budget.
+    BudgetExceeded,
+    /// The symbol can not be displayed, for example because it refers to a lifetime that is not bound.
+    NotDisplayable,
+    /// The writer fails to accept the output.
+    Write,
 }
 
-impl Display for Path<'_> {
+impl Display for DisplayError {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        self.display(if f.alternate() {
-            DisplayStyle::Normal
-        } else {
-            DisplayStyle::Long
+        f.write_str(match self {
+            Self::BudgetExceeded => "display budget exceeded",
+            Self::NotDisplayable => "the symbol can not be displayed",
+            Self::Write => "failed to write the output",
         })
-        .fmt(f)
     }
 }
 
-#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
-pub struct ImplPath<'a> {
-    pub disambiguator: u64,
-    pub path: Rc<Path<'a>>,
-}
-
-#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
-pub struct Identifier<'a> {
-    pub disambiguator: u64,
-    pub name: Cow<'a, str>,
-}
+impl std::error::Error for DisplayError {}
 
-impl Identifier<'_> {
-    /// Returns an object that implements [`Display`] for printing the identifier.
-    #[must_use]
-    pub fn display(&self) -> impl Display + '_ {
-        self.name.as_ref()
-    }
+/// The reason [`demangle_to_writer`] fails.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[non_exhaustive]
+pub enum DemangleError {
+    /// The input is not a mangled name with Rust v0 syntax.
+    Invalid,
+    /// The symbol can not be displayed, for example because it refers to a lifetime that is not bound.
+    NotDisplayable,
+    /// The writer fails to accept the output.
+    Write,
 }
 
-impl Display for Identifier<'_> {
+impl Display for DemangleError {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        self.display().fmt(f)
+        f.write_str(match self {
+            Self::Invalid => "the input is not a mangled name with Rust v0 syntax",
+            Self::NotDisplayable => "the symbol can not be displayed",
+            Self::Write => "failed to write the demangled name",
+        })
     }
 }
 
-#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
-pub enum GenericArg<'a> {
-    Lifetime(u64),
-    Type(Rc<Type<'a>>),
-    Const(Rc<Const<'a>>),
-}
+impl std::error::Error for DemangleError {}
 
-impl GenericArg<'_> {
-    /// Returns an object that implements [`Display`] for printing the generic argument.
-    #[must_use]
-    pub fn display(&self, style: DisplayStyle) -> impl Display + '_ {
-        display::display_fn(move |f| display::write_generic_arg(self, f, style, 0))
-    }
+/// Options for [`Symbol::parse_from_str_with_options`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct ParseOptions {
+    /// The maximum nesting depth of paths, types and constants, counting the nodes back references expand to. Parsing,
+    /// displaying and dropping a symbol use stack space proportional to the depth, so the limit protects against stack
+    /// overflows when handling untrusted input. The default is 100.
+    pub max_depth: usize,
+    /// The maximum number of paths, types and constants the parser may try to parse, including attempts that fail and
+    /// are backtracked, or [`None`] for no limit. This bounds the work spent on a single symbol. The default is
+    /// [`None`].
+    pub fuel: Option<usize>,
+    /// Whether to keep Punycode identifiers that can not be decoded instead of failing to parse the symbol, so the rest
+    /// of a slightly corrupted symbol can still be demangled. Such identifiers keep their encoded name, and are displayed
+    /// as `punycode{...}`, see [`Identifier::is_undecoded_punycode`]. The default is `false`.
+    pub lossy_punycode: bool,
+    /// Whether to accept constants of the floating point types `f32` and `f64`, encoded as their type followed by the
+    /// hexadecimal digits of their bit pattern, like integer constants. The grammar reserves this encoding for when
+    /// rustc allows floating point const generics, see [`Const::F32`]. The default is `false`.
+    pub float_consts: bool,
 }
 
-impl Display for GenericArg<'_> {
-    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        self.display(if f.alternate() {
-            DisplayStyle::Normal
-        } else {
-            DisplayStyle::Long
-        })
-        .fmt(f)
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            max_depth: 100,
+            fuel: None,
+            lossy_punycode: false,
+            float_consts: false,
+        }
     }
 }
 
-#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
-pub enum Type<'a> {
-    Basic(BasicType),
-    Named(Rc<Path<'a>>),
-    Array(Rc<Type<'a>>, Rc<Const<'a>>),
-    Slice(Rc<Type<'a>>),
-    Tuple(Vec<Rc<Type<'a>>>),
-    Ref { lifetime: u64, type_: Rc<Type<'a>> },
-    RefMut { lifetime: u64, type_: Rc<Type<'a>> },
-    PtrConst(Rc<Type<'a>>),
-    PtrMut(Rc<Type<'a>>),
-    Fn(FnSig<'a>),
-    DynTrait { dyn_bounds: DynBounds<'a>, lifetime: u64 },
+/// The prefix a mangled name starts with, see [`Symbol::prefix_of`].
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum SymbolPrefix {
+    /// The `_R` prefix, which is the usual form.
+    Underscore,
+    /// The `R` prefix, used on platforms that do not allow identifiers to start with an underscore.
+    Bare,
+    /// The `__R` prefix, used on platforms like macOS that add an underscore to every symbol name.
+    DoubleUnderscore,
 }
 
-impl Type<'_> {
-    /// Returns an object that implements [`Display`] for printing the type.
+impl SymbolPrefix {
+    /// Returns the prefix as it appears in mangled names.
     #[must_use]
-    pub fn display(&self, style: DisplayStyle) -> impl Display + '_ {
-        display::display_fn(move |f| display::write_type(self, f, style, 0))
-    }
-}
-
-impl Display for Type<'_> {
-    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        self.display(if f.alternate() {
-            DisplayStyle::Normal
-        } else {
-            DisplayStyle::Long
-        })
-        .fmt(f)
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Underscore => "_R",
+            Self::Bare => "R",
+            Self::DoubleUnderscore => "__R",
+        }
     }
 }
 
+/// A part of the Rust v0 syntax, used by [`ParseError`] to describe what fails to parse.
 #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
-pub enum BasicType {
-    I8,
-    Bool,
-    Char,
-    F64,
-    Str,
-    F32,
-    U8,
-    Isize,
-    Usize,
-    I32,
-    U32,
-    I128,
-    U128,
-    I16,
-    U16,
-    Unit,
-    Ellipsis,
-    I64,
-    U64,
-    Never,
-    Placeholder,
+#[non_exhaustive]
+pub enum Production {
+    /// The `_R`, `R` or `__R` prefix.
+    Prefix,
+    Path,
+    Identifier,
+    /// An identifier encoded with Punycode.
+    PunycodeIdentifier,
+    GenericArg,
+    Lifetime,
+    Binder,
+    Type,
+    FnSig,
+    Abi,
+    DynBounds,
+    DynTrait,
+    Const,
+    ConstFields,
+    Base62Number,
+    DecimalNumber,
+    /// A back reference, which may refer to a position that does not start a node of the expected kind.
+    BackRef,
+    /// The vendor specific suffix following the mangled name, which starts with `.` or `$`.
+    VendorSpecificSuffix,
 }
 
-impl BasicType {
-    /// Returns an object that implements [`Display`] for printing the basic type.
-    #[must_use]
-    pub fn display(self) -> impl Display {
-        display::display_fn(move |f| display::write_basic_type(self, f))
+impl Display for Production {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Self::Prefix => "prefix",
+            Self::Path => "path",
+            Self::Identifier => "identifier",
+            Self::PunycodeIdentifier => "Punycode identifier",
+            Self::GenericArg => "generic argument",
+            Self::Lifetime => "lifetime",
+            Self::Binder => "binder",
+            Self::Type => "type",
+            Self::FnSig => "function signature",
+            Self::Abi => "ABI",
+            Self::DynBounds => "dyn bounds",
+            Self::DynTrait => "dyn trait",
+            Self::Const => "constant",
+            Self::ConstFields => "constant fields",
+            Self::Base62Number => "base-62 number",
+            Self::DecimalNumber => "decimal number",
+            Self::BackRef => "back reference",
+            Self::VendorSpecificSuffix => "vendor specific suffix",
+        })
     }
 }
 
-impl Display for BasicType {
-    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        self.display().fmt(f)
-    }
+/// The reason an identifier is rejected by [`Identifier::new`].
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum InvalidIdentifierError {
+    /// The name is empty.
+    Empty,
+    /// The name starts with a digit.
+    LeadingDigit,
+    /// The name contains a character that is neither alphanumeric nor `_`.
+    InvalidCharacter(char),
 }
 
 #[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
-pub struct FnSig<'a> {
-    pub bound_lifetimes: u64,
-    pub is_unsafe: bool,
-    pub abi: Option<Abi<'a>>,
-    pub argument_types: Vec<Rc<Type<'a>>>,
-    pub return_type: Rc<Type<'a>>,
+#[cfg_attr(feature = "serde", derive(::serde::Deserialize, ::serde::Serialize))]
+pub struct Symbol<'a> {
+    pub version: Option<u64>,
+    pub path: NodeRc<Path<'a>>,
+    pub instantiating_crate: Option<NodeRc<Path<'a>>>,
+    pub vendor_specific_suffix: Option<Cow<'a, str>>,
 }
 
-impl FnSig<'_> {
-    /// Returns an object that implements [`Display`] for printing the function signature.
+impl<'a> Symbol<'a> {
+    /// Returns an object that implements [`Display`] for printing the symbol.
     #[must_use]
     pub fn display(&self, style: DisplayStyle) -> impl Display + '_ {
-        display::display_fn(move |f| display::write_fn_sig(self, f, style, 0))
+        display::display_fn(move |f| self.render(f, &mut DefaultRenderer, style))
     }
-}
 
-impl Display for FnSig<'_> {
-    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        self.display(if f.alternate() {
-            DisplayStyle::Normal
-        } else {
-            DisplayStyle::Long
+    /// Returns an object that implements [`Display`] for printing the symbol with `options`, which control parts of the
+    /// output individually.
+    #[must_use]
+    pub fn display_with_options(&self, options: DisplayOptions) -> impl Display + '_ {
+        display_options::display_symbol(self, options)
+    }
+
+    /// Returns an object that implements [`Display`] for printing the symbol truncated to at most `max_len` characters.
+    /// If the symbol is longer, its output ends with `…`. Rendering stops once the limit is reached, so this is cheap
+    /// even for symbols that expand into very long names. Formatting with a precision, like `{:.80}`, has the same
+    /// effect.
+    #[must_use]
+    pub fn display_truncated(&self, style: DisplayStyle, max_len: usize) -> impl Display + '_ {
+        display::display_fn(move |f| {
+            display::write_truncated(f, max_len, |out| self.render(out, &mut DefaultRenderer, style))
         })
-        .fmt(f)
     }
-}
 
-#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
-pub enum Abi<'a> {
-    C,
-    Named(Cow<'a, str>),
-}
+    /// Writes the symbol displayed with `style` into `out`, like an existing [`String`] buffer, without formatting it
+    /// into an intermediate string first. To write into an [`io::Write`], see [`Symbol::write_display`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing to `out` fails, or if the symbol can not be displayed. In that case, `out` may
+    /// contain part of the output.
+    pub fn write_to(&self, out: &mut (impl fmt::Write + ?Sized), style: DisplayStyle) -> fmt::Result {
+        self.render(&mut &mut *out, &mut DefaultRenderer, style)
+    }
 
-#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
-pub struct DynBounds<'a> {
-    pub bound_lifetimes: u64,
-    pub dyn_traits: Vec<DynTrait<'a>>,
-}
+    /// Writes the symbol displayed with `style` into `out`. The output is buffered internally to avoid a write call for
+    /// each fragment of the name, but `out` is not flushed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing to `out` fails, or if the symbol can not be displayed.
+    pub fn write_display(&self, out: &mut (impl io::Write + ?Sized), style: DisplayStyle) -> io::Result<()> {
+        display::write_io(out, |writer| self.render(writer, &mut DefaultRenderer, style))
+    }
 
-#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
-pub struct DynTrait<'a> {
-    pub path: Rc<Path<'a>>,
-    pub dyn_trait_assoc_bindings: Vec<DynTraitAssocBinding<'a>>,
-}
+    /// Displays the symbol with `style` into a string of at most `budget` bytes. Back references let a short mangled
+    /// name expand into a very long demangled one, so this bounds the work spent on displaying untrusted symbols.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DisplayError::BudgetExceeded`] if the output is longer than `budget` bytes, or
+    /// [`DisplayError::NotDisplayable`] if the symbol can not be displayed.
+    pub fn to_string_with_budget(&self, style: DisplayStyle, budget: usize) -> Result<String, DisplayError> {
+        display::to_string_with_budget(budget, |writer| self.render(writer, &mut DefaultRenderer, style))
+    }
 
-#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
-pub struct DynTraitAssocBinding<'a> {
-    pub name: Cow<'a, str>,
-    pub type_: Rc<Type<'a>>,
-}
+    /// Writes the symbol displayed with `style` into `out` if the output is at most `budget` bytes. Unlike formatting
+    /// the symbol with [`Display`], this reports every failure as an error, so it is safe to use where panicking is not,
+    /// such as in signal handlers and panic hooks. On failure, `out` may have received part of the output.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DisplayError::BudgetExceeded`] if the output is longer than `budget` bytes,
+    /// [`DisplayError::NotDisplayable`] if the symbol can not be displayed, or [`DisplayError::Write`] if `out` fails
+    /// to accept the output.
+    pub fn try_display(
+        &self,
+        out: &mut (impl fmt::Write + ?Sized),
+        style: DisplayStyle,
+        budget: usize,
+    ) -> Result<(), DisplayError> {
+        display::try_display(out, budget, |writer| self.render(writer, &mut DefaultRenderer, style))
+    }
+
+    /// Returns the length in bytes of the symbol displayed with `style`, without building the output. Counting stops once
+    /// the length exceeds `cap`, so like [`Symbol::to_string_with_budget`], this bounds the work spent on untrusted
+    /// symbols. Pass [`usize::MAX`] to always get the exact length.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DisplayError::BudgetExceeded`] if the output is longer than `cap` bytes, or
+    /// [`DisplayError::NotDisplayable`] if the symbol can not be displayed.
+    pub fn display_len(&self, style: DisplayStyle, cap: usize) -> Result<usize, DisplayError> {
+        display::display_len(cap, |writer| self.render(writer, &mut DefaultRenderer, style))
+    }
+
+    /// Writes the symbol into `out`, using `renderer` to display the AST nodes it customizes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `out` or `renderer` fails to write the output.
+    pub fn render(
+        &self,
+        out: &mut dyn DemangleWrite,
+        renderer: &mut (impl Renderer + ?Sized),
+        style: DisplayStyle,
+    ) -> fmt::Result {
+        display::write_path(&self.path, out, renderer, style, 0, true)
+    }
+
+    /// Writes the symbol displayed with `style` into `sink` as a sequence of tokens classified by [`TokenKind`], so
+    /// consumers like IDEs can highlight the output without parsing it again. Concatenating the tokens gives the same
+    /// output as [`Symbol::display`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `sink` fails to accept a token, or if the symbol can not be displayed.
+    pub fn render_tokens(&self, sink: &mut (impl TokenSink + ?Sized), style: DisplayStyle) -> fmt::Result {
+        tokens::render_symbol_tokens(self, sink, style)
+    }
+
+    /// Returns an object that implements [`Display`] for printing the symbol with ANSI escape codes that color its
+    /// tokens according to `color_scheme`, for terminal output like backtraces.
+    #[cfg(feature = "color")]
+    #[must_use]
+    pub fn display_colored(&self, style: DisplayStyle, color_scheme: ColorScheme) -> impl Display + '_ {
+        color::display_colored(self, style, color_scheme)
+    }
+
+    /// Returns an object that implements [`Display`] for printing the AST of the symbol as an indented tree, one node
+    /// per line, which is easier to read than the [`Debug`] output when investigating how a name was demangled.
+    ///
+    /// Paths, types and constants are followed by their encoded ranges, like `@5..17`, and nodes encoded as back
+    /// references point to the position of the referenced node instead of repeating its children. Positions are byte
+    /// offsets after the `_R` prefix in the name returned by [`Symbol::mangle`], which is the original name for
+    /// symbols produced by rustc. The format is meant for people and may change.
+    #[must_use]
+    pub fn debug_tree(&self) -> impl Display + '_ {
+        debug_tree::display_debug_tree(self)
+    }
+
+    /// Returns an object that implements [`Display`] for printing the symbol as JSON, with a stable schema that is
+    /// independent of the layout of the AST types and of the `serde` feature. See [`Symbol::to_json`] for the schema.
+    #[must_use]
+    pub fn display_json(&self) -> impl Display + '_ {
+        json::display_json(self)
+    }
+
+    /// Returns the symbol as a JSON object with tagged nodes for paths, types and constants, for tools that need its
+    /// structure without depending on this crate. The schema is stable and does not depend on the `serde` feature.
+    ///
+    /// Every path, type, generic argument, constant and constant field list is an object with a `"kind"` member naming
+    /// the node, and other members depending on the kind:
+    ///
+    /// - Symbol: `{"version", "path", "instantiating_crate", "vendor_specific_suffix"}`, where `version` is a number or
+    ///   `null`, `instantiating_crate` is a path or `null`, and `vendor_specific_suffix` is a string or `null`. Symbols
+    ///   have no `"kind"` member.
+    /// - Identifier: `{"name", "disambiguator"}`, where `name` is written as [`Identifier::display`] prints it.
+    /// - Path:
+    ///   - `{"kind": "crate_root", "identifier"}`
+    ///   - `{"kind": "inherent_impl", "disambiguator", "impl_path", "type"}`
+    ///   - `{"kind": "trait_impl", "disambiguator", "impl_path", "type", "trait"}`
+    ///   - `{"kind": "trait_definition", "type", "trait"}`
+    ///   - `{"kind": "nested", "namespace", "path", "identifier"}`, where `namespace` is the namespace character, like
+    ///     `"v"` or `"C"`.
+    ///   - `{"kind": "generic", "path", "generic_args"}`
+    /// - Generic argument: `{"kind": "lifetime", "lifetime"}`, `{"kind": "type", "type"}` or
+    ///   `{"kind": "const", "const"}`.
+    /// - Type:
+    ///   - `{"kind": "basic", "name"}`, where `name` is the displayed name of the type, like `"u8"`, `"()"` or `"_"`.
+    ///   - `{"kind": "named", "path"}`
+    ///   - `{"kind": "array", "element_type", "length"}`, where `length` is a constant.
+    ///   - `{"kind": "slice", "element_type"}`
+    ///   - `{"kind": "tuple", "types"}`
+    ///   - `{"kind": "ref", "mutable", "lifetime", "type"}`
+    ///   - `{"kind": "ptr", "mutable", "type"}`
+    ///   - `{"kind": "fn", "bound_lifetimes", "unsafe", "abi", "argument_types", "return_type"}`, where `abi` is a
+    ///     string or `null`.
+    ///   - `{"kind": "dyn_trait", "bound_lifetimes", "traits", "lifetime"}`, where each trait is
+    ///     `{"path", "assoc_bindings"}` and each associated type binding is `{"name", "type"}`, with `name` written as
+    ///     [`DynTraitAssocBinding::display_name`] prints it.
+    /// - Constant:
+    ///   - `{"kind": "int", "type", "value"}`, where `type` is the integer type, like `"usize"`.
+    ///   - `{"kind": "float", "type", "value"}`, where `type` is `"f32"` or `"f64"` and `value` is a decimal string.
+    ///   - `{"kind": "bool", "value"}`, `{"kind": "char", "value"}` or `{"kind": "str", "value"}`, where invalid UTF-8
+    ///     in string values is replaced as [`ConstStr::decode`] does.
+    ///   - `{"kind": "ref", "mutable", "value"}`
+    ///   - `{"kind": "array", "items"}` or `{"kind": "tuple", "items"}`
+    ///   - `{"kind": "struct", "path", "fields"}`, where `fields` is `{"kind": "unit"}`, `{"kind": "tuple", "items"}`
+    ///     or `{"kind": "struct", "fields"}` with each field being `{"name", "value"}`.
+    ///   - `{"kind": "placeholder"}`
+    ///
+    /// Disambiguators and integer constant values are strings of decimal digits, because they may not fit into the
+    /// numbers of JSON parsers using 64-bit floating point. Lifetimes, bound lifetime counts and versions are numbers.
+    /// Lifetimes are de Bruijn indices as in the AST, with 0 being the erased lifetime `'_`. Back references are
+    /// expanded, so shared subtrees are written once for every place they appear in.
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        self.display_json().to_string()
+    }
+
+    /// Serializes the symbol back into a mangled name with Rust v0 syntax, starting with `_R`. Repeated paths, types and
+    /// constants are replaced with back references the same way rustc does, so parsing the result gives back an equal
+    /// symbol, and symbols produced by rustc are mangled back into their original names.
+    ///
+    /// Symbols containing identifiers created with [`Identifier::new_unchecked`] may not be mangled into well-formed
+    /// names.
+    #[must_use]
+    pub fn mangle(&self) -> String {
+        mangle::mangle_symbol(self)
+    }
+
+    /// Returns the mangled name of the symbol with every crate root named `from` replaced by a crate named `to` with
+    /// the disambiguator `new_disambiguator`, for tools that vendor or version crates at the symbol level. Crate roots
+    /// are replaced wherever they appear, including generic arguments, impl paths and the instantiating crate, while the
+    /// vendor specific suffix is kept.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`InvalidIdentifierError`] if `to` is not a valid identifier.
+    pub fn rename_crate(&self, from: &str, to: &str, new_disambiguator: u64) -> Result<String, InvalidIdentifierError> {
+        rename::rename_crate(self, from, to, new_disambiguator)
+    }
+
+    /// Returns a stable 64-bit hash of the symbol, for keying storage by symbol instead of by the mangled or demangled
+    /// name. The fingerprint is the 64-bit [FNV-1a](http://www.isthe.com/chongo/tech/comp/fnv/) hash of the bytes of
+    /// [`Symbol::mangle`] without the vendor specific suffix, which is kept stable across versions of this crate. It does
+    /// not depend on which nodes are shared or on the platform, and equal symbols have equal fingerprints. To identify
+    /// symbols across builds, take the fingerprint of the [`Symbol::normalize`]d symbol.
+    #[must_use]
+    pub fn fingerprint(&self) -> u64 {
+        mangle::fingerprint(self)
+    }
+
+    /// Converts the symbol into one that does not borrow from the mangled name, so it can be stored after the mangled
+    /// name is dropped. Subtrees shared through back references stay shared in the result.
+    #[must_use]
+    pub fn into_owned(self) -> Symbol<'static> {
+        owned::Owner::default().symbol(self)
+    }
+
+    /// Like [`Symbol::into_owned`], but shares the nodes of the result with equal nodes of the symbols converted with
+    /// `interner` before, so that many symbols can be kept in memory without storing their common parts repeatedly.
+    #[must_use]
+    pub fn into_interned(self, interner: &mut Interner) -> Symbol<'static> {
+        let mut owner = owned::Owner::with_interner(mem::take(interner));
+        let symbol = owner.symbol(self);
+
+        *interner = owner.into_interner().unwrap_or_default();
+
+        symbol
+    }
+
+    /// Parses `input` with Rust
+    /// [v0 syntax](https://rust-lang.github.io/rfcs/2603-rust-symbol-name-mangling-v0.html#syntax-of-mangled-names),
+    /// returns a tuple that contains a [`Symbol`] object and an [`&str`] object containing the suffix that is
+    /// not part of the the Rust v0 syntax.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParseError`] if `input` does not start with a valid prefix with Rust v0 syntax.
+    pub fn parse_from_str(input: &'a str) -> Result<(Self, &'a str), ParseError> {
+        Self::parse_from_str_with_options(input, ParseOptions::default())
+    }
+
+    /// Like [`Symbol::parse_from_str`], but parses a byte string, like a name taken from the symbol table of an object
+    /// file, and returns the bytes that are not part of the Rust v0 syntax as the suffix. Only the part of `input` up to
+    /// the first byte that is not valid UTF-8 is parsed, so the input may contain arbitrary bytes after the mangled name.
+    /// A vendor specific suffix ends before the first such byte.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParseError`] if `input` does not start with a valid prefix with Rust v0 syntax.
+    pub fn parse_from_bytes(input: &'a [u8]) -> Result<(Self, &'a [u8]), ParseError> {
+        let valid = input.utf8_chunks().next().map_or("", |chunk| chunk.valid());
+        let (symbol, suffix) = Self::parse_from_str(valid)?;
+
+        Ok((symbol, &input[valid.len() - suffix.len()..]))
+    }
+
+    /// Like [`Symbol::parse_from_str`], but converts the symbol with [`Symbol::into_interned`], so the result does not
+    /// borrow from `input` and shares its nodes with the symbols parsed with `interner` before.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParseError`] if `input` cannot be parsed, see [`Symbol::parse_from_str`].
+    pub fn parse_interned<'n>(
+        input: &'n str,
+        interner: &mut Interner,
+    ) -> Result<(Symbol<'static>, &'n str), ParseError> {
+        let (symbol, suffix) = Symbol::parse_from_str(input)?;
+
+        Ok((symbol.into_interned(interner), suffix))
+    }
+
+    /// Like [`Symbol::parse_from_str`], but requires the whole `input` to be a mangled name, optionally followed by a
+    /// vendor specific suffix starting with `.` or `$`, instead of returning the remaining characters.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParseError`] if `input` is not a mangled name with Rust v0 syntax. If characters other than a vendor
+    /// specific suffix follow the mangled name, the error points at them with [`Production::VendorSpecificSuffix`].
+    pub fn parse_from_str_strict(input: &'a str) -> Result<Self, ParseError> {
+        let (symbol, suffix) = Self::parse_from_str(input)?;
+
+        if suffix.is_empty() {
+            Ok(symbol)
+        } else {
+            Err(ParseError {
+                offset: input.len() - suffix.len(),
+                production: Production::VendorSpecificSuffix,
+                kind: ParseErrorKind::Invalid,
+            })
+        }
+    }
+
+    /// Like [`Symbol::parse_from_str`], but parses with `options`, for example to allow less nesting when parsing
+    /// untrusted input on a thread with a small stack.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParseError`] if `input` does not start with a valid prefix with Rust v0 syntax, or if it exceeds the
+    /// limits in `options`.
+    pub fn parse_from_str_with_options(input: &'a str, options: ParseOptions) -> Result<(Self, &'a str), ParseError> {
+        let (prefix_length, rest) = Self::strip_prefix(input)?;
+
+        parsers::parse_symbol(rest, options).map_err(|error| error.shifted(prefix_length))
+    }
+
+    /// Like [`Symbol::parse_from_str`], but recovers what it can from mangled names that fail to parse, like names
+    /// truncated by length limited symbol tables. If the path of the symbol fails to parse, the symbol is built from the
+    /// outermost enclosing path that parses, and is returned together with the error, so callers can show the known
+    /// part of the name:
+    ///
+    /// ```rust
+    /// use ast_demangle::rust_v0::{DisplayStyle, Symbol};
+    ///
+    /// let (symbol, error) = Symbol::parse_partial("_RNvNtCs123_5regex4utf84ne").unwrap();
+    /// let displayed = symbol.display(DisplayStyle::Normal);
+    ///
+    /// assert_eq!(format!("{displayed}::<truncated>"), "regex::utf8::<truncated>");
+    /// assert_eq!(error.unwrap().offset, 24);
+    /// ```
+    ///
+    /// The error is [`None`] if the whole mangled name parses, in which case the symbol is the same as the one
+    /// [`Symbol::parse_from_str`] returns. The suffix after the mangled name is not returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParseError`] if `input` does not start with a valid prefix, if no part of the path parses, or if the
+    /// input exceeds the limits of [`ParseOptions`], which are not a sign of truncation.
+    pub fn parse_partial(input: &'a str) -> Result<(Self, Option<ParseError>), ParseError> {
+        let (prefix_length, rest) = Self::strip_prefix(input)?;
+
+        parsers::parse_partial_symbol(rest, ParseOptions::default())
+            .map(|(symbol, error)| (symbol, error.map(|error| error.shifted(prefix_length))))
+            .map_err(|error| error.shifted(prefix_length))
+    }
+
+    /// Like [`Symbol::parse_from_str`], but also returns a [`SharingGraph`] that records which AST nodes are produced
+    /// by back references, and how often each shared node is referenced.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParseError`] if `input` does not start with a valid prefix with Rust v0 syntax.
+    pub fn parse_with_sharing_graph(input: &'a str) -> Result<(Self, &'a str, SharingGraph<'a>), ParseError> {
+        let (prefix_length, rest) = Self::strip_prefix(input)?;
+
+        parsers::parse_symbol_with_sharing_graph(rest).map_err(|error| error.shifted(prefix_length))
+    }
+
+    /// Like [`Symbol::parse_from_str`], but also returns the [`SourceSpans`] that map the paths, types and constants of
+    /// the AST back to the byte ranges of `input` they are parsed from, including nodes encoded as back references,
+    /// for tools that explain or visualize mangled names.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParseError`] if `input` does not start with a valid prefix with Rust v0 syntax.
+    pub fn parse_with_spans(input: &'a str) -> Result<(Self, &'a str, SourceSpans<'a>), ParseError> {
+        let (prefix_length, rest) = Self::strip_prefix(input)?;
+
+        parsers::parse_symbol_with_spans(rest, prefix_length).map_err(|error| error.shifted(prefix_length))
+    }
+
+    /// Returns which of the prefixes the Rust v0 syntax allows `input` starts with, or [`None`] if it does not start
+    /// with any of them. All of them are accepted by the parsing functions.
+    #[must_use]
+    pub fn prefix_of(input: &str) -> Option<SymbolPrefix> {
+        [
+            SymbolPrefix::Underscore,
+            SymbolPrefix::Bare,
+            SymbolPrefix::DoubleUnderscore,
+        ]
+        .into_iter()
+        .find(|prefix| input.starts_with(prefix.as_str()))
+    }
+
+    /// Parses only the spine of the path of the mangled name at the start of `input`, and returns its crate and its last
+    /// identifier, like `tokio` and `spawn` for `tokio::runtime::task::spawn::<u8>`. Generic arguments, impl self types
+    /// and other parts that are not needed are checked without building an AST, so this is much faster than
+    /// [`Symbol::parse_from_str`] when only a short label is needed, like for flame graph frames. Whatever follows the
+    /// path, like the instantiating crate, is not parsed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DemangleError::Invalid`] if `input` does not start with a prefix and a path with Rust v0 syntax.
+    pub fn parse_path_head(input: &str) -> Result<PathHead<'_>, DemangleError> {
+        stream::parse_path_head(input)
+    }
+
+    /// Returns the length of the prefix and the rest of the input.
+    fn strip_prefix(input: &str) -> Result<(usize, &str), ParseError> {
+        let prefix_length = Self::prefix_of(input)
+            .ok_or(ParseError {
+                offset: 0,
+                production: Production::Prefix,
+                kind: ParseErrorKind::Invalid,
+            })?
+            .as_str()
+            .len();
+
+        Ok((prefix_length, &input[prefix_length..]))
+    }
+
+    /// Returns the hash that wasm toolchains like `wasm-ld` and Emscripten append to symbol names with a `$`
+    /// separator, if the vendor specific suffix ends with one.
+    #[must_use]
+    pub fn wasm_hash(&self) -> Option<&str> {
+        self.vendor_specific_suffix
+            .as_deref()
+            .and_then(parsers::split_wasm_hash)
+            .map(|(_, hash)| hash)
+    }
+
+    /// Returns the vendor specific suffix with the trailing `$`-separated wasm hash removed, see
+    /// [`Symbol::wasm_hash`]. Returns `None` if nothing is left.
+    #[must_use]
+    pub fn vendor_specific_suffix_without_wasm_hash(&self) -> Option<&str> {
+        self.vendor_specific_suffix
+            .as_deref()
+            .map(|suffix| parsers::split_wasm_hash(suffix).map_or(suffix, |(rest, _)| rest))
+            .filter(|suffix| !suffix.is_empty())
+    }
+
+    /// Returns the vendor specific suffix split into its components, see [`VendorSuffix`].
+    #[must_use]
+    pub fn vendor_suffix(&self) -> Option<VendorSuffix<'_>> {
+        self.vendor_specific_suffix.as_deref().map(VendorSuffix::parse)
+    }
+
+    /// Returns `true` if any type appearing in the symbol matches `pattern`, see [`Type::matches_pattern`]. This can be
+    /// used to find symbols instantiated with a certain generic argument.
+    #[must_use]
+    pub fn contains_type_matching(&self, pattern: &Type) -> bool {
+        pattern::symbol_contains_type(self, pattern)
+    }
+
+    /// Returns the kind of the symbol, so that compiler generated symbols like drop glue, closures and shims can be
+    /// labeled or collapsed, for example in backtraces.
+    #[must_use]
+    pub fn kind(&self) -> SymbolKind {
+        kind::symbol_kind(self)
+    }
+
+    /// Returns a canonical form of the symbol, so that instances of the same logical function from different crates
+    /// and builds compare equal, for example to merge profiler samples. See [`NormalizeOptions`] for what is changed.
+    #[must_use]
+    pub fn normalize(&self, options: NormalizeOptions) -> Self {
+        normalize::normalize_symbol(self, options)
+    }
+
+    /// Returns the symbol with every generic argument list removed, like `<regex::utf8::Decoder>::decode_utf8` for
+    /// `<regex::utf8::Decoder<u8>>::decode_utf8::<&str>`, so that the instantiations of a generic item display the same.
+    /// Subtrees without generic arguments are shared with the original symbol.
+    #[must_use]
+    pub fn strip_generic_args(&self) -> Self {
+        normalize::strip_generic_args(self)
+    }
+
+    /// Returns every lifetime in the symbol together with the `for<...>` binder of the function pointer or trait object
+    /// type that introduces it, in the order the lifetimes are displayed. Lifetimes are stored as de Bruijn indices, so
+    /// this is needed to analyze higher-ranked lifetimes without reimplementing the bookkeeping of the printer. Shared
+    /// subtrees are resolved at every place they appear in.
+    #[must_use]
+    pub fn resolve_lifetimes(&self) -> Vec<ResolvedLifetime<'a>> {
+        lifetimes::resolve_lifetimes(self)
+    }
+
+    /// Checks constraints of the Rust v0 mangling scheme that the AST does not enforce, like valid identifiers,
+    /// namespaces and ABI names, array lengths of type `usize`, and bound lifetimes, and returns the violations found,
+    /// which is empty for well-formed symbols. Symbols parsed from mangled names satisfy most of the constraints, so
+    /// this is mostly useful for checking symbols built by hand or by tools, for example before mangling them. Lifetimes
+    /// are checked with [`Symbol::resolve_lifetimes`], and other nodes shared through back references are checked once.
+    #[must_use]
+    pub fn validate(&self) -> Vec<Violation<'a>> {
+        validate::validate(self)
+    }
+
+    /// Returns size measurements of the symbol, like the number of nodes and an estimate of its displayed length, for
+    /// example to find symbols that expand into very long names or to choose a truncation limit. This takes time
+    /// proportional to the size of the AST without expanding back references.
+    #[must_use]
+    pub fn metrics(&self) -> SymbolMetrics {
+        metrics::symbol_metrics(self)
+    }
+
+    /// Returns the generic item the symbol instantiates together with the generic arguments of the instantiation, like
+    /// `<alloc::vec::Vec>::push` and `[u8]` for `<alloc::vec::Vec<u8>>::push`. All instantiations of a generic item
+    /// have equal [`GenericRoot::path`]s, for example to add up the code size of the instantiations. The instantiating
+    /// crate is not part of the result.
+    #[must_use]
+    pub fn generic_root(&self) -> GenericRoot<'a> {
+        normalize::generic_root(self)
+    }
+
+    /// Returns `true` if the symbol equals `other` when the disambiguators of identifiers and impl paths, including
+    /// crate hashes, are ignored, for example to match symbols from two builds of the same program. To use this
+    /// equality for hashing, see [`IgnoringDisambiguators`].
+    #[must_use]
+    pub fn eq_ignoring_disambiguators(&self, other: &Symbol) -> bool {
+        normalize::eq_ignoring_disambiguators(self, other)
+    }
+
+    /// Returns the crate roots the symbol refers to anywhere in its path, including generic arguments, impl paths,
+    /// trait objects and function signatures, in the order they appear in the mangled name. Crates are yielded once,
+    /// even if they appear in many places. The instantiating crate is not included.
+    pub fn referenced_crates(&self) -> impl Iterator<Item = Identifier<'a>> {
+        references::referenced_crates(self).into_iter()
+    }
+
+    /// Returns the paths of the named types and traits the symbol refers to, like `alloc::vec::Vec<u8>` or
+    /// `core::fmt::Debug`, in the order they appear in the mangled name. Equal paths are yielded once, even if they
+    /// appear in many places. The instantiating crate is not included.
+    pub fn referenced_paths(&self) -> impl Iterator<Item = NodeRc<Path<'a>>> {
+        references::referenced_paths(self).into_iter()
+    }
+
+    /// Returns the name of the crate the symbol belongs to, see [`Path::crate_root`].
+    #[must_use]
+    pub fn crate_name(&self) -> &str {
+        &self.path.crate_root().name
+    }
+
+    /// Returns the name of the item the symbol refers to, see [`Path::item_name`].
+    #[must_use]
+    pub fn item_name(&self) -> Option<&str> {
+        self.path.item_name()
+    }
+
+    /// Returns the segments of the symbol path from the crate root to the item, see [`Path::segments`].
+    #[must_use]
+    pub fn path_segments(&self) -> impl DoubleEndedIterator<Item = PathSegment<'_, 'a>> {
+        self.path.segments()
+    }
+}
+
+/// Returns an object that implements [`Display`] for printing `symbols` compactly, one line per group of symbols
+/// that share the same enclosing path, like `regex::utf8::{decode_utf8, decode_last_utf8}`. Groups are ordered by the
+/// first appearance of their enclosing paths.
+#[must_use]
+pub fn display_grouped<'s>(symbols: &'s [Symbol], style: DisplayStyle) -> impl Display + 's {
+    display::display_fn(move |f| display::write_grouped_symbols(symbols, f, style))
+}
+
+/// Demangles `input` with Rust
+/// [v0 syntax](https://rust-lang.github.io/rfcs/2603-rust-symbol-name-mangling-v0.html#syntax-of-mangled-names) and
+/// writes the result displayed with `style` into `out`, without building a [`Symbol`]. The output is the same as
+/// [`Symbol::display`], and the input is accepted if and only if [`Symbol::parse_from_str`] accepts it. Returns the
+/// suffix that is not part of the Rust v0 syntax.
+///
+/// This is faster than parsing and displaying a [`Symbol`] because nothing is allocated for the AST, so it suits hot
+/// symbolization paths. Instead of sharing subtrees, back references are followed by parsing their targets again.
+///
+/// # Errors
+///
+/// Returns [`DemangleError::Invalid`] without writing anything if `input` does not start with a valid prefix with Rust
+/// v0 syntax. Returns [`DemangleError::NotDisplayable`] if the symbol can not be displayed, or
+/// [`DemangleError::Write`] if `out` fails, in which case part of the output may already be written.
+pub fn demangle_to_writer<'a>(
+    input: &'a str,
+    style: DisplayStyle,
+    out: &mut impl fmt::Write,
+) -> Result<&'a str, DemangleError> {
+    stream::demangle(input, style, out)
+}
+
+/// Demangles `name` the way `rustc-demangle` does, so that the output can be compared with the output of tools built
+/// on `rustc-demangle`. Displaying the result gives the same text as displaying `rustc_demangle::demangle(name)`, with
+/// crate hashes and const value types, or without them with the alternate format (`{:#}`).
+///
+/// Unlike [`Symbol::parse_from_str`], a `.llvm.` suffix is stripped if it only contains uppercase hexadecimal digits
+/// and `@`, other suffixes are kept only if they start with `.` and consist of ASCII alphanumeric and punctuation
+/// characters, and names with other trailing text or an encoding version are rejected. Returns [`None`] where
+/// `rustc_demangle::try_demangle` fails. Names that `rustc-demangle` only partially demangles, with markers like
+/// `{invalid syntax}` in its output, are not reproduced.
+#[must_use]
+pub fn demangle_rustc_compatible(name: &str) -> Option<RustcCompatible<'_>> {
+    rustc_compat::demangle(name)
+}
+
+/// Returns an object that implements [`Display`] for printing a lifetime given its de Bruijn index, like `'a` for the
+/// lifetime bound by the outermost binder, or `'_` for the erased lifetime. `bound_lifetime_depth` is the total number
+/// of lifetimes bound by the binders enclosing the lifetime. Returns [`None`] if `lifetime` does not refer to a bound
+/// lifetime.
+#[must_use]
+pub fn display_lifetime(lifetime: u64, bound_lifetime_depth: u64) -> Option<impl Display> {
+    (lifetime <= bound_lifetime_depth)
+        .then(|| display::display_fn(move |f| display::write_lifetime(lifetime, f, bound_lifetime_depth)))
+}
+
+impl Display for Symbol<'_> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.display(if f.alternate() {
+            DisplayStyle::Normal
+        } else {
+            DisplayStyle::Long
+        })
+        .fmt(f)
+    }
+}
+
+/// Parses a whole mangled name with [`Symbol::parse_from_str_strict`], and converts the result into an owned symbol
+/// with [`Symbol::into_owned`], since the input does not outlive the call.
+impl FromStr for Symbol<'static> {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Symbol::parse_from_str_strict(s).map(Symbol::into_owned)
+    }
+}
+
+/// Parses a whole mangled name with [`Symbol::parse_from_str_strict`]. To keep what follows the mangled name instead,
+/// convert into [`SymbolWithSuffix`].
+impl<'a> TryFrom<&'a str> for Symbol<'a> {
+    type Error = ParseError;
+
+    fn try_from(value: &'a str) -> Result<Self, Self::Error> {
+        Self::parse_from_str_strict(value)
+    }
+}
+
+/// A symbol together with the part of the input that follows the mangled name, as returned by
+/// [`Symbol::parse_from_str`].
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct SymbolWithSuffix<'a> {
+    /// The parsed symbol.
+    pub symbol: Symbol<'a>,
+    /// The suffix that is not part of the Rust v0 syntax.
+    pub suffix: &'a str,
+}
+
+/// Parses a mangled name with [`Symbol::parse_from_str`], keeping the suffix.
+impl<'a> TryFrom<&'a str> for SymbolWithSuffix<'a> {
+    type Error = ParseError;
+
+    fn try_from(value: &'a str) -> Result<Self, Self::Error> {
+        Symbol::parse_from_str(value).map(|(symbol, suffix)| Self { symbol, suffix })
+    }
+}
+
+#[derive(Clone, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(::serde::Deserialize, ::serde::Serialize))]
+pub enum Path<'a> {
+    CrateRoot(Identifier<'a>),
+    InherentImpl {
+        impl_path: ImplPath<'a>,
+        type_: NodeRc<Type<'a>>,
+    },
+    TraitImpl {
+        impl_path: ImplPath<'a>,
+        type_: NodeRc<Type<'a>>,
+        trait_: NodeRc<Self>,
+    },
+    TraitDefinition {
+        type_: NodeRc<Type<'a>>,
+        trait_: NodeRc<Self>,
+    },
+    Nested {
+        namespace: Namespace,
+        path: NodeRc<Self>,
+        identifier: Identifier<'a>,
+    },
+    Generic {
+        path: NodeRc<Self>,
+        generic_args: Vec<GenericArg<'a>>,
+    },
+}
+
+impl Debug for Path<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::CrateRoot(identifier) => f.debug_tuple("CrateRoot").field(identifier).finish(),
+            Self::InherentImpl { impl_path, type_ } => f
+                .debug_struct("InherentImpl")
+                .field("impl_path", impl_path)
+                .field("type_", type_.as_ref())
+                .finish(),
+            Self::TraitImpl {
+                impl_path,
+                type_,
+                trait_,
+            } => f
+                .debug_struct("TraitImpl")
+                .field("impl_path", impl_path)
+                .field("type_", type_.as_ref())
+                .field("trait_", trait_.as_ref())
+                .finish(),
+            Self::TraitDefinition { type_, trait_ } => f
+                .debug_struct("TraitDefinition")
+                .field("type_", type_.as_ref())
+                .field("trait_", trait_.as_ref())
+                .finish(),
+            Self::Nested {
+                namespace,
+                path,
+                identifier,
+            } => f
+                .debug_struct("Nested")
+                .field("namespace", namespace)
+                .field("path", path.as_ref())
+                .field("identifier", identifier)
+                .finish(),
+            Self::Generic { path, generic_args } => f
+                .debug_struct("Generic")
+                .field("path", path.as_ref())
+                .field("generic_args", &generic_args.as_slice())
+                .finish(),
+        }
+    }
+}
+
+/// A segment of a path, returned by [`Path::segments`].
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct PathSegment<'s, 'a> {
+    /// The namespace of the segment, or [`None`] for the crate root.
+    pub namespace: Option<Namespace>,
+    pub identifier: &'s Identifier<'a>,
+}
+
+impl<'a> Path<'a> {
+    /// Returns the path that encloses this one: the path an item is nested in, the path generic arguments are applied
+    /// to, the path of the module containing an impl block, or the trait of a trait definition.
+    fn parent(&self) -> Option<&Self> {
+        match self {
+            Self::CrateRoot(_) => None,
+            Self::InherentImpl { impl_path, .. } | Self::TraitImpl { impl_path, .. } => Some(&impl_path.path),
+            Self::TraitDefinition { trait_, .. } => Some(trait_),
+            Self::Nested { path, .. } | Self::Generic { path, .. } => Some(path),
+        }
+    }
+
+    /// Returns the path with every generic argument list removed, including the lists in the types the path contains.
+    /// Subtrees without generic arguments are shared with the original path.
+    #[must_use]
+    pub fn strip_generic_args(self: &NodeRc<Self>) -> NodeRc<Self> {
+        normalize::strip_generic_args_from_path(self)
+    }
+
+    /// Returns the identifier of the crate the path belongs to. Items in impl blocks belong to the crate containing the
+    /// impl block, and items in trait definitions belong to the crate defining the trait.
+    #[must_use]
+    pub fn crate_root(&self) -> &Identifier<'a> {
+        let mut path = self;
+
+        loop {
+            path = match path {
+                Self::CrateRoot(identifier) => return identifier,
+                Self::InherentImpl { impl_path, .. } | Self::TraitImpl { impl_path, .. } => &impl_path.path,
+                Self::TraitDefinition { trait_, .. } => trait_,
+                Self::Nested { path, .. } | Self::Generic { path, .. } => path,
+            };
+        }
+    }
+
+    /// Returns the name of the item the path refers to, ignoring generic arguments, like `swap` for
+    /// `std::mem::swap::<u8>`, or the crate name for a crate root. Returns [`None`] for impl blocks and trait
+    /// definitions. Items in special namespaces, like closures, may have empty names.
+    #[must_use]
+    pub fn item_name(&self) -> Option<&str> {
+        match self {
+            Self::CrateRoot(identifier) | Self::Nested { identifier, .. } => Some(&identifier.name),
+            Self::InherentImpl { .. } | Self::TraitImpl { .. } | Self::TraitDefinition { .. } => None,
+            Self::Generic { path, .. } => path.item_name(),
+        }
+    }
+
+    /// Returns the segments of the path from the crate root to the item, skipping generic arguments. Impl blocks and
+    /// trait definitions do not have segments of their own: they are represented by the segments of the module
+    /// containing the impl block, or of the trait, the same way [`Path::crate_root`] follows them.
+    #[must_use]
+    pub fn segments(&self) -> impl DoubleEndedIterator<Item = PathSegment<'_, 'a>> {
+        let mut segments = Vec::new();
+        let mut path = Some(self);
+
+        while let Some(current) = path {
+            match current {
+                Self::CrateRoot(identifier) => segments.push(PathSegment {
+                    namespace: None,
+                    identifier,
+                }),
+                Self::Nested {
+                    namespace, identifier, ..
+                } => segments.push(PathSegment {
+                    namespace: Some(*namespace),
+                    identifier,
+                }),
+                _ => {}
+            }
+
+            path = current.parent();
+        }
+
+        segments.into_iter().rev()
+    }
+
+    /// Returns the generic arguments the path applies, like `[u8]` for `Vec::<u8>`, or [`None`] if the path is not
+    /// generic. Generic arguments of enclosing paths, like the `u8` in `<Vec<u8>>::push`, are not included.
+    #[must_use]
+    pub fn generic_args(&self) -> Option<&[GenericArg<'a>]> {
+        match self {
+            Self::Generic { generic_args, .. } => Some(generic_args),
+            _ => None,
+        }
+    }
+
+    /// Returns the path the generic arguments are applied to, like `Vec` for `Vec::<u8>`, or the path itself if it is
+    /// not generic. The result is a node of the original AST rather than a copy. Unlike [`Path::strip_generic_args`],
+    /// enclosing paths keep their generic arguments.
+    #[must_use]
+    pub fn base(&self) -> &Self {
+        let mut path = self;
+
+        while let Self::Generic { path: base, .. } = path {
+            path = base;
+        }
+
+        path
+    }
+}
+
+impl Path<'_> {
+    /// Returns an object that implements [`Display`] for printing the path.
+    #[must_use]
+    pub fn display(&self, style: DisplayStyle) -> impl Display + '_ {
+        display::display_fn(move |f| self.structured_demangle(f, style))
+    }
+
+    /// Writes the path into `out`, reporting the syntactic structure of the output with
+    /// [`DemangleWrite::push_demangle_node`] and [`DemangleWrite::pop_demangle_node`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `out` fails to accept the output.
+    pub fn structured_demangle(&self, out: &mut dyn DemangleWrite, style: DisplayStyle) -> fmt::Result {
+        self.render(out, &mut DefaultRenderer, style)
+    }
+
+    /// Writes the path into `out`, using `renderer` to display the AST nodes it customizes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `out` or `renderer` fails to write the output.
+    pub fn render(
+        &self,
+        out: &mut dyn DemangleWrite,
+        renderer: &mut (impl Renderer + ?Sized),
+        style: DisplayStyle,
+    ) -> fmt::Result {
+        display::write_path(self, out, renderer, style, 0, false)
+    }
+
+    /// Converts the path into one that does not borrow from the mangled name, see [`Symbol::into_owned`].
+    #[must_use]
+    pub fn into_owned(self) -> Path<'static> {
+        owned::Owner::default().path_node(&self)
+    }
+
+    /// Returns `true` if the path matches `pattern`, see [`Type::matches_pattern`].
+    #[must_use]
+    pub fn matches_pattern(&self, pattern: &Path) -> bool {
+        pattern::match_path(self, pattern)
+    }
+}
+
+impl Display for Path<'_> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.display(if f.alternate() {
+            DisplayStyle::Normal
+        } else {
+            DisplayStyle::Long
+        })
+        .fmt(f)
+    }
+}
+
+/// The namespace of a [`Path::Nested`] item. Uppercase namespaces are special namespaces that are displayed like
+/// `{closure#0}`, while lowercase namespaces are implementation internal and only the item name is displayed.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(::serde::Deserialize, ::serde::Serialize))]
+pub enum Namespace {
+    /// The `v` namespace rustc uses for functions, constants and statics.
+    Value,
+    /// The `t` namespace rustc uses for modules, types and traits.
+    Type,
+    /// The `C` namespace for closures.
+    Closure,
+    /// The `S` namespace for shims.
+    Shim,
+    /// Any other namespace, which should be an ASCII letter not covered by the other variants. Use
+    /// [`Namespace::from_char`] to create namespaces from characters.
+    Other(char),
+}
+
+impl Namespace {
+    /// Returns the namespace denoted by `c` in mangled names, or [`None`] if `c` is not an ASCII letter.
+    #[must_use]
+    pub fn from_char(c: char) -> Option<Self> {
+        Some(match c {
+            'v' => Self::Value,
+            't' => Self::Type,
+            'C' => Self::Closure,
+            'S' => Self::Shim,
+            _ if c.is_ascii_alphabetic() => Self::Other(c),
+            _ => return None,
+        })
+    }
+
+    /// Returns the character that denotes the namespace in mangled names.
+    #[must_use]
+    pub fn as_char(self) -> char {
+        match self {
+            Self::Value => 'v',
+            Self::Type => 't',
+            Self::Closure => 'C',
+            Self::Shim => 'S',
+            Self::Other(c) => c,
+        }
+    }
+
+    /// Returns whether the namespace is a special namespace, which is denoted by an uppercase letter.
+    #[must_use]
+    pub fn is_special(self) -> bool {
+        self.as_char().is_ascii_uppercase()
+    }
+}
+
+impl From<Namespace> for char {
+    fn from(namespace: Namespace) -> Self {
+        namespace.as_char()
+    }
+}
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(::serde::Deserialize, ::serde::Serialize))]
+pub struct ImplPath<'a> {
+    pub disambiguator: u64,
+    pub path: NodeRc<Path<'a>>,
+}
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(::serde::Deserialize, ::serde::Serialize))]
+pub struct Identifier<'a> {
+    pub disambiguator: u64,
+    pub name: Cow<'a, str>,
+    /// Whether `name` is a Punycode encoding that is kept undecoded because it is parsed with
+    /// [`ParseOptions::lossy_punycode`]. Such names are displayed wrapped in `punycode{...}`.
+    pub is_undecoded_punycode: bool,
+}
+
+impl<'a> Identifier<'a> {
+    /// Creates an identifier, checking that `name` is a valid Rust identifier: it must be non-empty, must not start
+    /// with a digit, and may only contain alphanumeric characters and `_`. Non-ASCII names are allowed, they are
+    /// encoded with Punycode when mangled.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`InvalidIdentifierError`] if `name` is not a valid identifier.
+    pub fn new(disambiguator: u64, name: impl Into<Cow<'a, str>>) -> Result<Self, InvalidIdentifierError> {
+        let name = name.into();
+        let first = name.chars().next().ok_or(InvalidIdentifierError::Empty)?;
+
+        if first.is_numeric() {
+            return Err(InvalidIdentifierError::LeadingDigit);
+        }
+
+        if let Some(c) = name.chars().find(|&c| !(c.is_alphanumeric() || c == '_')) {
+            return Err(InvalidIdentifierError::InvalidCharacter(c));
+        }
+
+        Ok(Self {
+            disambiguator,
+            name,
+            is_undecoded_punycode: false,
+        })
+    }
+
+    /// Creates an identifier without checking `name`. This is needed for identifiers in special namespaces, like the
+    /// empty names of closures, but the result may not be mangled into a well-formed symbol.
+    #[must_use]
+    pub fn new_unchecked(disambiguator: u64, name: impl Into<Cow<'a, str>>) -> Self {
+        Self {
+            disambiguator,
+            name: name.into(),
+            is_undecoded_punycode: false,
+        }
+    }
+
+    /// Returns an object that implements [`Display`] for printing the identifier.
+    #[must_use]
+    pub fn display(&self) -> impl Display + '_ {
+        display::DisplayName {
+            name: &self.name,
+            is_undecoded_punycode: self.is_undecoded_punycode,
+        }
+    }
+
+    /// Returns the Punycode encoding the name has in mangled names, like `caf_dma` for `café`, with the last `-` of the
+    /// encoding replaced with `_` the way rustc does, or [`None`] if the name is ASCII and is mangled as is. The parser
+    /// decodes Punycode into [`Identifier::name`], and since rustc encodes names canonically, this recovers the bytes
+    /// of the mangled name. Undecoded Punycode names, see [`Identifier::is_undecoded_punycode`], are returned as they
+    /// are.
+    #[must_use]
+    pub fn punycode(&self) -> Option<String> {
+        if self.is_undecoded_punycode {
+            Some(self.name.to_string())
+        } else {
+            mangle::encode_punycode_identifier(&self.name)
+        }
+    }
+}
+
+impl Display for Identifier<'_> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.display().fmt(f)
+    }
+}
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(::serde::Deserialize, ::serde::Serialize))]
+pub enum GenericArg<'a> {
+    Lifetime(u64),
+    Type(NodeRc<Type<'a>>),
+    Const(NodeRc<Const<'a>>),
+}
+
+impl<'a> GenericArg<'a> {
+    /// Returns the lifetime, if the generic argument is a lifetime.
+    #[must_use]
+    pub fn as_lifetime(&self) -> Option<u64> {
+        match *self {
+            Self::Lifetime(lifetime) => Some(lifetime),
+            _ => None,
+        }
+    }
+
+    /// Returns the type, if the generic argument is a type.
+    #[must_use]
+    pub fn as_type(&self) -> Option<&Type<'a>> {
+        match self {
+            Self::Type(type_) => Some(type_),
+            _ => None,
+        }
+    }
+
+    /// Returns the constant, if the generic argument is a constant.
+    #[must_use]
+    pub fn as_const(&self) -> Option<&Const<'a>> {
+        match self {
+            Self::Const(const_) => Some(const_),
+            _ => None,
+        }
+    }
+
+    /// Returns an object that implements [`Display`] for printing the generic argument.
+    #[must_use]
+    pub fn display(&self, style: DisplayStyle) -> impl Display + '_ {
+        display::display_fn(move |f| display::write_generic_arg(self, f, &mut DefaultRenderer, style, 0))
+    }
+}
+
+impl Display for GenericArg<'_> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.display(if f.alternate() {
+            DisplayStyle::Normal
+        } else {
+            DisplayStyle::Long
+        })
+        .fmt(f)
+    }
+}
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(::serde::Deserialize, ::serde::Serialize))]
+pub enum Type<'a> {
+    Basic(BasicType),
+    Named(NodeRc<Path<'a>>),
+    Array(NodeRc<Self>, NodeRc<Const<'a>>),
+    Slice(NodeRc<Self>),
+    Tuple(Vec<NodeRc<Self>>),
+    Ref { lifetime: u64, type_: NodeRc<Self> },
+    RefMut { lifetime: u64, type_: NodeRc<Self> },
+    PtrConst(NodeRc<Self>),
+    PtrMut(NodeRc<Self>),
+    Fn(FnSig<'a>),
+    DynTrait { dyn_bounds: DynBounds<'a>, lifetime: u64 },
+}
+
+impl Type<'_> {
+    /// Returns an object that implements [`Display`] for printing the type.
+    #[must_use]
+    pub fn display(&self, style: DisplayStyle) -> impl Display + '_ {
+        display::display_fn(move |f| display::write_type(self, f, &mut DefaultRenderer, style, 0))
+    }
+
+    /// Converts the type into one that does not borrow from the mangled name, see [`Symbol::into_owned`].
+    #[must_use]
+    pub fn into_owned(self) -> Type<'static> {
+        owned::Owner::default().type_node(&self)
+    }
+
+    /// Returns `true` if the type matches `pattern`, which is a type where placeholders (`_`) match any type or
+    /// constant, like `Vec<_>` or `Result<_, std::io::Error>`.
+    ///
+    /// Identifiers without disambiguators in `pattern` match identifiers with any disambiguator, generic arguments in
+    /// `pattern` only need to match a prefix of the generic arguments, and lifetimes are ignored.
+    #[must_use]
+    pub fn matches_pattern(&self, pattern: &Type) -> bool {
+        pattern::match_type(self, pattern)
+    }
+}
+
+impl Display for Type<'_> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.display(if f.alternate() {
+            DisplayStyle::Normal
+        } else {
+            DisplayStyle::Long
+        })
+        .fmt(f)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(::serde::Deserialize, ::serde::Serialize))]
+pub enum BasicType {
+    I8,
+    Bool,
+    Char,
+    F64,
+    Str,
+    F32,
+    U8,
+    Isize,
+    Usize,
+    I32,
+    U32,
+    I128,
+    U128,
+    I16,
+    U16,
+    Unit,
+    Ellipsis,
+    I64,
+    U64,
+    Never,
+    Placeholder,
+}
+
+impl BasicType {
+    /// Returns the basic type encoded by `tag` in mangled names, like `j` for `usize`.
+    #[must_use]
+    pub fn from_tag(tag: u8) -> Option<Self> {
+        Some(match tag {
+            b'a' => Self::I8,
+            b'b' => Self::Bool,
+            b'c' => Self::Char,
+            b'd' => Self::F64,
+            b'e' => Self::Str,
+            b'f' => Self::F32,
+            b'h' => Self::U8,
+            b'i' => Self::Isize,
+            b'j' => Self::Usize,
+            b'l' => Self::I32,
+            b'm' => Self::U32,
+            b'n' => Self::I128,
+            b'o' => Self::U128,
+            b's' => Self::I16,
+            b't' => Self::U16,
+            b'u' => Self::Unit,
+            b'v' => Self::Ellipsis,
+            b'x' => Self::I64,
+            b'y' => Self::U64,
+            b'z' => Self::Never,
+            b'p' => Self::Placeholder,
+            _ => return None,
+        })
+    }
+
+    /// Returns the tag that encodes the basic type in mangled names, like `j` for `usize`.
+    #[must_use]
+    pub fn tag(self) -> u8 {
+        match self {
+            Self::I8 => b'a',
+            Self::Bool => b'b',
+            Self::Char => b'c',
+            Self::F64 => b'd',
+            Self::Str => b'e',
+            Self::F32 => b'f',
+            Self::U8 => b'h',
+            Self::Isize => b'i',
+            Self::Usize => b'j',
+            Self::I32 => b'l',
+            Self::U32 => b'm',
+            Self::I128 => b'n',
+            Self::U128 => b'o',
+            Self::I16 => b's',
+            Self::U16 => b't',
+            Self::Unit => b'u',
+            Self::Ellipsis => b'v',
+            Self::I64 => b'x',
+            Self::U64 => b'y',
+            Self::Never => b'z',
+            Self::Placeholder => b'p',
+        }
+    }
+
+    /// Returns how the basic type is written in Rust source code, like `usize`, `()` or `!`. The ellipsis of variadic
+    /// functions is written as `...`, and the placeholder for a type that is not known as `_`.
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::I8 => "i8",
+            Self::Bool => "bool",
+            Self::Char => "char",
+            Self::F64 => "f64",
+            Self::Str => "str",
+            Self::F32 => "f32",
+            Self::U8 => "u8",
+            Self::Isize => "isize",
+            Self::Usize => "usize",
+            Self::I32 => "i32",
+            Self::U32 => "u32",
+            Self::I128 => "i128",
+            Self::U128 => "u128",
+            Self::I16 => "i16",
+            Self::U16 => "u16",
+            Self::Unit => "()",
+            Self::Ellipsis => "...",
+            Self::I64 => "i64",
+            Self::U64 => "u64",
+            Self::Never => "!",
+            Self::Placeholder => "_",
+        }
+    }
+
+    /// Returns an object that implements [`Display`] for printing the basic type.
+    #[must_use]
+    pub fn display(self) -> impl Display {
+        display::display_fn(move |f| display::write_basic_type(self, f))
+    }
+}
+
+impl Display for BasicType {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.display().fmt(f)
+    }
+}
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(::serde::Deserialize, ::serde::Serialize))]
+pub struct FnSig<'a> {
+    pub bound_lifetimes: u64,
+    pub is_unsafe: bool,
+    pub abi: Option<Abi<'a>>,
+    pub argument_types: Vec<NodeRc<Type<'a>>>,
+    pub return_type: NodeRc<Type<'a>>,
+}
+
+impl FnSig<'_> {
+    /// Returns an object that implements [`Display`] for printing the function signature.
+    #[must_use]
+    pub fn display(&self, style: DisplayStyle) -> impl Display + '_ {
+        display::display_fn(move |f| display::write_fn_sig(self, f, &mut DefaultRenderer, style, 0))
+    }
+}
+
+impl Display for FnSig<'_> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.display(if f.alternate() {
+            DisplayStyle::Normal
+        } else {
+            DisplayStyle::Long
+        })
+        .fmt(f)
+    }
+}
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(::serde::Deserialize, ::serde::Serialize))]
+pub enum Abi<'a> {
+    C,
+    Named(Cow<'a, str>),
+}
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(::serde::Deserialize, ::serde::Serialize))]
+pub struct DynBounds<'a> {
+    pub bound_lifetimes: u64,
+    pub dyn_traits: Vec<DynTrait<'a>>,
+}
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(::serde::Deserialize, ::serde::Serialize))]
+pub struct DynTrait<'a> {
+    pub path: NodeRc<Path<'a>>,
+    pub dyn_trait_assoc_bindings: Vec<DynTraitAssocBinding<'a>>,
+}
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(::serde::Deserialize, ::serde::Serialize))]
+pub struct DynTraitAssocBinding<'a> {
+    pub name: Cow<'a, str>,
+    pub type_: NodeRc<Type<'a>>,
+    /// Whether `name` is a Punycode encoding that is kept undecoded, like [`Identifier::is_undecoded_punycode`].
+    pub is_undecoded_punycode: bool,
+}
+
+impl DynTraitAssocBinding<'_> {
+    /// Returns an object that implements [`Display`] for printing the name of the associated type, like
+    /// [`Identifier::display`].
+    #[must_use]
+    pub fn display_name(&self) -> impl Display + '_ {
+        display::DisplayName {
+            name: &self.name,
+            is_undecoded_punycode: self.is_undecoded_punycode,
+        }
+    }
+}
 
 #[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(::serde::Deserialize, ::serde::Serialize))]
 pub enum Const<'a> {
     I8(i8),
     U8(u8),
@@ -353,30 +1743,87 @@ pub enum Const<'a> {
     U64(u64),
     Bool(bool),
     Char(char),
-    Str(String),
-    Ref(Rc<Const<'a>>),
-    RefMut(Rc<Const<'a>>),
-    Array(Vec<Rc<Const<'a>>>),
-    Tuple(Vec<Rc<Const<'a>>>),
+    Str(ConstStr),
+    Ref(NodeRc<Self>),
+    RefMut(NodeRc<Self>),
+    Array(Vec<NodeRc<Self>>),
+    Tuple(Vec<NodeRc<Self>>),
     NamedStruct {
-        path: Rc<Path<'a>>,
+        path: NodeRc<Path<'a>>,
         fields: ConstFields<'a>,
     },
     Placeholder,
+    /// A constant of type `f32`, stored as its bit pattern so constants can be compared and hashed. Floating point
+    /// constants are not emitted by rustc yet, so they are only parsed with [`ParseOptions::float_consts`].
+    F32(u32),
+    /// A constant of type `f64`, stored as its bit pattern like [`Const::F32`].
+    F64(u64),
+}
+
+/// The bytes of a string constant. Symbols emitted by rustc always encode valid UTF-8, but other bytes are kept so that
+/// corrupted symbols can still be demangled.
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(::serde::Deserialize, ::serde::Serialize))]
+pub struct ConstStr {
+    pub bytes: Vec<u8>,
+}
+
+impl ConstStr {
+    /// Returns the string the bytes encode, replacing invalid UTF-8 sequences with `U+FFFD REPLACEMENT CHARACTER`. The
+    /// string is borrowed if the bytes are valid UTF-8.
+    #[must_use]
+    pub fn decode(&self) -> Cow<'_, str> {
+        String::from_utf8_lossy(&self.bytes)
+    }
+
+    /// Returns the string the bytes encode, or [`None`] if they are not valid UTF-8.
+    #[must_use]
+    pub fn to_str(&self) -> Option<&str> {
+        str::from_utf8(&self.bytes).ok()
+    }
+}
+
+impl From<String> for ConstStr {
+    fn from(value: String) -> Self {
+        Self {
+            bytes: value.into_bytes(),
+        }
+    }
+}
+
+impl From<&str> for ConstStr {
+    fn from(value: &str) -> Self {
+        Self {
+            bytes: value.as_bytes().to_vec(),
+        }
+    }
 }
 
 #[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(::serde::Deserialize, ::serde::Serialize))]
 pub enum ConstFields<'a> {
     Unit,
-    Tuple(Vec<Rc<Const<'a>>>),
-    Struct(Vec<(Identifier<'a>, Rc<Const<'a>>)>),
+    Tuple(Vec<NodeRc<Const<'a>>>),
+    Struct(Vec<(Identifier<'a>, NodeRc<Const<'a>>)>),
 }
 
 impl Const<'_> {
     /// Returns an object that implements [`Display`] for printing the constant value.
     #[must_use]
     pub fn display(&self, style: DisplayStyle) -> impl Display + '_ {
-        display::display_fn(move |f| display::write_const(self, f, style, 0, true))
+        display::display_fn(move |f| display::write_const(self, f, &mut DefaultRenderer, style, 0, true))
+    }
+
+    /// Converts the constant value into one that does not borrow from the mangled name, see [`Symbol::into_owned`].
+    #[must_use]
+    pub fn into_owned(self) -> Const<'static> {
+        owned::Owner::default().const_node(&self)
+    }
+
+    /// Returns `true` if the constant value matches `pattern`, see [`Type::matches_pattern`].
+    #[must_use]
+    pub fn matches_pattern(&self, pattern: &Const) -> bool {
+        pattern::match_const(self, pattern)
     }
 }
 
@@ -390,3 +1837,952 @@ impl Display for Const<'_> {
         .fmt(f)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        BasicType, Const, ConstStr, DemangleError, DisplayError, DisplayStyle, Identifier, InvalidIdentifierError,
+        Namespace, ParseCache, ParseError, ParseErrorKind, ParseOptions, Path, Production, Symbol, SymbolPrefix,
+        SymbolWithSuffix,
+    };
+    use crate::{breakpad, filter};
+    use std::borrow::Cow;
+    use std::cmp::Ordering;
+    use std::collections::{BTreeMap, HashSet};
+    use std::fmt::{self, Write};
+    use std::hash::{DefaultHasher, Hash, Hasher};
+    use std::{env, hint, process, slice};
+
+    #[test]
+    fn test_identifier_new() {
+        assert_eq!(
+            Identifier::new(3, "foo_1"),
+            Ok(Identifier {
+                disambiguator: 3,
+                name: Cow::Borrowed("foo_1"),
+                is_undecoded_punycode: false,
+            })
+        );
+
+        assert_eq!(
+            Identifier::new(0, String::from("_")).map(|identifier| identifier.name),
+            Ok("_".into())
+        );
+        assert!(Identifier::new(0, "gödel").is_ok());
+        assert_eq!(Identifier::new(0, ""), Err(InvalidIdentifierError::Empty));
+        assert_eq!(Identifier::new(0, "1foo"), Err(InvalidIdentifierError::LeadingDigit));
+        assert_eq!(
+            Identifier::new(0, "foo bar"),
+            Err(InvalidIdentifierError::InvalidCharacter(' '))
+        );
+        assert_eq!(Identifier::new_unchecked(0, "").name, "");
+    }
+
+    #[test]
+    fn test_identifier_punycode() {
+        assert_eq!(
+            Identifier::new_unchecked(0, "café").punycode().as_deref(),
+            Some("caf_dma")
+        );
+        assert_eq!(Identifier::new_unchecked(0, "foo").punycode(), None);
+    }
+
+    #[test]
+    fn test_namespace() {
+        assert_eq!(Namespace::from_char('v'), Some(Namespace::Value));
+        assert_eq!(Namespace::from_char('t'), Some(Namespace::Type));
+        assert_eq!(Namespace::from_char('C'), Some(Namespace::Closure));
+        assert_eq!(Namespace::from_char('S'), Some(Namespace::Shim));
+        assert_eq!(Namespace::from_char('X'), Some(Namespace::Other('X')));
+        assert_eq!(Namespace::from_char('1'), None);
+        assert_eq!(Namespace::Shim.as_char(), 'S');
+        assert_eq!(char::from(Namespace::Other('q')), 'q');
+        assert!(Namespace::Other('X').is_special());
+        assert!(!Namespace::Value.is_special());
+    }
+
+    #[cfg(feature = "sync")]
+    #[test]
+    fn test_send_symbol() {
+        let symbol = Symbol::parse_from_str("_RINvC3foo3barThhEE").unwrap().0.into_owned();
+        let handle = std::thread::spawn(move || symbol.to_string());
+
+        assert_eq!(handle.join().unwrap(), "foo::bar::<(u8, u8)>");
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let test_cases = [
+            ("foo", 0, Production::Prefix),
+            ("_R", 2, Production::Path),
+            ("_RX", 3, Production::Path),
+            ("__RNvC3fo", 7, Production::Identifier),
+            ("_RNvCsZZZZZZZZZZZZ_3foo3bar", 6, Production::Base62Number),
+            ("_RNvCs123_u3a0_3bar", 12, Production::PunycodeIdentifier),
+            ("_RINvC3foo3barRwE", 15, Production::Type),
+            ("_RINvC3foo3barKwE", 15, Production::Const),
+            ("_RINvC3foo3barAhjE", 16, Production::Const),
+            ("_RINvC3foo3barFKgE", 16, Production::Abi),
+            ("_RNvBZZZZZZZZZZZZ_3foo", 5, Production::BackRef),
+        ];
+
+        for (name, offset, production) in test_cases {
+            assert_eq!(
+                Symbol::parse_from_str(name),
+                Err(ParseError {
+                    offset,
+                    production,
+                    kind: ParseErrorKind::Invalid
+                }),
+                "{name}"
+            );
+        }
+
+        assert_eq!(
+            ParseError {
+                offset: 3,
+                production: Production::PunycodeIdentifier,
+                kind: ParseErrorKind::Invalid
+            }
+            .to_string(),
+            "invalid Punycode identifier at offset 3"
+        );
+    }
+
+    #[test]
+    fn test_parse_back_ref_error() {
+        let test_cases = [
+            ("_RNvB1_3foo", 5, ParseErrorKind::ForwardBackRef { target: 4 }),
+            ("_RNvB2_3foo", 5, ParseErrorKind::ForwardBackRef { target: 5 }),
+            (
+                "_RNvB_3foo",
+                5,
+                ParseErrorKind::BackRefTargetMismatch {
+                    target: 2,
+                    expected: Production::Path,
+                },
+            ),
+            (
+                "_RINvC3foo3barKj1_Bc_E",
+                19,
+                ParseErrorKind::BackRefTargetMismatch {
+                    target: 15,
+                    expected: Production::Type,
+                },
+            ),
+        ];
+
+        for (name, offset, kind) in test_cases {
+            assert_eq!(
+                Symbol::parse_from_str(name),
+                Err(ParseError {
+                    offset,
+                    production: Production::BackRef,
+                    kind
+                }),
+                "{name}"
+            );
+
+            assert_eq!(
+                super::demangle_to_writer(name, DisplayStyle::Long, &mut String::new()),
+                Err(DemangleError::Invalid),
+                "{name}"
+            );
+        }
+
+        assert_eq!(
+            Symbol::parse_from_str("_RNvB1_3foo").unwrap_err().to_string(),
+            "back reference at offset 5 refers to offset 4, which is not before the back reference"
+        );
+
+        assert_eq!(
+            Symbol::parse_from_str("_RINvC3foo3barKj1_Bc_E")
+                .unwrap_err()
+                .to_string(),
+            "back reference at offset 19 refers to offset 15, where no type starts"
+        );
+    }
+
+    #[test]
+    fn test_path_accessors() {
+        let (symbol, _) = Symbol::parse_from_str("_RINvNtCs1234_3std3mem4swapNtNtB4_6string6StringEB4_").unwrap();
+
+        assert_eq!(symbol.crate_name(), "std");
+        assert_eq!(symbol.item_name(), Some("swap"));
+
+        assert_eq!(
+            symbol
+                .path_segments()
+                .map(|segment| (segment.namespace, segment.identifier.name.as_ref()))
+                .collect::<Vec<_>>(),
+            [
+                (None, "std"),
+                (Some(Namespace::Type), "mem"),
+                (Some(Namespace::Value), "swap")
+            ]
+        );
+
+        // Items in impl blocks belong to the crate containing the impl block.
+        let (symbol, _) = Symbol::parse_from_str("_RNvXs_NtC3foo3barNtC3baz3QuxNtNtC4core3fmt7Display3fmt").unwrap();
+
+        assert_eq!(symbol.to_string(), "<baz::Qux as core::fmt::Display>::fmt");
+        assert_eq!(symbol.crate_name(), "foo");
+        assert_eq!(symbol.item_name(), Some("fmt"));
+
+        assert_eq!(
+            symbol
+                .path_segments()
+                .map(|segment| segment.identifier.name.as_ref())
+                .collect::<Vec<_>>(),
+            ["foo", "bar", "fmt"]
+        );
+
+        let Path::Nested { path: impl_path, .. } = symbol.path.as_ref() else {
+            unreachable!()
+        };
+
+        assert_eq!(impl_path.item_name(), None);
+        assert_eq!(impl_path.generic_args(), None);
+        assert!(std::ptr::eq(impl_path.base(), impl_path.as_ref()));
+
+        let (symbol, _) = Symbol::parse_from_str("_RINvMNtC5alloc3vecINtB3_3VechE4pushReKj3_KpE").unwrap();
+        let generic_args = symbol.path.generic_args().unwrap();
+
+        assert_eq!(symbol.path.base().to_string(), "<alloc::vec::Vec<u8>>::push");
+        assert_eq!(
+            generic_args[0].as_type().map(ToString::to_string).as_deref(),
+            Some("&str")
+        );
+        assert_eq!(generic_args[1].as_const(), Some(&Const::Usize(3)));
+        assert_eq!(generic_args[2].as_const(), Some(&Const::Placeholder));
+        assert_eq!(generic_args[0].as_const(), None);
+        assert_eq!(generic_args[1].as_lifetime(), None);
+
+        let (symbol, _) = Symbol::parse_from_str("_RINvC3foo3barL_E").unwrap();
+
+        assert_eq!(symbol.path.generic_args().unwrap()[0].as_lifetime(), Some(0));
+
+        let Path::Generic { path: base, .. } = symbol.path.as_ref() else {
+            unreachable!()
+        };
+
+        assert!(std::ptr::eq(symbol.path.base(), base.as_ref()));
+
+        let (symbol, _) = Symbol::parse_from_str("_RNCNvC3foo3bar0").unwrap();
+
+        assert_eq!(symbol.item_name(), Some(""));
+        assert_eq!(
+            symbol.path_segments().next_back().and_then(|segment| segment.namespace),
+            Some(Namespace::Closure)
+        );
+    }
+
+    #[test]
+    fn test_prefix_of() {
+        assert_eq!(Symbol::prefix_of("_RNvC3foo3bar"), Some(SymbolPrefix::Underscore));
+        assert_eq!(Symbol::prefix_of("RNvC3foo3bar"), Some(SymbolPrefix::Bare));
+        assert_eq!(
+            Symbol::prefix_of("__RNvC3foo3bar"),
+            Some(SymbolPrefix::DoubleUnderscore)
+        );
+        assert_eq!(Symbol::prefix_of("_ZN3foo3barE"), None);
+
+        for name in ["_RNvC3foo3bar", "RNvC3foo3bar", "__RNvC3foo3bar"] {
+            assert_eq!(Symbol::parse_from_str(name).unwrap().0.to_string(), "foo::bar");
+        }
+    }
+
+    #[test]
+    fn test_parse_from_bytes() {
+        let (symbol, suffix) = Symbol::parse_from_bytes(b"_RNvC3foo3bar\xFF\xFE").unwrap();
+
+        assert_eq!(symbol.to_string(), "foo::bar");
+        assert_eq!(suffix, b"\xFF\xFE");
+
+        let (symbol, suffix) = Symbol::parse_from_bytes(b"_RNvC3foo3bar.llvm.123\xFF.cold").unwrap();
+
+        assert_eq!(symbol.vendor_specific_suffix.as_deref(), Some(".llvm.123"));
+        assert_eq!(suffix, b"\xFF.cold");
+
+        let (_, suffix) = Symbol::parse_from_bytes(b"_RNvC3foo3bar foo").unwrap();
+
+        assert_eq!(suffix, b" foo");
+
+        assert_eq!(
+            Symbol::parse_from_bytes(b"_RNvC3fo\xFF"),
+            Err(ParseError {
+                offset: 6,
+                production: Production::Identifier,
+                kind: ParseErrorKind::Invalid
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_from_str_strict() {
+        assert_eq!(
+            Symbol::parse_from_str_strict("_RNvC3foo3bar.llvm.123").map(|symbol| symbol.to_string()),
+            Ok("foo::bar".to_string())
+        );
+
+        assert!(Symbol::parse_from_str_strict("_RNvC3foo3bar").is_ok());
+
+        let error = Symbol::parse_from_str_strict("_RNvC3foo3bar foo").unwrap_err();
+
+        assert_eq!(
+            error,
+            ParseError {
+                offset: 13,
+                production: Production::VendorSpecificSuffix,
+                kind: ParseErrorKind::Invalid
+            }
+        );
+
+        assert_eq!(error.to_string(), "invalid vendor specific suffix at offset 13");
+
+        assert_eq!(
+            Symbol::parse_from_str_strict("_RX").unwrap_err().production,
+            Production::Path
+        );
+    }
+
+    #[test]
+    fn test_from_str() {
+        let symbol = "_RNvC3foo3bar.llvm.123".parse::<Symbol>().unwrap();
+
+        assert_eq!(symbol.to_string(), "foo::bar");
+        assert_eq!(symbol.vendor_specific_suffix.as_deref(), Some(".llvm.123"));
+
+        assert_eq!(
+            "_RNvC3foo3bar foo".parse::<Symbol>(),
+            Err(ParseError {
+                offset: 13,
+                production: Production::VendorSpecificSuffix,
+                kind: ParseErrorKind::Invalid
+            })
+        );
+
+        assert_eq!(
+            Symbol::try_from("_RNvC3foo3bar").map(|symbol| symbol.to_string()),
+            Ok("foo::bar".to_string())
+        );
+
+        assert!(Symbol::try_from("_RNvC3foo3bar foo").is_err());
+
+        let parsed = SymbolWithSuffix::try_from("_RNvC3foo3bar foo").unwrap();
+
+        assert_eq!(parsed.symbol.to_string(), "foo::bar");
+        assert_eq!(parsed.suffix, " foo");
+        assert_eq!(
+            SymbolWithSuffix::try_from("_RX").unwrap_err().production,
+            Production::Path
+        );
+    }
+
+    #[test]
+    fn test_structural_ordering_and_hashing() {
+        fn hash(value: &impl Hash) -> u64 {
+            let mut hasher = DefaultHasher::new();
+
+            value.hash(&mut hasher);
+
+            hasher.finish()
+        }
+
+        // The same symbol with and without back references.
+        let (shared, _) = Symbol::parse_from_str("_RINvC3foo3barNtB2_3BazE").unwrap();
+        let (unshared, _) = Symbol::parse_from_str("_RINvC3foo3barNtC3foo3BazE").unwrap();
+
+        assert_eq!(shared, unshared);
+        assert_eq!(shared.cmp(&unshared), Ordering::Equal);
+        assert_eq!(hash(&shared), hash(&unshared));
+        assert_eq!(hash(&shared.path), hash(&unshared.path));
+
+        let mut paths = [
+            "_RNvC3foo3baz",
+            "_RNvC3foo3bar",
+            "_RNvC3bar3foo",
+            "_RINvC3foo3barNtB2_3BazE",
+            "_RNvC3foo3bar",
+        ]
+        .map(|name| Symbol::parse_from_str(name).unwrap().0.path);
+
+        paths.sort();
+
+        let mut counts = BTreeMap::new();
+
+        for path in &paths {
+            *counts.entry(path).or_insert(0) += 1;
+        }
+
+        assert_eq!(
+            paths.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            ["bar::foo", "foo::bar", "foo::bar", "foo::baz", "foo::bar<foo::Baz>"]
+        );
+
+        assert_eq!(paths.iter().collect::<HashSet<_>>().len(), 4);
+        assert_eq!(counts.len(), 4);
+        assert_eq!(counts[&paths[1]], 2);
+    }
+
+    #[test]
+    fn test_basic_type_tags() {
+        let basic_types = (0..=u8::MAX).filter_map(BasicType::from_tag).collect::<Vec<_>>();
+
+        assert_eq!(basic_types.len(), 21);
+
+        for basic_type in basic_types {
+            assert_eq!(BasicType::from_tag(basic_type.tag()), Some(basic_type));
+            assert_eq!(basic_type.as_str(), basic_type.to_string());
+
+            let name = format!("_RINvC3foo3bar{}E", char::from(basic_type.tag()));
+            let (symbol, _) = Symbol::parse_from_str(&name).unwrap();
+
+            assert_eq!(symbol.to_string(), format!("foo::bar::<{}>", basic_type.as_str()));
+        }
+
+        assert_eq!(BasicType::from_tag(b'j'), Some(BasicType::Usize));
+        assert_eq!(BasicType::Never.as_str(), "!");
+        assert_eq!(BasicType::Unit.tag(), b'u');
+        assert_eq!(BasicType::from_tag(b'g'), None);
+    }
+
+    #[test]
+    fn test_const_str() {
+        let value = ConstStr::from("abc");
+
+        assert_eq!(value.decode(), "abc");
+        assert!(matches!(value.decode(), Cow::Borrowed(_)));
+        assert_eq!(value.to_str(), Some("abc"));
+
+        let value = ConstStr {
+            bytes: vec![b'a', 0x80, b'b'],
+        };
+
+        assert_eq!(value.decode(), "a\u{fffd}b");
+        assert_eq!(value.to_str(), None);
+
+        for (name, expected) in [
+            ("_RINvC3foo3barKRe616263_E", "foo::bar::<\"abc\">"),
+            ("_RINvC3foo3barKRe80ff_E", "foo::bar::<b\"\\x80\\xff\">"),
+            ("_RINvC3foo3barKe80ff_E", "foo::bar::<{*b\"\\x80\\xff\"}>"),
+        ] {
+            let (symbol, _) = Symbol::parse_from_str(name).unwrap();
+            let mut buffer = String::new();
+
+            super::demangle_to_writer(name, DisplayStyle::Normal, &mut buffer).unwrap();
+
+            assert_eq!(symbol.display(DisplayStyle::Normal).to_string(), expected);
+            assert_eq!(buffer, expected);
+        }
+    }
+
+    #[test]
+    fn test_parse_recursion_limit() {
+        let name = format!("_RINvC3foo3bar{}hE", "S".repeat(150));
+
+        assert_eq!(
+            Symbol::parse_from_str(&name),
+            Err(ParseError {
+                offset: 112,
+                production: Production::Path,
+                kind: ParseErrorKind::RecursionLimit
+            })
+        );
+
+        let options = ParseOptions {
+            max_depth: 200,
+            ..ParseOptions::default()
+        };
+        let (symbol, _) = Symbol::parse_from_str_with_options(&name, options).unwrap();
+
+        assert!(symbol
+            .to_string()
+            .ends_with(&format!("{}u8{}>", "[".repeat(150), "]".repeat(150))));
+
+        let options = ParseOptions {
+            max_depth: 2,
+            ..ParseOptions::default()
+        };
+
+        assert!(Symbol::parse_from_str_with_options("_RNvC3foo3bar", options).is_ok());
+        assert_eq!(
+            Symbol::parse_from_str_with_options("_RNvNtC3foo3bar3baz", options)
+                .unwrap_err()
+                .to_string(),
+            "recursion limit exceeded when parsing path at offset 6"
+        );
+    }
+
+    /// Returns a mangled name with generic arguments that double in length one after another, while the mangled name
+    /// only grows linearly because each argument refers to the previous one twice.
+    fn exponential_symbol(levels: usize) -> String {
+        const DIGITS: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+        let mut name = String::from("_RINvC3foo3barh");
+        let mut position = name.len() - 3;
+
+        for _ in 0..levels {
+            let mut back_ref = String::from("_");
+            let mut value = position - 1;
+
+            loop {
+                back_ref.insert(0, char::from(DIGITS[value % 62]));
+                value /= 62;
+
+                if value == 0 {
+                    break;
+                }
+            }
+
+            let next = name.len() - 2;
+
+            write!(name, "TB{back_ref}B{back_ref}E").unwrap();
+            position = next;
+        }
+
+        name.push('E');
+
+        name
+    }
+
+    #[test]
+    fn test_parse_fuel() {
+        assert_eq!(
+            Symbol::parse_from_str(&exponential_symbol(2)).unwrap().0.to_string(),
+            "foo::bar::<u8, (u8, u8), ((u8, u8), (u8, u8))>"
+        );
+
+        let name = exponential_symbol(40);
+
+        assert!(Symbol::parse_from_str(&name).is_ok());
+
+        let options = ParseOptions {
+            fuel: Some(10),
+            ..ParseOptions::default()
+        };
+
+        assert_eq!(
+            Symbol::parse_from_str_with_options(&name, options).map_err(|error| error.kind),
+            Err(ParseErrorKind::BudgetExceeded)
+        );
+
+        let options = ParseOptions {
+            fuel: Some(1000),
+            ..ParseOptions::default()
+        };
+
+        assert!(Symbol::parse_from_str_with_options(&name, options).is_ok());
+    }
+
+    #[test]
+    fn test_parse_lossy_punycode() {
+        let name = "_RNvNvCs123_u3a0_u7caf_dma3bar";
+
+        assert!(Symbol::parse_from_str(name).is_err());
+
+        let options = ParseOptions {
+            lossy_punycode: true,
+            ..ParseOptions::default()
+        };
+
+        let (symbol, _) = Symbol::parse_from_str_with_options(name, options).unwrap();
+
+        assert_eq!(
+            symbol.display(DisplayStyle::Normal).to_string(),
+            "punycode{a0_}::café::bar"
+        );
+        assert_eq!(symbol.mangle(), name);
+
+        let Path::Nested { path, .. } = symbol.path.as_ref() else {
+            panic!();
+        };
+        let Path::Nested { path, identifier, .. } = path.as_ref() else {
+            panic!();
+        };
+        let Path::CrateRoot(crate_root) = path.as_ref() else {
+            panic!();
+        };
+
+        assert_eq!(crate_root.name, "a0_");
+        assert!(crate_root.is_undecoded_punycode);
+        assert_eq!(crate_root.punycode().as_deref(), Some("a0_"));
+        assert!(!identifier.is_undecoded_punycode);
+        assert_eq!(symbol.validate(), []);
+
+        // The encoded names of undecoded identifiers are kept as they are, so they do not fail validation.
+        for (name, expected) in [
+            ("_RCu5rrrRY", "punycode{rrrRY}"),
+            (
+                "_RINvC3foo3barDNtB2_5Traitpu3a0_lEL_E",
+                "foo::bar::<dyn foo::Trait<punycode{a0_} = i32>>",
+            ),
+        ] {
+            let (symbol, _) = Symbol::parse_from_str_with_options(name, options).unwrap();
+
+            assert_eq!(symbol.display(DisplayStyle::Normal).to_string(), expected);
+            assert_eq!(symbol.mangle(), name);
+            assert_eq!(symbol.validate(), []);
+        }
+    }
+
+    #[test]
+    fn test_parse_float_consts() {
+        let name = "_RINvCs123_3foo3barKf3fc00000_Kdbfd0000000000000_Kd3ff0000000000000_E";
+
+        assert!(Symbol::parse_from_str(name).is_err());
+
+        let options = ParseOptions {
+            float_consts: true,
+            ..ParseOptions::default()
+        };
+
+        let (symbol, _) = Symbol::parse_from_str_with_options(name, options).unwrap();
+
+        assert_eq!(
+            symbol.display(DisplayStyle::Normal).to_string(),
+            "foo::bar::<1.5, -0.25, 1.0>"
+        );
+        assert_eq!(symbol.mangle(), name);
+
+        let Path::Generic { generic_args, .. } = symbol.path.as_ref() else {
+            panic!();
+        };
+
+        assert_eq!(generic_args[0].as_const(), Some(&Const::F32(1.5_f32.to_bits())));
+        assert_eq!(generic_args[1].as_const(), Some(&Const::F64((-0.25_f64).to_bits())));
+        assert_eq!(
+            generic_args[1]
+                .as_const()
+                .unwrap()
+                .display(DisplayStyle::Long)
+                .to_string(),
+            "-0.25f64"
+        );
+        assert!(symbol
+            .to_json()
+            .contains(r#"{"kind":"float","type":"f32","value":"1.5"}"#));
+    }
+
+    #[test]
+    fn test_parse_path_head() {
+        let test_cases = [
+            ("_RNvNtNtCs123_5tokio7runtime4task5spawn", "tokio", Some("spawn")),
+            (
+                "_RINvNtC4core3ptr13drop_in_placeINtNtC5alloc3vec3VechEEB4_",
+                "core",
+                Some("drop_in_place"),
+            ),
+            ("_RNvMNtC5alloc3vecINtB2_3VechE4push", "alloc", Some("push")),
+            ("_RNvXCs123_3fooRShNtNtC4core3fmt7Display3fmt", "foo", Some("fmt")),
+            ("_RNCNvCs123_3foo4main0B3_", "foo", Some("main")),
+            ("_RNvYhNtNtC4core3fmt7Display3fmt", "core", Some("fmt")),
+            ("_RMNtC5alloc3vecINtB2_3VechE", "alloc", None),
+            ("__R3C3foo", "foo", None),
+        ];
+
+        for (name, crate_root, item) in test_cases {
+            let head = Symbol::parse_path_head(name).unwrap();
+
+            assert_eq!(head.crate_root.name, crate_root, "{name}");
+            assert_eq!(head.item.map(|item| item.name), item.map(Cow::Borrowed), "{name}");
+        }
+
+        for name in ["_RNvC3foo", "_RNvB4_3bar", "_ZN3foo3barE", "_RINvC3foo3barNvB9_3bazE"] {
+            assert_eq!(Symbol::parse_path_head(name), Err(DemangleError::Invalid), "{name}");
+        }
+    }
+
+    #[test]
+    fn test_parse_partial() {
+        let name = "_RINvNtCs123_5regex4utf84nextINtB4_3FooNtB4_3BarEE";
+        let (symbol, error) = Symbol::parse_partial(name).unwrap();
+
+        assert_eq!(symbol, Symbol::parse_from_str(name).unwrap().0);
+        assert_eq!(error, None);
+
+        let test_cases = [
+            (&name[..30], "regex::utf8::next"),
+            (&name[..27], "regex::utf8"),
+            (&name[..21], "regex"),
+            ("_RNvMNtCs123_5regex4utf8NtB2_3Foo3ba", "<regex::utf8::Foo>"),
+        ];
+
+        for (name, expected) in test_cases {
+            let (symbol, error) = Symbol::parse_partial(name).unwrap();
+
+            assert_eq!(symbol.display(DisplayStyle::Normal).to_string(), expected, "{name}");
+            assert_eq!(error, Some(Symbol::parse_from_str(name).unwrap_err()), "{name}");
+        }
+
+        for name in ["_RNvNtCs123_5reg", "_ZN3foo3barE"] {
+            assert!(Symbol::parse_partial(name).is_err(), "{name}");
+        }
+    }
+
+    #[test]
+    fn test_to_string_with_budget() {
+        let (symbol, _) = Symbol::parse_from_str("_RINvC3foo3barThhEE").unwrap();
+
+        assert_eq!(
+            symbol.to_string_with_budget(DisplayStyle::Normal, 20).as_deref(),
+            Ok("foo::bar::<(u8, u8)>")
+        );
+        assert_eq!(
+            symbol.to_string_with_budget(DisplayStyle::Normal, 19),
+            Err(DisplayError::BudgetExceeded)
+        );
+
+        let name = exponential_symbol(60);
+        let (symbol, _) = Symbol::parse_from_str(&name).unwrap();
+
+        assert_eq!(
+            symbol.to_string_with_budget(DisplayStyle::Normal, 1 << 16),
+            Err(DisplayError::BudgetExceeded)
+        );
+
+        let (symbol, _) = Symbol::parse_from_str("_RINvC3foo3barRL1_hE").unwrap();
+
+        assert_eq!(
+            symbol.to_string_with_budget(DisplayStyle::Normal, 100),
+            Err(DisplayError::NotDisplayable)
+        );
+    }
+
+    #[test]
+    fn test_display_len() {
+        let (symbol, _) = Symbol::parse_from_str("_RINvC3foo3barThhEE").unwrap();
+
+        for style in [DisplayStyle::Short, DisplayStyle::Normal, DisplayStyle::Long] {
+            assert_eq!(
+                symbol.display_len(style, usize::MAX),
+                Ok(symbol.display(style).to_string().len())
+            );
+        }
+
+        assert_eq!(symbol.display_len(DisplayStyle::Normal, 20), Ok(20));
+        assert_eq!(
+            symbol.display_len(DisplayStyle::Normal, 19),
+            Err(DisplayError::BudgetExceeded)
+        );
+
+        let name = exponential_symbol(60);
+        let (symbol, _) = Symbol::parse_from_str(&name).unwrap();
+
+        assert_eq!(
+            symbol.display_len(DisplayStyle::Normal, 1 << 16),
+            Err(DisplayError::BudgetExceeded)
+        );
+
+        let (symbol, _) = Symbol::parse_from_str("_RINvC3foo3barRL1_hE").unwrap();
+
+        assert_eq!(
+            symbol.display_len(DisplayStyle::Normal, usize::MAX),
+            Err(DisplayError::NotDisplayable)
+        );
+    }
+
+    /// Accepts at most as many bytes as it holds, and fails to write any more.
+    struct LimitedWriter(usize);
+
+    impl Write for LimitedWriter {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            self.0 = self.0.checked_sub(s.len()).ok_or(fmt::Error)?;
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_try_display() {
+        let (symbol, _) = Symbol::parse_from_str("_RINvC3foo3barThhEE").unwrap();
+        let mut buffer = String::new();
+
+        assert_eq!(symbol.try_display(&mut buffer, DisplayStyle::Normal, 20), Ok(()));
+        assert_eq!(buffer, "foo::bar::<(u8, u8)>");
+        assert_eq!(
+            symbol.try_display(&mut String::new(), DisplayStyle::Normal, 19),
+            Err(DisplayError::BudgetExceeded)
+        );
+        assert_eq!(
+            symbol.try_display(&mut LimitedWriter(0), DisplayStyle::Normal, 100),
+            Err(DisplayError::Write)
+        );
+
+        // The binders bind more lifetimes in total than a `u64` counts, which used to overflow when displayed.
+        let name = "_RINvC3foo3barFGc0000000000_FGc0000000000_EuEuE";
+        let (symbol, _) = Symbol::parse_from_str(name).unwrap();
+
+        for style in [DisplayStyle::Short, DisplayStyle::Normal, DisplayStyle::Long] {
+            assert!(symbol.try_display(&mut String::new(), style, 1 << 16).is_err());
+        }
+    }
+
+    #[test]
+    fn test_back_ref_recursion_limit() {
+        // Every level nests the previous one through back references, so the expanded tree is deeper than the limit.
+        let name = exponential_symbol(150);
+
+        assert_eq!(
+            Symbol::parse_from_str(&name).map_err(|error| error.kind),
+            Err(ParseErrorKind::RecursionLimit)
+        );
+        assert_eq!(
+            super::demangle_to_writer(&name, DisplayStyle::Normal, &mut LimitedWriter(0)),
+            Err(DemangleError::Invalid)
+        );
+
+        let name = exponential_symbol(90);
+
+        assert!(Symbol::parse_from_str(&name).is_ok());
+        assert_eq!(
+            super::demangle_to_writer(&name, DisplayStyle::Normal, &mut LimitedWriter(0)),
+            Err(DemangleError::Write)
+        );
+    }
+
+    /// Parses and displays `name` in every way that promises not to panic, including the wrappers that display
+    /// symbols, and checks that both parsers accept it or neither does.
+    fn check_never_panics(name: &str, cache: &mut ParseCache) {
+        let parsed = Symbol::parse_from_str(name);
+
+        if let Ok((symbol, suffix)) = &parsed {
+            for style in [DisplayStyle::Short, DisplayStyle::Normal, DisplayStyle::Long] {
+                let mut buffer = String::new();
+                let displayed = symbol.try_display(&mut buffer, style, 1 << 12).map(|()| buffer.len());
+
+                assert_eq!(displayed, symbol.display_len(style, 1 << 12), "{name}");
+            }
+
+            // The debug tree does not follow back references, so its size is bounded by the size of the name.
+            let mut buffer = String::new();
+
+            assert!(write!(buffer, "{}", symbol.debug_tree()).is_ok(), "{name}");
+
+            // The wrappers display symbols without a budget, so they are only checked with short outputs.
+            let is_short = [
+                DisplayStyle::Short,
+                DisplayStyle::Normal,
+                DisplayStyle::Long,
+                DisplayStyle::Debuginfo,
+            ]
+            .into_iter()
+            .all(|style| symbol.display_len(style, 1 << 12) != Err(DisplayError::BudgetExceeded));
+
+            if is_short {
+                let mut buffer = String::new();
+                let displayed = write!(buffer, "{}", symbol.display(DisplayStyle::Normal))
+                    .is_ok()
+                    .then_some(buffer);
+
+                assert_eq!(
+                    cache.demangle(name).map(|cached| cached.display(DisplayStyle::Normal)),
+                    Ok(displayed.as_deref()),
+                    "{name}"
+                );
+
+                let line = format!("FUNC 0 0 0 {name}");
+                let expected = match &displayed {
+                    Some(displayed) if suffix.is_empty() => format!("FUNC 0 0 0 {displayed}"),
+                    _ => line.clone(),
+                };
+
+                assert_eq!(breakpad::demangle_line(&line, DisplayStyle::Normal), expected, "{name}");
+
+                let mut buffer = String::new();
+                let grouped = super::display_grouped(slice::from_ref(symbol), DisplayStyle::Normal);
+
+                hint::black_box(write!(buffer, "{grouped}").is_ok());
+                hint::black_box(filter::demangle_line(name));
+            }
+        }
+
+        let demangled = super::demangle_to_writer(name, DisplayStyle::Normal, &mut LimitedWriter(1 << 12));
+
+        assert_eq!(parsed.is_ok(), demangled != Err(DemangleError::Invalid), "{name}");
+
+        // Names that are not Rust v0 names are tried with the other schemes.
+        let demangled = crate::demangle(name);
+
+        assert!(
+            parsed.is_err() || matches!(demangled, Ok((crate::Symbol::RustV0(_), _))),
+            "{name}"
+        );
+    }
+
+    #[test]
+    fn test_never_panics() {
+        let names = [
+            "_RINvNtCs1234_3std3mem8align_ofINtB2_6ManualNtNtB4_3ops5RangeEE",
+            "_RINvMNtC5alloc3vecINtB3_3VechE4pushReKj3_KpE",
+            "_RNvXs_NtC3foo3barNtC3baz3QuxNtNtC4core3fmt7Display3fmt",
+            "_RNvNvCs123_u3a0_u7caf_dma3bar",
+            "_RINvC3foo3barFGc0000000000_FGc0000000000_EuEuE",
+            "_RINvC3foo3barKj1_Bc_E",
+            "_RMC0RLY_z",
+            "_RINvC3foo3barRL0_hE.llvm.123",
+        ];
+        let replacements = [
+            "", "B_", "B0_", "Gs_", "L0_", "Kj", "Ki", "u", "_", "E", "F", "T", "9", "\u{e9}",
+        ];
+
+        // The cache is only kept in memory, since it is not saved.
+        let path = env::temp_dir().join(format!("ast-demangle-test-never-panics-{}", process::id()));
+        let mut cache = ParseCache::open(path.join("cache")).unwrap();
+
+        for name in names {
+            for index in 0..name.len() {
+                check_never_panics(&name[..index], &mut cache);
+
+                for replacement in replacements {
+                    check_never_panics(
+                        &format!("{}{replacement}{}", &name[..index], &name[index + 1..]),
+                        &mut cache,
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_demangle_to_writer() {
+        fn demangle(name: &str, style: DisplayStyle) -> Result<(String, &str), DemangleError> {
+            let mut buffer = String::new();
+
+            super::demangle_to_writer(name, style, &mut buffer).map(|suffix| (buffer, suffix))
+        }
+
+        let name = "_RINvNtCs1234_3std3mem8align_ofINtB2_6ManualNtNtB4_3ops5RangeEE.llvm.123 foo";
+
+        for style in [
+            DisplayStyle::Short,
+            DisplayStyle::Normal,
+            DisplayStyle::Long,
+            DisplayStyle::Debuginfo,
+        ] {
+            let (symbol, suffix) = Symbol::parse_from_str(name).unwrap();
+
+            assert_eq!(demangle(name, style), Ok((symbol.display(style).to_string(), suffix)));
+        }
+
+        assert_eq!(
+            demangle(name, DisplayStyle::Long).unwrap().0,
+            "std[3c1c0]::mem::align_of::<std[3c1c0]::mem::Manual<std[3c1c0]::ops::Range>>"
+        );
+
+        // Back references are checked the same way as by the AST parser.
+        assert_eq!(
+            demangle("_RNvC3foo3bar", DisplayStyle::Short),
+            Ok((String::from("bar"), ""))
+        );
+        assert_eq!(
+            demangle("_RNvB0_3bar", DisplayStyle::Normal),
+            Err(DemangleError::Invalid)
+        );
+        assert_eq!(demangle("_RNvC3foo", DisplayStyle::Normal), Err(DemangleError::Invalid));
+        assert_eq!(
+            demangle("_RINvC3foo3barRL1_hE", DisplayStyle::Normal),
+            Err(DemangleError::NotDisplayable)
+        );
+    }
+}
@@ -0,0 +1,302 @@
+//! Normalizing symbols so that instances of the same logical function compare equal.
+
+use crate::rust_v0::{Const, Folder, GenericArg, NodeRc, Path, Symbol};
+use std::hash::{Hash, Hasher};
+
+/// Options for [`Symbol::normalize`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct NormalizeOptions {
+    /// Removes the instantiating crate. The default is `true`.
+    pub strip_instantiating_crate: bool,
+    /// Sets the disambiguators of identifiers and impl paths to 0, including crate hashes. Note that this also makes
+    /// closures and shims within the same item indistinguishable. The default is `true`.
+    pub zero_disambiguators: bool,
+    /// Replaces constant generic arguments with placeholders, which are displayed as `_`. The default is `false`.
+    pub erase_const_generic_args: bool,
+}
+
+impl Default for NormalizeOptions {
+    fn default() -> Self {
+        Self {
+            strip_instantiating_crate: true,
+            zero_disambiguators: true,
+            erase_const_generic_args: false,
+        }
+    }
+}
+
+struct Normalizer(NormalizeOptions);
+
+impl<'a> Folder<'a> for Normalizer {
+    fn fold_disambiguator(&mut self, disambiguator: u64) -> u64 {
+        if self.0.zero_disambiguators {
+            0
+        } else {
+            disambiguator
+        }
+    }
+
+    fn fold_generic_arg(&mut self, generic_arg: &GenericArg<'a>) -> GenericArg<'a> {
+        match generic_arg {
+            GenericArg::Const(const_) if self.0.erase_const_generic_args && **const_ != Const::Placeholder => {
+                GenericArg::Const(NodeRc::new(Const::Placeholder))
+            }
+            _ => super::super_fold_generic_arg(self, generic_arg),
+        }
+    }
+}
+
+pub fn normalize_symbol<'a>(symbol: &Symbol<'a>, options: NormalizeOptions) -> Symbol<'a> {
+    let mut symbol = Normalizer(options).fold_symbol(symbol);
+
+    if options.strip_instantiating_crate {
+        symbol.instantiating_crate = None;
+    }
+
+    symbol
+}
+
+/// Removes every generic argument list, keeping the paths they are applied to, and collects the removed arguments.
+#[derive(Default)]
+struct GenericArgsStripper<'a> {
+    generic_args: Vec<GenericArg<'a>>,
+}
+
+impl<'a> Folder<'a> for GenericArgsStripper<'a> {
+    fn fold_path(&mut self, path: &NodeRc<Path<'a>>) -> NodeRc<Path<'a>> {
+        match path.as_ref() {
+            Path::Generic { path, generic_args } => {
+                let path = self.fold_path(path);
+
+                self.generic_args.extend(generic_args.iter().cloned());
+
+                path
+            }
+            _ => super::super_fold_path(self, path),
+        }
+    }
+}
+
+pub fn strip_generic_args_from_path<'a>(path: &NodeRc<Path<'a>>) -> NodeRc<Path<'a>> {
+    GenericArgsStripper::default().fold_path(path)
+}
+
+pub fn strip_generic_args<'a>(symbol: &Symbol<'a>) -> Symbol<'a> {
+    GenericArgsStripper::default().fold_symbol(symbol)
+}
+
+/// The generic item a symbol instantiates, see [`Symbol::generic_root`]. Symbols with equal [`GenericRoot::path`]s are
+/// instantiations of the same item, which makes the path usable as a key for grouping symbols.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct GenericRoot<'a> {
+    /// The symbol path with every generic argument list removed, like `<alloc::vec::Vec>::push`.
+    pub path: NodeRc<Path<'a>>,
+    /// The removed generic arguments in the order they appear in the symbol path, like `u8` for
+    /// `<alloc::vec::Vec<u8>>::push`. Arguments are not searched for nested generic arguments.
+    pub generic_args: Vec<GenericArg<'a>>,
+}
+
+pub fn generic_root<'a>(symbol: &Symbol<'a>) -> GenericRoot<'a> {
+    let mut stripper = GenericArgsStripper::default();
+    let path = stripper.fold_path(&symbol.path);
+
+    GenericRoot {
+        path,
+        generic_args: stripper.generic_args,
+    }
+}
+
+/// Normalization options that only zero disambiguators.
+const ZERO_DISAMBIGUATORS: NormalizeOptions = NormalizeOptions {
+    strip_instantiating_crate: false,
+    zero_disambiguators: true,
+    erase_const_generic_args: false,
+};
+
+pub fn eq_ignoring_disambiguators(lhs: &Symbol, rhs: &Symbol) -> bool {
+    normalize_symbol(lhs, ZERO_DISAMBIGUATORS) == normalize_symbol(rhs, ZERO_DISAMBIGUATORS)
+}
+
+/// A [`Symbol`] that is compared and hashed ignoring the disambiguators of identifiers and impl paths, including
+/// crate hashes, see [`Symbol::eq_ignoring_disambiguators`]. This can be used as a key of hash maps to match symbols
+/// from two builds of the same program.
+#[derive(Clone, Debug)]
+pub struct IgnoringDisambiguators<'a> {
+    symbol: Symbol<'a>,
+    key: Symbol<'a>,
+}
+
+impl<'a> IgnoringDisambiguators<'a> {
+    /// Wraps `symbol`.
+    #[must_use]
+    pub fn new(symbol: Symbol<'a>) -> Self {
+        Self {
+            key: normalize_symbol(&symbol, ZERO_DISAMBIGUATORS),
+            symbol,
+        }
+    }
+
+    /// Returns the wrapped symbol.
+    #[must_use]
+    pub fn symbol(&self) -> &Symbol<'a> {
+        &self.symbol
+    }
+
+    /// Unwraps the symbol.
+    #[must_use]
+    pub fn into_inner(self) -> Symbol<'a> {
+        self.symbol
+    }
+}
+
+impl PartialEq for IgnoringDisambiguators<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl Eq for IgnoringDisambiguators<'_> {}
+
+impl Hash for IgnoringDisambiguators<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key.hash(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{IgnoringDisambiguators, NormalizeOptions};
+    use crate::rust_v0::{DisplayStyle, Symbol};
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_normalize() {
+        let (first, _) = Symbol::parse_from_str("_RINvNtCs1234_3foo3bar3bazKj1_ECs5678_3qux").unwrap();
+        let (second, _) = Symbol::parse_from_str("_RINvNtCsabcd_3foo3bar3bazKj1_E").unwrap();
+
+        assert_ne!(first, second);
+
+        let normalized = first.normalize(NormalizeOptions::default());
+
+        assert_eq!(normalized, second.normalize(NormalizeOptions::default()));
+        assert_eq!(normalized.instantiating_crate, None);
+        assert_eq!(
+            normalized.display(DisplayStyle::Long).to_string(),
+            "foo::bar::baz::<1usize>"
+        );
+
+        let options = NormalizeOptions {
+            erase_const_generic_args: true,
+            ..NormalizeOptions::default()
+        };
+
+        let (third, _) = Symbol::parse_from_str("_RINvNtCsabcd_3foo3bar3bazKj2_E").unwrap();
+
+        assert_eq!(first.normalize(options), third.normalize(options));
+        assert_eq!(first.normalize(options).to_string(), "foo::bar::baz::<_>");
+
+        let options = NormalizeOptions {
+            strip_instantiating_crate: false,
+            zero_disambiguators: false,
+            erase_const_generic_args: false,
+        };
+
+        assert_eq!(first.normalize(options), first);
+    }
+
+    #[test]
+    fn test_strip_generic_args() {
+        let (symbol, _) = Symbol::parse_from_str(
+            "_RINvMNtCs1234_5regex4utf8INtB3_7DecoderjE11decode_utf8INtNtCs5678_5alloc3vec3VechEECs9_3app",
+        )
+        .unwrap();
+
+        assert_eq!(
+            symbol.display(DisplayStyle::Normal).to_string(),
+            "<regex::utf8::Decoder<usize>>::decode_utf8::<alloc::vec::Vec<u8>>"
+        );
+
+        let stripped = symbol.strip_generic_args();
+
+        assert_eq!(
+            stripped.display(DisplayStyle::Normal).to_string(),
+            "<regex::utf8::Decoder>::decode_utf8"
+        );
+        assert_eq!(stripped.instantiating_crate, symbol.instantiating_crate);
+        assert_eq!(
+            symbol
+                .path
+                .strip_generic_args()
+                .display(DisplayStyle::Normal)
+                .to_string(),
+            "<regex::utf8::Decoder>::decode_utf8"
+        );
+
+        let (symbol, _) = Symbol::parse_from_str("_RNvNtCs1234_5regex4utf811decode_utf8").unwrap();
+        let stripped = symbol.strip_generic_args();
+
+        assert!(std::ptr::eq(stripped.path.as_ref(), symbol.path.as_ref()));
+    }
+
+    #[test]
+    fn test_generic_root() {
+        let (first, _) = Symbol::parse_from_str(
+            "_RINvMNtCs1234_5regex4utf8INtB3_7DecoderjE11decode_utf8INtNtCs5678_5alloc3vec3VechEECs9_3app",
+        )
+        .unwrap();
+
+        let (second, _) =
+            Symbol::parse_from_str("_RINvMNtCs1234_5regex4utf8INtB3_7DecoderKj2_E11decode_utf8eE").unwrap();
+
+        let first_root = first.generic_root();
+        let second_root = second.generic_root();
+
+        assert_eq!(first_root.path, second_root.path);
+        assert_eq!(
+            first_root.path.display(DisplayStyle::Normal).to_string(),
+            "<regex::utf8::Decoder>::decode_utf8"
+        );
+        assert_eq!(
+            first_root
+                .generic_args
+                .iter()
+                .map(|generic_arg| generic_arg.display(DisplayStyle::Normal).to_string())
+                .collect::<Vec<_>>(),
+            ["usize", "alloc::vec::Vec<u8>"]
+        );
+        assert_eq!(
+            second_root
+                .generic_args
+                .iter()
+                .map(|generic_arg| generic_arg.display(DisplayStyle::Normal).to_string())
+                .collect::<Vec<_>>(),
+            ["2", "str"]
+        );
+
+        let (symbol, _) = Symbol::parse_from_str("_RNvNtCs1234_5regex4utf811decode_utf8").unwrap();
+        let root = symbol.generic_root();
+
+        assert!(std::ptr::eq(root.path.as_ref(), symbol.path.as_ref()));
+        assert!(root.generic_args.is_empty());
+    }
+
+    #[test]
+    fn test_eq_ignoring_disambiguators() {
+        let (first, _) = Symbol::parse_from_str("_RNvMs_NtCs1234_3foo3barNtB4_3Baz3qux").unwrap();
+        let (second, _) = Symbol::parse_from_str("_RNvMs0_NtCsabcd_3foo3barNtB5_3Baz3qux").unwrap();
+        let (third, _) = Symbol::parse_from_str("_RNvMs_NtCs1234_3foo3barNtB4_3Baz4quux").unwrap();
+
+        assert_ne!(first, second);
+        assert!(first.eq_ignoring_disambiguators(&second));
+        assert!(!first.eq_ignoring_disambiguators(&third));
+
+        let symbols = [first.clone(), second, third]
+            .into_iter()
+            .map(IgnoringDisambiguators::new)
+            .collect::<HashSet<_>>();
+
+        assert_eq!(symbols.len(), 2);
+        assert!(symbols.contains(&IgnoringDisambiguators::new(first.clone())));
+        assert_eq!(IgnoringDisambiguators::new(first.clone()).into_inner(), first);
+    }
+}
@@ -0,0 +1,761 @@
+//! Converting ASTs into ones that do not borrow from the mangled name.
+
+use crate::rust_v0::{
+    Abi, Const, ConstFields, DynBounds, DynTrait, DynTraitAssocBinding, FnSig, GenericArg, Identifier, ImplPath,
+    NodeRc, Path, Symbol, Type,
+};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::fmt::{self, Debug, Formatter};
+use std::hash::{Hash, Hasher};
+use std::mem;
+
+const MIN_PRUNE_LENGTH: usize = 1024;
+
+/// Converts AST nodes, remembering the converted shared nodes, so subtrees shared through back references are only
+/// converted once and stay shared.
+#[derive(Default)]
+pub struct Owner {
+    paths: HashMap<usize, NodeRc<Path<'static>>>,
+    types: HashMap<usize, NodeRc<Type<'static>>>,
+    consts: HashMap<usize, NodeRc<Const<'static>>>,
+    interner: Option<Interner>,
+}
+
+/// An owned path that is hashed and compared by the addresses of its child nodes rather than their contents. Children
+/// are interned before their parents, so equal paths have identical children, and interning a path does not walk its
+/// subtrees.
+struct InternedPath(NodeRc<Path<'static>>);
+
+impl Hash for InternedPath {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        mem::discriminant(self.0.as_ref()).hash(state);
+
+        match self.0.as_ref() {
+            Path::CrateRoot(identifier) => identifier.hash(state),
+            Path::InherentImpl { impl_path, type_ } => {
+                (impl_path.disambiguator, key(&impl_path.path), key(type_)).hash(state);
+            }
+            Path::TraitImpl {
+                impl_path,
+                type_,
+                trait_,
+            } => (impl_path.disambiguator, key(&impl_path.path), key(type_), key(trait_)).hash(state),
+            Path::TraitDefinition { type_, trait_ } => (key(type_), key(trait_)).hash(state),
+            Path::Nested {
+                namespace,
+                path,
+                identifier,
+            } => (namespace, key(path), identifier).hash(state),
+            Path::Generic { path, generic_args } => {
+                key(path).hash(state);
+
+                for generic_arg in generic_args {
+                    match generic_arg {
+                        GenericArg::Lifetime(lifetime) => lifetime.hash(state),
+                        GenericArg::Type(type_) => key(type_).hash(state),
+                        GenericArg::Const(const_) => key(const_).hash(state),
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl PartialEq for InternedPath {
+    fn eq(&self, other: &Self) -> bool {
+        fn same_impl_path(lhs: &ImplPath, rhs: &ImplPath) -> bool {
+            lhs.disambiguator == rhs.disambiguator && NodeRc::ptr_eq(&lhs.path, &rhs.path)
+        }
+
+        match (self.0.as_ref(), other.0.as_ref()) {
+            (Path::CrateRoot(lhs), Path::CrateRoot(rhs)) => lhs == rhs,
+            (
+                Path::InherentImpl {
+                    impl_path: lhs_impl_path,
+                    type_: lhs_type,
+                },
+                Path::InherentImpl {
+                    impl_path: rhs_impl_path,
+                    type_: rhs_type,
+                },
+            ) => same_impl_path(lhs_impl_path, rhs_impl_path) && NodeRc::ptr_eq(lhs_type, rhs_type),
+            (
+                Path::TraitImpl {
+                    impl_path: lhs_impl_path,
+                    type_: lhs_type,
+                    trait_: lhs_trait,
+                },
+                Path::TraitImpl {
+                    impl_path: rhs_impl_path,
+                    type_: rhs_type,
+                    trait_: rhs_trait,
+                },
+            ) => {
+                same_impl_path(lhs_impl_path, rhs_impl_path)
+                    && NodeRc::ptr_eq(lhs_type, rhs_type)
+                    && NodeRc::ptr_eq(lhs_trait, rhs_trait)
+            }
+            (
+                Path::TraitDefinition {
+                    type_: lhs_type,
+                    trait_: lhs_trait,
+                },
+                Path::TraitDefinition {
+                    type_: rhs_type,
+                    trait_: rhs_trait,
+                },
+            ) => NodeRc::ptr_eq(lhs_type, rhs_type) && NodeRc::ptr_eq(lhs_trait, rhs_trait),
+            (
+                Path::Nested {
+                    namespace: lhs_namespace,
+                    path: lhs_path,
+                    identifier: lhs_identifier,
+                },
+                Path::Nested {
+                    namespace: rhs_namespace,
+                    path: rhs_path,
+                    identifier: rhs_identifier,
+                },
+            ) => {
+                lhs_namespace == rhs_namespace && NodeRc::ptr_eq(lhs_path, rhs_path) && lhs_identifier == rhs_identifier
+            }
+            (
+                Path::Generic {
+                    path: lhs_path,
+                    generic_args: lhs_generic_args,
+                },
+                Path::Generic {
+                    path: rhs_path,
+                    generic_args: rhs_generic_args,
+                },
+            ) => {
+                NodeRc::ptr_eq(lhs_path, rhs_path)
+                    && lhs_generic_args.len() == rhs_generic_args.len()
+                    && lhs_generic_args.iter().zip(rhs_generic_args).all(|pair| match pair {
+                        (GenericArg::Lifetime(lhs), GenericArg::Lifetime(rhs)) => lhs == rhs,
+                        (GenericArg::Type(lhs), GenericArg::Type(rhs)) => NodeRc::ptr_eq(lhs, rhs),
+                        (GenericArg::Const(lhs), GenericArg::Const(rhs)) => NodeRc::ptr_eq(lhs, rhs),
+                        _ => false,
+                    })
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Eq for InternedPath {}
+
+/// An owned type that is hashed and compared by the addresses of its child nodes, see [`InternedPath`].
+struct InternedType(NodeRc<Type<'static>>);
+
+impl Hash for InternedType {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        mem::discriminant(self.0.as_ref()).hash(state);
+
+        match self.0.as_ref() {
+            Type::Basic(basic_type) => basic_type.hash(state),
+            Type::Named(path) => key(path).hash(state),
+            Type::Array(type_, length) => (key(type_), key(length)).hash(state),
+            Type::Slice(type_) | Type::PtrConst(type_) | Type::PtrMut(type_) => key(type_).hash(state),
+            Type::Tuple(tuple_types) => keys(tuple_types).for_each(|key| key.hash(state)),
+            Type::Ref { lifetime, type_ } | Type::RefMut { lifetime, type_ } => (lifetime, key(type_)).hash(state),
+            Type::Fn(fn_sig) => {
+                (
+                    fn_sig.bound_lifetimes,
+                    fn_sig.is_unsafe,
+                    &fn_sig.abi,
+                    key(&fn_sig.return_type),
+                )
+                    .hash(state);
+                keys(&fn_sig.argument_types).for_each(|key| key.hash(state));
+            }
+            Type::DynTrait { dyn_bounds, lifetime } => {
+                (lifetime, dyn_bounds.bound_lifetimes).hash(state);
+
+                for dyn_trait in &dyn_bounds.dyn_traits {
+                    key(&dyn_trait.path).hash(state);
+
+                    for binding in &dyn_trait.dyn_trait_assoc_bindings {
+                        (&binding.name, key(&binding.type_)).hash(state);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl PartialEq for InternedType {
+    fn eq(&self, other: &Self) -> bool {
+        fn same_dyn_trait(lhs: &DynTrait, rhs: &DynTrait) -> bool {
+            NodeRc::ptr_eq(&lhs.path, &rhs.path)
+                && lhs.dyn_trait_assoc_bindings.len() == rhs.dyn_trait_assoc_bindings.len()
+                && lhs
+                    .dyn_trait_assoc_bindings
+                    .iter()
+                    .zip(&rhs.dyn_trait_assoc_bindings)
+                    .all(|(lhs, rhs)| lhs.name == rhs.name && NodeRc::ptr_eq(&lhs.type_, &rhs.type_))
+        }
+
+        match (self.0.as_ref(), other.0.as_ref()) {
+            (Type::Named(lhs), Type::Named(rhs)) => NodeRc::ptr_eq(lhs, rhs),
+            (Type::Array(lhs_type, lhs_length), Type::Array(rhs_type, rhs_length)) => {
+                NodeRc::ptr_eq(lhs_type, rhs_type) && NodeRc::ptr_eq(lhs_length, rhs_length)
+            }
+            (Type::Slice(lhs), Type::Slice(rhs))
+            | (Type::PtrConst(lhs), Type::PtrConst(rhs))
+            | (Type::PtrMut(lhs), Type::PtrMut(rhs)) => NodeRc::ptr_eq(lhs, rhs),
+            (Type::Tuple(lhs), Type::Tuple(rhs)) => same_nodes(lhs, rhs),
+            (
+                Type::Ref {
+                    lifetime: lhs_lifetime,
+                    type_: lhs_type,
+                },
+                Type::Ref {
+                    lifetime: rhs_lifetime,
+                    type_: rhs_type,
+                },
+            )
+            | (
+                Type::RefMut {
+                    lifetime: lhs_lifetime,
+                    type_: lhs_type,
+                },
+                Type::RefMut {
+                    lifetime: rhs_lifetime,
+                    type_: rhs_type,
+                },
+            ) => lhs_lifetime == rhs_lifetime && NodeRc::ptr_eq(lhs_type, rhs_type),
+            (Type::Fn(lhs), Type::Fn(rhs)) => {
+                lhs.bound_lifetimes == rhs.bound_lifetimes
+                    && lhs.is_unsafe == rhs.is_unsafe
+                    && lhs.abi == rhs.abi
+                    && same_nodes(&lhs.argument_types, &rhs.argument_types)
+                    && NodeRc::ptr_eq(&lhs.return_type, &rhs.return_type)
+            }
+            (
+                Type::DynTrait {
+                    dyn_bounds: lhs_dyn_bounds,
+                    lifetime: lhs_lifetime,
+                },
+                Type::DynTrait {
+                    dyn_bounds: rhs_dyn_bounds,
+                    lifetime: rhs_lifetime,
+                },
+            ) => {
+                lhs_lifetime == rhs_lifetime
+                    && lhs_dyn_bounds.bound_lifetimes == rhs_dyn_bounds.bound_lifetimes
+                    && lhs_dyn_bounds.dyn_traits.len() == rhs_dyn_bounds.dyn_traits.len()
+                    && lhs_dyn_bounds
+                        .dyn_traits
+                        .iter()
+                        .zip(&rhs_dyn_bounds.dyn_traits)
+                        .all(|(lhs, rhs)| same_dyn_trait(lhs, rhs))
+            }
+            // Basic types, and types of different kinds, which are compared without walking any subtree.
+            (lhs, rhs) => lhs == rhs,
+        }
+    }
+}
+
+impl Eq for InternedType {}
+
+/// An owned constant that is hashed and compared by the addresses of its child nodes, see [`InternedPath`].
+struct InternedConst(NodeRc<Const<'static>>);
+
+impl Hash for InternedConst {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        mem::discriminant(self.0.as_ref()).hash(state);
+
+        match self.0.as_ref() {
+            Const::Ref(value) | Const::RefMut(value) => key(value).hash(state),
+            Const::Array(items) | Const::Tuple(items) => keys(items).for_each(|key| key.hash(state)),
+            Const::NamedStruct { path, fields } => {
+                key(path).hash(state);
+                mem::discriminant(fields).hash(state);
+
+                match fields {
+                    ConstFields::Unit => {}
+                    ConstFields::Tuple(fields) => keys(fields).for_each(|key| key.hash(state)),
+                    ConstFields::Struct(fields) => {
+                        for (name, field) in fields {
+                            (name, key(field)).hash(state);
+                        }
+                    }
+                }
+            }
+            leaf => leaf.hash(state),
+        }
+    }
+}
+
+impl PartialEq for InternedConst {
+    fn eq(&self, other: &Self) -> bool {
+        match (self.0.as_ref(), other.0.as_ref()) {
+            (Const::Ref(lhs), Const::Ref(rhs)) | (Const::RefMut(lhs), Const::RefMut(rhs)) => NodeRc::ptr_eq(lhs, rhs),
+            (Const::Array(lhs), Const::Array(rhs)) | (Const::Tuple(lhs), Const::Tuple(rhs)) => same_nodes(lhs, rhs),
+            (
+                Const::NamedStruct {
+                    path: lhs_path,
+                    fields: lhs_fields,
+                },
+                Const::NamedStruct {
+                    path: rhs_path,
+                    fields: rhs_fields,
+                },
+            ) => {
+                NodeRc::ptr_eq(lhs_path, rhs_path)
+                    && match (lhs_fields, rhs_fields) {
+                        (ConstFields::Unit, ConstFields::Unit) => true,
+                        (ConstFields::Tuple(lhs), ConstFields::Tuple(rhs)) => same_nodes(lhs, rhs),
+                        (ConstFields::Struct(lhs), ConstFields::Struct(rhs)) => {
+                            lhs.len() == rhs.len()
+                                && lhs.iter().zip(rhs).all(|((lhs_name, lhs), (rhs_name, rhs))| {
+                                    lhs_name == rhs_name && NodeRc::ptr_eq(lhs, rhs)
+                                })
+                        }
+                        _ => false,
+                    }
+            }
+            // Scalars, and constants of different kinds, which are compared without walking any subtree.
+            (lhs, rhs) => lhs == rhs,
+        }
+    }
+}
+
+impl Eq for InternedConst {}
+
+/// Owned AST nodes shared between symbols, for tools that keep the symbols of a whole binary in memory. Equal paths,
+/// types and constants in symbols converted with the same interner, like common crate roots, module paths and
+/// generic argument types, are stored once together with their identifier strings.
+///
+/// ```rust
+/// use ast_demangle::rust_v0::{Interner, NodeRc, Path, Symbol};
+///
+/// let mut interner = Interner::new();
+/// let (first, _) = Symbol::parse_interned("_RNvNtCs1_3std3mem4swap", &mut interner).unwrap();
+/// let (second, _) = Symbol::parse_interned("_RNvNtCs1_3std3mem7replace", &mut interner).unwrap();
+///
+/// let (Path::Nested { path: first, .. }, Path::Nested { path: second, .. }) = (first.path.as_ref(), second.path.as_ref())
+/// else {
+///     unreachable!()
+/// };
+///
+/// assert!(NodeRc::ptr_eq(first, second));
+/// ```
+#[derive(Default)]
+pub struct Interner {
+    paths: HashSet<InternedPath>,
+    types: HashSet<InternedType>,
+    consts: HashSet<InternedConst>,
+    prune_length: usize,
+}
+
+impl Interner {
+    /// Creates an empty interner.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of interned paths, types and constants.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.paths.len() + self.types.len() + self.consts.len()
+    }
+
+    /// Returns `true` if no node is interned.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Forgets all interned nodes. Symbols converted before keep their nodes, but no longer share them with symbols
+    /// converted afterwards.
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    fn intern_path(&mut self, path: NodeRc<Path<'static>>) -> NodeRc<Path<'static>> {
+        intern(&mut self.paths, InternedPath(path), |path| &path.0)
+    }
+
+    fn intern_type(&mut self, type_: NodeRc<Type<'static>>) -> NodeRc<Type<'static>> {
+        intern(&mut self.types, InternedType(type_), |type_| &type_.0)
+    }
+
+    fn intern_const(&mut self, const_: NodeRc<Const<'static>>) -> NodeRc<Const<'static>> {
+        intern(&mut self.consts, InternedConst(const_), |const_| &const_.0)
+    }
+
+    /// Drops the nodes that are no longer used outside of the interner once the interner has doubled in size since the
+    /// last time, so the cost is amortized over the interned nodes. Nodes are dropped from the root down, so a node
+    /// that is only referenced by dropped nodes is dropped in a later call.
+    pub fn prune(&mut self) {
+        if self.len() >= self.prune_length.max(MIN_PRUNE_LENGTH) {
+            self.paths.retain(|path| NodeRc::strong_count(&path.0) > 1);
+            self.types.retain(|type_| NodeRc::strong_count(&type_.0) > 1);
+            self.consts.retain(|const_| NodeRc::strong_count(&const_.0) > 1);
+            self.prune_length = self.len() * 2;
+        }
+    }
+}
+
+impl Debug for Interner {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("Interner")
+            .field("paths", &self.paths.len())
+            .field("types", &self.types.len())
+            .field("consts", &self.consts.len())
+            .finish_non_exhaustive()
+    }
+}
+
+fn intern<T, N>(set: &mut HashSet<N>, node: N, rc: impl Fn(&N) -> &NodeRc<T>) -> NodeRc<T>
+where
+    N: Eq + Hash,
+{
+    if let Some(interned) = set.get(&node) {
+        NodeRc::clone(rc(interned))
+    } else {
+        let result = NodeRc::clone(rc(&node));
+
+        set.insert(node);
+
+        result
+    }
+}
+
+fn key<T>(node: &NodeRc<T>) -> usize {
+    NodeRc::as_ptr(node).cast::<()>() as usize
+}
+
+fn keys<T>(nodes: &[NodeRc<T>]) -> impl Iterator<Item = usize> + '_ {
+    nodes.iter().map(key)
+}
+
+fn same_nodes<T>(lhs: &[NodeRc<T>], rhs: &[NodeRc<T>]) -> bool {
+    lhs.len() == rhs.len() && lhs.iter().zip(rhs).all(|(lhs, rhs)| NodeRc::ptr_eq(lhs, rhs))
+}
+
+fn owned_str(s: Cow<str>) -> Cow<'static, str> {
+    Cow::Owned(s.into_owned())
+}
+
+impl Owner {
+    /// Creates an owner that shares the converted nodes with equal nodes in `interner`.
+    pub fn with_interner(interner: Interner) -> Self {
+        Self {
+            interner: Some(interner),
+            ..Self::default()
+        }
+    }
+
+    pub fn into_interner(self) -> Option<Interner> {
+        self.interner
+    }
+
+    pub fn symbol(&mut self, symbol: Symbol) -> Symbol<'static> {
+        Symbol {
+            version: symbol.version,
+            path: self.path(&symbol.path),
+            instantiating_crate: symbol
+                .instantiating_crate
+                .as_ref()
+                .map(|instantiating_crate| self.path(instantiating_crate)),
+            vendor_specific_suffix: symbol.vendor_specific_suffix.map(owned_str),
+        }
+    }
+
+    fn path(&mut self, path: &NodeRc<Path>) -> NodeRc<Path<'static>> {
+        if let Some(result) = self.paths.get(&key(path)) {
+            return NodeRc::clone(result);
+        }
+
+        let mut result = NodeRc::new(self.path_node(path));
+
+        if let Some(interner) = &mut self.interner {
+            result = interner.intern_path(result);
+        }
+
+        self.paths.insert(key(path), NodeRc::clone(&result));
+
+        result
+    }
+
+    pub fn path_node(&mut self, path: &Path) -> Path<'static> {
+        match path {
+            Path::CrateRoot(identifier) => Path::CrateRoot(identifier_node(identifier)),
+            Path::InherentImpl { impl_path, type_ } => Path::InherentImpl {
+                impl_path: self.impl_path(impl_path),
+                type_: self.type_(type_),
+            },
+            Path::TraitImpl {
+                impl_path,
+                type_,
+                trait_,
+            } => Path::TraitImpl {
+                impl_path: self.impl_path(impl_path),
+                type_: self.type_(type_),
+                trait_: self.path(trait_),
+            },
+            Path::TraitDefinition { type_, trait_ } => Path::TraitDefinition {
+                type_: self.type_(type_),
+                trait_: self.path(trait_),
+            },
+            Path::Nested {
+                namespace,
+                path,
+                identifier,
+            } => Path::Nested {
+                namespace: *namespace,
+                path: self.path(path),
+                identifier: identifier_node(identifier),
+            },
+            Path::Generic { path, generic_args } => Path::Generic {
+                path: self.path(path),
+                generic_args: generic_args
+                    .iter()
+                    .map(|generic_arg| self.generic_arg(generic_arg))
+                    .collect(),
+            },
+        }
+    }
+
+    fn impl_path(&mut self, impl_path: &ImplPath) -> ImplPath<'static> {
+        ImplPath {
+            disambiguator: impl_path.disambiguator,
+            path: self.path(&impl_path.path),
+        }
+    }
+
+    fn generic_arg(&mut self, generic_arg: &GenericArg) -> GenericArg<'static> {
+        match generic_arg {
+            GenericArg::Lifetime(lifetime) => GenericArg::Lifetime(*lifetime),
+            GenericArg::Type(type_) => GenericArg::Type(self.type_(type_)),
+            GenericArg::Const(const_) => GenericArg::Const(self.const_(const_)),
+        }
+    }
+
+    fn type_(&mut self, type_: &NodeRc<Type>) -> NodeRc<Type<'static>> {
+        if let Some(result) = self.types.get(&key(type_)) {
+            return NodeRc::clone(result);
+        }
+
+        let mut result = NodeRc::new(self.type_node(type_));
+
+        if let Some(interner) = &mut self.interner {
+            result = interner.intern_type(result);
+        }
+
+        self.types.insert(key(type_), NodeRc::clone(&result));
+
+        result
+    }
+
+    pub fn type_node(&mut self, type_: &Type) -> Type<'static> {
+        match type_ {
+            Type::Basic(basic_type) => Type::Basic(*basic_type),
+            Type::Named(path) => Type::Named(self.path(path)),
+            Type::Array(type_, length) => Type::Array(self.type_(type_), self.const_(length)),
+            Type::Slice(type_) => Type::Slice(self.type_(type_)),
+            Type::Tuple(tuple_types) => Type::Tuple(tuple_types.iter().map(|type_| self.type_(type_)).collect()),
+            Type::Ref { lifetime, type_ } => Type::Ref {
+                lifetime: *lifetime,
+                type_: self.type_(type_),
+            },
+            Type::RefMut { lifetime, type_ } => Type::RefMut {
+                lifetime: *lifetime,
+                type_: self.type_(type_),
+            },
+            Type::PtrConst(type_) => Type::PtrConst(self.type_(type_)),
+            Type::PtrMut(type_) => Type::PtrMut(self.type_(type_)),
+            Type::Fn(fn_sig) => Type::Fn(self.fn_sig(fn_sig)),
+            Type::DynTrait { dyn_bounds, lifetime } => Type::DynTrait {
+                dyn_bounds: self.dyn_bounds(dyn_bounds),
+                lifetime: *lifetime,
+            },
+        }
+    }
+
+    fn fn_sig(&mut self, fn_sig: &FnSig) -> FnSig<'static> {
+        FnSig {
+            bound_lifetimes: fn_sig.bound_lifetimes,
+            is_unsafe: fn_sig.is_unsafe,
+            abi: fn_sig.abi.as_ref().map(|abi| match abi {
+                Abi::C => Abi::C,
+                Abi::Named(name) => Abi::Named(owned_str(name.clone())),
+            }),
+            argument_types: fn_sig.argument_types.iter().map(|type_| self.type_(type_)).collect(),
+            return_type: self.type_(&fn_sig.return_type),
+        }
+    }
+
+    fn dyn_bounds(&mut self, dyn_bounds: &DynBounds) -> DynBounds<'static> {
+        DynBounds {
+            bound_lifetimes: dyn_bounds.bound_lifetimes,
+            dyn_traits: dyn_bounds
+                .dyn_traits
+                .iter()
+                .map(|dyn_trait| DynTrait {
+                    path: self.path(&dyn_trait.path),
+                    dyn_trait_assoc_bindings: dyn_trait
+                        .dyn_trait_assoc_bindings
+                        .iter()
+                        .map(|binding| DynTraitAssocBinding {
+                            name: owned_str(binding.name.clone()),
+                            type_: self.type_(&binding.type_),
+                            is_undecoded_punycode: binding.is_undecoded_punycode,
+                        })
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+
+    fn const_(&mut self, const_: &NodeRc<Const>) -> NodeRc<Const<'static>> {
+        if let Some(result) = self.consts.get(&key(const_)) {
+            return NodeRc::clone(result);
+        }
+
+        let mut result = NodeRc::new(self.const_node(const_));
+
+        if let Some(interner) = &mut self.interner {
+            result = interner.intern_const(result);
+        }
+
+        self.consts.insert(key(const_), NodeRc::clone(&result));
+
+        result
+    }
+
+    pub fn const_node(&mut self, const_: &Const) -> Const<'static> {
+        match const_ {
+            Const::I8(value) => Const::I8(*value),
+            Const::U8(value) => Const::U8(*value),
+            Const::Isize(value) => Const::Isize(*value),
+            Const::Usize(value) => Const::Usize(*value),
+            Const::I32(value) => Const::I32(*value),
+            Const::U32(value) => Const::U32(*value),
+            Const::I128(value) => Const::I128(*value),
+            Const::U128(value) => Const::U128(*value),
+            Const::I16(value) => Const::I16(*value),
+            Const::U16(value) => Const::U16(*value),
+            Const::I64(value) => Const::I64(*value),
+            Const::U64(value) => Const::U64(*value),
+            Const::Bool(value) => Const::Bool(*value),
+            Const::Char(value) => Const::Char(*value),
+            Const::F32(bits) => Const::F32(*bits),
+            Const::F64(bits) => Const::F64(*bits),
+            Const::Str(value) => Const::Str(value.clone()),
+            Const::Ref(value) => Const::Ref(self.const_(value)),
+            Const::RefMut(value) => Const::RefMut(self.const_(value)),
+            Const::Array(items) => Const::Array(items.iter().map(|item| self.const_(item)).collect()),
+            Const::Tuple(items) => Const::Tuple(items.iter().map(|item| self.const_(item)).collect()),
+            Const::NamedStruct { path, fields } => Const::NamedStruct {
+                path: self.path(path),
+                fields: match fields {
+                    ConstFields::Unit => ConstFields::Unit,
+                    ConstFields::Tuple(fields) => {
+                        ConstFields::Tuple(fields.iter().map(|field| self.const_(field)).collect())
+                    }
+                    ConstFields::Struct(fields) => ConstFields::Struct(
+                        fields
+                            .iter()
+                            .map(|(name, field)| (identifier_node(name), self.const_(field)))
+                            .collect(),
+                    ),
+                },
+            },
+            Const::Placeholder => Const::Placeholder,
+        }
+    }
+}
+
+fn identifier_node(identifier: &Identifier) -> Identifier<'static> {
+    Identifier {
+        disambiguator: identifier.disambiguator,
+        name: owned_str(identifier.name.clone()),
+        is_undecoded_punycode: identifier.is_undecoded_punycode,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rust_v0::{GenericArg, Interner, NodeRc, Path, Symbol, Type};
+
+    #[test]
+    fn test_into_owned() {
+        let name = String::from("_RINvC3foo3barINtC3baz3QuxhEBb_E.llvm.123");
+        let (symbol, _) = Symbol::parse_from_str(&name).unwrap();
+        let expected = symbol.to_string();
+        let owned: Symbol<'static> = symbol.clone().into_owned();
+
+        drop(name);
+
+        assert_eq!(owned.to_string(), expected);
+        assert_eq!(owned.vendor_specific_suffix.as_deref(), Some(".llvm.123"));
+
+        // Back referenced subtrees stay shared after the conversion.
+        let Path::Generic { generic_args, .. } = owned.path.as_ref() else {
+            unreachable!()
+        };
+
+        let [GenericArg::Type(first), GenericArg::Type(second)] = generic_args.as_slice() else {
+            unreachable!()
+        };
+
+        let (Type::Named(first), Type::Named(second)) = (first.as_ref(), second.as_ref()) else {
+            unreachable!()
+        };
+
+        assert!(NodeRc::ptr_eq(first, second));
+    }
+
+    #[test]
+    fn test_into_interned() {
+        let mut interner = Interner::new();
+
+        // Different crates with the same generic arguments.
+        let (first, _) =
+            Symbol::parse_interned("_RINvC3foo3barINtC5alloc3VechEKj2_INtC5alloc3VechEE", &mut interner).unwrap();
+        let (second, _) = Symbol::parse_interned("_RINvC3baz3quxINtC5alloc3VechEKj2_E", &mut interner).unwrap();
+
+        assert_eq!(first.to_string(), "foo::bar::<alloc::Vec<u8>, 2usize, alloc::Vec<u8>>");
+
+        let generic_args = |symbol: &Symbol<'static>| match symbol.path.as_ref() {
+            Path::Generic { generic_args, .. } => generic_args.clone(),
+            _ => unreachable!(),
+        };
+
+        let first_args = generic_args(&first);
+        let second_args = generic_args(&second);
+
+        let (
+            [GenericArg::Type(first_type), GenericArg::Const(first_const), GenericArg::Type(third_type)],
+            [GenericArg::Type(second_type), GenericArg::Const(second_const)],
+        ) = (first_args.as_slice(), second_args.as_slice())
+        else {
+            unreachable!()
+        };
+
+        // Equal subtrees are shared within and between symbols.
+        assert!(NodeRc::ptr_eq(first_type, third_type));
+        assert!(NodeRc::ptr_eq(first_type, second_type));
+        assert!(NodeRc::ptr_eq(first_const, second_const));
+        assert!(!interner.is_empty());
+
+        let (third, _) = Symbol::parse_from_str("_RINvC3foo3barINtC5alloc3VechEKj2_INtC5alloc3VechEE").unwrap();
+
+        assert_eq!(third.clone().into_interned(&mut interner), first);
+        assert_eq!(third.into_owned(), first);
+
+        interner.clear();
+
+        assert!(interner.is_empty());
+        assert_eq!(
+            format!("{interner:?}"),
+            "Interner { paths: 0, types: 0, consts: 0, .. }"
+        );
+    }
+}
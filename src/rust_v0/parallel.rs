@@ -0,0 +1,47 @@
+//! Demangling many symbols in parallel.
+
+use crate::rust_v0::{DemangleError, DisplayStyle};
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+/// Demangles every mangled name in `symbols` with [`demangle_to_writer`](crate::rust_v0::demangle_to_writer) on the
+/// rayon thread pool, and returns the results in the order of `symbols`. Each result contains the symbol displayed with
+/// `style`, and the suffix of the mangled name that is not part of the Rust v0 syntax.
+///
+/// No AST is built, so this works without the `sync` feature.
+pub fn demangle_all<'a>(
+    symbols: impl IntoParallelIterator<Item = &'a str>,
+    style: DisplayStyle,
+) -> Vec<Result<(String, &'a str), DemangleError>> {
+    symbols
+        .into_par_iter()
+        .map(|symbol| {
+            let mut output = String::new();
+
+            crate::rust_v0::demangle_to_writer(symbol, style, &mut output).map(|suffix| (output, suffix))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rust_v0::{DemangleError, DisplayStyle};
+
+    #[test]
+    fn test_demangle_all() {
+        let symbols = ["_RNvC3foo3bar", "foo", "_RNvNtC3std3mem4swap.llvm.123"].repeat(100);
+        let results = super::demangle_all(symbols.clone(), DisplayStyle::Normal);
+
+        assert_eq!(results.len(), symbols.len());
+
+        for chunk in results.chunks(3) {
+            assert_eq!(
+                chunk,
+                [
+                    Ok((String::from("foo::bar"), "")),
+                    Err(DemangleError::Invalid),
+                    Ok((String::from("std::mem::swap"), "")),
+                ]
+            );
+        }
+    }
+}
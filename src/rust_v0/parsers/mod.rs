@@ -3,26 +3,113 @@ use crate::mini_parser::input::{Find, SplitAt, StripPrefix};
 use crate::mini_parser::parsers::{alphanumeric0, digit1, lower_hex_digit0, tag, take};
 use crate::mini_parser::Parser;
 use crate::rust_v0::{
-    Abi, BasicType, Const, ConstFields, DynBounds, DynTrait, DynTraitAssocBinding, FnSig, GenericArg, Identifier,
-    ImplPath, Path, Symbol, Type,
+    sharing, spans, Abi, BackRef, BasicType, Const, ConstFields, ConstStr, DynBounds, DynTrait, DynTraitAssocBinding,
+    FnSig, GenericArg, Identifier, ImplPath, Namespace, NodeRc, NodeSpan, ParseError, ParseErrorKind, ParseOptions,
+    Path, Production, SharedNode, SharingGraph, SourceSpans, Symbol, Type,
 };
 use num_traits::{CheckedNeg, PrimInt};
 use std::borrow::Cow;
 use std::collections::HashMap;
-use std::rc::Rc;
+use std::mem::{self, Discriminant};
 use std::str;
 
 #[cfg(test)]
 mod tests;
 
-const MAX_DEPTH: usize = 100;
+const BASIC_TYPE_COUNT: usize = BasicType::Placeholder as usize + 1;
+
+/// Parsed nodes that can be back referenced with the heights of their subtrees, indexed by their positions in the
+/// input. Nodes are recorded after their children, so out of order, and indexing keeps both inserting and looking up
+/// constant time.
+struct BackRefTable<T> {
+    nodes: Vec<Option<(NodeRc<T>, usize)>>,
+}
+
+impl<T> Default for BackRefTable<T> {
+    fn default() -> Self {
+        Self { nodes: Vec::new() }
+    }
+}
+
+impl<T> BackRefTable<T> {
+    fn get(&self, position: usize) -> Option<(&NodeRc<T>, usize)> {
+        self.nodes.get(position)?.as_ref().map(|(node, height)| (node, *height))
+    }
+
+    /// Inserts `node` at `position`, replacing the node parsed at the same position before backtracking, if any.
+    fn insert(&mut self, position: usize, node: NodeRc<T>, height: usize) {
+        if self.nodes.len() <= position {
+            self.nodes.resize_with(position + 1, || None);
+        }
+
+        self.nodes[position] = Some((node, height));
+    }
+}
 
 #[derive(Default)]
 struct Context<'a> {
-    paths: HashMap<usize, Rc<Path<'a>>>,
-    types: HashMap<usize, Rc<Type<'a>>>,
-    consts: HashMap<usize, Rc<Const<'a>>>,
+    paths: BackRefTable<Path<'a>>,
+    types: BackRefTable<Type<'a>>,
+    consts: BackRefTable<Const<'a>>,
+    basic_types: [Option<NodeRc<Type<'a>>>; BASIC_TYPE_COUNT],
+    options: ParseOptions,
     depth: usize,
+    /// The deepest nesting level reached by the node being parsed, counting the nodes back references expand to.
+    deepest: usize,
+    sharing: Option<SharingRecorder<'a>>,
+    spans: Option<SpanRecorder<'a>>,
+    error: Option<ParseError>,
+}
+
+impl<'a> Context<'a> {
+    /// Returns the node of `basic_type`, allocating it on first use, so repeated basic types within a symbol share one
+    /// node. Other nodes are only shared through back references.
+    fn basic_type(&mut self, basic_type: BasicType) -> NodeRc<Type<'a>> {
+        NodeRc::clone(self.basic_types[basic_type as usize].get_or_insert_with(|| NodeRc::new(Type::Basic(basic_type))))
+    }
+
+    /// Keeps the failure that happens farthest into the input, since failures before it are usually alternatives that
+    /// do not match. Among failures at the same offset, the outermost production is kept. Exceeding a limit takes
+    /// precedence over syntax errors, because the syntax errors may be caused by the limit.
+    fn record_error(&mut self, offset: usize, production: Production, kind: ParseErrorKind) {
+        let replace = self
+            .error
+            .is_none_or(|error| match (error.kind.is_limit(), kind.is_limit()) {
+                (false, false) => offset >= error.offset,
+                (false, true) => true,
+                (true, _) => false,
+            });
+
+        if replace {
+            self.error = Some(ParseError {
+                offset,
+                production,
+                kind,
+            });
+        }
+    }
+
+    fn take_error(&mut self) -> ParseError {
+        self.error.take().unwrap_or(ParseError {
+            offset: 0,
+            production: Production::Path,
+            kind: ParseErrorKind::Invalid,
+        })
+    }
+}
+
+/// Records the extents of back referenceable nodes and the back references to them.
+#[derive(Default)]
+struct SharingRecorder<'a> {
+    ends: HashMap<usize, usize>,
+    back_refs: Vec<(usize, usize, usize, SharedNode<'a>)>,
+}
+
+/// Records the spans of parsed paths, types and constants, keyed by their positions and kinds. Nodes parsed at the same
+/// position before backtracking are replaced.
+#[derive(Default)]
+struct SpanRecorder<'a> {
+    spans: HashMap<(usize, Discriminant<SharedNode<'a>>), NodeSpan<'a>>,
 }
 
 #[derive(Clone)]
@@ -92,12 +179,43 @@ where
     })
 }
 
-fn limit_recursion_depth<'a, I, T>(
-    mut parser: impl Parser<I, Context<'a>, Output = T>,
-) -> impl Parser<I, Context<'a>, Output = T> {
-    move |input, context: &mut Context<'a>| {
-        if context.depth < MAX_DEPTH {
+/// Records a failure of `parser` as a failure to parse `production` at the start of the input.
+fn production<'a, T>(
+    production: Production,
+    mut parser: impl Parser<IndexedStr<'a>, Context<'a>, Output = T>,
+) -> impl Parser<IndexedStr<'a>, Context<'a>, Output = T> {
+    move |input: IndexedStr<'a>, context: &mut Context<'a>| {
+        let offset = input.index;
+        let result = parser.parse(input, context);
+
+        if result.is_err() {
+            context.record_error(offset, production, ParseErrorKind::Invalid);
+        }
+
+        result
+    }
+}
+
+/// Enforces [`ParseOptions::max_depth`] and [`ParseOptions::fuel`]. Every attempt to parse a path, type or constant
+/// consumes one unit of fuel, including attempts that fail and are backtracked.
+fn limit_resources<'a, T>(
+    production: Production,
+    mut parser: impl Parser<IndexedStr<'a>, Context<'a>, Output = T>,
+) -> impl Parser<IndexedStr<'a>, Context<'a>, Output = T> {
+    move |input: IndexedStr<'a>, context: &mut Context<'a>| {
+        if let Some(fuel) = &mut context.options.fuel {
+            if *fuel == 0 {
+                context.record_error(input.index, production, ParseErrorKind::BudgetExceeded);
+
+                return Err(());
+            }
+
+            *fuel -= 1;
+        }
+
+        if context.depth < context.options.max_depth {
             context.depth += 1;
+            context.deepest = context.deepest.max(context.depth);
 
             let result = parser.parse(input, context);
 
@@ -105,6 +223,8 @@ fn limit_recursion_depth<'a, I, T>(
 
             result
         } else {
+            context.record_error(input.index, production, ParseErrorKind::RecursionLimit);
+
             Err(())
         }
     }
@@ -112,20 +232,97 @@ fn limit_recursion_depth<'a, I, T>(
 
 fn back_referenced<'a, T>(
     index: usize,
-    base_parser: impl Parser<IndexedStr<'a>, Context<'a>, Output = T>,
-    mut get_table_fn: impl for<'b> FnMut(&'b mut Context<'a>) -> &'b mut HashMap<usize, Rc<T>> + Copy,
-) -> impl Parser<IndexedStr<'a>, Context<'a>, Output = Rc<T>>
+    base_parser: impl Parser<IndexedStr<'a>, Context<'a>, Output = NodeRc<T>>,
+    mut get_table_fn: impl for<'b> FnMut(&'b mut Context<'a>) -> &'b mut BackRefTable<T> + Copy,
+    to_shared_node: fn(NodeRc<T>) -> SharedNode<'a>,
+    node_production: Production,
+) -> impl Parser<IndexedStr<'a>, Context<'a>, Output = NodeRc<T>>
 where
     T: 'a,
 {
-    limit_recursion_depth(
-        or(
-            base_parser.map(Rc::new),
-            parse_back_ref.map_opt_with_context(move |back_ref, context| get_table_fn(context).get(&back_ref).cloned()),
-        )
-        .inspect_with_context(move |result, context| {
-            get_table_fn(context).insert(index, Rc::clone(result));
+    // The failure is reported at the index rather than the `B` tag, so it is not replaced by the enclosing production
+    // failing at the same position.
+    let mut parse_back_ref = production(Production::BackRef, parse_back_ref_index);
+
+    let mut parser = or(
+        base_parser.map(|result| (result, None)),
+        preceded(tag('B'), move |input: IndexedStr<'a>, context: &mut Context<'a>| {
+            let offset = input.index;
+            let (target, rest) = parse_back_ref.parse(input, context)?;
+
+            // Nodes parsed by backtracked alternatives may be left at later positions, so the table alone does not
+            // reject forward references.
+            let kind = if target >= index {
+                ParseErrorKind::ForwardBackRef { target }
+            } else if let Some((result, height)) = get_table_fn(context).get(target) {
+                let result = NodeRc::clone(result);
+
+                // The target is expanded in place, so its subtree counts towards the nesting depth. Otherwise chains
+                // of back references could build trees deep enough to overflow the stack when displayed or dropped.
+                return match context.depth.checked_add(height) {
+                    Some(deepest) if deepest <= context.options.max_depth => {
+                        context.deepest = context.deepest.max(deepest);
+
+                        Ok(((result, Some(target)), rest))
+                    }
+                    _ => {
+                        context.record_error(offset, Production::BackRef, ParseErrorKind::RecursionLimit);
+
+                        Err(())
+                    }
+                };
+            } else {
+                ParseErrorKind::BackRefTargetMismatch {
+                    target,
+                    expected: node_production,
+                }
+            };
+
+            context.record_error(offset, Production::BackRef, kind);
+
+            Err(())
         }),
+    );
+
+    limit_resources(
+        node_production,
+        move |input: IndexedStr<'a>, context: &mut Context<'a>| {
+            let outer_deepest = mem::replace(&mut context.deepest, context.depth);
+            let result = parser.parse(input, context);
+            let deepest = mem::replace(&mut context.deepest, outer_deepest);
+            let ((result, back_ref), rest) = result?;
+
+            context.deepest = context.deepest.max(deepest);
+
+            if let Some(sharing) = &mut context.sharing {
+                sharing.ends.insert(index, rest.index);
+
+                if let Some(target) = back_ref {
+                    sharing
+                        .back_refs
+                        .push((index, rest.index, target, to_shared_node(NodeRc::clone(&result))));
+                }
+            }
+
+            if let Some(spans) = &mut context.spans {
+                let node = to_shared_node(NodeRc::clone(&result));
+
+                spans.spans.insert(
+                    (index, mem::discriminant(&node)),
+                    NodeSpan {
+                        range: index..rest.index,
+                        back_ref_target: back_ref,
+                        node,
+                    },
+                );
+            }
+
+            let height = deepest - context.depth;
+
+            get_table_fn(context).insert(index, NodeRc::clone(&result), height);
+
+            Ok((result, rest))
+        },
     )
 }
 
@@ -136,8 +333,136 @@ where
 // - <https://github.com/rust-lang/rust/blob/master/compiler/rustc_symbol_mangling/src/v0.rs>.
 // - <https://rust-lang.github.io/rfcs/2603-rust-symbol-name-mangling-v0.html>.
 
-pub fn parse_symbol(input: &str) -> Result<(Symbol, &str), ()> {
-    parse_symbol_inner(IndexedStr::new(input), &mut Context::default()).map(|(symbol, suffix)| (symbol, suffix.data))
+pub fn parse_symbol(input: &str, options: ParseOptions) -> Result<(Symbol<'_>, &str), ParseError> {
+    let mut context = Context {
+        options,
+        ..Context::default()
+    };
+
+    parse_symbol_inner(IndexedStr::new(input), &mut context)
+        .map(|(symbol, suffix)| (symbol, suffix.data))
+        .map_err(|()| context.take_error())
+}
+
+/// Parses `input` like [`parse_symbol`], but if the path of the symbol fails to parse, for example because the input is
+/// truncated, recovers the outermost path enclosing the failure that parses on its own.
+pub fn parse_partial_symbol(
+    input: &str,
+    options: ParseOptions,
+) -> Result<(Symbol<'_>, Option<ParseError>), ParseError> {
+    let error = match parse_symbol(input, options) {
+        Ok((symbol, _)) => return Ok((symbol, None)),
+        Err(error) if error.kind.is_limit() => return Err(error),
+        Err(error) => error,
+    };
+
+    let mut context = Context {
+        options,
+        ..Context::default()
+    };
+
+    let (version, rest) = parse_decimal_number
+        .opt()
+        .parse(IndexedStr::new(input), &mut context)
+        .map_err(|()| error)?;
+
+    // Paths enclosing others come first in the mangled name, so every `N` and `I` tag on the spine of the path starts
+    // a path that ends before the failure, unless the failure is inside that path.
+    let mut starts = Vec::new();
+    let mut position = rest.index;
+
+    loop {
+        starts.push(position);
+
+        match input.as_bytes().get(position) {
+            Some(b'N') => position += 2,
+            Some(b'I') => position += 1,
+            _ => break,
+        }
+    }
+
+    let path = starts
+        .into_iter()
+        .skip(1)
+        .find_map(|start| {
+            let mut context = Context {
+                options,
+                ..Context::default()
+            };
+
+            let data = input.get(start..)?;
+
+            parse_path(IndexedStr { index: start, data }, &mut context)
+                .ok()
+                .map(|(path, _)| path)
+        })
+        .ok_or(error)?;
+
+    let symbol = Symbol {
+        version,
+        path,
+        instantiating_crate: None,
+        vendor_specific_suffix: None,
+    };
+
+    Ok((symbol, Some(error)))
+}
+
+pub fn parse_symbol_with_sharing_graph(input: &str) -> Result<(Symbol<'_>, &str, SharingGraph<'_>), ParseError> {
+    let mut context = Context {
+        sharing: Some(SharingRecorder::default()),
+        ..Context::default()
+    };
+
+    let (symbol, suffix) =
+        parse_symbol_inner(IndexedStr::new(input), &mut context).map_err(|()| context.take_error())?;
+    let sharing = context.sharing.unwrap_or_default();
+
+    let back_refs = sharing
+        .back_refs
+        .into_iter()
+        .map(|(position, end, target, node)| BackRef {
+            position,
+            length: end - position,
+            target,
+            target_length: sharing.ends[&target] - target,
+            node,
+        })
+        .collect();
+
+    let encoded_length =
+        input.len() - suffix.data.len() - symbol.vendor_specific_suffix.as_ref().map_or(0, |suffix| suffix.len());
+
+    Ok((symbol, suffix.data, sharing::sharing_graph(back_refs, encoded_length)))
+}
+
+/// Parses `input` while recording the spans of nodes, shifting the positions by `offset`.
+pub fn parse_symbol_with_spans(input: &str, offset: usize) -> Result<(Symbol<'_>, &str, SourceSpans<'_>), ParseError> {
+    let mut context = Context {
+        spans: Some(SpanRecorder::default()),
+        ..Context::default()
+    };
+
+    let (symbol, suffix) =
+        parse_symbol_inner(IndexedStr::new(input), &mut context).map_err(|()| context.take_error())?;
+    let recorder = context.spans.unwrap_or_default();
+
+    let encoded_length =
+        input.len() - suffix.data.len() - symbol.vendor_specific_suffix.as_ref().map_or(0, |suffix| suffix.len());
+
+    // Nodes of an instantiating crate that failed to parse may be left after the end of the parsed part.
+    let spans = recorder
+        .spans
+        .into_values()
+        .filter(|span| span.range.end <= encoded_length)
+        .map(|span| NodeSpan {
+            range: span.range.start + offset..span.range.end + offset,
+            back_ref_target: span.back_ref_target.map(|target| target + offset),
+            node: span.node,
+        })
+        .collect();
+
+    Ok((symbol, suffix.data, spans::source_spans(spans)))
 }
 
 fn parse_symbol_inner<'a>(
@@ -154,40 +479,46 @@ fn parse_symbol_inner<'a>(
         version,
         path,
         instantiating_crate,
-        vendor_specific_suffix,
+        vendor_specific_suffix: vendor_specific_suffix.map(Cow::Borrowed),
     })
     .parse(input, context)
 }
 
-fn parse_path<'a>(input: IndexedStr<'a>, context: &mut Context<'a>) -> Result<(Rc<Path<'a>>, IndexedStr<'a>), ()> {
-    back_referenced(
-        input.index,
-        alt((
-            preceded(tag('C'), parse_identifier).map(Path::CrateRoot),
-            preceded(tag('M'), and(parse_impl_path, parse_type))
-                .map(|(impl_path, type_)| Path::InherentImpl { impl_path, type_ }),
-            preceded(tag('X'), tuple((parse_impl_path, parse_type, parse_path))).map(|(impl_path, type_, trait_)| {
-                Path::TraitImpl {
-                    impl_path,
-                    type_,
-                    trait_,
-                }
-            }),
-            preceded(tag('Y'), and(parse_type, parse_path))
-                .map(|(type_, trait_)| Path::TraitDefinition { type_, trait_ }),
-            preceded(tag('N'), tuple((take(1_usize), parse_path, parse_identifier))).map_opt(
-                |(namespace, path, identifier)| {
-                    matches!(namespace.as_bytes()[0], b'A'..=b'Z' | b'a'..=b'z').then(|| Path::Nested {
-                        namespace: namespace.as_bytes()[0],
-                        path,
-                        identifier,
-                    })
-                },
-            ),
-            delimited(tag('I'), and(parse_path, parse_generic_arg.many0()), tag('E'))
-                .map(|(path, generic_args)| Path::Generic { path, generic_args }),
-        )),
-        |context| &mut context.paths,
+fn parse_path<'a>(input: IndexedStr<'a>, context: &mut Context<'a>) -> Result<(NodeRc<Path<'a>>, IndexedStr<'a>), ()> {
+    production(
+        Production::Path,
+        back_referenced(
+            input.index,
+            alt((
+                preceded(tag('C'), parse_identifier).map(Path::CrateRoot),
+                preceded(tag('M'), and(parse_impl_path, parse_type))
+                    .map(|(impl_path, type_)| Path::InherentImpl { impl_path, type_ }),
+                preceded(tag('X'), tuple((parse_impl_path, parse_type, parse_path))).map(
+                    |(impl_path, type_, trait_)| Path::TraitImpl {
+                        impl_path,
+                        type_,
+                        trait_,
+                    },
+                ),
+                preceded(tag('Y'), and(parse_type, parse_path))
+                    .map(|(type_, trait_)| Path::TraitDefinition { type_, trait_ }),
+                preceded(tag('N'), tuple((take(1_usize), parse_path, parse_identifier))).map_opt(
+                    |(namespace, path, identifier)| {
+                        Namespace::from_char(char::from(namespace.as_bytes()[0])).map(|namespace| Path::Nested {
+                            namespace,
+                            path,
+                            identifier,
+                        })
+                    },
+                ),
+                delimited(tag('I'), and(parse_path, parse_generic_arg.many0()), tag('E'))
+                    .map(|(path, generic_args)| Path::Generic { path, generic_args }),
+            ))
+            .map(NodeRc::new),
+            |context| &mut context.paths,
+            SharedNode::Path,
+            Production::Path,
+        ),
     )
     .parse(input, context)
 }
@@ -202,151 +533,165 @@ fn parse_identifier<'a>(
     input: IndexedStr<'a>,
     context: &mut Context<'a>,
 ) -> Result<(Identifier<'a>, IndexedStr<'a>), ()> {
-    and(opt_u64(parse_disambiguator), parse_undisambiguated_identifier)
-        .map(|(disambiguator, name)| Identifier { disambiguator, name })
-        .parse(input, context)
+    production(
+        Production::Identifier,
+        and(opt_u64(parse_disambiguator), parse_undisambiguated_identifier).map(
+            |(disambiguator, (name, is_undecoded_punycode))| Identifier {
+                disambiguator,
+                name,
+                is_undecoded_punycode,
+            },
+        ),
+    )
+    .parse(input, context)
 }
 
 fn parse_disambiguator<'a>(input: IndexedStr<'a>, context: &mut Context<'a>) -> Result<(u64, IndexedStr<'a>), ()> {
     preceded(tag('s'), parse_base62_number).parse(input, context)
 }
 
+/// Parses an identifier without a disambiguator, returning its name and whether the name is Punycode that is kept
+/// undecoded because of [`ParseOptions::lossy_punycode`].
 fn parse_undisambiguated_identifier<'a>(
     input: IndexedStr<'a>,
     context: &mut Context<'a>,
-) -> Result<(Cow<'a, str>, IndexedStr<'a>), ()> {
+) -> Result<((Cow<'a, str>, bool), IndexedStr<'a>), ()> {
+    let lossy_punycode = context.options.lossy_punycode;
+
     tuple((tag('u').opt(), parse_decimal_number, tag('_').opt()))
-        .flat_map(|(punycode, length, _)| {
+        .flat_map(move |(punycode, length, _)| {
             let is_punycode = punycode.is_some();
 
-            take(length).map_opt(move |name: &str| {
-                if is_punycode {
-                    let i = name.bytes().rposition(|c| c == b'_').map_or(0, |i| i + 1);
-                    let right = &name[i..];
-
-                    if right.is_empty() {
-                        None
-                    } else if right.bytes().all(|c| matches!(c, b'0'..=b'9' | b'a'..=b'z')) {
-                        let mut bytes = Vec::with_capacity(name.len());
-
-                        if i != 0 {
-                            bytes.extend(&name.as_bytes()[..i - 1]);
-                            bytes.push(b'-');
-                        }
-
-                        bytes.extend(right.as_bytes());
-
-                        if let Ok(decoded) = punycode::decode(str::from_utf8(&bytes).unwrap()) {
-                            Some(Cow::Owned(decoded))
-                        } else {
-                            None
-                        }
+            let kind = if is_punycode {
+                Production::PunycodeIdentifier
+            } else {
+                Production::Identifier
+            };
+
+            production(
+                kind,
+                take(length).map_opt(move |name: &str| {
+                    if !is_punycode {
+                        Some((Cow::Borrowed(name), false))
+                    } else if let Some(decoded) = decode_punycode_identifier(name) {
+                        Some((Cow::Owned(decoded), false))
                     } else {
-                        None
+                        lossy_punycode.then_some((Cow::Borrowed(name), true))
                     }
-                } else {
-                    Some(Cow::Borrowed(name))
-                }
-            })
+                }),
+            )
         })
         .parse(input, context)
 }
 
+/// Decodes the name of a punycode identifier, where rustc replaces the last `-` of the punycode encoding with `_`.
+pub fn decode_punycode_identifier(name: &str) -> Option<String> {
+    let i = name.bytes().rposition(|c| c == b'_').map_or(0, |i| i + 1);
+    let right = &name[i..];
+
+    if right.is_empty() || !right.bytes().all(|c| matches!(c, b'0'..=b'9' | b'a'..=b'z')) {
+        return None;
+    }
+
+    let mut encoded = String::with_capacity(name.len());
+
+    if i != 0 {
+        encoded.push_str(&name[..i - 1]);
+        encoded.push('-');
+    }
+
+    encoded.push_str(right);
+
+    punycode::decode(&encoded).ok()
+}
+
 fn parse_generic_arg<'a>(
     input: IndexedStr<'a>,
     context: &mut Context<'a>,
 ) -> Result<(GenericArg<'a>, IndexedStr<'a>), ()> {
-    alt((
-        parse_lifetime.map(GenericArg::Lifetime),
-        parse_type.map(GenericArg::Type),
-        preceded(tag('K'), parse_const).map(GenericArg::Const),
-    ))
+    production(
+        Production::GenericArg,
+        alt((
+            parse_lifetime.map(GenericArg::Lifetime),
+            parse_type.map(GenericArg::Type),
+            preceded(tag('K'), parse_const).map(GenericArg::Const),
+        )),
+    )
     .parse(input, context)
 }
 
 fn parse_lifetime<'a>(input: IndexedStr<'a>, context: &mut Context<'a>) -> Result<(u64, IndexedStr<'a>), ()> {
-    preceded(tag('L'), parse_base62_number).parse(input, context)
+    production(Production::Lifetime, preceded(tag('L'), parse_base62_number)).parse(input, context)
 }
 
 fn parse_binder<'a>(input: IndexedStr<'a>, context: &mut Context<'a>) -> Result<(u64, IndexedStr<'a>), ()> {
-    preceded(tag('G'), parse_base62_number).parse(input, context)
-}
-
-fn parse_type<'a>(input: IndexedStr<'a>, context: &mut Context<'a>) -> Result<(Rc<Type<'a>>, IndexedStr<'a>), ()> {
-    back_referenced(
-        input.index,
-        alt((
-            parse_basic_type.map(Type::Basic),
-            parse_path.map(Type::Named),
-            preceded(tag('A'), and(parse_type, parse_const)).map(|(type_, length)| Type::Array(type_, length)),
-            preceded(tag('S'), parse_type).map(Type::Slice),
-            delimited(tag('T'), parse_type.many0(), tag('E')).map(Type::Tuple),
-            preceded(
-                tag('R'),
-                and(parse_lifetime.opt().map(Option::unwrap_or_default), parse_type),
-            )
-            .map(|(lifetime, type_)| Type::Ref { lifetime, type_ }),
-            preceded(
-                tag('Q'),
-                and(parse_lifetime.opt().map(Option::unwrap_or_default), parse_type),
-            )
-            .map(|(lifetime, type_)| Type::RefMut { lifetime, type_ }),
-            preceded(tag('P'), parse_type).map(Type::PtrConst),
-            preceded(tag('O'), parse_type).map(Type::PtrMut),
-            preceded(tag('F'), parse_fn_sig).map(Type::Fn),
-            preceded(tag('D'), and(parse_dyn_bounds, parse_lifetime))
-                .map(|(dyn_bounds, lifetime)| Type::DynTrait { dyn_bounds, lifetime }),
-        )),
-        |context| &mut context.types,
+    production(Production::Binder, preceded(tag('G'), parse_base62_number)).parse(input, context)
+}
+
+fn parse_type<'a>(input: IndexedStr<'a>, context: &mut Context<'a>) -> Result<(NodeRc<Type<'a>>, IndexedStr<'a>), ()> {
+    production(
+        Production::Type,
+        back_referenced(
+            input.index,
+            or(
+                parse_basic_type
+                    .map_with_context(|basic_type, context: &mut Context<'a>| context.basic_type(basic_type)),
+                alt((
+                    parse_path.map(Type::Named),
+                    preceded(tag('A'), and(parse_type, parse_const)).map(|(type_, length)| Type::Array(type_, length)),
+                    preceded(tag('S'), parse_type).map(Type::Slice),
+                    delimited(tag('T'), parse_type.many0(), tag('E')).map(Type::Tuple),
+                    preceded(
+                        tag('R'),
+                        and(parse_lifetime.opt().map(Option::unwrap_or_default), parse_type),
+                    )
+                    .map(|(lifetime, type_)| Type::Ref { lifetime, type_ }),
+                    preceded(
+                        tag('Q'),
+                        and(parse_lifetime.opt().map(Option::unwrap_or_default), parse_type),
+                    )
+                    .map(|(lifetime, type_)| Type::RefMut { lifetime, type_ }),
+                    preceded(tag('P'), parse_type).map(Type::PtrConst),
+                    preceded(tag('O'), parse_type).map(Type::PtrMut),
+                    preceded(tag('F'), parse_fn_sig).map(Type::Fn),
+                    preceded(tag('D'), and(parse_dyn_bounds, parse_lifetime))
+                        .map(|(dyn_bounds, lifetime)| Type::DynTrait { dyn_bounds, lifetime }),
+                ))
+                .map(NodeRc::new),
+            ),
+            |context| &mut context.types,
+            SharedNode::Type,
+            Production::Type,
+        ),
     )
     .parse(input, context)
 }
 
 fn parse_basic_type<'a>(input: IndexedStr<'a>, context: &mut Context<'a>) -> Result<(BasicType, IndexedStr<'a>), ()> {
     take(1_usize)
-        .map_opt(|s: &str| match s.as_bytes()[0] {
-            b'a' => Some(BasicType::I8),
-            b'b' => Some(BasicType::Bool),
-            b'c' => Some(BasicType::Char),
-            b'd' => Some(BasicType::F64),
-            b'e' => Some(BasicType::Str),
-            b'f' => Some(BasicType::F32),
-            b'h' => Some(BasicType::U8),
-            b'i' => Some(BasicType::Isize),
-            b'j' => Some(BasicType::Usize),
-            b'l' => Some(BasicType::I32),
-            b'm' => Some(BasicType::U32),
-            b'n' => Some(BasicType::I128),
-            b'o' => Some(BasicType::U128),
-            b's' => Some(BasicType::I16),
-            b't' => Some(BasicType::U16),
-            b'u' => Some(BasicType::Unit),
-            b'v' => Some(BasicType::Ellipsis),
-            b'x' => Some(BasicType::I64),
-            b'y' => Some(BasicType::U64),
-            b'z' => Some(BasicType::Never),
-            b'p' => Some(BasicType::Placeholder),
-            _ => None,
-        })
+        .map_opt(|s: &str| BasicType::from_tag(s.as_bytes()[0]))
         .parse(input, context)
 }
 
 fn parse_fn_sig<'a>(input: IndexedStr<'a>, context: &mut Context<'a>) -> Result<(FnSig<'a>, IndexedStr<'a>), ()> {
-    tuple((
-        opt_u64(parse_binder),
-        tag('U').opt(),
-        preceded(tag('K'), parse_abi).opt(),
-        terminated(parse_type.many0(), tag('E')),
-        parse_type,
-    ))
-    .map(
-        |(bound_lifetimes, unsafe_tag, abi, argument_types, return_type)| FnSig {
-            bound_lifetimes,
-            is_unsafe: unsafe_tag.is_some(),
-            abi,
-            argument_types,
-            return_type,
-        },
+    production(
+        Production::FnSig,
+        tuple((
+            opt_u64(parse_binder),
+            tag('U').opt(),
+            preceded(tag('K'), parse_abi).opt(),
+            terminated(parse_type.many0(), tag('E')),
+            parse_type,
+        ))
+        .map(
+            |(bound_lifetimes, unsafe_tag, abi, argument_types, return_type)| FnSig {
+                bound_lifetimes,
+                is_unsafe: unsafe_tag.is_some(),
+                abi,
+                argument_types,
+                return_type,
+            },
+        ),
     )
     .parse(input, context)
 }
@@ -356,10 +701,15 @@ fn parse_abi<'a>(input: IndexedStr<'a>, context: &mut Context<'a>) -> Result<(Ab
         !name.is_empty() && name.is_ascii()
     }
 
-    alt((
-        tag('C').map(|_| Abi::C),
-        parse_undisambiguated_identifier.map_opt(|id| is_abi_name(&id).then_some(Abi::Named(id))),
-    ))
+    production(
+        Production::Abi,
+        alt((
+            tag('C').map(|_| Abi::C),
+            parse_undisambiguated_identifier.map_opt(|(id, is_undecoded_punycode)| {
+                (!is_undecoded_punycode && is_abi_name(&id)).then_some(Abi::Named(id))
+            }),
+        )),
+    )
     .parse(input, context)
 }
 
@@ -367,21 +717,27 @@ fn parse_dyn_bounds<'a>(
     input: IndexedStr<'a>,
     context: &mut Context<'a>,
 ) -> Result<(DynBounds<'a>, IndexedStr<'a>), ()> {
-    and(opt_u64(parse_binder), terminated(parse_dyn_trait.many0(), tag('E')))
-        .map(|(bound_lifetimes, dyn_traits)| DynBounds {
-            bound_lifetimes,
-            dyn_traits,
-        })
-        .parse(input, context)
+    production(
+        Production::DynBounds,
+        and(opt_u64(parse_binder), terminated(parse_dyn_trait.many0(), tag('E'))).map(
+            |(bound_lifetimes, dyn_traits)| DynBounds {
+                bound_lifetimes,
+                dyn_traits,
+            },
+        ),
+    )
+    .parse(input, context)
 }
 
 fn parse_dyn_trait<'a>(input: IndexedStr<'a>, context: &mut Context<'a>) -> Result<(DynTrait<'a>, IndexedStr<'a>), ()> {
-    and(parse_path, parse_dyn_trait_assoc_binding.many0())
-        .map(|(path, dyn_trait_assoc_bindings)| DynTrait {
+    production(
+        Production::DynTrait,
+        and(parse_path, parse_dyn_trait_assoc_binding.many0()).map(|(path, dyn_trait_assoc_bindings)| DynTrait {
             path,
             dyn_trait_assoc_bindings,
-        })
-        .parse(input, context)
+        }),
+    )
+    .parse(input, context)
 }
 
 fn parse_dyn_trait_assoc_binding<'a>(
@@ -389,44 +745,60 @@ fn parse_dyn_trait_assoc_binding<'a>(
     context: &mut Context<'a>,
 ) -> Result<(DynTraitAssocBinding<'a>, IndexedStr<'a>), ()> {
     preceded(tag('p'), and(parse_undisambiguated_identifier, parse_type))
-        .map(|(name, type_)| DynTraitAssocBinding { name, type_ })
+        .map(|((name, is_undecoded_punycode), type_)| DynTraitAssocBinding {
+            name,
+            type_,
+            is_undecoded_punycode,
+        })
         .parse(input, context)
 }
 
-fn parse_const<'a>(input: IndexedStr<'a>, context: &mut Context<'a>) -> Result<(Rc<Const<'a>>, IndexedStr<'a>), ()> {
+fn parse_const<'a>(
+    input: IndexedStr<'a>,
+    context: &mut Context<'a>,
+) -> Result<(NodeRc<Const<'a>>, IndexedStr<'a>), ()> {
     let index = input.index;
-
-    back_referenced(
-        index,
-        alt((
-            preceded(tag('a'), parse_const_int).map(Const::I8),
-            preceded(tag('h'), parse_const_int).map(Const::U8),
-            preceded(tag('i'), parse_const_int).map(Const::Isize),
-            preceded(tag('j'), parse_const_int).map(Const::Usize),
-            preceded(tag('l'), parse_const_int).map(Const::I32),
-            preceded(tag('m'), parse_const_int).map(Const::U32),
-            preceded(tag('n'), parse_const_int).map(Const::I128),
-            preceded(tag('o'), parse_const_int).map(Const::U128),
-            preceded(tag('s'), parse_const_int).map(Const::I16),
-            preceded(tag('t'), parse_const_int).map(Const::U16),
-            preceded(tag('x'), parse_const_int).map(Const::I64),
-            preceded(tag('y'), parse_const_int).map(Const::U64),
-            preceded(tag('b'), parse_const_int::<u8>).map_opt(|result| match result {
-                0 => Some(Const::Bool(false)),
-                1 => Some(Const::Bool(true)),
-                _ => None,
-            }),
-            preceded(tag('c'), parse_const_int::<u32>).map_opt(|result| result.try_into().ok().map(Const::Char)),
-            preceded(tag('e'), parse_const_str).map(Const::Str),
-            preceded(tag('R'), parse_const).map(Const::Ref),
-            preceded(tag('Q'), parse_const).map(Const::RefMut),
-            delimited(tag('A'), parse_const.many0(), tag('E')).map(Const::Array),
-            delimited(tag('T'), parse_const.many0(), tag('E')).map(Const::Tuple),
-            preceded(tag('V'), and(parse_path, parse_const_fields))
-                .map(|(path, fields)| Const::NamedStruct { path, fields }),
-            tag('p').map(|_| Const::Placeholder),
-        )),
-        |context| &mut context.consts,
+    let float_consts = context.options.float_consts;
+
+    production(
+        Production::Const,
+        back_referenced(
+            index,
+            alt((
+                preceded(tag('a'), parse_const_int).map(Const::I8),
+                preceded(tag('h'), parse_const_int).map(Const::U8),
+                preceded(tag('i'), parse_const_int).map(Const::Isize),
+                preceded(tag('j'), parse_const_int).map(Const::Usize),
+                preceded(tag('l'), parse_const_int).map(Const::I32),
+                preceded(tag('m'), parse_const_int).map(Const::U32),
+                preceded(tag('n'), parse_const_int).map(Const::I128),
+                preceded(tag('o'), parse_const_int).map(Const::U128),
+                preceded(tag('s'), parse_const_int).map(Const::I16),
+                preceded(tag('t'), parse_const_int).map(Const::U16),
+                preceded(tag('x'), parse_const_int).map(Const::I64),
+                preceded(tag('y'), parse_const_int).map(Const::U64),
+                preceded(tag('b'), parse_const_int::<u8>).map_opt(|result| match result {
+                    0 => Some(Const::Bool(false)),
+                    1 => Some(Const::Bool(true)),
+                    _ => None,
+                }),
+                preceded(tag('c'), parse_const_int::<u32>).map_opt(|result| result.try_into().ok().map(Const::Char)),
+                preceded(tag('e'), parse_const_str).map(Const::Str),
+                preceded(tag('R'), parse_const).map(Const::Ref),
+                preceded(tag('Q'), parse_const).map(Const::RefMut),
+                delimited(tag('A'), parse_const.many0(), tag('E')).map(Const::Array),
+                delimited(tag('T'), parse_const.many0(), tag('E')).map(Const::Tuple),
+                preceded(tag('V'), and(parse_path, parse_const_fields))
+                    .map(|(path, fields)| Const::NamedStruct { path, fields }),
+                tag('p').map(|_| Const::Placeholder),
+                preceded(tag('f'), parse_const_int).map_opt(move |bits| float_consts.then_some(Const::F32(bits))),
+                preceded(tag('d'), parse_const_int).map_opt(move |bits| float_consts.then_some(Const::F64(bits))),
+            ))
+            .map(NodeRc::new),
+            |context| &mut context.consts,
+            SharedNode::Const,
+            Production::Const,
+        ),
     )
     .parse(input, context)
 }
@@ -435,11 +807,14 @@ fn parse_const_fields<'a>(
     input: IndexedStr<'a>,
     context: &mut Context<'a>,
 ) -> Result<(ConstFields<'a>, IndexedStr<'a>), ()> {
-    alt((
-        tag('U').map(|_| ConstFields::Unit),
-        delimited(tag('T'), parse_const.many0(), tag('E')).map(ConstFields::Tuple),
-        delimited(tag('S'), and(parse_identifier, parse_const).many0(), tag('E')).map(ConstFields::Struct),
-    ))
+    production(
+        Production::ConstFields,
+        alt((
+            tag('U').map(|_| ConstFields::Unit),
+            delimited(tag('T'), parse_const.many0(), tag('E')).map(ConstFields::Tuple),
+            delimited(tag('S'), and(parse_identifier, parse_const).many0(), tag('E')).map(ConstFields::Struct),
+        )),
+    )
     .parse(input, context)
 }
 
@@ -448,25 +823,42 @@ where
     T: CheckedNeg + PrimInt,
 {
     terminated(
-        and(tag('n').opt(), lower_hex_digit0).map_opt(|(is_negative, data): (_, &str)| {
-            if data.is_empty() {
-                Some(T::zero())
-            } else {
-                let base = T::from_str_radix(data, 16).ok();
-
-                if is_negative.is_none() {
-                    base
-                } else {
-                    base.and_then(|value| value.checked_neg())
-                }
-            }
-        }),
+        and(tag('n').opt(), lower_hex_digit0)
+            .map_opt(|(is_negative, data): (_, &str)| decode_const_int(is_negative.is_some(), data)),
         tag('_'),
     )
     .parse(input, context)
 }
 
-fn parse_const_str<'a>(input: IndexedStr<'a>, context: &mut Context<'a>) -> Result<(String, IndexedStr<'a>), ()> {
+/// Decodes the lower case hexadecimal digits of an integer constant, which are empty for zero. Only constants of signed
+/// types may be negative, even if they are zero.
+pub fn decode_const_int<T>(is_negative: bool, data: &str) -> Option<T>
+where
+    T: CheckedNeg + PrimInt,
+{
+    if is_negative && T::min_value() == T::zero() {
+        None
+    } else if data.is_empty() {
+        Some(T::zero())
+    } else {
+        let base = T::from_str_radix(data, 16).ok();
+
+        if is_negative {
+            base.and_then(|value| value.checked_neg())
+        } else {
+            base
+        }
+    }
+}
+
+fn parse_const_str<'a>(input: IndexedStr<'a>, context: &mut Context<'a>) -> Result<(ConstStr, IndexedStr<'a>), ()> {
+    terminated(lower_hex_digit0, tag('_'))
+        .map_opt(decode_const_str)
+        .parse(input, context)
+}
+
+/// Decodes the hexadecimal encoded bytes of a string constant.
+pub fn decode_const_str(s: &str) -> Option<ConstStr> {
     fn decode_hex_digit(digit: u8) -> Option<u8> {
         match digit {
             b'0'..=b'9' => Some(digit - b'0'),
@@ -475,30 +867,27 @@ fn parse_const_str<'a>(input: IndexedStr<'a>, context: &mut Context<'a>) -> Resu
         }
     }
 
-    terminated(lower_hex_digit0, tag('_'))
-        .map_opt(|s: &str| {
-            if s.len() % 2 == 0 {
-                if let Some(s2) = s.as_bytes().get(1..) {
-                    let mut bytes = Vec::with_capacity(s.len() / 2);
-
-                    for (high, low) in s.bytes().zip(s2.iter().copied()).step_by(2) {
-                        bytes.push((decode_hex_digit(high)? << 4) | decode_hex_digit(low)?);
-                    }
+    if s.len().is_multiple_of(2) {
+        if let Some(s2) = s.as_bytes().get(1..) {
+            let mut bytes = Vec::with_capacity(s.len() / 2);
 
-                    String::from_utf8(bytes).ok()
-                } else {
-                    Some(String::new())
-                }
-            } else {
-                None
+            for (high, low) in s.bytes().zip(s2.iter().copied()).step_by(2) {
+                bytes.push((decode_hex_digit(high)? << 4) | decode_hex_digit(low)?);
             }
-        })
-        .parse(input, context)
+
+            Some(ConstStr { bytes })
+        } else {
+            Some(ConstStr::default())
+        }
+    } else {
+        None
+    }
 }
 
 fn parse_base62_number<'a>(input: IndexedStr<'a>, context: &mut Context<'a>) -> Result<(u64, IndexedStr<'a>), ()> {
-    terminated(alphanumeric0, tag('_'))
-        .map_opt(|num: &str| {
+    production(
+        Production::Base62Number,
+        terminated(alphanumeric0, tag('_')).map_opt(|num: &str| {
             if num.is_empty() {
                 Some(0)
             } else {
@@ -517,12 +906,13 @@ fn parse_base62_number<'a>(input: IndexedStr<'a>, context: &mut Context<'a>) ->
 
                 value.checked_add(1)
             }
-        })
-        .parse(input, context)
+        }),
+    )
+    .parse(input, context)
 }
 
-fn parse_back_ref<'a>(input: IndexedStr<'a>, context: &mut Context<'a>) -> Result<(usize, IndexedStr<'a>), ()> {
-    preceded(tag('B'), parse_base62_number)
+fn parse_back_ref_index<'a>(input: IndexedStr<'a>, context: &mut Context<'a>) -> Result<(usize, IndexedStr<'a>), ()> {
+    parse_base62_number
         .map_opt(|num| num.try_into().ok())
         .parse(input, context)
 }
@@ -534,17 +924,25 @@ fn parse_vendor_specific_suffix<'a>(
     if input.data.starts_with(['.', '$']) {
         let length = input.data.len();
 
-        Ok(input.split_at(length).unwrap())
+        input.split_at(length).ok_or(())
     } else {
         Err(())
     }
 }
 
+pub fn split_wasm_hash(suffix: &str) -> Option<(&str, &str)> {
+    let (rest, hash) = suffix.rsplit_once('$')?;
+
+    (!hash.is_empty() && hash.bytes().all(|c| c.is_ascii_alphanumeric())).then_some((rest, hash))
+}
+
 fn parse_decimal_number<'a, T>(input: IndexedStr<'a>, context: &mut Context<'a>) -> Result<(T, IndexedStr<'a>), ()>
 where
     T: PrimInt,
 {
-    or(tag('0'), digit1)
-        .map_opt(|num: &str| T::from_str_radix(num, 10).ok())
-        .parse(input, context)
+    production(
+        Production::DecimalNumber,
+        or(tag('0'), digit1).map_opt(|num: &str| T::from_str_radix(num, 10).ok()),
+    )
+    .parse(input, context)
 }
@@ -1,17 +1,17 @@
 use crate::mini_parser::Parser;
 use crate::rust_v0::display::{self, Style};
-use crate::rust_v0::parsers::{Context, IndexedStr};
+use crate::rust_v0::parsers::{BackRefTable, Context, IndexedStr};
 use crate::rust_v0::{
-    Abi, BasicType, Const, DynBounds, DynTrait, DynTraitAssocBinding, GenericArg, Identifier, ImplPath, Path, Symbol,
-    Type,
+    Abi, BasicType, Const, DynBounds, DynTrait, DynTraitAssocBinding, GenericArg, Identifier, ImplPath, Namespace,
+    NodeRc, ParseError, ParseOptions, Path, Symbol, Type,
 };
 use std::borrow::Cow;
-use std::rc::Rc;
 
-fn id(disambiguator: u64, name: &str) -> Identifier {
+fn id(disambiguator: u64, name: &str) -> Identifier<'_> {
     Identifier {
         disambiguator,
         name: Cow::Borrowed(name),
+        is_undecoded_punycode: false,
     }
 }
 
@@ -31,12 +31,12 @@ fn test_parse_undisambiguated_identifier() {
     let mut parse = simplify_parser(super::parse_undisambiguated_identifier);
 
     assert_eq!(parse(""), Err(()));
-    assert_eq!(parse("6_123foo"), Ok((Cow::Borrowed("123foo"), "")));
-    assert_eq!(parse("3bar"), Ok((Cow::Borrowed("bar"), "")));
+    assert_eq!(parse("6_123foo"), Ok(((Cow::Borrowed("123foo"), false), "")));
+    assert_eq!(parse("3bar"), Ok(((Cow::Borrowed("bar"), false), "")));
 
     assert_eq!(
         parse("u30____7hkackfecea1cbdathfdh9hlq6y"),
-        Ok((Cow::Borrowed("საჭმელად_გემრიელი_სადილი"), ""))
+        Ok(((Cow::Borrowed("საჭმელად_გემრიელი_სადილი"), false), ""))
     );
 }
 
@@ -55,6 +55,7 @@ fn check_parse_const(input: &str, expected: &str) {
         display::write_const(
             &simplify_parser(super::parse_const)(input).unwrap().0,
             f,
+            &mut display::DefaultRenderer,
             Style::Normal,
             0,
             false,
@@ -71,6 +72,7 @@ fn test_parse_const_str() {
     check_parse_const("e27_", r#"{*"'"}"#);
     check_parse_const("e090a_", "{*\"\\t\\n\"}");
     check_parse_const("ee28882c3bc_", "{*\"∂ü\"}");
+    check_parse_const("e22275c0d_", r#"{*"\"'\\\r"}"#);
 
     check_parse_const(
         "ee183a1e18390e183ade1839be18394e1839ae18390e183935fe18392e18394e1839b\
@@ -83,6 +85,31 @@ fn test_parse_const_str() {
          95f09f94a520c2a720f09fa7a1f09f929bf09f929af09f9299f09f929c_",
         "{*\"🐊🦈🦆🐮 § 🐶👒☕🔥 § 🧡💛💚💙💜\"}",
     );
+
+    // Bytes that are not valid UTF-8 are displayed as a byte string literal.
+    check_parse_const("e80ff_", r#"{*b"\x80\xff"}"#);
+    check_parse_const("e61225c_", r#"{*"a\"\\"}"#);
+    check_parse_const("e61c3225c_", r#"{*b"a\xc3\"\\"}"#);
+
+    let mut parse = simplify_parser(super::parse_const);
+
+    // Odd numbers of hex digits are rejected, the same as rustc-demangle does.
+    assert!(parse("e616_").is_err());
+    assert!(parse("e6_").is_err());
+}
+
+#[test]
+fn test_parse_const_negative() {
+    check_parse_const("an7f_", "-127");
+    check_parse_const("xn1_", "-1");
+
+    let mut parse = simplify_parser(super::parse_const);
+
+    // Only constants of signed types may be negative, the same as rustc-demangle requires.
+    assert!(parse("hn_").is_err());
+    assert!(parse("hn0_").is_err());
+    assert!(parse("cn_").is_err());
+    assert!(parse("bn_").is_err());
 }
 
 #[test]
@@ -91,6 +118,7 @@ fn test_parse_const_ref_str() {
     check_parse_const("Re27_", r#""'""#);
     check_parse_const("Re090a_", "\"\\t\\n\"");
     check_parse_const("Ree28882c3bc_", "\"∂ü\"");
+    check_parse_const("Re80ff_", r#"b"\x80\xff""#);
 
     check_parse_const(
         "Ree183a1e18390e183ade1839be18394e1839ae18390e183935fe18392e18394e1839b\
@@ -186,8 +214,8 @@ fn test_parse_decimal_number() {
     assert_eq!(parse("999999999999999999999999"), Err(()));
 }
 
-fn parse_symbol(input: &str) -> Result<(Symbol, &str), ()> {
-    super::parse_symbol(input)
+fn parse_symbol(input: &str) -> Result<(Symbol<'_>, &str), ParseError> {
+    super::parse_symbol(input, ParseOptions::default())
 }
 
 #[test]
@@ -198,12 +226,9 @@ fn test_rustc_demangle_crate_with_leading_digit() {
             Symbol {
                 version: None,
                 path: Path::Nested {
-                    namespace: b'v',
+                    namespace: Namespace::Value,
                     path: Path::CrateRoot(id(0, "123foo")).into(),
-                    identifier: Identifier {
-                        disambiguator: 0,
-                        name: Cow::Borrowed("bar"),
-                    }
+                    identifier: id(0, "bar")
                 }
                 .into(),
                 instantiating_crate: None,
@@ -222,7 +247,7 @@ fn test_rustc_demangle_utf8_idents() {
             Symbol {
                 version: None,
                 path: Path::Nested {
-                    namespace: b'q',
+                    namespace: Namespace::Other('q'),
                     path: Path::CrateRoot(id(0x_317d_4810_89b8_c8fe, "utf8_idents")).into(),
                     identifier: id(0, "საჭმელად_გემრიელი_სადილი")
                 }
@@ -243,11 +268,11 @@ fn test_rustc_demangle_closure_1() {
             Symbol {
                 version: None,
                 path: Path::Nested {
-                    namespace: b'C',
+                    namespace: Namespace::Closure,
                     path: Path::Nested {
-                        namespace: b'C',
+                        namespace: Namespace::Closure,
                         path: Path::Nested {
-                            namespace: b'g',
+                            namespace: Namespace::Other('g'),
                             path: Path::CrateRoot(id(0x_4d64_68d6_c9fd_4bb3, "cc")).into(),
                             identifier: id(0, "spawn")
                         }
@@ -268,14 +293,11 @@ fn test_rustc_demangle_closure_1() {
 
 #[test]
 fn test_rustc_demangle_closure_2() {
-    let crate_root = Rc::new(Path::CrateRoot(Identifier {
-        disambiguator: 0x_8468_17f7_41e5_4dfd,
-        name: Cow::Borrowed("core"),
-    }));
-
-    let core_slice = Rc::new(Path::Nested {
-        namespace: b'g',
-        path: Rc::clone(&crate_root),
+    let crate_root = NodeRc::new(Path::CrateRoot(id(0x_8468_17f7_41e5_4dfd, "core")));
+
+    let core_slice = NodeRc::new(Path::Nested {
+        namespace: Namespace::Other('g'),
+        path: NodeRc::clone(&crate_root),
         identifier: id(0, "slice"),
     });
 
@@ -287,20 +309,20 @@ fn test_rustc_demangle_closure_2() {
             Symbol {
                 version: None,
                 path: Path::Nested {
-                    namespace: b'C',
+                    namespace: Namespace::Closure,
                     path: Path::Generic {
                         path: Path::Nested {
-                            namespace: b'k',
+                            namespace: Namespace::Other('k'),
                             path: Path::TraitImpl {
                                 impl_path: ImplPath {
                                     disambiguator: 131,
-                                    path: Rc::clone(&core_slice)
+                                    path: NodeRc::clone(&core_slice)
                                 },
                                 type_: Type::Named(
                                     Path::Generic {
                                         path: Path::Nested {
-                                            namespace: b'y',
-                                            path: Rc::clone(&core_slice),
+                                            namespace: Namespace::Other('y'),
+                                            path: NodeRc::clone(&core_slice),
                                             identifier: id(0, "Iter")
                                         }
                                         .into(),
@@ -310,12 +332,12 @@ fn test_rustc_demangle_closure_2() {
                                 )
                                 .into(),
                                 trait_: Path::Nested {
-                                    namespace: b'u',
+                                    namespace: Namespace::Other('u'),
                                     path: Path::Nested {
-                                        namespace: b'g',
+                                        namespace: Namespace::Other('g'),
                                         path: Path::Nested {
-                                            namespace: b'o',
-                                            path: Rc::clone(&crate_root),
+                                            namespace: Namespace::Other('o'),
+                                            path: NodeRc::clone(&crate_root),
                                             identifier: id(0, "iter")
                                         }
                                         .into(),
@@ -333,12 +355,12 @@ fn test_rustc_demangle_closure_2() {
                         generic_args: vec![GenericArg::Type(
                             Type::Named(
                                 Path::Nested {
-                                    namespace: b'C',
+                                    namespace: Namespace::Closure,
                                     path: Path::Nested {
-                                        namespace: b'g',
+                                        namespace: Namespace::Other('g'),
                                         path: Path::Nested {
-                                            namespace: b'p',
-                                            path: Rc::clone(&core_slice),
+                                            namespace: Namespace::Other('p'),
+                                            path: NodeRc::clone(&core_slice),
                                             identifier: id(0, "memchr")
                                         }
                                         .into(),
@@ -373,9 +395,9 @@ fn test_rustc_demangle_dyn_trait() {
                 version: None,
                 path: Path::Generic {
                     path: Path::Nested {
-                        namespace: b'b',
+                        namespace: Namespace::Other('b'),
                         path: Path::Nested {
-                            namespace: b'b',
+                            namespace: Namespace::Other('b'),
                             path: Path::CrateRoot(id(0x_f15a_878b_47eb_696b, "alloc")).into(),
                             identifier: id(0, "alloc")
                         }
@@ -390,9 +412,9 @@ fn test_rustc_demangle_dyn_trait() {
                                 dyn_traits: vec![DynTrait {
                                     path: Path::Generic {
                                         path: Path::Nested {
-                                            namespace: b'b',
+                                            namespace: Namespace::Other('b'),
                                             path: Path::Nested {
-                                                namespace: b'i',
+                                                namespace: Namespace::Other('i'),
                                                 path: Path::CrateRoot(id(0x_f15a_878b_47eb_696b, "alloc")).into(),
                                                 identifier: id(0, "boxed")
                                             }
@@ -405,7 +427,8 @@ fn test_rustc_demangle_dyn_trait() {
                                     .into(),
                                     dyn_trait_assoc_bindings: vec![DynTraitAssocBinding {
                                         name: Cow::Borrowed("Output"),
-                                        type_: Type::Basic(BasicType::Unit).into()
+                                        type_: Type::Basic(BasicType::Unit).into(),
+                                        is_undecoded_punycode: false,
                                     }]
                                 }]
                             },
@@ -432,7 +455,7 @@ fn test_rustc_demangle_const_generics_usize_123() {
                 version: None,
                 path: Path::Generic {
                     path: Path::Nested {
-                        namespace: b't',
+                        namespace: Namespace::Type,
                         path: Path::CrateRoot(id(0, "arrayvec")).into(),
                         identifier: id(0, "ArrayVec")
                     }
@@ -466,7 +489,7 @@ fn test_rustc_demangle_const_generics_u8_11() {
                     type_: Type::Named(
                         Path::Generic {
                             path: Path::Nested {
-                                namespace: b't',
+                                namespace: Namespace::Type,
                                 path: Path::CrateRoot(id(0x_317d_4810_89b8_c8fe, "const_generic")).into(),
                                 identifier: id(0, "Unsigned")
                             }
@@ -501,7 +524,7 @@ fn test_rustc_demangle_const_generics_i16_152() {
                     type_: Type::Named(
                         Path::Generic {
                             path: Path::Nested {
-                                namespace: b't',
+                                namespace: Namespace::Type,
                                 path: Path::CrateRoot(id(0x_317d_4810_89b8_c8fe, "const_generic")).into(),
                                 identifier: id(0, "Signed")
                             }
@@ -536,7 +559,7 @@ fn test_rustc_demangle_const_generics_i8_negative_11() {
                     type_: Type::Named(
                         Path::Generic {
                             path: Path::Nested {
-                                namespace: b't',
+                                namespace: Namespace::Type,
                                 path: Path::CrateRoot(id(0x_317d_4810_89b8_c8fe, "const_generic")).into(),
                                 identifier: id(0, "Signed")
                             }
@@ -571,7 +594,7 @@ fn test_rustc_demangle_const_generics_bool_false() {
                     type_: Type::Named(
                         Path::Generic {
                             path: Path::Nested {
-                                namespace: b't',
+                                namespace: Namespace::Type,
                                 path: Path::CrateRoot(id(0x_317d_4810_89b8_c8fe, "const_generic")).into(),
                                 identifier: id(0, "Bool")
                             }
@@ -606,7 +629,7 @@ fn test_rustc_demangle_const_generics_bool_true() {
                     type_: Type::Named(
                         Path::Generic {
                             path: Path::Nested {
-                                namespace: b't',
+                                namespace: Namespace::Type,
                                 path: Path::CrateRoot(id(0x_317d_4810_89b8_c8fe, "const_generic")).into(),
                                 identifier: id(0, "Bool")
                             }
@@ -641,7 +664,7 @@ fn test_rustc_demangle_const_generics_char_v() {
                     type_: Type::Named(
                         Path::Generic {
                             path: Path::Nested {
-                                namespace: b't',
+                                namespace: Namespace::Type,
                                 path: Path::CrateRoot(id(0x_317d_4810_89b8_c8fe, "const_generic")).into(),
                                 identifier: id(0, "Char")
                             }
@@ -676,7 +699,7 @@ fn test_rustc_demangle_const_generics_char_lf() {
                     type_: Type::Named(
                         Path::Generic {
                             path: Path::Nested {
-                                namespace: b't',
+                                namespace: Namespace::Type,
                                 path: Path::CrateRoot(id(0x_317d_4810_89b8_c8fe, "const_generic")).into(),
                                 identifier: id(0, "Char")
                             }
@@ -711,7 +734,7 @@ fn test_rustc_demangle_const_generics_char_partial_differential() {
                     type_: Type::Named(
                         Path::Generic {
                             path: Path::Nested {
-                                namespace: b't',
+                                namespace: Namespace::Type,
                                 path: Path::CrateRoot(id(0x_317d_4810_89b8_c8fe, "const_generic")).into(),
                                 identifier: id(0, "Char")
                             }
@@ -739,9 +762,9 @@ fn test_rustc_demangle_const_generics_placeholder() {
             Symbol {
                 version: None,
                 path: Path::Nested {
-                    namespace: b'v',
+                    namespace: Namespace::Value,
                     path: Path::Nested {
-                        namespace: b'v',
+                        namespace: Namespace::Value,
                         path: Path::InherentImpl {
                             impl_path: ImplPath {
                                 disambiguator: 0,
@@ -750,7 +773,7 @@ fn test_rustc_demangle_const_generics_placeholder() {
                             type_: Type::Named(
                                 Path::Generic {
                                     path: Path::Nested {
-                                        namespace: b't',
+                                        namespace: Namespace::Type,
                                         path: Path::CrateRoot(id(0x_317d_4810_89b8_c8fe, "const_generic")).into(),
                                         identifier: id(0, "Foo")
                                     }
@@ -778,13 +801,13 @@ fn test_rustc_demangle_const_generics_placeholder() {
 
 #[test]
 fn test_rustc_demangle_exponential_explosion() {
-    let placeholder = Rc::new(Type::Basic(BasicType::Placeholder));
-    let tuple = Rc::new(Type::Tuple(vec![Rc::clone(&placeholder), placeholder]));
-    let tuple = Rc::new(Type::Tuple(vec![Rc::clone(&tuple), tuple]));
-    let tuple = Rc::new(Type::Tuple(vec![Rc::clone(&tuple), tuple]));
-    let tuple = Rc::new(Type::Tuple(vec![Rc::clone(&tuple), tuple]));
-    let tuple = Rc::new(Type::Tuple(vec![Rc::clone(&tuple), tuple]));
-    let tuple = Rc::new(Type::Tuple(vec![Rc::clone(&tuple), tuple]));
+    let placeholder = NodeRc::new(Type::Basic(BasicType::Placeholder));
+    let tuple = NodeRc::new(Type::Tuple(vec![NodeRc::clone(&placeholder), placeholder]));
+    let tuple = NodeRc::new(Type::Tuple(vec![NodeRc::clone(&tuple), tuple]));
+    let tuple = NodeRc::new(Type::Tuple(vec![NodeRc::clone(&tuple), tuple]));
+    let tuple = NodeRc::new(Type::Tuple(vec![NodeRc::clone(&tuple), tuple]));
+    let tuple = NodeRc::new(Type::Tuple(vec![NodeRc::clone(&tuple), tuple]));
+    let tuple = NodeRc::new(Type::Tuple(vec![NodeRc::clone(&tuple), tuple]));
 
     assert_eq!(
         parse_symbol("MC0TTTTTTpB8_EB7_EB6_EB5_EB4_EB3_E"),
@@ -816,7 +839,7 @@ fn test_rustc_demangle_thinlto() {
                 version: None,
                 path: Path::CrateRoot(id(0, "foo")).into(),
                 instantiating_crate: None,
-                vendor_specific_suffix: Some(".llvm.9D1C9369"),
+                vendor_specific_suffix: Some(Cow::Borrowed(".llvm.9D1C9369")),
             },
             ""
         ))
@@ -829,7 +852,7 @@ fn test_rustc_demangle_thinlto() {
                 version: None,
                 path: Path::CrateRoot(id(0, "foo")).into(),
                 instantiating_crate: None,
-                vendor_specific_suffix: Some(".llvm.9D1C9369@@16"),
+                vendor_specific_suffix: Some(Cow::Borrowed(".llvm.9D1C9369@@16")),
             },
             ""
         ))
@@ -841,13 +864,13 @@ fn test_rustc_demangle_thinlto() {
             Symbol {
                 version: None,
                 path: Path::Nested {
-                    namespace: b'v',
+                    namespace: Namespace::Value,
                     path: Path::CrateRoot(id(0, "backtrace")).into(),
                     identifier: id(0, "foo")
                 }
                 .into(),
                 instantiating_crate: None,
-                vendor_specific_suffix: Some(".llvm.A5310EB9"),
+                vendor_specific_suffix: Some(Cow::Borrowed(".llvm.A5310EB9")),
             },
             "",
         ))
@@ -862,15 +885,15 @@ fn test_rustc_demangle_extra_suffix() {
             Symbol {
                 version: None,
                 path: Path::Nested {
-                    namespace: 118,
+                    namespace: Namespace::Value,
                     path: Path::Nested {
-                        namespace: 116,
+                        namespace: Namespace::Type,
                         path: Path::Nested {
-                            namespace: 116,
+                            namespace: Namespace::Type,
                             path: Path::Nested {
-                                namespace: 116,
+                                namespace: Namespace::Type,
                                 path: Path::Nested {
-                                    namespace: 116,
+                                    namespace: Namespace::Type,
                                     path: Path::CrateRoot(id(0x_693e_a8e7_2247_470f, "rand")).into(),
                                     identifier: id(0, "rngs")
                                 }
@@ -888,9 +911,81 @@ fn test_rustc_demangle_extra_suffix() {
                 }
                 .into(),
                 instantiating_crate: None,
-                vendor_specific_suffix: Some(".0.0"),
+                vendor_specific_suffix: Some(Cow::Borrowed(".0.0")),
             },
             "",
         ))
     );
 }
+
+#[test]
+fn test_split_wasm_hash() {
+    assert_eq!(super::split_wasm_hash("$a1B2"), Some(("", "a1B2")));
+    assert_eq!(
+        super::split_wasm_hash(".llvm.9D1C9369$17"),
+        Some((".llvm.9D1C9369", "17"))
+    );
+    assert_eq!(super::split_wasm_hash(".llvm.9D1C9369"), None);
+    assert_eq!(super::split_wasm_hash(".0$"), None);
+    assert_eq!(super::split_wasm_hash("$a.b"), None);
+}
+
+#[test]
+fn test_wasm_hash_suffix() {
+    let (symbol, suffix) = parse_symbol("NvC9backtrace3foo$e0f1").unwrap();
+
+    assert_eq!(symbol.vendor_specific_suffix.as_deref(), Some("$e0f1"));
+    assert_eq!(symbol.wasm_hash(), Some("e0f1"));
+    assert_eq!(symbol.vendor_specific_suffix_without_wasm_hash(), None);
+    assert_eq!(suffix, "");
+
+    let (symbol, _) = parse_symbol("NvC9backtrace3foo.llvm.A5310EB9$2").unwrap();
+
+    assert_eq!(symbol.wasm_hash(), Some("2"));
+    assert_eq!(
+        symbol.vendor_specific_suffix_without_wasm_hash(),
+        Some(".llvm.A5310EB9")
+    );
+
+    let (symbol, _) = parse_symbol("NvC9backtrace3foo.llvm.A5310EB9").unwrap();
+
+    assert_eq!(symbol.wasm_hash(), None);
+    assert_eq!(
+        symbol.vendor_specific_suffix_without_wasm_hash(),
+        Some(".llvm.A5310EB9")
+    );
+}
+
+#[test]
+fn test_parse_type_shares_basic_types() {
+    let (type_, _) = simplify_parser(super::parse_type)("ThhlE").unwrap();
+
+    if let Type::Tuple(types) = type_.as_ref() {
+        assert!(NodeRc::ptr_eq(&types[0], &types[1]));
+        assert!(!NodeRc::ptr_eq(&types[0], &types[2]));
+        assert_eq!(*types[2], Type::Basic(BasicType::I32));
+    } else {
+        panic!("unexpected type: {type_:?}");
+    }
+}
+
+#[test]
+fn test_back_ref_table() {
+    let mut table = BackRefTable::default();
+
+    for position in [5, 2, 9, 0] {
+        table.insert(position, NodeRc::new(position), position % 3);
+    }
+
+    table.insert(2, NodeRc::new(20), 4);
+
+    assert_eq!(
+        (0..=10)
+            .filter_map(|position| table.get(position).map(|(node, height)| (position, **node, height)))
+            .collect::<Vec<_>>(),
+        [(0, 0, 0), (2, 20, 4), (5, 5, 2), (9, 9, 0)]
+    );
+
+    assert_eq!(table.get(5).map(|(node, height)| (**node, height)), Some((5, 2)));
+    assert_eq!(table.get(3), None);
+}
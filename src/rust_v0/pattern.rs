@@ -0,0 +1,712 @@
+//! Matching AST nodes against patterns with placeholders.
+//!
+//! A pattern is an ordinary AST whose [`BasicType::Placeholder`] types and [`Const::Placeholder`] constants match
+//! anything. Besides placeholders, patterns are more lenient than equality in a few ways:
+//!
+//! - Identifiers with a zero disambiguator match identifiers with any disambiguator.
+//! - Generic arguments of a pattern only need to match a prefix of the generic arguments, so `Vec<_>` matches
+//!   `Vec<u8, Global>`, and a path without generic arguments matches the path with any generic arguments.
+//! - Lifetimes, bound lifetimes and impl disambiguators are ignored.
+//!
+//! [`SymbolPattern`] matches paths against glob patterns written like demangled paths instead.
+
+use crate::rust_v0::{
+    display, BasicType, Const, ConstFields, DynBounds, FnSig, GenericArg, Identifier, NodeRc, Path, Symbol, Type,
+};
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+fn match_identifier(identifier: &Identifier, pattern: &Identifier) -> bool {
+    (pattern.disambiguator == 0 || identifier.disambiguator == pattern.disambiguator)
+        && identifier.name == pattern.name
+        && identifier.is_undecoded_punycode == pattern.is_undecoded_punycode
+}
+
+fn match_all<T, U>(values: &[T], patterns: &[U], mut f: impl FnMut(&T, &U) -> bool) -> bool {
+    values.len() == patterns.len() && values.iter().zip(patterns).all(|(value, pattern)| f(value, pattern))
+}
+
+pub fn match_path(path: &Path, pattern: &Path) -> bool {
+    match (path, pattern) {
+        (Path::CrateRoot(identifier), Path::CrateRoot(pattern)) => match_identifier(identifier, pattern),
+        (
+            Path::InherentImpl { impl_path, type_ },
+            Path::InherentImpl {
+                impl_path: impl_path_pattern,
+                type_: type_pattern,
+            },
+        ) => match_path(&impl_path.path, &impl_path_pattern.path) && match_type(type_, type_pattern),
+        (
+            Path::TraitImpl {
+                impl_path,
+                type_,
+                trait_,
+            },
+            Path::TraitImpl {
+                impl_path: impl_path_pattern,
+                type_: type_pattern,
+                trait_: trait_pattern,
+            },
+        ) => {
+            match_path(&impl_path.path, &impl_path_pattern.path)
+                && match_type(type_, type_pattern)
+                && match_path(trait_, trait_pattern)
+        }
+        (
+            Path::TraitDefinition { type_, trait_ },
+            Path::TraitDefinition {
+                type_: type_pattern,
+                trait_: trait_pattern,
+            },
+        ) => match_type(type_, type_pattern) && match_path(trait_, trait_pattern),
+        (
+            Path::Nested {
+                namespace,
+                path,
+                identifier,
+            },
+            Path::Nested {
+                namespace: namespace_pattern,
+                path: path_pattern,
+                identifier: identifier_pattern,
+            },
+        ) => {
+            namespace == namespace_pattern
+                && match_identifier(identifier, identifier_pattern)
+                && match_path(path, path_pattern)
+        }
+        (
+            Path::Generic { path, generic_args },
+            Path::Generic {
+                path: path_pattern,
+                generic_args: generic_args_pattern,
+            },
+        ) => {
+            generic_args_pattern.len() <= generic_args.len()
+                && generic_args
+                    .iter()
+                    .zip(generic_args_pattern)
+                    .all(|(generic_arg, pattern)| match_generic_arg(generic_arg, pattern))
+                && match_path(path, path_pattern)
+        }
+        (Path::Generic { path, .. }, _) => match_path(path, pattern),
+        _ => false,
+    }
+}
+
+fn match_generic_arg(generic_arg: &GenericArg, pattern: &GenericArg) -> bool {
+    match (generic_arg, pattern) {
+        (GenericArg::Lifetime(_), GenericArg::Lifetime(_)) => true,
+        (GenericArg::Type(type_), GenericArg::Type(pattern)) => match_type(type_, pattern),
+        (GenericArg::Const(const_), GenericArg::Const(pattern)) => match_const(const_, pattern),
+        _ => false,
+    }
+}
+
+pub fn match_type(type_: &Type, pattern: &Type) -> bool {
+    match (type_, pattern) {
+        (_, Type::Basic(BasicType::Placeholder)) => true,
+        (Type::Basic(basic_type), Type::Basic(pattern)) => basic_type == pattern,
+        (Type::Named(path), Type::Named(pattern)) => match_path(path, pattern),
+        (Type::Array(type_, length), Type::Array(type_pattern, length_pattern)) => {
+            match_type(type_, type_pattern) && match_const(length, length_pattern)
+        }
+        (Type::Slice(type_), Type::Slice(pattern))
+        | (Type::Ref { type_, .. }, Type::Ref { type_: pattern, .. })
+        | (Type::RefMut { type_, .. }, Type::RefMut { type_: pattern, .. })
+        | (Type::PtrConst(type_), Type::PtrConst(pattern))
+        | (Type::PtrMut(type_), Type::PtrMut(pattern)) => match_type(type_, pattern),
+        (Type::Tuple(tuple_types), Type::Tuple(patterns)) => {
+            match_all(tuple_types, patterns, |type_, pattern| match_type(type_, pattern))
+        }
+        (Type::Fn(fn_sig), Type::Fn(pattern)) => match_fn_sig(fn_sig, pattern),
+        (
+            Type::DynTrait { dyn_bounds, .. },
+            Type::DynTrait {
+                dyn_bounds: pattern, ..
+            },
+        ) => match_dyn_bounds(dyn_bounds, pattern),
+        _ => false,
+    }
+}
+
+fn match_fn_sig(fn_sig: &FnSig, pattern: &FnSig) -> bool {
+    fn_sig.is_unsafe == pattern.is_unsafe
+        && fn_sig.abi == pattern.abi
+        && match_all(&fn_sig.argument_types, &pattern.argument_types, |type_, pattern| {
+            match_type(type_, pattern)
+        })
+        && match_type(&fn_sig.return_type, &pattern.return_type)
+}
+
+fn match_dyn_bounds(dyn_bounds: &DynBounds, pattern: &DynBounds) -> bool {
+    match_all(&dyn_bounds.dyn_traits, &pattern.dyn_traits, |dyn_trait, pattern| {
+        match_path(&dyn_trait.path, &pattern.path)
+            && match_all(
+                &dyn_trait.dyn_trait_assoc_bindings,
+                &pattern.dyn_trait_assoc_bindings,
+                |binding, pattern| {
+                    binding.name == pattern.name
+                        && binding.is_undecoded_punycode == pattern.is_undecoded_punycode
+                        && match_type(&binding.type_, &pattern.type_)
+                },
+            )
+    })
+}
+
+pub fn match_const(const_: &Const, pattern: &Const) -> bool {
+    match (const_, pattern) {
+        (_, Const::Placeholder) => true,
+        (Const::Ref(value), Const::Ref(pattern)) | (Const::RefMut(value), Const::RefMut(pattern)) => {
+            match_const(value, pattern)
+        }
+        (Const::Array(items), Const::Array(patterns)) | (Const::Tuple(items), Const::Tuple(patterns)) => {
+            match_all(items, patterns, |item, pattern| match_const(item, pattern))
+        }
+        (
+            Const::NamedStruct { path, fields },
+            Const::NamedStruct {
+                path: path_pattern,
+                fields: fields_pattern,
+            },
+        ) => {
+            match_path(path, path_pattern)
+                && match (fields, fields_pattern) {
+                    (ConstFields::Unit, ConstFields::Unit) => true,
+                    (ConstFields::Tuple(fields), ConstFields::Tuple(patterns)) => {
+                        match_all(fields, patterns, |field, pattern| match_const(field, pattern))
+                    }
+                    (ConstFields::Struct(fields), ConstFields::Struct(patterns)) => {
+                        match_all(fields, patterns, |(name, field), (name_pattern, pattern)| {
+                            match_identifier(name, name_pattern) && match_const(field, pattern)
+                        })
+                    }
+                    _ => false,
+                }
+        }
+        _ => const_ == pattern,
+    }
+}
+
+/// Searches the types appearing in a symbol for one that matches a pattern.
+struct TypeSearcher<'p, 'a> {
+    pattern: &'p Type<'a>,
+    visited: HashSet<usize>,
+}
+
+impl TypeSearcher<'_, '_> {
+    /// Shared subtrees are only visited once, so back references can not cause exponential work.
+    fn first_visit<T>(&mut self, node: &NodeRc<T>) -> bool {
+        self.visited.insert(NodeRc::as_ptr(node).cast::<()>() as usize)
+    }
+
+    fn search_path(&mut self, path: &NodeRc<Path>) -> bool {
+        self.first_visit(path)
+            && match path.as_ref() {
+                Path::CrateRoot(_) => false,
+                Path::InherentImpl { impl_path, type_ } => self.search_path(&impl_path.path) || self.search_type(type_),
+                Path::TraitImpl {
+                    impl_path,
+                    type_,
+                    trait_,
+                } => self.search_path(&impl_path.path) || self.search_type(type_) || self.search_path(trait_),
+                Path::TraitDefinition { type_, trait_ } => self.search_type(type_) || self.search_path(trait_),
+                Path::Nested { path, .. } => self.search_path(path),
+                Path::Generic { path, generic_args } => {
+                    self.search_path(path)
+                        || generic_args.iter().any(|generic_arg| match generic_arg {
+                            GenericArg::Lifetime(_) => false,
+                            GenericArg::Type(type_) => self.search_type(type_),
+                            GenericArg::Const(const_) => self.search_const(const_),
+                        })
+                }
+            }
+    }
+
+    fn search_type(&mut self, type_: &NodeRc<Type>) -> bool {
+        self.first_visit(type_)
+            && (match_type(type_, self.pattern)
+                || match type_.as_ref() {
+                    Type::Basic(_) => false,
+                    Type::Named(path) => self.search_path(path),
+                    Type::Array(type_, length) => self.search_type(type_) || self.search_const(length),
+                    Type::Slice(type_)
+                    | Type::Ref { type_, .. }
+                    | Type::RefMut { type_, .. }
+                    | Type::PtrConst(type_)
+                    | Type::PtrMut(type_) => self.search_type(type_),
+                    Type::Tuple(tuple_types) => tuple_types.iter().any(|type_| self.search_type(type_)),
+                    Type::Fn(fn_sig) => {
+                        fn_sig.argument_types.iter().any(|type_| self.search_type(type_))
+                            || self.search_type(&fn_sig.return_type)
+                    }
+                    Type::DynTrait { dyn_bounds, .. } => dyn_bounds.dyn_traits.iter().any(|dyn_trait| {
+                        self.search_path(&dyn_trait.path)
+                            || dyn_trait
+                                .dyn_trait_assoc_bindings
+                                .iter()
+                                .any(|binding| self.search_type(&binding.type_))
+                    }),
+                })
+    }
+
+    fn search_const(&mut self, const_: &NodeRc<Const>) -> bool {
+        self.first_visit(const_)
+            && match const_.as_ref() {
+                Const::Ref(value) | Const::RefMut(value) => self.search_const(value),
+                Const::Array(items) | Const::Tuple(items) => items.iter().any(|item| self.search_const(item)),
+                Const::NamedStruct { path, fields } => {
+                    self.search_path(path)
+                        || match fields {
+                            ConstFields::Unit => false,
+                            ConstFields::Tuple(fields) => fields.iter().any(|field| self.search_const(field)),
+                            ConstFields::Struct(fields) => fields.iter().any(|(_, field)| self.search_const(field)),
+                        }
+                }
+                _ => false,
+            }
+    }
+}
+
+pub fn symbol_contains_type(symbol: &Symbol, pattern: &Type) -> bool {
+    TypeSearcher {
+        pattern,
+        visited: HashSet::new(),
+    }
+    .search_path(&symbol.path)
+}
+
+/// The reason [`SymbolPattern::new`] rejects a pattern.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct InvalidPatternError {
+    /// The byte offset in the pattern where it fails to parse.
+    pub offset: usize,
+}
+
+impl Display for InvalidPatternError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "invalid symbol pattern at offset {}", self.offset)
+    }
+}
+
+impl std::error::Error for InvalidPatternError {}
+
+/// A pattern that matches the segments of a path, like `tokio::runtime::*` or `*::drop_in_place<std::*>`, compiled
+/// once and matched against the AST without displaying it:
+///
+/// ```rust
+/// use ast_demangle::rust_v0::{Symbol, SymbolPattern};
+///
+/// let pattern = SymbolPattern::new("*::drop_in_place<alloc::*>").unwrap();
+/// let (symbol, _) = Symbol::parse_from_str("_RINvNtC4core3ptr13drop_in_placeINtNtC5alloc3vec3VechEEB4_").unwrap();
+///
+/// assert!(pattern.matches(&symbol));
+/// ```
+///
+/// Patterns are segments separated by `::`:
+///
+/// - A segment that is just `*` matches one or more segments.
+/// - Other segments match a single segment, with `*` matching any run of characters, so `drop_*` matches `drop_slow`.
+///   Closures and other special segments are matched in their displayed form, like `{closure#0}`.
+/// - A segment may be followed by generic arguments like `<u8, std::*>`, optionally written with a turbofish. Each one
+///   is a pattern that matches a type with that path, or a basic type with that name, and a lone `*` matches any
+///   generic argument. Patterns only need to match the leading generic arguments, and a segment without generic
+///   arguments matches the segment with any generic arguments.
+///
+/// Impl blocks contribute the segments of their self type, so `alloc::vec::Vec::push` matches
+/// `<alloc::vec::Vec<u8>>::push`, and `*::Display::fmt` does not match `<u8 as core::fmt::Display>::fmt`. Self types that
+/// are not paths are a single segment that only `*` matches. The pattern must match the path as a whole.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct SymbolPattern {
+    segments: Vec<SegmentPattern>,
+}
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+enum SegmentPattern {
+    /// `*`, which matches one or more segments.
+    Any,
+    Glob {
+        glob: String,
+        generic_args: Option<Vec<SymbolPattern>>,
+    },
+}
+
+/// A segment of a path as [`SymbolPattern`] sees it.
+struct Segment<'p, 'a> {
+    /// The name of the segment, or [`None`] for self types that are not paths.
+    name: Option<Cow<'p, str>>,
+    generic_args: &'p [GenericArg<'a>],
+}
+
+impl SymbolPattern {
+    /// Compiles `pattern`, see [`SymbolPattern`] for the syntax.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`InvalidPatternError`] if `pattern` is not a valid pattern.
+    pub fn new(pattern: &str) -> Result<Self, InvalidPatternError> {
+        let mut parser = PatternParser {
+            input: pattern,
+            position: 0,
+        };
+        let result = parser.path()?;
+
+        parser.skip_whitespace();
+
+        if parser.position == pattern.len() {
+            Ok(result)
+        } else {
+            Err(parser.error())
+        }
+    }
+
+    /// Returns `true` if the path of `symbol` matches the pattern.
+    #[must_use]
+    pub fn matches(&self, symbol: &Symbol) -> bool {
+        self.matches_path(&symbol.path)
+    }
+
+    /// Returns `true` if `path` matches the pattern.
+    #[must_use]
+    pub fn matches_path(&self, path: &Path) -> bool {
+        let mut segments = Vec::new();
+
+        collect_segments(path, &mut segments);
+
+        match_segments(&segments, &self.segments)
+    }
+
+    fn is_any(&self) -> bool {
+        matches!(*self.segments, [SegmentPattern::Any])
+    }
+
+    fn matches_generic_arg(&self, generic_arg: &GenericArg) -> bool {
+        if self.is_any() {
+            return true;
+        }
+
+        let GenericArg::Type(type_) = generic_arg else {
+            return false;
+        };
+
+        match type_.as_ref() {
+            Type::Named(path) => self.matches_path(path),
+            Type::Basic(basic_type) => match_segments(
+                &[Segment {
+                    name: Some(Cow::Borrowed(basic_type.as_str())),
+                    generic_args: &[],
+                }],
+                &self.segments,
+            ),
+            _ => false,
+        }
+    }
+}
+
+impl FromStr for SymbolPattern {
+    type Err = InvalidPatternError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(s)
+    }
+}
+
+struct PatternParser<'s> {
+    input: &'s str,
+    position: usize,
+}
+
+impl PatternParser<'_> {
+    fn rest(&self) -> &str {
+        &self.input[self.position..]
+    }
+
+    fn error(&self) -> InvalidPatternError {
+        InvalidPatternError { offset: self.position }
+    }
+
+    fn eat(&mut self, tag: &str) -> bool {
+        let result = self.rest().starts_with(tag);
+
+        if result {
+            self.position += tag.len();
+        }
+
+        result
+    }
+
+    fn skip_whitespace(&mut self) {
+        self.position = self.input.len() - self.rest().trim_start().len();
+    }
+
+    fn path(&mut self) -> Result<SymbolPattern, InvalidPatternError> {
+        let mut segments = vec![self.segment()?];
+
+        while !self.rest().starts_with("::<") && self.eat("::") {
+            segments.push(self.segment()?);
+        }
+
+        Ok(SymbolPattern { segments })
+    }
+
+    fn segment(&mut self) -> Result<SegmentPattern, InvalidPatternError> {
+        self.skip_whitespace();
+
+        let length = self
+            .rest()
+            .find(|c: char| matches!(c, ':' | '<' | '>' | ',') || c.is_whitespace())
+            .unwrap_or(self.rest().len());
+
+        if length == 0 {
+            return Err(self.error());
+        }
+
+        let glob = self.rest()[..length].to_string();
+
+        self.position += length;
+
+        let generic_args = if self.eat("::<") || self.eat("<") {
+            let mut generic_args = vec![self.path()?];
+
+            self.skip_whitespace();
+
+            while self.eat(",") {
+                generic_args.push(self.path()?);
+                self.skip_whitespace();
+            }
+
+            if !self.eat(">") {
+                return Err(self.error());
+            }
+
+            Some(generic_args)
+        } else if glob == "*" {
+            return Ok(SegmentPattern::Any);
+        } else {
+            None
+        };
+
+        Ok(SegmentPattern::Glob { glob, generic_args })
+    }
+}
+
+/// Returns the name of `identifier` as it is displayed, which is what glob patterns are written against.
+fn segment_name<'p>(identifier: &'p Identifier) -> Cow<'p, str> {
+    if identifier.is_undecoded_punycode {
+        Cow::Owned(identifier.display().to_string())
+    } else {
+        Cow::Borrowed(&identifier.name)
+    }
+}
+
+fn collect_segments<'p, 'a>(path: &'p Path<'a>, segments: &mut Vec<Segment<'p, 'a>>) {
+    match path {
+        Path::CrateRoot(identifier) => segments.push(Segment {
+            name: Some(segment_name(identifier)),
+            generic_args: &[],
+        }),
+        Path::InherentImpl { type_, .. } | Path::TraitImpl { type_, .. } | Path::TraitDefinition { type_, .. } => {
+            if let Type::Named(path) = type_.as_ref() {
+                collect_segments(path, segments);
+            } else {
+                segments.push(Segment {
+                    name: None,
+                    generic_args: &[],
+                });
+            }
+        }
+        Path::Nested {
+            namespace,
+            path,
+            identifier,
+        } => {
+            collect_segments(path, segments);
+
+            let name = if namespace.is_special() {
+                let mut name = String::new();
+
+                display::write_special_identifier(*namespace, identifier, &mut name).unwrap();
+
+                Cow::Owned(name)
+            } else if identifier.name.is_empty() {
+                return;
+            } else {
+                segment_name(identifier)
+            };
+
+            segments.push(Segment {
+                name: Some(name),
+                generic_args: &[],
+            });
+        }
+        Path::Generic { path, generic_args } => {
+            collect_segments(path, segments);
+
+            if let Some(segment) = segments.last_mut() {
+                segment.generic_args = generic_args;
+            }
+        }
+    }
+}
+
+fn match_segment(segment: &Segment, glob: &str, generic_args: Option<&[SymbolPattern]>) -> bool {
+    let name_matches = match &segment.name {
+        Some(name) => match_glob(name, glob),
+        None => glob.bytes().all(|c| c == b'*'),
+    };
+
+    name_matches
+        && generic_args.is_none_or(|generic_args| {
+            generic_args.len() <= segment.generic_args.len()
+                && segment
+                    .generic_args
+                    .iter()
+                    .zip(generic_args)
+                    .all(|(generic_arg, pattern)| pattern.matches_generic_arg(generic_arg))
+        })
+}
+
+fn match_segments(segments: &[Segment], patterns: &[SegmentPattern]) -> bool {
+    match patterns.split_first() {
+        None => segments.is_empty(),
+        Some((SegmentPattern::Any, patterns)) => (1..=segments.len()).any(|n| match_segments(&segments[n..], patterns)),
+        Some((SegmentPattern::Glob { glob, generic_args }, patterns)) => {
+            segments.split_first().is_some_and(|(segment, segments)| {
+                match_segment(segment, glob, generic_args.as_deref()) && match_segments(segments, patterns)
+            })
+        }
+    }
+}
+
+/// Matches `name` against `glob`, where `*` matches any run of bytes. When a literal part fails to match, only the
+/// last `*` needs to be retried with a longer run, so this takes linear time in practice.
+fn match_glob(name: &str, glob: &str) -> bool {
+    let (name, glob) = (name.as_bytes(), glob.as_bytes());
+    let (mut i, mut j) = (0, 0);
+    let mut backtrack = None;
+
+    while i < name.len() {
+        if glob.get(j) == Some(&b'*') {
+            j += 1;
+            backtrack = Some((i, j));
+        } else if glob.get(j) == Some(&name[i]) {
+            i += 1;
+            j += 1;
+        } else if let Some((star_i, star_j)) = backtrack {
+            i = star_i + 1;
+            j = star_j;
+            backtrack = Some((i, j));
+        } else {
+            return false;
+        }
+    }
+
+    glob[j..].iter().all(|&c| c == b'*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{InvalidPatternError, SymbolPattern};
+    use crate::rust_v0::{BasicType, GenericArg, NodeRc, Path, Symbol, Type};
+
+    fn first_generic_type(symbol: &str) -> NodeRc<Type<'_>> {
+        match Symbol::parse_from_str(symbol).unwrap().0.path.as_ref() {
+            Path::Generic { generic_args, .. } => match &generic_args[0] {
+                GenericArg::Type(type_) => NodeRc::clone(type_),
+                _ => panic!(),
+            },
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn test_match_type() {
+        let byte_vec = first_generic_type("_RINvC3foo3barINtNtC5alloc3vec3VechNtNtC5alloc5alloc6GlobalEE");
+        let vec_placeholder = first_generic_type("_RINvC3foo3barINtNtC5alloc3vec3VecpEE");
+        let signed_byte_vec = first_generic_type("_RINvC3foo3barINtNtC5alloc3vec3VecaEE");
+        let vec = first_generic_type("_RINvC3foo3barNtNtC5alloc3vec3VecE");
+        let other_vec = first_generic_type("_RINvC3foo3barNtNtCs123_5alloc3vec3VecE");
+        let option_placeholder = first_generic_type("_RINvC3foo3barINtNtC4core6option6OptionpEE");
+
+        assert!(byte_vec.matches_pattern(&vec_placeholder));
+        assert!(byte_vec.matches_pattern(&byte_vec));
+        assert!(byte_vec.matches_pattern(&Type::Basic(BasicType::Placeholder)));
+        assert!(byte_vec.matches_pattern(&vec));
+        assert!(!byte_vec.matches_pattern(&signed_byte_vec));
+        assert!(!byte_vec.matches_pattern(&other_vec));
+        assert!(!byte_vec.matches_pattern(&option_placeholder));
+        assert!(!vec_placeholder.matches_pattern(&byte_vec));
+    }
+
+    #[test]
+    fn test_contains_type() {
+        let (symbol, _) =
+            Symbol::parse_from_str("_RINvC3foo3barINtNtC4core6result6ResultTRShENtNtC3std2io5ErrorEE").unwrap();
+
+        let io_error_result = first_generic_type("_RINvC3foo3barINtNtC4core6result6ResultpNtNtC3std2io5ErrorEE");
+        let io_error = first_generic_type("_RINvC3foo3barNtNtC3std2io5ErrorE");
+        let fmt_error = first_generic_type("_RINvC3foo3barNtNtC4core3fmt5ErrorE");
+        let slice = first_generic_type("_RINvC3foo3barSpE");
+
+        assert!(symbol.contains_type_matching(&io_error_result));
+        assert!(symbol.contains_type_matching(&io_error));
+        assert!(symbol.contains_type_matching(&slice));
+        assert!(!symbol.contains_type_matching(&fmt_error));
+    }
+
+    #[test]
+    fn test_symbol_pattern() {
+        let names = [
+            "_RNvNtNtCs123_5tokio7runtime4task5spawn",
+            "_RINvNtC4core3ptr13drop_in_placeINtNtC5alloc3vec3VechEEB4_",
+            "_RINvNtC4core3ptr13drop_in_placehEB4_",
+            "_RNvMNtC5alloc3vecINtB2_3VechE4push",
+            "_RNvXCs123_3fooRShNtNtC4core3fmt7Display3fmt",
+            "_RNCNvCs123_3foo4main0B3_",
+        ];
+
+        let symbols = names
+            .iter()
+            .map(|name| Symbol::parse_from_str(name).unwrap().0)
+            .collect::<Vec<_>>();
+
+        let test_cases: [(&str, &[bool]); 13] = [
+            ("tokio::runtime::*", &[true, false, false, false, false, false]),
+            ("tokio::*::spawn", &[true, false, false, false, false, false]),
+            ("tokio::runtime::spawn", &[false; 6]),
+            ("*::drop_in_place", &[false, true, true, false, false, false]),
+            ("*::drop_in_place<alloc::*>", &[false, true, false, false, false, false]),
+            (
+                "core::ptr::drop_in_place::<u8>",
+                &[false, false, true, false, false, false],
+            ),
+            ("*::drop_in_place<*>", &[false, true, true, false, false, false]),
+            ("*::drop_*", &[false, true, true, false, false, false]),
+            ("alloc::vec::Vec::push", &[false, false, false, true, false, false]),
+            ("alloc::vec::Vec<i8>::push", &[false; 6]),
+            ("*::fmt", &[false, false, false, false, true, false]),
+            ("foo::main::{closure*}", &[false, false, false, false, false, true]),
+            ("*", &[true; 6]),
+        ];
+
+        for (pattern, expected) in test_cases {
+            let pattern = pattern.parse::<SymbolPattern>().unwrap();
+
+            assert_eq!(
+                symbols.iter().map(|symbol| pattern.matches(symbol)).collect::<Vec<_>>(),
+                expected,
+                "{pattern:?}"
+            );
+        }
+
+        for (pattern, offset) in [("", 0), ("foo::", 5), ("foo<bar", 7), ("foo<>", 4), ("foo bar", 4)] {
+            assert_eq!(
+                SymbolPattern::new(pattern),
+                Err(InvalidPatternError { offset }),
+                "{pattern}"
+            );
+        }
+    }
+}
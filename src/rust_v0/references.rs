@@ -0,0 +1,156 @@
+//! Collecting the crates and paths a symbol refers to.
+
+use crate::rust_v0::{self, Const, DynTrait, Identifier, NodeRc, Path, Symbol, Type, Visitor};
+use std::collections::HashSet;
+
+/// Collects crate roots and the paths of named types and traits, visiting every shared subtree once.
+#[derive(Default)]
+struct ReferenceCollector<'a> {
+    visited: HashSet<usize>,
+    crate_set: HashSet<Identifier<'a>>,
+    crates: Vec<Identifier<'a>>,
+    path_set: HashSet<NodeRc<Path<'a>>>,
+    paths: Vec<NodeRc<Path<'a>>>,
+}
+
+impl<'a> ReferenceCollector<'a> {
+    /// Shared subtrees are only visited once, so back references can not cause exponential work.
+    fn first_visit<T>(&mut self, node: &NodeRc<T>) -> bool {
+        self.visited.insert(NodeRc::as_ptr(node).cast::<()>() as usize)
+    }
+
+    fn add_crate(&mut self, identifier: &Identifier<'a>) {
+        if self.crate_set.insert(identifier.clone()) {
+            self.crates.push(identifier.clone());
+        }
+    }
+
+    fn add_path(&mut self, path: &NodeRc<Path<'a>>) {
+        if self.path_set.insert(NodeRc::clone(path)) {
+            self.paths.push(NodeRc::clone(path));
+        }
+    }
+}
+
+impl<'a> Visitor<'a> for ReferenceCollector<'a> {
+    fn visit_path(&mut self, path: &NodeRc<Path<'a>>) {
+        if self.first_visit(path) {
+            match path.as_ref() {
+                Path::CrateRoot(identifier) => self.add_crate(identifier),
+                Path::TraitImpl {
+                    impl_path,
+                    type_,
+                    trait_,
+                } => {
+                    self.visit_impl_path(impl_path);
+                    self.visit_type(type_);
+                    self.add_path(trait_);
+                    self.visit_path(trait_);
+
+                    return;
+                }
+                Path::TraitDefinition { type_, trait_ } => {
+                    self.visit_type(type_);
+                    self.add_path(trait_);
+                    self.visit_path(trait_);
+
+                    return;
+                }
+                _ => {}
+            }
+
+            rust_v0::walk_path(self, path);
+        }
+    }
+
+    fn visit_type(&mut self, type_: &NodeRc<Type<'a>>) {
+        if self.first_visit(type_) {
+            if let Type::Named(path) = type_.as_ref() {
+                self.add_path(path);
+            }
+
+            rust_v0::walk_type(self, type_);
+        }
+    }
+
+    fn visit_dyn_trait(&mut self, dyn_trait: &DynTrait<'a>) {
+        self.add_path(&dyn_trait.path);
+
+        rust_v0::walk_dyn_trait(self, dyn_trait);
+    }
+
+    fn visit_const(&mut self, const_: &NodeRc<Const<'a>>) {
+        if self.first_visit(const_) {
+            rust_v0::walk_const(self, const_);
+        }
+    }
+}
+
+fn collect<'a>(symbol: &Symbol<'a>) -> ReferenceCollector<'a> {
+    let mut collector = ReferenceCollector::default();
+
+    collector.visit_path(&symbol.path);
+
+    collector
+}
+
+pub fn referenced_crates<'a>(symbol: &Symbol<'a>) -> Vec<Identifier<'a>> {
+    collect(symbol).crates
+}
+
+pub fn referenced_paths<'a>(symbol: &Symbol<'a>) -> Vec<NodeRc<Path<'a>>> {
+    collect(symbol).paths
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rust_v0::{DisplayStyle, Symbol};
+
+    #[test]
+    fn test_referenced_crates_and_paths() {
+        let (symbol, _) = Symbol::parse_from_str(
+            "_RINvXs_NtCs1_3foo3barNtCs2_3baz3QuxNtNtCs3_4core3fmt7Display3fmtFRDNtNtCs3_4core3fmt5DebugEL_Eu\
+             INtCs4_5alloc3VecNtCs2_3baz3QuxEE",
+        )
+        .unwrap();
+
+        // The same symbol with back references.
+        let mangled = symbol.mangle();
+        let (shared, _) = Symbol::parse_from_str(&mangled).unwrap();
+
+        assert_eq!(shared, symbol);
+
+        assert_eq!(
+            symbol.display(DisplayStyle::Normal).to_string(),
+            "<baz::Qux as core::fmt::Display>::fmt::<fn(&dyn core::fmt::Debug), alloc::Vec<baz::Qux>>"
+        );
+
+        for symbol in [&symbol, &shared] {
+            assert_eq!(
+                symbol
+                    .referenced_crates()
+                    .map(|identifier| (identifier.name.into_owned(), identifier.disambiguator))
+                    .collect::<Vec<_>>(),
+                [
+                    ("foo".to_string(), 3),
+                    ("baz".to_string(), 4),
+                    ("core".to_string(), 5),
+                    ("alloc".to_string(), 6)
+                ]
+            );
+
+            assert_eq!(
+                symbol
+                    .referenced_paths()
+                    .map(|path| path.display(DisplayStyle::Normal).to_string())
+                    .collect::<Vec<_>>(),
+                [
+                    "baz::Qux",
+                    "core::fmt::Display",
+                    "core::fmt::Debug",
+                    "alloc::Vec<baz::Qux>"
+                ]
+            );
+        }
+    }
+}
@@ -0,0 +1,82 @@
+//! Renaming the crates a symbol refers to.
+
+use crate::rust_v0::{self, Folder, Identifier, InvalidIdentifierError, NodeRc, Path, Symbol};
+
+/// Replaces every crate root named `from` with `to`. The replacement is allocated once and shared by every place it
+/// appears in.
+struct CrateRenamer<'a, 'f> {
+    from: &'f str,
+    to: NodeRc<Path<'a>>,
+}
+
+impl<'a> Folder<'a> for CrateRenamer<'a, '_> {
+    fn fold_path(&mut self, path: &NodeRc<Path<'a>>) -> NodeRc<Path<'a>> {
+        match path.as_ref() {
+            Path::CrateRoot(identifier) if identifier.name == self.from => NodeRc::clone(&self.to),
+            _ => rust_v0::super_fold_path(self, path),
+        }
+    }
+}
+
+pub fn rename_crate(
+    symbol: &Symbol,
+    from: &str,
+    to: &str,
+    new_disambiguator: u64,
+) -> Result<String, InvalidIdentifierError> {
+    let to = Identifier::new(new_disambiguator, to.to_string())?;
+    let mut renamer = CrateRenamer {
+        from,
+        to: NodeRc::new(Path::CrateRoot(to)),
+    };
+
+    Ok(renamer.fold_symbol(symbol).mangle())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rust_v0::{DisplayStyle, InvalidIdentifierError, Symbol};
+
+    #[test]
+    fn test_rename_crate() {
+        let (symbol, _) = Symbol::parse_from_str(
+            "_RINvXs_NtCs1_3foo3barNtCs1_3foo3QuxNtNtCs3_4core3fmt7Display3fmtINtCs1_3foo3VecNtCs2_3baz3QuxEECs1_3foo\
+             .llvm.123",
+        )
+        .unwrap();
+
+        assert_eq!(
+            symbol.display(DisplayStyle::Normal).to_string(),
+            "<foo::Qux as core::fmt::Display>::fmt::<foo::Vec<baz::Qux>>"
+        );
+
+        let renamed = symbol.rename_crate("foo", "vendored_foo", 7).unwrap();
+        let (renamed_symbol, suffix) = Symbol::parse_from_str(&renamed).unwrap();
+
+        assert_eq!(suffix, "");
+        assert_eq!(
+            renamed_symbol.display(DisplayStyle::Normal).to_string(),
+            "<vendored_foo::Qux as core::fmt::Display>::fmt::<vendored_foo::Vec<baz::Qux>>"
+        );
+        assert_eq!(
+            renamed_symbol
+                .instantiating_crate
+                .unwrap()
+                .display(DisplayStyle::Long)
+                .to_string(),
+            "vendored_foo[7]"
+        );
+        assert_eq!(renamed_symbol.vendor_specific_suffix.as_deref(), Some(".llvm.123"));
+
+        // The renamed crate root is encoded once and then referred to with back references.
+        assert_eq!(renamed.matches("12vendored_foo").count(), 1);
+
+        // Renaming a crate the symbol does not refer to gives back the original name.
+        assert_eq!(symbol.rename_crate("alloc", "vendored_alloc", 7), Ok(symbol.mangle()));
+
+        assert_eq!(
+            symbol.rename_crate("foo", "vendored-foo", 7),
+            Err(InvalidIdentifierError::InvalidCharacter('-'))
+        );
+    }
+}
@@ -0,0 +1,134 @@
+//! Descriptions of the unmangled symbols that the Rust runtime and compiler emit into Rust binaries.
+
+/// The runtime component a [`RuntimeSymbol`] belongs to.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum RuntimeSymbolKind {
+    /// Global allocator entry points and the shims that forward to the selected allocator.
+    Allocator,
+    /// Panic machinery entry points.
+    Panic,
+    /// Exception handling and stack unwinding support.
+    Unwinding,
+    /// Stack overflow detection.
+    StackProbe,
+}
+
+/// A Rust runtime symbol that is not mangled, like `__rust_alloc` or `rust_eh_personality`.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct RuntimeSymbol {
+    /// The name of the symbol, without any platform specific prefix.
+    pub name: &'static str,
+    /// The runtime component the symbol belongs to.
+    pub kind: RuntimeSymbolKind,
+    /// A short human readable description of the symbol.
+    pub description: &'static str,
+}
+
+const RUNTIME_SYMBOLS: &[RuntimeSymbol] = &{
+    use RuntimeSymbolKind::{Allocator, Panic, StackProbe, Unwinding};
+
+    const fn symbol(name: &'static str, kind: RuntimeSymbolKind, description: &'static str) -> RuntimeSymbol {
+        RuntimeSymbol {
+            name,
+            kind,
+            description,
+        }
+    }
+
+    [
+        symbol("__rust_alloc", Allocator, "global allocator: allocate"),
+        symbol("__rust_alloc_zeroed", Allocator, "global allocator: allocate zeroed"),
+        symbol("__rust_dealloc", Allocator, "global allocator: deallocate"),
+        symbol("__rust_realloc", Allocator, "global allocator: reallocate"),
+        symbol("__rust_alloc_error_handler", Allocator, "allocation error handler"),
+        symbol(
+            "__rust_alloc_error_handler_should_panic",
+            Allocator,
+            "allocation error handler panic flag",
+        ),
+        symbol(
+            "__rust_no_alloc_shim_is_unstable",
+            Allocator,
+            "allocator shim stability marker",
+        ),
+        symbol("__rg_alloc", Allocator, "#[global_allocator] shim: allocate"),
+        symbol(
+            "__rg_alloc_zeroed",
+            Allocator,
+            "#[global_allocator] shim: allocate zeroed",
+        ),
+        symbol("__rg_dealloc", Allocator, "#[global_allocator] shim: deallocate"),
+        symbol("__rg_realloc", Allocator, "#[global_allocator] shim: reallocate"),
+        symbol("__rg_oom", Allocator, "#[alloc_error_handler] shim"),
+        symbol("__rdl_alloc", Allocator, "default allocator: allocate"),
+        symbol("__rdl_alloc_zeroed", Allocator, "default allocator: allocate zeroed"),
+        symbol("__rdl_dealloc", Allocator, "default allocator: deallocate"),
+        symbol("__rdl_realloc", Allocator, "default allocator: reallocate"),
+        symbol("__rdl_oom", Allocator, "default allocation error handler"),
+        symbol("rust_oom", Allocator, "allocation error handler"),
+        symbol("rust_begin_unwind", Panic, "panic handler"),
+        symbol("rust_panic", Panic, "panic entry point"),
+        symbol("__rust_start_panic", Panic, "panic runtime: start panic"),
+        symbol("__rust_panic_cleanup", Panic, "panic runtime: clean up caught panic"),
+        symbol(
+            "__rust_drop_panic",
+            Panic,
+            "panic runtime: panic payload dropped with a panic",
+        ),
+        symbol(
+            "__rust_foreign_exception",
+            Panic,
+            "panic runtime: foreign exception caught",
+        ),
+        symbol(
+            "rust_eh_personality",
+            Unwinding,
+            "exception handling personality routine",
+        ),
+        symbol("rust_eh_unwind_resume", Unwinding, "resume unwinding"),
+        symbol("rust_eh_register_frames", Unwinding, "register unwind frames"),
+        symbol("rust_eh_unregister_frames", Unwinding, "unregister unwind frames"),
+        symbol("__rust_probestack", StackProbe, "stack probe"),
+    ]
+};
+
+impl RuntimeSymbol {
+    /// Looks up the unmangled Rust runtime symbol named `name`. A single leading underscore added by platforms like
+    /// macOS is accepted.
+    #[must_use]
+    pub fn lookup(name: &str) -> Option<Self> {
+        let find = |name| RUNTIME_SYMBOLS.iter().find(|symbol| symbol.name == name).copied();
+
+        find(name).or_else(|| name.strip_prefix('_').and_then(find))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RuntimeSymbol, RuntimeSymbolKind};
+
+    #[test]
+    fn test_lookup() {
+        assert_eq!(
+            RuntimeSymbol::lookup("__rust_alloc"),
+            Some(RuntimeSymbol {
+                name: "__rust_alloc",
+                kind: RuntimeSymbolKind::Allocator,
+                description: "global allocator: allocate",
+            })
+        );
+
+        assert_eq!(
+            RuntimeSymbol::lookup("_rust_eh_personality").map(|symbol| symbol.kind),
+            Some(RuntimeSymbolKind::Unwinding)
+        );
+
+        assert_eq!(
+            RuntimeSymbol::lookup("___rust_probestack").map(|symbol| symbol.name),
+            Some("__rust_probestack")
+        );
+
+        assert_eq!(RuntimeSymbol::lookup("rust_alloc"), None);
+        assert_eq!(RuntimeSymbol::lookup("_RNvC6_123foo3bar"), None);
+    }
+}
@@ -0,0 +1,135 @@
+//! Displaying mangled names byte for byte the way `rustc-demangle` displays them.
+
+use crate::padding;
+use crate::rust_v0::{DisplayStyle, Symbol};
+use std::fmt::{self, Display, Formatter};
+
+/// A demangled name, see [`demangle_rustc_compatible`](crate::rust_v0::demangle_rustc_compatible).
+#[derive(Clone, Debug)]
+pub struct RustcCompatible<'a> {
+    symbol: Symbol<'a>,
+    suffix: &'a str,
+}
+
+impl<'a> RustcCompatible<'a> {
+    /// Returns the parsed symbol, without its vendor specific suffix.
+    #[must_use]
+    pub fn symbol(&self) -> &Symbol<'a> {
+        &self.symbol
+    }
+
+    /// Returns the suffix that is displayed after the symbol, which does not include a stripped `.llvm.` suffix.
+    #[must_use]
+    pub fn suffix(&self) -> &'a str {
+        self.suffix
+    }
+}
+
+impl Display for RustcCompatible<'_> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let style = if f.alternate() {
+            DisplayStyle::Normal
+        } else {
+            DisplayStyle::Long
+        };
+
+        let precision = f.precision();
+
+        // The padding applies to the symbol and the suffix together.
+        padding::write_padded(f, |out| {
+            match precision {
+                None => write!(out, "{}", self.symbol.display(style))?,
+                Some(max_len) => write!(out, "{:.max_len$}", self.symbol.display(style))?,
+            }
+
+            out.write_str(self.suffix)
+        })
+    }
+}
+
+/// Strips a `.llvm.` suffix added by thin LTO, but only if the rest of the name consists of uppercase hexadecimal digits
+/// and `@`, which is the check `rustc-demangle` does.
+fn strip_llvm_suffix(name: &str) -> &str {
+    name.find(".llvm.").map_or(name, |i| {
+        if name[i + 6..].chars().all(|c| matches!(c, 'A'..='F' | '0'..='9' | '@')) {
+            &name[..i]
+        } else {
+            name
+        }
+    })
+}
+
+/// `rustc-demangle` keeps a suffix only if it starts with `.` and consists of ASCII alphanumeric and punctuation
+/// characters.
+fn is_symbol_like(suffix: &str) -> bool {
+    suffix.starts_with('.')
+        && suffix
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c.is_ascii_punctuation())
+}
+
+pub fn demangle(name: &str) -> Option<RustcCompatible<'_>> {
+    let name = strip_llvm_suffix(name);
+    let (mut symbol, rest) = Symbol::parse_from_str(name).ok()?;
+
+    // `rustc-demangle` does not accept encoding versions, and only keeps suffixes that look like LLVM IR suffixes.
+    if !rest.is_empty() || symbol.version.is_some() {
+        return None;
+    }
+
+    let suffix = match symbol.vendor_specific_suffix.take() {
+        None => "",
+        Some(suffix) => {
+            let suffix = &name[name.len() - suffix.len()..];
+
+            is_symbol_like(suffix).then_some(suffix)?
+        }
+    };
+
+    Some(RustcCompatible { symbol, suffix })
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_demangle_rustc_compatible() {
+        let test_cases = [
+            ("_RNvCs123_3foo3bar", Some(("foo[f85]::bar", "foo::bar"))),
+            ("_RNvC3foo3bar.llvm.0123ABCD", Some(("foo::bar", "foo::bar"))),
+            ("_RNvC3foo3bar.llvm.0123@@", Some(("foo::bar", "foo::bar"))),
+            (
+                "_RNvC3foo3bar.llvm.abc",
+                Some(("foo::bar.llvm.abc", "foo::bar.llvm.abc")),
+            ),
+            ("_RNvC3foo3bar.cold.1", Some(("foo::bar.cold.1", "foo::bar.cold.1"))),
+            ("_RNvC3foo3bar.cold.llvm.12", Some(("foo::bar.cold", "foo::bar.cold"))),
+            ("_RNvC3foo3bar$abc", None),
+            ("_RNvC3foo3bar.a b", None),
+            ("_RNvC3foo3barbaz", None),
+            ("_R0NvC3foo3bar", None),
+            ("_RNvC3foo", None),
+        ];
+
+        for (name, expected) in test_cases {
+            let demangled = super::demangle(name);
+
+            assert_eq!(
+                demangled
+                    .as_ref()
+                    .map(|demangled| (demangled.to_string(), format!("{demangled:#}"))),
+                expected.map(|(long, normal)| (long.to_string(), normal.to_string())),
+                "{name}"
+            );
+
+            assert_eq!(
+                demangled
+                    .as_ref()
+                    .map(|demangled| (demangled.to_string(), format!("{demangled:#}"))),
+                rustc_demangle::try_demangle(name)
+                    .ok()
+                    .map(|demangled| (demangled.to_string(), format!("{demangled:#}"))),
+                "{name}"
+            );
+        }
+    }
+}
@@ -0,0 +1,83 @@
+//! Finding mangled names embedded in arbitrary text.
+
+use crate::rust_v0::Symbol;
+use std::iter::FusedIterator;
+use std::ops::Range;
+
+fn is_symbol_char(c: u8) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, b'_' | b'.' | b'$')
+}
+
+/// An iterator over the mangled names in a string, created by [`find_symbols`].
+#[derive(Clone, Debug)]
+pub struct FindSymbols<'a> {
+    haystack: &'a str,
+    position: usize,
+}
+
+impl<'a> Iterator for FindSymbols<'a> {
+    type Item = (Range<usize>, Symbol<'a>, &'a str);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let bytes = self.haystack.as_bytes();
+
+        while self.position < bytes.len() {
+            if is_symbol_char(bytes[self.position]) {
+                let start = self.position;
+
+                while self.position < bytes.len() && is_symbol_char(bytes[self.position]) {
+                    self.position += 1;
+                }
+
+                let word = &self.haystack[start..self.position];
+
+                if word.starts_with("_R") || word.starts_with("__R") {
+                    if let Ok((symbol, suffix)) = Symbol::parse_from_str(word) {
+                        return Some((start..self.position - suffix.len(), symbol, suffix));
+                    }
+                }
+            } else {
+                self.position += 1;
+            }
+        }
+
+        None
+    }
+}
+
+impl FusedIterator for FindSymbols<'_> {}
+
+/// Returns an iterator over the mangled names with Rust v0 syntax in `haystack`, like symbols in LLVM IR or assembly
+/// listings. Mangled names are recognized by their `_R` or `__R` prefix at the start of a word, where words consist of
+/// ASCII alphanumeric characters, `_`, `.` and `$`, so names surrounded by quotes or punctuation are found too.
+///
+/// Each item contains the byte range of the mangled name in `haystack`, including its vendor specific suffix, the
+/// parsed [`Symbol`], and the rest of the word that is not part of the Rust v0 syntax.
+#[must_use]
+pub fn find_symbols(haystack: &str) -> FindSymbols<'_> {
+    FindSymbols { haystack, position: 0 }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_find_symbols() {
+        let haystack =
+            r#"  call void @"_RNvC3foo3bar.llvm.12"(ptr @__RNvC3foo3baz), !dbg _Rx _RNvC3foo3quxX FOO_RNvC3foo3bar"#;
+
+        let found = super::find_symbols(haystack)
+            .map(|(range, symbol, suffix)| (&haystack[range], symbol.to_string(), suffix))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            found,
+            [
+                ("_RNvC3foo3bar.llvm.12", "foo::bar".to_string(), ""),
+                ("__RNvC3foo3baz", "foo::baz".to_string(), ""),
+                ("_RNvC3foo3qux", "foo::qux".to_string(), "X"),
+            ]
+        );
+
+        assert_eq!(super::find_symbols("no symbols").count(), 0);
+    }
+}
@@ -0,0 +1,160 @@
+//! Serializing symbols as strings. The AST types derive their structural `Serialize` and `Deserialize` implementations.
+
+use crate::rust_v0::{DisplayStyle, ParseSymbolError, Symbol};
+use serde::de::{self, Deserialize, Deserializer};
+use serde::ser::{self, Serialize, Serializer};
+use std::fmt::{self, Display, Formatter, Write};
+
+/// A [`Symbol`] together with its mangled name. It serializes to the mangled name and deserializes by parsing a
+/// mangled name, so it can be exchanged with APIs that represent symbols as strings.
+///
+/// Deserialization borrows the mangled name from the input, so it fails on inputs that need to be unescaped.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct MangledSymbol<'a> {
+    name: &'a str,
+    symbol: Symbol<'a>,
+}
+
+impl<'a> MangledSymbol<'a> {
+    /// Parses `name` as a mangled name with Rust v0 syntax.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParseSymbolError`] if `name` cannot be parsed, or if it contains characters following the vendor
+    /// specific suffix.
+    pub fn parse(name: &'a str) -> Result<Self, ParseSymbolError> {
+        match Symbol::parse_from_str(name)? {
+            (symbol, "") => Ok(Self { name, symbol }),
+            _ => Err(ParseSymbolError),
+        }
+    }
+
+    /// Returns the mangled name.
+    #[must_use]
+    pub fn name(&self) -> &'a str {
+        self.name
+    }
+
+    /// Returns the parsed symbol.
+    #[must_use]
+    pub fn symbol(&self) -> &Symbol<'a> {
+        &self.symbol
+    }
+
+    /// Returns the parsed symbol.
+    #[must_use]
+    pub fn into_symbol(self) -> Symbol<'a> {
+        self.symbol
+    }
+}
+
+impl Serialize for MangledSymbol<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name)
+    }
+}
+
+impl<'de: 'a, 'a> Deserialize<'de> for MangledSymbol<'a> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = <&'de str>::deserialize(deserializer)?;
+
+        Self::parse(name).map_err(|ParseSymbolError| {
+            de::Error::invalid_value(de::Unexpected::Str(name), &"a mangled name with Rust v0 syntax")
+        })
+    }
+}
+
+/// A [`Symbol`] that serializes to its demangled name displayed with a [`DisplayStyle`]. Demangled names can not be
+/// parsed back, so use [`MangledSymbol`] if the value needs to be deserialized. Serializing fails if the symbol can not
+/// be displayed.
+#[derive(Clone, Copy, Debug)]
+pub struct DemangledSymbol<'s, 'a> {
+    symbol: &'s Symbol<'a>,
+    style: DisplayStyle,
+}
+
+impl<'s, 'a> DemangledSymbol<'s, 'a> {
+    /// Creates an object that serializes `symbol` displayed with `style`.
+    #[must_use]
+    pub fn new(symbol: &'s Symbol<'a>, style: DisplayStyle) -> Self {
+        Self { symbol, style }
+    }
+}
+
+impl Display for DemangledSymbol<'_, '_> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.symbol.display(self.style).fmt(f)
+    }
+}
+
+impl Serialize for DemangledSymbol<'_, '_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        // `collect_str` may panic if formatting fails, so the name is formatted first.
+        let mut name = String::new();
+
+        write!(name, "{self}").map_err(|fmt::Error| ser::Error::custom("the symbol can not be displayed"))?;
+
+        serializer.serialize_str(&name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DemangledSymbol, MangledSymbol};
+    use crate::rust_v0::{DisplayStyle, Symbol};
+
+    #[test]
+    fn test_mangled_symbol() {
+        let symbol = serde_json::from_str::<MangledSymbol>("\"_RNvC6_123foo3bar.llvm.123\"").unwrap();
+
+        assert_eq!(symbol.name(), "_RNvC6_123foo3bar.llvm.123");
+        assert_eq!(symbol.symbol().vendor_specific_suffix.as_deref(), Some(".llvm.123"));
+        assert_eq!(
+            serde_json::to_string(&symbol).unwrap(),
+            "\"_RNvC6_123foo3bar.llvm.123\""
+        );
+
+        assert!(serde_json::from_str::<MangledSymbol>("\"foo\"").is_err());
+        assert!(serde_json::from_str::<MangledSymbol>("\"_RNvC6_123foo3bar foo\"").is_err());
+        assert!(serde_json::from_str::<MangledSymbol>("1").is_err());
+    }
+
+    #[test]
+    fn test_demangled_symbol() {
+        let symbol = MangledSymbol::parse("_RNvCs123_3foo3bar").unwrap();
+
+        assert_eq!(
+            serde_json::to_string(&DemangledSymbol::new(symbol.symbol(), DisplayStyle::Long)).unwrap(),
+            "\"foo[f85]::bar\""
+        );
+
+        assert_eq!(
+            serde_json::to_string(&DemangledSymbol::new(symbol.symbol(), DisplayStyle::Short)).unwrap(),
+            "\"bar\""
+        );
+
+        // The name parses, but refers to a lifetime that is not bound.
+        let symbol = MangledSymbol::parse("_RMC0RLY_z").unwrap();
+
+        assert!(serde_json::to_string(&DemangledSymbol::new(symbol.symbol(), DisplayStyle::Normal)).is_err());
+    }
+
+    #[test]
+    fn test_structural_serialization() {
+        let (symbol, _) = Symbol::parse_from_str("_RINvC3foo3barRShE.llvm.123").unwrap();
+        let json = serde_json::to_string(&symbol).unwrap();
+
+        assert_eq!(
+            json,
+            concat!(
+                r#"{"version":null,"path":{"Generic":{"path":{"Nested":{"namespace":"Value","path":{"CrateRoot":"#,
+                r#"{"disambiguator":0,"name":"foo","is_undecoded_punycode":false}},"identifier":{"disambiguator":0,"#,
+                r#""name":"bar","is_undecoded_punycode":false}}},"#,
+                r#""generic_args":[{"Type":{"Ref":{"lifetime":0,"type_":{"Slice":{"Basic":"U8"}}}}}]}},"#,
+                r#""instantiating_crate":null,"vendor_specific_suffix":".llvm.123"}"#
+            )
+        );
+
+        assert_eq!(serde_json::from_str::<Symbol>(&json).unwrap(), symbol);
+    }
+}
@@ -0,0 +1,201 @@
+//! Back references recorded while parsing a symbol.
+
+use crate::rust_v0::{Const, NodeRc, Path, Type};
+use std::collections::HashMap;
+
+/// An AST node that can be referenced by a back reference.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum SharedNode<'a> {
+    Path(NodeRc<Path<'a>>),
+    Type(NodeRc<Type<'a>>),
+    Const(NodeRc<Const<'a>>),
+}
+
+impl SharedNode<'_> {
+    /// Returns `true` if `self` and `other` are the same node, rather than equal nodes.
+    #[must_use]
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Path(lhs), Self::Path(rhs)) => NodeRc::ptr_eq(lhs, rhs),
+            (Self::Type(lhs), Self::Type(rhs)) => NodeRc::ptr_eq(lhs, rhs),
+            (Self::Const(lhs), Self::Const(rhs)) => NodeRc::ptr_eq(lhs, rhs),
+            _ => false,
+        }
+    }
+}
+
+/// A back reference in a mangled name. Positions are byte offsets from the start of the mangled name with the `_R`
+/// prefix removed, which is also how back references themselves are encoded.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct BackRef<'a> {
+    /// The position of the back reference.
+    pub position: usize,
+    /// The encoded length of the back reference.
+    pub length: usize,
+    /// The position of the referenced node.
+    pub target: usize,
+    /// The encoded length of the referenced node, which may itself contain back references.
+    pub target_length: usize,
+    /// The referenced node, which is shared with the place the back reference appears in the AST.
+    pub node: SharedNode<'a>,
+}
+
+/// A node that is referenced by back references, see [`SharingGraph::shared_nodes`].
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct SharedSubtree<'a> {
+    /// The position of the node.
+    pub position: usize,
+    /// The encoded length of the node.
+    pub length: usize,
+    /// The length of the node with all back references in it expanded.
+    pub expanded_length: usize,
+    /// The number of back references to the node.
+    pub reference_count: usize,
+    /// The node.
+    pub node: SharedNode<'a>,
+}
+
+/// The back references of a parsed symbol, returned by [`Symbol::parse_with_sharing_graph`](crate::rust_v0::Symbol::parse_with_sharing_graph).
+#[derive(Clone, Debug)]
+pub struct SharingGraph<'a> {
+    back_refs: Vec<BackRef<'a>>,
+    encoded_length: usize,
+    expanded_lengths: Vec<usize>,
+}
+
+impl<'a> SharingGraph<'a> {
+    /// Returns all back references, ordered by their positions.
+    #[must_use]
+    pub fn back_refs(&self) -> &[BackRef<'a>] {
+        &self.back_refs
+    }
+
+    /// Returns the nodes referenced by back references, ordered by their positions.
+    #[must_use]
+    pub fn shared_nodes(&self) -> Vec<SharedSubtree<'a>> {
+        let mut indices = HashMap::new();
+        let mut result = Vec::<SharedSubtree>::new();
+
+        for (back_ref, &expanded_length) in self.back_refs.iter().zip(&self.expanded_lengths) {
+            let index = *indices.entry(back_ref.target).or_insert_with(|| {
+                result.push(SharedSubtree {
+                    position: back_ref.target,
+                    length: back_ref.target_length,
+                    expanded_length,
+                    reference_count: 0,
+                    node: back_ref.node.clone(),
+                });
+
+                result.len() - 1
+            });
+
+            result[index].reference_count += 1;
+        }
+
+        result.sort_unstable_by_key(|shared_subtree| shared_subtree.position);
+
+        result
+    }
+
+    /// Returns `true` if `node` appears in the AST through a back reference.
+    #[must_use]
+    pub fn is_shared(&self, node: &SharedNode) -> bool {
+        self.back_refs.iter().any(|back_ref| back_ref.node.ptr_eq(node))
+    }
+
+    /// Returns the length of the parsed part of the mangled name, excluding the `_R` prefix.
+    #[must_use]
+    pub fn encoded_length(&self) -> usize {
+        self.encoded_length
+    }
+
+    /// Returns the length the parsed part of the mangled name would have without back references.
+    #[must_use]
+    pub fn expanded_length(&self) -> usize {
+        expanded_length(&self.back_refs, &self.expanded_lengths, 0, self.encoded_length)
+    }
+}
+
+pub fn sharing_graph(mut back_refs: Vec<BackRef>, encoded_length: usize) -> SharingGraph {
+    back_refs.sort_unstable_by_key(|back_ref| back_ref.position);
+
+    // A back reference can only refer to a node that ends before it, so the expanded lengths of all referenced
+    // nodes are known when the back references are visited in order.
+    let mut expanded_lengths = Vec::with_capacity(back_refs.len());
+
+    for back_ref in &back_refs {
+        let end = back_ref.target + back_ref.target_length;
+        let expanded_length = expanded_length(
+            &back_refs[..expanded_lengths.len()],
+            &expanded_lengths,
+            back_ref.target,
+            end,
+        );
+
+        expanded_lengths.push(expanded_length);
+    }
+
+    SharingGraph {
+        back_refs,
+        encoded_length,
+        expanded_lengths,
+    }
+}
+
+/// Computes the expanded length of the range `start..end`, given the expanded lengths of the targets of
+/// `back_refs`.
+fn expanded_length(back_refs: &[BackRef], target_expanded_lengths: &[usize], start: usize, end: usize) -> usize {
+    back_refs
+        .iter()
+        .zip(target_expanded_lengths)
+        .filter(|(back_ref, _)| start <= back_ref.position && back_ref.position < end)
+        .fold(end - start, |length, (back_ref, expanded_length)| {
+            length - back_ref.length + expanded_length
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SharedNode;
+    use crate::rust_v0::{GenericArg, NodeRc, Path, Symbol, Type};
+
+    #[test]
+    fn test_sharing_graph() {
+        let (symbol, suffix, sharing_graph) =
+            Symbol::parse_with_sharing_graph("_RINvC3foo3barTNtB2_3BazBc_Bc_EE.llvm.123").unwrap();
+
+        assert_eq!(suffix, "");
+        assert_eq!(sharing_graph.encoded_length(), 30);
+        assert_eq!(sharing_graph.back_refs().len(), 3);
+
+        let shared_nodes = sharing_graph.shared_nodes();
+
+        assert_eq!(shared_nodes.len(), 2);
+        assert_eq!((shared_nodes[0].position, shared_nodes[0].length), (3, 5));
+        assert_eq!(shared_nodes[0].reference_count, 1);
+        assert_eq!((shared_nodes[1].position, shared_nodes[1].length), (13, 9));
+        assert_eq!(shared_nodes[1].expanded_length, 11);
+        assert_eq!(shared_nodes[1].reference_count, 2);
+        assert_eq!(sharing_graph.expanded_length(), 48);
+
+        let Path::Generic { generic_args, .. } = symbol.path.as_ref() else {
+            panic!();
+        };
+
+        let GenericArg::Type(tuple) = &generic_args[0] else {
+            panic!();
+        };
+
+        let Type::Tuple(tuple_types) = tuple.as_ref() else {
+            panic!();
+        };
+
+        let Type::Named(path) = tuple_types[1].as_ref() else {
+            panic!();
+        };
+
+        assert!(sharing_graph.is_shared(&SharedNode::Path(NodeRc::clone(path))));
+        assert!(!sharing_graph.is_shared(&SharedNode::Type(NodeRc::clone(tuple))));
+        assert!(!sharing_graph.is_shared(&SharedNode::Type(NodeRc::clone(&tuple_types[1]))));
+    }
+}
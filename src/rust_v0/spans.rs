@@ -0,0 +1,137 @@
+//! Byte ranges of AST nodes in the mangled names they are parsed from.
+
+use crate::rust_v0::SharedNode;
+use std::cmp::Reverse;
+use std::ops::Range;
+
+/// A path, type or constant in a mangled name, see [`SourceSpans`].
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct NodeSpan<'a> {
+    /// The byte range of the node in the mangled name, including the `_R` prefix.
+    pub range: Range<usize>,
+    /// The start of the referenced node, if the node is encoded as a back reference.
+    pub back_ref_target: Option<usize>,
+    /// The node, which is shared with the place it appears in the AST.
+    pub node: SharedNode<'a>,
+}
+
+/// The byte ranges of the paths, types and constants of a parsed symbol, returned by
+/// [`Symbol::parse_with_spans`](crate::rust_v0::Symbol::parse_with_spans).
+#[derive(Clone, Debug)]
+pub struct SourceSpans<'a> {
+    spans: Vec<NodeSpan<'a>>,
+}
+
+impl<'a> SourceSpans<'a> {
+    /// Returns the spans of all nodes, ordered by their positions, with enclosing nodes before the nodes they contain.
+    /// This is the order the nodes appear in the AST, except that nodes encoded as back references have no spans for
+    /// their children.
+    #[must_use]
+    pub fn spans(&self) -> &[NodeSpan<'a>] {
+        &self.spans
+    }
+
+    /// Returns the spans of every place `node` appears in, which is more than one if it is referenced by back
+    /// references. Basic types are shared within a symbol, so all occurrences of the same basic type are returned.
+    pub fn spans_of<'s>(&'s self, node: &'s SharedNode) -> impl Iterator<Item = &'s NodeSpan<'a>> + 's {
+        self.spans.iter().filter(move |span| span.node.ptr_eq(node))
+    }
+
+    /// Returns the innermost node whose span contains `offset`, if any.
+    #[must_use]
+    pub fn innermost_at(&self, offset: usize) -> Option<&NodeSpan<'a>> {
+        self.spans.iter().rev().find(|span| span.range.contains(&offset))
+    }
+}
+
+fn node_rank(node: &SharedNode) -> u8 {
+    match node {
+        SharedNode::Type(_) => 0,
+        SharedNode::Path(_) => 1,
+        SharedNode::Const(_) => 2,
+    }
+}
+
+pub fn source_spans(mut spans: Vec<NodeSpan>) -> SourceSpans {
+    // A named type has the same span as its path, the type encloses the path.
+    spans.sort_unstable_by(|lhs, rhs| {
+        (lhs.range.start, Reverse(lhs.range.end), node_rank(&lhs.node)).cmp(&(
+            rhs.range.start,
+            Reverse(rhs.range.end),
+            node_rank(&rhs.node),
+        ))
+    });
+
+    SourceSpans { spans }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rust_v0::{SharedNode, Symbol};
+
+    fn describe(span: &super::NodeSpan) -> (String, &'static str, std::ops::Range<usize>, Option<usize>) {
+        let (kind, text) = match &span.node {
+            SharedNode::Path(path) => ("path", path.to_string()),
+            SharedNode::Type(type_) => ("type", type_.to_string()),
+            SharedNode::Const(const_) => ("const", const_.to_string()),
+        };
+
+        (text, kind, span.range.clone(), span.back_ref_target)
+    }
+
+    #[test]
+    fn test_parse_with_spans() {
+        let name = "_RINvC3foo3barNtB2_3BazKj1_EB2_.llvm.1";
+        let (symbol, suffix, spans) = Symbol::parse_with_spans(name).unwrap();
+
+        assert_eq!(symbol.to_string(), "foo::bar::<foo::Baz, 1usize>");
+        assert_eq!(suffix, "");
+
+        assert_eq!(
+            spans.spans().iter().map(describe).collect::<Vec<_>>(),
+            [
+                ("foo::bar<foo::Baz, 1usize>".to_string(), "path", 2..28, None),
+                ("foo::bar".to_string(), "path", 3..14, None),
+                ("foo".to_string(), "path", 5..10, None),
+                ("foo::Baz".to_string(), "type", 14..23, None),
+                ("foo::Baz".to_string(), "path", 14..23, None),
+                ("foo".to_string(), "path", 16..19, Some(5)),
+                ("1usize".to_string(), "const", 24..27, None),
+                ("foo".to_string(), "path", 28..31, Some(5)),
+            ]
+        );
+
+        for span in spans.spans() {
+            assert!(
+                name[span.range.clone()].starts_with(['I', 'N', 'C', 'B', 'j']),
+                "{span:?}"
+            );
+        }
+
+        let crate_root = &spans.spans()[2].node;
+
+        assert_eq!(
+            spans
+                .spans_of(crate_root)
+                .map(|span| span.range.clone())
+                .collect::<Vec<_>>(),
+            [5..10, 16..19, 28..31]
+        );
+
+        assert_eq!(spans.innermost_at(8).map(describe).unwrap().2, 5..10);
+        assert_eq!(spans.innermost_at(20).map(describe).unwrap().2, 14..23);
+        assert_eq!(spans.innermost_at(25).map(describe).unwrap().2, 24..27);
+        assert!(spans.innermost_at(1).is_none());
+        assert!(spans.innermost_at(31).is_none());
+    }
+
+    #[test]
+    fn test_parse_with_spans_failed_instantiating_crate() {
+        // The instantiating crate fails to parse after its crate root, which must not leave a span behind.
+        let (symbol, suffix, spans) = Symbol::parse_with_spans("_RNvC3foo3barNvC3baz").unwrap();
+
+        assert_eq!(symbol.instantiating_crate, None);
+        assert_eq!(suffix, "NvC3baz");
+        assert!(spans.spans().iter().all(|span| span.range.end <= 13));
+    }
+}
@@ -0,0 +1,1048 @@
+//! Demangling symbols by printing while parsing, without building ASTs.
+//!
+//! The mangled name is parsed twice, the same way rustc-demangle does: the first pass checks the syntax with the same
+//! rules as the AST parser and records the positions of the nodes that can be back referenced, and the second pass
+//! prints the name, jumping to the targets of back references and parsing them again.
+
+use crate::rust_v0::display::{self, DefaultRenderer, DemangleWrite, Style};
+use crate::rust_v0::{parsers, Abi, BasicType, DemangleError, Identifier, Namespace, ParseOptions, Symbol};
+use num_traits::{CheckedNeg, PrimInt};
+use std::borrow::Cow;
+use std::{fmt, mem};
+
+/// The crate and the item name of a symbol, returned by [`Symbol::parse_path_head`].
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct PathHead<'a> {
+    /// The crate the symbol path starts from. For paths in impl blocks, this is the crate of the impl block, and for
+    /// paths in trait definitions like `<T as Trait>::method`, this is the crate of the trait.
+    pub crate_root: Identifier<'a>,
+    /// The last identifier of the path, skipping closures, shims and empty identifiers, like `bar` for
+    /// `foo::bar::{closure#0}`, or [`None`] for paths without such identifiers, like impl blocks themselves.
+    pub item: Option<Identifier<'a>>,
+}
+
+/// Back references can expand a short mangled name into deeply nested output, so printing has its own nesting limit.
+const MAX_PRINT_DEPTH: usize = 500;
+
+/// The heights of the subtrees of the successfully parsed nodes of one kind, indexed by their positions, which are the
+/// valid back reference targets. Nodes are recorded after their children, so indexing keeps inserting out of order
+/// constant time.
+#[derive(Default)]
+struct Positions(Vec<Option<usize>>);
+
+impl Positions {
+    fn contains(&self, position: usize) -> bool {
+        self.get(position).is_some()
+    }
+
+    fn get(&self, position: usize) -> Option<usize> {
+        self.0.get(position).copied().flatten()
+    }
+
+    fn insert(&mut self, position: usize, height: usize) {
+        if self.0.len() <= position {
+            self.0.resize(position + 1, None);
+        }
+
+        self.0[position] = Some(height);
+    }
+}
+
+#[derive(Default)]
+struct Nodes {
+    paths: Positions,
+    types: Positions,
+    consts: Positions,
+}
+
+#[derive(Clone, Copy)]
+enum Kind {
+    Path,
+    Type,
+    Const,
+}
+
+impl Nodes {
+    fn get_mut(&mut self, kind: Kind) -> &mut Positions {
+        match kind {
+            Kind::Path => &mut self.paths,
+            Kind::Type => &mut self.types,
+            Kind::Const => &mut self.consts,
+        }
+    }
+}
+
+/// Walks over a mangled name in one of three modes: checking the syntax while `nodes` is set, printing while `out` is
+/// set, or skipping over a node that is not printed while neither is set. Back references are checked when checking
+/// the syntax, followed when printing and ignored when skipping.
+struct Demangler<'a, 'o> {
+    input: &'a str,
+    position: usize,
+    style: Style,
+    out: Option<&'o mut dyn DemangleWrite>,
+    nodes: Option<Nodes>,
+    depth: usize,
+    /// The deepest nesting level reached by the node being checked, counting the nodes back references expand to.
+    deepest: usize,
+    bound_lifetime_depth: u64,
+}
+
+impl<'a, 'o> Demangler<'a, 'o> {
+    fn new(input: &'a str, style: Style, out: Option<&'o mut dyn DemangleWrite>, nodes: Option<Nodes>) -> Self {
+        Self {
+            input,
+            position: 0,
+            style,
+            out,
+            nodes,
+            depth: 0,
+            deepest: 0,
+            bound_lifetime_depth: 0,
+        }
+    }
+
+    fn is_printing(&self) -> bool {
+        self.out.is_some()
+    }
+
+    // Output.
+
+    fn write(&mut self, f: impl FnOnce(&mut dyn DemangleWrite) -> fmt::Result) -> Result<(), DemangleError> {
+        match &mut self.out {
+            Some(out) => f(&mut **out).map_err(|fmt::Error| DemangleError::Write),
+            None => Ok(()),
+        }
+    }
+
+    fn write_str(&mut self, s: &str) -> Result<(), DemangleError> {
+        self.write(|out| out.write_str(s))
+    }
+
+    fn write_lifetime(&mut self, lifetime: u64) -> Result<(), DemangleError> {
+        let bound_lifetime_depth = self.bound_lifetime_depth;
+
+        if self.is_printing() && lifetime > bound_lifetime_depth {
+            return Err(DemangleError::NotDisplayable);
+        }
+
+        self.write(|out| display::write_lifetime(lifetime, out, bound_lifetime_depth))
+    }
+
+    fn write_binder(&mut self, bound_lifetimes: u64) -> Result<(), DemangleError> {
+        if bound_lifetimes == 0 || self.style == Style::Debuginfo {
+            return Ok(());
+        }
+
+        let bound_lifetime_depth = self.bound_lifetime_depth;
+
+        if self.is_printing() && bound_lifetime_depth.checked_add(bound_lifetimes).is_none() {
+            return Err(DemangleError::NotDisplayable);
+        }
+
+        self.write(|out| {
+            display::write_binder(bound_lifetimes, out, &mut DefaultRenderer, bound_lifetime_depth)?;
+            out.write_str(" ")
+        })
+    }
+
+    // Moving around.
+
+    /// Runs `f` at `position`, then comes back to the current position.
+    fn at<T>(
+        &mut self,
+        position: usize,
+        f: impl FnOnce(&mut Self) -> Result<T, DemangleError>,
+    ) -> Result<T, DemangleError> {
+        let saved = mem::replace(&mut self.position, position);
+        let result = f(self);
+
+        self.position = saved;
+
+        result
+    }
+
+    /// Runs `f` without writing anything.
+    fn skipping(&mut self, f: impl FnOnce(&mut Self) -> Result<(), DemangleError>) -> Result<(), DemangleError> {
+        let out = self.out.take();
+        let result = f(self);
+
+        self.out = out;
+
+        result
+    }
+
+    /// Follows back references starting at `position`, and returns the position of the node they refer to.
+    fn resolve(&mut self, position: usize) -> Result<usize, DemangleError> {
+        self.at(position, |this| {
+            while this.eat(b'B') {
+                this.position = this.back_ref_index()?;
+            }
+
+            Ok(this.position)
+        })
+    }
+
+    fn resolve_tag(&mut self, position: usize) -> Result<Option<u8>, DemangleError> {
+        let position = self.resolve(position)?;
+
+        Ok(self.input.as_bytes().get(position).copied())
+    }
+
+    // Lexing.
+
+    fn peek(&self) -> Option<u8> {
+        self.input.as_bytes().get(self.position).copied()
+    }
+
+    fn next(&mut self) -> Result<u8, DemangleError> {
+        let c = self.peek().ok_or(DemangleError::Invalid)?;
+
+        self.position += 1;
+
+        Ok(c)
+    }
+
+    fn eat(&mut self, c: u8) -> bool {
+        let result = self.peek() == Some(c);
+
+        if result {
+            self.position += 1;
+        }
+
+        result
+    }
+
+    fn expect(&mut self, c: u8) -> Result<(), DemangleError> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(DemangleError::Invalid)
+        }
+    }
+
+    fn take_while(&mut self, f: impl Fn(u8) -> bool) -> &'a str {
+        let start = self.position;
+
+        while self.peek().is_some_and(&f) {
+            self.position += 1;
+        }
+
+        &self.input[start..self.position]
+    }
+
+    fn decimal<T: PrimInt>(&mut self) -> Result<T, DemangleError> {
+        let digits = if self.peek() == Some(b'0') {
+            self.position += 1;
+
+            "0"
+        } else {
+            self.take_while(|c| c.is_ascii_digit())
+        };
+
+        T::from_str_radix(digits, 10).map_err(|_| DemangleError::Invalid)
+    }
+
+    fn base62(&mut self) -> Result<u64, DemangleError> {
+        let digits = self.take_while(|c| c.is_ascii_alphanumeric());
+
+        self.expect(b'_')?;
+
+        if digits.is_empty() {
+            return Ok(0);
+        }
+
+        let mut value = 0_u64;
+
+        for c in digits.bytes() {
+            let digit = match c {
+                b'0'..=b'9' => c - b'0',
+                b'a'..=b'z' => 10 + (c - b'a'),
+                _ => 36 + (c - b'A'),
+            };
+
+            value = value
+                .checked_mul(62)
+                .and_then(|value| value.checked_add(digit.into()))
+                .ok_or(DemangleError::Invalid)?;
+        }
+
+        value.checked_add(1).ok_or(DemangleError::Invalid)
+    }
+
+    /// Parses a base 62 number preceded by `tag`, which is encoded plus one, or zero if there is no `tag`.
+    fn opt_base62(&mut self, tag: u8) -> Result<u64, DemangleError> {
+        if self.eat(tag) {
+            self.base62()?.checked_add(1).ok_or(DemangleError::Invalid)
+        } else {
+            Ok(0)
+        }
+    }
+
+    fn back_ref_index(&mut self) -> Result<usize, DemangleError> {
+        self.base62()?.try_into().map_err(|_| DemangleError::Invalid)
+    }
+
+    fn const_int<T: CheckedNeg + PrimInt>(&mut self) -> Result<T, DemangleError> {
+        let is_negative = self.eat(b'n');
+        let data = self.take_while(|c| matches!(c, b'0'..=b'9' | b'a'..=b'z'));
+
+        self.expect(b'_')?;
+
+        parsers::decode_const_int(is_negative, data).ok_or(DemangleError::Invalid)
+    }
+
+    fn undisambiguated_identifier(&mut self) -> Result<Cow<'a, str>, DemangleError> {
+        let is_punycode = self.eat(b'u');
+        let length = self.decimal::<usize>()?;
+
+        self.eat(b'_');
+
+        let name = self
+            .position
+            .checked_add(length)
+            .and_then(|end| self.input.get(self.position..end))
+            .ok_or(DemangleError::Invalid)?;
+
+        self.position += length;
+
+        if is_punycode {
+            parsers::decode_punycode_identifier(name)
+                .map(Cow::Owned)
+                .ok_or(DemangleError::Invalid)
+        } else {
+            Ok(Cow::Borrowed(name))
+        }
+    }
+
+    fn identifier(&mut self) -> Result<Identifier<'a>, DemangleError> {
+        let disambiguator = self.opt_base62(b's')?;
+        let name = self.undisambiguated_identifier()?;
+
+        Ok(Identifier {
+            disambiguator,
+            name,
+            is_undecoded_punycode: false,
+        })
+    }
+
+    // Grammar.
+
+    /// Parses a node of `kind` with `f`, enforcing the nesting limits. When checking the syntax, the limit and the
+    /// recorded back reference targets are the same as the AST parser’s.
+    fn node(
+        &mut self,
+        kind: Kind,
+        f: impl FnOnce(&mut Self, usize) -> Result<(), DemangleError>,
+    ) -> Result<(), DemangleError> {
+        let start = self.position;
+
+        if self.nodes.is_some() {
+            if self.depth >= ParseOptions::default().max_depth {
+                return Err(DemangleError::Invalid);
+            }
+        } else if self.is_printing() && self.depth >= MAX_PRINT_DEPTH {
+            return Err(DemangleError::NotDisplayable);
+        }
+
+        self.depth += 1;
+
+        let outer_deepest = mem::replace(&mut self.deepest, self.depth);
+        let result = f(self, start);
+        let height = self.deepest - self.depth;
+
+        self.deepest = self.deepest.max(outer_deepest);
+        self.depth -= 1;
+
+        result?;
+
+        if let Some(nodes) = &mut self.nodes {
+            nodes.get_mut(kind).insert(start, height);
+        }
+
+        Ok(())
+    }
+
+    /// Checks that a back reference target of `height` fits in the nesting limit when it is expanded at `depth`, the
+    /// same as the AST parser does.
+    fn expand_back_ref(&mut self, depth: usize, height: Option<usize>) -> bool {
+        match height.and_then(|height| depth.checked_add(height)) {
+            Some(deepest) if deepest <= ParseOptions::default().max_depth => {
+                self.deepest = self.deepest.max(deepest);
+
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Parses a back reference to a node of `kind` after the `B` tag, printing the target with `f`.
+    fn back_ref(
+        &mut self,
+        kind: Kind,
+        f: impl FnOnce(&mut Self) -> Result<(), DemangleError>,
+    ) -> Result<(), DemangleError> {
+        let start = self.position - 1;
+        let target = self.back_ref_index()?;
+
+        if let Some(nodes) = &mut self.nodes {
+            let height = nodes.get_mut(kind).get(target).filter(|_| target < start);
+
+            if self.expand_back_ref(self.depth, height) {
+                Ok(())
+            } else {
+                Err(DemangleError::Invalid)
+            }
+        } else if self.is_printing() {
+            self.at(target, f)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn symbol(&mut self) -> Result<(), DemangleError> {
+        if self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.decimal::<u64>()?;
+        }
+
+        self.path(true)?;
+
+        if self.nodes.is_some() {
+            let position = self.position;
+
+            if self.path(false).is_err() {
+                self.position = position;
+            }
+
+            if self.input[self.position..].starts_with(['.', '$']) {
+                self.position = self.input.len();
+            }
+        }
+
+        Ok(())
+    }
+
+    fn path(&mut self, in_value: bool) -> Result<(), DemangleError> {
+        self.node(Kind::Path, |this, _| match this.next()? {
+            b'C' => {
+                let identifier = this.identifier()?;
+                let style = this.style;
+
+                this.write(|out| display::write_crate_root(&identifier, out, style))
+            }
+            tag @ (b'M' | b'X') => {
+                let disambiguator = this.opt_base62(b's')?;
+
+                if this.is_printing() && this.style == Style::Debuginfo {
+                    this.path(in_value)?;
+                    this.write(|out| write!(out, "::{{impl#{disambiguator}}}"))?;
+
+                    this.skipping(|this| {
+                        this.type_()?;
+
+                        if tag == b'X' {
+                            this.path(false)?;
+                        }
+
+                        Ok(())
+                    })
+                } else {
+                    this.skipping(|this| this.path(false))?;
+                    this.write_str("<")?;
+                    this.type_()?;
+
+                    if tag == b'X' {
+                        this.write_str(" as ")?;
+                        this.path(false)?;
+                    }
+
+                    this.write_str(">")
+                }
+            }
+            b'Y' => {
+                if this.is_printing() && this.style == Style::Debuginfo {
+                    this.skipping(Self::type_)?;
+                    this.path(in_value)
+                } else {
+                    this.write_str("<")?;
+                    this.type_()?;
+                    this.write_str(" as ")?;
+                    this.path(false)?;
+                    this.write_str(">")
+                }
+            }
+            b'N' => {
+                let namespace = Namespace::from_char(char::from(this.next()?)).ok_or(DemangleError::Invalid)?;
+
+                this.nested_path(namespace, in_value)
+            }
+            b'I' => {
+                this.path(in_value)?;
+
+                if in_value && this.style != Style::Debuginfo {
+                    this.write_str("::")?;
+                }
+
+                this.generic_args()
+            }
+            b'B' => this.back_ref(Kind::Path, |this| this.path(in_value)),
+            _ => Err(DemangleError::Invalid),
+        })
+    }
+
+    /// Checks the syntax of a path like [`Demangler::path`] does while checking, and returns its head. Back references
+    /// in the spine of the path are followed, others are only checked.
+    fn path_head(&mut self) -> Result<PathHead<'a>, DemangleError> {
+        let mut head = None;
+
+        self.node(Kind::Path, |this, start| {
+            head = Some(match this.next()? {
+                b'C' => {
+                    let identifier = this.identifier()?;
+
+                    PathHead {
+                        crate_root: identifier,
+                        item: None,
+                    }
+                }
+                tag @ (b'M' | b'X') => {
+                    this.opt_base62(b's')?;
+
+                    let impl_head = this.path_head()?;
+
+                    this.type_()?;
+
+                    if tag == b'X' {
+                        this.path(false)?;
+                    }
+
+                    PathHead {
+                        item: None,
+                        ..impl_head
+                    }
+                }
+                b'Y' => {
+                    this.type_()?;
+
+                    PathHead {
+                        item: None,
+                        ..this.path_head()?
+                    }
+                }
+                b'N' => {
+                    let namespace = Namespace::from_char(char::from(this.next()?)).ok_or(DemangleError::Invalid)?;
+                    let mut head = this.path_head()?;
+                    let identifier = this.identifier()?;
+
+                    if !namespace.is_special() && !identifier.name.is_empty() {
+                        head.item = Some(identifier);
+                    }
+
+                    head
+                }
+                b'I' => {
+                    let head = this.path_head()?;
+
+                    this.generic_args()?;
+
+                    head
+                }
+                b'B' => {
+                    let target = this.back_ref_index()?;
+
+                    if target >= start || !this.nodes.as_ref().is_some_and(|nodes| nodes.paths.contains(target)) {
+                        return Err(DemangleError::Invalid);
+                    }
+
+                    this.at(target, Self::path_head)?
+                }
+                _ => return Err(DemangleError::Invalid),
+            });
+
+            Ok(())
+        })?;
+
+        head.ok_or(DemangleError::Invalid)
+    }
+
+    fn nested_path(&mut self, namespace: Namespace, in_value: bool) -> Result<(), DemangleError> {
+        let inner = self.position;
+
+        if namespace.is_special() {
+            self.path(in_value)?;
+
+            let identifier = self.identifier()?;
+
+            self.write(|out| {
+                out.write_str("::")?;
+                display::write_special_identifier(namespace, &identifier, out)
+            })
+        } else if !self.is_printing()
+            || self.style != Style::Short
+            || matches!(self.resolve_tag(inner)?, Some(b'M' | b'X' | b'Y' | b'I'))
+        {
+            self.path(in_value)?;
+
+            let identifier = self.identifier()?;
+
+            if identifier.name.is_empty() {
+                Ok(())
+            } else {
+                self.write(|out| write!(out, "::{}", identifier.name))
+            }
+        } else {
+            // The short style omits the enclosing path unless the identifier is empty, which is only known after the
+            // enclosing path.
+            self.skipping(|this| this.path(in_value))?;
+
+            let identifier = self.identifier()?;
+
+            if identifier.name.is_empty() {
+                self.at(inner, |this| this.path(in_value))
+            } else {
+                self.write_str(&identifier.name)
+            }
+        }
+    }
+
+    fn generic_args(&mut self) -> Result<(), DemangleError> {
+        if self.style == Style::Debuginfo {
+            // Debuginfo names do not contain lifetimes.
+            let mut shown = 0_usize;
+
+            while !self.eat(b'E') {
+                if self.peek() == Some(b'L') {
+                    self.skipping(Self::generic_arg)?;
+                } else {
+                    self.write_str(if shown == 0 { "<" } else { ", " })?;
+                    self.generic_arg()?;
+
+                    shown += 1;
+                }
+            }
+
+            if shown == 0 {
+                Ok(())
+            } else {
+                self.write_str(">")
+            }
+        } else {
+            self.write_str("<")?;
+            self.separated_list(b'E', ", ", Self::generic_arg)?;
+            self.write_str(">")
+        }
+    }
+
+    /// Parses items with `f` until `terminator`, separating them with `separator`. Returns the number of items.
+    fn separated_list(
+        &mut self,
+        terminator: u8,
+        separator: &str,
+        mut f: impl FnMut(&mut Self) -> Result<(), DemangleError>,
+    ) -> Result<usize, DemangleError> {
+        let mut count = 0;
+
+        while !self.eat(terminator) {
+            if count != 0 {
+                self.write_str(separator)?;
+            }
+
+            f(self)?;
+
+            count += 1;
+        }
+
+        Ok(count)
+    }
+
+    fn generic_arg(&mut self) -> Result<(), DemangleError> {
+        if self.eat(b'L') {
+            let lifetime = self.base62()?;
+
+            self.write_lifetime(lifetime)
+        } else if self.eat(b'K') {
+            self.const_(false, false)
+        } else {
+            self.type_()
+        }
+    }
+
+    fn type_(&mut self) -> Result<(), DemangleError> {
+        self.node(Kind::Type, |this, start| {
+            let tag = this.peek().ok_or(DemangleError::Invalid)?;
+
+            if let Some(basic_type) = BasicType::from_tag(tag) {
+                this.position += 1;
+
+                return this.write(|out| display::write_basic_type(basic_type, out));
+            }
+
+            if matches!(tag, b'C' | b'M' | b'X' | b'Y' | b'N' | b'I') {
+                return this.path(false);
+            }
+
+            this.position += 1;
+
+            match tag {
+                b'A' => {
+                    this.write_str("[")?;
+                    this.type_()?;
+                    this.write_str("; ")?;
+                    this.const_(true, false)?;
+                    this.write_str("]")
+                }
+                b'S' => {
+                    this.write_str("[")?;
+                    this.type_()?;
+                    this.write_str("]")
+                }
+                b'T' => {
+                    this.write_str("(")?;
+
+                    if this.separated_list(b'E', ", ", Self::type_)? == 1 {
+                        this.write_str(",")?;
+                    }
+
+                    this.write_str(")")
+                }
+                b'R' | b'Q' => {
+                    let lifetime = if this.eat(b'L') { this.base62()? } else { 0 };
+
+                    this.write_str("&")?;
+
+                    if lifetime != 0 && this.style != Style::Debuginfo {
+                        this.write_lifetime(lifetime)?;
+                        this.write_str(" ")?;
+                    }
+
+                    if tag == b'Q' {
+                        this.write_str("mut ")?;
+                    }
+
+                    this.type_()
+                }
+                b'P' => {
+                    this.write_str("*const ")?;
+                    this.type_()
+                }
+                b'O' => {
+                    this.write_str("*mut ")?;
+                    this.type_()
+                }
+                b'F' => this.fn_sig(),
+                b'D' => {
+                    this.dyn_bounds()?;
+                    this.expect(b'L')?;
+
+                    let lifetime = this.base62()?;
+
+                    if lifetime == 0 || this.style == Style::Debuginfo {
+                        Ok(())
+                    } else {
+                        this.write_str(" + ")?;
+                        this.write_lifetime(lifetime)
+                    }
+                }
+                b'B' => this.type_back_ref(start),
+                _ => Err(DemangleError::Invalid),
+            }
+        })
+    }
+
+    /// A back reference in a type refers to a path if the AST parser would parse it as a path, which is tried before
+    /// the back reference to a type.
+    fn type_back_ref(&mut self, start: usize) -> Result<(), DemangleError> {
+        let target = self.back_ref_index()?;
+        let can_nest_path = self.depth < ParseOptions::default().max_depth;
+
+        if let Some(nodes) = &mut self.nodes {
+            if target >= start {
+                return Err(DemangleError::Invalid);
+            }
+
+            let path_height = nodes.paths.get(target).filter(|_| can_nest_path);
+            let type_height = nodes.types.get(target);
+
+            // The path is nested in the type, one level deeper.
+            if self.expand_back_ref(self.depth + 1, path_height) {
+                if let (Some(nodes), Some(height)) = (&mut self.nodes, path_height) {
+                    nodes.paths.insert(start, height);
+                }
+            } else if !self.expand_back_ref(self.depth, type_height) {
+                return Err(DemangleError::Invalid);
+            }
+
+            Ok(())
+        } else if self.is_printing() {
+            self.at(target, Self::type_)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn with_binder(&mut self, f: impl FnOnce(&mut Self) -> Result<(), DemangleError>) -> Result<(), DemangleError> {
+        let bound_lifetimes = self.opt_base62(b'G')?;
+        let bound_lifetime_depth = self.bound_lifetime_depth;
+
+        self.write_binder(bound_lifetimes)?;
+        self.bound_lifetime_depth = bound_lifetime_depth.saturating_add(bound_lifetimes);
+
+        let result = f(self);
+
+        self.bound_lifetime_depth = bound_lifetime_depth;
+
+        result
+    }
+
+    fn fn_sig(&mut self) -> Result<(), DemangleError> {
+        self.with_binder(|this| {
+            if this.eat(b'U') {
+                this.write_str("unsafe ")?;
+            }
+
+            if this.eat(b'K') {
+                let abi = this.abi()?;
+
+                this.write(|out| {
+                    out.write_str("extern ")?;
+                    display::write_abi(&abi, out)?;
+                    out.write_str(" ")
+                })?;
+            }
+
+            this.write_str("fn(")?;
+            this.separated_list(b'E', ", ", Self::type_)?;
+            this.write_str(")")?;
+
+            if this.is_printing() && this.resolve_tag(this.position)? == Some(b'u') {
+                this.skipping(Self::type_)
+            } else {
+                this.write_str(" -> ")?;
+                this.type_()
+            }
+        })
+    }
+
+    fn abi(&mut self) -> Result<Abi<'a>, DemangleError> {
+        if self.eat(b'C') {
+            Ok(Abi::C)
+        } else {
+            let name = self.undisambiguated_identifier()?;
+
+            if !name.is_empty() && name.is_ascii() {
+                Ok(Abi::Named(name))
+            } else {
+                Err(DemangleError::Invalid)
+            }
+        }
+    }
+
+    fn dyn_bounds(&mut self) -> Result<(), DemangleError> {
+        self.write_str("dyn ")?;
+        self.with_binder(|this| this.separated_list(b'E', " + ", Self::dyn_trait).map(drop))
+    }
+
+    fn dyn_trait(&mut self) -> Result<(), DemangleError> {
+        let start = self.position;
+
+        if !self.is_printing() {
+            self.path(false)?;
+
+            while self.eat(b'p') {
+                self.undisambiguated_identifier()?;
+                self.type_()?;
+            }
+
+            return Ok(());
+        }
+
+        // Associated type bindings are written into the generic arguments of the trait, so the trait path is printed
+        // after finding out whether there are any.
+        self.skipping(|this| this.path(false))?;
+
+        if self.peek() != Some(b'p') {
+            return self.at(start, |this| this.path(false));
+        }
+
+        let path = self.resolve(start)?;
+
+        let mut has_items = if self.input.as_bytes()[path] == b'I' {
+            self.at(path + 1, |this| {
+                this.path(false)?;
+                this.write_str("<")?;
+                this.separated_list(b'E', ", ", Self::generic_arg)
+            })? != 0
+        } else {
+            self.at(start, |this| this.path(false))?;
+            self.write_str("<")?;
+
+            false
+        };
+
+        while self.eat(b'p') {
+            if has_items {
+                self.write_str(", ")?;
+            }
+
+            let name = self.undisambiguated_identifier()?;
+
+            self.write(|out| write!(out, "{name} = "))?;
+            self.type_()?;
+
+            has_items = true;
+        }
+
+        self.write_str(">")
+    }
+
+    fn integer<T>(&mut self) -> Result<(), DemangleError>
+    where
+        T: CheckedNeg + PrimInt + fmt::Display,
+    {
+        let value = self.const_int::<T>()?;
+        let style = self.style;
+
+        self.write(|out| display::write_integer(value, out, style))
+    }
+
+    /// Parses a constant. `is_str_ref` is `true` if the constant is the target of a reference that is written as a
+    /// string literal.
+    #[allow(clippy::use_debug)] // `Debug` is used for escaping char and string literals.
+    fn const_(&mut self, in_value: bool, is_str_ref: bool) -> Result<(), DemangleError> {
+        self.node(Kind::Const, |this, _| match this.next()? {
+            b'a' => this.integer::<i8>(),
+            b'h' => this.integer::<u8>(),
+            b'i' => this.integer::<isize>(),
+            b'j' => this.integer::<usize>(),
+            b'l' => this.integer::<i32>(),
+            b'm' => this.integer::<u32>(),
+            b'n' => this.integer::<i128>(),
+            b'o' => this.integer::<u128>(),
+            b's' => this.integer::<i16>(),
+            b't' => this.integer::<u16>(),
+            b'x' => this.integer::<i64>(),
+            b'y' => this.integer::<u64>(),
+            b'b' => {
+                let value = match this.const_int::<u8>()? {
+                    0 => false,
+                    1 => true,
+                    _ => return Err(DemangleError::Invalid),
+                };
+
+                this.write(|out| write!(out, "{value}"))
+            }
+            b'c' => {
+                let value = char::try_from(this.const_int::<u32>()?).map_err(|_| DemangleError::Invalid)?;
+
+                this.write(|out| write!(out, "{value:?}"))
+            }
+            b'e' => {
+                let data = this.take_while(|c| matches!(c, b'0'..=b'9' | b'a'..=b'z'));
+
+                this.expect(b'_')?;
+
+                let value = parsers::decode_const_str(data).ok_or(DemangleError::Invalid)?;
+
+                this.write(|out| {
+                    if is_str_ref {
+                        display::write_const_str(&value, out)
+                    } else {
+                        out.write_str(if in_value { "*" } else { "{*" })?;
+                        display::write_const_str(&value, out)?;
+                        out.write_str(if in_value { "" } else { "}" })
+                    }
+                })
+            }
+            b'R' => {
+                if this.is_printing() && this.resolve_tag(this.position)? == Some(b'e') {
+                    this.const_(true, true)
+                } else {
+                    this.write_str(if in_value { "&" } else { "{&" })?;
+                    this.const_(true, false)?;
+                    this.write_str(if in_value { "" } else { "}" })
+                }
+            }
+            b'Q' => {
+                this.write_str(if in_value { "&mut " } else { "{&mut " })?;
+                this.const_(true, false)?;
+                this.write_str(if in_value { "" } else { "}" })
+            }
+            b'A' => {
+                this.write_str(if in_value { "[" } else { "{[" })?;
+                this.separated_list(b'E', ", ", |this| this.const_(true, false))?;
+                this.write_str(if in_value { "]" } else { "]}" })
+            }
+            b'T' => {
+                this.write_str(if in_value { "(" } else { "{(" })?;
+
+                let count = this.separated_list(b'E', ", ", |this| this.const_(true, false))?;
+
+                this.write_str(match (in_value, count == 1) {
+                    (true, false) => ")",
+                    (true, true) => ",)",
+                    (false, false) => ")}",
+                    (false, true) => ",)}",
+                })
+            }
+            b'V' => {
+                this.write_str(if in_value { "" } else { "{" })?;
+                this.path(true)?;
+                this.const_fields()?;
+                this.write_str(if in_value { "" } else { "}" })
+            }
+            b'p' => this.write_str("_"),
+            b'B' => this.back_ref(Kind::Const, |this| this.const_(in_value, is_str_ref)),
+            _ => Err(DemangleError::Invalid),
+        })
+    }
+
+    fn const_fields(&mut self) -> Result<(), DemangleError> {
+        match self.next()? {
+            b'U' => Ok(()),
+            b'T' => {
+                self.write_str("(")?;
+                self.separated_list(b'E', ", ", |this| this.const_(true, false))?;
+                self.write_str(")")
+            }
+            b'S' => {
+                let mut is_empty = true;
+
+                while !self.eat(b'E') {
+                    self.write_str(if is_empty { " { " } else { ", " })?;
+
+                    let identifier = self.identifier()?;
+
+                    self.write(|out| write!(out, "{}: ", identifier.name))?;
+                    self.const_(true, false)?;
+
+                    is_empty = false;
+                }
+
+                // Matches the behavior of `rustc-demangle` for empty structs.
+                self.write_str(if is_empty { " {  }" } else { " }" })
+            }
+            _ => Err(DemangleError::Invalid),
+        }
+    }
+}
+
+pub fn demangle<'a>(input: &'a str, style: Style, out: &mut dyn DemangleWrite) -> Result<&'a str, DemangleError> {
+    let (_, rest) = Symbol::strip_prefix(input).map_err(|_| DemangleError::Invalid)?;
+    let mut checker = Demangler::new(rest, style, None, Some(Nodes::default()));
+
+    checker.symbol()?;
+
+    Demangler::new(rest, style, Some(out), None).symbol()?;
+
+    Ok(&rest[checker.position..])
+}
+
+pub fn parse_path_head(input: &str) -> Result<PathHead<'_>, DemangleError> {
+    let (_, rest) = Symbol::strip_prefix(input).map_err(|_| DemangleError::Invalid)?;
+    let mut checker = Demangler::new(rest, Style::Short, None, Some(Nodes::default()));
+
+    if checker.peek().is_some_and(|c| c.is_ascii_digit()) {
+        checker.decimal::<u64>()?;
+    }
+
+    checker.path_head()
+}
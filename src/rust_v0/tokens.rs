@@ -0,0 +1,292 @@
+//! Displaying symbols as a sequence of classified tokens, for example for syntax highlighting.
+
+use crate::rust_v0::{
+    BasicType, Const, DemangleWrite, DisplayStyle, GenericArg, Identifier, Namespace, Renderer, Symbol,
+};
+use std::cell::Cell;
+use std::fmt;
+
+/// The kind of a token produced by [`Symbol::render_tokens`].
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[non_exhaustive]
+pub enum TokenKind {
+    /// Punctuation, keywords like `dyn`, `unsafe` and `as`, and binders like `for<'a>`.
+    Syntax,
+    /// The name of a crate, including its hash with [`DisplayStyle::Long`].
+    CrateRoot,
+    /// An identifier of a path item, or a field name of a constant struct.
+    Identifier,
+    /// An identifier in a special namespace, like `{closure#0}`.
+    SpecialIdentifier,
+    /// A basic type, like `u8` or `str`.
+    BasicType,
+    /// A lifetime, like `'a`.
+    Lifetime,
+    /// A constant value, like `1` or `'x'`, including its type suffix with [`DisplayStyle::Long`]. Crate roots and
+    /// identifiers in constant structs are reported as their own tokens, and the punctuation between them is part of
+    /// the constant.
+    Const,
+}
+
+/// Receives the tokens of a displayed symbol, see [`Symbol::render_tokens`].
+pub trait TokenSink {
+    /// Receives the text of a token.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error to stop displaying the symbol.
+    fn write_token(&mut self, kind: TokenKind, text: &str) -> fmt::Result;
+}
+
+impl<F: FnMut(TokenKind, &str) -> fmt::Result> TokenSink for F {
+    fn write_token(&mut self, kind: TokenKind, text: &str) -> fmt::Result {
+        self(kind, text)
+    }
+}
+
+/// Collects the output into a token until the kind of the output changes.
+struct TokenWriter<'s, S: ?Sized> {
+    sink: &'s mut S,
+    kind: &'s Cell<TokenKind>,
+    token_kind: TokenKind,
+    token: String,
+}
+
+impl<S: TokenSink + ?Sized> TokenWriter<'_, S> {
+    fn flush(&mut self) -> fmt::Result {
+        if self.token.is_empty() {
+            Ok(())
+        } else {
+            let result = self.sink.write_token(self.token_kind, &self.token);
+
+            self.token.clear();
+
+            result
+        }
+    }
+}
+
+impl<S: TokenSink + ?Sized> DemangleWrite for TokenWriter<'_, S> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let kind = self.kind.get();
+
+        if kind != self.token_kind {
+            self.flush()?;
+            self.token_kind = kind;
+        }
+
+        self.token.push_str(s);
+
+        Ok(())
+    }
+}
+
+/// Sets the token kind while the nodes it customizes are written.
+struct TokenRenderer<'c> {
+    kind: &'c Cell<TokenKind>,
+}
+
+impl TokenRenderer<'_> {
+    fn with_kind(kind_cell: &Cell<TokenKind>, kind: TokenKind, f: impl FnOnce() -> fmt::Result) -> fmt::Result {
+        let previous = kind_cell.replace(kind);
+        let result = f();
+
+        kind_cell.set(previous);
+
+        result
+    }
+}
+
+impl Renderer for TokenRenderer<'_> {
+    fn render_crate_root(
+        &mut self,
+        identifier: &Identifier,
+        out: &mut dyn DemangleWrite,
+        style: DisplayStyle,
+    ) -> fmt::Result {
+        Self::with_kind(self.kind, TokenKind::CrateRoot, || {
+            super::display::write_crate_root(identifier, out, style)
+        })
+    }
+
+    fn render_identifier(
+        &mut self,
+        identifier: &Identifier,
+        out: &mut dyn DemangleWrite,
+        _: DisplayStyle,
+    ) -> fmt::Result {
+        Self::with_kind(self.kind, TokenKind::Identifier, || {
+            write!(out, "{}", identifier.display())
+        })
+    }
+
+    fn render_special_identifier(
+        &mut self,
+        namespace: Namespace,
+        identifier: &Identifier,
+        out: &mut dyn DemangleWrite,
+        _: DisplayStyle,
+    ) -> fmt::Result {
+        Self::with_kind(self.kind, TokenKind::SpecialIdentifier, || {
+            super::display::write_special_identifier(namespace, identifier, out)
+        })
+    }
+
+    fn render_generic_args(
+        &mut self,
+        generic_args: &[GenericArg],
+        out: &mut dyn DemangleWrite,
+        style: DisplayStyle,
+        bound_lifetime_depth: u64,
+    ) -> fmt::Result {
+        let kind = self.kind;
+
+        Self::with_kind(kind, TokenKind::Syntax, || {
+            super::display::write_generic_args(generic_args, out, self, style, bound_lifetime_depth)
+        })
+    }
+
+    fn render_const(
+        &mut self,
+        const_: &Const,
+        out: &mut dyn DemangleWrite,
+        style: DisplayStyle,
+        bound_lifetime_depth: u64,
+        in_value: bool,
+    ) -> fmt::Result {
+        let kind = self.kind;
+
+        Self::with_kind(kind, TokenKind::Const, || {
+            super::display::write_const(const_, out, self, style, bound_lifetime_depth, in_value)
+        })
+    }
+
+    fn render_lifetime(
+        &mut self,
+        lifetime: u64,
+        out: &mut dyn DemangleWrite,
+        bound_lifetime_depth: u64,
+    ) -> fmt::Result {
+        Self::with_kind(self.kind, TokenKind::Lifetime, || {
+            super::display::write_lifetime(lifetime, out, bound_lifetime_depth)
+        })
+    }
+
+    fn render_basic_type(
+        &mut self,
+        basic_type: BasicType,
+        out: &mut dyn DemangleWrite,
+        _: DisplayStyle,
+    ) -> fmt::Result {
+        Self::with_kind(self.kind, TokenKind::BasicType, || {
+            super::display::write_basic_type(basic_type, out)
+        })
+    }
+}
+
+pub fn render_symbol_tokens(symbol: &Symbol, sink: &mut (impl TokenSink + ?Sized), style: DisplayStyle) -> fmt::Result {
+    let kind = Cell::new(TokenKind::Syntax);
+
+    let mut writer = TokenWriter {
+        sink,
+        kind: &kind,
+        token_kind: TokenKind::Syntax,
+        token: String::new(),
+    };
+
+    symbol.render(&mut writer, &mut TokenRenderer { kind: &kind }, style)?;
+
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TokenKind;
+    use crate::rust_v0::{DisplayStyle, Symbol};
+    use std::fmt;
+
+    fn tokens(name: &str, style: DisplayStyle) -> Vec<(TokenKind, String)> {
+        let (symbol, _) = Symbol::parse_from_str(name).unwrap();
+        let mut tokens = Vec::new();
+
+        symbol
+            .render_tokens(
+                &mut |kind, text: &str| {
+                    tokens.push((kind, text.to_string()));
+
+                    Ok(())
+                },
+                style,
+            )
+            .unwrap();
+
+        assert_eq!(
+            tokens.iter().map(|(_, text)| text.as_str()).collect::<String>(),
+            symbol.display(style).to_string()
+        );
+
+        tokens
+    }
+
+    #[test]
+    fn test_render_tokens() {
+        use TokenKind::{BasicType, Const, CrateRoot, Identifier, SpecialIdentifier, Syntax};
+
+        let expected = [
+            (CrateRoot, "foo[45f]"),
+            (Syntax, "::"),
+            (Identifier, "bar"),
+            (Syntax, "::"),
+            (SpecialIdentifier, "{closure#0}"),
+            (Syntax, "::"),
+            (Identifier, "baz"),
+            (Syntax, "::<&"),
+            (BasicType, "u8"),
+            (Syntax, ", "),
+            (Const, "1usize"),
+            (Syntax, ">"),
+        ];
+
+        assert_eq!(
+            tokens("_RINvNCNvCsi1_3foo3bar03bazRhKj1_E", DisplayStyle::Long),
+            expected.map(|(kind, text)| (kind, text.to_string()))
+        );
+
+        assert_eq!(
+            tokens("_RINvC3foo3barKVNtC3foo3BazS1xb1_EE", DisplayStyle::Normal),
+            [
+                (CrateRoot, "foo"),
+                (Syntax, "::"),
+                (Identifier, "bar"),
+                (Syntax, "::<"),
+                (Const, "{"),
+                (CrateRoot, "foo"),
+                (Const, "::"),
+                (Identifier, "Baz"),
+                (Const, " { "),
+                (Identifier, "x"),
+                (Const, ": true }}"),
+                (Syntax, ">"),
+            ]
+            .map(|(kind, text)| (kind, text.to_string()))
+        );
+    }
+
+    #[test]
+    fn test_render_tokens_error() {
+        let (symbol, _) = Symbol::parse_from_str("_RNvC3foo3bar").unwrap();
+        let mut count = 0;
+
+        let result = symbol.render_tokens(
+            &mut |_, _: &str| {
+                count += 1;
+
+                Err(fmt::Error)
+            },
+            DisplayStyle::Normal,
+        );
+
+        assert_eq!(result, Err(fmt::Error));
+        assert_eq!(count, 1);
+    }
+}
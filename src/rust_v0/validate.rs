@@ -0,0 +1,241 @@
+//! Checking constraints of the Rust v0 mangling scheme that the AST does not enforce.
+
+use crate::rust_v0::{
+    self, lifetimes, Abi, Const, DynTrait, FnSig, Identifier, InvalidIdentifierError, LifetimeBinding, Namespace,
+    NodeRc, Path, ResolvedLifetime, Symbol, Type, Visitor,
+};
+use std::borrow::Cow;
+use std::collections::HashSet;
+
+/// A constraint of the Rust v0 mangling scheme that a symbol violates, see [`Symbol::validate`].
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[non_exhaustive]
+pub enum Violation<'a> {
+    /// A [`Namespace::Other`] namespace that is not an ASCII letter, or that is a letter with its own [`Namespace`]
+    /// variant.
+    InvalidNamespace(char),
+    /// An identifier whose name is not a valid Rust identifier. The identifiers of crate roots, constant struct fields
+    /// and associated type bindings must be valid, and the identifiers of nested paths may also be empty.
+    InvalidIdentifier {
+        name: Cow<'a, str>,
+        error: InvalidIdentifierError,
+    },
+    /// An ABI name that is empty or contains characters other than ASCII alphanumeric characters and `_`. rustc writes
+    /// the `-` in ABI names like `C-unwind` as `_`.
+    InvalidAbi(Cow<'a, str>),
+    /// An array length that is neither a `usize` constant nor a placeholder.
+    InvalidArrayLength(NodeRc<Const<'a>>),
+    /// An instantiating crate that is not a crate root.
+    InvalidInstantiatingCrate(NodeRc<Path<'a>>),
+    /// A lifetime that refers past the outermost binder.
+    UnboundLifetime(ResolvedLifetime<'a>),
+}
+
+/// Collects violations, visiting every shared subtree once.
+#[derive(Default)]
+struct Validator<'a> {
+    visited: HashSet<usize>,
+    violations: Vec<Violation<'a>>,
+}
+
+impl Validator<'_> {
+    /// Shared subtrees are only visited once, so back references can not cause exponential work.
+    fn first_visit<T>(&mut self, node: &NodeRc<T>) -> bool {
+        self.visited.insert(NodeRc::as_ptr(node).cast::<()>() as usize)
+    }
+
+    /// Checks that `name` is a valid Rust identifier, or if it is Punycode that is kept undecoded, that it only contains
+    /// the characters of Punycode encodings.
+    fn check_name(&mut self, name: &str, is_undecoded_punycode: bool, allow_empty: bool) {
+        if allow_empty && name.is_empty() {
+            return;
+        }
+
+        let result = if !is_undecoded_punycode {
+            Identifier::new(0, name).map(drop)
+        } else if name.is_empty() {
+            Err(InvalidIdentifierError::Empty)
+        } else if let Some(c) = name.chars().find(|&c| !(c.is_ascii_alphanumeric() || c == '_')) {
+            Err(InvalidIdentifierError::InvalidCharacter(c))
+        } else {
+            Ok(())
+        };
+
+        if let Err(error) = result {
+            self.violations.push(Violation::InvalidIdentifier {
+                name: Cow::Owned(name.to_string()),
+                error,
+            });
+        }
+    }
+}
+
+impl<'a> Visitor<'a> for Validator<'a> {
+    fn visit_path(&mut self, path: &NodeRc<Path<'a>>) {
+        if self.first_visit(path) {
+            match path.as_ref() {
+                Path::CrateRoot(identifier) => {
+                    self.check_name(&identifier.name, identifier.is_undecoded_punycode, false);
+                }
+                Path::Nested {
+                    namespace,
+                    path,
+                    identifier,
+                } => {
+                    if let Namespace::Other(c) = *namespace {
+                        if Namespace::from_char(c) != Some(*namespace) {
+                            self.violations.push(Violation::InvalidNamespace(c));
+                        }
+                    }
+
+                    self.visit_path(path);
+                    self.check_name(&identifier.name, identifier.is_undecoded_punycode, true);
+                }
+                _ => rust_v0::walk_path(self, path),
+            }
+        }
+    }
+
+    fn visit_type(&mut self, type_: &NodeRc<Type<'a>>) {
+        if self.first_visit(type_) {
+            if let Type::Array(_, length) = type_.as_ref() {
+                if !matches!(length.as_ref(), Const::Usize(_) | Const::Placeholder) {
+                    self.violations
+                        .push(Violation::InvalidArrayLength(NodeRc::clone(length)));
+                }
+            }
+
+            rust_v0::walk_type(self, type_);
+        }
+    }
+
+    fn visit_fn_sig(&mut self, fn_sig: &FnSig<'a>) {
+        if let Some(Abi::Named(name)) = &fn_sig.abi {
+            if name.is_empty() || !name.bytes().all(|c| c.is_ascii_alphanumeric() || c == b'_') {
+                self.violations.push(Violation::InvalidAbi(name.clone()));
+            }
+        }
+
+        rust_v0::walk_fn_sig(self, fn_sig);
+    }
+
+    fn visit_dyn_trait(&mut self, dyn_trait: &DynTrait<'a>) {
+        for binding in &dyn_trait.dyn_trait_assoc_bindings {
+            self.check_name(&binding.name, binding.is_undecoded_punycode, false);
+        }
+
+        rust_v0::walk_dyn_trait(self, dyn_trait);
+    }
+
+    fn visit_const(&mut self, const_: &NodeRc<Const<'a>>) {
+        if self.first_visit(const_) {
+            rust_v0::walk_const(self, const_);
+        }
+    }
+
+    fn visit_identifier(&mut self, identifier: &Identifier<'a>) {
+        // The identifiers of paths are checked without walking them, so these are the field names of constant structs.
+        self.check_name(&identifier.name, identifier.is_undecoded_punycode, false);
+    }
+}
+
+pub fn validate<'a>(symbol: &Symbol<'a>) -> Vec<Violation<'a>> {
+    let mut validator = Validator::default();
+
+    validator.visit_symbol(symbol);
+
+    if let Some(instantiating_crate) = &symbol.instantiating_crate {
+        if !matches!(instantiating_crate.as_ref(), Path::CrateRoot(_)) {
+            validator
+                .violations
+                .push(Violation::InvalidInstantiatingCrate(NodeRc::clone(instantiating_crate)));
+        }
+    }
+
+    validator.violations.extend(
+        lifetimes::resolve_lifetimes(symbol)
+            .into_iter()
+            .filter(|lifetime| lifetime.binding == LifetimeBinding::Unbound)
+            .map(Violation::UnboundLifetime),
+    );
+
+    validator.violations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Violation;
+    use crate::rust_v0::{
+        Abi, BasicType, Const, FnSig, GenericArg, Identifier, InvalidIdentifierError, LifetimeBinding, Namespace,
+        NodeRc, Path, Symbol, Type,
+    };
+    use std::borrow::Cow;
+
+    #[test]
+    fn test_validate() {
+        let (symbol, _) = Symbol::parse_from_str("_RINvNtCs123_3foo3bar3bazAhj3_EB4_").unwrap();
+
+        assert_eq!(symbol.validate(), []);
+
+        let crate_root = NodeRc::new(Path::CrateRoot(Identifier::new_unchecked(0, "foo bar")));
+        let length = NodeRc::new(Const::U8(3));
+        let u8_type = NodeRc::new(Type::Basic(BasicType::U8));
+
+        let symbol = Symbol {
+            version: None,
+            path: NodeRc::new(Path::Generic {
+                path: NodeRc::new(Path::Nested {
+                    namespace: Namespace::Other('1'),
+                    path: NodeRc::clone(&crate_root),
+                    identifier: Identifier::new_unchecked(0, ""),
+                }),
+                generic_args: vec![
+                    GenericArg::Type(NodeRc::new(Type::Array(
+                        NodeRc::clone(&u8_type),
+                        NodeRc::clone(&length),
+                    ))),
+                    GenericArg::Type(NodeRc::new(Type::Fn(FnSig {
+                        bound_lifetimes: 0,
+                        is_unsafe: false,
+                        abi: Some(Abi::Named(Cow::Borrowed("C-unwind"))),
+                        argument_types: vec![NodeRc::new(Type::Ref {
+                            lifetime: 1,
+                            type_: NodeRc::clone(&u8_type),
+                        })],
+                        return_type: NodeRc::new(Type::Tuple(Vec::new())),
+                    }))),
+                ],
+            }),
+            instantiating_crate: Some(NodeRc::new(Path::Nested {
+                namespace: Namespace::Type,
+                path: NodeRc::clone(&crate_root),
+                identifier: Identifier::new_unchecked(0, "qux"),
+            })),
+            vendor_specific_suffix: None,
+        };
+
+        let violations = symbol.validate();
+
+        assert_eq!(violations.len(), 6);
+
+        assert_eq!(
+            violations[..5],
+            [
+                Violation::InvalidNamespace('1'),
+                Violation::InvalidIdentifier {
+                    name: Cow::Borrowed("foo bar"),
+                    error: InvalidIdentifierError::InvalidCharacter(' ')
+                },
+                Violation::InvalidArrayLength(length),
+                Violation::InvalidAbi(Cow::Borrowed("C-unwind")),
+                Violation::InvalidInstantiatingCrate(NodeRc::clone(symbol.instantiating_crate.as_ref().unwrap())),
+            ]
+        );
+
+        let Violation::UnboundLifetime(lifetime) = &violations[5] else {
+            panic!();
+        };
+
+        assert_eq!(lifetime.binding, LifetimeBinding::Unbound);
+    }
+}
@@ -0,0 +1,243 @@
+//! Structured vendor specific suffixes, like `.llvm.1234` or `.constprop.0`.
+
+use crate::rust_v0::parsers;
+use std::fmt::{self, Display, Formatter};
+
+/// Suffixes that optimizers append to the names of the copies they make of a function, like `.constprop.0`.
+const CLONE_KINDS: &[&str] = &[
+    "clone",
+    "constprop",
+    "isra",
+    "localalias",
+    "lto_priv",
+    "part",
+    "specialized",
+];
+
+/// A component of a [`VendorSuffix`].
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum VendorSuffixComponent<'a> {
+    /// `.llvm.<hash>`, added by LLVM to local symbols promoted to global ones during thin LTO, where the hash identifies
+    /// the module the symbol comes from.
+    LlvmHash(&'a str),
+    /// `.cold` or `.cold.<counter>`, marking the cold part of a function split by hot/cold splitting.
+    Cold(Option<u64>),
+    /// `.<kind>` or `.<kind>.<counter>`, marking a copy of a function made by an optimization like constant
+    /// propagation (`constprop`), scalar replacement of aggregates (`isra`), partial inlining (`part`) or function
+    /// specialization (`specialized`).
+    Clone { kind: &'a str, counter: Option<u64> },
+    /// `.<counter>`, used by LLVM and linkers to make duplicate names unique.
+    Counter(u64),
+    /// `$<hash>`, appended by wasm toolchains, see [`Symbol::wasm_hash`](super::Symbol::wasm_hash).
+    WasmHash(&'a str),
+    /// A part of the suffix that is not recognized, including its leading `.`, if any.
+    Other(&'a str),
+}
+
+impl Display for VendorSuffixComponent<'_> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match *self {
+            Self::LlvmHash(hash) => write!(f, ".llvm.{hash}"),
+            Self::Cold(counter) => {
+                f.write_str(".cold")?;
+
+                counter.map_or(Ok(()), |counter| write!(f, ".{counter}"))
+            }
+            Self::Clone { kind, counter } => {
+                write!(f, ".{kind}")?;
+
+                counter.map_or(Ok(()), |counter| write!(f, ".{counter}"))
+            }
+            Self::Counter(counter) => write!(f, ".{counter}"),
+            Self::WasmHash(hash) => write!(f, "${hash}"),
+            Self::Other(text) => f.write_str(text),
+        }
+    }
+}
+
+/// A vendor specific suffix split into its components, so that copies of a function made by optimizers can be told
+/// apart from, or grouped with, the function itself. Displaying a `VendorSuffix` gives back the original suffix.
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct VendorSuffix<'a> {
+    pub components: Vec<VendorSuffixComponent<'a>>,
+}
+
+/// Parses a decimal counter, rejecting leading zeros so that the counter displays as the original text.
+fn parse_counter(text: &str) -> Option<u64> {
+    (!text.is_empty() && text.bytes().all(|c| c.is_ascii_digit()) && (text == "0" || !text.starts_with('0')))
+        .then(|| text.parse().ok())
+        .flatten()
+}
+
+impl<'a> VendorSuffix<'a> {
+    /// Splits `suffix` into components. Unrecognized parts become [`VendorSuffixComponent::Other`], so parsing never
+    /// fails.
+    #[must_use]
+    pub fn parse(suffix: &'a str) -> Self {
+        let (rest, wasm_hash) =
+            parsers::split_wasm_hash(suffix).map_or((suffix, None), |(rest, hash)| (rest, Some(hash)));
+        let mut components = Vec::new();
+
+        // Text before the first `.`, which only exists if the suffix starts with `$`.
+        let leading_length = rest.find('.').unwrap_or(rest.len());
+
+        if leading_length != 0 {
+            components.push(VendorSuffixComponent::Other(&rest[..leading_length]));
+        }
+
+        // Each segment is paired with its text including the leading `.`.
+        let tail = &rest[leading_length..];
+        let mut segments = Vec::new();
+        let mut offset = 0;
+
+        for segment in tail.split('.').skip(1) {
+            let end = offset + 1 + segment.len();
+
+            segments.push((&tail[offset..end], segment));
+            offset = end;
+        }
+
+        let mut i = 0;
+
+        while let Some(&(text, segment)) = segments.get(i) {
+            let next = segments.get(i + 1).map(|&(_, next)| next);
+            let counter = next.and_then(parse_counter);
+            let counter_length = usize::from(counter.is_some());
+
+            let (component, length) = match (segment, next) {
+                ("llvm", Some(hash)) => (VendorSuffixComponent::LlvmHash(hash), 2),
+                ("cold", _) => (VendorSuffixComponent::Cold(counter), 1 + counter_length),
+                (kind, _) if CLONE_KINDS.contains(&kind) => {
+                    (VendorSuffixComponent::Clone { kind, counter }, 1 + counter_length)
+                }
+                _ => (
+                    parse_counter(segment).map_or(VendorSuffixComponent::Other(text), VendorSuffixComponent::Counter),
+                    1,
+                ),
+            };
+
+            components.push(component);
+            i += length;
+        }
+
+        components.extend(wasm_hash.map(VendorSuffixComponent::WasmHash));
+
+        Self { components }
+    }
+
+    /// Returns the thin LTO module hash from the `.llvm.<hash>` component, if there is one.
+    #[must_use]
+    pub fn llvm_hash(&self) -> Option<&'a str> {
+        self.components.iter().find_map(|component| match *component {
+            VendorSuffixComponent::LlvmHash(hash) => Some(hash),
+            _ => None,
+        })
+    }
+
+    /// Returns `true` if the symbol is the cold part of a function split by hot/cold splitting.
+    #[must_use]
+    pub fn is_cold(&self) -> bool {
+        self.components
+            .iter()
+            .any(|component| matches!(component, VendorSuffixComponent::Cold(_)))
+    }
+
+    /// Returns `true` if the symbol is a copy of a function made by an optimization, including the cold part of a
+    /// split function. Such symbols can be grouped with the function they are made from, which has the same path.
+    #[must_use]
+    pub fn is_clone(&self) -> bool {
+        self.components.iter().any(|component| {
+            matches!(
+                component,
+                VendorSuffixComponent::Cold(_) | VendorSuffixComponent::Clone { .. }
+            )
+        })
+    }
+}
+
+impl Display for VendorSuffix<'_> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.components.iter().try_for_each(|component| component.fmt(f))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{VendorSuffix, VendorSuffixComponent};
+    use crate::rust_v0::Symbol;
+
+    #[test]
+    fn test_vendor_suffix_parse() {
+        use VendorSuffixComponent::{Clone, Cold, Counter, LlvmHash, Other, WasmHash};
+
+        let test_cases: &[(&str, &[VendorSuffixComponent])] = &[
+            (".llvm.1119170478327948870", &[LlvmHash("1119170478327948870")]),
+            (".llvm.9D1C9369@@16", &[LlvmHash("9D1C9369@@16")]),
+            (".cold", &[Cold(None)]),
+            (".cold.1", &[Cold(Some(1))]),
+            (
+                ".constprop.0.isra.12",
+                &[
+                    Clone {
+                        kind: "constprop",
+                        counter: Some(0),
+                    },
+                    Clone {
+                        kind: "isra",
+                        counter: Some(12),
+                    },
+                ],
+            ),
+            (
+                ".part.0.cold",
+                &[
+                    Clone {
+                        kind: "part",
+                        counter: Some(0),
+                    },
+                    Cold(None),
+                ],
+            ),
+            (
+                ".localalias",
+                &[Clone {
+                    kind: "localalias",
+                    counter: None,
+                }],
+            ),
+            (".0.0", &[Counter(0), Counter(0)]),
+            (".llvm.123.cold$e0f1", &[LlvmHash("123"), Cold(None), WasmHash("e0f1")]),
+            ("$e0f1", &[WasmHash("e0f1")]),
+            (".llvm", &[Other(".llvm")]),
+            (".cold.01", &[Cold(None), Other(".01")]),
+            (".foo..bar", &[Other(".foo"), Other("."), Other(".bar")]),
+            ("$foo.bar", &[Other("$foo"), Other(".bar")]),
+            (".", &[Other(".")]),
+        ];
+
+        for (suffix, expected) in test_cases {
+            let vendor_suffix = VendorSuffix::parse(suffix);
+
+            assert_eq!(vendor_suffix.components, *expected, "{suffix}");
+            assert_eq!(vendor_suffix.to_string(), *suffix);
+        }
+    }
+
+    #[test]
+    fn test_symbol_vendor_suffix() {
+        let (symbol, _) = Symbol::parse_from_str("_RNvC3foo3bar.constprop.0.llvm.123").unwrap();
+        let vendor_suffix = symbol.vendor_suffix().unwrap();
+
+        assert_eq!(vendor_suffix.llvm_hash(), Some("123"));
+        assert!(vendor_suffix.is_clone());
+        assert!(!vendor_suffix.is_cold());
+
+        let (symbol, _) = Symbol::parse_from_str("_RNvC3foo3bar.cold").unwrap();
+
+        assert!(symbol.vendor_suffix().unwrap().is_cold());
+
+        let (symbol, _) = Symbol::parse_from_str("_RNvC3foo3bar").unwrap();
+
+        assert_eq!(symbol.vendor_suffix(), None);
+    }
+}
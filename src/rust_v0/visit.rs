@@ -0,0 +1,261 @@
+//! Walking the AST of a symbol.
+
+use crate::rust_v0::{
+    BasicType, Const, ConstFields, DynBounds, DynTrait, FnSig, GenericArg, Identifier, ImplPath, NodeRc, Path, Symbol,
+    Type,
+};
+
+/// Visits the nodes of a [`Symbol`] AST. Every method forwards to the corresponding `walk_*` function by default, which
+/// visits the children of the node in the order they are displayed, so an implementation only needs to override the
+/// methods for the nodes it is interested in, and calls the `walk_*` function to keep descending.
+///
+/// Nodes shared through back references are visited once for every place they appear in. Since the number of places
+/// can grow exponentially with the length of the mangled name, visitors of untrusted names may want to skip nodes that
+/// are already visited, which can be identified by [`NodeRc::as_ptr`].
+pub trait Visitor<'a> {
+    fn visit_symbol(&mut self, symbol: &Symbol<'a>) {
+        walk_symbol(self, symbol);
+    }
+
+    fn visit_path(&mut self, path: &NodeRc<Path<'a>>) {
+        walk_path(self, path);
+    }
+
+    fn visit_impl_path(&mut self, impl_path: &ImplPath<'a>) {
+        walk_impl_path(self, impl_path);
+    }
+
+    /// Visits an identifier of a path, or a field name of a constant struct.
+    fn visit_identifier(&mut self, identifier: &Identifier<'a>) {
+        let _ = identifier;
+    }
+
+    fn visit_generic_arg(&mut self, generic_arg: &GenericArg<'a>) {
+        walk_generic_arg(self, generic_arg);
+    }
+
+    /// Visits a lifetime, which is 0 for erased lifetimes, or the distance of the binder that binds the lifetime.
+    fn visit_lifetime(&mut self, lifetime: u64) {
+        let _ = lifetime;
+    }
+
+    fn visit_type(&mut self, type_: &NodeRc<Type<'a>>) {
+        walk_type(self, type_);
+    }
+
+    fn visit_basic_type(&mut self, basic_type: BasicType) {
+        let _ = basic_type;
+    }
+
+    fn visit_fn_sig(&mut self, fn_sig: &FnSig<'a>) {
+        walk_fn_sig(self, fn_sig);
+    }
+
+    fn visit_dyn_bounds(&mut self, dyn_bounds: &DynBounds<'a>) {
+        walk_dyn_bounds(self, dyn_bounds);
+    }
+
+    fn visit_dyn_trait(&mut self, dyn_trait: &DynTrait<'a>) {
+        walk_dyn_trait(self, dyn_trait);
+    }
+
+    fn visit_const(&mut self, const_: &NodeRc<Const<'a>>) {
+        walk_const(self, const_);
+    }
+}
+
+/// Visits the path of `symbol`, then its instantiating crate.
+pub fn walk_symbol<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, symbol: &Symbol<'a>) {
+    visitor.visit_path(&symbol.path);
+
+    if let Some(instantiating_crate) = &symbol.instantiating_crate {
+        visitor.visit_path(instantiating_crate);
+    }
+}
+
+pub fn walk_path<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, path: &Path<'a>) {
+    match path {
+        Path::CrateRoot(identifier) => visitor.visit_identifier(identifier),
+        Path::InherentImpl { impl_path, type_ } => {
+            visitor.visit_impl_path(impl_path);
+            visitor.visit_type(type_);
+        }
+        Path::TraitImpl {
+            impl_path,
+            type_,
+            trait_,
+        } => {
+            visitor.visit_impl_path(impl_path);
+            visitor.visit_type(type_);
+            visitor.visit_path(trait_);
+        }
+        Path::TraitDefinition { type_, trait_ } => {
+            visitor.visit_type(type_);
+            visitor.visit_path(trait_);
+        }
+        Path::Nested { path, identifier, .. } => {
+            visitor.visit_path(path);
+            visitor.visit_identifier(identifier);
+        }
+        Path::Generic { path, generic_args } => {
+            visitor.visit_path(path);
+
+            for generic_arg in generic_args {
+                visitor.visit_generic_arg(generic_arg);
+            }
+        }
+    }
+}
+
+pub fn walk_impl_path<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, impl_path: &ImplPath<'a>) {
+    visitor.visit_path(&impl_path.path);
+}
+
+pub fn walk_generic_arg<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, generic_arg: &GenericArg<'a>) {
+    match generic_arg {
+        GenericArg::Lifetime(lifetime) => visitor.visit_lifetime(*lifetime),
+        GenericArg::Type(type_) => visitor.visit_type(type_),
+        GenericArg::Const(const_) => visitor.visit_const(const_),
+    }
+}
+
+pub fn walk_type<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, type_: &Type<'a>) {
+    match type_ {
+        Type::Basic(basic_type) => visitor.visit_basic_type(*basic_type),
+        Type::Named(path) => visitor.visit_path(path),
+        Type::Array(type_, length) => {
+            visitor.visit_type(type_);
+            visitor.visit_const(length);
+        }
+        Type::Slice(type_) | Type::PtrConst(type_) | Type::PtrMut(type_) => visitor.visit_type(type_),
+        Type::Tuple(tuple_types) => {
+            for type_ in tuple_types {
+                visitor.visit_type(type_);
+            }
+        }
+        Type::Ref { lifetime, type_ } | Type::RefMut { lifetime, type_ } => {
+            visitor.visit_lifetime(*lifetime);
+            visitor.visit_type(type_);
+        }
+        Type::Fn(fn_sig) => visitor.visit_fn_sig(fn_sig),
+        Type::DynTrait { dyn_bounds, lifetime } => {
+            visitor.visit_dyn_bounds(dyn_bounds);
+            visitor.visit_lifetime(*lifetime);
+        }
+    }
+}
+
+pub fn walk_fn_sig<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, fn_sig: &FnSig<'a>) {
+    for type_ in &fn_sig.argument_types {
+        visitor.visit_type(type_);
+    }
+
+    visitor.visit_type(&fn_sig.return_type);
+}
+
+pub fn walk_dyn_bounds<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, dyn_bounds: &DynBounds<'a>) {
+    for dyn_trait in &dyn_bounds.dyn_traits {
+        visitor.visit_dyn_trait(dyn_trait);
+    }
+}
+
+pub fn walk_dyn_trait<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, dyn_trait: &DynTrait<'a>) {
+    visitor.visit_path(&dyn_trait.path);
+
+    for binding in &dyn_trait.dyn_trait_assoc_bindings {
+        visitor.visit_type(&binding.type_);
+    }
+}
+
+pub fn walk_const<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, const_: &Const<'a>) {
+    match const_ {
+        Const::Ref(value) | Const::RefMut(value) => visitor.visit_const(value),
+        Const::Array(items) | Const::Tuple(items) => {
+            for item in items {
+                visitor.visit_const(item);
+            }
+        }
+        Const::NamedStruct { path, fields } => {
+            visitor.visit_path(path);
+
+            match fields {
+                ConstFields::Unit => {}
+                ConstFields::Tuple(fields) => {
+                    for field in fields {
+                        visitor.visit_const(field);
+                    }
+                }
+                ConstFields::Struct(fields) => {
+                    for (name, field) in fields {
+                        visitor.visit_identifier(name);
+                        visitor.visit_const(field);
+                    }
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Visitor;
+    use crate::rust_v0::{GenericArg, NodeRc, Path, Symbol};
+
+    #[test]
+    fn test_collect_crate_names() {
+        struct CrateNames(Vec<String>);
+
+        impl<'a> Visitor<'a> for CrateNames {
+            fn visit_path(&mut self, path: &NodeRc<Path<'a>>) {
+                if let Path::CrateRoot(identifier) = path.as_ref() {
+                    self.0.push(identifier.name.to_string());
+                }
+
+                super::walk_path(self, path);
+            }
+        }
+
+        let (symbol, _) =
+            Symbol::parse_from_str("_RINvNtC3std3mem8align_ofINtC3foo3BarNtC3baz3QuxEECs1234_3app").unwrap();
+
+        let mut visitor = CrateNames(Vec::new());
+
+        visitor.visit_symbol(&symbol);
+
+        assert_eq!(visitor.0, ["std", "foo", "baz", "app"]);
+    }
+
+    #[test]
+    fn test_count_generic_args() {
+        #[derive(Default)]
+        struct Counter {
+            generic_args: usize,
+            lifetimes: usize,
+        }
+
+        impl<'a> Visitor<'a> for Counter {
+            fn visit_generic_arg(&mut self, generic_arg: &GenericArg<'a>) {
+                self.generic_args += 1;
+
+                super::walk_generic_arg(self, generic_arg);
+            }
+
+            fn visit_lifetime(&mut self, _lifetime: u64) {
+                self.lifetimes += 1;
+            }
+        }
+
+        // `foo::bar::<&str, [u8; 4], [u8; 4], '_>`, where the second `[u8; 4]` is a back reference.
+        let (symbol, _) = Symbol::parse_from_str("_RINvC3foo3barReAhj4_Bd_L_E").unwrap();
+
+        assert_eq!(symbol.to_string(), "foo::bar::<&str, [u8; 4usize], [u8; 4usize], '_>");
+
+        let mut visitor = Counter::default();
+
+        visitor.visit_symbol(&symbol);
+
+        assert_eq!(visitor.generic_args, 4);
+        assert_eq!(visitor.lifetimes, 2);
+    }
+}
@@ -0,0 +1,349 @@
+//! A uniform interface over mangling schemes, so that symbolication tools can plug in this crate next to their own
+//! demanglers.
+//!
+//! Every supported mangling scheme is a type implementing [`Demangle`], and a [`Registry`] tries a list of schemes in
+//! order, which may include schemes defined outside this crate:
+//!
+//! ```rust
+//! use ast_demangle::rust_v0::DisplayStyle;
+//! use ast_demangle::scheme::{Demangle, DisplaySymbol, Registry};
+//! use ast_demangle::DemangleError;
+//! use std::fmt::{self, Formatter};
+//!
+//! /// Demangles names like `$foo$bar` into `foo::bar`.
+//! struct Dollar;
+//!
+//! struct DollarSymbol<'a>(Vec<&'a str>);
+//!
+//! impl DisplaySymbol for DollarSymbol<'_> {
+//!     fn fmt_with_style(&self, f: &mut Formatter, style: DisplayStyle) -> fmt::Result {
+//!         let path = if style == DisplayStyle::Short { &self.0[self.0.len() - 1..] } else { &self.0[..] };
+//!
+//!         f.write_str(&path.join("::"))
+//!     }
+//! }
+//!
+//! impl Demangle for Dollar {
+//!     type Output<'a> = DollarSymbol<'a>;
+//!
+//!     fn demangle<'a>(&self, input: &'a str) -> Result<(DollarSymbol<'a>, &'a str), DemangleError> {
+//!         let path = input.strip_prefix('$').ok_or(DemangleError)?;
+//!
+//!         Ok((DollarSymbol(path.split('$').collect()), ""))
+//!     }
+//! }
+//!
+//! let mut registry = Registry::default();
+//!
+//! registry.push(Dollar);
+//!
+//! let (symbol, _) = registry.demangle("_RNvCs123_3foo3bar").unwrap();
+//!
+//! assert_eq!(symbol.scheme(), 0);
+//! assert_eq!(symbol.to_string(), "foo[f85]::bar");
+//!
+//! let (symbol, _) = registry.demangle("$foo$bar").unwrap();
+//!
+//! assert_eq!(symbol.scheme(), 5);
+//! assert_eq!(symbol.display(DisplayStyle::Short).to_string(), "bar");
+//! ```
+
+use crate::rust_v0::DisplayStyle;
+use crate::{cxx_itanium, msvc, rust_legacy, rust_v0, swift, DemangleError};
+use std::fmt::{self, Display, Formatter};
+
+/// A demangled symbol that can be displayed with a [`DisplayStyle`].
+pub trait DisplaySymbol {
+    /// Writes the symbol displayed with `style` into `f`. Schemes are free to treat styles they have no use for like
+    /// the closest style they support.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `f` fails.
+    fn fmt_with_style(&self, f: &mut Formatter, style: DisplayStyle) -> fmt::Result;
+}
+
+/// A mangling scheme.
+pub trait Demangle {
+    /// The symbol a mangled name is parsed into, which may borrow from the mangled name.
+    type Output<'a>: DisplaySymbol + 'a;
+
+    /// Parses a mangled name from the start of `input`, returns the symbol and the suffix of `input` that is not part
+    /// of the mangled name.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DemangleError`] if `input` does not start with a mangled name in this scheme.
+    fn demangle<'a>(&self, input: &'a str) -> Result<(Self::Output<'a>, &'a str), DemangleError>;
+}
+
+/// The [Rust v0](crate::rust_v0) mangling scheme.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct RustV0;
+
+/// The [legacy Rust](crate::rust_legacy) mangling scheme. Names followed by a suffix that does not start with `.` are
+/// rejected, so that C++ names like `_ZN3foo3barEv` are left to [`CxxItanium`], the same as [`demangle`](crate::demangle)
+/// does.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct RustLegacy;
+
+/// The [Itanium C++ ABI](crate::cxx_itanium) mangling scheme.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct CxxItanium;
+
+/// The [Microsoft Visual C++](crate::msvc) mangling scheme.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Msvc;
+
+/// The [Swift](crate::swift) mangling scheme.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Swift;
+
+impl Demangle for RustV0 {
+    type Output<'a> = rust_v0::Symbol<'a>;
+
+    fn demangle<'a>(&self, input: &'a str) -> Result<(Self::Output<'a>, &'a str), DemangleError> {
+        rust_v0::Symbol::parse_from_str(input).map_err(|_| DemangleError)
+    }
+}
+
+impl Demangle for RustLegacy {
+    type Output<'a> = rust_legacy::Symbol<'a>;
+
+    fn demangle<'a>(&self, input: &'a str) -> Result<(Self::Output<'a>, &'a str), DemangleError> {
+        rust_legacy::Symbol::parse_from_str(input)
+            .ok()
+            .filter(|(_, suffix)| suffix.is_empty() || suffix.starts_with('.'))
+            .ok_or(DemangleError)
+    }
+}
+
+impl Demangle for CxxItanium {
+    type Output<'a> = cxx_itanium::Symbol<'a>;
+
+    fn demangle<'a>(&self, input: &'a str) -> Result<(Self::Output<'a>, &'a str), DemangleError> {
+        cxx_itanium::Symbol::parse_from_str(input).map_err(|_| DemangleError)
+    }
+}
+
+impl Demangle for Msvc {
+    type Output<'a> = msvc::Symbol<'a>;
+
+    fn demangle<'a>(&self, input: &'a str) -> Result<(Self::Output<'a>, &'a str), DemangleError> {
+        msvc::Symbol::parse_from_str(input).map_err(|_| DemangleError)
+    }
+}
+
+impl Demangle for Swift {
+    type Output<'a> = swift::Symbol<'a>;
+
+    fn demangle<'a>(&self, input: &'a str) -> Result<(Self::Output<'a>, &'a str), DemangleError> {
+        swift::Symbol::parse_from_str(input).map_err(|_| DemangleError)
+    }
+}
+
+impl DisplaySymbol for rust_v0::Symbol<'_> {
+    fn fmt_with_style(&self, f: &mut Formatter, style: DisplayStyle) -> fmt::Result {
+        self.display(style).fmt(f)
+    }
+}
+
+impl DisplaySymbol for rust_legacy::Symbol<'_> {
+    fn fmt_with_style(&self, f: &mut Formatter, style: DisplayStyle) -> fmt::Result {
+        self.display(style).fmt(f)
+    }
+}
+
+impl DisplaySymbol for cxx_itanium::Symbol<'_> {
+    fn fmt_with_style(&self, f: &mut Formatter, style: DisplayStyle) -> fmt::Result {
+        self.display(style).fmt(f)
+    }
+}
+
+impl DisplaySymbol for msvc::Symbol<'_> {
+    fn fmt_with_style(&self, f: &mut Formatter, style: DisplayStyle) -> fmt::Result {
+        self.display(style).fmt(f)
+    }
+}
+
+impl DisplaySymbol for swift::Symbol<'_> {
+    fn fmt_with_style(&self, f: &mut Formatter, style: DisplayStyle) -> fmt::Result {
+        self.display(style).fmt(f)
+    }
+}
+
+impl DisplaySymbol for crate::Symbol<'_> {
+    fn fmt_with_style(&self, f: &mut Formatter, style: DisplayStyle) -> fmt::Result {
+        self.display(style).fmt(f)
+    }
+}
+
+/// [`Demangle`] with the output type erased, so that schemes with different output types can be stored together.
+trait DynDemangle {
+    fn demangle_dyn<'a>(&self, input: &'a str) -> Result<(Box<dyn DisplaySymbol + 'a>, &'a str), DemangleError>;
+}
+
+impl<D: Demangle> DynDemangle for D {
+    fn demangle_dyn<'a>(&self, input: &'a str) -> Result<(Box<dyn DisplaySymbol + 'a>, &'a str), DemangleError> {
+        let (symbol, suffix) = self.demangle(input)?;
+
+        Ok((Box::new(symbol), suffix))
+    }
+}
+
+/// A symbol demangled by a [`Registry`].
+pub struct Demangled<'a> {
+    scheme: usize,
+    symbol: Box<dyn DisplaySymbol + 'a>,
+}
+
+impl Demangled<'_> {
+    /// Returns the index of the scheme that demangled the symbol, in the order the schemes were added to the registry.
+    #[must_use]
+    pub fn scheme(&self) -> usize {
+        self.scheme
+    }
+
+    /// Returns an object that implements [`Display`] for printing the symbol.
+    #[must_use]
+    pub fn display(&self, style: DisplayStyle) -> impl Display + '_ {
+        struct Wrapper<'s, 'a>(&'s Demangled<'a>, DisplayStyle);
+
+        impl Display for Wrapper<'_, '_> {
+            fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+                self.0.symbol.fmt_with_style(f, self.1)
+            }
+        }
+
+        Wrapper(self, style)
+    }
+}
+
+impl DisplaySymbol for Demangled<'_> {
+    fn fmt_with_style(&self, f: &mut Formatter, style: DisplayStyle) -> fmt::Result {
+        self.symbol.fmt_with_style(f, style)
+    }
+}
+
+impl Display for Demangled<'_> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.symbol.fmt_with_style(
+            f,
+            if f.alternate() {
+                DisplayStyle::Normal
+            } else {
+                DisplayStyle::Long
+            },
+        )
+    }
+}
+
+/// A list of mangling schemes that are tried in order. [`Registry::default`] contains the schemes of this crate in the
+/// order [`demangle`](crate::demangle) tries them: [`RustV0`], [`RustLegacy`], [`CxxItanium`], [`Msvc`] and [`Swift`].
+pub struct Registry {
+    schemes: Vec<Box<dyn DynDemangle>>,
+}
+
+impl Registry {
+    /// Creates a registry without any scheme.
+    #[must_use]
+    pub fn new() -> Self {
+        Self { schemes: Vec::new() }
+    }
+
+    /// Appends `scheme`, which is tried after the schemes already in the registry.
+    pub fn push(&mut self, scheme: impl Demangle + 'static) {
+        self.schemes.push(Box::new(scheme));
+    }
+
+    /// Returns the number of schemes.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.schemes.len()
+    }
+
+    /// Returns `true` if the registry contains no scheme.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.schemes.is_empty()
+    }
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        let mut registry = Self::new();
+
+        registry.push(RustV0);
+        registry.push(RustLegacy);
+        registry.push(CxxItanium);
+        registry.push(Msvc);
+        registry.push(Swift);
+
+        registry
+    }
+}
+
+impl Demangle for Registry {
+    type Output<'a> = Demangled<'a>;
+
+    /// Demangles `input` with the first scheme that accepts it.
+    fn demangle<'a>(&self, input: &'a str) -> Result<(Self::Output<'a>, &'a str), DemangleError> {
+        self.schemes
+            .iter()
+            .enumerate()
+            .find_map(|(scheme, demangler)| {
+                demangler
+                    .demangle_dyn(input)
+                    .ok()
+                    .map(|(symbol, suffix)| (Demangled { scheme, symbol }, suffix))
+            })
+            .ok_or(DemangleError)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Demangle, Registry, RustLegacy, RustV0};
+    use crate::rust_v0::DisplayStyle;
+    use crate::DemangleError;
+
+    #[test]
+    fn test_registry() {
+        let registry = Registry::default();
+
+        for name in [
+            "_RNvCs123_3foo3bar.llvm.123",
+            "_ZN3foo3bar17h05af221e174051e9E.cold",
+            "_ZN3foo3barEv",
+            "?foo@@YAHH@Z",
+            "$s4main3add1a1bS2i_SitF",
+        ] {
+            let (expected, expected_suffix) = crate::demangle(name).unwrap();
+            let (symbol, suffix) = registry.demangle(name).unwrap();
+
+            assert_eq!(suffix, expected_suffix);
+            assert_eq!(symbol.to_string(), expected.to_string());
+            assert_eq!(format!("{symbol:#}"), format!("{expected:#}"));
+
+            assert_eq!(
+                symbol.display(DisplayStyle::Short).to_string(),
+                expected.display(DisplayStyle::Short).to_string()
+            );
+        }
+
+        assert_eq!(registry.demangle("_ZN3foo3barEv").unwrap().0.scheme(), 2);
+        assert!(registry.demangle("main").is_err());
+
+        let mut registry = Registry::new();
+
+        assert!(registry.is_empty());
+        assert!(registry.demangle("_RNvC3foo3bar").is_err());
+
+        registry.push(RustLegacy);
+        registry.push(RustV0);
+
+        assert_eq!(registry.len(), 2);
+        assert_eq!(registry.demangle("_RNvC3foo3bar").unwrap().0.scheme(), 1);
+        assert_eq!(RustLegacy.demangle("_ZN3foo3barEv").err(), Some(DemangleError));
+    }
+}
@@ -0,0 +1,501 @@
+use crate::swift::{
+    Context, DeclName, DisplayStyle, Entity, FunctionSignature, FunctionType, GenericSignature, NominalKind,
+    NominalType, Requirement, Symbol, TupleElement, Type,
+};
+use std::fmt::{self, Write};
+
+// Output follows the formatting of `swift-demangle`, see
+// <https://github.com/swiftlang/swift/blob/main/lib/Demangling/NodePrinter.cpp>.
+
+pub fn write_symbol(symbol: &Symbol, out: &mut dyn Write, style: DisplayStyle) -> fmt::Result {
+    Printer { out, style }.write_entity(&symbol.entity)
+}
+
+struct Printer<'f> {
+    out: &'f mut dyn Write,
+    style: DisplayStyle,
+}
+
+impl Printer<'_> {
+    fn is_long(&self) -> bool {
+        self.style == DisplayStyle::Long
+    }
+
+    fn is_short(&self) -> bool {
+        self.style == DisplayStyle::Short
+    }
+
+    fn write_entity(&mut self, entity: &Entity) -> fmt::Result {
+        match entity {
+            Entity::Function {
+                context,
+                name,
+                signature,
+            } => {
+                self.write_qualifier(context)?;
+                self.write_decl_name(name)?;
+                self.write_signature(signature)
+            }
+            Entity::Variable { context, name, type_ } => {
+                self.write_qualifier(context)?;
+                self.write_decl_name(name)?;
+
+                if self.is_long() {
+                    self.out.write_str(" : ")?;
+                    self.write_type(type_)?;
+                }
+
+                Ok(())
+            }
+            Entity::Subscript { signature, .. } => {
+                self.write_storage(entity)?;
+
+                if self.is_long() {
+                    self.out.write_str(" : ")?;
+                    self.write_function_type(&signature.type_)?;
+                }
+
+                Ok(())
+            }
+            Entity::Accessor { kind, storage } => {
+                self.write_storage(storage)?;
+                write!(self.out, ".{}", kind.as_str())?;
+
+                if self.is_long() {
+                    match storage_entity(storage) {
+                        Entity::Variable { type_, .. } => {
+                            self.out.write_str(" : ")?;
+                            self.write_type(type_)?;
+                        }
+                        Entity::Subscript { signature, .. } => {
+                            self.out.write_str(" : ")?;
+                            self.write_function_type(&signature.type_)?;
+                        }
+                        _ => {}
+                    }
+                }
+
+                Ok(())
+            }
+            Entity::Initializer {
+                context,
+                is_allocating,
+                signature,
+            } => {
+                self.write_qualifier(context)?;
+
+                self.out.write_str(if *is_allocating && is_class_context(context) {
+                    "__allocating_init"
+                } else {
+                    "init"
+                })?;
+
+                self.write_signature(signature)
+            }
+            Entity::Deinitializer {
+                context,
+                is_deallocating,
+            } => {
+                self.write_qualifier(context)?;
+
+                self.out.write_str(if *is_deallocating && is_class_context(context) {
+                    "__deallocating_deinit"
+                } else {
+                    "deinit"
+                })
+            }
+            Entity::Closure {
+                context,
+                is_implicit,
+                index,
+                type_,
+            } => {
+                if *is_implicit {
+                    self.out.write_str("implicit ")?;
+                }
+
+                write!(self.out, "closure #{}", u128::from(*index) + 1)?;
+
+                if self.is_long() {
+                    self.out.write_char(' ')?;
+                    self.write_type(type_)?;
+                }
+
+                self.out.write_str(" in ")?;
+                self.write_context(context)
+            }
+            Entity::Static(entity) => {
+                if !self.is_short() {
+                    self.out.write_str("static ")?;
+                }
+
+                self.write_entity(entity)
+            }
+        }
+    }
+
+    /// Writes the name of a variable or a subscript without its type.
+    fn write_storage(&mut self, storage: &Entity) -> fmt::Result {
+        match storage {
+            Entity::Variable { context, name, .. } => {
+                self.write_qualifier(context)?;
+                self.write_decl_name(name)
+            }
+            Entity::Subscript { context, .. } => {
+                self.write_qualifier(context)?;
+                self.out.write_str("subscript")
+            }
+            Entity::Static(storage) => {
+                if !self.is_short() {
+                    self.out.write_str("static ")?;
+                }
+
+                self.write_storage(storage)
+            }
+            _ => self.write_entity(storage),
+        }
+    }
+
+    /// Writes the context of a declaration followed by `.`, which is omitted with [`DisplayStyle::Short`].
+    fn write_qualifier(&mut self, context: &Context) -> fmt::Result {
+        if self.is_short() {
+            Ok(())
+        } else {
+            self.write_context(context)?;
+            self.out.write_char('.')
+        }
+    }
+
+    fn write_context(&mut self, context: &Context) -> fmt::Result {
+        match context {
+            Context::Module(name) => self.out.write_str(name),
+            Context::Nominal(type_) => self.write_nominal_type(type_),
+            Context::Extension { module, extended } => {
+                if self.is_long() {
+                    write!(self.out, "(extension in {module}):")?;
+                }
+
+                self.write_nominal_type(extended)
+            }
+            Context::Entity(entity) => self.write_entity(entity),
+        }
+    }
+
+    fn write_decl_name(&mut self, name: &DeclName) -> fmt::Result {
+        match &name.private_discriminator {
+            Some(discriminator) if self.is_long() => write!(self.out, "({} in {discriminator})", name.name),
+            _ => self.out.write_str(&name.name),
+        }
+    }
+
+    fn write_nominal_type(&mut self, type_: &NominalType) -> fmt::Result {
+        self.write_qualifier(&type_.context)?;
+        self.write_decl_name(&type_.name)
+    }
+
+    fn write_signature(&mut self, signature: &FunctionSignature) -> fmt::Result {
+        let labels = signature.labels.as_deref();
+
+        if self.is_short() {
+            self.out.write_char('(')?;
+
+            for i in 0..signature.type_.parameters.len() {
+                let label = labels.and_then(|labels| labels.get(i)).and_then(Option::as_deref);
+
+                write!(self.out, "{}:", label.unwrap_or("_"))?;
+            }
+
+            return self.out.write_char(')');
+        }
+
+        if self.is_long() {
+            if let Some(generic_signature) = &signature.generic_signature {
+                self.write_generic_signature(generic_signature)?;
+            }
+        }
+
+        self.out.write_char('(')?;
+
+        for (i, parameter) in signature.type_.parameters.iter().enumerate() {
+            if i != 0 {
+                self.out.write_str(", ")?;
+            }
+
+            if let Some(labels) = labels {
+                write!(
+                    self.out,
+                    "{}: ",
+                    labels.get(i).and_then(Option::as_deref).unwrap_or("_")
+                )?;
+            }
+
+            self.write_parameter(parameter)?;
+        }
+
+        self.out.write_char(')')?;
+
+        if self.is_long() {
+            self.write_effects_and_result(&signature.type_)?;
+        }
+
+        Ok(())
+    }
+
+    fn write_generic_signature(&mut self, signature: &GenericSignature) -> fmt::Result {
+        self.out.write_char('<')?;
+
+        let mut is_first_group = true;
+
+        for (depth, &count) in (0..).zip(&signature.param_counts) {
+            if count == 0 {
+                continue;
+            }
+
+            if !is_first_group {
+                self.out.write_str("><")?;
+            }
+
+            is_first_group = false;
+
+            for index in 0..count {
+                if index != 0 {
+                    self.out.write_str(", ")?;
+                }
+
+                write_generic_param_name(self.out, depth, index)?;
+            }
+        }
+
+        for (i, requirement) in signature.requirements.iter().enumerate() {
+            self.out.write_str(if i == 0 { " where " } else { ", " })?;
+
+            match requirement {
+                Requirement::Conformance { type_, protocol } => {
+                    self.write_type(type_)?;
+                    self.out.write_str(": ")?;
+                    self.write_nominal_type(protocol)?;
+                }
+            }
+        }
+
+        self.out.write_char('>')
+    }
+
+    fn write_parameter(&mut self, parameter: &TupleElement) -> fmt::Result {
+        if let Type::Function(FunctionType { is_escaping: true, .. }) = parameter.type_.as_ref() {
+            self.out.write_str("@escaping ")?;
+        }
+
+        self.write_type(&parameter.type_)?;
+
+        if parameter.is_variadic {
+            self.out.write_str("...")?;
+        }
+
+        Ok(())
+    }
+
+    fn write_effects_and_result(&mut self, type_: &FunctionType) -> fmt::Result {
+        if type_.is_async {
+            self.out.write_str(" async")?;
+        }
+
+        if type_.throws {
+            self.out.write_str(" throws")?;
+        }
+
+        self.out.write_str(" -> ")?;
+        self.write_type(&type_.result)
+    }
+
+    fn write_function_type(&mut self, type_: &FunctionType) -> fmt::Result {
+        self.out.write_char('(')?;
+
+        for (i, parameter) in type_.parameters.iter().enumerate() {
+            if i != 0 {
+                self.out.write_str(", ")?;
+            }
+
+            if let Some(label) = &parameter.label {
+                write!(self.out, "{label}: ")?;
+            }
+
+            self.write_parameter(parameter)?;
+        }
+
+        self.out.write_char(')')?;
+        self.write_effects_and_result(type_)
+    }
+
+    /// Types are written with qualified names in every style.
+    fn write_type(&mut self, type_: &Type) -> fmt::Result {
+        let saved_style = self.style;
+
+        if self.is_short() {
+            self.style = DisplayStyle::Normal;
+        }
+
+        let result = self.write_type_inner(type_);
+
+        self.style = saved_style;
+
+        result
+    }
+
+    fn write_type_inner(&mut self, type_: &Type) -> fmt::Result {
+        match type_ {
+            Type::Nominal(type_) => self.write_nominal_type(type_),
+            Type::BoundGeneric { type_, args } => match (standard_type_name(type_), args.as_slice()) {
+                (Some("Optional"), [arg]) => {
+                    self.write_type_with_parentheses(arg)?;
+                    self.out.write_char('?')
+                }
+                (Some("Array"), [element]) => {
+                    self.out.write_char('[')?;
+                    self.write_type_inner(element)?;
+                    self.out.write_char(']')
+                }
+                (Some("Dictionary"), [key, value]) => {
+                    self.out.write_char('[')?;
+                    self.write_type_inner(key)?;
+                    self.out.write_str(" : ")?;
+                    self.write_type_inner(value)?;
+                    self.out.write_char(']')
+                }
+                _ => {
+                    self.write_nominal_type(type_)?;
+                    self.out.write_char('<')?;
+
+                    for (i, arg) in args.iter().enumerate() {
+                        if i != 0 {
+                            self.out.write_str(", ")?;
+                        }
+
+                        self.write_type_inner(arg)?;
+                    }
+
+                    self.out.write_char('>')
+                }
+            },
+            Type::Tuple(elements) => {
+                self.out.write_char('(')?;
+
+                for (i, element) in elements.iter().enumerate() {
+                    if i != 0 {
+                        self.out.write_str(", ")?;
+                    }
+
+                    if let Some(label) = &element.label {
+                        write!(self.out, "{label}: ")?;
+                    }
+
+                    self.write_type_inner(&element.type_)?;
+
+                    if element.is_variadic {
+                        self.out.write_str("...")?;
+                    }
+                }
+
+                self.out.write_char(')')
+            }
+            Type::Function(function_type) => self.write_function_type(function_type),
+            Type::Metatype(type_) => {
+                self.write_type_with_parentheses(type_)?;
+                self.out.write_str(".Type")
+            }
+            Type::Existential(protocols) => {
+                if protocols.is_empty() {
+                    return self.out.write_str("Any");
+                }
+
+                for (i, protocol) in protocols.iter().enumerate() {
+                    if i != 0 {
+                        self.out.write_str(" & ")?;
+                    }
+
+                    self.write_nominal_type(protocol)?;
+                }
+
+                Ok(())
+            }
+            Type::GenericParam { depth, index } => write_generic_param_name(self.out, *depth, *index),
+            Type::InOut(type_) => {
+                self.out.write_str("inout ")?;
+                self.write_type_inner(type_)
+            }
+            Type::Shared(type_) => {
+                self.out.write_str("__shared ")?;
+                self.write_type_inner(type_)
+            }
+            Type::Owned(type_) => {
+                self.out.write_str("__owned ")?;
+                self.write_type_inner(type_)
+            }
+        }
+    }
+
+    /// Writes a type that is followed by a postfix, like `?` or `.Type`, which needs parentheses around function types
+    /// and protocol compositions.
+    fn write_type_with_parentheses(&mut self, type_: &Type) -> fmt::Result {
+        let needs_parentheses = match type_ {
+            Type::Function(_) => true,
+            Type::Existential(protocols) => protocols.len() > 1,
+            _ => false,
+        };
+
+        if needs_parentheses {
+            self.out.write_char('(')?;
+            self.write_type_inner(type_)?;
+            self.out.write_char(')')
+        } else {
+            self.write_type_inner(type_)
+        }
+    }
+}
+
+/// Returns the variable or the subscript an accessor belongs to.
+fn storage_entity<'s, 'a>(mut storage: &'s Entity<'a>) -> &'s Entity<'a> {
+    while let Entity::Static(inner) = storage {
+        storage = inner;
+    }
+
+    storage
+}
+
+fn is_class_context(context: &Context) -> bool {
+    match context {
+        Context::Nominal(type_) | Context::Extension { extended: type_, .. } => type_.kind == NominalKind::Class,
+        Context::Module(_) | Context::Entity(_) => false,
+    }
+}
+
+/// Returns the name of `type_` if it is declared in the standard library, for using the sugared syntax of optionals,
+/// arrays and dictionaries.
+fn standard_type_name<'s>(type_: &'s NominalType) -> Option<&'s str> {
+    match &type_.context {
+        Context::Module(module) if module == "Swift" && type_.name.private_discriminator.is_none() => {
+            Some(&type_.name.name)
+        }
+        _ => None,
+    }
+}
+
+/// Writes the name of a generic parameter, which is a sequence of letters for the index followed by the depth if it is
+/// not zero, like `A`, `B1` and `BA`.
+fn write_generic_param_name(out: &mut dyn Write, depth: u64, mut index: u64) -> fmt::Result {
+    loop {
+        out.write_char(char::from(b'A' + (index % 26) as u8))?;
+
+        index /= 26;
+
+        if index == 0 {
+            break;
+        }
+    }
+
+    if depth != 0 {
+        write!(out, "{depth}")?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,463 @@
+//! Tools for demangling symbols using the
+//! [Swift mangling scheme](https://github.com/swiftlang/swift/blob/main/docs/ABI/Mangling.rst), as used by Swift 5 and
+//! later.
+//!
+//! Functions, methods, initializers, deinitializers, variables, subscripts, accessors and closures are supported,
+//! including generic signatures with protocol requirements, private declarations, extensions, word substitutions and
+//! the standard library type substitutions. Types can be nominal types, bound generic types, tuples, function types,
+//! metatypes, protocol compositions and generic parameters. Thunks, specializations, metadata, witness tables,
+//! builtin types, operators, Punycode identifiers, local declarations and other requirement kinds are rejected.
+//!
+//! With [`DisplayStyle::Long`], symbols are displayed the same way as `swift-demangle` displays them.
+
+use crate::padding;
+pub use crate::rust_v0::DisplayStyle;
+use std::borrow::Cow;
+use std::fmt::{self, Display, Formatter};
+use std::rc::Rc;
+
+mod display;
+mod parsers;
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct ParseSymbolError;
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Symbol<'a> {
+    pub entity: Rc<Entity<'a>>,
+}
+
+impl<'a> Symbol<'a> {
+    /// Returns an object that implements [`Display`] for printing the symbol.
+    ///
+    /// [`DisplayStyle::Short`] shows the name of the entity with its argument labels, like `add(_:to:)`,
+    /// [`DisplayStyle::Normal`] and [`DisplayStyle::Debuginfo`] show the qualified name with parameter types, and
+    /// [`DisplayStyle::Long`] also shows generic signatures, effects, result types, variable types, closure types and
+    /// the discriminators of private declarations.
+    #[must_use]
+    pub fn display(&self, style: DisplayStyle) -> impl Display + '_ {
+        struct Wrapper<'s, 'a>(&'s Symbol<'a>, DisplayStyle);
+
+        impl Display for Wrapper<'_, '_> {
+            fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+                padding::write_padded(f, |out| display::write_symbol(self.0, out, self.1))
+            }
+        }
+
+        Wrapper(self, style)
+    }
+
+    /// Parses `input` with the Swift mangling scheme, returns a tuple that contains a [`Symbol`] object and an
+    /// [`&str`] object containing the suffix that starts with the first `.` after the mangled name. Names with `$s`
+    /// and `_$s` prefixes are accepted.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParseSymbolError`] if `input` does not start with a supported mangled name.
+    pub fn parse_from_str(input: &'a str) -> Result<(Self, &'a str), ParseSymbolError> {
+        let input = input
+            .strip_prefix("$s")
+            .or_else(|| input.strip_prefix("_$s"))
+            .ok_or(ParseSymbolError)?;
+
+        parsers::parse_symbol(input).map_err(|()| ParseSymbolError)
+    }
+}
+
+impl Display for Symbol<'_> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.display(if f.alternate() {
+            DisplayStyle::Normal
+        } else {
+            DisplayStyle::Long
+        })
+        .fmt(f)
+    }
+}
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Entity<'a> {
+    Function {
+        context: Context<'a>,
+        name: DeclName<'a>,
+        signature: FunctionSignature<'a>,
+    },
+    Variable {
+        context: Context<'a>,
+        name: DeclName<'a>,
+        type_: Rc<Type<'a>>,
+    },
+    Subscript {
+        context: Context<'a>,
+        signature: FunctionSignature<'a>,
+    },
+    /// An accessor of `storage`, which is a [`Entity::Variable`] or an [`Entity::Subscript`], possibly wrapped in
+    /// [`Entity::Static`].
+    Accessor {
+        kind: AccessorKind,
+        storage: Rc<Self>,
+    },
+    /// An initializer. Allocating initializers allocate the instance before initializing it, the others only
+    /// initialize an instance that is already allocated.
+    Initializer {
+        context: Context<'a>,
+        is_allocating: bool,
+        signature: FunctionSignature<'a>,
+    },
+    /// A deinitializer. Deallocating deinitializers also free the instance after deinitializing it.
+    Deinitializer {
+        context: Context<'a>,
+        is_deallocating: bool,
+    },
+    /// A closure defined in `context`. Implicit closures are created by the compiler, for example for `@autoclosure`
+    /// arguments. `index` tells closures in the same context apart, it is displayed starting from 1.
+    Closure {
+        context: Context<'a>,
+        is_implicit: bool,
+        index: u64,
+        type_: Rc<Type<'a>>,
+    },
+    Static(Rc<Self>),
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum AccessorKind {
+    Getter,
+    Setter,
+    Modify,
+    Read,
+    WillSet,
+    DidSet,
+    Init,
+    MaterializeForSet,
+}
+
+impl AccessorKind {
+    /// Returns the name of the accessor as it is displayed, like `getter`.
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Getter => "getter",
+            Self::Setter => "setter",
+            Self::Modify => "modify",
+            Self::Read => "read",
+            Self::WillSet => "willset",
+            Self::DidSet => "didset",
+            Self::Init => "init",
+            Self::MaterializeForSet => "materializeForSet",
+        }
+    }
+}
+
+/// The signature of a function, an initializer or a subscript.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct FunctionSignature<'a> {
+    pub generic_signature: Option<GenericSignature<'a>>,
+    /// The argument labels of the parameters, with [`None`] for parameters without a label. The whole list is
+    /// [`None`] if no parameter has a label.
+    pub labels: Option<Vec<Option<Cow<'a, str>>>>,
+    pub type_: FunctionType<'a>,
+}
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct GenericSignature<'a> {
+    /// The number of generic parameters at each depth, starting from the outermost generic context.
+    pub param_counts: Vec<u64>,
+    pub requirements: Vec<Requirement<'a>>,
+}
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Requirement<'a> {
+    /// `type_` conforms to `protocol`.
+    Conformance {
+        type_: Rc<Type<'a>>,
+        protocol: Rc<NominalType<'a>>,
+    },
+}
+
+/// The context an entity or a type is declared in.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Context<'a> {
+    Module(Cow<'a, str>),
+    Nominal(Rc<NominalType<'a>>),
+    /// An extension of `extended` declared in `module`.
+    Extension {
+        module: Cow<'a, str>,
+        extended: Rc<NominalType<'a>>,
+    },
+    Entity(Rc<Entity<'a>>),
+}
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct DeclName<'a> {
+    pub name: Cow<'a, str>,
+    /// The discriminator of a `private` or `fileprivate` declaration, which tells declarations with the same name in
+    /// different files apart.
+    pub private_discriminator: Option<Cow<'a, str>>,
+}
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct NominalType<'a> {
+    pub kind: NominalKind,
+    pub context: Context<'a>,
+    pub name: DeclName<'a>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum NominalKind {
+    Class,
+    Struct,
+    Enum,
+    Protocol,
+    TypeAlias,
+}
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Type<'a> {
+    Nominal(Rc<NominalType<'a>>),
+    BoundGeneric {
+        type_: Rc<NominalType<'a>>,
+        args: Vec<Rc<Self>>,
+    },
+    Tuple(Vec<TupleElement<'a>>),
+    Function(FunctionType<'a>),
+    /// The metatype of a type, displayed as `T.Type`.
+    Metatype(Rc<Self>),
+    /// A protocol composition, like `P & Q`. The empty composition is `Any`.
+    Existential(Vec<Rc<NominalType<'a>>>),
+    /// A generic parameter, displayed as `A`, `B` and so on at depth 0, and as `A1`, `B1` and so on at depth 1.
+    GenericParam {
+        depth: u64,
+        index: u64,
+    },
+    InOut(Rc<Self>),
+    Shared(Rc<Self>),
+    Owned(Rc<Self>),
+}
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct TupleElement<'a> {
+    pub label: Option<Cow<'a, str>>,
+    pub type_: Rc<Type<'a>>,
+    pub is_variadic: bool,
+}
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct FunctionType<'a> {
+    /// The parameters. A parameter list of a single tuple type is a tuple with one element whose type is the tuple.
+    pub parameters: Vec<TupleElement<'a>>,
+    pub result: Rc<Type<'a>>,
+    pub is_async: bool,
+    pub throws: bool,
+    /// Whether the function may escape, which is `false` for closure parameters that are not `@escaping`.
+    pub is_escaping: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DisplayStyle, Entity, Symbol};
+
+    // Long style expectations follow the output of `swift-demangle`.
+    fn check_display(test_cases: &[(&str, &str, &str, &str)]) {
+        for &(name, long, normal, short) in test_cases {
+            let (symbol, suffix) = Symbol::parse_from_str(name).unwrap();
+
+            assert_eq!(suffix, "", "{name}");
+            assert_eq!(symbol.display(DisplayStyle::Long).to_string(), long, "{name}");
+            assert_eq!(symbol.display(DisplayStyle::Normal).to_string(), normal, "{name}");
+            assert_eq!(symbol.display(DisplayStyle::Short).to_string(), short, "{name}");
+            assert_eq!(symbol.to_string(), long, "{name}");
+            assert_eq!(format!("{symbol:#}"), normal, "{name}");
+        }
+    }
+
+    #[test]
+    fn test_display_functions() {
+        check_display(&[
+            ("$s4main3fooyyF", "main.foo() -> ()", "main.foo()", "foo()"),
+            (
+                "$s4main3add1a1bS2i_SitF",
+                "main.add(a: Swift.Int, b: Swift.Int) -> Swift.Int",
+                "main.add(a: Swift.Int, b: Swift.Int)",
+                "add(a:b:)",
+            ),
+            (
+                "$s4main3addyS2i_SitF",
+                "main.add(Swift.Int, Swift.Int) -> Swift.Int",
+                "main.add(Swift.Int, Swift.Int)",
+                "add(_:_:)",
+            ),
+            (
+                "$s4main3foo_1xySS_SitF",
+                "main.foo(_: Swift.String, x: Swift.Int) -> ()",
+                "main.foo(_: Swift.String, x: Swift.Int)",
+                "foo(_:x:)",
+            ),
+            (
+                "_$s4main3fooyySaySiG_SDySSSiGSiSgtF",
+                "main.foo([Swift.Int], [Swift.String : Swift.Int], Swift.Int?) -> ()",
+                "main.foo([Swift.Int], [Swift.String : Swift.Int], Swift.Int?)",
+                "foo(_:_:_:)",
+            ),
+            (
+                "$s4main3fooyyYaKF",
+                "main.foo() async throws -> ()",
+                "main.foo()",
+                "foo()",
+            ),
+            ("$s4main3fooyyxlF", "main.foo<A>(A) -> ()", "main.foo(A)", "foo(_:)"),
+            (
+                "$s4main3fooyyx_q_tSQRzSHR_r0_lF",
+                "main.foo<A, B where A: Swift.Equatable, B: Swift.Hashable>(A, B) -> ()",
+                "main.foo(A, B)",
+                "foo(_:_:)",
+            ),
+            (
+                "$s4main3fooyyySiXE_SSmSbztF",
+                "main.foo((Swift.Int) -> (), Swift.String.Type, inout Swift.Bool) -> ()",
+                "main.foo((Swift.Int) -> (), Swift.String.Type, inout Swift.Bool)",
+                "foo(_:_:_:)",
+            ),
+            (
+                "$s4main3fooyyyycF",
+                "main.foo(@escaping () -> ()) -> ()",
+                "main.foo(@escaping () -> ())",
+                "foo(_:)",
+            ),
+            (
+                "$s4main3fooyySnySiGF",
+                "main.foo(Swift.Range<Swift.Int>) -> ()",
+                "main.foo(Swift.Range<Swift.Int>)",
+                "foo(_:)",
+            ),
+            ("$s4main3fooyyypF", "main.foo(Any) -> ()", "main.foo(Any)", "foo(_:)"),
+            (
+                "$s4main3fooyySid_tF",
+                "main.foo(Swift.Int...) -> ()",
+                "main.foo(Swift.Int...)",
+                "foo(_:)",
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_display_members() {
+        check_display(&[
+            ("$s4main3FooV3baryyF", "main.Foo.bar() -> ()", "main.Foo.bar()", "bar()"),
+            (
+                "$s4main3FooV3baryyFZ",
+                "static main.Foo.bar() -> ()",
+                "static main.Foo.bar()",
+                "bar()",
+            ),
+            (
+                "$s4main3FooV1xACSi_tcfC",
+                "main.Foo.init(x: Swift.Int) -> main.Foo",
+                "main.Foo.init(x: Swift.Int)",
+                "init(x:)",
+            ),
+            (
+                "$s4main3FooCACycfC",
+                "main.Foo.__allocating_init() -> main.Foo",
+                "main.Foo.__allocating_init()",
+                "__allocating_init()",
+            ),
+            (
+                "$s4main3FooCfD",
+                "main.Foo.__deallocating_deinit",
+                "main.Foo.__deallocating_deinit",
+                "__deallocating_deinit",
+            ),
+            ("$s4main3FooCfd", "main.Foo.deinit", "main.Foo.deinit", "deinit"),
+            ("$s4main1xSivp", "main.x : Swift.Int", "main.x", "x"),
+            (
+                "$s4main1xSivg",
+                "main.x.getter : Swift.Int",
+                "main.x.getter",
+                "x.getter",
+            ),
+            (
+                "$s4main3FooV1xSSvs",
+                "main.Foo.x.setter : Swift.String",
+                "main.Foo.x.setter",
+                "x.setter",
+            ),
+            (
+                "$s4main3FooVyS2icig",
+                "main.Foo.subscript.getter : (Swift.Int) -> Swift.Int",
+                "main.Foo.subscript.getter",
+                "subscript.getter",
+            ),
+            (
+                "$s4main3fooyyFyycfU_",
+                "closure #1 () -> () in main.foo() -> ()",
+                "closure #1 in main.foo()",
+                "closure #1 in foo()",
+            ),
+            (
+                "$s4main3fooyyFSbyXEfu0_",
+                "implicit closure #2 () -> Swift.Bool in main.foo() -> ()",
+                "implicit closure #2 in main.foo()",
+                "implicit closure #2 in foo()",
+            ),
+            (
+                "$sSi4mainE6doubleSiyF",
+                "(extension in main):Swift.Int.double() -> Swift.Int",
+                "Swift.Int.double()",
+                "double()",
+            ),
+            (
+                "$s4main3Foo33_0123456789ABCDEF0123456789ABCDEFLLV3baryyF",
+                "main.(Foo in _0123456789ABCDEF0123456789ABCDEF).bar() -> ()",
+                "main.Foo.bar()",
+                "bar()",
+            ),
+            (
+                "$s4main12SomeLongNameV0C4TypeV3fooyyF",
+                "main.SomeLongName.LongType.foo() -> ()",
+                "main.SomeLongName.LongType.foo()",
+                "foo()",
+            ),
+            (
+                "$s4main3FooV3bar1xyAC_tF",
+                "main.Foo.bar(x: main.Foo) -> ()",
+                "main.Foo.bar(x: main.Foo)",
+                "bar(x:)",
+            ),
+            (
+                "$sSo8NSObjectC4mainE3fooyyF",
+                "(extension in main):__C.NSObject.foo() -> ()",
+                "__C.NSObject.foo()",
+                "foo()",
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_parse() {
+        let (symbol, suffix) = Symbol::parse_from_str("$s4main3fooyyF.cold").unwrap();
+
+        assert_eq!(suffix, ".cold");
+        assert!(matches!(symbol.entity.as_ref(), Entity::Function { .. }));
+
+        for name in [
+            "_RNvC3foo3bar",
+            "$s",
+            "$s4main",
+            "$s4main3foo",
+            "$s4main3fooyyFyyF",
+            "$s4main3fooyyFTA",
+            "$s4mainAZ3fooyyF",
+            "$s4main0a3fooyyF",
+        ] {
+            assert!(Symbol::parse_from_str(name).is_err(), "{name}");
+        }
+    }
+
+    #[test]
+    fn test_depth_limit() {
+        let name = format!("$s4main3fooyySi{}F", "Sg".repeat(1000));
+
+        assert!(Symbol::parse_from_str(&name).is_err());
+    }
+}
@@ -0,0 +1,1026 @@
+use crate::mini_parser::parsers::{digit1, take};
+use crate::mini_parser::Parser;
+use crate::swift::{
+    AccessorKind, Context as DeclContext, DeclName, Entity, FunctionSignature, FunctionType, GenericSignature,
+    NominalKind, NominalType, Requirement, Symbol, TupleElement, Type,
+};
+use std::borrow::Cow;
+use std::rc::Rc;
+
+// References:
+//
+// - <https://github.com/swiftlang/swift/blob/main/docs/ABI/Mangling.rst>.
+// - <https://github.com/swiftlang/swift/blob/main/lib/Demangling/Demangler.cpp>.
+//
+// The mangling is postfix: every operator pops its operands from a stack of nodes and pushes its result, and the
+// symbol is the single node left at the end.
+
+const MAX_DEPTH: usize = 256;
+
+/// The maximum number of words for word substitutions in identifiers.
+const MAX_WORDS: usize = 26;
+
+/// The maximum repeat count of a substitution.
+const MAX_REPEAT_COUNT: u64 = 2048;
+
+type Labels<'a> = Option<Vec<Option<Cow<'a, str>>>>;
+
+#[derive(Clone)]
+enum Node<'a> {
+    Identifier(Cow<'a, str>),
+    PrivateName(DeclName<'a>),
+    Context(DeclContext<'a>),
+    Type(Rc<Type<'a>>),
+    /// A type with a generic signature, which is only valid as the type of an entity.
+    GenericType(GenericSignature<'a>, Rc<Type<'a>>),
+    Entity(Rc<Entity<'a>>),
+    GenericSignature(GenericSignature<'a>),
+    Requirement(Requirement<'a>),
+    EmptyList,
+    FirstElementMarker,
+    VariadicMarker,
+    Async,
+    Throws,
+}
+
+/// The state of the demangler. Every node is stored with its depth, the number of nodes on the longest chain from the
+/// node to a leaf, so deeply nested symbols can be rejected before they overflow the stack when displayed or dropped.
+#[derive(Default)]
+struct Context<'a> {
+    stack: Vec<(Node<'a>, usize)>,
+    substitutions: Vec<(Node<'a>, usize)>,
+    words: Vec<&'a str>,
+}
+
+impl<'a> Context<'a> {
+    fn push(&mut self, node: Node<'a>, depth: usize) -> Result<(), ()> {
+        if depth > MAX_DEPTH {
+            return Err(());
+        }
+
+        self.stack.push((node, depth));
+
+        Ok(())
+    }
+
+    /// Pushes `node` and also adds it to the substitutions.
+    fn push_substitutable(&mut self, node: Node<'a>, depth: usize) -> Result<(), ()> {
+        self.push(node.clone(), depth)?;
+        self.substitutions.push((node, depth));
+
+        Ok(())
+    }
+
+    /// Pops the top node if `f` accepts it.
+    fn pop_map<T>(&mut self, f: impl FnOnce(&Node<'a>) -> Option<T>) -> Option<(T, usize)> {
+        let (node, depth) = self.stack.last()?;
+        let result = f(node)?;
+        let depth = *depth;
+
+        self.stack.pop();
+
+        Some((result, depth))
+    }
+
+    fn pop_marker(&mut self, f: impl FnOnce(&Node<'a>) -> bool) -> bool {
+        self.pop_map(|node| f(node).then_some(())).is_some()
+    }
+
+    fn pop_entity(&mut self) -> Option<(Rc<Entity<'a>>, usize)> {
+        self.pop_map(|node| match node {
+            Node::Entity(entity) => Some(Rc::clone(entity)),
+            _ => None,
+        })
+    }
+
+    fn pop_type(&mut self) -> Option<(Rc<Type<'a>>, usize)> {
+        self.pop_map(|node| match node {
+            Node::Type(type_) => Some(Rc::clone(type_)),
+            _ => None,
+        })
+    }
+
+    fn pop_nominal_type(&mut self) -> Option<(Rc<NominalType<'a>>, usize)> {
+        self.pop_map(|node| match node {
+            Node::Type(type_) => match type_.as_ref() {
+                Type::Nominal(nominal) => Some(Rc::clone(nominal)),
+                _ => None,
+            },
+            _ => None,
+        })
+    }
+
+    fn pop_protocol(&mut self) -> Option<(Rc<NominalType<'a>>, usize)> {
+        let (protocol, depth) = self.pop_nominal_type()?;
+
+        if protocol.kind == NominalKind::Protocol {
+            Some((protocol, depth))
+        } else {
+            self.stack.push((Node::Type(Rc::new(Type::Nominal(protocol))), depth));
+
+            None
+        }
+    }
+
+    fn pop_decl_name(&mut self) -> Option<(DeclName<'a>, usize)> {
+        self.pop_map(|node| match node {
+            Node::Identifier(name) => Some(DeclName {
+                name: name.clone(),
+                private_discriminator: None,
+            }),
+            Node::PrivateName(name) => Some(name.clone()),
+            _ => None,
+        })
+    }
+
+    fn pop_module(&mut self) -> Option<(Cow<'a, str>, usize)> {
+        self.pop_map(|node| match node {
+            Node::Identifier(name) | Node::Context(DeclContext::Module(name)) => Some(name.clone()),
+            _ => None,
+        })
+    }
+
+    fn pop_context(&mut self) -> Option<(DeclContext<'a>, usize)> {
+        self.pop_map(|node| match node {
+            Node::Identifier(name) => Some(DeclContext::Module(name.clone())),
+            Node::Context(context) => Some(context.clone()),
+            Node::Type(type_) => match type_.as_ref() {
+                Type::Nominal(nominal) => Some(DeclContext::Nominal(Rc::clone(nominal))),
+                _ => None,
+            },
+            Node::Entity(entity) => Some(DeclContext::Entity(Rc::clone(entity))),
+            _ => None,
+        })
+    }
+
+    /// Pops the type of a function, an initializer or a subscript, which may have a generic signature.
+    fn pop_function_type(&mut self) -> Option<(Option<GenericSignature<'a>>, FunctionType<'a>, usize)> {
+        self.pop_map(|node| match node {
+            Node::Type(type_) => match type_.as_ref() {
+                Type::Function(function_type) => Some((None, function_type.clone())),
+                _ => None,
+            },
+            Node::GenericType(signature, type_) => match type_.as_ref() {
+                Type::Function(function_type) => Some((Some(signature.clone()), function_type.clone())),
+                _ => None,
+            },
+            _ => None,
+        })
+        .map(|((signature, function_type), depth)| (signature, function_type, depth))
+    }
+
+    /// Pops the parameter or result type of a function type, where an empty list is the empty tuple.
+    fn pop_function_params(&mut self) -> Option<(Rc<Type<'a>>, usize)> {
+        if self.pop_marker(|node| matches!(node, Node::EmptyList)) {
+            Some((Rc::new(Type::Tuple(Vec::new())), 1))
+        } else {
+            self.pop_type()
+        }
+    }
+
+    /// Pops the argument labels of a function with `parameter_count` parameters. Labels are identifiers, with `_` for
+    /// parameters without a label, or a single empty list if no parameter has a label.
+    fn pop_labels(&mut self, parameter_count: usize) -> Result<(Labels<'a>, usize), ()> {
+        if self.pop_marker(|node| matches!(node, Node::EmptyList)) || parameter_count == 0 {
+            return Ok((None, 0));
+        }
+
+        let mut labels = Vec::with_capacity(parameter_count);
+        let mut depth = 0;
+
+        for _ in 0..parameter_count {
+            let (label, label_depth) = self
+                .pop_map(|node| match node {
+                    Node::Identifier(label) => Some(Some(label.clone())),
+                    Node::FirstElementMarker => Some(None),
+                    _ => None,
+                })
+                .ok_or(())?;
+
+            labels.push(label);
+            depth = depth.max(label_depth);
+        }
+
+        labels.reverse();
+
+        Ok((labels.iter().any(Option::is_some).then_some(labels), depth))
+    }
+
+    fn pop_function_signature(&mut self) -> Result<(FunctionSignature<'a>, usize), ()> {
+        let (generic_signature, type_, type_depth) = self.pop_function_type().ok_or(())?;
+        let (labels, labels_depth) = self.pop_labels(type_.parameters.len())?;
+
+        Ok((
+            FunctionSignature {
+                generic_signature,
+                labels,
+                type_,
+            },
+            type_depth.max(labels_depth),
+        ))
+    }
+
+    /// Pops the elements of a tuple, which are written as the first element, `_` and the other elements.
+    fn pop_tuple_elements(&mut self) -> Result<(Vec<TupleElement<'a>>, usize), ()> {
+        let mut elements = Vec::new();
+        let mut depth = 0;
+
+        if !self.pop_marker(|node| matches!(node, Node::EmptyList)) {
+            loop {
+                let is_first = self.pop_marker(|node| matches!(node, Node::FirstElementMarker));
+                let is_variadic = self.pop_marker(|node| matches!(node, Node::VariadicMarker));
+                let label = self
+                    .pop_map(|node| match node {
+                        Node::Identifier(label) => Some(label.clone()),
+                        _ => None,
+                    })
+                    .map(|(label, _)| label);
+                let (type_, type_depth) = self.pop_type().ok_or(())?;
+
+                elements.push(TupleElement {
+                    label,
+                    type_,
+                    is_variadic,
+                });
+
+                depth = depth.max(type_depth);
+
+                if is_first {
+                    break;
+                }
+            }
+
+            elements.reverse();
+        }
+
+        Ok((elements, depth))
+    }
+
+    fn push_type(&mut self, type_: Type<'a>, depth: usize) -> Result<(), ()> {
+        self.push(Node::Type(Rc::new(type_)), depth + 1)
+    }
+
+    fn push_entity(&mut self, entity: Entity<'a>, depth: usize) -> Result<(), ()> {
+        self.push(Node::Entity(Rc::new(entity)), depth + 1)
+    }
+
+    fn add_words(&mut self, literal: &'a str) {
+        let bytes = literal.as_bytes();
+        let mut word_start = None;
+
+        for i in 0..=bytes.len() {
+            let c = bytes.get(i).copied();
+
+            if let Some(start) = word_start {
+                let is_word_end = match c {
+                    None | Some(b'_') => true,
+                    Some(c) => c.is_ascii_uppercase() && !bytes[i - 1].is_ascii_uppercase(),
+                };
+
+                if is_word_end {
+                    if i - start >= 2 && self.words.len() < MAX_WORDS {
+                        self.words.push(&literal[start..i]);
+                    }
+
+                    word_start = None;
+                }
+            }
+
+            if word_start.is_none() && c.is_some_and(|c| !c.is_ascii_digit() && c != b'_') {
+                word_start = Some(i);
+            }
+        }
+    }
+}
+
+pub fn parse_symbol(input: &str) -> Result<(Symbol<'_>, &str), ()> {
+    let mut context = Context::default();
+    let (_, rest) = parse_operator.many0().parse(input, &mut context)?;
+
+    if !(rest.is_empty() || rest.starts_with('.')) {
+        return Err(());
+    }
+
+    match context.stack.as_slice() {
+        [(Node::Entity(entity), _)] => Ok((
+            Symbol {
+                entity: Rc::clone(entity),
+            },
+            rest,
+        )),
+        _ => Err(()),
+    }
+}
+
+fn parse_natural<'a, C>(input: &'a str, context: &mut C) -> Result<(u64, &'a str), ()> {
+    digit1.map_opt(|digits: &str| digits.parse().ok()).parse(input, context)
+}
+
+/// Parses `_` as 0 and `<natural> _` as `<natural> + 1`.
+fn parse_index<'a, C>(input: &'a str, context: &mut C) -> Result<(u64, &'a str), ()> {
+    if let Some(rest) = input.strip_prefix('_') {
+        Ok((0, rest))
+    } else {
+        let (index, rest) = parse_natural(input, context)?;
+        let rest = rest.strip_prefix('_').ok_or(())?;
+
+        Ok((index.checked_add(1).ok_or(())?, rest))
+    }
+}
+
+/// Parses the depth and index of a generic parameter.
+fn parse_generic_param_index<'a, C>(input: &'a str, context: &mut C) -> Result<((u64, u64), &'a str), ()> {
+    if let Some(rest) = input.strip_prefix('z') {
+        Ok(((0, 0), rest))
+    } else if let Some(rest) = input.strip_prefix('d') {
+        let (depth, rest) = parse_index(rest, context)?;
+        let (index, rest) = parse_index(rest, context)?;
+
+        Ok(((depth.checked_add(1).ok_or(())?, index), rest))
+    } else {
+        let (index, rest) = parse_index(input, context)?;
+
+        Ok(((0, index.checked_add(1).ok_or(())?), rest))
+    }
+}
+
+fn split_first(input: &str) -> Result<(char, &str), ()> {
+    let mut chars = input.chars();
+    let c = chars.next().ok_or(())?;
+
+    Ok((c, chars.as_str()))
+}
+
+fn parse_operator<'a>(input: &'a str, context: &mut Context<'a>) -> Result<((), &'a str), ()> {
+    let (c, rest) = split_first(input)?;
+
+    match c {
+        '0'..='9' => parse_identifier(input, context),
+        'A' => parse_multi_substitution(rest, context),
+        'S' => parse_standard_substitution(rest, context),
+        'C' => push_nominal_type(rest, context, NominalKind::Class),
+        'V' => push_nominal_type(rest, context, NominalKind::Struct),
+        'O' => push_nominal_type(rest, context, NominalKind::Enum),
+        'P' => push_nominal_type(rest, context, NominalKind::Protocol),
+        'a' => push_nominal_type(rest, context, NominalKind::TypeAlias),
+        'E' => parse_extension(rest, context),
+        'L' => parse_private_name(rest, context),
+        'F' => parse_function(rest, context),
+        'f' => parse_function_entity(rest, context),
+        'v' => parse_variable(rest, context),
+        'i' => parse_subscript(rest, context),
+        'Z' => parse_static(rest, context),
+        'G' => parse_bound_generic_type(rest, context),
+        'c' => parse_function_type(rest, context, true),
+        'X' => parse_function_type(rest.strip_prefix('E').ok_or(())?, context, false),
+        't' => {
+            let (elements, depth) = context.pop_tuple_elements()?;
+
+            context.push_type(Type::Tuple(elements), depth)?;
+
+            Ok(((), rest))
+        }
+        'p' => parse_protocol_composition(rest, context),
+        'm' | 'z' | 'h' | 'n' => {
+            let (type_, depth) = context.pop_type().ok_or(())?;
+
+            context.push_type(
+                match c {
+                    'm' => Type::Metatype(type_),
+                    'z' => Type::InOut(type_),
+                    'h' => Type::Shared(type_),
+                    _ => Type::Owned(type_),
+                },
+                depth,
+            )?;
+
+            Ok(((), rest))
+        }
+        'x' => {
+            context.push_type(Type::GenericParam { depth: 0, index: 0 }, 0)?;
+
+            Ok(((), rest))
+        }
+        'q' => {
+            let ((depth, index), rest) = parse_generic_param_index(rest, context)?;
+
+            context.push_type(Type::GenericParam { depth, index }, 0)?;
+
+            Ok(((), rest))
+        }
+        'l' | 'r' => parse_generic_signature(input, context),
+        'R' => parse_conformance_requirement(rest, context),
+        'u' => parse_generic_type(rest, context),
+        's' | 'y' | '_' | 'd' | 'K' => {
+            let node = match c {
+                's' => Node::Context(DeclContext::Module(Cow::Borrowed("Swift"))),
+                'y' => Node::EmptyList,
+                '_' => Node::FirstElementMarker,
+                'd' => Node::VariadicMarker,
+                _ => Node::Throws,
+            };
+
+            context.push(node, 1)?;
+
+            Ok(((), rest))
+        }
+        'Y' => {
+            let rest = rest.strip_prefix('a').ok_or(())?;
+
+            context.push(Node::Async, 1)?;
+
+            Ok(((), rest))
+        }
+        _ => Err(()),
+    }
+}
+
+fn parse_extension<'a>(input: &'a str, context: &mut Context<'a>) -> Result<((), &'a str), ()> {
+    let (module, module_depth) = context.pop_module().ok_or(())?;
+    let (extended, type_depth) = context.pop_nominal_type().ok_or(())?;
+
+    context.push(
+        Node::Context(DeclContext::Extension { module, extended }),
+        module_depth.max(type_depth) + 1,
+    )?;
+
+    Ok(((), input))
+}
+
+/// Parses `LL`, which makes a name private with the discriminator that follows the name.
+fn parse_private_name<'a>(input: &'a str, context: &mut Context<'a>) -> Result<((), &'a str), ()> {
+    let rest = input.strip_prefix('L').ok_or(())?;
+    let mut pop_identifier = || {
+        context.pop_map(|node| match node {
+            Node::Identifier(identifier) => Some(identifier.clone()),
+            _ => None,
+        })
+    };
+
+    let (discriminator, discriminator_depth) = pop_identifier().ok_or(())?;
+    let (name, name_depth) = pop_identifier().ok_or(())?;
+
+    context.push(
+        Node::PrivateName(DeclName {
+            name,
+            private_discriminator: Some(discriminator),
+        }),
+        discriminator_depth.max(name_depth) + 1,
+    )?;
+
+    Ok(((), rest))
+}
+
+fn parse_function<'a>(input: &'a str, context: &mut Context<'a>) -> Result<((), &'a str), ()> {
+    let generic_signature = context.pop_map(|node| match node {
+        Node::GenericSignature(signature) => Some(signature.clone()),
+        _ => None,
+    });
+
+    let (type_, type_depth) = pop_function_type(context, true)?;
+    let (labels, labels_depth) = context.pop_labels(type_.parameters.len())?;
+    let (name, name_depth) = context.pop_decl_name().ok_or(())?;
+    let (entity_context, context_depth) = context.pop_context().ok_or(())?;
+    let generic_signature_depth = generic_signature.as_ref().map_or(0, |(_, depth)| *depth);
+
+    context.push_entity(
+        Entity::Function {
+            context: entity_context,
+            name,
+            signature: FunctionSignature {
+                generic_signature: generic_signature.map(|(signature, _)| signature),
+                labels,
+                type_,
+            },
+        },
+        type_depth
+            .max(labels_depth)
+            .max(name_depth)
+            .max(context_depth)
+            .max(generic_signature_depth),
+    )?;
+
+    Ok(((), input))
+}
+
+fn parse_variable<'a>(input: &'a str, context: &mut Context<'a>) -> Result<((), &'a str), ()> {
+    let (type_, type_depth) = context.pop_type().ok_or(())?;
+
+    // Variables have no argument labels, but are mangled with an empty label list.
+    context.pop_marker(|node| matches!(node, Node::EmptyList));
+
+    let (name, name_depth) = context.pop_decl_name().ok_or(())?;
+    let (entity_context, context_depth) = context.pop_context().ok_or(())?;
+
+    context.push_entity(
+        Entity::Variable {
+            context: entity_context,
+            name,
+            type_,
+        },
+        type_depth.max(name_depth).max(context_depth),
+    )?;
+
+    parse_accessor(input, context)
+}
+
+fn parse_subscript<'a>(input: &'a str, context: &mut Context<'a>) -> Result<((), &'a str), ()> {
+    let (signature, signature_depth) = context.pop_function_signature()?;
+    let (entity_context, context_depth) = context.pop_context().ok_or(())?;
+
+    context.push_entity(
+        Entity::Subscript {
+            context: entity_context,
+            signature,
+        },
+        signature_depth.max(context_depth),
+    )?;
+
+    parse_accessor(input, context)
+}
+
+fn parse_static<'a>(input: &'a str, context: &mut Context<'a>) -> Result<((), &'a str), ()> {
+    let (entity, depth) = context.pop_entity().ok_or(())?;
+
+    context.push_entity(Entity::Static(entity), depth)?;
+
+    Ok(((), input))
+}
+
+fn parse_bound_generic_type<'a>(input: &'a str, context: &mut Context<'a>) -> Result<((), &'a str), ()> {
+    let mut args = Vec::new();
+    let mut depth = 0;
+
+    while let Some((arg, arg_depth)) = context.pop_type() {
+        args.push(arg);
+        depth = depth.max(arg_depth);
+    }
+
+    args.reverse();
+
+    // Generic arguments of enclosing types are separated by `_`, which is not supported.
+    if !context.pop_marker(|node| matches!(node, Node::EmptyList)) {
+        return Err(());
+    }
+
+    let (type_, type_depth) = context.pop_nominal_type().ok_or(())?;
+
+    context.push_substitutable(
+        Node::Type(Rc::new(Type::BoundGeneric { type_, args })),
+        depth.max(type_depth) + 1,
+    )?;
+
+    Ok(((), input))
+}
+
+fn parse_function_type<'a>(input: &'a str, context: &mut Context<'a>, is_escaping: bool) -> Result<((), &'a str), ()> {
+    let (function_type, depth) = pop_function_type(context, is_escaping)?;
+
+    context.push_type(Type::Function(function_type), depth)?;
+
+    Ok(((), input))
+}
+
+/// Parses `p`, which makes a protocol composition from a list of protocols written like the elements of a tuple.
+fn parse_protocol_composition<'a>(input: &'a str, context: &mut Context<'a>) -> Result<((), &'a str), ()> {
+    let mut protocols = Vec::new();
+    let mut depth = 0;
+
+    if !context.pop_marker(|node| matches!(node, Node::EmptyList)) {
+        loop {
+            let is_first = context.pop_marker(|node| matches!(node, Node::FirstElementMarker));
+            let (protocol, protocol_depth) = context.pop_protocol().ok_or(())?;
+
+            protocols.push(protocol);
+            depth = depth.max(protocol_depth);
+
+            if is_first {
+                break;
+            }
+        }
+
+        protocols.reverse();
+    }
+
+    context.push_type(Type::Existential(protocols), depth)?;
+
+    Ok(((), input))
+}
+
+fn parse_conformance_requirement<'a>(input: &'a str, context: &mut Context<'a>) -> Result<((), &'a str), ()> {
+    let ((depth, index), rest) = parse_generic_param_index(input, context)?;
+    let (protocol, protocol_depth) = context.pop_protocol().ok_or(())?;
+
+    context.push(
+        Node::Requirement(Requirement::Conformance {
+            type_: Rc::new(Type::GenericParam { depth, index }),
+            protocol,
+        }),
+        protocol_depth + 1,
+    )?;
+
+    Ok(((), rest))
+}
+
+fn parse_generic_type<'a>(input: &'a str, context: &mut Context<'a>) -> Result<((), &'a str), ()> {
+    let (signature, signature_depth) = context
+        .pop_map(|node| match node {
+            Node::GenericSignature(signature) => Some(signature.clone()),
+            _ => None,
+        })
+        .ok_or(())?;
+
+    let (type_, type_depth) = context.pop_type().ok_or(())?;
+
+    context.push(Node::GenericType(signature, type_), signature_depth.max(type_depth) + 1)?;
+
+    Ok(((), input))
+}
+
+/// Parses an identifier, which is either a literal like `3foo`, or starts with `0` and consists of literals and
+/// substitutions of words from previous identifiers, where the last substitution is an uppercase letter.
+fn parse_identifier<'a>(input: &'a str, context: &mut Context<'a>) -> Result<((), &'a str), ()> {
+    let (has_word_substitutions, mut rest) = match input.strip_prefix('0') {
+        // Punycode identifiers start with `00`.
+        Some(rest) if rest.starts_with('0') => return Err(()),
+        Some(rest) => (true, rest),
+        None => (false, input),
+    };
+
+    let mut has_word_substitutions = has_word_substitutions;
+    let mut identifier = Cow::Borrowed("");
+
+    loop {
+        while has_word_substitutions {
+            let Some(c) = rest.chars().next().filter(char::is_ascii_alphabetic) else {
+                break;
+            };
+
+            let word_index = if c.is_ascii_lowercase() {
+                c as usize - 'a' as usize
+            } else {
+                has_word_substitutions = false;
+
+                c as usize - 'A' as usize
+            };
+
+            identifier.to_mut().push_str(context.words.get(word_index).ok_or(())?);
+            rest = &rest[1..];
+        }
+
+        if let Some(next) = rest.strip_prefix('0') {
+            rest = next;
+
+            break;
+        }
+
+        let (literal, next) = parse_natural
+            .map_opt(|length| usize::try_from(length).ok().filter(|&length| length != 0))
+            .flat_map(take)
+            .parse(rest, &mut ())?;
+
+        context.add_words(literal);
+
+        if identifier.is_empty() {
+            identifier = Cow::Borrowed(literal);
+        } else {
+            identifier.to_mut().push_str(literal);
+        }
+
+        rest = next;
+
+        if !has_word_substitutions {
+            break;
+        }
+    }
+
+    if identifier.is_empty() {
+        return Err(());
+    }
+
+    context.push_substitutable(Node::Identifier(identifier), 1)?;
+
+    Ok(((), rest))
+}
+
+/// Parses substitutions of previous identifiers and types, where each is a letter with an optional repeat count, and
+/// the last one is an uppercase letter. `A` followed by an index refers to the substitutions after the 26th.
+fn parse_multi_substitution<'a>(mut input: &'a str, context: &mut Context<'a>) -> Result<((), &'a str), ()> {
+    loop {
+        let (repeat_count, rest) = parse_natural.opt().parse(input, &mut ())?;
+        let (c, rest) = split_first(rest)?;
+
+        let index = match c {
+            'a'..='z' => c as usize - 'a' as usize,
+            'A'..='Z' => c as usize - 'A' as usize,
+            '_' => usize::try_from(repeat_count.ok_or(())?)
+                .ok()
+                .and_then(|index| index.checked_add(27))
+                .ok_or(())?,
+            _ => return Err(()),
+        };
+
+        let (node, depth) = context.substitutions.get(index).cloned().ok_or(())?;
+
+        if c == '_' {
+            context.push(node, depth)?;
+
+            return Ok(((), rest));
+        }
+
+        push_repeated(context, &node, depth, repeat_count.unwrap_or(1))?;
+
+        if c.is_ascii_uppercase() {
+            return Ok(((), rest));
+        }
+
+        input = rest;
+    }
+}
+
+fn push_repeated<'a>(context: &mut Context<'a>, node: &Node<'a>, depth: usize, count: u64) -> Result<(), ()> {
+    if count > MAX_REPEAT_COUNT {
+        return Err(());
+    }
+
+    for _ in 0..count {
+        context.push(node.clone(), depth)?;
+    }
+
+    Ok(())
+}
+
+fn parse_standard_substitution<'a>(input: &'a str, context: &mut Context<'a>) -> Result<((), &'a str), ()> {
+    let (c, rest) = split_first(input)?;
+
+    match c {
+        'o' | 'C' => {
+            let module = if c == 'o' { "__C" } else { "__C_Synthesized" };
+
+            context.push(Node::Context(DeclContext::Module(Cow::Borrowed(module))), 1)?;
+
+            return Ok(((), rest));
+        }
+        'g' => {
+            let (type_, depth) = context.pop_type().ok_or(())?;
+
+            context.push_type(
+                Type::BoundGeneric {
+                    type_: standard_type(NominalKind::Enum, "Optional"),
+                    args: vec![type_],
+                },
+                depth,
+            )?;
+
+            return Ok(((), rest));
+        }
+        _ => {}
+    }
+
+    let (repeat_count, rest) = parse_natural.opt().parse(input, &mut ())?;
+    let (c, rest) = split_first(rest)?;
+    let (kind, name) = standard_type_name(c).ok_or(())?;
+    let type_ = Node::Type(Rc::new(Type::Nominal(standard_type(kind, name))));
+
+    push_repeated(context, &type_, 2, repeat_count.unwrap_or(1))?;
+
+    Ok(((), rest))
+}
+
+fn standard_type<'a>(kind: NominalKind, name: &'static str) -> Rc<NominalType<'a>> {
+    Rc::new(NominalType {
+        kind,
+        context: DeclContext::Module(Cow::Borrowed("Swift")),
+        name: DeclName {
+            name: Cow::Borrowed(name),
+            private_discriminator: None,
+        },
+    })
+}
+
+/// Returns the standard library type that `S` followed by `c` refers to.
+fn standard_type_name(c: char) -> Option<(NominalKind, &'static str)> {
+    Some(match c {
+        'A' => (NominalKind::Struct, "AutoreleasingUnsafeMutablePointer"),
+        'a' => (NominalKind::Struct, "Array"),
+        'b' => (NominalKind::Struct, "Bool"),
+        'D' => (NominalKind::Struct, "Dictionary"),
+        'd' => (NominalKind::Struct, "Double"),
+        'f' => (NominalKind::Struct, "Float"),
+        'h' => (NominalKind::Struct, "Set"),
+        'I' => (NominalKind::Struct, "DefaultIndices"),
+        'i' => (NominalKind::Struct, "Int"),
+        'J' => (NominalKind::Struct, "Character"),
+        'N' => (NominalKind::Struct, "ClosedRange"),
+        'n' => (NominalKind::Struct, "Range"),
+        'O' => (NominalKind::Struct, "ObjectIdentifier"),
+        'P' => (NominalKind::Struct, "UnsafePointer"),
+        'p' => (NominalKind::Struct, "UnsafeMutablePointer"),
+        'R' => (NominalKind::Struct, "UnsafeBufferPointer"),
+        'r' => (NominalKind::Struct, "UnsafeMutableBufferPointer"),
+        'S' => (NominalKind::Struct, "String"),
+        's' => (NominalKind::Struct, "Substring"),
+        'u' => (NominalKind::Struct, "UInt"),
+        'V' => (NominalKind::Struct, "UnsafeRawPointer"),
+        'v' => (NominalKind::Struct, "UnsafeMutableRawPointer"),
+        'W' => (NominalKind::Struct, "UnsafeRawBufferPointer"),
+        'w' => (NominalKind::Struct, "UnsafeMutableRawBufferPointer"),
+        'q' => (NominalKind::Enum, "Optional"),
+        'B' => (NominalKind::Protocol, "BinaryFloatingPoint"),
+        'E' => (NominalKind::Protocol, "Encodable"),
+        'e' => (NominalKind::Protocol, "Decodable"),
+        'F' => (NominalKind::Protocol, "FloatingPoint"),
+        'G' => (NominalKind::Protocol, "RandomNumberGenerator"),
+        'H' => (NominalKind::Protocol, "Hashable"),
+        'j' => (NominalKind::Protocol, "Numeric"),
+        'K' => (NominalKind::Protocol, "BidirectionalCollection"),
+        'k' => (NominalKind::Protocol, "RandomAccessCollection"),
+        'L' => (NominalKind::Protocol, "Comparable"),
+        'l' => (NominalKind::Protocol, "Collection"),
+        'M' => (NominalKind::Protocol, "MutableCollection"),
+        'm' => (NominalKind::Protocol, "RangeReplaceableCollection"),
+        'Q' => (NominalKind::Protocol, "Equatable"),
+        'T' => (NominalKind::Protocol, "Sequence"),
+        't' => (NominalKind::Protocol, "IteratorProtocol"),
+        'U' => (NominalKind::Protocol, "UnsignedInteger"),
+        'X' => (NominalKind::Protocol, "RangeExpression"),
+        'x' => (NominalKind::Protocol, "Strideable"),
+        'Y' => (NominalKind::Protocol, "RawRepresentable"),
+        'y' => (NominalKind::Protocol, "StringProtocol"),
+        'Z' => (NominalKind::Protocol, "SignedInteger"),
+        'z' => (NominalKind::Protocol, "BinaryInteger"),
+        _ => return None,
+    })
+}
+
+fn push_nominal_type<'a>(input: &'a str, context: &mut Context<'a>, kind: NominalKind) -> Result<((), &'a str), ()> {
+    let (name, name_depth) = context.pop_decl_name().ok_or(())?;
+    let (type_context, context_depth) = context.pop_context().ok_or(())?;
+
+    context.push_substitutable(
+        Node::Type(Rc::new(Type::Nominal(Rc::new(NominalType {
+            kind,
+            context: type_context,
+            name,
+        })))),
+        name_depth.max(context_depth) + 2,
+    )?;
+
+    Ok(((), input))
+}
+
+/// Pops the effects, the parameters and the result of a function type.
+fn pop_function_type<'a>(context: &mut Context<'a>, is_escaping: bool) -> Result<(FunctionType<'a>, usize), ()> {
+    let throws = context.pop_marker(|node| matches!(node, Node::Throws));
+    let is_async = context.pop_marker(|node| matches!(node, Node::Async));
+    let (parameters, parameters_depth) = context.pop_function_params().ok_or(())?;
+    let (result, result_depth) = context.pop_function_params().ok_or(())?;
+
+    let parameters = match parameters.as_ref() {
+        Type::Tuple(elements) => elements.clone(),
+        _ => vec![TupleElement {
+            label: None,
+            type_: parameters,
+            is_variadic: false,
+        }],
+    };
+
+    Ok((
+        FunctionType {
+            parameters,
+            result,
+            is_async,
+            throws,
+            is_escaping,
+        },
+        parameters_depth.max(result_depth),
+    ))
+}
+
+fn parse_function_entity<'a>(input: &'a str, context: &mut Context<'a>) -> Result<((), &'a str), ()> {
+    let (c, rest) = split_first(input)?;
+
+    let (entity, depth, rest) = match c {
+        'C' | 'c' => {
+            let (signature, signature_depth) = context.pop_function_signature()?;
+            let (entity_context, context_depth) = context.pop_context().ok_or(())?;
+
+            (
+                Entity::Initializer {
+                    context: entity_context,
+                    is_allocating: c == 'C',
+                    signature,
+                },
+                signature_depth.max(context_depth),
+                rest,
+            )
+        }
+        'D' | 'd' => {
+            let (entity_context, depth) = context.pop_context().ok_or(())?;
+
+            (
+                Entity::Deinitializer {
+                    context: entity_context,
+                    is_deallocating: c == 'D',
+                },
+                depth,
+                rest,
+            )
+        }
+        'U' | 'u' => {
+            let (index, rest) = parse_index(rest, context)?;
+            let (type_, type_depth) = context.pop_type().ok_or(())?;
+            let (entity_context, context_depth) = context.pop_context().ok_or(())?;
+
+            (
+                Entity::Closure {
+                    context: entity_context,
+                    is_implicit: c == 'u',
+                    index,
+                    type_,
+                },
+                type_depth.max(context_depth),
+                rest,
+            )
+        }
+        _ => return Err(()),
+    };
+
+    context.push_entity(entity, depth)?;
+
+    Ok(((), rest))
+}
+
+/// Parses the accessor that follows a variable or a subscript, where `p` refers to the storage itself.
+fn parse_accessor<'a>(input: &'a str, context: &mut Context<'a>) -> Result<((), &'a str), ()> {
+    let (c, rest) = split_first(input)?;
+
+    let kind = match c {
+        'p' => return Ok(((), rest)),
+        'g' => AccessorKind::Getter,
+        's' => AccessorKind::Setter,
+        'M' => AccessorKind::Modify,
+        'r' => AccessorKind::Read,
+        'w' => AccessorKind::WillSet,
+        'W' => AccessorKind::DidSet,
+        'i' => AccessorKind::Init,
+        'm' => AccessorKind::MaterializeForSet,
+        _ => return Err(()),
+    };
+
+    let (storage, depth) = context.pop_entity().ok_or(())?;
+
+    context.push_entity(Entity::Accessor { kind, storage }, depth)?;
+
+    Ok(((), rest))
+}
+
+/// Parses a generic signature, which is `l` for a single generic parameter, or `r` followed by the number of generic
+/// parameters at each depth and `l`. The requirements of the signature precede it.
+fn parse_generic_signature<'a>(input: &'a str, context: &mut Context<'a>) -> Result<((), &'a str), ()> {
+    let (param_counts, rest) = if let Some(rest) = input.strip_prefix('l') {
+        (vec![1], rest)
+    } else {
+        let mut rest = input.strip_prefix('r').ok_or(())?;
+        let mut param_counts = Vec::new();
+
+        loop {
+            if let Some(next) = rest.strip_prefix('l') {
+                break (param_counts, next);
+            }
+
+            if let Some(next) = rest.strip_prefix('z') {
+                param_counts.push(0);
+                rest = next;
+            } else {
+                let (index, next) = parse_index(rest, context)?;
+
+                param_counts.push(index.checked_add(1).ok_or(())?);
+                rest = next;
+            }
+        }
+    };
+
+    let mut requirements = Vec::new();
+    let mut depth = 0;
+
+    while let Some((requirement, requirement_depth)) = context.pop_map(|node| match node {
+        Node::Requirement(requirement) => Some(requirement.clone()),
+        _ => None,
+    }) {
+        requirements.push(requirement);
+        depth = depth.max(requirement_depth);
+    }
+
+    requirements.reverse();
+
+    context.push(
+        Node::GenericSignature(GenericSignature {
+            param_counts,
+            requirements,
+        }),
+        depth + 1,
+    )?;
+
+    Ok(((), rest))
+}
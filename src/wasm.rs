@@ -0,0 +1,69 @@
+//! WebAssembly bindings for demangling names in JavaScript, enabled by the `wasm` feature. Build the crate as a
+//! `cdylib` for the `wasm32-unknown-unknown` target, with
+//! `cargo rustc --lib --release --features wasm --target wasm32-unknown-unknown --crate-type cdylib`, and process it
+//! with `wasm-bindgen` to get the JavaScript module.
+
+use crate::rust_v0::{DisplayStyle, Symbol};
+use serde::Serialize;
+use std::fmt::Write;
+use wasm_bindgen::prelude::wasm_bindgen;
+use wasm_bindgen::JsValue;
+
+fn parse_style(style: &str) -> Option<DisplayStyle> {
+    Some(match style {
+        "short" => DisplayStyle::Short,
+        "normal" => DisplayStyle::Normal,
+        "long" => DisplayStyle::Long,
+        "debuginfo" => DisplayStyle::Debuginfo,
+        _ => return None,
+    })
+}
+
+/// Demangles the Rust v0 mangled name `name` and displays it with `style`, which is one of `"short"`, `"normal"`,
+/// `"long"` and `"debuginfo"`. Returns `undefined` if `name` is not a mangled name with Rust v0 syntax, or `style` is
+/// unknown.
+#[must_use]
+#[wasm_bindgen]
+pub fn demangle(name: &str, style: &str) -> Option<String> {
+    let style = parse_style(style)?;
+    let (symbol, _) = Symbol::parse_from_str(name).ok()?;
+    let mut result = String::new();
+
+    write!(result, "{}", symbol.display(style)).ok()?;
+
+    Some(result)
+}
+
+/// Parses the Rust v0 mangled name `name`, and returns the [`Symbol`] as a JavaScript object with the same structure
+/// as the `serde` representation, where 64-bit and 128-bit integers are `BigInt`s. Returns `undefined` if `name` is not
+/// a mangled name with Rust v0 syntax.
+#[must_use]
+#[wasm_bindgen(js_name = parseSymbol)]
+pub fn parse_symbol(name: &str) -> JsValue {
+    let serializer = serde_wasm_bindgen::Serializer::new().serialize_large_number_types_as_bigints(true);
+
+    Symbol::parse_from_str(name)
+        .ok()
+        .and_then(|(symbol, _)| symbol.serialize(&serializer).ok())
+        .unwrap_or(JsValue::UNDEFINED)
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_demangle() {
+        assert_eq!(
+            super::demangle("_RNvNtCs1234_3std3mem4swap", "long").as_deref(),
+            Some("std[3c1c0]::mem::swap")
+        );
+
+        assert_eq!(
+            super::demangle("_RNvNtCs1234_3std3mem4swap", "short").as_deref(),
+            Some("swap")
+        );
+
+        assert_eq!(super::demangle("_RNvNtCs1234_3std3mem4swap", "wide"), None);
+        assert_eq!(super::demangle("foo", "normal"), None);
+        assert_eq!(super::demangle("_RINvC3foo3barRL1_hE", "normal"), None);
+    }
+}
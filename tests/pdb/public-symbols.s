@@ -0,0 +1,31 @@
+# The source of public-symbols.pdb, which is regenerated with:
+#
+#     llvm-mc -triple x86_64-pc-windows-msvc -filetype=obj public-symbols.s -o public-symbols.obj
+#     rust-lld -flavor link /dll /noentry /debug /opt:noref /pdb:public-symbols.pdb /out:public-symbols.dll public-symbols.obj
+
+    .text
+    .globl _RNvCs123_3foo3bar
+    .def _RNvCs123_3foo3bar
+    .scl 2
+    .type 32
+    .endef
+_RNvCs123_3foo3bar:
+    retq
+    .globl _RMC0RLY_z
+    .def _RMC0RLY_z
+    .scl 2
+    .type 32
+    .endef
+_RMC0RLY_z:
+    retq
+    .globl "?foo@@YAHH@Z"
+    .def "?foo@@YAHH@Z"
+    .scl 2
+    .type 32
+    .endef
+"?foo@@YAHH@Z":
+    retq
+    .data
+    .globl _RNvCs123_3foo7COUNTER
+_RNvCs123_3foo7COUNTER:
+    .long 0
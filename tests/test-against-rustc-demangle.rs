@@ -1,4 +1,4 @@
-use ast_demangle::rust_v0::Symbol;
+use ast_demangle::rust_v0::{self, DisplayStyle, Symbol};
 use std::fmt::Write;
 use test_utilities::BoundedWriter;
 
@@ -15,12 +15,13 @@ fn demangle_ast_demangle<'a>(name: &str, buffer: &'a mut String) -> Option<(&'a
     if rest.is_empty() {
         if symbol
             .vendor_specific_suffix
-            .map_or(false, |suffix| suffix.starts_with(".llvm."))
+            .as_ref()
+            .is_some_and(|suffix| suffix.starts_with(".llvm."))
         {
             symbol.vendor_specific_suffix = None;
         }
 
-        let suffix = symbol.vendor_specific_suffix.unwrap_or("");
+        let suffix = symbol.vendor_specific_suffix.as_deref().unwrap_or("");
 
         write!(buffer, "{symbol}{suffix}").ok()?;
 
@@ -34,20 +35,21 @@ fn demangle_ast_demangle<'a>(name: &str, buffer: &'a mut String) -> Option<(&'a
     }
 }
 
-fn demangle_rustc_demangle<'a>(name: &str, buffer: &'a mut String) -> Option<(&'a str, &'a str)> {
-    fn has_error(buffer: &str) -> bool {
-        [
-            "{invalid syntax}",
-            "{recursion limit reached}",
-            "{size limit reached}",
-            "<? as ?>",
-            "<?>",
-            "punycode{",
-        ]
-        .into_iter()
-        .any(|pattern| buffer.contains(pattern))
-    }
+/// Returns `true` if `rustc-demangle` only partially demangled a name.
+fn has_error(demangled: &str) -> bool {
+    [
+        "{invalid syntax}",
+        "{recursion limit reached}",
+        "{size limit reached}",
+        "<? as ?>",
+        "<?>",
+        "punycode{",
+    ]
+    .into_iter()
+    .any(|pattern| demangled.contains(pattern))
+}
 
+fn demangle_rustc_demangle<'a>(name: &str, buffer: &'a mut String) -> Option<(&'a str, &'a str)> {
     let mut buffer = bounded_writer(buffer);
     let demangle = rustc_demangle::try_demangle(name).ok()?;
 
@@ -85,3 +87,70 @@ fn test_against_rustc_demangle() {
         }
     }
 }
+
+#[test]
+fn test_demangle_to_writer_against_display() {
+    let styles = [
+        DisplayStyle::Short,
+        DisplayStyle::Normal,
+        DisplayStyle::Long,
+        DisplayStyle::Debuginfo,
+    ];
+
+    for line in TEST_DATA.lines() {
+        if !line.is_empty() && !line.starts_with('#') {
+            let symbol = Symbol::parse_from_str(line);
+
+            for style in styles {
+                let mut buffer = String::new();
+                let mut writer = bounded_writer(&mut buffer);
+                let result = rust_v0::demangle_to_writer(line, style, &mut writer);
+
+                match &symbol {
+                    Ok((symbol, suffix)) => {
+                        let mut expected = String::new();
+                        let mut expected_writer = bounded_writer(&mut expected);
+
+                        if write!(expected_writer, "{}", symbol.display(style)).is_ok() {
+                            assert_eq!(result, Ok(*suffix), "Failed: {line}");
+                            assert_eq!(buffer, expected, "Failed: {line}");
+                        } else {
+                            assert!(result.is_err(), "Failed: {line}");
+                        }
+                    }
+                    Err(_) => assert_eq!(result, Err(rust_v0::DemangleError::Invalid), "Failed: {line}"),
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_rustc_compatible_against_rustc_demangle() {
+    let suffixes = ["", ".llvm.0123ABCD", ".llvm.abc", ".cold.1", ".a b", "$abc", "foo"];
+
+    for line in TEST_DATA.lines() {
+        // Back references of partially demangled names may point into the suffix, which `rustc-demangle` follows
+        // without reporting an error.
+        if !line.is_empty()
+            && !line.starts_with('#')
+            && !rustc_demangle::try_demangle(line).is_ok_and(|demangled| has_error(&demangled.to_string()))
+        {
+            for suffix in suffixes {
+                let name = format!("{line}{suffix}");
+                let expected = rustc_demangle::try_demangle(&name)
+                    .ok()
+                    .map(|demangled| (demangled.to_string(), format!("{demangled:#}")));
+
+                if expected.as_ref().is_some_and(|(long, _)| has_error(long)) {
+                    continue;
+                }
+
+                let actual = rust_v0::demangle_rustc_compatible(&name)
+                    .map(|demangled| (demangled.to_string(), format!("{demangled:#}")));
+
+                assert_eq!(actual, expected, "Failed: {name}");
+            }
+        }
+    }
+}
@@ -0,0 +1,85 @@
+#![cfg(feature = "cli")]
+
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+fn run(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_ast-demangle"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    child.stdin.take().unwrap().write_all(stdin.as_bytes()).unwrap();
+
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn test_cli_names() {
+    let output = run(
+        &["_RNvCs1234_3foo3bar.llvm.123", "--style", "long", "_ZN3foo3barE", "baz"],
+        "",
+    );
+
+    assert!(output.status.success());
+    assert_eq!(output.stdout, b"foo[3c1c0]::bar\nfoo::bar\nbaz\n");
+
+    let output = run(&["--style=short"], "_RNvNtC3std3mem4swap\n  _RNvC3foo3bar.cold\n");
+
+    assert!(output.status.success());
+    assert_eq!(output.stdout, b"swap\nbar.cold\n");
+
+    // The name parses, but refers to a lifetime that is not bound.
+    let output = run(&["_RMC0RLY_z"], "");
+
+    assert!(output.status.success());
+    assert_eq!(output.stdout, b"_RMC0RLY_z\n");
+}
+
+#[test]
+fn test_cli_json() {
+    let output = run(&["--json", "_RNvC3foo3bar", "baz"], "");
+
+    assert!(output.status.success());
+
+    let lines = String::from_utf8(output.stdout).unwrap();
+    let values = lines
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+        .collect::<Vec<_>>();
+
+    assert_eq!(values.len(), 2);
+    assert_eq!(values[0]["mangled"], "_RNvC3foo3bar");
+    assert_eq!(values[0]["demangled"], "foo::bar");
+    assert!(values[0]["symbol"].is_object());
+    assert_eq!(values[1]["mangled"], "baz");
+    assert!(values[1]["error"].is_string());
+}
+
+#[test]
+fn test_cli_filter() {
+    let output = run(&["--filter"], "at _RNvC3foo3bar+0x10\nno symbols\n");
+
+    assert!(output.status.success());
+    assert_eq!(output.stdout, b"at foo::bar+0x10\nno symbols\n");
+}
+
+#[test]
+fn test_cli_usage_errors() {
+    for args in [
+        &["--style", "wide"][..],
+        &["--style"],
+        &["--frobnicate"],
+        &["--filter", "--json"],
+    ] {
+        let output = run(args, "");
+
+        assert_eq!(output.status.code(), Some(2));
+        assert!(output.stdout.is_empty());
+    }
+
+    assert!(run(&["--help"], "").status.success());
+}
@@ -0,0 +1,42 @@
+use ast_demangle::rust_legacy::Symbol;
+
+const TEST_DATA: &[&str] = &[
+    "_ZN4testE",
+    "_ZN4test1a2bcE",
+    "ZN4testE",
+    "__ZN4testE",
+    "_ZN4$RP$E",
+    "_ZN8$RF$testE",
+    "_ZN8$BP$test4foobE",
+    "_ZN9$u20$test4foobE",
+    "_ZN35Bar$LT$$u5b$u32$u3b$$u20$4$u5d$$GT$E",
+    "_ZN13test$u20$test4foobE",
+    "_ZN12test$BP$test4foobE",
+    "_ZN4test1a2bc.llvm.123E",
+    "_ZN3foo17h05af221e174051e9E",
+    "_ZN3foo3barE",
+    "_ZN4$UP$E",
+    "_ZN8$u{4e}$E",
+    "_ZN4testE.cold",
+    "_ZN4core3ptr13drop_in_place17h1b53f4d1b6d6f0f2E",
+    "_ZN71_$LT$Test$u20$$u2b$$u20$$u27$static$u20$as$u20$foo..Bar$LT$Test$GT$$GT$3bar17h930b740aa94f1d3aE",
+    "_ZN3fooE",
+    "_ZNE",
+    "_ZN1hE",
+];
+
+#[test]
+fn test_legacy_against_rustc_demangle() {
+    for name in TEST_DATA {
+        let ast_demangle_result = Symbol::parse_from_str(name)
+            .ok()
+            .filter(|(_, suffix)| suffix.is_empty() || suffix.starts_with('.'))
+            .map(|(symbol, suffix)| (format!("{symbol}{suffix}"), format!("{symbol:#}{suffix}")));
+
+        let rustc_demangle_result = rustc_demangle::try_demangle(name)
+            .ok()
+            .map(|demangle| (format!("{demangle}"), format!("{demangle:#}")));
+
+        assert_eq!(ast_demangle_result, rustc_demangle_result, "Failed: {name}");
+    }
+}
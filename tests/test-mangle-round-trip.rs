@@ -0,0 +1,19 @@
+use ast_demangle::rust_v0::Symbol;
+
+const TEST_DATA: &str = include_str!("test-against-rustc-demangle-data.txt");
+
+#[test]
+fn test_mangle_round_trip() {
+    for line in TEST_DATA.lines() {
+        if !line.is_empty() && !line.starts_with('#') {
+            if let Ok((symbol, _)) = Symbol::parse_from_str(line) {
+                let mangled = symbol.mangle();
+                let (reparsed, suffix) = Symbol::parse_from_str(&mangled).expect(line);
+
+                assert_eq!(suffix, "", "Failed: {line}");
+                assert_eq!(reparsed, symbol, "Failed: {line}");
+                assert_eq!(reparsed.mangle(), mangled, "Failed: {line}");
+            }
+        }
+    }
+}